@@ -2,9 +2,10 @@
 //!
 //! This example demonstrates how to read ZIP files directly from HTTP sources
 //! without downloading the entire file first. Uses the generic async reader
-//! with reqwest to stream from HTTP.
+//! for seekable sources, and the forward-only reader for response bodies that
+//! arrive as a one-shot stream of chunks.
 
-use s_zip::{AsyncStreamingZipWriter, GenericAsyncZipReader};
+use s_zip::{AsyncStreamingZipWriter, ForwardZipReader, GenericAsyncZipReader};
 use std::io::Cursor;
 use tokio::io::AsyncReadExt;
 
@@ -74,7 +75,19 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         println!("   Streamed {} bytes in {} chunks\n", total_bytes, chunks);
     }
 
-    // Step 6: Demonstrate with actual HTTP (commented out - requires server)
+    // Step 6: Unzip while downloading, from a body delivered in chunks
+    println!("📡 Step 6: Reading entries as the body arrives (no Seek):\n");
+    let chunks: Vec<std::io::Result<Vec<u8>>> =
+        zip_bytes.chunks(1024).map(|c| Ok(c.to_vec())).collect();
+    let mut forward = ForwardZipReader::from_byte_stream(futures_util::stream::iter(chunks));
+    while let Some(mut entry) = forward.next_entry().await? {
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data).await?;
+        println!("   {} - {} bytes", entry.entry().name, data.len());
+    }
+    println!();
+
+    // Step 7: Demonstrate with actual HTTP (commented out - requires server)
     println!("╔══════════════════════════════════════════════════════════════╗");
     println!("║                 Real HTTP Example                           ║");
     println!("╚══════════════════════════════════════════════════════════════╝\n");
@@ -84,13 +97,17 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("use reqwest;");
     println!("use std::io::Cursor;");
     println!();
-    println!("// Download ZIP into memory");
+    println!("// Unzip while downloading, without buffering the archive");
     println!("let response = reqwest::get(\"https://example.com/file.zip\").await?;");
-    println!("let bytes = response.bytes().await?;");
-    println!("let cursor = Cursor::new(bytes.to_vec());");
+    println!("let mut zip = ForwardZipReader::from_byte_stream(response.bytes_stream());");
+    println!("while let Some(mut entry) = zip.next_entry().await? {{");
+    println!("    let mut data = Vec::new();");
+    println!("    entry.read_to_end(&mut data).await?;");
+    println!("}}");
     println!();
-    println!("// Read ZIP from memory");
-    println!("let mut reader = GenericAsyncZipReader::new(cursor).await?;");
+    println!("// Or download into memory for random access by name");
+    println!("let bytes = reqwest::get(\"https://example.com/file.zip\").await?.bytes().await?;");
+    println!("let mut reader = GenericAsyncZipReader::new(Cursor::new(bytes.to_vec())).await?;");
     println!("let data = reader.read_entry_by_name(\"file.txt\").await?;");
     println!("```\n");
 
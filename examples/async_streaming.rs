@@ -11,7 +11,6 @@
 use s_zip::{AsyncStreamingZipWriter, Result};
 use std::path::Path;
 use tokio::fs::File;
-use tokio::io::AsyncReadExt;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -54,23 +53,14 @@ async fn stream_file_to_zip<W>(
     zip_entry_name: &str,
 ) -> Result<()>
 where
-    W: tokio::io::AsyncWrite + tokio::io::AsyncSeek + Unpin,
+    W: tokio::io::AsyncWrite + Unpin,
 {
-    writer.start_entry(zip_entry_name).await?;
-
-    let mut file = File::open(source_path).await?;
-    let mut buffer = vec![0u8; 8192]; // 8KB buffer for streaming
-
-    let mut total_bytes = 0u64;
-    loop {
-        let n = file.read(&mut buffer).await?;
-        if n == 0 {
-            break;
-        }
-        writer.write_data(&buffer[..n]).await?;
-        total_bytes += n as u64;
-    }
+    // Streams the file in chunks, keeping its mtime and permissions
+    writer
+        .write_file(zip_entry_name, Path::new(source_path))
+        .await?;
 
+    let total_bytes = tokio::fs::metadata(source_path).await?.len();
     println!("    → Streamed {} bytes", total_bytes);
 
     Ok(())
@@ -11,7 +11,7 @@ use std::io::Cursor;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 use std::time::{Duration, Instant};
-use tokio::io::{AsyncSeek, AsyncWrite};
+use tokio::io::AsyncWrite;
 
 /// Simulated "slow network" writer - adds 5ms delay per write
 struct SlowNetworkWriter {
@@ -46,16 +46,6 @@ impl AsyncWrite for SlowNetworkWriter {
     }
 }
 
-impl AsyncSeek for SlowNetworkWriter {
-    fn start_seek(mut self: Pin<&mut Self>, position: std::io::SeekFrom) -> std::io::Result<()> {
-        Pin::new(&mut self.inner).start_seek(position)
-    }
-
-    fn poll_complete(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<u64>> {
-        Pin::new(&mut self.inner).poll_complete(cx)
-    }
-}
-
 impl Unpin for SlowNetworkWriter {}
 
 async fn create_zip_with_network_delay(_id: usize) -> Duration {
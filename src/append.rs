@@ -0,0 +1,99 @@
+//! Adding entries to an existing archive.
+//!
+//! Existing entries stay where they are. New entries are written over the
+//! old central directory, and the finished archive's directory starts with
+//! the old records, copied byte for byte, followed by the new ones. Until
+//! the writer is finished the file has no valid central directory.
+
+use crate::error::{Result, SZipError};
+use crate::sansio::{self, DirectoryLocator, LocatedDirectory};
+use std::io::{Read, Seek, SeekFrom};
+
+/// Size of a ZIP64 end of central directory record and its locator
+const ZIP64_TRAILER_LEN: u64 = 56 + 20;
+
+/// Central directory of the archive being appended to
+pub(crate) struct ExistingArchive {
+    /// Offset of the old central directory, where new entries start
+    pub offset: u64,
+    /// Central directory records, as stored
+    pub records: Vec<u8>,
+    /// Names of the existing entries
+    pub names: Vec<String>,
+    /// Highest "version needed to extract" of the existing entries
+    pub version_needed: u16,
+    /// Archive comment
+    pub comment: Vec<u8>,
+}
+
+impl ExistingArchive {
+    /// Check the located directory and parse its records
+    pub(crate) fn new(found: LocatedDirectory, records: Vec<u8>) -> Result<Self> {
+        if found.disk != 0 {
+            return Err(SZipError::InvalidFormat(
+                "Can't append to a split archive".to_string(),
+            ));
+        }
+        // With data prepended to the archive (a self-extractor stub, say) the
+        // recorded offsets don't count from the start of the file, and new
+        // entries would overwrite the wrong bytes
+        let end = found.location.offset + found.location.size;
+        let gap = found.eocd_offset.checked_sub(end);
+        if gap != Some(0) && gap != Some(ZIP64_TRAILER_LEN) {
+            return Err(SZipError::InvalidFormat(
+                "Central directory isn't where the end record says; can't append to an archive with prepended data"
+                    .to_string(),
+            ));
+        }
+        let entries = sansio::parse_central_directory(&records, found.location.count)?;
+        Ok(ExistingArchive {
+            offset: found.location.offset,
+            records,
+            version_needed: entries.iter().map(|e| e.version_needed).max().unwrap_or(0),
+            names: entries.into_iter().map(|e| e.name).collect(),
+            comment: found.comment,
+        })
+    }
+
+    /// Number of existing entries
+    pub(crate) fn count(&self) -> u64 {
+        self.names.len() as u64
+    }
+}
+
+/// Read the central directory of the archive in `file`
+pub(crate) fn read<R: Read + Seek>(file: &mut R) -> Result<ExistingArchive> {
+    let len = file.seek(SeekFrom::End(0))?;
+    let found = crate::split::locate(file, DirectoryLocator::new(len))?;
+    file.seek(SeekFrom::Start(found.location.offset))?;
+    let mut records = Vec::new();
+    file.by_ref()
+        .take(found.location.size)
+        .read_to_end(&mut records)?;
+    ExistingArchive::new(found, records)
+}
+
+/// Read the central directory of the archive in `file`
+#[cfg(feature = "async")]
+pub(crate) async fn read_async<R>(file: &mut R) -> Result<ExistingArchive>
+where
+    R: tokio::io::AsyncRead + tokio::io::AsyncSeek + Unpin,
+{
+    use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+    let mut locator = DirectoryLocator::new(file.seek(SeekFrom::End(0)).await?);
+    while let Some(request) = locator.next_read() {
+        file.seek(SeekFrom::Start(request.offset)).await?;
+        let mut buf = Vec::new();
+        (&mut *file).take(request.len).read_to_end(&mut buf).await?;
+        locator.feed(&buf)?;
+    }
+    let found = locator.finish()?;
+    file.seek(SeekFrom::Start(found.location.offset)).await?;
+    let mut records = Vec::new();
+    (&mut *file)
+        .take(found.location.size)
+        .read_to_end(&mut records)
+        .await?;
+    ExistingArchive::new(found, records)
+}
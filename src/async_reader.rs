@@ -1,21 +1,60 @@
 //! Generic async ZIP reader for arbitrary async sources (files, HTTP, S3, in-memory, etc.)
 //!
 //! This module provides a generic async ZIP reader that works with any source
-//! implementing AsyncRead + AsyncSeek + Unpin + Send.
+//! implementing AsyncRead + AsyncSeek + Unpin. Only the methods that spawn
+//! tasks or return owned streams also require `Send`.
 
+use crate::budget::MemoryBudget;
+use crate::capabilities;
+use crate::deadline::DeadlineReader;
+use crate::double_buffer::DoubleBuffer;
 use crate::error::{Result, SZipError};
-use crate::format::{
-    find_eocd_in_buffer, find_zip64_eocd_offset, parse_aes_extra_field_buf,
-    parse_zip64_extra_field, CENTRAL_DIRECTORY_SIGNATURE, END_OF_CENTRAL_DIRECTORY_SIGNATURE,
-    LOCAL_FILE_HEADER_SIGNATURE, MAX_ENTRY_ALLOC, ZIP64_END_OF_CENTRAL_DIRECTORY_SIGNATURE,
+use crate::extract::{self, ExtractConfig, ExtractOptions};
+use crate::format::{CentralDirectoryLocation, INITIAL_ALLOC_CAP, MAX_ENTRY_ALLOC};
+use crate::quirks::{self, QuirksMode};
+use crate::reader::{NameFilter, ReaderOptions};
+use crate::report::{self, FormatReport};
+use crate::salvage::DescriptorScan;
+use crate::sansio::{
+    CentralRecordReader, DirectoryLocator, LocalHeader, RecordRead, LOCAL_HEADER_LEN,
 };
+use crate::verify::{self, EntryProblem, EntryReport, VerifyReport};
+
+#[cfg(feature = "encryption")]
+use crate::decrypt_reader::r#async::{AsyncDecryptingReader, CtrReader};
+#[cfg(feature = "async-zstd")]
+use crate::zstd_dict::{ZstdDictionary, DICTIONARY_ENTRY};
+#[cfg(feature = "bzip2")]
+use async_compression::tokio::bufread::BzDecoder;
+#[cfg(feature = "deflate64")]
+use async_compression::tokio::bufread::Deflate64Decoder;
 use async_compression::tokio::bufread::DeflateDecoder;
 #[cfg(feature = "async-zstd")]
 use async_compression::tokio::bufread::ZstdDecoder;
+#[cfg(feature = "lzma")]
+use async_compression::tokio::bufread::{LzmaDecoder, XzDecoder};
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::io::SeekFrom;
 use std::path::Path;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Instant;
 use tokio::fs::File;
-use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt, BufReader};
+use tokio::io::{
+    AsyncBufRead, AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt, AsyncWriteExt, BufReader,
+    ReadBuf, Take,
+};
+use tokio::task::JoinHandle;
+
+/// Background task that is aborted when its handle is dropped
+struct AbortOnDrop<T>(JoinHandle<T>);
+
+impl<T> Drop for AbortOnDrop<T> {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
 
 // Re-export ZipEntry so existing `use s_zip::async_reader::ZipEntry` paths still compile.
 pub use crate::format::ZipEntry;
@@ -23,11 +62,23 @@ pub use crate::format::ZipEntry;
 /// Generic async streaming ZIP reader that works with any async reader + seeker
 ///
 /// Supports adaptive buffering for optimized read performance based on file size.
-pub struct GenericAsyncZipReader<R: AsyncRead + AsyncSeek + Unpin + Send> {
+pub struct GenericAsyncZipReader<R: AsyncRead + AsyncSeek + Unpin> {
     reader: BufReader<R>,
     entries: Vec<ZipEntry>,
+    comment: Vec<u8>,
+    cd_location: CentralDirectoryLocation,
     #[cfg(feature = "encryption")]
     password: Option<String>,
+    #[cfg(feature = "async-zstd")]
+    zstd_dictionary: Option<ZstdDictionary>,
+    /// In-flight `prefetch` reads of raw entry records, keyed by header offset
+    prefetched: HashMap<u64, AbortOnDrop<std::io::Result<Vec<u8>>>>,
+    /// Budget the read-ahead buffers of entry streams are drawn from
+    memory_budget: Option<MemoryBudget>,
+    /// How the archive was opened, reused when its directory is read again
+    options: ReaderOptions,
+    /// Entry filter the reader was opened with, reapplied on reload
+    filter: Option<NameFilter>,
 }
 
 /// Type alias for file-based async ZIP reader (convenience)
@@ -154,31 +205,253 @@ impl AsyncStreamingZipReader {
     }
 }
 
-impl<R: AsyncRead + AsyncSeek + Unpin + Send> GenericAsyncZipReader<R> {
+impl GenericAsyncZipReader<std::io::Cursor<Vec<u8>>> {
+    /// Open a ZIP archive held in memory
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use s_zip::GenericAsyncZipReader;
+    /// # async fn example(bytes: Vec<u8>) -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut reader = GenericAsyncZipReader::from_bytes(bytes).await?;
+    /// let data = reader.read_entry_by_name("file.txt").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn from_bytes(bytes: impl Into<Vec<u8>>) -> Result<Self> {
+        // The data is already in memory; a large read buffer would only copy it.
+        Self::new_with_buffer_size(std::io::Cursor::new(bytes.into()), Some(8 * 1024)).await
+    }
+}
+
+impl<R: AsyncRead + AsyncSeek + Unpin> GenericAsyncZipReader<R> {
+    /// Create a new generic async ZIP reader with the default buffer size
+    pub async fn new(reader: R) -> Result<Self> {
+        Self::new_with_buffer_size(reader, None).await
+    }
+
     /// Create a new generic async ZIP reader with custom buffer size
     ///
     /// Allows fine-tuning read performance based on expected data patterns.
     pub async fn new_with_buffer_size(reader: R, buffer_size: Option<usize>) -> Result<Self> {
+        Self::new_with_filter(reader, buffer_size, None, ReaderOptions::default()).await
+    }
+
+    /// Create a reader that parses the archive as `options` describe.
+    ///
+    /// Async counterpart of
+    /// [`StreamingZipReader::open_with_options`](crate::StreamingZipReader::open_with_options);
+    /// the options are kept and applied again by [`reload`](Self::reload).
+    pub async fn new_with_options(reader: R, options: ReaderOptions) -> Result<Self> {
+        Self::new_with_filter(reader, None, None, options).await
+    }
+
+    /// Create a reader that keeps only the entries whose name passes `keep`.
+    ///
+    /// Async counterpart of
+    /// [`StreamingZipReader::open_filtered`](crate::StreamingZipReader::open_filtered):
+    /// rejected central directory records are dropped as they are parsed,
+    /// and again by [`reload`](Self::reload).
+    pub async fn new_filtered(
+        reader: R,
+        keep: impl Fn(&str) -> bool + Send + Sync + 'static,
+    ) -> Result<Self> {
+        Self::new_with_filter(
+            reader,
+            None,
+            Some(std::sync::Arc::new(keep)),
+            ReaderOptions::default(),
+        )
+        .await
+    }
+
+    /// Create a reader that keeps only entries whose name starts with one of
+    /// `prefixes`; see [`new_filtered`](Self::new_filtered)
+    pub async fn new_with_prefixes(reader: R, prefixes: &[&str]) -> Result<Self> {
+        let filter = crate::reader::prefix_filter(prefixes);
+        Self::new_with_filter(reader, None, Some(filter), ReaderOptions::default()).await
+    }
+
+    /// Re-read the end of central directory record and the central directory
+    /// in place, replacing the entry list and comment.
+    ///
+    /// Async counterpart of
+    /// [`StreamingZipReader::reload`](crate::StreamingZipReader::reload). The
+    /// source must report its current length when seeking from its end;
+    /// sources that fix the length when created (such as `S3ZipReader`) only
+    /// see the archive as it was then.
+    pub async fn reload(&mut self) -> Result<()> {
+        let (mut entries, comment, cd_location) =
+            Self::read_central_directory(&mut self.reader, self.filter.as_ref(), &self.options)
+                .await?;
+        if self.options.quirks.is_lenient() {
+            Self::recover_descriptor_sizes(&mut self.reader, &mut entries).await?;
+        }
+        // Prefetched records belong to the old layout
+        self.prefetched.clear();
+        self.entries = entries;
+        self.comment = comment;
+        self.cd_location = cd_location;
+        Ok(())
+    }
+
+    async fn new_with_filter(
+        reader: R,
+        buffer_size: Option<usize>,
+        filter: Option<NameFilter>,
+        mut options: ReaderOptions,
+    ) -> Result<Self> {
         // Use adaptive buffer size
         let buf_size = buffer_size.unwrap_or(1024 * 1024); // Default 1MB for async
         let mut reader = BufReader::with_capacity(buf_size, reader);
 
         // Find and read central directory
-        let entries = Self::read_central_directory(&mut reader).await?;
+        let (mut entries, comment, cd_location) = match options.cd_location.take() {
+            Some(location) => {
+                let mut entries =
+                    Self::read_central_directory_at(&mut reader, location, &options).await?;
+                if let Some(keep) = &filter {
+                    entries.retain(|entry| keep(&entry.name));
+                }
+                (entries, Vec::new(), location)
+            }
+            None => Self::read_central_directory(&mut reader, filter.as_ref(), &options).await?,
+        };
+        if options.quirks.is_lenient() {
+            Self::recover_descriptor_sizes(&mut reader, &mut entries).await?;
+        }
 
         Ok(GenericAsyncZipReader {
             reader,
             entries,
+            comment,
+            cd_location,
             #[cfg(feature = "encryption")]
             password: None,
+            #[cfg(feature = "async-zstd")]
+            zstd_dictionary: None,
+            prefetched: HashMap::new(),
+            memory_budget: None,
+            options,
+            filter,
         })
     }
 
+    /// Open a source whose central directory location is already known.
+    ///
+    /// Async counterpart of
+    /// [`StreamingZipReader::open_with_cd_location`](crate::StreamingZipReader::open_with_cd_location):
+    /// skips the EOCD scan (and, on remote sources, the 64 KB tail read) and
+    /// fetches the central directory in one read.  [`comment`](Self::comment)
+    /// is empty.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # #[cfg(feature = "cloud-s3")]
+    /// # async fn example(location: s_zip::CentralDirectoryLocation) -> Result<(), Box<dyn std::error::Error>> {
+    /// use s_zip::{cloud::S3ZipReader, GenericAsyncZipReader};
+    ///
+    /// let config = aws_config::load_from_env().await;
+    /// let client = aws_sdk_s3::Client::new(&config);
+    /// let source = S3ZipReader::new(client, "my-bucket", "archive.zip").await?;
+    /// let reader = GenericAsyncZipReader::open_with_cd_location(
+    ///     source,
+    ///     location.offset,
+    ///     location.size,
+    ///     location.count,
+    /// )
+    /// .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn open_with_cd_location(
+        reader: R,
+        offset: u64,
+        size: u64,
+        count: u64,
+    ) -> Result<Self> {
+        let location = CentralDirectoryLocation {
+            offset,
+            size,
+            count,
+        };
+        Self::new_with_options(reader, ReaderOptions::default().with_cd_location(location)).await
+    }
+
+    /// Where the central directory is, for reopening the archive later with
+    /// [`open_with_cd_location`](Self::open_with_cd_location)
+    pub fn cd_location(&self) -> CentralDirectoryLocation {
+        self.cd_location
+    }
+
     /// Get list of all entries in the ZIP
     pub fn entries(&self) -> &[ZipEntry] {
         &self.entries
     }
 
+    /// Number of entries in the ZIP
+    pub fn entry_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Up to `limit` entries starting at `offset`, in central directory
+    /// order; empty once `offset` is past the last entry
+    pub fn entries_page(&self, offset: usize, limit: usize) -> &[ZipEntry] {
+        let start = offset.min(self.entries.len());
+        let end = start.saturating_add(limit).min(self.entries.len());
+        &self.entries[start..end]
+    }
+
+    /// Drop macOS Finder metadata (`.DS_Store`, `__MACOSX/`, `._` AppleDouble
+    /// files) from the entry list, so listings and lookups only see user content.
+    ///
+    /// To skip them while the central directory is parsed instead, open with a
+    /// filter that rejects [`crate::format::is_macos_metadata`].
+    pub fn hide_macos_metadata(&mut self) -> &mut Self {
+        self.entries
+            .retain(|e| !crate::format::is_macos_metadata(&e.name));
+        self
+    }
+
+    /// Archive comment stored in the end-of-central-directory record.
+    ///
+    /// Decoded with the reader's
+    /// [`filename_encoding`](crate::ReaderOptions::filename_encoding), like
+    /// entry names. Returns an empty string when the archive has no comment.
+    pub fn comment(&self) -> Cow<'_, str> {
+        self.options.filename_encoding.decode_comment(&self.comment)
+    }
+
+    /// Raw bytes of the archive comment, as stored
+    pub fn archive_comment(&self) -> &[u8] {
+        &self.comment
+    }
+
+    /// Whether this build can read `entry`: its compression method is
+    /// enabled and, if encrypted, it uses WinZip AES and a password is set.
+    ///
+    /// See [`capabilities()`](crate::capabilities()) for what is available.
+    pub fn can_read_entry(&self, entry: &ZipEntry) -> bool {
+        #[cfg(feature = "encryption")]
+        let has_password = self.password.is_some();
+        #[cfg(not(feature = "encryption"))]
+        let has_password = false;
+        capabilities::can_read(entry, &capabilities::async_read_methods(), has_password)
+    }
+
+    /// Describe the ZIP format features this archive uses.
+    ///
+    /// Async counterpart of
+    /// [`StreamingZipReader::format_report`](crate::StreamingZipReader::format_report).
+    pub async fn format_report(&mut self) -> Result<FormatReport> {
+        let file_size = self.reader.seek(SeekFrom::End(0)).await?;
+        self.reader
+            .seek(SeekFrom::Start(file_size.saturating_sub(report::TAIL_LEN)))
+            .await?;
+        let mut tail = Vec::new();
+        self.reader.read_to_end(&mut tail).await?;
+        Ok(FormatReport::new(&self.entries, &self.comment, &tail))
+    }
+
     /// Find an entry by name
     pub fn find_entry(&self, name: &str) -> Option<&ZipEntry> {
         self.entries.iter().find(|e| e.name == name)
@@ -203,66 +476,143 @@ impl<R: AsyncRead + AsyncSeek + Unpin + Send> GenericAsyncZipReader<R> {
         self.password = Some(password.into());
     }
 
-    /// Read an entry's decompressed data into a vector
-    pub async fn read_entry(&mut self, entry: &ZipEntry) -> Result<Vec<u8>> {
-        // Seek to local file header
-        self.reader.seek(SeekFrom::Start(entry.offset)).await?;
+    /// Decompress Zstd entries with `dictionary` instead of the one embedded
+    /// in the archive, if any.
+    ///
+    /// See [`StreamingZipReader::set_zstd_dictionary`](crate::StreamingZipReader::set_zstd_dictionary).
+    #[cfg(feature = "async-zstd")]
+    pub fn set_zstd_dictionary(&mut self, dictionary: ZstdDictionary) -> &mut Self {
+        self.zstd_dictionary = Some(dictionary);
+        self
+    }
 
-        // Read and verify local file header
-        let signature = self.read_u32_le().await?;
-        if signature != LOCAL_FILE_HEADER_SIGNATURE {
-            return Err(SZipError::InvalidFormat(
-                "Invalid local file header signature".to_string(),
-            ));
+    /// The dictionary to decompress `entry` with, loading the embedded one
+    /// the first time a Zstd entry needs it
+    #[cfg(feature = "async-zstd")]
+    async fn zstd_dictionary_for(&mut self, entry: &ZipEntry) -> Result<Option<ZstdDictionary>> {
+        if entry.compression_method != 93 || entry.name == DICTIONARY_ENTRY {
+            return Ok(None);
         }
+        if self.zstd_dictionary.is_none() {
+            if let Some(embedded) = self.find_entry(DICTIONARY_ENTRY).cloned() {
+                let bytes = Self::read_entry_at(
+                    &mut self.reader,
+                    &embedded,
+                    embedded.offset,
+                    self.options.quirks,
+                    #[cfg(feature = "encryption")]
+                    self.password.as_deref(),
+                    None,
+                )
+                .await?;
+                self.zstd_dictionary = Some(ZstdDictionary::new(bytes));
+            }
+        }
+        Ok(self.zstd_dictionary.clone())
+    }
 
-        // Skip version (2 bytes)
-        self.reader.seek(SeekFrom::Current(2)).await?;
-
-        // Read flags to check for encryption
-        let flags = self.read_u16_le().await?;
-        let is_encrypted_local = (flags & 0x01) != 0;
+    /// Fail reads with `SZipError::DeadlineExceeded` once `deadline` passes,
+    /// including entry streams already handed out.
+    ///
+    /// Opening the archive is not covered; pass the deadline in
+    /// [`ReaderOptions::deadline`] to bound the central directory scan too.
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.options.deadline = Some(deadline);
+        self
+    }
 
-        // Read compression method (skip — use value from central directory)
-        self.reader.seek(SeekFrom::Current(2)).await?;
+    /// Draw the read-ahead buffers of entry streams from `budget`, shared
+    /// with other jobs.
+    ///
+    /// Streams opened while the budget is exhausted use small unreserved
+    /// buffers instead. See [`crate::budget`].
+    pub fn with_memory_budget(mut self, budget: &MemoryBudget) -> Self {
+        self.memory_budget = Some(budget.clone());
+        self
+    }
 
-        // Skip modification time and date, CRC-32
-        self.reader.seek(SeekFrom::Current(8)).await?;
+    /// Read an entry's decompressed data into a vector
+    ///
+    /// Entries passed to [`prefetch`](Self::prefetch) are decoded from the
+    /// prefetched bytes instead of the source.
+    pub async fn read_entry(&mut self, entry: &ZipEntry) -> Result<Vec<u8>> {
+        crate::deadline::check(self.options.deadline)?;
+        #[cfg(feature = "async-zstd")]
+        let zstd_dictionary = self.zstd_dictionary_for(entry).await?;
+        if let Some(mut task) = self.prefetched.remove(&entry.offset) {
+            // A failed prefetch falls through to a normal read
+            if let Ok(Ok(record)) = (&mut task.0).await {
+                return Self::read_entry_at(
+                    std::io::Cursor::new(record),
+                    entry,
+                    0,
+                    self.options.quirks,
+                    #[cfg(feature = "encryption")]
+                    self.password.as_deref(),
+                    #[cfg(feature = "async-zstd")]
+                    zstd_dictionary.as_ref(),
+                )
+                .await;
+            }
+        }
+        Self::read_entry_at(
+            &mut self.reader,
+            entry,
+            entry.offset,
+            self.options.quirks,
+            #[cfg(feature = "encryption")]
+            self.password.as_deref(),
+            #[cfg(feature = "async-zstd")]
+            zstd_dictionary.as_ref(),
+        )
+        .await
+    }
 
-        // Skip compressed and uncompressed sizes (use from central directory)
-        self.reader.seek(SeekFrom::Current(8)).await?;
+    /// Decode the entry whose local file header starts at `header_offset` in `src`
+    async fn read_entry_at<S: AsyncRead + AsyncSeek + Unpin>(
+        mut src: S,
+        entry: &ZipEntry,
+        header_offset: u64,
+        quirks: QuirksMode,
+        #[cfg(feature = "encryption")] password: Option<&str>,
+        #[cfg(feature = "async-zstd")] zstd_dictionary: Option<&ZstdDictionary>,
+    ) -> Result<Vec<u8>> {
+        // Seek to local file header
+        src.seek(SeekFrom::Start(header_offset)).await?;
 
-        // Read filename length and extra field length
-        let filename_len = self.read_u16_le().await? as i64;
-        let extra_len = self.read_u16_le().await? as usize;
+        // Read and verify local file header; sizes and method come from the
+        // central directory
+        let header = Self::read_local_header(&mut src).await?;
+        let is_encrypted = if quirks.is_lenient() {
+            entry.is_encrypted
+        } else {
+            (header.flags & 0x01) != 0
+        };
+        let filename_len = header.name_len as i64;
+        let extra_len = header.extra_len as usize;
 
         // Skip filename
-        self.reader.seek(SeekFrom::Current(filename_len)).await?;
+        src.seek(SeekFrom::Current(filename_len)).await?;
 
         // Parse AES extra field if encrypted
         #[cfg(feature = "encryption")]
-        let encryption_info = if is_encrypted_local {
-            self.parse_aes_extra_field(extra_len).await?
+        let encryption_info = if is_encrypted {
+            Self::parse_aes_extra_field(&mut src, extra_len).await?
         } else {
-            self.reader
-                .seek(SeekFrom::Current(extra_len as i64))
-                .await?;
+            src.seek(SeekFrom::Current(extra_len as i64)).await?;
             None
         };
 
         #[cfg(not(feature = "encryption"))]
         {
-            if is_encrypted_local {
+            if is_encrypted {
                 return Err(SZipError::InvalidFormat(
                     "Encrypted entry found but encryption feature not enabled".to_string(),
                 ));
             }
-            self.reader
-                .seek(SeekFrom::Current(extra_len as i64))
-                .await?;
+            src.seek(SeekFrom::Current(extra_len as i64)).await?;
         }
 
-        // Guard against OOM from corrupt/malicious compressed_size values.
         // For encrypted entries, the actual data size is smaller (salt + pw_verify already consumed).
         #[cfg(feature = "encryption")]
         let data_size = if let Some((strength, _, _)) = encryption_info {
@@ -275,78 +625,55 @@ impl<R: AsyncRead + AsyncSeek + Unpin + Send> GenericAsyncZipReader<R> {
         #[cfg(not(feature = "encryption"))]
         let data_size = entry.compressed_size;
 
-        if data_size > MAX_ENTRY_ALLOC {
-            return Err(SZipError::InvalidFormat(format!(
-                "Entry '{}' is too large to read into memory ({} bytes). \
-                 Use read_entry_streaming() for entries larger than 2 GiB.",
-                entry.name, data_size
-            )));
-        }
-
-        // Read compressed (and possibly encrypted) data
-        let mut compressed_data = vec![0u8; data_size as usize];
-        self.reader.read_exact(&mut compressed_data).await?;
-
-        // Read auth code if encrypted (10 bytes HMAC-SHA1 truncated)
         #[cfg(feature = "encryption")]
-        let auth_code = if encryption_info.is_some() {
-            let mut ac = vec![0u8; 10];
-            self.reader.read_exact(&mut ac).await?;
-            Some(ac)
-        } else {
-            None
-        };
+        let data_start = src.stream_position().await?;
 
-        // Decrypt compressed data in-place (Step 1)
+        // Set up decryption (Step 1)
         #[cfg(feature = "encryption")]
-        let decryptor_opt = if let Some((strength, salt, pw_verify)) = encryption_info {
+        let mut decryptor_opt = if let Some((strength, salt, pw_verify)) = encryption_info {
             use crate::encryption::AesDecryptor;
-            let password = self.password.as_ref().ok_or_else(|| {
+            let password = password.ok_or_else(|| {
                 SZipError::EncryptionError(
                     "Encrypted entry but no password set. Call set_password() first.".to_string(),
                 )
             })?;
-            let mut decryptor = AesDecryptor::new(password, strength, &salt, &pw_verify)?;
-            decryptor.decrypt(&mut compressed_data)?;
-            Some(decryptor)
+            Some(AesDecryptor::new(password, strength, &salt, &pw_verify)?)
         } else {
             None
         };
 
-        // Decompress if needed (Step 2)
-        let data = if entry.compression_method == 8 {
-            // DEFLATE compression
-            let mut decoder = DeflateDecoder::new(&compressed_data[..]);
-            let mut decompressed = Vec::new();
-            decoder.read_to_end(&mut decompressed).await?;
-            decompressed
-        } else if entry.compression_method == 0 {
-            // No compression (stored)
-            compressed_data
-        } else if entry.compression_method == 93 {
-            // Zstd compression
-            #[cfg(feature = "async-zstd")]
-            {
-                let mut decoder = ZstdDecoder::new(&compressed_data[..]);
-                let mut decompressed = Vec::new();
-                decoder.read_to_end(&mut decompressed).await?;
-                decompressed
-            }
-            #[cfg(not(feature = "async-zstd"))]
-            {
-                return Err(SZipError::UnsupportedCompression(entry.compression_method));
-            }
-        } else {
-            return Err(SZipError::UnsupportedCompression(entry.compression_method));
-        };
+        // Stream the payload through decryption and decompression (Step 2).
+        // Header sizes are untrusted, so only a bounded buffer is reserved upfront.
+        let mut data = Vec::with_capacity(entry.uncompressed_size.min(INITIAL_ALLOC_CAP) as usize);
+        {
+            let limited = (&mut src).take(data_size);
+            #[cfg(feature = "encryption")]
+            let source = CtrReader {
+                inner: limited,
+                decryptor: decryptor_opt.as_mut(),
+            };
+            #[cfg(not(feature = "encryption"))]
+            let source = limited;
+            Self::decompress_into(
+                entry,
+                source,
+                data_size,
+                &mut data,
+                #[cfg(feature = "async-zstd")]
+                zstd_dictionary,
+            )
+            .await?;
+        }
 
-        // Verify HMAC authentication for encrypted entries (Step 3)
+        // Verify HMAC authentication for encrypted entries (Step 3);
+        // the auth code follows the data
         #[cfg(feature = "encryption")]
         if let Some(mut decryptor) = decryptor_opt {
+            src.seek(SeekFrom::Start(data_start + data_size)).await?;
+            let mut auth_code = [0u8; 10];
+            src.read_exact(&mut auth_code).await?;
             decryptor.update_hmac(&data);
-            if let Some(ac) = auth_code {
-                decryptor.verify_auth_code(&ac)?;
-            }
+            decryptor.verify_auth_code(&auth_code)?;
         }
 
         // Verify CRC-32 integrity — catches bit-rot and truncated downloads.
@@ -375,12 +702,332 @@ impl<R: AsyncRead + AsyncSeek + Unpin + Send> GenericAsyncZipReader<R> {
         self.read_entry(&entry).await
     }
 
+    /// Read the entry at `index` in [`entries()`](Self::entries)
+    pub async fn read_entry_by_index(&mut self, index: usize) -> Result<Vec<u8>> {
+        let entry = self
+            .entries
+            .get(index)
+            .ok_or_else(|| SZipError::EntryNotFound(format!("index {}", index)))?
+            .clone();
+
+        self.read_entry(&entry).await
+    }
+
+    /// Read several entries by name in a single forward pass.
+    ///
+    /// The requested entries are sorted by their local header offset before
+    /// reading, so remote sources (S3, HTTP) see strictly increasing range
+    /// requests instead of random seeks.  Much faster than N independent
+    /// `read_entry_by_name` calls on high-latency sources.
+    ///
+    /// Duplicate names, and names whose central records point at the same
+    /// local header, are read once; every requested name is a key of the
+    /// result.  Returns `SZipError::EntryNotFound` for the first name that
+    /// is not present in the archive.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use s_zip::AsyncStreamingZipReader;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut reader = AsyncStreamingZipReader::open("archive.zip").await?;
+    /// let files = reader.read_entries(&["a.json", "b.json"]).await?;
+    /// println!("a.json: {} bytes", files["a.json"].len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn read_entries(&mut self, names: &[&str]) -> Result<HashMap<String, Vec<u8>>> {
+        let targets = crate::reader::entries_named(&self.entries, names)?;
+        let mut results: HashMap<String, Vec<u8>> = HashMap::with_capacity(targets.len());
+        let mut previous: Option<&ZipEntry> = None;
+        for entry in &targets {
+            // Names aliasing the data just read share it
+            let data = match previous.filter(|p| p.offset == entry.offset) {
+                Some(alias) => results[&alias.name].clone(),
+                None => self.read_entry(entry).await?,
+            };
+            results.insert(entry.name.clone(), data);
+            previous = Some(entry);
+        }
+        Ok(results)
+    }
+
+    /// Extract every entry into `dir`, restoring Unix modes and mtimes.
+    ///
+    /// Shorthand for [`extract_to_dir_with_options`](Self::extract_to_dir_with_options)
+    /// with `ExtractOptions::default()`.
+    pub async fn extract_to_dir<P: AsRef<Path>>(&mut self, dir: P) -> Result<()> {
+        self.extract_to_dir_with_options(dir, &ExtractOptions::default())
+            .await
+    }
+
+    /// Extract every entry into `dir`, restoring the metadata selected in `options`.
+    ///
+    /// Entries are written one after another through this reader's source;
+    /// for cloneable sources, [`extract_all`](Self::extract_all) and
+    /// [`extract_entries_parallel`](Self::extract_entries_parallel) write
+    /// several at once.
+    ///
+    /// Archives with absolute entry names or `..` components are refused
+    /// before anything is written; clear [`ExtractOptions::validate_names`]
+    /// to sanitize such names with [`ZipEntry::safe_path`] instead.
+    /// Parent directories are created as needed and entry data is streamed,
+    /// never fully buffered.  Use
+    /// [`ExtractOptions::with_filter`] / [`ExtractOptions::with_rename`] to
+    /// extract a subset or relocate entries.
+    pub async fn extract_to_dir_with_options<P: AsRef<Path>>(
+        &mut self,
+        dir: P,
+        options: &ExtractOptions,
+    ) -> Result<()> {
+        extract::check_names(&self.entries, options)?;
+        let dir = dir.as_ref();
+        tokio::fs::create_dir_all(dir).await?;
+
+        let entries = self.entries.clone();
+        let mut dirs = Vec::new();
+        let mut links = Vec::new();
+        for entry in &entries {
+            let Some(path) = extract::output_path(dir, entry, options) else {
+                continue;
+            };
+            if extract::is_dir_entry(entry) {
+                tokio::fs::create_dir_all(&path).await?;
+                dirs.push((entry, path));
+                continue;
+            }
+            if extract::is_link_entry(entry) {
+                links.push((entry, path));
+                continue;
+            }
+            self.extract_file(entry, &path, options).await?;
+        }
+        // Links last, so no entry is written through one
+        for (entry, path) in links {
+            let target = self.read_symlink_target(entry).await?;
+            extract::create_symlink(dir, &path, entry, &target)?;
+        }
+        extract::apply_dir_metadata(dirs, options)?;
+        Ok(())
+    }
+
+    /// Target of a symbolic link entry (see [`ZipEntry::is_symlink`]).
+    ///
+    /// Fails if the entry is not a link, or its target is longer than
+    /// [`MAX_SYMLINK_TARGET`](crate::format::MAX_SYMLINK_TARGET) bytes or not UTF-8.
+    /// Extraction recreates links on Unix instead of writing their target as data.
+    pub async fn read_symlink_target(&mut self, entry: &ZipEntry) -> Result<String> {
+        extract::check_symlink_entry(entry)?;
+        let data = self.read_entry(entry).await?;
+        extract::symlink_target(entry, data)
+    }
+
+    /// Stream a single entry to `path` without loading it into memory.
+    ///
+    /// Data is written to a temporary file next to `path` and renamed into
+    /// place only after the CRC-32 has been verified, so `path` never holds a
+    /// partial or corrupt file.  Parent directories are created as needed and
+    /// the Unix mode and mtime are restored as with `ExtractOptions::default()`.
+    pub async fn extract_entry_to_path<P: AsRef<Path>>(
+        &mut self,
+        entry: &ZipEntry,
+        path: P,
+    ) -> Result<()> {
+        self.extract_file(entry, path.as_ref(), &ExtractOptions::default())
+            .await
+    }
+
+    /// Extract one file entry via a verified temporary file
+    async fn extract_file(
+        &mut self,
+        entry: &ZipEntry,
+        path: &Path,
+        options: &ExtractOptions,
+    ) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        // Removed on error, and when the extraction is dropped mid-write
+        let tmp = extract::TempFile::new(path);
+        self.write_entry_file(entry, tmp.path(), options).await?;
+        tokio::fs::rename(tmp.path(), path).await?;
+        tmp.keep();
+        Ok(())
+    }
+
+    /// Write and verify an entry's data at `path`, then restore its metadata
+    async fn write_entry_file(
+        &mut self,
+        entry: &ZipEntry,
+        path: &Path,
+        options: &ExtractOptions,
+    ) -> Result<()> {
+        let file = tokio::fs::OpenOptions::from(extract::open_options(entry))
+            .open(path)
+            .await?;
+        let mut writer = extract::CrcWriter::new(file);
+        #[cfg(feature = "async-zstd")]
+        let zstd_dictionary = self.zstd_dictionary_for(entry).await?;
+        {
+            // Concrete stream (not boxed) so this future is `Send` when `R` is
+            let mut stream = Self::entry_stream(
+                &mut self.reader,
+                entry,
+                #[cfg(feature = "encryption")]
+                self.password.as_deref(),
+                #[cfg(feature = "async-zstd")]
+                zstd_dictionary.as_ref(),
+                self.memory_budget.as_ref(),
+            )
+            .await?;
+            let mut buf = vec![0u8; 64 * 1024];
+            loop {
+                let n = stream.read(&mut buf).await?;
+                if n == 0 {
+                    break;
+                }
+                writer.update(&buf[..n]);
+                writer.inner.write_all(&buf[..n]).await?;
+            }
+        }
+        extract::check_crc(entry, writer.crc32())?;
+        writer.inner.flush().await?;
+        let file = writer.inner.into_std().await;
+        extract::apply_metadata(&file, path, entry, options)?;
+        Ok(())
+    }
+
+    /// Test every entry, like `unzip -t`.
+    ///
+    /// Async counterpart of [`StreamingZipReader::verify`](crate::StreamingZipReader::verify):
+    /// local headers are compared against the central directory and entry data
+    /// is decompressed to check the CRC-32 and size.  Problems are collected
+    /// per entry in the returned report.
+    pub async fn verify(&mut self) -> Result<VerifyReport> {
+        self.check_entries(true).await
+    }
+
+    /// Cheap consistency check: compare every local header with the central
+    /// directory without reading any entry data.
+    ///
+    /// Catches truncated archives, mismatched names, methods and flags in a
+    /// few bytes of I/O per entry, so it stays fast on multi-gigabyte files.
+    /// Use [`verify`](Self::verify) to also check CRCs.
+    pub async fn quick_check(&mut self) -> Result<VerifyReport> {
+        self.check_entries(false).await
+    }
+
+    /// Shared body of `verify` / `quick_check`
+    async fn check_entries(&mut self, read_data: bool) -> Result<VerifyReport> {
+        let archive_len = self.reader.seek(SeekFrom::End(0)).await?;
+        let entries = self.entries.clone();
+        let mut report = VerifyReport::default();
+        for entry in &entries {
+            let mut problems = match self.check_local_header(entry, archive_len).await {
+                Ok(problems) => problems,
+                Err(e) => vec![EntryProblem::BadLocalHeader(e.to_string())],
+            };
+            if self.options.quirks.is_lenient() {
+                // The central directory is trusted over the local header
+                problems.retain(|p| !p.is_local_mismatch());
+            }
+            if read_data && !problems.iter().any(EntryProblem::is_fatal) {
+                match self.checksum_entry(entry).await {
+                    Ok((crc, size)) => problems.extend(verify::check_data(entry, crc, size)),
+                    Err(e) => problems.push(EntryProblem::ReadError(e.to_string())),
+                }
+            }
+            report.entries.push(EntryReport {
+                name: entry.name.clone(),
+                problems,
+            });
+        }
+        Ok(report)
+    }
+
+    /// Read the local header of `entry` and compare it with the central directory
+    async fn check_local_header(
+        &mut self,
+        entry: &ZipEntry,
+        archive_len: u64,
+    ) -> Result<Vec<EntryProblem>> {
+        self.reader.seek(SeekFrom::Start(entry.offset)).await?;
+        let mut fixed = [0u8; LOCAL_HEADER_LEN];
+        self.reader.read_exact(&mut fixed).await?;
+        let mut variable = vec![0u8; verify::local_variable_len(&fixed)];
+        self.reader.read_exact(&mut variable).await?;
+        Ok(verify::check_local_header(
+            entry,
+            &fixed,
+            &variable,
+            &self.options.filename_encoding,
+            archive_len,
+        ))
+    }
+
+    /// Where `entry`'s stored data lies in the archive, as
+    /// `(data_offset, data_len)`.
+    ///
+    /// Async counterpart of
+    /// [`StreamingZipReader::data_span`](crate::StreamingZipReader::data_span).
+    pub async fn data_span(&mut self, entry: &ZipEntry) -> Result<(u64, u64)> {
+        self.reader.seek(SeekFrom::Start(entry.offset)).await?;
+        let mut fixed = [0u8; LOCAL_HEADER_LEN];
+        self.reader.read_exact(&mut fixed).await?;
+        Ok((verify::data_offset(entry, &fixed)?, entry.compressed_size))
+    }
+
+    /// Extra field of `entry`'s local header, and its stored data
+    pub(crate) async fn raw_entry(
+        &mut self,
+        entry: &ZipEntry,
+    ) -> Result<(Vec<u8>, Take<&mut BufReader<R>>)> {
+        self.reader.seek(SeekFrom::Start(entry.offset)).await?;
+        let mut fixed = [0u8; LOCAL_HEADER_LEN];
+        self.reader.read_exact(&mut fixed).await?;
+        let header = LocalHeader::parse(&fixed)?;
+        self.reader
+            .seek(SeekFrom::Current(header.name_len as i64))
+            .await?;
+        let mut extra = vec![0u8; header.extra_len as usize];
+        self.reader.read_exact(&mut extra).await?;
+        Ok((extra, (&mut self.reader).take(entry.compressed_size)))
+    }
+
+    /// Decompress `entry`, returning its CRC-32 and size
+    async fn checksum_entry(&mut self, entry: &ZipEntry) -> Result<(u32, u64)> {
+        #[cfg(feature = "async-zstd")]
+        let zstd_dictionary = self.zstd_dictionary_for(entry).await?;
+        let mut stream = Self::entry_stream(
+            &mut self.reader,
+            entry,
+            #[cfg(feature = "encryption")]
+            self.password.as_deref(),
+            #[cfg(feature = "async-zstd")]
+            zstd_dictionary.as_ref(),
+            self.memory_budget.as_ref(),
+        )
+        .await?;
+        let mut hasher = crc32fast::Hasher::new();
+        let mut size = 0u64;
+        let mut buf = vec![0u8; 64 * 1024];
+        loop {
+            let n = stream.read(&mut buf).await?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buf[..n]);
+            size += n as u64;
+        }
+        Ok((hasher.finalize(), size))
+    }
+
     /// Get a streaming reader for an entry by name (for large files)
     /// Returns a reader that decompresses data on-the-fly without loading everything into memory
     pub async fn read_entry_streaming_by_name(
         &mut self,
         name: &str,
-    ) -> Result<Box<dyn AsyncRead + Unpin + Send + '_>> {
+    ) -> Result<AsyncZipEntryReader<'_, R>> {
         let entry = self
             .find_entry(name)
             .ok_or_else(|| SZipError::EntryNotFound(name.to_string()))?
@@ -391,8 +1038,11 @@ impl<R: AsyncRead + AsyncSeek + Unpin + Send> GenericAsyncZipReader<R> {
 
     /// Get a streaming reader for an entry (for large files).
     ///
-    /// Returns an `AsyncRead` impl that decompresses (and, if encrypted, decrypts)
-    /// data on-the-fly without loading the entire entry into memory.
+    /// Returns an [`AsyncZipEntryReader`] that decompresses (and, if encrypted,
+    /// decrypts) data on-the-fly without loading the entire entry into memory.
+    /// It implements `AsyncBufRead`, so `AsyncBufReadExt::lines()` and
+    /// `read_line()` work directly on the entry; it is `Send` when `R` is, so
+    /// `!Send` sources work on a `current_thread` runtime.
     ///
     /// # Encrypted entries
     ///
@@ -409,41 +1059,152 @@ impl<R: AsyncRead + AsyncSeek + Unpin + Send> GenericAsyncZipReader<R> {
     pub async fn read_entry_streaming(
         &mut self,
         entry: &ZipEntry,
-    ) -> Result<Box<dyn AsyncRead + Unpin + Send + '_>> {
-        // Seek to local file header
-        self.reader.seek(SeekFrom::Start(entry.offset)).await?;
+    ) -> Result<AsyncZipEntryReader<'_, R>> {
+        let deadline = self.options.deadline;
+        crate::deadline::check(deadline)?;
+        #[cfg(feature = "async-zstd")]
+        let zstd_dictionary = self.zstd_dictionary_for(entry).await?;
+        let stream = Self::entry_stream(
+            &mut self.reader,
+            entry,
+            #[cfg(feature = "encryption")]
+            self.password.as_deref(),
+            #[cfg(feature = "async-zstd")]
+            zstd_dictionary.as_ref(),
+            self.memory_budget.as_ref(),
+        )
+        .await?;
+        Ok(AsyncZipEntryReader {
+            entry: entry.clone(),
+            inner: BufReader::new(DeadlineReader::new(stream, deadline)),
+        })
+    }
 
-        // Read and verify local file header
-        let signature = self.read_u32_le().await?;
-        if signature != LOCAL_FILE_HEADER_SIGNATURE {
-            return Err(SZipError::InvalidFormat(
-                "Invalid local file header signature".to_string(),
-            ));
-        }
+    /// Get a streaming reader for an entry by name
+    pub async fn read_entry_by_name_streaming(
+        &mut self,
+        name: &str,
+    ) -> Result<AsyncZipEntryReader<'_, R>> {
+        let entry = self
+            .find_entry(name)
+            .ok_or_else(|| SZipError::EntryNotFound(name.to_string()))?
+            .clone();
 
-        // Skip version, flags, compression method
-        self.reader.seek(SeekFrom::Current(6)).await?;
+        self.read_entry_streaming(&entry).await
+    }
 
-        // Skip modification time and date, CRC-32
-        self.reader.seek(SeekFrom::Current(8)).await?;
+    /// Decompress `data_size` bytes from `source` into `out` for `read_entry`.
+    ///
+    /// Output is capped at `MAX_ENTRY_ALLOC` so a decompression bomb fails
+    /// instead of exhausting memory.
+    async fn decompress_into<S: AsyncRead + Unpin>(
+        entry: &ZipEntry,
+        source: S,
+        data_size: u64,
+        out: &mut Vec<u8>,
+        #[cfg(feature = "async-zstd")] zstd_dictionary: Option<&ZstdDictionary>,
+    ) -> Result<()> {
+        let limit = MAX_ENTRY_ALLOC + 1;
+        match entry.compression_method {
+            8 => {
+                DeflateDecoder::new(BufReader::new(source))
+                    .take(limit)
+                    .read_to_end(out)
+                    .await?
+            }
+            0 => {
+                let n = source.take(limit).read_to_end(out).await?;
+                if (n as u64) < data_size {
+                    return Err(SZipError::Io(std::io::ErrorKind::UnexpectedEof.into()));
+                }
+                n
+            }
+            #[cfg(feature = "async-zstd")]
+            93 => {
+                let source = BufReader::new(source);
+                zstd_decoder(source, zstd_dictionary)?
+                    .take(limit)
+                    .read_to_end(out)
+                    .await?
+            }
+            #[cfg(feature = "deflate64")]
+            9 => {
+                Deflate64Decoder::new(BufReader::new(source))
+                    .take(limit)
+                    .read_to_end(out)
+                    .await?
+            }
+            #[cfg(feature = "bzip2")]
+            12 => {
+                BzDecoder::new(BufReader::new(source))
+                    .take(limit)
+                    .read_to_end(out)
+                    .await?
+            }
+            #[cfg(feature = "lzma")]
+            14 => {
+                let mut source = source;
+                let header = crate::lzma::read_alone_header(
+                    &mut source,
+                    entry.flags,
+                    entry.uncompressed_size,
+                )
+                .await?;
+                LzmaDecoder::new(BufReader::new(std::io::Cursor::new(header).chain(source)))
+                    .take(limit)
+                    .read_to_end(out)
+                    .await?
+            }
+            #[cfg(feature = "lzma")]
+            95 => {
+                XzDecoder::new(BufReader::new(source))
+                    .take(limit)
+                    .read_to_end(out)
+                    .await?
+            }
+            method => return Err(SZipError::UnsupportedCompression(method)),
+        };
+        if out.len() as u64 > MAX_ENTRY_ALLOC {
+            return Err(SZipError::InvalidFormat(format!(
+                "Entry '{}' is too large to read into memory (more than {} bytes). \
+                 Use read_entry_streaming() for entries larger than 2 GiB.",
+                entry.name, MAX_ENTRY_ALLOC
+            )));
+        }
+        Ok(())
+    }
 
-        // Read compressed and uncompressed sizes (use from central directory)
-        self.reader.seek(SeekFrom::Current(8)).await?;
+    /// Position `src` at the entry's data and wrap it in the appropriate
+    /// decryption/decompression layers.
+    ///
+    /// Generic over the source so the same logic serves both the borrowing
+    /// (`&mut BufReader<R>`) and owning (`BufReader<R>`) entry readers, and
+    /// both the `Send` and local streaming APIs.
+    async fn entry_stream<S: AsyncRead + AsyncSeek + Unpin>(
+        mut src: S,
+        entry: &ZipEntry,
+        #[cfg(feature = "encryption")] password: Option<&str>,
+        #[cfg(feature = "async-zstd")] zstd_dictionary: Option<&ZstdDictionary>,
+        budget: Option<&MemoryBudget>,
+    ) -> Result<EntryStream<S>> {
+        // Seek to local file header
+        src.seek(SeekFrom::Start(entry.offset)).await?;
 
-        // Read filename length and extra field length
-        let filename_len = self.read_u16_le().await? as i64;
-        let extra_len = self.read_u16_le().await? as usize;
+        // Read and verify local file header; sizes come from the central directory
+        let header = Self::read_local_header(&mut src).await?;
+        let filename_len = header.name_len as i64;
+        let extra_len = header.extra_len as usize;
 
         // Skip filename
-        self.reader.seek(SeekFrom::Current(filename_len)).await?;
+        src.seek(SeekFrom::Current(filename_len)).await?;
 
         // For encrypted entries: decrypt on-the-fly.
         #[cfg(feature = "encryption")]
         if entry.is_encrypted {
-            let encryption_info = self.parse_aes_extra_field(extra_len).await?;
+            let encryption_info = Self::parse_aes_extra_field(&mut src, extra_len).await?;
 
             if let Some((strength, salt, pw_verify)) = encryption_info {
-                let password = self.password.as_ref().ok_or_else(|| {
+                let password = password.ok_or_else(|| {
                     SZipError::EncryptionError(
                         "Encrypted entry but no password set. Call set_password() first."
                             .to_string(),
@@ -454,17 +1215,14 @@ impl<R: AsyncRead + AsyncSeek + Unpin + Send> GenericAsyncZipReader<R> {
                 let cipher_size = entry.compressed_size.saturating_sub(overhead);
 
                 // Read auth code (seek past ciphertext, read 10 bytes, seek back)
-                let current_pos = self.reader.stream_position().await?;
-                self.reader
-                    .seek(SeekFrom::Start(current_pos + cipher_size))
-                    .await?;
+                let current_pos = src.stream_position().await?;
+                src.seek(SeekFrom::Start(current_pos + cipher_size)).await?;
                 let mut auth_code = vec![0u8; 10];
-                self.reader.read_exact(&mut auth_code).await?;
-                self.reader.seek(SeekFrom::Start(current_pos)).await?;
+                src.read_exact(&mut auth_code).await?;
+                src.seek(SeekFrom::Start(current_pos)).await?;
 
-                let limited_reader = (&mut self.reader).take(cipher_size);
+                let limited_reader = DoubleBuffer::with_budget(src.take(cipher_size), budget);
 
-                use crate::decrypt_reader::r#async::AsyncDecryptingReader;
                 let decrypt_reader = AsyncDecryptingReader::new(
                     limited_reader,
                     password,
@@ -475,351 +1233,713 @@ impl<R: AsyncRead + AsyncSeek + Unpin + Send> GenericAsyncZipReader<R> {
                 )?;
 
                 return if entry.compression_method == 8 {
-                    Ok(Box::new(DeflateDecoder::new(BufReader::new(
-                        decrypt_reader,
-                    ))))
+                    Ok(EntryStream::EncryptedDeflate(DeflateDecoder::new(
+                        BufReader::new(decrypt_reader),
+                    )))
                 } else if entry.compression_method == 0 {
-                    Ok(Box::new(decrypt_reader))
+                    Ok(EntryStream::EncryptedStored(decrypt_reader))
                 } else {
                     Err(SZipError::UnsupportedCompression(entry.compression_method))
                 };
-            } else {
-                self.reader
-                    .seek(SeekFrom::Current(extra_len as i64))
-                    .await?;
             }
+            // Extra field didn't contain AES info — it has already been
+            // consumed by parse_aes_extra_field, fall through to plain read.
         }
 
         // Non-encrypted path: skip extra field
         #[cfg(feature = "encryption")]
         if !entry.is_encrypted {
-            self.reader
-                .seek(SeekFrom::Current(extra_len as i64))
-                .await?;
+            src.seek(SeekFrom::Current(extra_len as i64)).await?;
         }
 
         #[cfg(not(feature = "encryption"))]
-        self.reader
-            .seek(SeekFrom::Current(extra_len as i64))
-            .await?;
+        src.seek(SeekFrom::Current(extra_len as i64)).await?;
 
-        // Create a reader limited to compressed data size
-        let limited_reader = (&mut self.reader).take(entry.compressed_size);
+        // Create a reader limited to compressed data size that fetches the
+        // next chunk while the current one is being decompressed
+        let limited_reader = DoubleBuffer::with_budget(src.take(entry.compressed_size), budget);
 
         // Wrap with decompressor if needed
         if entry.compression_method == 8 {
             // DEFLATE compression
-            Ok(Box::new(DeflateDecoder::new(BufReader::new(
-                limited_reader,
-            ))))
+            Ok(EntryStream::Deflate(DeflateDecoder::new(limited_reader)))
         } else if entry.compression_method == 0 {
             // No compression (stored)
-            Ok(Box::new(limited_reader))
+            Ok(EntryStream::Stored(limited_reader))
         } else if entry.compression_method == 93 {
             // Zstd compression
             #[cfg(feature = "async-zstd")]
             {
-                Ok(Box::new(ZstdDecoder::new(BufReader::new(limited_reader))))
+                Ok(EntryStream::Zstd(zstd_decoder(
+                    limited_reader,
+                    zstd_dictionary,
+                )?))
             }
             #[cfg(not(feature = "async-zstd"))]
             {
                 Err(SZipError::UnsupportedCompression(entry.compression_method))
             }
+        } else if entry.compression_method == 9 {
+            // Deflate64, as written by Windows for large files
+            #[cfg(feature = "deflate64")]
+            {
+                Ok(EntryStream::Deflate64(Deflate64Decoder::new(
+                    limited_reader,
+                )))
+            }
+            #[cfg(not(feature = "deflate64"))]
+            {
+                Err(SZipError::UnsupportedCompression(entry.compression_method))
+            }
+        } else if entry.compression_method == 12 {
+            // BZip2 compression
+            #[cfg(feature = "bzip2")]
+            {
+                Ok(EntryStream::Bzip2(BzDecoder::new(limited_reader)))
+            }
+            #[cfg(not(feature = "bzip2"))]
+            {
+                Err(SZipError::UnsupportedCompression(entry.compression_method))
+            }
+        } else if entry.compression_method == 14 {
+            // LZMA compression
+            #[cfg(feature = "lzma")]
+            {
+                let mut limited_reader = limited_reader;
+                let header = crate::lzma::read_alone_header(
+                    &mut limited_reader,
+                    entry.flags,
+                    entry.uncompressed_size,
+                )
+                .await?;
+                Ok(EntryStream::Lzma(LzmaDecoder::new(
+                    std::io::Cursor::new(header).chain(limited_reader),
+                )))
+            }
+            #[cfg(not(feature = "lzma"))]
+            {
+                Err(SZipError::UnsupportedCompression(entry.compression_method))
+            }
+        } else if entry.compression_method == 95 {
+            // XZ compression
+            #[cfg(feature = "lzma")]
+            {
+                Ok(EntryStream::Xz(XzDecoder::new(limited_reader)))
+            }
+            #[cfg(not(feature = "lzma"))]
+            {
+                Err(SZipError::UnsupportedCompression(entry.compression_method))
+            }
         } else {
             Err(SZipError::UnsupportedCompression(entry.compression_method))
         }
     }
 
-    /// Get a streaming reader for an entry by name
-    pub async fn read_entry_by_name_streaming(
-        &mut self,
-        name: &str,
-    ) -> Result<Box<dyn AsyncRead + Unpin + Send + '_>> {
-        let entry = self
-            .find_entry(name)
-            .ok_or_else(|| SZipError::EntryNotFound(name.to_string()))?
-            .clone();
-
-        self.read_entry_streaming(&entry).await
-    }
-
     /// Read the central directory from the ZIP file
-    async fn read_central_directory(reader: &mut BufReader<R>) -> Result<Vec<ZipEntry>> {
-        // Find end of central directory record
-        let eocd_offset = Self::find_eocd(reader).await?;
-
-        // Seek to EOCD
-        reader.seek(SeekFrom::Start(eocd_offset)).await?;
-
-        // Read EOCD
-        let signature = Self::read_u32_le_static(reader).await?;
-        if signature != END_OF_CENTRAL_DIRECTORY_SIGNATURE {
-            return Err(SZipError::InvalidFormat(format!(
-                "Invalid end of central directory signature: 0x{:08x}",
-                signature
-            )));
+    async fn read_central_directory(
+        reader: &mut BufReader<R>,
+        keep: Option<&NameFilter>,
+        options: &ReaderOptions,
+    ) -> Result<(Vec<ZipEntry>, Vec<u8>, CentralDirectoryLocation)> {
+        crate::deadline::check(options.deadline)?;
+        let mut locator = DirectoryLocator::new(reader.seek(SeekFrom::End(0)).await?);
+        while let Some(request) = locator.next_read() {
+            crate::deadline::check(options.deadline)?;
+            reader.seek(SeekFrom::Start(request.offset)).await?;
+            let mut buf = Vec::new();
+            (&mut *reader)
+                .take(request.len)
+                .read_to_end(&mut buf)
+                .await?;
+            locator.feed(&buf)?;
         }
+        let found = locator.finish()?;
+        let location = found.location;
 
-        // Skip disk number fields (4 bytes)
-        reader.seek(SeekFrom::Current(4)).await?;
-
-        // Read number of entries on this disk (2 bytes)
-        let _entries_on_disk = Self::read_u16_le_static(reader).await?;
-
-        // Read total number of entries (2 bytes)
-        // These values may be placeholder 0xFFFF/0xFFFFFFFF when ZIP64 is used
-        let total_entries_16 = Self::read_u16_le_static(reader).await?;
+        // Seek to central directory
+        reader.seek(SeekFrom::Start(location.offset)).await?;
+        let mut reader = DeadlineReader::new(reader, options.deadline);
 
-        // Read central directory size (4 bytes)
-        let cd_size_32 = Self::read_u32_le_static(reader).await?;
+        // Read all central directory entries; a filtered list grows as needed
+        let mut entries = match keep {
+            Some(_) => Vec::new(),
+            None => Vec::with_capacity(location.count as usize),
+        };
+        let mut records = CentralRecordReader::new(options.quirks, &options.filename_encoding);
+        let mut buf = vec![0u8; records.next_len()];
+        let mut remaining = location.count;
+        while remaining > 0 {
+            reader.read_exact(&mut buf).await?;
+            let read = records.feed(&buf)?;
+            buf.resize(records.next_len(), 0);
+            let entry = match read {
+                RecordRead::More => continue,
+                RecordRead::Entry(entry) => entry,
+                RecordRead::End => break,
+            };
+            remaining -= 1;
+            if keep.is_some_and(|keep| !keep(&entry.name)) {
+                continue;
+            }
+            entries.push(entry);
+        }
 
-        // Read central directory offset (4 bytes)
-        let cd_offset_32 = Self::read_u32_le_static(reader).await? as u64;
+        Ok((entries, found.comment, location))
+    }
 
-        // Promote to u64 and handle ZIP64 if markers present
-        let mut total_entries = total_entries_16 as usize;
-        let mut cd_offset = cd_offset_32;
-        let _cd_size = cd_size_32 as u64;
+    /// Read the central directory at a known location in one read
+    async fn read_central_directory_at(
+        reader: &mut BufReader<R>,
+        location: CentralDirectoryLocation,
+        options: &ReaderOptions,
+    ) -> Result<Vec<ZipEntry>> {
+        crate::deadline::check(options.deadline)?;
+        reader.seek(SeekFrom::Start(location.offset)).await?;
+        // The size is caller-supplied: `take` bounds the read, not the allocation
+        let mut buf = Vec::new();
+        DeadlineReader::new((&mut *reader).take(location.size), options.deadline)
+            .read_to_end(&mut buf)
+            .await?;
+        if (buf.len() as u64) < location.size {
+            return Err(SZipError::InvalidFormat(
+                "Central directory extends past the end of the archive".to_string(),
+            ));
+        }
+        crate::sansio::parse_central_directory_with_encoding(
+            &buf,
+            location.count,
+            options.quirks,
+            &options.filename_encoding,
+        )
+    }
 
-        if total_entries_16 == 0xFFFF || cd_size_32 == 0xFFFFFFFF || cd_offset_32 == 0xFFFFFFFF {
-            // Need to find ZIP64 EOCD locator and read ZIP64 EOCD record
-            let (zip64_total_entries, zip64_cd_size, zip64_cd_offset) =
-                Self::read_zip64_eocd(reader, eocd_offset).await?;
-            total_entries = zip64_total_entries as usize;
-            cd_offset = zip64_cd_offset;
-            // _cd_size can be used if needed (zip64_cd_size)
-            let _ = zip64_cd_size;
+    /// Fill in sizes and CRCs that the central directory left to data
+    /// descriptors (a Java quirk)
+    async fn recover_descriptor_sizes(
+        reader: &mut BufReader<R>,
+        entries: &mut [ZipEntry],
+    ) -> Result<()> {
+        for entry in entries
+            .iter_mut()
+            .filter(|e| quirks::needs_descriptor_sizes(e))
+        {
+            reader.seek(SeekFrom::Start(entry.offset)).await?;
+            let header = Self::read_local_header(reader).await?;
+            reader
+                .seek(SeekFrom::Current(header.variable_len() as i64))
+                .await?;
+            let mut scan = DescriptorScan::default();
+            let mut chunk = vec![0u8; DescriptorScan::CHUNK];
+            let descriptor = loop {
+                let n = reader.read(&mut chunk).await?;
+                if let Some(found) = scan.push(&chunk[..n]) {
+                    break found;
+                }
+            };
+            if let Some(descriptor) = descriptor {
+                entry.crc32 = descriptor.crc32;
+                entry.compressed_size = descriptor.compressed_size;
+                entry.uncompressed_size = descriptor.uncompressed_size;
+            }
         }
+        Ok(())
+    }
 
-        // Seek to central directory
-        reader.seek(SeekFrom::Start(cd_offset)).await?;
+    /// Read and decode the local file header at the current position
+    async fn read_local_header<S: AsyncRead + Unpin>(src: &mut S) -> Result<LocalHeader> {
+        let mut fixed = [0u8; LOCAL_HEADER_LEN];
+        src.read_exact(&mut fixed).await?;
+        LocalHeader::parse(&fixed)
+    }
 
-        // Read all central directory entries
-        let mut entries = Vec::with_capacity(total_entries);
-        for _ in 0..total_entries {
-            let signature = Self::read_u32_le_static(reader).await?;
-            if signature != CENTRAL_DIRECTORY_SIGNATURE {
-                break;
-            }
+    /// Parse the AES extra field (ID 0x9901) from the local file header extra data,
+    /// then read the salt and password-verification bytes that follow the extra field.
+    ///
+    /// Returns `Some((strength, salt, pw_verify))` if AES encryption is present,
+    /// or `None` if no AES extra field is found (extra field is consumed either way).
+    #[cfg(feature = "encryption")]
+    async fn parse_aes_extra_field<S: AsyncRead + Unpin>(
+        src: &mut S,
+        extra_len: usize,
+    ) -> Result<Option<(crate::encryption::AesStrength, Vec<u8>, [u8; 2])>> {
+        if extra_len == 0 {
+            return Ok(None);
+        }
 
-            // Skip version made by (2), version needed (2); read flags (2)
-            reader.seek(SeekFrom::Current(4)).await?;
-            let flags = Self::read_u16_le_static(reader).await?;
-            let is_encrypted = (flags & 0x01) != 0;
+        let mut extra_buf = vec![0u8; extra_len];
+        src.read_exact(&mut extra_buf).await?;
+        let Some(strength) = crate::sansio::parse_aes_extra(&extra_buf)? else {
+            return Ok(None);
+        };
 
-            let compression_method = Self::read_u16_le_static(reader).await?;
+        // Salt and password-verify bytes follow the extra field in the file data
+        let salt_size = strength.salt_size();
+        let mut salt = vec![0u8; salt_size];
+        src.read_exact(&mut salt).await?;
 
-            // Read modification time, date, and CRC-32
-            reader.seek(SeekFrom::Current(4)).await?; // mod time + date
-            let crc32 = Self::read_u32_le_static(reader).await?;
+        let mut pw_verify = [0u8; 2];
+        src.read_exact(&mut pw_verify).await?;
 
-            // Read sizes as 32-bit placeholders (may be 0xFFFFFFFF meaning ZIP64)
-            let compressed_size_32 = Self::read_u32_le_static(reader).await? as u64;
-            let uncompressed_size_32 = Self::read_u32_le_static(reader).await? as u64;
-            let filename_len = Self::read_u16_le_static(reader).await? as usize;
-            let extra_len = Self::read_u16_le_static(reader).await? as usize;
-            let comment_len = Self::read_u16_le_static(reader).await? as usize;
+        Ok(Some((strength, salt, pw_verify)))
+    }
+}
 
-            // Skip disk number, internal attributes, external attributes
-            reader.seek(SeekFrom::Current(8)).await?;
+impl<R: AsyncRead + AsyncSeek + Unpin + Send + 'static> GenericAsyncZipReader<R> {
+    /// Consume the reader and return an owned streaming reader for an entry.
+    ///
+    /// Unlike [`read_entry_streaming`](Self::read_entry_streaming), the
+    /// returned reader does not borrow `self`: it takes ownership of the
+    /// underlying source, so it is `Send + 'static` and can be moved into
+    /// `tokio::spawn` or returned from a function.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use s_zip::AsyncStreamingZipReader;
+    /// # use tokio::io::AsyncReadExt;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let reader = AsyncStreamingZipReader::open("archive.zip").await?;
+    /// let mut stream = reader.into_entry_reader("large.bin").await?;
+    ///
+    /// let handle = tokio::spawn(async move {
+    ///     let mut data = Vec::new();
+    ///     stream.read_to_end(&mut data).await.map(|_| data.len())
+    /// });
+    /// println!("read {} bytes", handle.await??);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(not(feature = "async-zstd"), allow(unused_mut))]
+    pub async fn into_entry_reader(
+        mut self,
+        name: &str,
+    ) -> Result<Box<dyn AsyncBufRead + Unpin + Send + 'static>> {
+        let entry = self
+            .find_entry(name)
+            .ok_or_else(|| SZipError::EntryNotFound(name.to_string()))?
+            .clone();
 
-            let offset_32 = Self::read_u32_le_static(reader).await? as u64;
+        #[cfg(feature = "encryption")]
+        let password = self.password.clone();
+
+        let deadline = self.options.deadline;
+        crate::deadline::check(deadline)?;
+        #[cfg(feature = "async-zstd")]
+        let zstd_dictionary = self.zstd_dictionary_for(&entry).await?;
+        let stream = Self::entry_stream(
+            self.reader,
+            &entry,
+            #[cfg(feature = "encryption")]
+            password.as_deref(),
+            #[cfg(feature = "async-zstd")]
+            zstd_dictionary.as_ref(),
+            self.memory_budget.as_ref(),
+        )
+        .await?;
+        Ok(Box::new(BufReader::new(DeadlineReader::new(
+            stream, deadline,
+        ))))
+    }
+}
 
-            // Read filename
-            let mut filename_buf = vec![0u8; filename_len];
-            reader.read_exact(&mut filename_buf).await?;
-            let name = String::from_utf8_lossy(&filename_buf).to_string();
+impl<R: AsyncRead + AsyncSeek + Unpin + Clone + Send + 'static> GenericAsyncZipReader<R> {
+    /// Extract every entry into `dir`, running up to `concurrency` entries at
+    /// once and restoring Unix modes and mtimes.
+    ///
+    /// Each task reads through its own clone of the source, so `R::clone` must
+    /// yield an independent handle with its own position — e.g.
+    /// `S3ZipReader`, where every clone issues its own ranged GET requests.
+    /// Concurrency is bounded with a semaphore, like
+    /// [`write_entries_parallel`](crate::AsyncStreamingZipWriter::write_entries_parallel).
+    /// For other [`ExtractOptions`], use
+    /// [`extract_entries_parallel`](Self::extract_entries_parallel); readers
+    /// over a source that cannot be cloned extract sequentially with
+    /// [`extract_to_dir_with_options`](Self::extract_to_dir_with_options).
+    ///
+    /// # Example
+    /// ```no_run
+    /// # #[cfg(feature = "cloud-s3")]
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// use s_zip::{cloud::S3ZipReader, GenericAsyncZipReader};
+    ///
+    /// let config = aws_config::load_from_env().await;
+    /// let client = aws_sdk_s3::Client::new(&config);
+    /// let source = S3ZipReader::new(client, "my-bucket", "archive.zip").await?;
+    /// let reader = GenericAsyncZipReader::new(source).await?;
+    /// reader.extract_all("out", 8).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn extract_all<P: AsRef<Path>>(&self, dir: P, concurrency: usize) -> Result<()> {
+        let config = ExtractConfig::default().with_workers(concurrency);
+        self.extract_entries_parallel(&self.entries, dir, &config)
+            .await
+    }
 
-            // Read extra field so we can parse ZIP64 extra if present
-            let mut extra_buf = vec![0u8; extra_len];
-            if extra_len > 0 {
-                reader.read_exact(&mut extra_buf).await?;
+    /// Extract `entries` into `dir`, running up to `config.workers` of them at once.
+    ///
+    /// Like [`extract_all`](Self::extract_all), but for any subset of the
+    /// archive — e.g. entries picked from
+    /// [`entries`](Self::entries) by name. Each task reads through its own
+    /// clone of the source; paths, filters and metadata follow
+    /// `config.options`, and symbolic links are created once every file is
+    /// written. The first failure is returned.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use s_zip::{ExtractConfig, GenericAsyncZipReader};
+    /// # async fn example<R>(reader: GenericAsyncZipReader<R>) -> Result<(), s_zip::SZipError>
+    /// # where R: tokio::io::AsyncRead + tokio::io::AsyncSeek + Unpin + Clone + Send + 'static {
+    /// let assets: Vec<_> = reader
+    ///     .entries()
+    ///     .iter()
+    ///     .filter(|e| e.name.starts_with("assets/"))
+    ///     .cloned()
+    ///     .collect();
+    /// reader
+    ///     .extract_entries_parallel(&assets, "out", &ExtractConfig::default())
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn extract_entries_parallel<P: AsRef<Path>>(
+        &self,
+        entries: &[ZipEntry],
+        dir: P,
+        config: &ExtractConfig,
+    ) -> Result<()> {
+        use std::sync::Arc;
+        use tokio::sync::Semaphore;
+
+        let options = &config.options;
+        extract::check_names(entries, options)?;
+        let dir = dir.as_ref();
+        tokio::fs::create_dir_all(dir).await?;
+
+        #[cfg(feature = "async-zstd")]
+        let zstd_dictionary = self.shared_zstd_dictionary().await?;
+        let semaphore = Arc::new(Semaphore::new(config.workers.max(1)));
+        let mut tasks = tokio::task::JoinSet::new();
+        let mut dirs = Vec::new();
+        let mut links = Vec::new();
+        for entry in entries {
+            let Some(path) = extract::output_path(dir, entry, options) else {
+                continue;
+            };
+            if extract::is_dir_entry(entry) {
+                tokio::fs::create_dir_all(&path).await?;
+                dirs.push((entry, path));
+                continue;
+            }
+            if extract::is_link_entry(entry) {
+                links.push((entry, path));
+                continue;
             }
 
-            // Resolve ZIP64 placeholders using shared pure helper
-            let (uncompressed_size, compressed_size, offset) = if compressed_size_32 == 0xFFFFFFFF
-                || uncompressed_size_32 == 0xFFFFFFFF
-                || offset_32 == 0xFFFFFFFF
+            let permit = Arc::clone(&semaphore).acquire_owned().await.map_err(|_| {
+                SZipError::Io(std::io::Error::other(
+                    "extraction cancelled: worker semaphore closed",
+                ))
+            })?;
+            let mut reader = self.fork();
+            #[cfg(feature = "async-zstd")]
             {
-                parse_zip64_extra_field(
-                    &extra_buf,
-                    compressed_size_32,
-                    uncompressed_size_32,
-                    offset_32,
-                )
-            } else {
-                (uncompressed_size_32, compressed_size_32, offset_32)
-            };
-
-            // Skip comment
-            if comment_len > 0 {
-                reader.seek(SeekFrom::Current(comment_len as i64)).await?;
+                reader.zstd_dictionary = zstd_dictionary.clone();
             }
-
-            entries.push(ZipEntry {
-                name,
-                compressed_size,
-                uncompressed_size,
-                compression_method,
-                offset,
-                crc32,
-                is_encrypted,
+            let entry = entry.clone();
+            let options = options.clone();
+            tasks.spawn(async move {
+                let _permit = permit;
+                reader.extract_file(&entry, &path, &options).await
             });
-        }
 
-        Ok(entries)
-    }
-
-    /// When EOCD indicates ZIP64 usage, find and read ZIP64 EOCD locator and record
-    async fn read_zip64_eocd(
-        reader: &mut BufReader<R>,
-        eocd_offset: u64,
-    ) -> Result<(u64, u64, u64)> {
-        // Search backwards from EOCD for ZIP64 EOCD locator signature (50 4b 06 07)
-        let search_start = eocd_offset.saturating_sub(65557);
-        reader.seek(SeekFrom::Start(search_start)).await?;
-        let mut buffer = Vec::new();
-        reader.read_to_end(&mut buffer).await?;
-
-        let zip64_eocd_offset = find_zip64_eocd_offset(&buffer)
-            .ok_or_else(|| SZipError::InvalidFormat("ZIP64 EOCD locator not found".to_string()))?;
-
-        // Seek to ZIP64 EOCD record
-        reader.seek(SeekFrom::Start(zip64_eocd_offset)).await?;
-
-        let sig = Self::read_u32_le_static(reader).await?;
-        if sig != ZIP64_END_OF_CENTRAL_DIRECTORY_SIGNATURE {
-            return Err(SZipError::InvalidFormat(format!(
-                "Invalid ZIP64 EOCD signature: 0x{:08x}",
-                sig
-            )));
+            // Surface failures early instead of after every entry is queued
+            while let Some(done) = tasks.try_join_next() {
+                done.map_err(|e| SZipError::Io(std::io::Error::other(e)))??;
+            }
         }
 
-        // size of ZIP64 EOCD record (8 bytes)
-        let _size = {
-            let mut buf = [0u8; 8];
-            reader.read_exact(&mut buf).await?;
-            u64::from_le_bytes(buf)
-        };
-
-        // skip version made by (2), version needed (2), disk number (4), disk where central dir starts (4)
-        reader.seek(SeekFrom::Current(12)).await?;
+        while let Some(done) = tasks.join_next().await {
+            done.map_err(|e| SZipError::Io(std::io::Error::other(e)))??;
+        }
+        // Links once every file is written, so none is written through one
+        let mut reader = self.fork();
+        for (entry, path) in links {
+            let target = reader.read_symlink_target(entry).await?;
+            extract::create_symlink(dir, &path, entry, &target)?;
+        }
+        extract::apply_dir_metadata(dirs, options)?;
+        Ok(())
+    }
 
-        // total number of entries on this disk (8)
-        let total_entries = {
-            let mut buf = [0u8; 8];
-            reader.read_exact(&mut buf).await?;
-            u64::from_le_bytes(buf)
+    /// Get an owned streaming reader for an entry without consuming `self`.
+    ///
+    /// The stream reads through its own clone of the source, so any number
+    /// of entries can be streamed at the same time — with `S3ZipReader`,
+    /// each stream issues its own ranged GET requests instead of queueing
+    /// behind a single position.  Decryption and `finish()` work as for
+    /// [`read_entry_streaming`](Self::read_entry_streaming).
+    ///
+    /// # Example
+    /// ```no_run
+    /// # #[cfg(feature = "cloud-s3")]
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// use s_zip::{cloud::S3ZipReader, GenericAsyncZipReader};
+    /// use tokio::io::AsyncReadExt;
+    ///
+    /// let config = aws_config::load_from_env().await;
+    /// let client = aws_sdk_s3::Client::new(&config);
+    /// let source = S3ZipReader::new(client, "my-bucket", "archive.zip").await?;
+    /// let reader = GenericAsyncZipReader::new(source).await?;
+    ///
+    /// let mut a = reader.shared_entry_reader(&reader.entries()[0]).await?;
+    /// let mut b = reader.shared_entry_reader(&reader.entries()[1]).await?;
+    /// let (mut data_a, mut data_b) = (Vec::new(), Vec::new());
+    /// tokio::try_join!(a.read_to_end(&mut data_a), b.read_to_end(&mut data_b))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn shared_entry_reader(
+        &self,
+        entry: &ZipEntry,
+    ) -> Result<Box<dyn AsyncBufRead + Unpin + Send + 'static>> {
+        let deadline = self.options.deadline;
+        crate::deadline::check(deadline)?;
+        #[cfg(feature = "async-zstd")]
+        let zstd_dictionary = match entry.compression_method {
+            93 => self.shared_zstd_dictionary().await?,
+            _ => None,
         };
+        let stream = Self::entry_stream(
+            self.fork().reader,
+            entry,
+            #[cfg(feature = "encryption")]
+            self.password.as_deref(),
+            #[cfg(feature = "async-zstd")]
+            zstd_dictionary.as_ref(),
+            self.memory_budget.as_ref(),
+        )
+        .await?;
+        Ok(Box::new(BufReader::new(DeadlineReader::new(
+            stream, deadline,
+        ))))
+    }
 
-        // total number of entries (8) - some implementations write both; ignore the second value
-        {
-            let mut buf = [0u8; 8];
-            reader.read_exact(&mut buf).await?;
+    /// Start fetching entries in the background ahead of reading them.
+    ///
+    /// For each named entry, a task reads its raw record (local header and
+    /// compressed data) through a clone of the source.  A later
+    /// [`read_entry`](Self::read_entry) (or `read_entry_by_name` /
+    /// `read_entries`) of that entry waits for the task and decodes the
+    /// fetched bytes, so with remote sources like `S3ZipReader` the request
+    /// latency overlaps with whatever the caller does in between — e.g.
+    /// parsing a manifest before reading the files it references.
+    ///
+    /// These are hints: unknown names are ignored, and an entry whose
+    /// prefetch fails is read from the source as usual.  Prefetched bytes are
+    /// held in memory until the entry is read; dropping the reader cancels
+    /// the fetches still running.
+    /// Must be called within a Tokio runtime.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use s_zip::GenericAsyncZipReader;
+    /// # async fn example<R>(mut reader: GenericAsyncZipReader<R>) -> Result<(), s_zip::SZipError>
+    /// # where R: tokio::io::AsyncRead + tokio::io::AsyncSeek + Unpin + Clone + Send + 'static {
+    /// let manifest = reader.read_entry_by_name("manifest.txt").await?;
+    /// let names: Vec<String> = String::from_utf8_lossy(&manifest)
+    ///     .lines()
+    ///     .map(str::to_string)
+    ///     .collect();
+    /// let names: Vec<&str> = names.iter().map(String::as_str).collect();
+    /// reader.prefetch(&names);
+    /// let files = reader.read_entries(&names).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn prefetch(&mut self, names: &[&str]) {
+        for name in names {
+            let Some(entry) = self.entries.iter().find(|e| e.name == *name) else {
+                continue;
+            };
+            if self.prefetched.contains_key(&entry.offset) {
+                continue;
+            }
+            let src = self.reader.get_ref().clone();
+            let (offset, compressed_size) = (entry.offset, entry.compressed_size);
+            let task = tokio::spawn(Self::fetch_record(src, offset, compressed_size));
+            self.prefetched.insert(offset, AbortOnDrop(task));
         }
+    }
 
-        // central directory size (8)
-        let cd_size = {
-            let mut buf = [0u8; 8];
-            reader.read_exact(&mut buf).await?;
-            u64::from_le_bytes(buf)
-        };
+    /// Read the raw record (local header, name, extra field and data) at `offset`
+    async fn fetch_record(
+        mut src: R,
+        offset: u64,
+        compressed_size: u64,
+    ) -> std::io::Result<Vec<u8>> {
+        src.seek(SeekFrom::Start(offset)).await?;
+        let mut record = vec![0u8; LOCAL_HEADER_LEN];
+        src.read_exact(&mut record).await?;
+        let name_len = u16::from_le_bytes([record[26], record[27]]) as u64;
+        let extra_len = u16::from_le_bytes([record[28], record[29]]) as u64;
+        // Sizes are untrusted: `take` bounds the read, not the allocation
+        (&mut src)
+            .take(name_len + extra_len + compressed_size)
+            .read_to_end(&mut record)
+            .await?;
+        Ok(record)
+    }
 
-        // central directory offset (8)
-        let cd_offset = {
-            let mut buf = [0u8; 8];
-            reader.read_exact(&mut buf).await?;
-            u64::from_le_bytes(buf)
+    /// The dictionary for Zstd entries without loading it into `self`: the
+    /// one set, or else the embedded one read through a clone of the source
+    #[cfg(feature = "async-zstd")]
+    async fn shared_zstd_dictionary(&self) -> Result<Option<ZstdDictionary>> {
+        let embedded = match (&self.zstd_dictionary, self.find_entry(DICTIONARY_ENTRY)) {
+            (None, Some(embedded)) => embedded,
+            _ => return Ok(self.zstd_dictionary.clone()),
         };
-
-        Ok((total_entries, cd_size, cd_offset))
+        let bytes = Self::read_entry_at(
+            self.fork().reader,
+            embedded,
+            embedded.offset,
+            self.options.quirks,
+            #[cfg(feature = "encryption")]
+            self.password.as_deref(),
+            None,
+        )
+        .await?;
+        Ok(Some(ZstdDictionary::new(bytes)))
     }
 
-    /// Find the end of central directory record by scanning from the end of the file
-    async fn find_eocd(reader: &mut BufReader<R>) -> Result<u64> {
-        let file_size = reader.seek(SeekFrom::End(0)).await?;
-
-        // EOCD is at least 22 bytes, search last 65KB (max comment size + EOCD)
-        let search_start = file_size.saturating_sub(65557);
-        reader.seek(SeekFrom::Start(search_start)).await?;
+    /// A reader over a clone of the source, for one concurrent task or stream
+    fn fork(&self) -> Self {
+        GenericAsyncZipReader {
+            // Smaller than the default 1MB: many of these may be live at once
+            reader: BufReader::with_capacity(256 * 1024, self.reader.get_ref().clone()),
+            entries: Vec::new(),
+            comment: Vec::new(),
+            cd_location: self.cd_location,
+            #[cfg(feature = "encryption")]
+            password: self.password.clone(),
+            #[cfg(feature = "async-zstd")]
+            zstd_dictionary: self.zstd_dictionary.clone(),
+            prefetched: HashMap::new(),
+            memory_budget: self.memory_budget.clone(),
+            options: self.options.clone(),
+            filter: self.filter.clone(),
+        }
+    }
+}
 
-        let mut buffer = Vec::new();
-        reader.read_to_end(&mut buffer).await?;
+/// Streaming reader for one entry, returned by
+/// [`GenericAsyncZipReader::read_entry_streaming`].
+///
+/// Decompresses (and decrypts) on the fly; implements [`AsyncRead`] and
+/// [`AsyncBufRead`] and keeps the [`ZipEntry`] it reads.
+pub struct AsyncZipEntryReader<'a, R: AsyncRead + AsyncSeek + Unpin> {
+    entry: ZipEntry,
+    inner: BufReader<DeadlineReader<EntryStream<&'a mut BufReader<R>>>>,
+}
 
-        find_eocd_in_buffer(&buffer, search_start).ok_or_else(|| {
-            SZipError::InvalidFormat("End of central directory not found".to_string())
-        })
+impl<R: AsyncRead + AsyncSeek + Unpin> AsyncZipEntryReader<'_, R> {
+    /// The entry being read
+    pub fn entry(&self) -> &ZipEntry {
+        &self.entry
     }
+}
 
-    async fn read_u16_le(&mut self) -> Result<u16> {
-        let mut buf = [0u8; 2];
-        self.reader.read_exact(&mut buf).await?;
-        Ok(u16::from_le_bytes(buf))
+impl<R: AsyncRead + AsyncSeek + Unpin> AsyncRead for AsyncZipEntryReader<'_, R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_read(cx, buf)
     }
+}
 
-    async fn read_u32_le(&mut self) -> Result<u32> {
-        let mut buf = [0u8; 4];
-        self.reader.read_exact(&mut buf).await?;
-        Ok(u32::from_le_bytes(buf))
+impl<R: AsyncRead + AsyncSeek + Unpin> AsyncBufRead for AsyncZipEntryReader<'_, R> {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<&[u8]>> {
+        Pin::new(&mut self.get_mut().inner).poll_fill_buf(cx)
     }
 
-    async fn read_u16_le_static(reader: &mut BufReader<R>) -> Result<u16> {
-        let mut buf = [0u8; 2];
-        reader.read_exact(&mut buf).await?;
-        Ok(u16::from_le_bytes(buf))
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        Pin::new(&mut self.get_mut().inner).consume(amt)
     }
+}
 
-    async fn read_u32_le_static(reader: &mut BufReader<R>) -> Result<u32> {
-        let mut buf = [0u8; 4];
-        reader.read_exact(&mut buf).await?;
-        Ok(u32::from_le_bytes(buf))
+/// Zstd decoder of `source`, accepting windows up to the format's limit
+///
+/// async-compression can't combine a dictionary with parameters, so entries
+/// compressed with a dictionary keep zstd's default limit of 2^27.
+#[cfg(feature = "async-zstd")]
+fn zstd_decoder<B: AsyncBufRead>(
+    source: B,
+    dictionary: Option<&ZstdDictionary>,
+) -> std::io::Result<ZstdDecoder<B>> {
+    use async_compression::zstd::DParameter;
+
+    match dictionary {
+        Some(dictionary) => ZstdDecoder::with_dict(source, dictionary.as_bytes()),
+        None => Ok(ZstdDecoder::with_params(
+            source,
+            &[DParameter::window_log_max(
+                crate::writer::ZSTD_WINDOW_LOG_MAX,
+            )],
+        )),
     }
+}
 
-    /// Parse the AES extra field (ID 0x9901) from the local file header extra data,
-    /// then read the salt and password-verification bytes that follow the extra field.
-    ///
-    /// Returns `Some((strength, salt, pw_verify))` if AES encryption is present,
-    /// or `None` if no AES extra field is found (extra field is consumed either way).
+/// LZMA entry data behind the `.lzma` header made from its ZIP header
+#[cfg(feature = "lzma")]
+type LzmaInput<S> = tokio::io::Chain<std::io::Cursor<Vec<u8>>, DoubleBuffer<Take<S>>>;
+
+/// Concrete decoder stack produced for a streamed entry.
+///
+/// An enum rather than a boxed trait object so that it is `Send` exactly when
+/// the underlying source is; callers box it with or without `Send` as needed.
+enum EntryStream<S: AsyncRead + Unpin> {
+    Stored(DoubleBuffer<Take<S>>),
+    Deflate(DeflateDecoder<DoubleBuffer<Take<S>>>),
+    #[cfg(feature = "async-zstd")]
+    Zstd(ZstdDecoder<DoubleBuffer<Take<S>>>),
+    #[cfg(feature = "deflate64")]
+    Deflate64(Deflate64Decoder<DoubleBuffer<Take<S>>>),
+    #[cfg(feature = "bzip2")]
+    Bzip2(BzDecoder<DoubleBuffer<Take<S>>>),
+    #[cfg(feature = "lzma")]
+    Lzma(LzmaDecoder<LzmaInput<S>>),
+    #[cfg(feature = "lzma")]
+    Xz(XzDecoder<DoubleBuffer<Take<S>>>),
     #[cfg(feature = "encryption")]
-    async fn parse_aes_extra_field(
-        &mut self,
-        extra_len: usize,
-    ) -> Result<Option<(crate::encryption::AesStrength, Vec<u8>, [u8; 2])>> {
-        use crate::encryption::AesStrength;
+    EncryptedStored(AsyncDecryptingReader<DoubleBuffer<Take<S>>>),
+    #[cfg(feature = "encryption")]
+    EncryptedDeflate(DeflateDecoder<BufReader<AsyncDecryptingReader<DoubleBuffer<Take<S>>>>>),
+}
 
-        if extra_len == 0 {
-            return Ok(None);
+impl<S: AsyncRead + Unpin> AsyncRead for EntryStream<S> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        match self.get_mut() {
+            EntryStream::Stored(r) => Pin::new(r).poll_read(cx, buf),
+            EntryStream::Deflate(r) => Pin::new(r).poll_read(cx, buf),
+            #[cfg(feature = "async-zstd")]
+            EntryStream::Zstd(r) => Pin::new(r).poll_read(cx, buf),
+            #[cfg(feature = "deflate64")]
+            EntryStream::Deflate64(r) => Pin::new(r).poll_read(cx, buf),
+            #[cfg(feature = "bzip2")]
+            EntryStream::Bzip2(r) => Pin::new(r).poll_read(cx, buf),
+            #[cfg(feature = "lzma")]
+            EntryStream::Lzma(r) => Pin::new(r).poll_read(cx, buf),
+            #[cfg(feature = "lzma")]
+            EntryStream::Xz(r) => Pin::new(r).poll_read(cx, buf),
+            #[cfg(feature = "encryption")]
+            EntryStream::EncryptedStored(r) => Pin::new(r).poll_read(cx, buf),
+            #[cfg(feature = "encryption")]
+            EntryStream::EncryptedDeflate(r) => Pin::new(r).poll_read(cx, buf),
         }
-
-        let mut extra_buf = vec![0u8; extra_len];
-        self.reader.read_exact(&mut extra_buf).await?;
-
-        // Use shared pure helper to find the strength code
-        let strength_code = match parse_aes_extra_field_buf(&extra_buf) {
-            Some(code) => code,
-            None => return Ok(None),
-        };
-
-        let strength = match strength_code {
-            0x01 => AesStrength::Aes128,
-            0x02 => AesStrength::Aes192,
-            0x03 => AesStrength::Aes256,
-            _ => {
-                return Err(SZipError::InvalidFormat(format!(
-                    "Unsupported AES strength code: {}",
-                    strength_code
-                )))
-            }
-        };
-
-        // Salt and password-verify bytes follow the extra field in the file data
-        let salt_size = strength.salt_size();
-        let mut salt = vec![0u8; salt_size];
-        self.reader.read_exact(&mut salt).await?;
-
-        let mut pw_verify = [0u8; 2];
-        self.reader.read_exact(&mut pw_verify).await?;
-
-        Ok(Some((strength, salt, pw_verify)))
     }
 }
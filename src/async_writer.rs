@@ -10,20 +10,38 @@
 //!
 //! Supports arbitrary async writers (File, `Vec<u8>`, network streams, etc.)
 
+use crate::append::ExistingArchive;
+use crate::async_reader::GenericAsyncZipReader;
+use crate::block_deflate;
+use crate::budget::{MemoryBudget, Reservation};
+use crate::dostime::DosDateTime;
 use crate::error::{Result, SZipError};
-use crate::writer::CompressionMethod;
+use crate::format::ExtendedTimestamp;
+use crate::hook::{ArchiveSummary, EntryStatsHook, EntrySummary, Hooks, WriterHook};
+use crate::ignore::{DirOptions, IgnoreRules};
+use crate::raw::{self, CentralDirectoryRecord, EndOfCentralDirectory, LocalFileHeader};
+use crate::writer::{copied_header, CompressionMethod, CopiedRecord, NamePolicy};
+#[cfg(feature = "bzip2")]
+use async_compression::tokio::write::BzEncoder;
 use async_compression::tokio::write::DeflateEncoder;
-#[cfg(feature = "async-zstd")]
-use async_compression::tokio::write::ZstdEncoder;
 use crc32fast::Hasher as Crc32;
-use std::io::Write;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::io::{SeekFrom, Write};
 use std::path::Path;
 use std::pin::Pin;
-use std::task::{Context, Poll};
-use tokio::io::{AsyncSeek, AsyncSeekExt, AsyncWrite, AsyncWriteExt};
+use std::sync::Arc;
+use std::task::{ready, Context, Poll};
+use std::time::Instant;
+use tokio::io::{AsyncRead, AsyncSeek, AsyncSeekExt, AsyncWrite, AsyncWriteExt};
+use tokio::task::JoinHandle;
 
 #[cfg(feature = "encryption")]
 use crate::encryption::{AesEncryptor, AesStrength};
+#[cfg(feature = "async-zstd")]
+use crate::writer::ZstdOptions;
+#[cfg(feature = "async-zstd")]
+use crate::zstd_dict::{ZstdDictionary, DICTIONARY_ENTRY};
 
 /// Entry being written to ZIP
 struct ZipEntry {
@@ -33,17 +51,52 @@ struct ZipEntry {
     compressed_size: u64,
     uncompressed_size: u64,
     compression_method: u16,
+    /// General purpose bit flag, as written to the local header
+    flags: u16,
+    last_modified: DosDateTime,
+    external_attrs: u32,
+    extended_timestamp: ExtendedTimestamp,
     #[cfg(feature = "encryption")]
     encryption_strength: Option<u16>,
+    /// Set for entries copied from another archive
+    copied: Option<CopiedRecord>,
 }
 
+/// Sets the length of the output
+type Truncate<W> =
+    for<'a> fn(&'a mut W, u64) -> Pin<Box<dyn Future<Output = std::io::Result<()>> + Send + 'a>>;
+
 /// Async streaming ZIP writer that compresses data on-the-fly
-pub struct AsyncStreamingZipWriter<W: AsyncWrite + AsyncSeek + Unpin> {
-    output: W,
+pub struct AsyncStreamingZipWriter<W: AsyncWrite + Unpin> {
+    output: PositionTracker<W>,
     entries: Vec<ZipEntry>,
     current_entry: Option<CurrentEntry>,
     compression_level: u32,
     compression_method: CompressionMethod,
+    version_made_by: Option<u16>,
+    /// Archive comment written to the EOCD record
+    comment: Vec<u8>,
+    version_needed: Option<u16>,
+    names: NamePolicy,
+    /// Chunks at least this large are compressed on the blocking pool
+    compression_offload: Option<usize>,
+    auto_store: bool,
+    /// Threads each DEFLATE entry is compressed on
+    deflate_threads: usize,
+    /// Dictionary for Zstd entries, and whether to embed it
+    #[cfg(feature = "async-zstd")]
+    zstd_dictionary: Option<(ZstdDictionary, bool)>,
+    #[cfg(feature = "async-zstd")]
+    zstd_options: ZstdOptions,
+    deadline: Option<Instant>,
+    hooks: Hooks,
+    /// Share of a `MemoryBudget` covering the compressed-data buffer
+    memory: Option<Reservation>,
+    /// Central directory of the archive being appended to; see
+    /// [`append`](AsyncStreamingZipWriter::append)
+    existing: Option<ExistingArchive>,
+    /// Cuts the output to its final length once finished
+    truncate: Option<Truncate<W>>,
     #[cfg(feature = "encryption")]
     password: Option<String>,
     #[cfg(feature = "encryption")]
@@ -56,10 +109,27 @@ struct CurrentEntry {
     encoder: Box<dyn AsyncCompressorWrite>,
     counter: CrcCounter,
     compression_method: u16,
+    flags: u16,
+    /// Header and first data of an entry whose method auto-store hasn't
+    /// chosen yet; nothing of the entry is written until it has
+    undecided: Option<Undecided>,
+    last_modified: DosDateTime,
+    external_attrs: u32,
+    extended_timestamp: ExtendedTimestamp,
+    /// When `start_entry` was called
+    started: Instant,
     #[cfg(feature = "encryption")]
     encryptor: Option<AesEncryptor>,
 }
 
+/// An entry held back while auto-store samples its data
+struct Undecided {
+    header: Vec<u8>,
+    /// AES salt and password verifier, written after the header
+    preamble: Vec<u8>,
+    sample: Vec<u8>,
+}
+
 /// Trait for async compression encoders
 trait AsyncCompressorWrite: AsyncWrite + Unpin + Send {
     fn finish_compression(
@@ -105,13 +175,148 @@ impl AsyncCompressorWrite for DeflateCompressor {
     }
 }
 
+/// DEFLATE of independent blocks on tokio's blocking pool; see
+/// [`set_deflate_threads`](AsyncStreamingZipWriter::set_deflate_threads)
+struct ParallelDeflateCompressor {
+    level: flate2::Compression,
+    threads: usize,
+    /// Block being filled
+    block: Vec<u8>,
+    /// Blocks being compressed, in order
+    pending: VecDeque<JoinHandle<std::io::Result<Vec<u8>>>>,
+    buffer: CompressedBuffer,
+}
+
+impl ParallelDeflateCompressor {
+    fn new(level: flate2::Compression, threads: usize, buffer: CompressedBuffer) -> Self {
+        Self {
+            level,
+            threads,
+            block: Vec::new(),
+            pending: VecDeque::with_capacity(threads),
+            buffer,
+        }
+    }
+
+    fn spawn(&mut self, last: bool) {
+        let block = std::mem::take(&mut self.block);
+        let level = self.level;
+        self.pending.push_back(tokio::task::spawn_blocking(move || {
+            block_deflate::compress_block(&block, level, last)
+        }));
+    }
+
+    /// Move finished blocks to the buffer, in order, waiting for the oldest
+    /// while `limit` or more are in flight
+    fn poll_blocks(&mut self, cx: &mut Context<'_>, limit: usize) -> Poll<std::io::Result<()>> {
+        let mut in_flight = self.pending.len();
+        while let Some(oldest) = self.pending.front_mut() {
+            if in_flight < limit && !oldest.is_finished() {
+                break;
+            }
+            let compressed = ready!(Pin::new(oldest).poll(cx)).map_err(std::io::Error::other)??;
+            self.pending.pop_front();
+            in_flight -= 1;
+            Write::write_all(&mut self.buffer, &compressed)?;
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncWrite for ParallelDeflateCompressor {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let limit = self.threads;
+        ready!(self.poll_blocks(cx, limit))?;
+        let n = buf.len().min(block_deflate::BLOCK_LEN - self.block.len());
+        self.block.extend_from_slice(&buf[..n]);
+        if self.block.len() == block_deflate::BLOCK_LEN {
+            self.spawn(false);
+        }
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        // Blocks are compressed as they fill; output appears as they finish
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        self.poll_blocks(cx, 0)
+    }
+}
+
+impl AsyncCompressorWrite for ParallelDeflateCompressor {
+    fn finish_compression(
+        mut self: Box<Self>,
+    ) -> Pin<Box<dyn std::future::Future<Output = Result<CompressedBuffer>> + Send>> {
+        self.spawn(true);
+        Box::pin(async move {
+            std::future::poll_fn(|cx| self.poll_blocks(cx, 0)).await?;
+            Ok(self.buffer)
+        })
+    }
+
+    fn get_buffer_mut(&mut self) -> &mut CompressedBuffer {
+        &mut self.buffer
+    }
+}
+
+/// Drives the zstd encoder directly, as its output goes to memory, so that
+/// dictionaries and [`ZstdOptions`] combine
 #[cfg(feature = "async-zstd")]
 struct ZstdCompressor {
-    encoder: ZstdEncoder<CompressedBuffer>,
+    encoder: zstd::Encoder<'static, CompressedBuffer>,
+    /// A flush would wait for every worker thread
+    multithreaded: bool,
 }
 
 #[cfg(feature = "async-zstd")]
 impl AsyncWrite for ZstdCompressor {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        Poll::Ready(self.encoder.write(buf))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        if self.multithreaded {
+            // Output appears as the workers finish their jobs
+            return Poll::Ready(Ok(()));
+        }
+        Poll::Ready(self.encoder.flush())
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Poll::Ready(self.encoder.do_finish())
+    }
+}
+
+#[cfg(feature = "async-zstd")]
+impl AsyncCompressorWrite for ZstdCompressor {
+    fn finish_compression(
+        self: Box<Self>,
+    ) -> Pin<Box<dyn std::future::Future<Output = Result<CompressedBuffer>> + Send>> {
+        Box::pin(async move { Ok(self.encoder.finish()?) })
+    }
+
+    fn get_buffer_mut(&mut self) -> &mut CompressedBuffer {
+        self.encoder.get_mut()
+    }
+}
+
+#[cfg(feature = "bzip2")]
+struct Bzip2Compressor {
+    encoder: BzEncoder<CompressedBuffer>,
+}
+
+#[cfg(feature = "bzip2")]
+impl AsyncWrite for Bzip2Compressor {
     fn poll_write(
         mut self: Pin<&mut Self>,
         cx: &mut Context<'_>,
@@ -120,8 +325,10 @@ impl AsyncWrite for ZstdCompressor {
         Pin::new(&mut self.encoder).poll_write(cx, buf)
     }
 
-    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
-        Pin::new(&mut self.encoder).poll_flush(cx)
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        // A bzip2 flush ends the current block, which would cut every
+        // `write_data` chunk into its own block; output appears as blocks fill
+        Poll::Ready(Ok(()))
     }
 
     fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
@@ -129,8 +336,8 @@ impl AsyncWrite for ZstdCompressor {
     }
 }
 
-#[cfg(feature = "async-zstd")]
-impl AsyncCompressorWrite for ZstdCompressor {
+#[cfg(feature = "bzip2")]
+impl AsyncCompressorWrite for Bzip2Compressor {
     fn finish_compression(
         mut self: Box<Self>,
     ) -> Pin<Box<dyn std::future::Future<Output = Result<CompressedBuffer>> + Send>> {
@@ -145,6 +352,119 @@ impl AsyncCompressorWrite for ZstdCompressor {
     }
 }
 
+/// LZMA or XZ, depending on the stream the encoder was made with
+///
+/// liblzma has no async encoder of raw LZMA1; the encoder writes to memory,
+/// so it is driven directly.
+#[cfg(feature = "lzma")]
+struct LzmaCompressor {
+    encoder: liblzma::write::XzEncoder<CompressedBuffer>,
+}
+
+#[cfg(feature = "lzma")]
+impl AsyncWrite for LzmaCompressor {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        Poll::Ready(self.encoder.write(buf))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        // Raw LZMA1 can't be flushed mid-stream; output appears as the
+        // encoder's buffers fill
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Poll::Ready(self.encoder.try_finish())
+    }
+}
+
+#[cfg(feature = "lzma")]
+impl AsyncCompressorWrite for LzmaCompressor {
+    fn finish_compression(
+        self: Box<Self>,
+    ) -> Pin<Box<dyn std::future::Future<Output = Result<CompressedBuffer>> + Send>> {
+        Box::pin(async move { Ok(self.encoder.finish()?) })
+    }
+
+    fn get_buffer_mut(&mut self) -> &mut CompressedBuffer {
+        self.encoder.get_mut()
+    }
+}
+
+/// Compress `data` into `encoder`'s buffer on tokio's blocking pool
+async fn compress_offloaded(
+    mut encoder: Box<dyn AsyncCompressorWrite>,
+    data: Vec<u8>,
+) -> Result<Box<dyn AsyncCompressorWrite>> {
+    use futures_util::FutureExt;
+
+    tokio::task::spawn_blocking(move || {
+        // The encoders write into an in-memory `CompressedBuffer`, so these
+        // futures complete on the first poll
+        async {
+            encoder.write_all(&data).await?;
+            encoder.flush().await
+        }
+        .now_or_never()
+        .unwrap_or_else(|| Err(std::io::Error::other("compression did not complete")))?;
+        Ok(encoder)
+    })
+    .await
+    .map_err(|e| SZipError::Io(std::io::Error::other(e)))?
+}
+
+/// Stands in for an encoder while it is on the blocking pool. Left in place
+/// if the task fails, so the entry cannot be finished with data missing.
+struct OffloadedCompressor {
+    buffer: CompressedBuffer,
+}
+
+impl OffloadedCompressor {
+    fn new() -> Self {
+        Self {
+            buffer: CompressedBuffer::with_size_hint(Some(0)),
+        }
+    }
+
+    fn lost() -> std::io::Error {
+        std::io::Error::other("entry data was lost in a failed compression task")
+    }
+}
+
+impl AsyncWrite for OffloadedCompressor {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        _buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        Poll::Ready(Err(Self::lost()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Poll::Ready(Err(Self::lost()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Poll::Ready(Err(Self::lost()))
+    }
+}
+
+impl AsyncCompressorWrite for OffloadedCompressor {
+    fn finish_compression(
+        self: Box<Self>,
+    ) -> Pin<Box<dyn std::future::Future<Output = Result<CompressedBuffer>> + Send>> {
+        Box::pin(async { Err(Self::lost().into()) })
+    }
+
+    fn get_buffer_mut(&mut self) -> &mut CompressedBuffer {
+        &mut self.buffer
+    }
+}
+
 /// Metadata tracker for CRC and byte counts (reused from sync version)
 struct CrcCounter {
     crc: Crc32,
@@ -175,6 +495,51 @@ impl CrcCounter {
     }
 }
 
+/// Write-only wrapper that tracks the number of bytes written.
+///
+/// The writer only ever appends, so the byte count is the stream position;
+/// tracking it here means the output never needs to implement `AsyncSeek`.
+struct PositionTracker<W> {
+    inner: W,
+    position: u64,
+}
+
+impl<W> PositionTracker<W> {
+    fn new(inner: W) -> Self {
+        Self { inner, position: 0 }
+    }
+
+    fn position(&self) -> u64 {
+        self.position
+    }
+
+    fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWrite for PositionTracker<W> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let poll = Pin::new(&mut self.inner).poll_write(cx, buf);
+        if let Poll::Ready(Ok(n)) = poll {
+            self.position += n as u64;
+        }
+        poll
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
 /// Buffered writer for compressed data with adaptive sizing
 ///
 /// Automatically adjusts buffer capacity and flush threshold based on data size hints
@@ -276,11 +641,27 @@ impl AsyncStreamingZipWriter<tokio::fs::File> {
     ) -> Result<Self> {
         let output = tokio::fs::File::create(path).await?;
         Ok(Self {
-            output,
+            output: PositionTracker::new(output),
             entries: Vec::new(),
             current_entry: None,
             compression_level,
             compression_method: method,
+            version_made_by: None,
+            comment: Vec::new(),
+            version_needed: None,
+            names: NamePolicy::default(),
+            compression_offload: None,
+            auto_store: false,
+            deflate_threads: 1,
+            #[cfg(feature = "async-zstd")]
+            zstd_dictionary: None,
+            #[cfg(feature = "async-zstd")]
+            zstd_options: ZstdOptions::default(),
+            deadline: None,
+            hooks: Hooks::default(),
+            memory: None,
+            existing: None,
+            truncate: None,
             #[cfg(feature = "encryption")]
             password: None,
             #[cfg(feature = "encryption")]
@@ -293,17 +674,74 @@ impl AsyncStreamingZipWriter<tokio::fs::File> {
     pub async fn with_zstd<P: AsRef<Path>>(path: P, compression_level: i32) -> Result<Self> {
         let output = tokio::fs::File::create(path).await?;
         Ok(Self {
-            output,
+            output: PositionTracker::new(output),
             entries: Vec::new(),
             current_entry: None,
             compression_level: compression_level as u32,
             compression_method: CompressionMethod::Zstd,
+            version_made_by: None,
+            comment: Vec::new(),
+            version_needed: None,
+            names: NamePolicy::default(),
+            compression_offload: None,
+            auto_store: false,
+            deflate_threads: 1,
+            #[cfg(feature = "async-zstd")]
+            zstd_dictionary: None,
+            #[cfg(feature = "async-zstd")]
+            zstd_options: ZstdOptions::default(),
+            deadline: None,
+            hooks: Hooks::default(),
+            memory: None,
+            existing: None,
+            truncate: None,
             #[cfg(feature = "encryption")]
             password: None,
             #[cfg(feature = "encryption")]
             encryption_strength: AesStrength::Aes256,
         })
     }
+
+    /// Open an existing archive to add entries to it (DEFLATE, level 6)
+    ///
+    /// Async version of
+    /// [`StreamingZipWriter::append`](crate::StreamingZipWriter::append):
+    /// existing entry data is left in place, and [`finish`](Self::finish)
+    /// writes a central directory listing the existing entries followed by
+    /// the new ones.
+    pub async fn append<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::append_with_method(path, CompressionMethod::Deflate, 6).await
+    }
+
+    /// Like [`append`](Self::append), compressing new entries with the
+    /// given method and level
+    pub async fn append_with_method<P: AsRef<Path>>(
+        path: P,
+        method: CompressionMethod,
+        compression_level: u32,
+    ) -> Result<Self> {
+        let mut file = tokio::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(path)
+            .await?;
+        let mut existing = crate::append::read_async(&mut file).await?;
+        file.seek(SeekFrom::Start(existing.offset)).await?;
+        let mut writer = Self::from_writer_with_method(file, method, compression_level);
+        writer.output.position = existing.offset;
+        writer.comment = std::mem::take(&mut existing.comment);
+        writer.names.seen.extend(existing.names.iter().cloned());
+        writer.existing = Some(existing);
+        writer.truncate = Some(truncate_file);
+        Ok(writer)
+    }
+}
+
+fn truncate_file(
+    file: &mut tokio::fs::File,
+    len: u64,
+) -> Pin<Box<dyn Future<Output = std::io::Result<()>> + Send + '_>> {
+    Box::pin(file.set_len(len))
 }
 
 /// Pass-through async compressor for CompressionMethod::Stored (no compression).
@@ -342,7 +780,7 @@ impl AsyncCompressorWrite for StoredCompressor {
     }
 }
 
-impl<W: AsyncWrite + AsyncSeek + Unpin> AsyncStreamingZipWriter<W> {
+impl<W: AsyncWrite + Unpin> AsyncStreamingZipWriter<W> {
     /// Create a new async ZIP writer from an arbitrary writer with default compression level (6) using DEFLATE
     pub fn from_writer(writer: W) -> Self {
         Self::from_writer_with_compression(writer, 6)
@@ -356,7 +794,7 @@ impl<W: AsyncWrite + AsyncSeek + Unpin> AsyncStreamingZipWriter<W> {
     /// Create a new async ZIP writer from an arbitrary writer with specified compression method and level
     ///
     /// # Arguments
-    /// * `writer` - Any writer implementing AsyncWrite + AsyncSeek + Unpin
+    /// * `writer` - Any writer implementing AsyncWrite + Unpin (no seeking required)
     /// * `method` - Compression method to use (Deflate, Zstd, or Stored)
     /// * `compression_level` - Compression level (0-9 for DEFLATE, 1-21 for Zstd)
     pub fn from_writer_with_method(
@@ -365,11 +803,27 @@ impl<W: AsyncWrite + AsyncSeek + Unpin> AsyncStreamingZipWriter<W> {
         compression_level: u32,
     ) -> Self {
         Self {
-            output: writer,
+            output: PositionTracker::new(writer),
             entries: Vec::new(),
             current_entry: None,
             compression_level,
             compression_method: method,
+            version_made_by: None,
+            comment: Vec::new(),
+            version_needed: None,
+            names: NamePolicy::default(),
+            compression_offload: None,
+            auto_store: false,
+            deflate_threads: 1,
+            #[cfg(feature = "async-zstd")]
+            zstd_dictionary: None,
+            #[cfg(feature = "async-zstd")]
+            zstd_options: ZstdOptions::default(),
+            deadline: None,
+            hooks: Hooks::default(),
+            memory: None,
+            existing: None,
+            truncate: None,
             #[cfg(feature = "encryption")]
             password: None,
             #[cfg(feature = "encryption")]
@@ -415,6 +869,172 @@ impl<W: AsyncWrite + AsyncSeek + Unpin> AsyncStreamingZipWriter<W> {
         self
     }
 
+    /// Start a new entry encrypted with `password`, regardless of the
+    /// writer-wide [`set_password`](Self::set_password) setting
+    ///
+    /// Data is compressed, then AES-encrypted as it streams to the output, so
+    /// encrypted archives can go straight to S3 or GCS. Later entries use the
+    /// writer-wide password again.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use s_zip::{AsyncStreamingZipWriter, Result};
+    /// # async fn example() -> Result<()> {
+    /// let mut writer = AsyncStreamingZipWriter::new("mixed.zip").await?;
+    /// writer.add_entry("README.txt", b"Public notes").await?;
+    /// writer.start_entry_encrypted("secret.txt", "my_secure_password").await?;
+    /// writer.write_data(b"Confidential data").await?;
+    /// writer.finish().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "encryption")]
+    pub async fn start_entry_encrypted(&mut self, name: &str, password: &str) -> Result<()> {
+        let previous = self.password.replace(password.to_string());
+        let result = self.start_entry_with_hint(name, None).await;
+        self.password = previous;
+        result
+    }
+
+    /// Set the archive comment written to the end of central directory record
+    /// on `finish()`, e.g. to stamp build metadata into the archive.
+    ///
+    /// The comment is limited to 65,535 bytes; `finish()` fails if it is longer.
+    pub fn set_archive_comment(&mut self, comment: &str) -> &mut Self {
+        self.comment = comment.as_bytes().to_vec();
+        self
+    }
+
+    /// Override the "version made by" field written to the central directory.
+    ///
+    /// Some ecosystems (Java, Android) inspect this value, e.g. the high byte
+    /// must be 3 (Unix) for permission bits to be honoured. Defaults to 63
+    /// (MS-DOS host, spec 6.3), with the Unix host for entries that have a
+    /// `unix_mode`; the override applies to every entry.
+    pub fn set_version_made_by(&mut self, version: u16) -> &mut Self {
+        self.version_made_by = Some(version);
+        self
+    }
+
+    /// Override the "version needed to extract" field written to local headers
+    /// and the central directory.
+    ///
+    /// The override is raised per entry to the minimum its compression method,
+    /// encryption and ZIP64 use require (e.g. 5.1 for AES).
+    pub fn set_version_needed(&mut self, version: u16) -> &mut Self {
+        self.version_needed = Some(version);
+        self
+    }
+
+    /// The version needed for an entry requiring at least `minimum`,
+    /// honouring the override
+    fn version_needed_for(&self, minimum: u16) -> u16 {
+        self.version_needed
+            .map_or(minimum, |version| version.max(minimum))
+    }
+
+    crate::writer::name_policy_setters!();
+
+    /// Compress `write_data` chunks of at least `min_chunk` bytes on tokio's
+    /// blocking thread pool instead of the executor thread (default: off).
+    ///
+    /// Deflate and zstd are CPU-bound; under load, compressing large chunks
+    /// inline starves the other tasks on the same worker. Each offloaded
+    /// `write_data` call completes before it returns, so output order and
+    /// backpressure are unchanged. Smaller chunks are still compressed inline,
+    /// where a thread hand-off would cost more than it saves.
+    ///
+    /// Requires a tokio runtime.
+    pub fn set_compression_offload(&mut self, min_chunk: Option<usize>) -> &mut Self {
+        self.compression_offload = min_chunk;
+        self
+    }
+
+    /// Store entries that wouldn't compress instead of compressing them
+    /// (default: false).
+    ///
+    /// See [`StreamingZipWriter::set_auto_store`](crate::StreamingZipWriter::set_auto_store).
+    pub fn set_auto_store(&mut self, enabled: bool) -> &mut Self {
+        self.auto_store = enabled;
+        self
+    }
+
+    /// Compress each DEFLATE entry on `threads` threads of tokio's blocking
+    /// pool (default: 1, on the calling task).
+    ///
+    /// See [`StreamingZipWriter::set_deflate_threads`](crate::StreamingZipWriter::set_deflate_threads).
+    /// Takes the place of [`set_compression_offload`](Self::set_compression_offload)
+    /// for DEFLATE entries.
+    pub fn set_deflate_threads(&mut self, threads: usize) -> &mut Self {
+        self.deflate_threads = threads.max(1);
+        self
+    }
+
+    /// Configure the Zstd encoder: worker threads, window size and
+    /// long-distance matching.
+    ///
+    /// See [`StreamingZipWriter::set_zstd_options`](crate::StreamingZipWriter::set_zstd_options).
+    /// With worker threads, the workers take the place of
+    /// [`set_compression_offload`](Self::set_compression_offload).
+    #[cfg(feature = "async-zstd")]
+    pub fn set_zstd_options(&mut self, options: ZstdOptions) -> &mut Self {
+        self.zstd_options = options;
+        self
+    }
+
+    /// Compress Zstd entries with `dictionary`, and with `embed` store it in
+    /// the archive.
+    ///
+    /// See [`StreamingZipWriter::set_zstd_dictionary`](crate::StreamingZipWriter::set_zstd_dictionary).
+    #[cfg(feature = "async-zstd")]
+    pub fn set_zstd_dictionary(&mut self, dictionary: ZstdDictionary, embed: bool) -> &mut Self {
+        self.zstd_dictionary = Some((dictionary, embed));
+        self
+    }
+
+    /// Count output positions from `offset`, for archives written after a
+    /// prefix such as a self-extractor stub
+    pub(crate) fn with_start_offset(mut self, offset: u64) -> Self {
+        self.output.position = offset;
+        self
+    }
+
+    /// Fail with `SZipError::DeadlineExceeded` once `deadline` passes.
+    ///
+    /// Checked when entries start, on every `write_data` call and before the
+    /// central directory is written, so work stops at the next chunk boundary.
+    /// The partial archive is not valid and should be discarded.
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Draw compressed-data buffers from `budget`, shared with other jobs.
+    ///
+    /// When the budget runs low the buffer is written out early instead of
+    /// growing. See [`crate::budget`].
+    pub fn with_memory_budget(mut self, budget: &MemoryBudget) -> Self {
+        self.memory = Some(budget.empty());
+        self
+    }
+
+    /// Register a [`WriterHook`] called as headers, data, entries and the
+    /// central directory are written
+    pub fn add_hook(&mut self, hook: impl WriterHook + 'static) -> &mut Self {
+        self.hooks.push(Box::new(hook));
+        self
+    }
+
+    /// Call `callback` after each entry with its sizes, compression method
+    /// and wall time, e.g. to log compression ratios and throughput
+    pub fn on_entry_stats(
+        &mut self,
+        callback: impl FnMut(&EntrySummary<'_>) + Send + 'static,
+    ) -> &mut Self {
+        self.hooks.push(Box::new(EntryStatsHook(callback)));
+        self
+    }
+
     /// Start a new entry (file) in the ZIP
     pub async fn start_entry(&mut self, name: &str) -> Result<()> {
         crate::trace!(entry = name, "start_entry");
@@ -438,6 +1058,102 @@ impl<W: AsyncWrite + AsyncSeek + Unpin> AsyncStreamingZipWriter<W> {
         self.write_data(data).await
     }
 
+    /// Copy `entry` from `reader` without decompressing it
+    ///
+    /// Async version of
+    /// [`StreamingZipWriter::copy_entry`](crate::StreamingZipWriter::copy_entry):
+    /// the stored data is streamed through unchanged under a new local
+    /// header, keeping the entry's timestamp, attributes and extra fields.
+    pub async fn copy_entry<R: AsyncRead + AsyncSeek + Unpin>(
+        &mut self,
+        reader: &mut GenericAsyncZipReader<R>,
+        entry: &crate::ZipEntry,
+    ) -> Result<()> {
+        crate::trace!(entry = entry.name.as_str(), "copy_entry");
+        crate::deadline::check(self.deadline)?;
+        let name = self.names.entry_name(&entry.name, &mut self.hooks)?;
+        self.finish_current_entry().await?;
+
+        let started = Instant::now();
+        let (local_extra, mut data) = reader.raw_entry(entry).await?;
+        let (header, flags) = copied_header(entry, &name, &local_extra)?;
+        let local_header_offset = self.output.position();
+        self.output.write_all(&header).await?;
+        let copied = tokio::io::copy(&mut data, &mut self.output).await?;
+        if copied != entry.compressed_size {
+            return Err(SZipError::InvalidFormat(format!(
+                "Entry '{}' ends after {} of {} bytes",
+                entry.name, copied, entry.compressed_size
+            )));
+        }
+        if flags & raw::FLAG_DATA_DESCRIPTOR != 0 {
+            let mut descriptor = Vec::with_capacity(24);
+            raw::write_data_descriptor(
+                &mut descriptor,
+                entry.crc32,
+                entry.compressed_size,
+                entry.uncompressed_size,
+            )?;
+            self.output.write_all(&descriptor).await?;
+        }
+
+        self.hooks.entry_finished(&EntrySummary {
+            name: &name,
+            offset: local_header_offset,
+            crc32: entry.crc32,
+            compressed_size: entry.compressed_size,
+            uncompressed_size: entry.uncompressed_size,
+            compression_method: entry.compression_method,
+            elapsed: started.elapsed(),
+        })?;
+        self.entries.push(ZipEntry {
+            name,
+            local_header_offset,
+            crc32: entry.crc32,
+            compressed_size: entry.compressed_size,
+            uncompressed_size: entry.uncompressed_size,
+            compression_method: entry.compression_method,
+            flags,
+            last_modified: entry.last_modified,
+            external_attrs: entry.external_attrs,
+            extended_timestamp: ExtendedTimestamp::default(),
+            #[cfg(feature = "encryption")]
+            encryption_strength: None,
+            copied: Some(CopiedRecord::new(entry)),
+        });
+        Ok(())
+    }
+
+    /// Add an empty directory entry, so extractors create the folder even
+    /// when no file is archived inside it.
+    ///
+    /// A `/` is appended to `name` if missing. The entry is stored, has no
+    /// data and carries the directory attribute; it is never encrypted.
+    pub async fn add_directory(&mut self, name: &str) -> Result<()> {
+        let options = crate::EntryOptions {
+            dos_attributes: Some(crate::DosAttributes {
+                directory: true,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        self.start_entry_with_options(&crate::format::directory_entry_name(name), options)
+            .await?;
+        self.finish_current_entry().await
+    }
+
+    /// Add a symbolic link `name` pointing at `target`.
+    ///
+    /// The target is stored as the entry's data and the Unix mode marks the
+    /// entry as a link (`0o120777`), which `unzip` recreates as a link on Unix.
+    pub async fn add_symlink(&mut self, name: &str, target: &str) -> Result<()> {
+        let options =
+            crate::EntryOptions::default().with_unix_mode(crate::format::UNIX_SYMLINK | 0o777);
+        self.start_entry_with_options(name, options).await?;
+        self.write_data(target.as_bytes()).await?;
+        self.finish_current_entry().await
+    }
+
     /// Number of entries fully written so far.
     ///
     /// Includes any in-progress entry (started but not yet followed by
@@ -478,75 +1194,325 @@ impl<W: AsyncWrite + AsyncSeek + Unpin> AsyncStreamingZipWriter<W> {
         name: &str,
         size_hint: Option<u64>,
     ) -> Result<()> {
+        self.start_entry_with_options_and_hint(name, crate::EntryOptions::default(), size_hint)
+            .await
+    }
+
+    /// Start a new entry with file metadata (modification time and Unix permissions).
+    ///
+    /// Entries created with `start_entry()` are stamped with the current time and
+    /// carry no permission bits.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use s_zip::{AsyncStreamingZipWriter, EntryOptions};
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut writer = AsyncStreamingZipWriter::new("output.zip").await?;
+    /// let opts = EntryOptions::default()
+    ///     .with_mtime(std::time::SystemTime::now())
+    ///     .with_unix_mode(0o644);
+    /// writer.start_entry_with_options("readme.txt", opts).await?;
+    /// writer.write_data(b"Hello").await?;
+    /// writer.finish().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn start_entry_with_options(
+        &mut self,
+        name: &str,
+        options: crate::EntryOptions,
+    ) -> Result<()> {
+        self.start_entry_with_options_and_hint(name, options, None)
+            .await
+    }
+
+    /// Add the file at `path` as entry `name`, streaming it in chunks.
+    ///
+    /// The modification time (and Unix mode on Unix) come from the file
+    /// system, and the file size is used as the size hint.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use s_zip::AsyncStreamingZipWriter;
+    /// # use std::path::Path;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut writer = AsyncStreamingZipWriter::new("output.zip").await?;
+    /// writer.write_file("bin/run.sh", Path::new("scripts/run.sh")).await?;
+    /// writer.finish().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn write_file<P: AsRef<Path>>(&mut self, name: &str, path: P) -> Result<()> {
+        use tokio::io::AsyncReadExt;
+
+        let mut file = tokio::fs::File::open(path).await?;
+        let metadata = file.metadata().await?;
+        self.start_entry_with_options_and_hint(
+            name,
+            crate::EntryOptions::from_metadata(&metadata),
+            Some(metadata.len()),
+        )
+        .await?;
+        let mut buf = vec![0u8; 64 * 1024];
+        loop {
+            let n = file.read(&mut buf).await?;
+            if n == 0 {
+                break;
+            }
+            self.write_data(&buf[..n]).await?;
+        }
+        Ok(())
+    }
+
+    /// Add every file and directory under `dir`, skipping the
+    /// [`DEFAULT_PATTERNS`](crate::ignore::DEFAULT_PATTERNS) junk files.
+    ///
+    /// See [`add_dir_recursive_with_options`](Self::add_dir_recursive_with_options).
+    pub async fn add_dir_recursive<P: AsRef<Path>>(
+        &mut self,
+        dir: P,
+        prefix: &str,
+    ) -> Result<usize> {
+        self.add_dir_recursive_with_options(dir, prefix, &DirOptions::default())
+            .await
+    }
+
+    /// Add the files and directories under `dir` selected by `options`,
+    /// returning the number of files added.
+    ///
+    /// The tree is walked with `tokio::fs` in sorted order, and entries are
+    /// named as by the sync
+    /// [`add_dir_recursive_with_ignore`](crate::StreamingZipWriter::add_dir_recursive_with_ignore):
+    /// `prefix` followed by the `/`-separated path relative to `dir`, with
+    /// modification times (and Unix modes on Unix) from the file system.
+    /// Symbolic links are skipped. Files are streamed one by one, or
+    /// compressed together by
+    /// [`write_entries_parallel`](Self::write_entries_parallel) when
+    /// `options.parallel` is set.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use s_zip::{ignore::DirOptions, AsyncStreamingZipWriter, ParallelConfig};
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let options = DirOptions::default()
+    ///     .with_include("*.rs")
+    ///     .with_exclude("target/")
+    ///     .with_parallel(ParallelConfig::balanced());
+    /// let mut writer = AsyncStreamingZipWriter::new("sources.zip").await?;
+    /// writer
+    ///     .add_dir_recursive_with_options("my-project", "my-project/", &options)
+    ///     .await?;
+    /// writer.finish().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn add_dir_recursive_with_options<P: AsRef<Path>>(
+        &mut self,
+        dir: P,
+        prefix: &str,
+        options: &DirOptions,
+    ) -> Result<usize> {
+        let root = dir.as_ref();
+        let prefix = match prefix {
+            "" => String::new(),
+            p if p.ends_with('/') => p.to_string(),
+            p => format!("{}/", p),
+        };
+        let include = options.include_rules();
+
+        // Depth-first: each level holds a directory's children not yet visited
+        struct Level {
+            rel: String,
+            ignore: Arc<IgnoreRules>,
+            children: std::vec::IntoIter<tokio::fs::DirEntry>,
+        }
+        let ignore = Arc::new(options.ignore.clone());
+        let mut stack = vec![Level {
+            ignore: scoped_ignore(ignore, root, "").await?,
+            rel: String::new(),
+            children: read_dir_sorted(root).await?,
+        }];
+
+        let mut added = 0;
+        let mut parallel = Vec::new();
+        while let Some(level) = stack.last_mut() {
+            let Some(child) = level.children.next() else {
+                stack.pop();
+                continue;
+            };
+            let (rel, ignore) = (level.rel.clone(), Arc::clone(&level.ignore));
+            let file_name = child.file_name();
+            let file_name = file_name.to_str().ok_or_else(|| {
+                SZipError::InvalidFormat(format!("File name {:?} is not valid UTF-8", child.path()))
+            })?;
+            let path = format!("{}{}", rel, file_name);
+            let file_type = child.file_type().await?;
+            if !(file_type.is_dir() || file_type.is_file())
+                || ignore.matches(&path, file_type.is_dir())
+            {
+                continue;
+            }
+
+            if file_type.is_dir() {
+                let depth = rel.matches('/').count();
+                if options.max_depth.is_some_and(|max| depth >= max) {
+                    continue;
+                }
+                if include.is_none() {
+                    let entry_options =
+                        crate::EntryOptions::from_metadata(&child.metadata().await?);
+                    self.start_entry_with_options(&format!("{}{}/", prefix, path), entry_options)
+                        .await?;
+                }
+                let rel = format!("{}/", path);
+                stack.push(Level {
+                    ignore: scoped_ignore(ignore, &child.path(), &rel).await?,
+                    children: read_dir_sorted(&child.path()).await?,
+                    rel,
+                });
+            } else {
+                if include
+                    .as_ref()
+                    .is_some_and(|rules| !rules.matches(&path, false))
+                {
+                    continue;
+                }
+                let name = format!("{}{}", prefix, path);
+                if options.parallel.is_some() {
+                    let entry_options =
+                        crate::EntryOptions::from_metadata(&child.metadata().await?);
+                    parallel.push(
+                        crate::parallel::ParallelEntry::new(name, child.path())
+                            .with_options(entry_options),
+                    );
+                } else {
+                    self.write_file(&name, child.path()).await?;
+                }
+                added += 1;
+            }
+        }
+
+        if let Some(config) = &options.parallel {
+            self.write_entries_parallel(parallel, config.clone())
+                .await?;
+        }
+        Ok(added)
+    }
+
+    async fn start_entry_with_options_and_hint(
+        &mut self,
+        name: &str,
+        options: crate::EntryOptions,
+        size_hint: Option<u64>,
+    ) -> Result<()> {
+        crate::deadline::check(self.deadline)?;
+        let name = self.names.entry_name(name, &mut self.hooks)?;
+
         // Finish previous entry if any
         self.finish_current_entry().await?;
 
-        let local_header_offset = self.output.stream_position().await?;
-        let compression_method = self.compression_method.to_zip_method();
+        let started = Instant::now();
+        // Directories have no data to compress or encrypt
+        let directory = name.ends_with('/');
+        let method = if directory || self.auto_store && crate::autostore::stored_by_name(&name) {
+            CompressionMethod::Stored
+        } else {
+            self.compression_method
+        };
+        let compression_method = method.to_zip_method();
 
         // Check if encryption is enabled
         #[cfg(feature = "encryption")]
-        let (encryptor, encryption_flag) = if let Some(ref password) = self.password {
-            let enc = AesEncryptor::new(password, self.encryption_strength)?;
-            (Some(enc), 0x01) // bit 0 set for encryption
-        } else {
-            (None, 0x00)
-        };
+        let (encryptor, encryption_flag) =
+            if let Some(password) = self.password.as_ref().filter(|_| !directory) {
+                let enc = AesEncryptor::new(password, self.encryption_strength)?;
+                (Some(enc), 0x01) // bit 0 set for encryption
+            } else {
+                (None, 0x00)
+            };
 
         #[cfg(not(feature = "encryption"))]
         let encryption_flag = 0x00;
 
-        // Write local file header with data descriptor flag (bit 3) + encryption flag (bit 0)
-        self.output.write_all(&[0x50, 0x4b, 0x03, 0x04]).await?; // signature
-        self.output.write_all(&[51, 0]).await?; // version needed (5.1 for AES)
-        self.output.write_all(&[8 | encryption_flag, 0]).await?; // general purpose bit flag
-        self.output
-            .write_all(&compression_method.to_le_bytes())
-            .await?; // compression method
-        self.output.write_all(&[0, 0, 0, 0]).await?; // mod time/date
-        self.output.write_all(&0u32.to_le_bytes()).await?; // crc32 placeholder
-        self.output.write_all(&0u32.to_le_bytes()).await?; // compressed size placeholder
-        self.output.write_all(&0u32.to_le_bytes()).await?; // uncompressed size placeholder
-        self.output
-            .write_all(&(name.len() as u16).to_le_bytes())
-            .await?;
-
-        // Calculate extra field size for AES
-        #[cfg(feature = "encryption")]
-        let extra_len = if encryptor.is_some() { 11 } else { 0 };
-        #[cfg(not(feature = "encryption"))]
-        let extra_len = 0;
-
-        self.output
-            .write_all(&(extra_len as u16).to_le_bytes())
-            .await?; // extra len
-        self.output.write_all(name.as_bytes()).await?;
-
-        // Write AES extra field if encryption is enabled
+        // Extra field: AES (11 bytes) + Unix permissions (15 bytes) if set
+        #[cfg_attr(not(feature = "encryption"), allow(unused_mut))]
+        let mut extra: Vec<u8> = Vec::new();
         #[cfg(feature = "encryption")]
         if let Some(ref enc) = encryptor {
             // AES extra field header (0x9901)
             // Format per WinZip AE-2 spec:
             //   ID(2) + Length(2) + Version(2) + Vendor(2) + Strength(1) + ActualCompression(2) = 7 bytes data
-            self.output.write_all(&[0x01, 0x99]).await?; // WinZip AES encryption marker
-            self.output.write_all(&[7, 0]).await?; // data size (7 bytes)
-            self.output.write_all(&[2, 0]).await?; // AE-2 format version
-            self.output.write_all(&[0x41, 0x45]).await?; // vendor ID "AE"
-            self.output
-                .write_all(&[enc.strength().to_winzip_code() as u8])
-                .await?; // strength (1 byte!)
-            self.output
-                .write_all(&compression_method.to_le_bytes())
-                .await?; // actual compression (2 bytes)
-
-            // Write salt and password verification
-            self.output.write_all(enc.salt()).await?;
-            self.output.write_all(enc.password_verify()).await?;
+            extra.extend_from_slice(&[0x01, 0x99]); // WinZip AES encryption marker
+            extra.extend_from_slice(&[7, 0]); // data size (7 bytes)
+            extra.extend_from_slice(&[2, 0]); // AE-2 format version
+            extra.extend_from_slice(&[0x41, 0x45]); // vendor ID "AE"
+            extra.push(enc.strength().to_winzip_code() as u8); // strength (1 byte!)
+            extra.extend_from_slice(&compression_method.to_le_bytes()); // actual compression (2 bytes)
+        }
+        extra.extend_from_slice(&options.unix_extra_field());
+        extra.extend_from_slice(&options.extended_timestamp().to_extra_field(false));
+
+        // Write local file header with data descriptor flag (bit 3) + encryption flag (bit 0).
+        // CRC-32 and sizes are zero here and follow in the data descriptor.
+        let flags =
+            raw::FLAG_DATA_DESCRIPTOR | encryption_flag | raw::name_flags(&name) | method.flags();
+
+        // Sizes follow the data; a hint past 4 GiB announces ZIP64 up front
+        let mut version_needed = raw::version_needed(compression_method, encryption_flag != 0);
+        if size_hint.is_some_and(|n| n > u32::MAX as u64) {
+            version_needed = version_needed.max(raw::VERSION_ZIP64);
         }
+        let (dos_time, dos_date) = options.msdos_datetime();
+        let mut header = Vec::with_capacity(30 + name.len() + extra.len());
+        raw::write_local_header(
+            &mut header,
+            &LocalFileHeader {
+                version_needed: self.version_needed_for(version_needed),
+                flags,
+                compression_method,
+                last_mod_time: dos_time,
+                last_mod_date: dos_date,
+                name: name.as_bytes(),
+                extra: &extra,
+                ..Default::default()
+            },
+        )?;
+
+        // Salt and password verification follow the header
+        #[cfg_attr(not(feature = "encryption"), allow(unused_mut))]
+        let mut preamble = Vec::new();
+        #[cfg(feature = "encryption")]
+        if let Some(ref enc) = encryptor {
+            preamble.extend_from_slice(enc.salt());
+            preamble.extend_from_slice(enc.password_verify());
+        }
+        // With auto-store the offset is known once the header is written
+        let (local_header_offset, undecided) =
+            if self.auto_store && method != CompressionMethod::Stored {
+                let sample = Vec::new();
+                (
+                    0,
+                    Some(Undecided {
+                        header,
+                        preamble,
+                        sample,
+                    }),
+                )
+            } else {
+                let offset = self.write_entry_header(&name, &header, &preamble).await?;
+                (offset, None)
+            };
 
         // Create encoder for this entry based on compression method
         // Use adaptive buffer if size hint is provided
-        let encoder: Box<dyn AsyncCompressorWrite> = match self.compression_method {
+        let encoder: Box<dyn AsyncCompressorWrite> = match method {
+            CompressionMethod::Deflate if self.deflate_threads > 1 => {
+                Box::new(ParallelDeflateCompressor::new(
+                    flate2::Compression::new(self.compression_level.min(9)),
+                    self.deflate_threads,
+                    CompressedBuffer::with_size_hint(size_hint),
+                ))
+            }
             CompressionMethod::Deflate => {
                 let level = match self.compression_level {
                     0 => async_compression::Level::Fastest,
@@ -564,12 +1530,17 @@ impl<W: AsyncWrite + AsyncSeek + Unpin> AsyncStreamingZipWriter<W> {
             }
             #[cfg(all(feature = "zstd-support", feature = "async-zstd"))]
             CompressionMethod::Zstd => {
-                let level = async_compression::Level::Precise(self.compression_level as i32);
+                let dictionary = self
+                    .zstd_dictionary
+                    .as_ref()
+                    .map(|(dictionary, _)| dictionary);
                 Box::new(ZstdCompressor {
-                    encoder: ZstdEncoder::with_quality(
+                    encoder: self.zstd_options.encoder(
                         CompressedBuffer::with_size_hint(size_hint),
-                        level,
-                    ),
+                        self.compression_level as i32,
+                        dictionary,
+                    )?,
+                    multithreaded: self.zstd_options.workers > 0,
                 })
             }
             #[cfg(all(feature = "zstd-support", not(feature = "async-zstd")))]
@@ -578,6 +1549,27 @@ impl<W: AsyncWrite + AsyncSeek + Unpin> AsyncStreamingZipWriter<W> {
                     "Zstd compression requires 'async-zstd' feature".to_string(),
                 ));
             }
+            #[cfg(feature = "lzma")]
+            CompressionMethod::Lzma | CompressionMethod::Xz => {
+                let (header, stream) =
+                    crate::lzma::encoder(compression_method, self.compression_level)?;
+                let mut buffer = CompressedBuffer::with_size_hint(size_hint);
+                Write::write_all(&mut buffer, &header)?;
+                Box::new(LzmaCompressor {
+                    encoder: liblzma::write::XzEncoder::new_stream(buffer, stream),
+                })
+            }
+            #[cfg(feature = "bzip2")]
+            CompressionMethod::Bzip2 => {
+                let level =
+                    async_compression::Level::Precise(self.compression_level.clamp(1, 9) as i32);
+                Box::new(Bzip2Compressor {
+                    encoder: BzEncoder::with_quality(
+                        CompressedBuffer::with_size_hint(size_hint),
+                        level,
+                    ),
+                })
+            }
             CompressionMethod::Stored => Box::new(StoredCompressor {
                 buffer: CompressedBuffer::with_size_hint(size_hint),
             }),
@@ -599,6 +1591,12 @@ impl<W: AsyncWrite + AsyncSeek + Unpin> AsyncStreamingZipWriter<W> {
             encoder,
             counter,
             compression_method,
+            flags,
+            undecided,
+            last_modified: DosDateTime::new(dos_time, dos_date),
+            external_attrs: options.external_attrs(name.ends_with('/')),
+            extended_timestamp: options.extended_timestamp(),
+            started,
             #[cfg(feature = "encryption")]
             encryptor,
         });
@@ -609,10 +1607,71 @@ impl<W: AsyncWrite + AsyncSeek + Unpin> AsyncStreamingZipWriter<W> {
     /// Write uncompressed data to current entry (will be compressed and/or encrypted on-the-fly)
     pub async fn write_data(&mut self, data: &[u8]) -> Result<()> {
         crate::trace!(bytes = data.len(), "write_data");
+        crate::deadline::check(self.deadline)?;
         let entry = self
             .current_entry
             .as_mut()
             .ok_or_else(|| SZipError::InvalidFormat("No entry started".to_string()))?;
+        if let Some(undecided) = entry.undecided.as_mut() {
+            undecided.sample.extend_from_slice(data);
+            if undecided.sample.len() >= crate::autostore::SAMPLE_LEN {
+                self.choose_method().await?;
+            }
+            return Ok(());
+        }
+        self.compress_data(data).await
+    }
+
+    /// Write an entry's local header and the preamble of encrypted data,
+    /// returning the header's offset
+    async fn write_entry_header(
+        &mut self,
+        name: &str,
+        header: &[u8],
+        preamble: &[u8],
+    ) -> Result<u64> {
+        let offset = self.output.position();
+        self.output.write_all(header).await?;
+        self.hooks.header_written(name, offset, header)?;
+        self.output.write_all(preamble).await?;
+        Ok(offset)
+    }
+
+    /// Settle the method of an entry held back by auto-store, then write its
+    /// header and the data sampled so far
+    async fn choose_method(&mut self) -> Result<()> {
+        let Some(entry) = self.current_entry.as_mut() else {
+            return Ok(());
+        };
+        let Some(mut undecided) = entry.undecided.take() else {
+            return Ok(());
+        };
+        if crate::autostore::incompressible(&undecided.sample) {
+            crate::autostore::patch_header(&mut undecided.header, self.version_needed.is_some());
+            entry.compression_method = CompressionMethod::Stored.to_zip_method();
+            entry.flags &= !crate::autostore::METHOD_FLAGS;
+            entry.encoder = Box::new(StoredCompressor {
+                buffer: CompressedBuffer::new(),
+            });
+        }
+        let name = entry.name.clone();
+        let offset = self
+            .write_entry_header(&name, &undecided.header, &undecided.preamble)
+            .await?;
+        if let Some(entry) = self.current_entry.as_mut() {
+            entry.local_header_offset = offset;
+        }
+        self.compress_data(&undecided.sample).await
+    }
+
+    /// Compress (and encrypt) data of the current entry, whose header is
+    /// written
+    async fn compress_data(&mut self, data: &[u8]) -> Result<()> {
+        let Some(entry) = self.current_entry.as_mut() else {
+            return Err(SZipError::InvalidFormat("No entry started".to_string()));
+        };
+
+        self.hooks.data_written(&entry.name, data)?;
 
         // Update CRC and size with uncompressed data
         entry.counter.update_uncompressed(data);
@@ -623,15 +1682,37 @@ impl<W: AsyncWrite + AsyncSeek + Unpin> AsyncStreamingZipWriter<W> {
             encryptor.update_hmac(data);
         }
 
-        // Write to encoder (compresses data into buffer)
-        entry.encoder.write_all(data).await?;
+        // Parallel DEFLATE is on the blocking pool already, and zstd
+        // workers are threads of their own
+        #[cfg_attr(not(feature = "async-zstd"), allow(unused_mut))]
+        let mut parallel = self.deflate_threads > 1 && entry.compression_method == 8;
+        #[cfg(feature = "async-zstd")]
+        {
+            parallel |= self.zstd_options.workers > 0 && entry.compression_method == 93;
+        }
+        if !parallel
+            && self
+                .compression_offload
+                .is_some_and(|min_chunk| data.len() >= min_chunk)
+        {
+            let encoder =
+                std::mem::replace(&mut entry.encoder, Box::new(OffloadedCompressor::new()));
+            entry.encoder = compress_offloaded(encoder, data.to_vec()).await?;
+        } else {
+            // Write to encoder (compresses data into buffer)
+            entry.encoder.write_all(data).await?;
 
-        // Flush encoder to ensure all data is in buffer
-        entry.encoder.flush().await?;
+            // Flush encoder to ensure all data is in buffer
+            entry.encoder.flush().await?;
+        }
 
         // Check if buffer should be flushed to output
         let buffer = entry.encoder.get_buffer_mut();
-        if buffer.should_flush() {
+        let over_budget = self
+            .memory
+            .as_mut()
+            .is_some_and(|memory| !memory.try_resize(buffer.buffer.len()));
+        if buffer.should_flush() || over_budget {
             // Flush buffer to output to keep memory usage low
             let compressed_data = buffer.take();
 
@@ -650,6 +1731,9 @@ impl<W: AsyncWrite + AsyncSeek + Unpin> AsyncStreamingZipWriter<W> {
 
             self.output.write_all(&data_to_write).await?;
             entry.counter.add_compressed(data_to_write.len() as u64);
+            if let Some(memory) = self.memory.as_mut() {
+                memory.try_resize(0);
+            }
         }
 
         Ok(())
@@ -657,6 +1741,7 @@ impl<W: AsyncWrite + AsyncSeek + Unpin> AsyncStreamingZipWriter<W> {
 
     /// Finish current entry and write data descriptor
     async fn finish_current_entry(&mut self) -> Result<()> {
+        self.choose_method().await?;
         if let Some(mut entry) = self.current_entry.take() {
             // Finish compression and get remaining buffered data
             let mut buffer = entry.encoder.finish_compression().await?;
@@ -681,6 +1766,10 @@ impl<W: AsyncWrite + AsyncSeek + Unpin> AsyncStreamingZipWriter<W> {
                 entry.counter.add_compressed(data_to_write.len() as u64);
             }
 
+            if let Some(memory) = self.memory.as_mut() {
+                memory.try_resize(0);
+            }
+
             // Write authentication code for AES encryption
             #[cfg(feature = "encryption")]
             let (encryption_strength_code, auth_code_size) =
@@ -701,24 +1790,19 @@ impl<W: AsyncWrite + AsyncSeek + Unpin> AsyncStreamingZipWriter<W> {
             let uncompressed_size = entry.counter.uncompressed_count;
 
             // Write data descriptor
-            self.output.write_all(&[0x50, 0x4b, 0x07, 0x08]).await?; // signature
-            self.output.write_all(&crc.to_le_bytes()).await?;
-            // If sizes exceed 32-bit, write 64-bit sizes (ZIP64 data descriptor)
-            if compressed_size > u32::MAX as u64 || uncompressed_size > u32::MAX as u64 {
-                self.output
-                    .write_all(&compressed_size.to_le_bytes())
-                    .await?;
-                self.output
-                    .write_all(&uncompressed_size.to_le_bytes())
-                    .await?;
-            } else {
-                self.output
-                    .write_all(&(compressed_size as u32).to_le_bytes())
-                    .await?;
-                self.output
-                    .write_all(&(uncompressed_size as u32).to_le_bytes())
-                    .await?;
-            }
+            let mut descriptor = Vec::with_capacity(24);
+            raw::write_data_descriptor(&mut descriptor, crc, compressed_size, uncompressed_size)?;
+            self.output.write_all(&descriptor).await?;
+
+            self.hooks.entry_finished(&EntrySummary {
+                name: &entry.name,
+                offset: entry.local_header_offset,
+                crc32: crc,
+                compressed_size,
+                uncompressed_size,
+                compression_method: entry.compression_method,
+                elapsed: entry.started.elapsed(),
+            })?;
 
             // Save entry info for central directory
             self.entries.push(ZipEntry {
@@ -728,8 +1812,13 @@ impl<W: AsyncWrite + AsyncSeek + Unpin> AsyncStreamingZipWriter<W> {
                 compressed_size,
                 uncompressed_size,
                 compression_method: entry.compression_method,
+                flags: entry.flags,
+                last_modified: entry.last_modified,
+                external_attrs: entry.external_attrs,
+                extended_timestamp: entry.extended_timestamp,
                 #[cfg(feature = "encryption")]
                 encryption_strength: encryption_strength_code,
+                copied: None,
             });
         }
         Ok(())
@@ -738,19 +1827,23 @@ impl<W: AsyncWrite + AsyncSeek + Unpin> AsyncStreamingZipWriter<W> {
     /// Compress and add multiple files in parallel with bounded concurrency
     ///
     /// This method compresses files in parallel to leverage multi-core CPUs, while
-    /// maintaining bounded memory usage through a semaphore-based concurrency limit.
+    /// maintaining bounded memory usage. Each entry is written as it is compressed
+    /// once its turn comes; later entries hold their compressed data meanwhile, up
+    /// to [`ParallelConfig::max_buffered`](crate::ParallelConfig::max_buffered)
+    /// bytes, then pause. Entries compressed before their turn have their sizes in
+    /// the local header; others are followed by a data descriptor.
     ///
     /// # Memory Usage
-    /// Peak memory = `max_concurrent × ~4MB`
-    /// - Conservative (2 threads): ~8MB
-    /// - Balanced (4 threads): ~16MB  
-    /// - Aggressive (8 threads): ~32MB
+    /// Peak memory = `max_concurrent × ~1MB + max_buffered`, whatever the file sizes
+    /// - Conservative (2 threads): ~18MB
+    /// - Balanced (4 threads): ~36MB
+    /// - Aggressive (8 threads): ~72MB
     ///
     /// # Performance
     /// Expected speedup: 2-4x on multi-core systems for CPU-bound compression
     ///
     /// # Arguments
-    /// * `entries` - List of files to compress
+    /// * `entries` - Entries to compress: files, data in memory or readers
     /// * `config` - Parallel compression configuration
     ///
     /// # Example
@@ -765,7 +1858,7 @@ impl<W: AsyncWrite + AsyncSeek + Unpin> AsyncStreamingZipWriter<W> {
     ///     ParallelEntry::new("file3.txt", "path/to/file3.txt"),
     /// ];
     ///
-    /// // Use balanced config (4 concurrent, ~16MB peak memory)
+    /// // Use balanced config (4 concurrent, ~36MB peak memory)
     /// let config = ParallelConfig::balanced();
     /// writer.write_entries_parallel(entries, config).await?;
     ///
@@ -778,80 +1871,136 @@ impl<W: AsyncWrite + AsyncSeek + Unpin> AsyncStreamingZipWriter<W> {
         entries: Vec<crate::parallel::ParallelEntry>,
         config: crate::parallel::ParallelConfig,
     ) -> Result<()> {
-        use crate::parallel::compress_entries_parallel;
+        use crate::parallel::{check_method, compress_entries_parallel, Chunk};
+        crate::deadline::check(self.deadline)?;
+        check_method(config.compression_method)?;
+        let method = config.compression_method;
+        let compression_method = method.to_zip_method();
+
+        let entries = entries
+            .into_iter()
+            .map(|mut entry| {
+                entry.name = self.names.entry_name(&entry.name, &mut self.hooks)?;
+                Ok(entry)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let mut options = entries
+            .iter()
+            .map(|entry| entry.options.clone())
+            .collect::<Vec<_>>()
+            .into_iter();
 
         // Finish any pending entry first
         self.finish_current_entry().await?;
 
-        // Compress all files in parallel with bounded concurrency
-        let compressed_entries = compress_entries_parallel(entries, config).await?;
-
-        // Write compressed entries sequentially to maintain order
-        for entry in compressed_entries {
-            // Write local file header
-            let local_header_offset = self.output.stream_position().await?;
-
-            let compressed_size = entry.data.len() as u64;
-            let uncompressed_size = entry.uncompressed_size;
-            let needs_zip64 =
-                compressed_size > u32::MAX as u64 || uncompressed_size > u32::MAX as u64;
-
-            self.output.write_all(&[0x50, 0x4b, 0x03, 0x04]).await?; // local file header sig
-                                                                     // version needed: 4.5 (ZIP64) or 2.0 (standard)
-            let version_needed: u16 = if needs_zip64 { 45 } else { 20 };
-            self.output.write_all(&version_needed.to_le_bytes()).await?;
-            self.output.write_all(&[8, 0]).await?; // general purpose bit flag (bit 3 set)
-            self.output.write_all(&[8, 0]).await?; // compression method (DEFLATE)
-            self.output.write_all(&[0, 0, 0, 0]).await?; // mod time/date
-            self.output.write_all(&entry.crc32.to_le_bytes()).await?;
-
-            if needs_zip64 {
-                // Use 0xFFFFFFFF placeholder; real sizes in ZIP64 extra field
-                self.output.write_all(&0xFFFFFFFFu32.to_le_bytes()).await?; // compressed size
-                self.output.write_all(&0xFFFFFFFFu32.to_le_bytes()).await?; // uncompressed size
+        // Compress in parallel, writing each entry as soon as its turn comes
+        let mut compression = compress_entries_parallel(entries, config);
+        while let Some((name, mut chunks)) = compression.next_entry() {
+            crate::deadline::check(self.deadline)?;
+            let options = options.next().unwrap_or_default();
+            let local_header_offset = self.output.position();
+            let (dos_time, dos_date) = options.msdos_datetime();
+            let last_modified = DosDateTime::new(dos_time, dos_date);
+            let mut extra = options.unix_extra_field();
+            extra.extend_from_slice(&options.extended_timestamp().to_extra_field(false));
+            let first = chunks.next().await?;
+
+            // An entry compressed before its turn has its sizes known up
+            // front, so no data descriptor follows
+            let mut flags = raw::name_flags(&name) | method.flags();
+            let (mut totals, mut compressed_size) = (None, 0u64);
+            if let Chunk::Complete(data, entry_totals) = &first {
+                compressed_size = data.iter().map(|chunk| chunk.len() as u64).sum();
+                totals = Some(entry_totals);
             } else {
-                self.output
-                    .write_all(&(compressed_size as u32).to_le_bytes())
-                    .await?;
-                self.output
-                    .write_all(&(uncompressed_size as u32).to_le_bytes())
-                    .await?;
+                flags |= raw::FLAG_DATA_DESCRIPTOR;
             }
 
-            self.output
-                .write_all(&(entry.name.len() as u16).to_le_bytes())
-                .await?; // filename length
-
-            // ZIP64 extra field (28 bytes) or no extra field
-            let extra_len: u16 = if needs_zip64 { 20 } else { 0 };
-            self.output.write_all(&extra_len.to_le_bytes()).await?;
-            self.output.write_all(entry.name.as_bytes()).await?;
-
-            if needs_zip64 {
-                // ZIP64 extended information extra field (ID 0x0001)
-                self.output.write_all(&0x0001u16.to_le_bytes()).await?; // header ID
-                self.output.write_all(&16u16.to_le_bytes()).await?; // data size (2×8 bytes)
-                self.output
-                    .write_all(&uncompressed_size.to_le_bytes())
-                    .await?;
-                self.output
-                    .write_all(&compressed_size.to_le_bytes())
-                    .await?;
-            }
-
-            // Write compressed data
-            self.output.write_all(&entry.data).await?;
+            // The record writer adds the ZIP64 extra field if needed
+            let mut header = Vec::with_capacity(50 + name.len() + extra.len());
+            raw::write_local_header(
+                &mut header,
+                &LocalFileHeader {
+                    // raised to 4.5 by the record writer when ZIP64 is needed
+                    version_needed: self
+                        .version_needed_for(raw::version_needed(compression_method, false)),
+                    flags,
+                    compression_method,
+                    crc32: totals.map_or(0, |totals| totals.crc32),
+                    compressed_size,
+                    uncompressed_size: totals.map_or(0, |totals| totals.uncompressed_size),
+                    last_mod_time: last_modified.time,
+                    last_mod_date: last_modified.date,
+                    name: name.as_bytes(),
+                    extra: &extra,
+                },
+            )?;
+            self.output.write_all(&header).await?;
+            self.hooks
+                .header_written(&name, local_header_offset, &header)?;
+
+            // Write compressed data, as it is produced for a streamed entry
+            let totals = match first {
+                Chunk::Complete(data, totals) => {
+                    for chunk in data {
+                        self.output.write_all(&chunk).await?;
+                    }
+                    totals
+                }
+                Chunk::Data(mut chunk) => loop {
+                    self.output.write_all(&chunk).await?;
+                    compressed_size += chunk.len() as u64;
+                    match chunks.next().await? {
+                        Chunk::Data(next) => chunk = next,
+                        Chunk::End(totals) => {
+                            let mut descriptor = Vec::with_capacity(24);
+                            raw::write_data_descriptor(
+                                &mut descriptor,
+                                totals.crc32,
+                                compressed_size,
+                                totals.uncompressed_size,
+                            )?;
+                            self.output.write_all(&descriptor).await?;
+                            break totals;
+                        }
+                        Chunk::Complete(..) => {
+                            return Err(SZipError::InvalidFormat(
+                                "Parallel compression chunks out of order".to_string(),
+                            ))
+                        }
+                    }
+                },
+                Chunk::End(_) => {
+                    return Err(SZipError::InvalidFormat(
+                        "Parallel compression chunks out of order".to_string(),
+                    ))
+                }
+            };
+            self.hooks.entry_finished(&EntrySummary {
+                name: &name,
+                offset: local_header_offset,
+                crc32: totals.crc32,
+                compressed_size,
+                uncompressed_size: totals.uncompressed_size,
+                compression_method,
+                elapsed: totals.elapsed,
+            })?;
 
             // Add to entries list
             self.entries.push(ZipEntry {
-                name: entry.name,
+                name,
                 local_header_offset,
-                crc32: entry.crc32,
+                crc32: totals.crc32,
                 compressed_size,
-                uncompressed_size,
-                compression_method: 8, // DEFLATE
+                uncompressed_size: totals.uncompressed_size,
+                compression_method,
+                flags,
+                last_modified,
+                external_attrs: options.external_attrs(false),
+                extended_timestamp: options.extended_timestamp(),
                 #[cfg(feature = "encryption")]
                 encryption_strength: None, // Parallel compression doesn't support encryption yet
+                copied: None,
             });
         }
 
@@ -861,58 +2010,38 @@ impl<W: AsyncWrite + AsyncSeek + Unpin> AsyncStreamingZipWriter<W> {
     /// Finish ZIP file (write central directory and return the writer)
     pub async fn finish(mut self) -> Result<W> {
         crate::trace!(entries = self.entries.len(), "finish");
+        crate::deadline::check(self.deadline)?;
         // Finish last entry
         self.finish_current_entry().await?;
 
-        let central_dir_offset = self.output.stream_position().await?;
+        // Stored, as it is read before anything can be decompressed
+        #[cfg(feature = "async-zstd")]
+        if let Some((dictionary, true)) = self.zstd_dictionary.clone() {
+            let method = std::mem::replace(&mut self.compression_method, CompressionMethod::Stored);
+            self.add_entry(DICTIONARY_ENTRY, dictionary.as_bytes())
+                .await?;
+            self.finish_current_entry().await?;
+            self.compression_method = method;
+        }
+
+        let central_dir_offset = self.output.position();
 
         // Write central directory
+        let mut entry_count = self.entries.len() as u64;
+        // Highest version needed over all records, for the ZIP64 EOCD record
+        let mut max_version_needed = 0;
+        if let Some(existing) = &self.existing {
+            // Existing entries haven't moved, so their records are unchanged
+            self.output.write_all(&existing.records).await?;
+            entry_count += existing.count();
+            max_version_needed = existing.version_needed;
+        }
+        let mut record = Vec::new();
         for entry in &self.entries {
-            self.output.write_all(&[0x50, 0x4b, 0x01, 0x02]).await?; // central dir sig
-            self.output.write_all(&[20, 0]).await?; // version made by
-            self.output.write_all(&[51, 0]).await?; // version needed (5.1 for AES)
-
-            // general purpose bit flag: bit 3 for data descriptor + bit 0 for encryption
-            #[cfg(feature = "encryption")]
-            let flags = if entry.encryption_strength.is_some() {
-                0x09 // bit 3 + bit 0 set
-            } else {
-                0x08 // only bit 3 set
-            };
-            #[cfg(not(feature = "encryption"))]
-            let flags = 0x08;
-
-            self.output.write_all(&[flags, 0]).await?; // general purpose bit flag
-            self.output
-                .write_all(&entry.compression_method.to_le_bytes())
-                .await?; // compression method
-            self.output.write_all(&[0, 0, 0, 0]).await?; // mod time/date
-            self.output.write_all(&entry.crc32.to_le_bytes()).await?;
-
-            // Write sizes (32-bit placeholders or actual values)
-            if entry.compressed_size > u32::MAX as u64 {
-                self.output.write_all(&0xFFFFFFFFu32.to_le_bytes()).await?;
-            } else {
-                self.output
-                    .write_all(&(entry.compressed_size as u32).to_le_bytes())
-                    .await?;
-            }
-
-            if entry.uncompressed_size > u32::MAX as u64 {
-                self.output.write_all(&0xFFFFFFFFu32.to_le_bytes()).await?;
-            } else {
-                self.output
-                    .write_all(&(entry.uncompressed_size as u32).to_le_bytes())
-                    .await?;
-            }
-
-            self.output
-                .write_all(&(entry.name.len() as u16).to_le_bytes())
-                .await?;
-
-            // Prepare extra fields
             let mut extra_field: Vec<u8> = Vec::new();
-
+            if let Some(copied) = &entry.copied {
+                extra_field.extend_from_slice(&copied.extra);
+            }
             // Add AES extra field if entry was encrypted
             #[cfg(feature = "encryption")]
             if let Some(strength_code) = entry.encryption_strength {
@@ -925,133 +2054,86 @@ impl<W: AsyncWrite + AsyncSeek + Unpin> AsyncStreamingZipWriter<W> {
                 extra_field.extend_from_slice(&entry.compression_method.to_le_bytes());
                 // actual compression
             }
-
-            // Add ZIP64 extra field if needed
-            if entry.uncompressed_size > u32::MAX as u64
-                || entry.compressed_size > u32::MAX as u64
-                || entry.local_header_offset > u32::MAX as u64
-            {
-                // ZIP64 extra header ID 0x0001
-                extra_field.extend_from_slice(&0x0001u16.to_le_bytes());
-                let mut data: Vec<u8> = Vec::new();
-                if entry.uncompressed_size > u32::MAX as u64 {
-                    data.extend_from_slice(&entry.uncompressed_size.to_le_bytes());
-                }
-                if entry.compressed_size > u32::MAX as u64 {
-                    data.extend_from_slice(&entry.compressed_size.to_le_bytes());
-                }
-                if entry.local_header_offset > u32::MAX as u64 {
-                    data.extend_from_slice(&entry.local_header_offset.to_le_bytes());
-                }
-                extra_field.extend_from_slice(&(data.len() as u16).to_le_bytes());
-                extra_field.extend_from_slice(&data);
+            if entry.copied.is_none() {
+                extra_field.extend_from_slice(&entry.extended_timestamp.to_extra_field(true));
             }
-
-            self.output
-                .write_all(&(extra_field.len() as u16).to_le_bytes())
-                .await?; // extra len
-            self.output.write_all(&0u16.to_le_bytes()).await?; // file comment len
-            self.output.write_all(&0u16.to_le_bytes()).await?; // disk number start
-            self.output.write_all(&0u16.to_le_bytes()).await?; // internal attrs
-            self.output.write_all(&0u32.to_le_bytes()).await?; // external attrs
-
-            // local header offset (32-bit or 0xFFFFFFFF)
-            if entry.local_header_offset > u32::MAX as u64 {
-                self.output.write_all(&0xFFFFFFFFu32.to_le_bytes()).await?;
-            } else {
-                self.output
-                    .write_all(&(entry.local_header_offset as u32).to_le_bytes())
-                    .await?;
-            }
-
-            self.output.write_all(entry.name.as_bytes()).await?;
-            if !extra_field.is_empty() {
-                self.output.write_all(&extra_field).await?;
-            }
-        }
-
-        let central_dir_size = self.output.stream_position().await? - central_dir_offset;
-
-        // Determine if we need ZIP64 EOCD
-        let need_zip64 = self.entries.len() > u16::MAX as usize
-            || central_dir_size > u32::MAX as u64
-            || central_dir_offset > u32::MAX as u64;
-
-        if need_zip64 {
-            // Write ZIP64 End of Central Directory Record
-            self.output.write_all(&[0x50, 0x4b, 0x06, 0x06]).await?;
-            let zip64_eocd_size: u64 = 44;
-            self.output
-                .write_all(&zip64_eocd_size.to_le_bytes())
-                .await?;
-            self.output.write_all(&[20, 0]).await?;
-            self.output.write_all(&[20, 0]).await?;
-            self.output.write_all(&0u32.to_le_bytes()).await?;
-            self.output.write_all(&0u32.to_le_bytes()).await?;
-            self.output
-                .write_all(&(self.entries.len() as u64).to_le_bytes())
-                .await?;
-            self.output
-                .write_all(&(self.entries.len() as u64).to_le_bytes())
-                .await?;
-            self.output
-                .write_all(&central_dir_size.to_le_bytes())
-                .await?;
-            self.output
-                .write_all(&central_dir_offset.to_le_bytes())
-                .await?;
-
-            // Write ZIP64 EOCD locator
-            self.output.write_all(&[0x50, 0x4b, 0x06, 0x07]).await?;
-            self.output.write_all(&0u32.to_le_bytes()).await?;
-            let zip64_eocd_pos = central_dir_offset + central_dir_size;
-            self.output.write_all(&zip64_eocd_pos.to_le_bytes()).await?;
-            self.output.write_all(&0u32.to_le_bytes()).await?;
-        }
-
-        // Write end of central directory (classic)
-        self.output.write_all(&[0x50, 0x4b, 0x05, 0x06]).await?;
-        self.output.write_all(&0u16.to_le_bytes()).await?; // disk number
-        self.output.write_all(&0u16.to_le_bytes()).await?; // disk with central dir
-
-        // number of entries (16-bit or 0xFFFF if ZIP64 used)
-        if self.entries.len() > u16::MAX as usize {
-            self.output.write_all(&0xFFFFu16.to_le_bytes()).await?;
-            self.output.write_all(&0xFFFFu16.to_le_bytes()).await?;
-        } else {
-            self.output
-                .write_all(&(self.entries.len() as u16).to_le_bytes())
-                .await?;
-            self.output
-                .write_all(&(self.entries.len() as u16).to_le_bytes())
-                .await?;
-        }
-
-        // central dir size and offset (32-bit or 0xFFFFFFFF)
-        if central_dir_size > u32::MAX as u64 {
-            self.output.write_all(&0xFFFFFFFFu32.to_le_bytes()).await?;
-        } else {
-            self.output
-                .write_all(&(central_dir_size as u32).to_le_bytes())
-                .await?;
+            let (version_made_by, version_needed, internal_attrs) = match &entry.copied {
+                Some(copied) => (
+                    copied.version_made_by,
+                    copied.version_needed,
+                    copied.internal_attrs,
+                ),
+                None => (
+                    self.version_made_by
+                        .unwrap_or(raw::version_made_by(entry.external_attrs)),
+                    self.version_needed_for(raw::version_needed(
+                        entry.compression_method,
+                        entry.flags & raw::FLAG_ENCRYPTED != 0,
+                    )),
+                    0,
+                ),
+            };
+            max_version_needed = max_version_needed.max(version_needed);
+
+            // ZIP64 extra field is added by the record writer when needed
+            record.clear();
+            raw::write_central_record(
+                &mut record,
+                &CentralDirectoryRecord {
+                    version_made_by,
+                    version_needed,
+                    flags: entry.flags,
+                    compression_method: entry.compression_method,
+                    crc32: entry.crc32,
+                    compressed_size: entry.compressed_size,
+                    uncompressed_size: entry.uncompressed_size,
+                    last_mod_time: entry.last_modified.time,
+                    last_mod_date: entry.last_modified.date,
+                    local_header_offset: entry.local_header_offset,
+                    internal_attrs,
+                    external_attrs: entry.external_attrs,
+                    name: entry.name.as_bytes(),
+                    extra: &extra_field,
+                    ..Default::default()
+                },
+            )?;
+            self.output.write_all(&record).await?;
         }
 
-        if central_dir_offset > u32::MAX as u64 {
-            self.output.write_all(&0xFFFFFFFFu32.to_le_bytes()).await?;
-        } else {
-            self.output
-                .write_all(&(central_dir_offset as u32).to_le_bytes())
-                .await?;
-        }
+        let central_dir_size = self.output.position() - central_dir_offset;
 
-        self.output.write_all(&0u16.to_le_bytes()).await?; // comment len
+        // Write end of central directory (with ZIP64 record + locator if needed)
+        let mut eocd = Vec::with_capacity(98);
+        raw::write_eocd(
+            &mut eocd,
+            &EndOfCentralDirectory {
+                entry_count,
+                cd_size: central_dir_size,
+                cd_offset: central_dir_offset,
+                comment: &self.comment,
+                version_made_by: self.version_made_by.unwrap_or(raw::VERSION_MADE_BY),
+                version_needed: max_version_needed,
+                ..Default::default()
+            },
+        )?;
+        self.output.write_all(&eocd).await?;
 
         // CRITICAL: Must call shutdown() to ensure cloud uploads complete
         // For cloud writers like S3ZipWriter, shutdown() completes the multipart upload
         self.output.flush().await?;
+        if let Some(truncate) = self.truncate {
+            let len = self.output.position();
+            truncate(&mut self.output.inner, len).await?;
+        }
         self.output.shutdown().await?;
-
-        Ok(self.output)
+        self.hooks.archive_finished(&ArchiveSummary {
+            entry_count,
+            central_directory_offset: central_dir_offset,
+            central_directory_size: central_dir_size,
+            total_size: self.output.position(),
+        })?;
+
+        Ok(self.output.into_inner())
     }
 
     /// Finish the ZIP archive and return the underlying writer together with
@@ -1103,3 +2185,34 @@ impl<W: AsyncWrite + AsyncSeek + Unpin> AsyncStreamingZipWriter<W> {
         ))
     }
 }
+
+/// The entries of `dir`, sorted by name
+async fn read_dir_sorted(dir: &Path) -> Result<std::vec::IntoIter<tokio::fs::DirEntry>> {
+    let mut read_dir = tokio::fs::read_dir(dir).await?;
+    let mut children = Vec::new();
+    while let Some(child) = read_dir.next_entry().await? {
+        children.push(child);
+    }
+    children.sort_by_key(|child| child.file_name());
+    Ok(children.into_iter())
+}
+
+/// `ignore` plus the `.gitignore` of `dir` (at `rel` below the root), when
+/// the rules read them
+async fn scoped_ignore(
+    ignore: Arc<IgnoreRules>,
+    dir: &Path,
+    rel: &str,
+) -> Result<Arc<IgnoreRules>> {
+    let gitignore = dir.join(".gitignore");
+    if !ignore.reads_gitignore()
+        || !tokio::fs::metadata(&gitignore)
+            .await
+            .is_ok_and(|m| m.is_file())
+    {
+        return Ok(ignore);
+    }
+    let mut rules = (*ignore).clone();
+    rules.add_lines(rel, &tokio::fs::read_to_string(&gitignore).await?);
+    Ok(Arc::new(rules))
+}
@@ -0,0 +1,83 @@
+//! Choosing Stored for entries that wouldn't shrink.
+//!
+//! With auto-store on (see
+//! [`StreamingZipWriter::set_auto_store`](crate::StreamingZipWriter::set_auto_store)),
+//! an entry whose name has the extension of an already-compressed format is
+//! stored outright. Any other entry has its first [`SAMPLE_LEN`] bytes
+//! compressed at the fastest level, and is stored if that saves less than
+//! 5%. The local header is held back until the method is chosen, so the
+//! output never has to be rewound.
+
+use crate::raw;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use std::io::Write;
+
+/// General purpose flags that are options of the compression method
+pub(crate) const METHOD_FLAGS: u16 = 0x0006;
+
+/// Bytes of an entry sampled before its method is chosen
+pub(crate) const SAMPLE_LEN: usize = 64 * 1024;
+
+/// Extensions of formats whose data is already compressed
+const COMPRESSED_EXTENSIONS: &[&str] = &[
+    "7z", "aac", "apk", "avif", "br", "bz2", "docx", "epub", "flac", "gif", "gz", "heic", "jar",
+    "jpeg", "jpg", "lz4", "m4a", "m4v", "mkv", "mov", "mp3", "mp4", "odp", "ods", "odt", "ogg",
+    "opus", "parquet", "png", "pptx", "rar", "tgz", "webm", "webp", "whl", "woff", "woff2", "xlsx",
+    "xz", "zip", "zst",
+];
+
+/// Whether `name` has the extension of an already-compressed format
+pub(crate) fn stored_by_name(name: &str) -> bool {
+    let file = name.rsplit('/').next().unwrap_or(name);
+    file.rsplit_once('.').is_some_and(|(stem, ext)| {
+        !stem.is_empty()
+            && COMPRESSED_EXTENSIONS
+                .iter()
+                .any(|known| ext.eq_ignore_ascii_case(known))
+    })
+}
+
+/// Whether compressing `sample` saves less than 5%
+pub(crate) fn incompressible(sample: &[u8]) -> bool {
+    if sample.is_empty() {
+        return false;
+    }
+    let mut encoder = DeflateEncoder::new(Vec::with_capacity(sample.len()), Compression::fast());
+    let compressed = encoder
+        .write_all(sample)
+        .and_then(|_| encoder.finish())
+        .map_or(usize::MAX, |out| out.len());
+    compressed as u64 * 100 >= sample.len() as u64 * 95
+}
+
+/// Change a local header written for a compressed entry to the Stored
+/// method, in the header itself and in an AES extra field, dropping the old
+/// method's option flags
+///
+/// The version needed is lowered to match unless `keep_version` is set
+/// (the caller chose it) or the header announces ZIP64.
+pub(crate) fn patch_header(header: &mut [u8], keep_version: bool) {
+    header[8..10].copy_from_slice(&0u16.to_le_bytes());
+    let flags = u16::from_le_bytes([header[6], header[7]]) & !METHOD_FLAGS;
+    header[6..8].copy_from_slice(&flags.to_le_bytes());
+    let version = u16::from_le_bytes([header[4], header[5]]);
+    if !keep_version && version < raw::VERSION_ZIP64 {
+        let version = raw::version_needed(0, flags & 0x01 != 0);
+        header[4..6].copy_from_slice(&version.to_le_bytes());
+    }
+
+    let name_len = u16::from_le_bytes([header[26], header[27]]) as usize;
+    let extra_len = u16::from_le_bytes([header[28], header[29]]) as usize;
+    let mut pos = 30 + name_len;
+    let end = (pos + extra_len).min(header.len());
+    while pos + 4 <= end {
+        let id = u16::from_le_bytes([header[pos], header[pos + 1]]);
+        let len = u16::from_le_bytes([header[pos + 2], header[pos + 3]]) as usize;
+        // AES extra: version, vendor, strength, then the actual method
+        if id == 0x9901 && len >= 7 && pos + 4 + len <= end {
+            header[pos + 9..pos + 11].copy_from_slice(&0u16.to_le_bytes());
+        }
+        pos += 4 + len;
+    }
+}
@@ -0,0 +1,71 @@
+//! DEFLATE of a single entry on several threads.
+//!
+//! As `pigz -i` does, the entry is cut into [`BLOCK_LEN`] blocks that are
+//! compressed independently. Every block but the last ends with a sync
+//! flush, an empty stored block that leaves the output byte-aligned, so the
+//! compressed blocks concatenate into one valid DEFLATE stream. Blocks can't
+//! refer back to data in earlier ones, which costs a fraction of a percent
+//! of compression.
+
+use flate2::{Compress, Compression, FlushCompress, Status};
+use std::io;
+
+/// Uncompressed bytes per block
+pub(crate) const BLOCK_LEN: usize = 1024 * 1024;
+
+/// Compress `data` as a run of DEFLATE blocks, ending the stream if `last`
+pub(crate) fn compress_block(data: &[u8], level: Compression, last: bool) -> io::Result<Vec<u8>> {
+    let mut compress = Compress::new(level, false);
+    let flush = if last {
+        FlushCompress::Finish
+    } else {
+        FlushCompress::Sync
+    };
+    let mut out = Vec::with_capacity(data.len() / 2 + 64);
+    loop {
+        let consumed = compress.total_in() as usize;
+        let status = compress
+            .compress_vec(&data[consumed..], &mut out, flush)
+            .map_err(io::Error::other)?;
+        let all_in = compress.total_in() as usize == data.len();
+        // A flush is complete once it leaves output space unused
+        let done = match status {
+            Status::StreamEnd => true,
+            _ => all_in && !last && out.len() < out.capacity(),
+        };
+        if done {
+            return Ok(out);
+        }
+        out.reserve(out.capacity().max(64));
+    }
+}
+
+/// Compress `blocks` on a thread each, in order; the last one ends the
+/// stream if `last`
+pub(crate) fn compress_blocks(
+    blocks: &[Vec<u8>],
+    level: Compression,
+    last: bool,
+) -> io::Result<Vec<Vec<u8>>> {
+    if let [block] = blocks {
+        return Ok(vec![compress_block(block, level, last)?]);
+    }
+    std::thread::scope(|scope| {
+        let workers: Vec<_> = blocks
+            .iter()
+            .enumerate()
+            .map(|(i, block)| {
+                let last = last && i + 1 == blocks.len();
+                scope.spawn(move || compress_block(block, level, last))
+            })
+            .collect();
+        workers
+            .into_iter()
+            .map(|worker| {
+                worker
+                    .join()
+                    .unwrap_or_else(|_| Err(io::Error::other("compression thread panicked")))
+            })
+            .collect()
+    })
+}
@@ -0,0 +1,231 @@
+//! Blocking wrappers around the async writers.
+//!
+//! `BlockingZipWriter` owns a Tokio runtime and drives an
+//! [`AsyncStreamingZipWriter`] on it, so synchronous code can stream ZIPs to
+//! any `AsyncWrite` target — including `S3ZipWriter` and `GCSZipWriter`, see
+//! `BlockingS3ZipWriter` and `BlockingGCSZipWriter` — without being
+//! restructured around async. The runtime has one worker thread, so
+//! background tasks such as part uploads keep running between calls.
+//!
+//! Do not use these wrappers from inside an async context: every call blocks
+//! the current thread on the internal runtime and will panic if it is already
+//! running inside a Tokio runtime.
+//!
+//! ```no_run
+//! use s_zip::blocking::BlockingZipWriter;
+//!
+//! let mut writer = BlockingZipWriter::new("output.zip")?;
+//! writer.add_entry("hello.txt", b"Hello from sync code")?;
+//! writer.finish()?;
+//! # Ok::<(), s_zip::SZipError>(())
+//! ```
+
+use crate::async_writer::AsyncStreamingZipWriter;
+use crate::error::Result;
+use std::future::Future;
+use std::path::Path;
+use tokio::io::AsyncWrite;
+use tokio::runtime::Runtime;
+
+#[cfg(feature = "encryption")]
+use crate::encryption::AesStrength;
+
+/// Synchronous ZIP writer backed by [`AsyncStreamingZipWriter`] and an internal runtime.
+pub struct BlockingZipWriter<W: AsyncWrite + Unpin> {
+    // Declared before `runtime` so the writer (and any tasks it owns) is
+    // dropped while the runtime is still alive.
+    inner: AsyncStreamingZipWriter<W>,
+    runtime: Runtime,
+}
+
+/// Blocking writer streaming to Amazon S3 with a multipart upload
+#[cfg(feature = "cloud-s3")]
+pub type BlockingS3ZipWriter = BlockingZipWriter<crate::cloud::S3ZipWriter>;
+
+/// Blocking writer streaming to Google Cloud Storage with a resumable upload
+#[cfg(feature = "cloud-gcs")]
+pub type BlockingGCSZipWriter = BlockingZipWriter<crate::cloud::GCSZipWriter>;
+
+// A worker thread runs spawned tasks while the caller is outside `block_on`
+fn build_runtime() -> Result<Runtime> {
+    Ok(tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .enable_all()
+        .build()?)
+}
+
+impl BlockingZipWriter<tokio::fs::File> {
+    /// Create a blocking ZIP writer for a file path with default compression (DEFLATE level 6)
+    pub fn new<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let runtime = build_runtime()?;
+        let inner = runtime.block_on(AsyncStreamingZipWriter::new(path))?;
+        Ok(Self { inner, runtime })
+    }
+}
+
+#[cfg(feature = "cloud-s3")]
+impl BlockingZipWriter<crate::cloud::S3ZipWriter> {
+    /// Create a blocking writer streaming to `key` in `bucket`
+    ///
+    /// # Example
+    /// ```no_run
+    /// # fn example(client: aws_sdk_s3::Client) -> Result<(), s_zip::SZipError> {
+    /// use s_zip::blocking::BlockingS3ZipWriter;
+    ///
+    /// let mut writer = BlockingS3ZipWriter::new_s3(client, "my-bucket", "exports/archive.zip")?;
+    /// writer.add_entry("report.csv", b"id,name\n1,Alice\n")?;
+    /// writer.finish()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new_s3(
+        client: aws_sdk_s3::Client,
+        bucket: impl Into<String>,
+        key: impl Into<String>,
+    ) -> Result<Self> {
+        Self::from_async(|| crate::cloud::S3ZipWriter::new(client, bucket, key))
+    }
+
+    /// Create a blocking writer from a configured [`S3ZipWriterBuilder`](crate::cloud::S3ZipWriterBuilder)
+    pub fn from_s3_builder(builder: crate::cloud::S3ZipWriterBuilder) -> Result<Self> {
+        Self::from_async(|| builder.build())
+    }
+}
+
+#[cfg(feature = "cloud-gcs")]
+impl BlockingZipWriter<crate::cloud::GCSZipWriter> {
+    /// Create a blocking writer streaming to `object` in `bucket`
+    pub fn new_gcs(
+        client: google_cloud_storage::client::Client,
+        bucket: impl Into<String>,
+        object: impl Into<String>,
+    ) -> Result<Self> {
+        Self::from_async(|| crate::cloud::GCSZipWriter::new(client, bucket, object))
+    }
+
+    /// Create a blocking writer from a configured
+    /// [`GCSZipWriterBuilder`](crate::cloud::gcs::GCSZipWriterBuilder)
+    pub fn from_gcs_builder(builder: crate::cloud::gcs::GCSZipWriterBuilder) -> Result<Self> {
+        Self::from_async(|| builder.build())
+    }
+}
+
+impl<W: AsyncWrite + Unpin> BlockingZipWriter<W> {
+    /// Create a blocking ZIP writer whose output is constructed asynchronously.
+    ///
+    /// `init` runs on the internal runtime, so it may build clients and
+    /// writers that need a Tokio context (cloud SDKs, background upload tasks).
+    ///
+    /// # Example
+    /// ```no_run
+    /// # #[cfg(feature = "cloud-s3")]
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// use s_zip::blocking::BlockingZipWriter;
+    /// use s_zip::cloud::S3ZipWriter;
+    ///
+    /// let mut writer = BlockingZipWriter::from_async(|| async {
+    ///     let config = aws_config::load_from_env().await;
+    ///     let client = aws_sdk_s3::Client::new(&config);
+    ///     S3ZipWriter::new(client, "my-bucket", "exports/archive.zip").await
+    /// })?;
+    ///
+    /// writer.add_entry("report.csv", b"id,name\n1,Alice\n")?;
+    /// writer.finish()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_async<F, Fut>(init: F) -> Result<Self>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<W>>,
+    {
+        let runtime = build_runtime()?;
+        let output = runtime.block_on(init())?;
+        Ok(Self {
+            inner: AsyncStreamingZipWriter::from_writer(output),
+            runtime,
+        })
+    }
+
+    /// Get mutable access to the wrapped async writer for configuration
+    /// (compression method, encryption strength, ...).
+    pub fn get_mut(&mut self) -> &mut AsyncStreamingZipWriter<W> {
+        &mut self.inner
+    }
+
+    /// Set password for AES encryption of subsequent entries (requires encryption feature)
+    #[cfg(feature = "encryption")]
+    pub fn set_password(&mut self, password: impl Into<String>) -> &mut Self {
+        self.inner.set_password(password);
+        self
+    }
+
+    /// Set AES encryption strength (default: AES-256)
+    #[cfg(feature = "encryption")]
+    pub fn set_encryption_strength(&mut self, strength: AesStrength) -> &mut Self {
+        self.inner.set_encryption_strength(strength);
+        self
+    }
+
+    /// Clear password (disable encryption for subsequent entries)
+    #[cfg(feature = "encryption")]
+    pub fn clear_password(&mut self) -> &mut Self {
+        self.inner.clear_password();
+        self
+    }
+
+    /// Start a new entry (file) in the ZIP
+    pub fn start_entry(&mut self, name: &str) -> Result<()> {
+        self.runtime.block_on(self.inner.start_entry(name))
+    }
+
+    /// Start a new entry with a size hint for buffer sizing
+    pub fn start_entry_with_hint(&mut self, name: &str, size_hint: Option<u64>) -> Result<()> {
+        self.runtime
+            .block_on(self.inner.start_entry_with_hint(name, size_hint))
+    }
+
+    /// Write uncompressed data to the current entry
+    pub fn write_data(&mut self, data: &[u8]) -> Result<()> {
+        self.runtime.block_on(self.inner.write_data(data))
+    }
+
+    /// Add a complete entry in one call
+    pub fn add_entry(&mut self, name: &str, data: &[u8]) -> Result<()> {
+        self.runtime.block_on(self.inner.add_entry(name, data))
+    }
+
+    /// Number of entries written so far
+    pub fn entry_count(&self) -> usize {
+        self.inner.entry_count()
+    }
+
+    /// Total uncompressed bytes written across all completed entries
+    pub fn bytes_written(&self) -> u64 {
+        self.inner.bytes_written()
+    }
+
+    /// Finish the ZIP file (write central directory) and return the underlying writer
+    pub fn finish(self) -> Result<W> {
+        let Self { inner, runtime } = self;
+        runtime.block_on(inner.finish())
+    }
+
+    /// Finish the ZIP file and return the underlying writer together with a
+    /// [`crate::ZipStats`] summary
+    pub fn finish_with_stats(self) -> Result<(W, crate::ZipStats)> {
+        let Self { inner, runtime } = self;
+        runtime.block_on(inner.finish_with_stats())
+    }
+}
+
+impl<W: AsyncWrite + Unpin> std::io::Write for BlockingZipWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.write_data(buf).map_err(std::io::Error::other)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
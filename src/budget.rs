@@ -0,0 +1,204 @@
+//! Process-wide memory budget shared by archive jobs.
+//!
+//! Each writer keeps up to several MiB of compressed data buffered per entry,
+//! and every parallel compression task or async entry stream holds its own
+//! buffers. A single job stays small, but many concurrent jobs in one process
+//! add up. A [`MemoryBudget`] caps their sum: jobs registered with the same
+//! budget reserve their buffers from it and scale back when it runs low.
+//!
+//! - Writers (`with_memory_budget`) flush their compressed buffer early
+//!   instead of growing it past what the budget allows.
+//! - `ParallelConfig::with_memory_budget` admits a compression task only
+//!   once its working memory is available, waiting otherwise.
+//! - Async readers (`with_memory_budget`) fall back to small read-ahead
+//!   buffers for entry streams when the budget is exhausted.
+//!
+//! Small fixed allocations (headers, encoder state, the central directory)
+//! are not counted, so set the cap with some headroom.
+//!
+//! ```
+//! use s_zip::budget::MemoryBudget;
+//! use s_zip::StreamingZipWriter;
+//! use std::io::Cursor;
+//!
+//! // One budget for every export job in the process
+//! let budget = MemoryBudget::new(64 * 1024 * 1024);
+//!
+//! let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new()))?
+//!     .with_memory_budget(&budget);
+//! writer.add_entry("report.csv", b"a,b,c\n")?;
+//! writer.finish()?;
+//! assert_eq!(budget.used(), 0);
+//! # Ok::<(), s_zip::SZipError>(())
+//! ```
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+/// Shared cap on the buffer memory of the jobs registered with it
+///
+/// Cloning is cheap and yields a handle to the same budget.
+#[derive(Clone)]
+pub struct MemoryBudget {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    limit: usize,
+    used: Mutex<usize>,
+    #[cfg(feature = "async")]
+    released: tokio::sync::Notify,
+}
+
+impl MemoryBudget {
+    /// Budget of `limit` bytes
+    pub fn new(limit: usize) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                limit,
+                used: Mutex::new(0),
+                #[cfg(feature = "async")]
+                released: tokio::sync::Notify::new(),
+            }),
+        }
+    }
+
+    /// Configured cap in bytes
+    pub fn limit(&self) -> usize {
+        self.inner.limit
+    }
+
+    /// Bytes currently reserved
+    pub fn used(&self) -> usize {
+        *self.inner.used.lock().unwrap()
+    }
+
+    /// Bytes still available
+    pub fn available(&self) -> usize {
+        self.limit().saturating_sub(self.used())
+    }
+
+    /// Reserve `bytes` if that many are available
+    pub fn try_reserve(&self, bytes: usize) -> Option<Reservation> {
+        let mut reservation = self.empty();
+        reservation.try_resize(bytes).then_some(reservation)
+    }
+
+    /// Reserve `bytes`, waiting until enough are released
+    ///
+    /// A request larger than the whole budget is clamped to the limit, so it
+    /// waits for the budget to drain rather than forever.
+    #[cfg(feature = "async")]
+    pub async fn reserve(&self, bytes: usize) -> Reservation {
+        let bytes = bytes.min(self.limit());
+        loop {
+            let released = self.inner.released.notified();
+            tokio::pin!(released);
+            // Register before checking, so a release in between is not missed
+            released.as_mut().enable();
+            if let Some(reservation) = self.try_reserve(bytes) {
+                return reservation;
+            }
+            released.await;
+        }
+    }
+
+    /// Reservation of zero bytes, to be grown with [`Reservation::try_resize`]
+    pub(crate) fn empty(&self) -> Reservation {
+        Reservation {
+            budget: self.clone(),
+            size: 0,
+        }
+    }
+}
+
+impl fmt::Debug for MemoryBudget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MemoryBudget")
+            .field("limit", &self.limit())
+            .field("used", &self.used())
+            .finish()
+    }
+}
+
+/// Bytes held from a [`MemoryBudget`], returned when dropped
+#[derive(Debug)]
+pub struct Reservation {
+    budget: MemoryBudget,
+    size: usize,
+}
+
+impl Reservation {
+    /// Bytes held
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Grow or shrink to `bytes`; returns `false`, keeping the current size,
+    /// if growing would exceed the budget
+    pub fn try_resize(&mut self, bytes: usize) -> bool {
+        let inner = &self.budget.inner;
+        let mut used = inner.used.lock().unwrap();
+        if bytes > self.size {
+            let extra = bytes - self.size;
+            if *used + extra > inner.limit {
+                return false;
+            }
+            *used += extra;
+        } else {
+            *used -= self.size - bytes;
+            #[cfg(feature = "async")]
+            if bytes < self.size {
+                inner.released.notify_waiters();
+            }
+        }
+        self.size = bytes;
+        true
+    }
+}
+
+impl Drop for Reservation {
+    fn drop(&mut self) {
+        self.try_resize(0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reservations_share_the_limit() {
+        let budget = MemoryBudget::new(100);
+        let mut a = budget.try_reserve(60).unwrap();
+        assert!(budget.try_reserve(50).is_none());
+        let b = budget.try_reserve(40).unwrap();
+        assert_eq!(budget.available(), 0);
+
+        assert!(!a.try_resize(70));
+        assert_eq!(a.size(), 60);
+        assert!(a.try_resize(10));
+        assert_eq!(budget.used(), 50);
+        drop(b);
+        drop(a);
+        assert_eq!(budget.used(), 0);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_reserve_waits_for_release() {
+        let budget = MemoryBudget::new(100);
+        let held = budget.try_reserve(80).unwrap();
+        let waiter = tokio::spawn({
+            let budget = budget.clone();
+            async move { budget.reserve(50).await.size() }
+        });
+        tokio::task::yield_now().await;
+        assert!(!waiter.is_finished());
+        drop(held);
+        assert_eq!(waiter.await.unwrap(), 50);
+
+        // Oversized requests take the whole budget
+        assert_eq!(budget.reserve(500).await.size(), 100);
+    }
+}
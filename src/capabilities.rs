@@ -0,0 +1,113 @@
+//! What this build of s-zip can read and write.
+//!
+//! Compression methods and encryption depend on Cargo features, so an
+//! application linking s-zip can't know statically whether an archive it
+//! receives is readable.  [`capabilities()`] answers that at runtime, and
+//! `can_read_entry()` on the readers checks a specific entry, so callers can
+//! fail fast or pick a fallback before starting a read.
+//!
+//! ```
+//! let caps = s_zip::capabilities();
+//! assert!(caps.read_methods.contains(&8)); // Deflate is always available
+//! if !caps.aes_encryption {
+//!     println!("encrypted archives need the `encryption` feature");
+//! }
+//! ```
+
+use crate::format::ZipEntry;
+use crate::writer::CompressionMethod;
+
+/// Compression methods and encryption schemes available in this build
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Capabilities {
+    /// ZIP method ids `StreamingZipReader` can decompress (0 = Stored, 8 = Deflate, 93 = Zstd)
+    pub read_methods: Vec<u16>,
+    /// ZIP method ids `GenericAsyncZipReader` can decompress; empty without the `async` feature
+    pub async_read_methods: Vec<u16>,
+    /// Compression methods the writers can produce
+    pub write_methods: Vec<CompressionMethod>,
+    /// WinZip AES (AES-128/192/256) for reading and writing (`encryption` feature)
+    pub aes_encryption: bool,
+    /// Traditional PKWARE (ZipCrypto) encryption; not supported
+    pub zipcrypto: bool,
+    /// Async readers and writers (`async` feature)
+    pub async_io: bool,
+}
+
+/// Report the compression methods and encryption schemes enabled in this build
+pub fn capabilities() -> Capabilities {
+    Capabilities {
+        read_methods: read_methods(),
+        async_read_methods: async_read_methods(),
+        write_methods: vec![
+            CompressionMethod::Stored,
+            CompressionMethod::Deflate,
+            #[cfg(feature = "zstd-support")]
+            CompressionMethod::Zstd,
+            #[cfg(feature = "bzip2")]
+            CompressionMethod::Bzip2,
+            #[cfg(feature = "lzma")]
+            CompressionMethod::Lzma,
+            #[cfg(feature = "lzma")]
+            CompressionMethod::Xz,
+        ],
+        aes_encryption: cfg!(feature = "encryption"),
+        zipcrypto: false,
+        async_io: cfg!(feature = "async"),
+    }
+}
+
+/// Method ids the sync reader decompresses
+pub(crate) fn read_methods() -> Vec<u16> {
+    let mut methods = vec![0, 8];
+    if cfg!(feature = "zstd-support") {
+        methods.push(93);
+    }
+    if cfg!(feature = "deflate64") {
+        methods.push(9);
+    }
+    if cfg!(feature = "bzip2") {
+        methods.push(12);
+    }
+    if cfg!(feature = "lzma") {
+        methods.extend([14, 95]);
+    }
+    methods
+}
+
+/// Method ids the async reader decompresses
+pub(crate) fn async_read_methods() -> Vec<u16> {
+    let mut methods = Vec::new();
+    if cfg!(feature = "async") {
+        methods.extend([0, 8]);
+        if cfg!(feature = "async-zstd") {
+            methods.push(93);
+        }
+        if cfg!(feature = "deflate64") {
+            methods.push(9);
+        }
+        if cfg!(feature = "bzip2") {
+            methods.push(12);
+        }
+        if cfg!(feature = "lzma") {
+            methods.extend([14, 95]);
+        }
+    }
+    methods
+}
+
+/// Whether a reader supporting `methods` can read `entry`.
+///
+/// Encrypted entries need the `encryption` feature, a WinZip AES extra field
+/// and a password; their data must be Stored or Deflate, the only methods the
+/// decrypting stream supports.
+pub(crate) fn can_read(entry: &ZipEntry, methods: &[u16], has_password: bool) -> bool {
+    if entry.is_encrypted {
+        cfg!(feature = "encryption")
+            && entry.aes_strength.is_some()
+            && has_password
+            && matches!(entry.compression_method, 0 | 8)
+    } else {
+        methods.contains(&entry.compression_method)
+    }
+}
@@ -1,7 +1,8 @@
 //! Cloud storage adapters for streaming ZIP files directly to S3, GCS, etc.
 //!
-//! This module provides adapters that implement `AsyncWrite + AsyncSeek + Unpin`
-//! to enable `AsyncStreamingZipWriter` to stream directly to cloud storage without
+//! This module provides adapters that implement `AsyncWrite + Unpin` (plus a
+//! position-only `AsyncSeek`, kept for backward compatibility) to enable
+//! `AsyncStreamingZipWriter` to stream directly to cloud storage without
 //! loading entire ZIPs into memory.
 //!
 //! ## Available Adapters
@@ -33,7 +33,7 @@
 //! ```
 
 use crate::error::{Result, SZipError};
-use aws_sdk_s3::primitives::ByteStream;
+use aws_sdk_s3::primitives::{ByteStream, DateTime};
 use aws_sdk_s3::types::{CompletedMultipartUpload, CompletedPart};
 use aws_sdk_s3::Client;
 use std::future::Future;
@@ -41,7 +41,7 @@ use std::io;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::io::{AsyncSeek, AsyncWrite};
 use tokio::sync::mpsc;
 
@@ -133,6 +133,7 @@ pub struct S3ZipWriterBuilder {
     region: Option<String>,
     force_path_style: bool,
     max_concurrent_uploads: usize,
+    deadline: Option<Instant>,
 }
 
 impl S3ZipWriter {
@@ -201,6 +202,7 @@ impl S3ZipWriter {
             region: None,
             force_path_style: false,
             max_concurrent_uploads: 4, // Default: 4 concurrent uploads
+            deadline: None,
         }
     }
 }
@@ -325,6 +327,16 @@ impl S3ZipWriterBuilder {
         self
     }
 
+    /// Abort the upload once `deadline` passes.
+    ///
+    /// In-flight part uploads are cancelled, the multipart upload is aborted so
+    /// no orphaned parts are left behind, and shutting the writer down fails
+    /// with an error that converts to `SZipError::DeadlineExceeded`.
+    pub fn deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
     /// Build the S3 writer and start the background upload task.
     ///
     /// If no client was provided, one will be created using environment credentials
@@ -360,12 +372,13 @@ impl S3ZipWriterBuilder {
 
         // Spawn background task for uploading parts with concurrent support
         let max_concurrent = self.max_concurrent_uploads;
-        let upload_task = tokio::spawn(upload_worker_concurrent(
+        let upload_task = tokio::spawn(upload_worker_with_deadline(
             client,
             self.bucket,
             self.key,
             rx,
             max_concurrent,
+            self.deadline,
         ));
 
         Ok(S3ZipWriter {
@@ -450,6 +463,9 @@ impl AsyncWrite for S3ZipWriter {
         if let Some(task) = self.upload_task.as_mut() {
             match Pin::new(task).poll(cx) {
                 Poll::Ready(Ok(Ok(()))) => Poll::Ready(Ok(())),
+                Poll::Ready(Ok(Err(SZipError::DeadlineExceeded))) => {
+                    Poll::Ready(Err(crate::deadline::io_error()))
+                }
                 Poll::Ready(Ok(Err(e))) => {
                     Poll::Ready(Err(io::Error::other(format!("S3 upload failed: {}", e))))
                 }
@@ -655,23 +671,63 @@ async fn upload_worker(
     Ok(())
 }
 
+/// Run `upload_worker_concurrent`, aborting the multipart upload if the
+/// deadline passes first
+async fn upload_worker_with_deadline(
+    client: Client,
+    bucket: String,
+    key: String,
+    rx: mpsc::UnboundedReceiver<UploadCommand>,
+    max_concurrent: usize,
+    deadline: Option<Instant>,
+) -> Result<()> {
+    let client = Arc::new(client);
+    let bucket = Arc::new(bucket);
+    let key = Arc::new(key);
+    let mut upload_id: Option<String> = None;
+
+    let worker = upload_worker_concurrent(
+        client.clone(),
+        bucket.clone(),
+        key.clone(),
+        rx,
+        max_concurrent,
+        &mut upload_id,
+    );
+    let result = match deadline {
+        Some(deadline) => tokio::time::timeout_at(deadline.into(), worker)
+            .await
+            .unwrap_or(Err(SZipError::DeadlineExceeded)),
+        None => worker.await,
+    };
+
+    if let (Err(SZipError::DeadlineExceeded), Some(id)) = (&result, upload_id) {
+        // Best effort: the deadline error is what the caller needs to see
+        let _ = client
+            .abort_multipart_upload()
+            .bucket(bucket.as_ref())
+            .key(key.as_ref())
+            .upload_id(id)
+            .send()
+            .await;
+    }
+    result
+}
+
 /// Concurrent upload worker with retry logic and parallel uploads
 ///
 /// This version uploads multiple parts in parallel for 3-5x faster S3 uploads.
 /// Includes automatic retry with exponential backoff for transient failures.
 async fn upload_worker_concurrent(
-    client: Client,
-    bucket: String,
-    key: String,
+    client: Arc<Client>,
+    bucket: Arc<String>,
+    key: Arc<String>,
     mut rx: mpsc::UnboundedReceiver<UploadCommand>,
     max_concurrent: usize,
+    upload_id: &mut Option<String>,
 ) -> Result<()> {
     use futures_util::stream::{FuturesUnordered, StreamExt};
 
-    let client = Arc::new(client);
-    let bucket = Arc::new(bucket);
-    let key = Arc::new(key);
-    let mut upload_id: Option<String> = None;
     let mut completed_parts: Vec<(usize, CompletedPart)> = Vec::new();
     let mut upload_futures = FuturesUnordered::new();
     let mut pending_parts: Vec<(usize, Vec<u8>)> = Vec::new();
@@ -694,7 +750,7 @@ async fn upload_worker_concurrent(
                             )))
                         })?;
 
-                    upload_id = Some(
+                    *upload_id = Some(
                         response
                             .upload_id()
                             .ok_or_else(|| {
@@ -766,7 +822,7 @@ async fn upload_worker_concurrent(
                                     )))
                                 })?;
 
-                            upload_id = Some(
+                            *upload_id = Some(
                                 response
                                     .upload_id()
                                     .ok_or_else(|| {
@@ -803,7 +859,7 @@ async fn upload_worker_concurrent(
                 let parts: Vec<_> = completed_parts.into_iter().map(|(_, p)| p).collect();
 
                 // Complete multipart upload
-                if let Some(id) = upload_id {
+                if let Some(id) = upload_id.take() {
                     client
                         .complete_multipart_upload()
                         .bucket(bucket.as_ref())
@@ -934,12 +990,23 @@ use tokio::io::AsyncRead;
 /// # Ok(())
 /// # }
 /// ```
+///
+/// ## Consistency
+///
+/// The ETag (or, failing that, the last-modified time) seen when the reader
+/// is built is sent as a precondition with every range request. If the
+/// object is overwritten mid-read, reads fail with an error that converts to
+/// `SZipError::SourceChanged` instead of mixing bytes from two versions.
 pub struct S3ZipReader {
     client: Client,
     bucket: String,
     key: String,
+    /// Object offset of position 0 (non-zero for `range_reader` views)
+    start: u64,
     position: u64,
     size: u64,
+    etag: Option<String>,
+    last_modified: Option<DateTime>,
     #[allow(clippy::type_complexity)]
     read_future: Option<Pin<Box<dyn Future<Output = io::Result<Vec<u8>>> + Send>>>,
 }
@@ -1039,10 +1106,51 @@ impl S3ZipReader {
         }
     }
 
-    /// Get the total size of the S3 object.
+    /// Get the total size of the S3 object, or of the range for readers
+    /// created with [`range_reader`](Self::range_reader).
     pub fn size(&self) -> u64 {
         self.size
     }
+
+    /// ETag of the object version being read, if S3 reported one
+    pub fn etag(&self) -> Option<&str> {
+        self.etag.as_deref()
+    }
+
+    /// Create an independent reader over `len` bytes starting at `start`.
+    ///
+    /// The new reader shares the S3 client but has its own position and
+    /// in-flight request, so several range readers can fetch different parts
+    /// of the object at the same time.  Offsets are relative to this reader's
+    /// range, and the range is clamped to its end.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use s_zip::cloud::S3ZipReader;
+    /// # use tokio::io::AsyncReadExt;
+    /// # async fn example(reader: S3ZipReader) -> std::io::Result<()> {
+    /// let mut head = reader.range_reader(0, 1024);
+    /// let mut tail = reader.range_reader(reader.size() - 1024, 1024);
+    /// let (mut a, mut b) = (Vec::new(), Vec::new());
+    /// tokio::try_join!(head.read_to_end(&mut a), tail.read_to_end(&mut b))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn range_reader(&self, start: u64, len: u64) -> S3ZipReader {
+        let start = start.min(self.size);
+        S3ZipReader {
+            client: self.client.clone(),
+            bucket: self.bucket.clone(),
+            key: self.key.clone(),
+            start: self.start + start,
+            position: 0,
+            size: len.min(self.size - start),
+            etag: self.etag.clone(),
+            last_modified: self.last_modified,
+            read_future: None,
+        }
+    }
 }
 
 impl S3ZipReaderBuilder {
@@ -1134,13 +1242,26 @@ impl S3ZipReaderBuilder {
             client,
             bucket: self.bucket,
             key: self.key,
+            start: 0,
             position: 0,
             size,
+            etag: head.e_tag().map(str::to_string),
+            last_modified: head.last_modified().copied(),
             read_future: None,
         })
     }
 }
 
+/// Cloning creates an independent handle on the same object (or range): it
+/// shares the client but starts at position 0 with no in-flight request, so
+/// clones can read different ranges concurrently (see
+/// `extract_all` and `shared_entry_reader`).
+impl Clone for S3ZipReader {
+    fn clone(&self) -> Self {
+        self.range_reader(0, self.size)
+    }
+}
+
 impl AsyncRead for S3ZipReader {
     fn poll_read(
         mut self: Pin<&mut Self>,
@@ -1167,28 +1288,39 @@ impl AsyncRead for S3ZipReader {
 
         // Calculate byte range to read
         let start = self.position;
-        let end = (start + buf.remaining() as u64 - 1).min(self.size - 1);
-
-        if start >= self.size {
-            return Poll::Ready(Ok(())); // EOF
+        if start >= self.size || buf.remaining() == 0 {
+            return Poll::Ready(Ok(())); // EOF (or empty range)
         }
+        let end = (start + buf.remaining() as u64 - 1).min(self.size - 1);
 
-        let range = format!("bytes={}-{}", start, end);
+        let range = format!("bytes={}-{}", self.start + start, self.start + end);
 
         // Create future for reading from S3
         let client = self.client.clone();
         let bucket = self.bucket.clone();
         let key = self.key.clone();
+        let etag = self.etag.clone();
+        let last_modified = self.last_modified;
 
         let fut = Box::pin(async move {
-            let response = client
-                .get_object()
-                .bucket(&bucket)
-                .key(&key)
-                .range(range)
-                .send()
-                .await
-                .map_err(|e| io::Error::other(format!("S3 GetObject failed: {}", e)))?;
+            let mut request = client.get_object().bucket(&bucket).key(&key).range(range);
+            // Only serve the range from the version the reader was opened on
+            if let Some(etag) = etag {
+                request = request.if_match(etag);
+            } else if let Some(last_modified) = last_modified {
+                request = request.if_unmodified_since(last_modified);
+            }
+            let response = request.send().await.map_err(|e| {
+                let status = e.raw_response().map(|r| r.status().as_u16());
+                if status == Some(412) {
+                    crate::error::source_changed(format!(
+                        "s3://{}/{} was modified after the reader was opened",
+                        bucket, key
+                    ))
+                } else {
+                    io::Error::other(format!("S3 GetObject failed: {}", e))
+                }
+            })?;
 
             let bytes = response
                 .body
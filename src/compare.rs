@@ -0,0 +1,274 @@
+//! Semantic archive comparison.
+//!
+//! Two archives built from the same inputs rarely match byte for byte:
+//! timestamps, compression levels and entry order all vary between tools
+//! and runs. [`are_equivalent`] compares what a user would extract instead:
+//! the set of entry names, their decompressed contents and, unless told to
+//! ignore them, timestamps, entry order, compression methods and
+//! attributes. [`differences`] lists what differs, for test failure output.
+//!
+//! ```
+//! use s_zip::{are_equivalent, EquivalenceOptions, StreamingZipReader, StreamingZipWriter};
+//! use s_zip::CompressionMethod;
+//! use std::io::Cursor;
+//!
+//! let build = |method| -> s_zip::Result<Vec<u8>> {
+//!     let mut writer = StreamingZipWriter::from_writer_with_method(
+//!         Cursor::new(Vec::new()),
+//!         method,
+//!         6,
+//!     )?;
+//!     writer.add_entry("report.csv", b"a,b,c\n1,2,3\n")?;
+//!     Ok(writer.finish()?.into_inner())
+//! };
+//! let mut stored = StreamingZipReader::from_vec(build(CompressionMethod::Stored)?)?;
+//! let mut deflated = StreamingZipReader::from_vec(build(CompressionMethod::Deflate)?)?;
+//!
+//! let same_content = EquivalenceOptions {
+//!     ignore_timestamps: true,
+//!     ..Default::default()
+//! };
+//! assert!(!are_equivalent(&mut stored, &mut deflated, same_content)?);
+//!
+//! let options = EquivalenceOptions {
+//!     ignore_compression: true,
+//!     ..same_content
+//! };
+//! assert!(are_equivalent(&mut stored, &mut deflated, options)?);
+//! # Ok::<(), s_zip::SZipError>(())
+//! ```
+
+use crate::dostime::DosDateTime;
+use crate::error::Result;
+use crate::format::ZipEntry;
+use crate::reader::StreamingZipReader;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io::{Read, Seek};
+
+/// What [`are_equivalent`] may disregard. The default compares everything.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EquivalenceOptions {
+    /// Ignore last-modified timestamps
+    pub ignore_timestamps: bool,
+    /// Ignore the order of entries in the central directory
+    pub ignore_order: bool,
+    /// Ignore the compression method; contents are still compared
+    pub ignore_compression: bool,
+    /// Ignore external attributes (permissions, DOS flags)
+    pub ignore_attributes: bool,
+}
+
+/// A way in which two archives differ
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Difference {
+    /// The entry is only in the first archive
+    OnlyInFirst(String),
+    /// The entry is only in the second archive
+    OnlyInSecond(String),
+    /// The decompressed contents differ
+    Content(String),
+    /// The last-modified timestamps differ
+    Timestamp {
+        name: String,
+        first: DosDateTime,
+        second: DosDateTime,
+    },
+    /// The compression methods differ
+    CompressionMethod {
+        name: String,
+        first: u16,
+        second: u16,
+    },
+    /// The external attributes differ
+    Attributes {
+        name: String,
+        first: u32,
+        second: u32,
+    },
+    /// The shared entries appear in a different order
+    Order,
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Difference::OnlyInFirst(name) => write!(f, "{}: only in first archive", name),
+            Difference::OnlyInSecond(name) => write!(f, "{}: only in second archive", name),
+            Difference::Content(name) => write!(f, "{}: contents differ", name),
+            Difference::Timestamp {
+                name,
+                first,
+                second,
+            } => write!(
+                f,
+                "{}: modified {} vs {}",
+                name,
+                format_time(first),
+                format_time(second)
+            ),
+            Difference::CompressionMethod {
+                name,
+                first,
+                second,
+            } => write!(f, "{}: compression method {} vs {}", name, first, second),
+            Difference::Attributes {
+                name,
+                first,
+                second,
+            } => write!(
+                f,
+                "{}: attributes {:#010x} vs {:#010x}",
+                name, first, second
+            ),
+            Difference::Order => write!(f, "entries are in a different order"),
+        }
+    }
+}
+
+fn format_time(t: &DosDateTime) -> String {
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        t.year(),
+        t.month(),
+        t.day(),
+        t.hour(),
+        t.minute(),
+        t.second()
+    )
+}
+
+/// Whether `first` and `second` hold the same entries with the same
+/// contents, subject to `options`
+pub fn are_equivalent<A, B>(
+    first: &mut StreamingZipReader<A>,
+    second: &mut StreamingZipReader<B>,
+    options: EquivalenceOptions,
+) -> Result<bool>
+where
+    A: Read + Seek,
+    B: Read + Seek,
+{
+    Ok(differences(first, second, options)?.is_empty())
+}
+
+/// Every difference between `first` and `second` that `options` does not
+/// ignore, in the order of the first archive's entries
+///
+/// Entry data is decompressed and compared byte for byte, so differing
+/// compression or encryption does not hide or fake a content change.
+pub fn differences<A, B>(
+    first: &mut StreamingZipReader<A>,
+    second: &mut StreamingZipReader<B>,
+    options: EquivalenceOptions,
+) -> Result<Vec<Difference>>
+where
+    A: Read + Seek,
+    B: Read + Seek,
+{
+    let first_entries = first.entries().to_vec();
+    let second_entries = second.entries().to_vec();
+    let by_name: HashMap<&str, &ZipEntry> = second_entries
+        .iter()
+        .map(|e| (e.name.as_str(), e))
+        .collect();
+    let first_names: HashSet<&str> = first_entries.iter().map(|e| e.name.as_str()).collect();
+
+    let mut diffs = Vec::new();
+    for a in &first_entries {
+        let Some(b) = by_name.get(a.name.as_str()) else {
+            diffs.push(Difference::OnlyInFirst(a.name.clone()));
+            continue;
+        };
+        if !same_content(first, a, second, b)? {
+            diffs.push(Difference::Content(a.name.clone()));
+        }
+        if !options.ignore_timestamps && a.last_modified != b.last_modified {
+            diffs.push(Difference::Timestamp {
+                name: a.name.clone(),
+                first: a.last_modified,
+                second: b.last_modified,
+            });
+        }
+        if !options.ignore_compression && a.compression_method != b.compression_method {
+            diffs.push(Difference::CompressionMethod {
+                name: a.name.clone(),
+                first: a.compression_method,
+                second: b.compression_method,
+            });
+        }
+        if !options.ignore_attributes && a.external_attrs != b.external_attrs {
+            diffs.push(Difference::Attributes {
+                name: a.name.clone(),
+                first: a.external_attrs,
+                second: b.external_attrs,
+            });
+        }
+    }
+    for b in &second_entries {
+        if !first_names.contains(b.name.as_str()) {
+            diffs.push(Difference::OnlyInSecond(b.name.clone()));
+        }
+    }
+
+    if !options.ignore_order {
+        let shared_first = first_entries
+            .iter()
+            .filter(|e| by_name.contains_key(e.name.as_str()));
+        let shared_second = second_entries
+            .iter()
+            .filter(|e| first_names.contains(e.name.as_str()));
+        if !shared_first
+            .map(|e| &e.name)
+            .eq(shared_second.map(|e| &e.name))
+        {
+            diffs.push(Difference::Order);
+        }
+    }
+    Ok(diffs)
+}
+
+/// Compare the decompressed data of two entries
+fn same_content<A, B>(
+    first: &mut StreamingZipReader<A>,
+    a: &ZipEntry,
+    second: &mut StreamingZipReader<B>,
+    b: &ZipEntry,
+) -> Result<bool>
+where
+    A: Read + Seek,
+    B: Read + Seek,
+{
+    // The central directory already tells most differences apart
+    if a.uncompressed_size != b.uncompressed_size || a.crc32 != b.crc32 {
+        return Ok(false);
+    }
+    let mut left = first.read_entry_streaming(a)?;
+    let mut right = second.read_entry_streaming(b)?;
+    let mut left_buf = vec![0u8; 64 * 1024];
+    let mut right_buf = vec![0u8; 64 * 1024];
+    loop {
+        let n = read_full(&mut left, &mut left_buf)?;
+        let m = read_full(&mut right, &mut right_buf)?;
+        if left_buf[..n] != right_buf[..m] {
+            return Ok(false);
+        }
+        if n == 0 {
+            return Ok(true);
+        }
+    }
+}
+
+/// Read until `buf` is full or the stream ends
+fn read_full(reader: &mut impl Read, buf: &mut [u8]) -> Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(filled)
+}
@@ -0,0 +1,79 @@
+//! Deadlines for long-running archive work.
+//!
+//! Readers and writers accept an overall deadline (`with_deadline`) and check
+//! it between steps; data flowing through `Read`/`AsyncRead` is wrapped in
+//! [`DeadlineReader`] so a single large entry is cut off too. Expiry inside an
+//! I/O trait is reported as an `io::Error` carrying a private payload, which
+//! `From<io::Error>` turns back into `SZipError::DeadlineExceeded`.
+
+use crate::error::{Result, SZipError};
+use std::io::{self, Read};
+use std::time::Instant;
+
+#[derive(Debug)]
+struct DeadlineExpired;
+
+impl std::fmt::Display for DeadlineExpired {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("deadline exceeded")
+    }
+}
+
+impl std::error::Error for DeadlineExpired {}
+
+/// `io::Error` reporting an expired deadline
+pub(crate) fn io_error() -> io::Error {
+    io::Error::new(io::ErrorKind::TimedOut, DeadlineExpired)
+}
+
+/// Whether `err` was created by [`io_error`]
+pub(crate) fn is_expired(err: &io::Error) -> bool {
+    err.get_ref().is_some_and(|e| e.is::<DeadlineExpired>())
+}
+
+/// Fail with `DeadlineExceeded` once `deadline` has passed
+pub(crate) fn check(deadline: Option<Instant>) -> Result<()> {
+    match deadline {
+        Some(deadline) if Instant::now() >= deadline => Err(SZipError::DeadlineExceeded),
+        _ => Ok(()),
+    }
+}
+
+/// Reader that fails every read once the deadline has passed
+pub(crate) struct DeadlineReader<R> {
+    inner: R,
+    deadline: Option<Instant>,
+}
+
+impl<R> DeadlineReader<R> {
+    pub(crate) fn new(inner: R, deadline: Option<Instant>) -> Self {
+        Self { inner, deadline }
+    }
+
+    fn expired(&self) -> bool {
+        self.deadline.is_some_and(|d| Instant::now() >= d)
+    }
+}
+
+impl<R: Read> Read for DeadlineReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.expired() {
+            return Err(io_error());
+        }
+        self.inner.read(buf)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<R: tokio::io::AsyncRead + Unpin> tokio::io::AsyncRead for DeadlineReader<R> {
+    fn poll_read(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> std::task::Poll<io::Result<()>> {
+        if self.expired() {
+            return std::task::Poll::Ready(Err(io_error()));
+        }
+        std::pin::Pin::new(&mut self.inner).poll_read(cx, buf)
+    }
+}
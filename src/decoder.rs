@@ -0,0 +1,48 @@
+//! User-supplied decompressors for the sync reader.
+//!
+//! `StreamingZipReader` decompresses Stored, Deflate, and with the matching
+//! features Zstandard (`zstd-support`), Deflate64 (`deflate64`), bzip2
+//! (`bzip2`), LZMA and XZ (`lzma`) entries itself. Other methods, such as
+//! PPMd (98), can be handled by registering a decoder for the method id with
+//! [`register_decoder`]; the reader then passes the raw (already decrypted)
+//! entry data through it wherever it would otherwise fail with
+//! `SZipError::UnsupportedCompression`. A registered decoder also takes
+//! precedence over the built-in one for its method.
+//!
+//! ```ignore
+//! use s_zip::StreamingZipReader;
+//!
+//! let mut reader = StreamingZipReader::open("legacy.zip")?;
+//! // PPMd entries, decoded with a `ppmd` crate
+//! reader.register_decoder(98, |input| Ok(Box::new(ppmd::Decoder::new(input)?)));
+//! let data = reader.read_entry_by_name("report.csv")?;
+//! ```
+//!
+//! [`register_decoder`]: crate::StreamingZipReader::register_decoder
+
+use std::collections::HashMap;
+use std::io::{self, Read};
+use std::sync::Arc;
+
+/// A decoder: wraps the compressed data of an entry in a reader that yields
+/// the decompressed bytes
+pub type DecoderFn =
+    dyn for<'a> Fn(Box<dyn Read + 'a>) -> io::Result<Box<dyn Read + 'a>> + Send + Sync;
+
+/// Decoders registered on a reader, by compression method id
+#[derive(Clone, Default)]
+pub(crate) struct Decoders(HashMap<u16, Arc<DecoderFn>>);
+
+impl Decoders {
+    pub(crate) fn insert(&mut self, method: u16, decoder: Arc<DecoderFn>) {
+        self.0.insert(method, decoder);
+    }
+
+    pub(crate) fn get(&self, method: u16) -> Option<&DecoderFn> {
+        self.0.get(&method).map(|d| d.as_ref())
+    }
+
+    pub(crate) fn methods(&self) -> impl Iterator<Item = u16> + '_ {
+        self.0.keys().copied()
+    }
+}
@@ -91,6 +91,28 @@ pub mod sync {
             Ok(n)
         }
     }
+
+    /// A `Read` wrapper that only decrypts, leaving the HMAC to the caller.
+    /// Passes data through unchanged when `decryptor` is `None`.
+    ///
+    /// Used by `read_entry()`, which feeds the decompressed output into the
+    /// decryptor's HMAC itself once the whole entry has been produced.
+    pub(crate) struct CtrReader<'d, R: Read> {
+        pub(crate) inner: R,
+        pub(crate) decryptor: Option<&'d mut AesDecryptor>,
+    }
+
+    impl<R: Read> Read for CtrReader<'_, R> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = self.inner.read(buf)?;
+            if let Some(decryptor) = self.decryptor.as_mut() {
+                decryptor
+                    .decrypt(&mut buf[..n])
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+            }
+            Ok(n)
+        }
+    }
 }
 
 #[cfg(all(feature = "encryption", feature = "async"))]
@@ -159,4 +181,32 @@ pub mod r#async {
             result
         }
     }
+
+    /// An `AsyncRead` wrapper that only decrypts, leaving the HMAC to the caller.
+    ///
+    /// Async counterpart of the sync `CtrReader`, used by `read_entry()`.
+    pub(crate) struct CtrReader<'d, R: AsyncRead + Unpin> {
+        pub(crate) inner: R,
+        pub(crate) decryptor: Option<&'d mut AesDecryptor>,
+    }
+
+    impl<R: AsyncRead + Unpin> AsyncRead for CtrReader<'_, R> {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            let this = self.get_mut();
+            let filled_before = buf.filled().len();
+            let result = Pin::new(&mut this.inner).poll_read(cx, buf);
+            if let (Poll::Ready(Ok(())), Some(decryptor)) = (&result, this.decryptor.as_mut()) {
+                decryptor
+                    .decrypt(&mut buf.filled_mut()[filled_before..])
+                    .map_err(|e| {
+                        std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string())
+                    })?;
+            }
+            result
+        }
+    }
 }
@@ -0,0 +1,320 @@
+//! MS-DOS date/time conversions.
+//!
+//! ZIP headers store modification times as two packed 16-bit words with a
+//! 2-second resolution and a 1980–2107 range. [`DosDateTime`] wraps that
+//! encoding and converts to and from `SystemTime`, Unix timestamps and —
+//! behind the `chrono` / `time` features — the calendar types of those crates.
+//!
+//! DOS timestamps carry no time zone. s-zip treats them as UTC when
+//! converting to or from `SystemTime` and Unix timestamps; the calendar
+//! conversions map wall-clock fields one-to-one.
+//!
+//! ```
+//! use s_zip::DosDateTime;
+//!
+//! let dt = DosDateTime::from_parts(2024, 2, 29, 13, 37, 43).unwrap();
+//! assert_eq!(dt.second(), 42); // rounded down to 2-second resolution
+//! assert_eq!(DosDateTime::new(dt.time, dt.date), dt);
+//! ```
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Seconds between 1970-01-01 and 1980-01-01, the earliest DOS timestamp
+const DOS_EPOCH_UNIX: i64 = 315_532_800;
+
+/// A ZIP "last modified" timestamp in packed MS-DOS format.
+///
+/// Time word: bits 15-11 hour, 10-5 minute, 4-0 second/2.
+/// Date word: bits 15-9 year-1980, 8-5 month, 4-0 day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct DosDateTime {
+    /// Packed time word as stored in the header
+    pub time: u16,
+    /// Packed date word as stored in the header
+    pub date: u16,
+}
+
+impl DosDateTime {
+    /// Wrap raw time/date words read from a header
+    pub fn new(time: u16, date: u16) -> Self {
+        Self { time, date }
+    }
+
+    /// Build from calendar fields. Seconds are rounded down to an even value.
+    ///
+    /// Returns `None` if any field is out of range or the year is outside 1980–2107.
+    pub fn from_parts(
+        year: u16,
+        month: u8,
+        day: u8,
+        hour: u8,
+        minute: u8,
+        second: u8,
+    ) -> Option<Self> {
+        if !(1980..=2107).contains(&year)
+            || !(1..=12).contains(&month)
+            || day == 0
+            || day > days_in_month(year as i64, month)
+            || hour > 23
+            || minute > 59
+            || second > 59
+        {
+            return None;
+        }
+        Some(Self {
+            time: ((hour as u16) << 11) | ((minute as u16) << 5) | (second as u16 / 2),
+            date: ((year - 1980) << 9) | ((month as u16) << 5) | day as u16,
+        })
+    }
+
+    /// Convert a Unix timestamp (seconds, UTC), clamping to the DOS range
+    pub fn from_unix_timestamp(secs: i64) -> Self {
+        // 2107-12-31 23:59:58, the last representable instant
+        const MAX: i64 = 4_354_819_198;
+        let secs = secs.clamp(DOS_EPOCH_UNIX, MAX);
+
+        let (year, month, day) = civil_from_days(secs.div_euclid(86_400));
+        let tod = secs.rem_euclid(86_400);
+        Self::from_parts(
+            year as u16,
+            month,
+            day,
+            (tod / 3600) as u8,
+            ((tod % 3600) / 60) as u8,
+            (tod % 60) as u8,
+        )
+        .unwrap_or_default()
+    }
+
+    /// Convert a `SystemTime`, clamping to the DOS range
+    pub fn from_system_time(time: SystemTime) -> Self {
+        let secs = match time.duration_since(UNIX_EPOCH) {
+            Ok(d) => d.as_secs().min(i64::MAX as u64) as i64,
+            Err(_) => 0,
+        };
+        Self::from_unix_timestamp(secs)
+    }
+
+    /// Current time, as used when writers default the modification time
+    pub fn now() -> Self {
+        Self::from_system_time(SystemTime::now())
+    }
+
+    /// Year (1980–2107)
+    pub fn year(&self) -> u16 {
+        (self.date >> 9) + 1980
+    }
+
+    /// Month (1–12 for valid timestamps)
+    pub fn month(&self) -> u8 {
+        ((self.date >> 5) & 0x0F) as u8
+    }
+
+    /// Day of month (1–31 for valid timestamps)
+    pub fn day(&self) -> u8 {
+        (self.date & 0x1F) as u8
+    }
+
+    /// Hour (0–23 for valid timestamps)
+    pub fn hour(&self) -> u8 {
+        (self.time >> 11) as u8
+    }
+
+    /// Minute (0–59 for valid timestamps)
+    pub fn minute(&self) -> u8 {
+        ((self.time >> 5) & 0x3F) as u8
+    }
+
+    /// Second, always even (0–58 for valid timestamps)
+    pub fn second(&self) -> u8 {
+        ((self.time & 0x1F) * 2) as u8
+    }
+
+    /// Whether the packed fields describe a real calendar date and time.
+    ///
+    /// Writers that leave the timestamp zeroed produce invalid values.
+    pub fn is_valid(&self) -> bool {
+        Self::from_parts(
+            self.year(),
+            self.month(),
+            self.day(),
+            self.hour(),
+            self.minute(),
+            self.second(),
+        )
+        .is_some()
+    }
+
+    /// Seconds since the Unix epoch (interpreting the fields as UTC), or
+    /// `None` for an invalid timestamp
+    pub fn to_unix_timestamp(&self) -> Option<i64> {
+        if !self.is_valid() {
+            return None;
+        }
+        let days = days_from_civil(self.year() as i64, self.month(), self.day());
+        Some(
+            days * 86_400
+                + self.hour() as i64 * 3600
+                + self.minute() as i64 * 60
+                + self.second() as i64,
+        )
+    }
+
+    /// Convert to `SystemTime` (interpreting the fields as UTC), or `None`
+    /// for an invalid timestamp
+    pub fn to_system_time(&self) -> Option<SystemTime> {
+        let secs = self.to_unix_timestamp()?;
+        Some(UNIX_EPOCH + Duration::from_secs(secs as u64))
+    }
+
+    /// Convert to a `chrono::NaiveDateTime` (requires chrono feature)
+    #[cfg(feature = "chrono")]
+    pub fn to_chrono(&self) -> Option<chrono::NaiveDateTime> {
+        if !self.is_valid() {
+            return None;
+        }
+        chrono::NaiveDate::from_ymd_opt(self.year() as i32, self.month() as u32, self.day() as u32)?
+            .and_hms_opt(
+                self.hour() as u32,
+                self.minute() as u32,
+                self.second() as u32,
+            )
+    }
+
+    /// Convert to a `time::PrimitiveDateTime` (requires time feature)
+    #[cfg(feature = "time")]
+    pub fn to_time(&self) -> Option<time::PrimitiveDateTime> {
+        if !self.is_valid() {
+            return None;
+        }
+        let month = time::Month::try_from(self.month()).ok()?;
+        let date = time::Date::from_calendar_date(self.year() as i32, month, self.day()).ok()?;
+        let time = time::Time::from_hms(self.hour(), self.minute(), self.second()).ok()?;
+        Some(time::PrimitiveDateTime::new(date, time))
+    }
+}
+
+impl From<SystemTime> for DosDateTime {
+    fn from(time: SystemTime) -> Self {
+        Self::from_system_time(time)
+    }
+}
+
+/// Uses the wall-clock fields in the value's own time zone, clamping to the DOS range
+#[cfg(feature = "chrono")]
+impl<Tz: chrono::TimeZone> From<chrono::DateTime<Tz>> for DosDateTime {
+    fn from(dt: chrono::DateTime<Tz>) -> Self {
+        Self::from(dt.naive_local())
+    }
+}
+
+/// Clamps to the DOS range
+#[cfg(feature = "chrono")]
+impl From<chrono::NaiveDateTime> for DosDateTime {
+    fn from(dt: chrono::NaiveDateTime) -> Self {
+        Self::from_unix_timestamp(dt.and_utc().timestamp())
+    }
+}
+
+/// Uses the wall-clock fields in the value's own offset, clamping to the DOS range
+#[cfg(feature = "time")]
+impl From<time::OffsetDateTime> for DosDateTime {
+    fn from(dt: time::OffsetDateTime) -> Self {
+        Self::from(time::PrimitiveDateTime::new(dt.date(), dt.time()))
+    }
+}
+
+/// Clamps to the DOS range
+#[cfg(feature = "time")]
+impl From<time::PrimitiveDateTime> for DosDateTime {
+    fn from(dt: time::PrimitiveDateTime) -> Self {
+        Self::from_unix_timestamp(dt.assume_utc().unix_timestamp())
+    }
+}
+
+fn is_leap_year(year: i64) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i64, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01 → (year, month, day), proleptic Gregorian
+fn civil_from_days(days: i64) -> (i64, u8, u8) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u8;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// (year, month, day) → days since 1970-01-01, proleptic Gregorian
+fn days_from_civil(year: i64, month: u8, day: u8) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y.rem_euclid(400);
+    let m = month as i64;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unix_roundtrip() {
+        // 2024-02-29 13:37:42 UTC
+        let dt = DosDateTime::from_unix_timestamp(1_709_213_862);
+        assert_eq!((dt.year(), dt.month(), dt.day()), (2024, 2, 29));
+        assert_eq!((dt.hour(), dt.minute(), dt.second()), (13, 37, 42));
+        assert_eq!(dt.to_unix_timestamp(), Some(1_709_213_862));
+    }
+
+    #[test]
+    fn test_clamps_out_of_range() {
+        let early = DosDateTime::from_unix_timestamp(0);
+        assert_eq!(early.to_unix_timestamp(), Some(DOS_EPOCH_UNIX));
+        let late = DosDateTime::from_unix_timestamp(i64::MAX);
+        assert_eq!((late.year(), late.month(), late.day()), (2107, 12, 31));
+    }
+
+    #[test]
+    fn test_zero_is_invalid() {
+        let zero = DosDateTime::default();
+        assert!(!zero.is_valid());
+        assert_eq!(zero.to_system_time(), None);
+        assert!(DosDateTime::from_parts(2023, 2, 29, 0, 0, 0).is_none());
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_chrono_roundtrip() {
+        let naive = chrono::NaiveDate::from_ymd_opt(2030, 1, 2)
+            .unwrap()
+            .and_hms_opt(3, 4, 6)
+            .unwrap();
+        let dt = DosDateTime::from(naive);
+        assert_eq!(dt.to_chrono(), Some(naive));
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_time_roundtrip() {
+        let odt = time::OffsetDateTime::from_unix_timestamp(1_709_213_862).unwrap();
+        let dt = DosDateTime::from(odt);
+        assert_eq!(dt.to_time().map(|p| p.assume_utc()), Some(odt));
+    }
+}
@@ -0,0 +1,196 @@
+//! Double-buffered reads for async entry streams.
+//!
+//! Decompression alternates with fetching: the decoder asks for compressed
+//! bytes, waits for the source, then burns CPU on what it got while the
+//! source sits idle.  On high-latency sources (S3, HTTP) that idle time
+//! dominates.  [`DoubleBuffer`] keeps two buffers: when the decoder takes the
+//! front one, a read into the back one is started straight away, so a source
+//! that sends its request on the first poll (such as a ranged GET) has the
+//! next one in flight while the current chunk is decompressed.
+//!
+//! Nothing runs in the background: the back buffer only fills while the
+//! stream is polled, and the source must make progress on its own (e.g. a
+//! spawned connection task) for the request latency to overlap.
+
+use crate::budget::{MemoryBudget, Reservation};
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncBufRead, AsyncRead, ReadBuf};
+
+/// Size of each of the two buffers
+pub(crate) const CHUNK: usize = 128 * 1024;
+
+/// Buffer size used when a memory budget cannot cover `CHUNK`
+const MIN_CHUNK: usize = 8 * 1024;
+
+/// `AsyncBufRead` adapter that starts reading the next chunk of `inner` as
+/// soon as the consumer takes the current one
+pub(crate) struct DoubleBuffer<R> {
+    inner: R,
+    /// Chunk handed out by `poll_fill_buf`: `front[front_pos..front_len]`
+    front: Box<[u8]>,
+    front_pos: usize,
+    front_len: usize,
+    /// Chunk being filled on later polls: `back[..back_len]`
+    back: Box<[u8]>,
+    back_len: usize,
+    eof: bool,
+    /// Error from an early read, reported when the consumer reaches it
+    error: Option<io::Error>,
+    /// Budget share covering both buffers, released on drop
+    _memory: Option<Reservation>,
+}
+
+impl<R: AsyncRead + Unpin> DoubleBuffer<R> {
+    pub(crate) fn new(inner: R) -> Self {
+        Self::with_chunk_size(inner, CHUNK)
+    }
+
+    pub(crate) fn with_chunk_size(inner: R, chunk: usize) -> Self {
+        Self {
+            inner,
+            front: vec![0u8; chunk].into_boxed_slice(),
+            front_pos: 0,
+            front_len: 0,
+            back: vec![0u8; chunk].into_boxed_slice(),
+            back_len: 0,
+            eof: false,
+            error: None,
+            _memory: None,
+        }
+    }
+
+    /// Full-size buffers if `budget` can cover them, small ones otherwise
+    pub(crate) fn with_budget(inner: R, budget: Option<&MemoryBudget>) -> Self {
+        let Some(budget) = budget else {
+            return Self::new(inner);
+        };
+        match budget.try_reserve(2 * CHUNK) {
+            Some(memory) => Self {
+                _memory: Some(memory),
+                ..Self::new(inner)
+            },
+            None => Self::with_chunk_size(inner, MIN_CHUNK),
+        }
+    }
+
+    /// Read into the back buffer until it is full, the source is pending, or EOF
+    fn poll_fill_back(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        // The remaining capacity stays fixed across `Pending`, which sources
+        // with an in-flight request sized to the first call rely on.
+        while !self.eof && self.back_len < self.back.len() {
+            let mut buf = ReadBuf::new(&mut self.back[self.back_len..]);
+            match Pin::new(&mut self.inner).poll_read(cx, &mut buf) {
+                Poll::Ready(Ok(())) => {
+                    let n = buf.filled().len();
+                    self.eof = n == 0;
+                    self.back_len += n;
+                }
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncBufRead for DoubleBuffer<R> {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        let this = self.get_mut();
+
+        if this.front_pos == this.front_len {
+            if let Some(e) = this.error.take() {
+                return Poll::Ready(Err(e));
+            }
+            match this.poll_fill_back(cx) {
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending if this.back_len == 0 => return Poll::Pending,
+                // Hand out a partial chunk rather than waiting for a full one
+                Poll::Pending | Poll::Ready(Ok(())) => {}
+            }
+            std::mem::swap(&mut this.front, &mut this.back);
+            this.front_pos = 0;
+            this.front_len = this.back_len;
+            this.back_len = 0;
+
+            // Start fetching the next chunk before the caller processes this one
+            if let Poll::Ready(Err(e)) = this.poll_fill_back(cx) {
+                this.error = Some(e);
+            }
+        }
+
+        Poll::Ready(Ok(&this.front[this.front_pos..this.front_len]))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        let this = self.get_mut();
+        this.front_pos = (this.front_pos + amt).min(this.front_len);
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for DoubleBuffer<R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let data = match self.as_mut().poll_fill_buf(cx) {
+            Poll::Ready(Ok(data)) => data,
+            Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+            Poll::Pending => return Poll::Pending,
+        };
+        let n = data.len().min(buf.remaining());
+        buf.put_slice(&data[..n]);
+        self.consume(n);
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncReadExt;
+
+    /// Source that returns `Pending` on every other poll and short reads
+    struct Trickle {
+        data: Vec<u8>,
+        pos: usize,
+        ready: bool,
+    }
+
+    impl AsyncRead for Trickle {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<io::Result<()>> {
+            self.ready = !self.ready;
+            if !self.ready {
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            let n = (self.data.len() - self.pos).min(buf.remaining()).min(700);
+            let start = self.pos;
+            buf.put_slice(&self.data[start..start + n]);
+            self.pos += n;
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_double_buffer_preserves_data() {
+        let data: Vec<u8> = (0..50_000u32).map(|i| (i % 251) as u8).collect();
+        let source = Trickle {
+            data: data.clone(),
+            pos: 0,
+            ready: false,
+        };
+        let mut out = Vec::new();
+        DoubleBuffer::with_chunk_size(source, 4096)
+            .read_to_end(&mut out)
+            .await
+            .unwrap();
+        assert_eq!(out, data);
+    }
+}
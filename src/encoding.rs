@@ -0,0 +1,209 @@
+//! Decoding of entry names.
+//!
+//! The ZIP format predates Unicode: names are in IBM code page 437 unless
+//! general purpose flag bit 11 (language encoding) marks them as UTF-8.
+//! Windows tools still write CP437 names without the flag, while macOS and
+//! Linux tools often write UTF-8 names without it. Names are decoded as:
+//!
+//! 1. UTF-8 when bit 11 is set;
+//! 2. the Info-ZIP Unicode Path extra field (`0x7075`), when present and its
+//!    CRC-32 matches the stored name;
+//! 3. UTF-8 when the stored name is valid UTF-8;
+//! 4. CP437 otherwise.
+//!
+//! Writers set bit 11 for every name that isn't plain ASCII.
+//!
+//! Archives from systems with another legacy code page (Shift-JIS on
+//! Japanese Windows, GBK on Chinese Windows) need that code page instead of
+//! CP437; [`FilenameEncoding`] selects it through
+//! [`ReaderOptions`](crate::ReaderOptions).
+//!
+//! ```
+//! use s_zip::encoding::decode_cp437;
+//!
+//! assert_eq!(decode_cp437(b"M\x81nchen.txt"), "München.txt");
+//! ```
+
+use crate::raw::FLAG_UTF8;
+use std::borrow::Cow;
+use std::fmt;
+use std::sync::Arc;
+
+/// Info-ZIP Unicode Path extra field
+const UNICODE_PATH_EXTRA_ID: u16 = 0x7075;
+
+/// CP437 characters for bytes `0x80..=0xFF`; `0x00..=0x7F` are ASCII
+const CP437_HIGH: [char; 128] = [
+    'Ç', 'ü', 'é', 'â', 'ä', 'à', 'å', 'ç', 'ê', 'ë', 'è', 'ï', 'î', 'ì', 'Ä', 'Å', //
+    'É', 'æ', 'Æ', 'ô', 'ö', 'ò', 'û', 'ù', 'ÿ', 'Ö', 'Ü', '¢', '£', '¥', '₧', 'ƒ', //
+    'á', 'í', 'ó', 'ú', 'ñ', 'Ñ', 'ª', 'º', '¿', '⌐', '¬', '½', '¼', '¡', '«', '»', //
+    '░', '▒', '▓', '│', '┤', '╡', '╢', '╖', '╕', '╣', '║', '╗', '╝', '╜', '╛', '┐', //
+    '└', '┴', '┬', '├', '─', '┼', '╞', '╟', '╚', '╔', '╩', '╦', '╠', '═', '╬', '╧', //
+    '╨', '╤', '╥', '╙', '╘', '╒', '╓', '╫', '╪', '┘', '┌', '█', '▄', '▌', '▐', '▀', //
+    'α', 'ß', 'Γ', 'π', 'Σ', 'σ', 'µ', 'τ', 'Φ', 'Θ', 'Ω', 'δ', '∞', 'φ', 'ε', '∩', //
+    '≡', '±', '≥', '≤', '⌠', '⌡', '÷', '≈', '°', '∙', '·', '√', 'ⁿ', '²', '■', '\u{a0}',
+];
+
+/// Decodes a stored entry name
+pub type NameDecoder = Arc<dyn Fn(&[u8]) -> String + Send + Sync>;
+
+/// How entry names without the UTF-8 flag are decoded
+///
+/// Names with the UTF-8 flag (bit 11) set are UTF-8 whatever the choice.
+///
+/// ```no_run
+/// use s_zip::encoding::FilenameEncoding;
+/// use s_zip::{ReaderOptions, StreamingZipReader};
+/// use std::sync::Arc;
+///
+/// # fn decode_shift_jis(name: &[u8]) -> String { String::from_utf8_lossy(name).into_owned() }
+/// // e.g. with `encoding_rs`: SHIFT_JIS.decode(name).0.into_owned()
+/// let options = ReaderOptions::default()
+///     .with_filename_encoding(FilenameEncoding::Custom(Arc::new(decode_shift_jis)));
+/// let reader = StreamingZipReader::open_with_options("from-windows.zip", options)?;
+/// # Ok::<(), s_zip::SZipError>(())
+/// ```
+#[derive(Clone, Default)]
+pub enum FilenameEncoding {
+    /// Unicode Path extra field, then UTF-8 if valid, then CP437 (the default)
+    #[default]
+    Auto,
+    /// Always UTF-8, replacing invalid sequences
+    Utf8,
+    /// Always CP437
+    Cp437,
+    /// A caller-supplied decoder
+    Custom(NameDecoder),
+}
+
+impl FilenameEncoding {
+    /// Decode a stored entry name with this encoding
+    pub fn decode(&self, name: &[u8], flags: u16, extra: &[u8]) -> String {
+        if flags & FLAG_UTF8 != 0 {
+            return String::from_utf8_lossy(name).into_owned();
+        }
+        match self {
+            FilenameEncoding::Auto => decode_entry_name(name, flags, extra),
+            FilenameEncoding::Utf8 => String::from_utf8_lossy(name).into_owned(),
+            FilenameEncoding::Cp437 => decode_cp437(name),
+            FilenameEncoding::Custom(decode) => decode(name),
+        }
+    }
+
+    /// Decode an archive comment with this encoding
+    ///
+    /// Comments carry no UTF-8 flag or extra fields, so `Auto` takes them as
+    /// UTF-8 when valid and CP437 otherwise.
+    pub fn decode_comment<'a>(&self, comment: &'a [u8]) -> Cow<'a, str> {
+        match self {
+            FilenameEncoding::Auto => match std::str::from_utf8(comment) {
+                Ok(comment) => Cow::Borrowed(comment),
+                Err(_) => Cow::Owned(decode_cp437(comment)),
+            },
+            FilenameEncoding::Utf8 => String::from_utf8_lossy(comment),
+            FilenameEncoding::Cp437 => Cow::Owned(decode_cp437(comment)),
+            FilenameEncoding::Custom(decode) => Cow::Owned(decode(comment)),
+        }
+    }
+}
+
+impl fmt::Debug for FilenameEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FilenameEncoding::Auto => f.write_str("Auto"),
+            FilenameEncoding::Utf8 => f.write_str("Utf8"),
+            FilenameEncoding::Cp437 => f.write_str("Cp437"),
+            FilenameEncoding::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+/// Decode bytes in IBM code page 437
+pub fn decode_cp437(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|&b| {
+            if b < 0x80 {
+                b as char
+            } else {
+                CP437_HIGH[(b - 0x80) as usize]
+            }
+        })
+        .collect()
+}
+
+/// Decode a stored entry name following the rules in the [module docs](self)
+pub fn decode_entry_name(name: &[u8], flags: u16, extra: &[u8]) -> String {
+    if flags & FLAG_UTF8 != 0 {
+        return String::from_utf8_lossy(name).into_owned();
+    }
+    if let Some(unicode) = parse_unicode_path_extra_field(extra, name) {
+        return unicode;
+    }
+    match std::str::from_utf8(name) {
+        Ok(name) => name.to_owned(),
+        Err(_) => decode_cp437(name),
+    }
+}
+
+/// UTF-8 name from an Info-ZIP Unicode Path extra field, if its CRC-32 matches
+/// `name`; a stale field means the name was changed by a tool unaware of it
+pub fn parse_unicode_path_extra_field(extra: &[u8], name: &[u8]) -> Option<String> {
+    let mut i = 0;
+    while i + 4 <= extra.len() {
+        let id = u16::from_le_bytes([extra[i], extra[i + 1]]);
+        let len = u16::from_le_bytes([extra[i + 2], extra[i + 3]]) as usize;
+        let data = extra.get(i + 4..i + 4 + len)?;
+        if id == UNICODE_PATH_EXTRA_ID {
+            // Version 1, CRC-32 of the stored name, then the UTF-8 name
+            if data.len() < 5 || data[0] != 1 {
+                return None;
+            }
+            let crc = u32::from_le_bytes([data[1], data[2], data[3], data[4]]);
+            if crc != crc32fast::hash(name) {
+                return None;
+            }
+            return std::str::from_utf8(&data[5..]).ok().map(str::to_owned);
+        }
+        i += 4 + len;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_entry_name() {
+        // Flag clear, not UTF-8: CP437
+        assert_eq!(decode_entry_name(b"\x84\x94\x81.txt", 0, &[]), "äöü.txt");
+        // Flag clear, valid UTF-8: kept as UTF-8
+        assert_eq!(decode_entry_name("äöü.txt".as_bytes(), 0, &[]), "äöü.txt");
+        // Flag set: always UTF-8
+        assert_eq!(
+            decode_entry_name(b"\x84.txt", FLAG_UTF8, &[]),
+            "\u{fffd}.txt"
+        );
+        assert_eq!(decode_cp437(b"\xb0\xc9\xff"), "░╔\u{a0}");
+    }
+
+    #[test]
+    fn test_unicode_path_extra_field() {
+        let stored = b"caf\x82.txt";
+        let field = |crc: u32| {
+            let mut extra = vec![0x75, 0x70];
+            let unicode = "Café.txt".as_bytes();
+            extra.extend_from_slice(&((5 + unicode.len()) as u16).to_le_bytes());
+            extra.push(1);
+            extra.extend_from_slice(&crc.to_le_bytes());
+            extra.extend_from_slice(unicode);
+            extra
+        };
+        let extra = field(crc32fast::hash(stored));
+        assert_eq!(decode_entry_name(stored, 0, &extra), "Café.txt");
+        // A stale CRC falls back to the stored name
+        let extra = field(0);
+        assert_eq!(decode_entry_name(stored, 0, &extra), "café.txt");
+    }
+}
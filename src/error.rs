@@ -22,6 +22,14 @@ pub enum SZipError {
     /// Incorrect password
     #[cfg(feature = "encryption")]
     IncorrectPassword,
+    /// The deadline set with `with_deadline` passed before the operation finished
+    DeadlineExceeded,
+    /// The remote object was replaced while it was being read, so its bytes
+    /// cannot be combined with those read before
+    SourceChanged(String),
+    /// The archive signature is missing, malformed or does not match its entries
+    #[cfg(feature = "signing")]
+    SignatureInvalid(String),
 }
 
 impl std::fmt::Display for SZipError {
@@ -37,6 +45,10 @@ impl std::fmt::Display for SZipError {
             SZipError::EncryptionError(msg) => write!(f, "Encryption error: {}", msg),
             #[cfg(feature = "encryption")]
             SZipError::IncorrectPassword => write!(f, "Incorrect password"),
+            SZipError::DeadlineExceeded => write!(f, "Deadline exceeded"),
+            SZipError::SourceChanged(msg) => write!(f, "Source changed during read: {}", msg),
+            #[cfg(feature = "signing")]
+            SZipError::SignatureInvalid(msg) => write!(f, "Invalid signature: {}", msg),
         }
     }
 }
@@ -45,6 +57,71 @@ impl std::error::Error for SZipError {}
 
 impl From<io::Error> for SZipError {
     fn from(err: io::Error) -> Self {
-        SZipError::Io(err)
+        if crate::deadline::is_expired(&err) {
+            return SZipError::DeadlineExceeded;
+        }
+        match err
+            .get_ref()
+            .and_then(|e| e.downcast_ref::<SourceChanged>())
+        {
+            Some(changed) => SZipError::SourceChanged(changed.0.clone()),
+            None => SZipError::Io(err),
+        }
+    }
+}
+
+/// Payload of the `io::Error` a remote reader returns when its object changed
+#[derive(Debug)]
+struct SourceChanged(String);
+
+impl std::fmt::Display for SourceChanged {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for SourceChanged {}
+
+/// `io::Error` that `From<io::Error>` turns into `SZipError::SourceChanged`
+pub(crate) fn source_changed(msg: impl Into<String>) -> io::Error {
+    io::Error::other(SourceChanged(msg.into()))
+}
+
+/// Whether `err` was created by [`source_changed`]
+pub(crate) fn is_source_changed(err: &io::Error) -> bool {
+    err.get_ref().is_some_and(|e| e.is::<SourceChanged>())
+}
+
+/// Map the status of a range request pinned to `validator` (with `If-Range`
+/// or `If-Match`): `206` passes, while `200` (the whole, newer object) and
+/// `412` (precondition failed) mean the object changed
+pub(crate) fn range_status(status: u16, validator: &str) -> io::Result<()> {
+    match status {
+        206 => Ok(()),
+        200 | 412 => Err(source_changed(format!(
+            "object no longer matches {} (HTTP {})",
+            validator, status
+        ))),
+        _ => Err(io::Error::other(format!(
+            "Range request failed with HTTP {}",
+            status
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_range_status() {
+        assert!(range_status(206, "\"v1\"").is_ok());
+        for status in [200, 412] {
+            let err = range_status(status, "\"v1\"").unwrap_err();
+            assert!(is_source_changed(&err));
+            assert!(matches!(SZipError::from(err), SZipError::SourceChanged(_)));
+        }
+        let err = range_status(500, "\"v1\"").unwrap_err();
+        assert!(matches!(SZipError::from(err), SZipError::Io(_)));
     }
 }
@@ -0,0 +1,506 @@
+//! Extracting archives to a directory.
+//!
+//! Shared by `StreamingZipReader::extract_to_dir` and
+//! `GenericAsyncZipReader::extract_to_dir`: output path resolution and
+//! restoring file metadata (Unix mode, mtime, owner, DOS attributes) are
+//! identical for both, only the data copy differs.
+
+use crate::error::{Result, SZipError};
+use crate::format::{ZipEntry, MAX_SYMLINK_TARGET};
+use std::fs::File;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Controls which entries `extract_to_dir` writes, where, and which metadata
+/// it restores on the extracted files.
+///
+/// # Example
+/// ```no_run
+/// # use s_zip::{ExtractOptions, StreamingZipReader};
+/// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut reader = StreamingZipReader::open("archive.zip")?;
+/// let options = ExtractOptions {
+///     preserve_owner: true, // takes effect with privileges to chown
+///     ..Default::default()
+/// };
+/// reader.extract_to_dir_with_options("out", &options)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ExtractOptions {
+    /// Restore Unix permission bits from the external attributes (Unix only).
+    /// Default: `true`.
+    pub preserve_permissions: bool,
+    /// Restore the last-modified time from the entry timestamp.
+    /// Default: `true`.
+    pub preserve_mtime: bool,
+    /// Restore uid/gid from the Info-ZIP Unix extra field (`0x7875`, Unix only).
+    /// Giving files away usually requires elevated privileges; without them,
+    /// files stay owned by the extracting user, as with `unzip -X`.
+    /// Default: `false`.
+    pub preserve_owner: bool,
+    /// Entry predicate, see [`with_filter`](Self::with_filter). Default: all entries.
+    pub filter: Option<EntryFilter>,
+    /// Output path mapping, see [`with_rename`](Self::with_rename). Default: the entry name.
+    pub rename: Option<EntryRename>,
+    /// Fail before writing anything if an entry name is unsafe: absolute,
+    /// with a `..` component, a NUL byte or a Windows-reserved device name
+    /// (see [`crate::format::check_entry_name`]). When `false`, such names
+    /// are silently sanitized with [`ZipEntry::safe_path`] instead.
+    /// Default: `true`.
+    pub validate_names: bool,
+}
+
+/// Predicate deciding whether an entry is extracted
+pub type EntryFilter = Arc<dyn Fn(&ZipEntry) -> bool + Send + Sync>;
+
+/// Maps an entry name to an output path relative to the target directory
+pub type EntryRename = Arc<dyn Fn(&str) -> Option<PathBuf> + Send + Sync>;
+
+impl Default for ExtractOptions {
+    fn default() -> Self {
+        Self {
+            preserve_permissions: true,
+            preserve_mtime: true,
+            preserve_owner: false,
+            filter: None,
+            rename: None,
+            validate_names: true,
+        }
+    }
+}
+
+impl std::fmt::Debug for ExtractOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExtractOptions")
+            .field("preserve_permissions", &self.preserve_permissions)
+            .field("preserve_mtime", &self.preserve_mtime)
+            .field("preserve_owner", &self.preserve_owner)
+            .field("filter", &self.filter.is_some())
+            .field("rename", &self.rename.is_some())
+            .field("validate_names", &self.validate_names)
+            .finish()
+    }
+}
+
+impl ExtractOptions {
+    /// Only extract entries for which `filter` returns `true`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use s_zip::{ExtractOptions, StreamingZipReader};
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let options = ExtractOptions::default()
+    ///     .with_filter(|entry| entry.name.ends_with(".csv"))
+    ///     .with_rename(|name| name.strip_prefix("export/").map(Into::into));
+    /// StreamingZipReader::open("archive.zip")?.extract_to_dir_with_options("out", &options)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_filter<F>(mut self, filter: F) -> Self
+    where
+        F: Fn(&ZipEntry) -> bool + Send + Sync + 'static,
+    {
+        self.filter = Some(Arc::new(filter));
+        self
+    }
+
+    /// Map entry names to output paths relative to the target directory.
+    ///
+    /// Returning `None` skips the entry.  The returned path is sanitized like
+    /// entry names are, so it cannot escape the target directory.
+    pub fn with_rename<F>(mut self, rename: F) -> Self
+    where
+        F: Fn(&str) -> Option<PathBuf> + Send + Sync + 'static,
+    {
+        self.rename = Some(Arc::new(rename));
+        self
+    }
+}
+
+/// Worker pool of `extract_entries_parallel` on the readers
+///
+/// # Example
+/// ```no_run
+/// # use s_zip::{ExtractConfig, ExtractOptions, StreamingZipReader};
+/// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let reader = StreamingZipReader::open("photos.zip")?;
+/// let config = ExtractConfig::default()
+///     .with_workers(8)
+///     .with_options(ExtractOptions::default().with_filter(|entry| entry.name.ends_with(".jpg")));
+/// reader.extract_entries_parallel(reader.entries(), "out", &config)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ExtractConfig {
+    /// Entries decompressed and written at once (default: the available
+    /// parallelism, at most 16)
+    pub workers: usize,
+    /// Paths, filters and metadata, as for `extract_to_dir_with_options`
+    pub options: ExtractOptions,
+}
+
+impl Default for ExtractConfig {
+    fn default() -> Self {
+        let workers = std::thread::available_parallelism().map_or(4, |n| n.get());
+        Self {
+            workers: workers.min(16),
+            options: ExtractOptions::default(),
+        }
+    }
+}
+
+impl ExtractConfig {
+    /// Extract up to `workers` entries at once (at least 1)
+    pub fn with_workers(mut self, workers: usize) -> Self {
+        self.workers = workers.max(1);
+        self
+    }
+
+    /// Restore metadata and select entries as `options` say
+    pub fn with_options(mut self, options: ExtractOptions) -> Self {
+        self.options = options;
+        self
+    }
+}
+
+/// With `options.validate_names`, fail on the first entry selected by the
+/// filter whose name is unsafe to extract
+pub(crate) fn check_names(entries: &[ZipEntry], options: &ExtractOptions) -> Result<()> {
+    if !options.validate_names {
+        return Ok(());
+    }
+    for entry in entries {
+        if options.filter.as_ref().is_some_and(|filter| !filter(entry)) {
+            continue;
+        }
+        if let Err(reason) = crate::format::check_entry_name(&entry.name) {
+            return Err(SZipError::InvalidFormat(format!(
+                "Unsafe entry name '{}': {}",
+                entry.name, reason
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Destination of `entry` under `dir`, or `None` if the entry is filtered out
+/// or its sanitized path is empty
+pub(crate) fn output_path(
+    dir: &Path,
+    entry: &ZipEntry,
+    options: &ExtractOptions,
+) -> Option<PathBuf> {
+    if let Some(filter) = &options.filter {
+        if !filter(entry) {
+            return None;
+        }
+    }
+    let relative: PathBuf = match &options.rename {
+        Some(rename) => rename(&entry.name)?
+            .components()
+            .filter(|c| matches!(c, Component::Normal(_)))
+            .collect(),
+        None => entry.safe_path(),
+    };
+    if relative.as_os_str().is_empty() {
+        None
+    } else {
+        Some(dir.join(relative))
+    }
+}
+
+/// Whether `entry` is a directory entry (trailing slash)
+pub(crate) fn is_dir_entry(entry: &ZipEntry) -> bool {
+    entry.name.ends_with('/') || entry.name.ends_with('\\')
+}
+
+/// Whether `entry` is extracted as a symbolic link. Links are only created
+/// on Unix; elsewhere the target is written as the file's content.
+pub(crate) fn is_link_entry(entry: &ZipEntry) -> bool {
+    cfg!(unix) && entry.is_symlink()
+}
+
+/// Check that `entry` is a symbolic link with a plausible target size
+pub(crate) fn check_symlink_entry(entry: &ZipEntry) -> Result<()> {
+    if !entry.is_symlink() {
+        return Err(SZipError::InvalidFormat(format!(
+            "Entry '{}' is not a symbolic link",
+            entry.name
+        )));
+    }
+    if entry.uncompressed_size > MAX_SYMLINK_TARGET {
+        return Err(SZipError::InvalidFormat(format!(
+            "Symbolic link '{}' has a {}-byte target, longer than {} bytes",
+            entry.name, entry.uncompressed_size, MAX_SYMLINK_TARGET
+        )));
+    }
+    Ok(())
+}
+
+/// Decode the data of a symbolic link entry into its target
+pub(crate) fn symlink_target(entry: &ZipEntry, data: Vec<u8>) -> Result<String> {
+    String::from_utf8(data).map_err(|_| {
+        SZipError::InvalidFormat(format!(
+            "Symbolic link '{}' has a target that is not valid UTF-8",
+            entry.name
+        ))
+    })
+}
+
+/// Create the symbolic link `path` → `target` inside `dir`.
+///
+/// Links are created after every other entry, so no file is written through
+/// one. The target must stay inside `dir` without relying on other links:
+/// it is relative, climbs with `..` only at its start and no higher than
+/// `dir`, and no directory between `dir` and the link is itself a link.
+pub(crate) fn create_symlink(
+    dir: &Path,
+    path: &Path,
+    entry: &ZipEntry,
+    target: &str,
+) -> Result<()> {
+    let unsafe_link = |reason: &str| {
+        SZipError::InvalidFormat(format!(
+            "Refusing to create symbolic link '{}' -> '{}': {}",
+            entry.name, target, reason
+        ))
+    };
+
+    let depth = path
+        .strip_prefix(dir)
+        .map_or(0, |rel| rel.components().count().saturating_sub(1));
+    let mut climbed = 0;
+    let mut descended = false;
+    for component in Path::new(target).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if !descended => climbed += 1,
+            Component::Normal(_) => descended = true,
+            Component::ParentDir => return Err(unsafe_link("'..' after a directory name")),
+            Component::RootDir | Component::Prefix(_) => {
+                return Err(unsafe_link("absolute target"))
+            }
+        }
+    }
+    if target.is_empty() {
+        return Err(unsafe_link("empty target"));
+    }
+    if climbed > depth {
+        return Err(unsafe_link("target outside the extraction directory"));
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+        let mut ancestor = parent;
+        while ancestor != dir && ancestor.starts_with(dir) {
+            if std::fs::symlink_metadata(ancestor)?
+                .file_type()
+                .is_symlink()
+            {
+                return Err(unsafe_link("a parent directory is a symbolic link"));
+            }
+            ancestor = ancestor.parent().unwrap_or(dir);
+        }
+    }
+    match std::fs::symlink_metadata(path) {
+        Ok(existing) if existing.is_dir() => {
+            return Err(unsafe_link("a directory exists at its path"))
+        }
+        Ok(_) => std::fs::remove_file(path)?,
+        Err(_) => {}
+    }
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(target, path)?;
+    #[cfg(not(unix))]
+    std::fs::write(path, target)?;
+    Ok(())
+}
+
+/// `OpenOptions` for creating the output file, carrying DOS attributes on Windows
+pub(crate) fn open_options(entry: &ZipEntry) -> std::fs::OpenOptions {
+    entry.dos_attributes().unwrap_or_default().open_options()
+}
+
+/// Restore metadata on an extracted file after its data has been written
+pub(crate) fn apply_metadata(
+    file: &File,
+    path: &Path,
+    entry: &ZipEntry,
+    options: &ExtractOptions,
+) -> io::Result<()> {
+    if options.preserve_mtime {
+        if let Some(mtime) = entry.modified() {
+            file.set_modified(mtime)?;
+        }
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        if options.preserve_owner {
+            if let Some((uid, gid)) = entry.unix_owner {
+                // As `unzip -X`: without the privilege to give files away,
+                // they stay owned by the extracting user
+                match std::os::unix::fs::fchown(file, Some(uid), Some(gid)) {
+                    Err(e) if e.kind() == io::ErrorKind::PermissionDenied => {}
+                    result => result?,
+                }
+            }
+        }
+        // Permissions last: a read-only mode must not block the steps above
+        let mode = (entry.external_attrs >> 16) & 0o7777;
+        if options.preserve_permissions && mode != 0 {
+            file.set_permissions(std::fs::Permissions::from_mode(mode))?;
+        }
+    }
+
+    if let Some(attrs) = entry.dos_attributes() {
+        attrs.apply_to_path(path)?;
+    }
+    Ok(())
+}
+
+/// Restore metadata on the extracted directory entries `dirs`, once every
+/// file and link inside them is written
+///
+/// Deepest first, so restoring a directory doesn't touch the mtime of one
+/// already restored, and a read-only mode only applies once its contents
+/// are complete.
+pub(crate) fn apply_dir_metadata(
+    mut dirs: Vec<(&ZipEntry, PathBuf)>,
+    options: &ExtractOptions,
+) -> io::Result<()> {
+    dirs.sort_by_key(|(_, path)| std::cmp::Reverse(path.components().count()));
+    for (entry, path) in dirs {
+        apply_metadata(&open_dir(&path)?, &path, entry, options)?;
+    }
+    Ok(())
+}
+
+/// Handle to a directory that its times can be set through
+fn open_dir(path: &Path) -> io::Result<File> {
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::OpenOptionsExt;
+        // FILE_FLAG_BACKUP_SEMANTICS, needed to open a directory
+        std::fs::OpenOptions::new()
+            .write(true)
+            .custom_flags(0x0200_0000)
+            .open(path)
+    }
+    #[cfg(not(windows))]
+    File::open(path)
+}
+
+/// Temporary file an entry is extracted to before being renamed into place
+///
+/// Removed on drop unless [`keep`](Self::keep) was called, so neither errors
+/// nor cancelled (dropped) async extractions leave `.part` files behind.
+pub(crate) struct TempFile {
+    path: PathBuf,
+    keep: bool,
+}
+
+impl TempFile {
+    /// Temporary sibling of `target`
+    pub(crate) fn new(target: &Path) -> Self {
+        Self {
+            path: temp_path(target),
+            keep: false,
+        }
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Leave the file in place, once it has been renamed
+    pub(crate) fn keep(mut self) {
+        self.keep = true;
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        if !self.keep {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Sibling temporary path that `path` is written to before being renamed into place
+fn temp_path(path: &Path) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    // Unique per call, so concurrent extractions of one name never share it
+    static NEXT: AtomicU64 = AtomicU64::new(0);
+    let n = NEXT.fetch_add(1, Ordering::Relaxed);
+    path.with_file_name(format!(".{}.{}.{}.part", name, std::process::id(), n))
+}
+
+/// Compare the CRC-32 of extracted data against the central directory.
+///
+/// Skipped for WinZip AE-2 entries (which store no CRC; the HMAC authenticates
+/// instead) and entries whose stored CRC is zero.
+pub(crate) fn check_crc(entry: &ZipEntry, actual: u32) -> Result<()> {
+    if entry.crc32 != 0 && !is_ae2(entry) && actual != entry.crc32 {
+        return Err(SZipError::InvalidFormat(format!(
+            "CRC-32 mismatch for '{}': expected {:#010x}, got {:#010x}. \
+             The entry may be corrupt or the download may be incomplete.",
+            entry.name, entry.crc32, actual
+        )));
+    }
+    Ok(())
+}
+
+/// Whether `entry` carries a WinZip AES extra field with vendor version 2
+fn is_ae2(entry: &ZipEntry) -> bool {
+    entry
+        .extra_fields()
+        .any(|(id, data)| id == 0x9901 && data.get(..2) == Some(&[2, 0][..]))
+}
+
+/// `Write` adapter that computes the CRC-32 of everything written through it
+pub(crate) struct CrcWriter<W> {
+    pub(crate) inner: W,
+    hasher: crc32fast::Hasher,
+}
+
+impl<W> CrcWriter<W> {
+    pub(crate) fn new(inner: W) -> Self {
+        Self {
+            inner,
+            hasher: crc32fast::Hasher::new(),
+        }
+    }
+
+    pub(crate) fn crc32(&self) -> u32 {
+        self.hasher.clone().finalize()
+    }
+
+    /// Hash data written to `inner` directly (the async path can't go through `Write`)
+    #[cfg(feature = "async")]
+    pub(crate) fn update(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+    }
+}
+
+impl<W: io::Write> io::Write for CrcWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.hasher.update(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
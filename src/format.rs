@@ -5,7 +5,9 @@
 //! perform I/O — they only operate on already-read byte slices so they can be
 //! used in both the sync and async code paths without any adaptation.
 
+use crate::dostime::DosDateTime;
 use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 // ── Signatures ────────────────────────────────────────────────────────────────
 
@@ -25,25 +27,43 @@ pub const ZIP64_END_OF_CENTRAL_DIRECTORY_SIGNATURE: u32 = 0x06064b50;
 
 /// Maximum single-entry allocation (2 GiB).
 ///
-/// Prevents OOM when reading a corrupt or maliciously crafted ZIP that
-/// advertises a huge `compressed_size` (e.g. `u64::MAX`) in its central
-/// directory.  Entries genuinely larger than this threshold must use the
+/// `read_entry` stops decompressing once an entry's output exceeds this, so
+/// a corrupt or malicious ZIP (or a decompression bomb) cannot exhaust
+/// memory.  Entries genuinely larger than this threshold must use the
 /// streaming API (`read_entry_streaming`).
 pub const MAX_ENTRY_ALLOC: u64 = 2 * 1024 * 1024 * 1024; // 2 GiB
 
+/// Longest symbolic link target read from an archive (`PATH_MAX` on Linux).
+pub const MAX_SYMLINK_TARGET: u64 = 4096;
+
+/// Largest buffer reserved upfront from an entry's declared size (16 MiB).
+///
+/// Header sizes are untrusted, so `read_entry` only pre-allocates up to this
+/// much and lets the buffer grow as decompressed data actually arrives.
+pub const INITIAL_ALLOC_CAP: u64 = 16 * 1024 * 1024;
+
 // ── Entry ─────────────────────────────────────────────────────────────────────
 
 /// Entry in a ZIP central directory.
 ///
-/// Shared between the sync (`reader`) and async (`async_reader`) modules.
+/// Shared between the sync (`reader`) and async (`async_reader`) modules,
+/// which fill in the same fields from the central directory record.
 #[derive(Debug, Clone)]
 pub struct ZipEntry {
+    /// Entry name, decoded as described in [`crate::encoding`].
     pub name: String,
+    /// Size of the stored (compressed, and possibly encrypted) data.
     pub compressed_size: u64,
+    /// Size of the data once decompressed.
     pub uncompressed_size: u64,
+    /// Compression method (0 = stored, 8 = DEFLATE, 93 = Zstandard).
     pub compression_method: u16,
     /// Offset of the local file header from the start of the archive.
     pub offset: u64,
+    /// Volume of a split archive that holds the local file header; 0 for
+    /// single-file archives. `offset` counts from the start of the first
+    /// volume once the reader has joined the volumes.
+    pub disk_number: u32,
     /// CRC-32 checksum from the central directory.
     pub crc32: u32,
     /// `true` when general-purpose bit 0 (encryption flag) is set in the
@@ -51,6 +71,31 @@ pub struct ZipEntry {
     /// `#[cfg(feature = "encryption")]` at the usage sites; the async reader
     /// always exposes it.
     pub is_encrypted: bool,
+    /// Raw general-purpose bit flag from the central directory.
+    pub flags: u16,
+    /// "Version made by" field (high byte: host OS, low byte: spec version).
+    pub version_made_by: u16,
+    /// "Version needed to extract" field.
+    pub version_needed: u16,
+    /// Last-modified timestamp from the central directory (zero if the writer didn't set one).
+    pub last_modified: DosDateTime,
+    /// Internal file attributes from the central directory; bit 0 marks a
+    /// text file.
+    pub internal_attrs: u16,
+    /// External file attributes from the central directory.  Interpretation
+    /// depends on the host in `version_made_by` (DOS bits in the low byte,
+    /// Unix mode in the high 16 bits).
+    pub external_attrs: u32,
+    /// `(uid, gid)` from the Info-ZIP Unix extra field (`0x7875`), if present.
+    pub unix_owner: Option<(u32, u32)>,
+    /// UTC times from the extended timestamp extra field (`0x5455`), if present.
+    pub extended_timestamp: Option<ExtendedTimestamp>,
+    /// AES key strength code (1 = AES-128, 2 = AES-192, 3 = AES-256) from the
+    /// WinZip AES extra field (`0x9901`); `None` for unencrypted or ZipCrypto entries.
+    pub aes_strength: Option<u8>,
+    /// Raw extra field from the central directory (from the local header for
+    /// readers without one); see [`extra_fields`](Self::extra_fields).
+    pub extra: Vec<u8>,
 }
 
 impl ZipEntry {
@@ -72,6 +117,221 @@ impl ZipEntry {
             .filter(|c| matches!(c, Component::Normal(_)))
             .collect()
     }
+
+    /// MS-DOS / Windows file attributes, if the entry was written on a host
+    /// that stores them (MS-DOS/FAT, NTFS or VFAT).
+    pub fn dos_attributes(&self) -> Option<DosAttributes> {
+        match self.version_made_by >> 8 {
+            HOST_MSDOS | HOST_NTFS | HOST_VFAT => {
+                Some(DosAttributes::from_bits(self.external_attrs as u8))
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the entry is a symbolic link: its Unix mode has the link file
+    /// type, and its data is the link target.
+    pub fn is_symlink(&self) -> bool {
+        self.unix_mode()
+            .is_some_and(|mode| mode & 0o170000 == UNIX_SYMLINK)
+    }
+
+    /// The `(id, data)` records of the raw [`extra`](Self::extra) field, for
+    /// vendor fields this crate doesn't interpret (NTFS times, Unicode Path)
+    ///
+    /// ```no_run
+    /// # use s_zip::StreamingZipReader;
+    /// let reader = StreamingZipReader::open("archive.zip")?;
+    /// for entry in reader.entries() {
+    ///     // NTFS extra field: reserved(4), then tagged attributes
+    ///     if let Some((_, ntfs)) = entry.extra_fields().find(|&(id, _)| id == 0x000a) {
+    ///         println!("{}: {} bytes of NTFS times", entry.name, ntfs.len());
+    ///     }
+    /// }
+    /// # Ok::<(), s_zip::SZipError>(())
+    /// ```
+    pub fn extra_fields(&self) -> ExtraFields<'_> {
+        extra_fields(&self.extra)
+    }
+
+    /// Whether the internal attributes mark the entry as a text file
+    pub fn is_text(&self) -> bool {
+        self.internal_attrs & 0x0001 != 0
+    }
+
+    /// Unix mode (file type and permission bits) from the upper half of the
+    /// external attributes, if the entry was written on a Unix host.
+    ///
+    /// Mask with `0o7777` for the permission bits to restore on an extracted file.
+    pub fn unix_mode(&self) -> Option<u32> {
+        let mode = self.external_attrs >> 16;
+        (self.version_made_by >> 8 == HOST_UNIX && mode != 0).then_some(mode)
+    }
+
+    /// Last-modified time, preferring the second-precision UTC time of the
+    /// extended timestamp field over the DOS timestamp.
+    pub fn modified(&self) -> Option<SystemTime> {
+        self.extended_timestamp
+            .and_then(|ts| ts.modified)
+            .or_else(|| self.last_modified.to_system_time())
+    }
+}
+
+/// Times from the Info-ZIP extended timestamp extra field (`0x5455`).
+///
+/// Unlike the DOS timestamp these are UTC with one-second precision. Central
+/// directory records usually carry only the modification time; the access
+/// and creation times are read from the local header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ExtendedTimestamp {
+    /// Last-modified time
+    pub modified: Option<SystemTime>,
+    /// Last-accessed time
+    pub accessed: Option<SystemTime>,
+    /// Creation time
+    pub created: Option<SystemTime>,
+}
+
+impl ExtendedTimestamp {
+    /// Build the extra field. The local header carries every time that is
+    /// set; the central directory only the modification time, as Info-ZIP
+    /// writes it. Times are clamped to the field's 1970–2106 range.
+    pub(crate) fn to_extra_field(self, central: bool) -> Vec<u8> {
+        let times = [self.modified, self.accessed, self.created];
+        let mut flags = 0u8;
+        let mut data = Vec::with_capacity(13);
+        for (bit, time) in times.iter().enumerate() {
+            let Some(time) = time else { continue };
+            flags |= 1 << bit;
+            if !central || bit == 0 {
+                let secs = time
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |d| d.as_secs().min(u32::MAX as u64));
+                data.extend_from_slice(&(secs as u32).to_le_bytes());
+            }
+        }
+        if flags == 0 {
+            return Vec::new();
+        }
+        let mut field = Vec::with_capacity(5 + data.len());
+        field.extend_from_slice(&0x5455u16.to_le_bytes()); // ID
+        field.extend_from_slice(&(1 + data.len() as u16).to_le_bytes()); // data size
+        field.push(flags);
+        field.extend_from_slice(&data);
+        field
+    }
+}
+
+/// Where an archive's central directory is, as recorded in its EOCD record.
+///
+/// Returned by `cd_location()` on the readers; pass it back to
+/// `open_with_cd_location` to reopen the archive without scanning for the
+/// EOCD record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CentralDirectoryLocation {
+    /// Offset of the first central directory record
+    pub offset: u64,
+    /// Size of the central directory in bytes
+    pub size: u64,
+    /// Number of central directory records
+    pub count: u64,
+}
+
+/// "Version made by" host byte for MS-DOS and OS/2 (FAT file systems)
+pub const HOST_MSDOS: u16 = 0;
+/// "Version made by" host byte for Windows NTFS
+pub const HOST_NTFS: u16 = 10;
+/// "Version made by" host byte for Windows VFAT
+pub const HOST_VFAT: u16 = 14;
+/// "Version made by" host byte for Unix, including macOS
+pub const HOST_UNIX: u16 = 3;
+
+/// Unix file type of a symbolic link (`S_IFLNK`)
+pub const UNIX_SYMLINK: u32 = 0o120000;
+
+/// MS-DOS / Windows file attributes stored in the low byte of an entry's
+/// external attributes.
+///
+/// When extracting on Windows, create the file with [`open_options`] so the
+/// hidden/system/archive bits are set, then call [`apply_to_path`] once the
+/// data is written to apply the read-only bit.  Both are plain file creation
+/// and a no-op respectively on other targets.
+///
+/// [`open_options`]: DosAttributes::open_options
+/// [`apply_to_path`]: DosAttributes::apply_to_path
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DosAttributes {
+    pub read_only: bool,
+    pub hidden: bool,
+    pub system: bool,
+    pub directory: bool,
+    pub archive: bool,
+}
+
+impl DosAttributes {
+    const READ_ONLY: u8 = 0x01;
+    const HIDDEN: u8 = 0x02;
+    const SYSTEM: u8 = 0x04;
+    const DIRECTORY: u8 = 0x10;
+    const ARCHIVE: u8 = 0x20;
+
+    /// Decode the DOS attribute byte
+    pub fn from_bits(bits: u8) -> Self {
+        Self {
+            read_only: bits & Self::READ_ONLY != 0,
+            hidden: bits & Self::HIDDEN != 0,
+            system: bits & Self::SYSTEM != 0,
+            directory: bits & Self::DIRECTORY != 0,
+            archive: bits & Self::ARCHIVE != 0,
+        }
+    }
+
+    /// Encode as the DOS attribute byte
+    pub fn to_bits(self) -> u8 {
+        let mut bits = 0;
+        for (set, bit) in [
+            (self.read_only, Self::READ_ONLY),
+            (self.hidden, Self::HIDDEN),
+            (self.system, Self::SYSTEM),
+            (self.directory, Self::DIRECTORY),
+            (self.archive, Self::ARCHIVE),
+        ] {
+            if set {
+                bits |= bit;
+            }
+        }
+        bits
+    }
+
+    /// `OpenOptions` for creating an extracted file (write + create + truncate).
+    ///
+    /// On Windows the hidden, system and archive bits are passed to
+    /// `CreateFileW`; the read-only bit is left to [`apply_to_path`](Self::apply_to_path).
+    pub fn open_options(&self) -> std::fs::OpenOptions {
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(windows)]
+        {
+            use std::os::windows::fs::OpenOptionsExt;
+            let bits = self.to_bits() & (Self::HIDDEN | Self::SYSTEM | Self::ARCHIVE);
+            options.attributes(bits as u32);
+        }
+        options
+    }
+
+    /// Apply the read-only bit to an extracted file (Windows only; no-op elsewhere,
+    /// where permissions come from the Unix mode instead).
+    pub fn apply_to_path(&self, path: &Path) -> std::io::Result<()> {
+        #[cfg(windows)]
+        if self.read_only {
+            let mut permissions = std::fs::metadata(path)?.permissions();
+            permissions.set_readonly(true);
+            std::fs::set_permissions(path, permissions)?;
+        }
+        #[cfg(not(windows))]
+        let _ = path;
+        Ok(())
+    }
 }
 
 // ── Pure parsing helpers ──────────────────────────────────────────────────────
@@ -218,6 +478,277 @@ pub fn parse_aes_extra_field_buf(extra_buf: &[u8]) -> Option<u8> {
     None
 }
 
+/// Parse the Info-ZIP "New Unix" extra field (tag `0x7875`) out of
+/// `extra_buf` and return `(uid, gid)`.
+///
+/// Returns `None` if the field is absent, malformed, or uses IDs wider than
+/// 32 bits.
+#[inline]
+pub fn parse_unix_owner_extra_field(extra_buf: &[u8]) -> Option<(u32, u32)> {
+    fn read_id(data: &[u8], pos: &mut usize) -> Option<u32> {
+        let size = *data.get(*pos)? as usize;
+        let bytes = data.get(*pos + 1..*pos + 1 + size)?;
+        *pos += 1 + size;
+        if size > 4 {
+            return None;
+        }
+        let mut buf = [0u8; 4];
+        buf[..size].copy_from_slice(bytes);
+        Some(u32::from_le_bytes(buf))
+    }
+
+    let mut i = 0usize;
+    while i + 4 <= extra_buf.len() {
+        let id = u16::from_le_bytes([extra_buf[i], extra_buf[i + 1]]);
+        let data_len = u16::from_le_bytes([extra_buf[i + 2], extra_buf[i + 3]]) as usize;
+        i += 4;
+        if i + data_len > extra_buf.len() {
+            break;
+        }
+        if id == 0x7875 {
+            // version(1) + uid_size(1) + uid(N) + gid_size(1) + gid(N)
+            let data = &extra_buf[i..i + data_len];
+            if data.first() != Some(&1) {
+                return None;
+            }
+            let mut pos = 1;
+            let uid = read_id(data, &mut pos)?;
+            let gid = read_id(data, &mut pos)?;
+            return Some((uid, gid));
+        }
+        i += data_len;
+    }
+    None
+}
+
+/// Parse the Info-ZIP extended timestamp extra field (tag `0x5455`) out of
+/// `extra_buf`.
+///
+/// The flags byte says which times are set; a time the flags announce but
+/// the field doesn't hold (as in central directory records) is left `None`.
+/// Times are read as unsigned seconds since the Unix epoch.
+#[inline]
+pub fn parse_extended_timestamp_extra_field(extra_buf: &[u8]) -> Option<ExtendedTimestamp> {
+    let mut i = 0usize;
+    while i + 4 <= extra_buf.len() {
+        let id = u16::from_le_bytes([extra_buf[i], extra_buf[i + 1]]);
+        let data_len = u16::from_le_bytes([extra_buf[i + 2], extra_buf[i + 3]]) as usize;
+        i += 4;
+        if i + data_len > extra_buf.len() {
+            break;
+        }
+        if id == 0x5455 {
+            // flags(1) + mtime(4) + atime(4) + ctime(4), each time optional
+            let data = &extra_buf[i..i + data_len];
+            let flags = *data.first()?;
+            let mut times = data[1..].chunks_exact(4);
+            let mut next = |bit: u8| {
+                if flags & bit == 0 {
+                    return None;
+                }
+                let secs = u32::from_le_bytes(times.next()?.try_into().unwrap());
+                Some(UNIX_EPOCH + Duration::from_secs(secs as u64))
+            };
+            return Some(ExtendedTimestamp {
+                modified: next(0x01),
+                accessed: next(0x02),
+                created: next(0x04),
+            });
+        }
+        i += data_len;
+    }
+    None
+}
+
+/// Iterate over the `(id, data)` records of an extra field
+///
+/// Iteration stops at a record whose length runs past the end of `extra`.
+pub fn extra_fields(extra: &[u8]) -> ExtraFields<'_> {
+    ExtraFields { rest: extra }
+}
+
+/// Iterator over the records of an extra field, see [`extra_fields`]
+#[derive(Debug, Clone)]
+pub struct ExtraFields<'a> {
+    rest: &'a [u8],
+}
+
+impl<'a> Iterator for ExtraFields<'a> {
+    type Item = (u16, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.rest.len() < 4 {
+            return None;
+        }
+        let id = u16::from_le_bytes([self.rest[0], self.rest[1]]);
+        let len = u16::from_le_bytes([self.rest[2], self.rest[3]]) as usize;
+        let Some(data) = self.rest.get(4..4 + len) else {
+            self.rest = &[];
+            return None;
+        };
+        self.rest = &self.rest[4 + len..];
+        Some((id, data))
+    }
+}
+
+// ── Entry-name helpers ────────────────────────────────────────────────────────
+
+/// Normalize a caller-supplied path into a well-formed ZIP entry name.
+///
+/// Converts `\` separators to `/`, strips a leading drive letter (`C:`) and
+/// leading slashes, drops empty and `.` segments, and resolves `..` against
+/// the preceding segment (a `..` with nothing to pop is discarded).  A
+/// trailing `/` is kept so directory entries stay directories.
+///
+/// ```
+/// # use s_zip::format::normalize_entry_name;
+/// assert_eq!(normalize_entry_name("C:\\data\\.\\logs\\..\\a.txt"), "data/a.txt");
+/// assert_eq!(normalize_entry_name("/../etc/"), "etc/");
+/// ```
+pub fn normalize_entry_name(name: &str) -> String {
+    let name = name.replace('\\', "/");
+    let bytes = name.as_bytes();
+    let rest = if bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' {
+        &name[2..]
+    } else {
+        &name[..]
+    };
+
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in rest.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            s => segments.push(s),
+        }
+    }
+
+    let mut normalized = segments.join("/");
+    if rest.ends_with('/') && !normalized.is_empty() {
+        normalized.push('/');
+    }
+    normalized
+}
+
+/// Windows device names that cannot be used as a path component, regardless
+/// of extension (`CON`, `con.txt`, ...).
+const WINDOWS_RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Check that an entry name is safe to extract on any platform.
+///
+/// Returns a description of the first problem found: an absolute path
+/// (leading `/`, `\` or drive letter), a `..` component, a NUL byte, or a
+/// Windows-reserved device name such as `CON` or `lpt1.txt`.
+pub fn check_entry_name(name: &str) -> std::result::Result<(), String> {
+    if name.contains('\0') {
+        return Err("contains a NUL byte".to_string());
+    }
+    let bytes = name.as_bytes();
+    if name.starts_with('/')
+        || name.starts_with('\\')
+        || (bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':')
+    {
+        return Err("is an absolute path".to_string());
+    }
+    for component in name.split(['/', '\\']) {
+        if component == ".." {
+            return Err("contains a '..' component".to_string());
+        }
+        let stem = component
+            .split('.')
+            .next()
+            .unwrap_or("")
+            .trim_end_matches(' ');
+        if WINDOWS_RESERVED_NAMES
+            .iter()
+            .any(|r| r.eq_ignore_ascii_case(stem))
+        {
+            return Err(format!(
+                "uses the reserved Windows device name '{}'",
+                component
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Whether `name` is macOS Finder metadata rather than user content: a
+/// `.DS_Store` file, anything under `__MACOSX/`, or an AppleDouble `._`
+/// companion file.
+///
+/// ```
+/// # use s_zip::format::is_macos_metadata;
+/// assert!(is_macos_metadata("__MACOSX/docs/._report.pdf"));
+/// assert!(is_macos_metadata("docs/.DS_Store"));
+/// assert!(!is_macos_metadata("docs/report.pdf"));
+/// ```
+pub fn is_macos_metadata(name: &str) -> bool {
+    if name.split('/').next() == Some("__MACOSX") {
+        return true;
+    }
+    match name.rsplit('/').next() {
+        Some(file) => file == ".DS_Store" || file.starts_with("._"),
+        None => false,
+    }
+}
+
+/// Name of the AppleDouble companion macOS stores for `name`:
+/// `__MACOSX/<dir>/._<file>`.
+///
+/// ```
+/// # use s_zip::format::apple_double_name;
+/// assert_eq!(apple_double_name("docs/report.pdf"), "__MACOSX/docs/._report.pdf");
+/// ```
+pub fn apple_double_name(name: &str) -> String {
+    let (dir, file) = match name.trim_end_matches('/').rsplit_once('/') {
+        Some((dir, file)) => (format!("{}/", dir), file),
+        None => (String::new(), name.trim_end_matches('/')),
+    };
+    format!("__MACOSX/{}._{}", dir, file)
+}
+
+/// Name of the directory entry for `name`, which ends in `/`
+///
+/// ```
+/// # use s_zip::format::directory_entry_name;
+/// assert_eq!(directory_entry_name("assets/empty"), "assets/empty/");
+/// assert_eq!(directory_entry_name("logs\\"), "logs/");
+/// ```
+pub fn directory_entry_name(name: &str) -> String {
+    format!("{}/", name.trim_end_matches(['/', '\\']))
+}
+
+/// Serialize an AppleDouble file (version 2) holding Finder info and a
+/// resource fork, the format macOS writes to `._` companions.
+pub fn apple_double(finder_info: &[u8; 32], resource_fork: &[u8]) -> Vec<u8> {
+    const FINDER_INFO: u32 = 9;
+    const RESOURCE_FORK: u32 = 2;
+    // magic(4) + version(4) + filler(16) + entry count(2) + 2 entries * 12
+    let header_len = 26 + 2 * 12;
+
+    let mut out = Vec::with_capacity(header_len + 32 + resource_fork.len());
+    out.extend_from_slice(&0x0005_1607u32.to_be_bytes());
+    out.extend_from_slice(&0x0002_0000u32.to_be_bytes());
+    out.extend_from_slice(b"Mac OS X        ");
+    out.extend_from_slice(&2u16.to_be_bytes());
+    for (id, offset, len) in [
+        (FINDER_INFO, header_len, 32),
+        (RESOURCE_FORK, header_len + 32, resource_fork.len()),
+    ] {
+        out.extend_from_slice(&id.to_be_bytes());
+        out.extend_from_slice(&(offset as u32).to_be_bytes());
+        out.extend_from_slice(&(len as u32).to_be_bytes());
+    }
+    out.extend_from_slice(finder_info);
+    out.extend_from_slice(resource_fork);
+    out
+}
+
 // ── Tests ─────────────────────────────────────────────────────────────────────
 
 #[cfg(test)]
@@ -278,10 +809,107 @@ mod tests {
             uncompressed_size: 0,
             compression_method: 0,
             offset: 0,
+            disk_number: 0,
             crc32: 0,
             is_encrypted: false,
+            flags: 0,
+            version_made_by: 0,
+            version_needed: 0,
+            last_modified: DosDateTime::default(),
+            internal_attrs: 0,
+            external_attrs: 0,
+            unix_owner: None,
+            extended_timestamp: None,
+            aes_strength: None,
+            extra: Vec::new(),
         };
         let p = entry.safe_path();
         assert_eq!(p, PathBuf::from("etc/passwd"));
     }
+
+    #[test]
+    fn test_normalize_entry_name() {
+        assert_eq!(
+            normalize_entry_name("dir\\sub\\file.txt"),
+            "dir/sub/file.txt"
+        );
+        assert_eq!(normalize_entry_name("/abs/path.txt"), "abs/path.txt");
+        assert_eq!(normalize_entry_name("D:/x/./y//z.txt"), "x/y/z.txt");
+        assert_eq!(normalize_entry_name("a/b/../../../c.txt"), "c.txt");
+        assert_eq!(normalize_entry_name("logs\\"), "logs/");
+        assert_eq!(normalize_entry_name("./.."), "");
+    }
+
+    #[test]
+    fn test_check_entry_name() {
+        assert!(check_entry_name("dir/file.txt").is_ok());
+        assert!(check_entry_name("a..b/console.log").is_ok());
+        assert!(check_entry_name("/etc/passwd").is_err());
+        assert!(check_entry_name("\\share\\x").is_err());
+        assert!(check_entry_name("C:/x").is_err());
+        assert!(check_entry_name("a/../b").is_err());
+        assert!(check_entry_name("a\\..\\b").is_err());
+        assert!(check_entry_name("bad\0name").is_err());
+        assert!(check_entry_name("out/CON").is_err());
+        assert!(check_entry_name("lpt1.txt").is_err());
+    }
+
+    #[test]
+    fn test_extra_fields() {
+        let extra = [
+            0x55, 0x54, 0x05, 0x00, 0x01, 0x10, 0x20, 0x30, 0x40, // 0x5455
+            0x0a, 0x00, 0x00, 0x00, // empty 0x000a
+            0x75, 0x70, 0x09, 0x00, 0x01, // 0x7075 running past the end
+        ];
+        let fields: Vec<(u16, &[u8])> = extra_fields(&extra).collect();
+        assert_eq!(
+            fields,
+            [
+                (0x5455, &[0x01, 0x10, 0x20, 0x30, 0x40][..]),
+                (0x000a, &[][..])
+            ]
+        );
+        assert_eq!(extra_fields(&[0x01]).count(), 0);
+    }
+
+    #[test]
+    fn test_dos_attributes() {
+        let attrs = DosAttributes::from_bits(0x23);
+        assert!(attrs.read_only && attrs.hidden && attrs.archive);
+        assert!(!attrs.system && !attrs.directory);
+        assert_eq!(attrs.to_bits(), 0x23);
+    }
+
+    #[test]
+    fn test_parse_unix_owner_extra_field() {
+        // 0x7875: version 1, 4-byte uid 1000, 2-byte gid 100
+        let extra = [0x75, 0x78, 9, 0, 1, 4, 0xE8, 0x03, 0, 0, 2, 100, 0];
+        assert_eq!(parse_unix_owner_extra_field(&extra), Some((1000, 100)));
+        assert_eq!(parse_unix_owner_extra_field(&[]), None);
+    }
+
+    #[test]
+    fn test_extended_timestamp_extra_field() {
+        let at = |secs| Some(UNIX_EPOCH + Duration::from_secs(secs));
+        let ts = ExtendedTimestamp {
+            modified: at(1_700_000_000),
+            accessed: at(1_700_000_123),
+            created: None,
+        };
+        let local = ts.to_extra_field(false);
+        assert_eq!(local.len(), 4 + 1 + 8);
+        assert_eq!(parse_extended_timestamp_extra_field(&local), Some(ts));
+
+        // The central form keeps the flags but only the modification time
+        let central = ts.to_extra_field(true);
+        assert_eq!(central[4], 0x03);
+        let parsed = parse_extended_timestamp_extra_field(&central).unwrap();
+        assert_eq!(parsed.modified, ts.modified);
+        assert_eq!(parsed.accessed, None);
+
+        assert!(ExtendedTimestamp::default()
+            .to_extra_field(false)
+            .is_empty());
+        assert_eq!(parse_extended_timestamp_extra_field(&[]), None);
+    }
 }
@@ -0,0 +1,498 @@
+//! Forward-only reading of archives that cannot be seeked.
+//!
+//! [`ForwardZipReader`] walks the local file headers of an archive as its
+//! bytes arrive, so entries of a ZIP that is still being downloaded can be
+//! processed before the download finishes and without buffering it. The
+//! central directory at the end is never read. [`ByteStreamReader`] turns the
+//! chunked body of an HTTP client (`reqwest::Response::bytes_stream()`, a
+//! hyper body stream, ...) into the `AsyncRead` the reader consumes. The sync
+//! counterpart is [`StreamingZipForwardReader`](crate::StreamingZipForwardReader).
+//!
+//! ```ignore
+//! use s_zip::forward::ForwardZipReader;
+//! use tokio::io::AsyncReadExt;
+//!
+//! let response = reqwest::get("https://example.com/export.zip").await?;
+//! let mut zip = ForwardZipReader::from_byte_stream(response.bytes_stream());
+//! while let Some(mut entry) = zip.next_entry().await? {
+//!     let mut data = Vec::new();
+//!     entry.read_to_end(&mut data).await?;
+//!     println!("{}: {} bytes", entry.entry().name, data.len());
+//! }
+//! ```
+//!
+//! Limitations of reading without the central directory:
+//!
+//! * Entry metadata comes from the local header. Entries written with a
+//!   data descriptor (the default for `AsyncStreamingZipWriter`) report a
+//!   CRC-32 and sizes of 0 until their data has been read.
+//! * External attributes are not stored in local headers and read as 0.
+//! * Stored entries with a data descriptor have no detectable end and fail
+//!   with `SZipError::InvalidFormat`; Deflate and Zstandard streams mark
+//!   their own end.
+//! * Encrypted entries and unsupported compression methods can be skipped
+//!   when their size is in the local header, but reading them fails.
+//!
+//! The CRC-32 and size of each entry are checked when the next entry is
+//! requested, after any unread data has been skipped.
+
+use crate::error::{Result, SZipError};
+use crate::format::{ZipEntry, LOCAL_FILE_HEADER_SIGNATURE};
+use crate::forward_reader::DATA_DESCRIPTOR_SIGNATURE;
+use crate::sansio::{has_zip64_extra, LocalHeader, LOCAL_HEADER_LEN};
+use async_compression::tokio::bufread::DeflateDecoder;
+#[cfg(feature = "async-zstd")]
+use async_compression::tokio::bufread::ZstdDecoder;
+use futures_util::stream::Stream;
+use std::future::poll_fn;
+use std::io;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use tokio::io::{AsyncBufRead, AsyncRead, AsyncReadExt, BufReader, ReadBuf, Take};
+
+/// Adapter turning a stream of byte chunks into `AsyncRead`/`AsyncBufRead`
+///
+/// Accepts any `Stream<Item = Result<B, E>>` where `B` is a byte chunk
+/// (`bytes::Bytes`, `Vec<u8>`, ...) and `E` an error type, which covers the
+/// body streams of reqwest and hyper. Stream errors surface as
+/// `io::ErrorKind::Other` wrapping the original error.
+pub struct ByteStreamReader<S, B> {
+    stream: Pin<Box<S>>,
+    chunk: Option<B>,
+    pos: usize,
+}
+
+impl<S, B> ByteStreamReader<S, B> {
+    /// Wrap a byte-chunk stream
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream: Box::pin(stream),
+            chunk: None,
+            pos: 0,
+        }
+    }
+}
+
+impl<S, B, E> AsyncBufRead for ByteStreamReader<S, B>
+where
+    S: Stream<Item = std::result::Result<B, E>>,
+    B: AsRef<[u8]> + Unpin,
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        let this = self.get_mut();
+        loop {
+            if let Some(chunk) = &this.chunk {
+                if this.pos < chunk.as_ref().len() {
+                    break;
+                }
+            }
+            match ready!(this.stream.as_mut().poll_next(cx)) {
+                Some(Ok(chunk)) => {
+                    this.chunk = Some(chunk);
+                    this.pos = 0;
+                }
+                Some(Err(e)) => return Poll::Ready(Err(io::Error::other(e))),
+                None => return Poll::Ready(Ok(&[])),
+            }
+        }
+        let chunk = this.chunk.as_ref().map_or(&[][..], |c| c.as_ref());
+        Poll::Ready(Ok(&chunk[this.pos..]))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        self.get_mut().pos += amt;
+    }
+}
+
+impl<S, B, E> AsyncRead for ByteStreamReader<S, B>
+where
+    S: Stream<Item = std::result::Result<B, E>>,
+    B: AsRef<[u8]> + Unpin,
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let available = ready!(self.as_mut().poll_fill_buf(cx))?;
+        let n = available.len().min(buf.remaining());
+        buf.put_slice(&available[..n]);
+        self.consume(n);
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// Buffered input that counts the bytes consumed from it
+struct Input<R> {
+    inner: BufReader<R>,
+    consumed: u64,
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for Input<R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let before = buf.filled().len();
+        ready!(Pin::new(&mut self.inner).poll_read(cx, buf))?;
+        self.consumed += (buf.filled().len() - before) as u64;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncBufRead for Input<R> {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        Pin::new(&mut self.get_mut().inner).poll_fill_buf(cx)
+    }
+
+    fn consume(mut self: Pin<&mut Self>, amt: usize) {
+        self.consumed += amt as u64;
+        Pin::new(&mut self.inner).consume(amt);
+    }
+}
+
+/// Zstd decoder accepting windows up to the format's limit
+#[cfg(feature = "async-zstd")]
+fn zstd_decoder<B: AsyncBufRead>(source: B) -> ZstdDecoder<B> {
+    ZstdDecoder::with_params(
+        source,
+        &[async_compression::zstd::DParameter::window_log_max(
+            crate::writer::ZSTD_WINDOW_LOG_MAX,
+        )],
+    )
+}
+
+/// Decoder for the data of the current entry
+enum Body<R> {
+    Stored(Take<Input<R>>),
+    Deflate(DeflateDecoder<Take<Input<R>>>),
+    /// Deflate data of unknown size, followed by a data descriptor
+    DeflateStream(DeflateDecoder<Input<R>>),
+    #[cfg(feature = "async-zstd")]
+    Zstd(ZstdDecoder<Take<Input<R>>>),
+    #[cfg(feature = "async-zstd")]
+    ZstdStream(ZstdDecoder<Input<R>>),
+    /// Encrypted or unsupported data of known size, which can only be skipped
+    Opaque(Take<Input<R>>, SZipError),
+}
+
+impl<R: AsyncRead + Unpin> Body<R> {
+    fn poll_read(&mut self, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        match self {
+            Body::Stored(r) => Pin::new(r).poll_read(cx, buf),
+            Body::Deflate(r) => Pin::new(r).poll_read(cx, buf),
+            Body::DeflateStream(r) => Pin::new(r).poll_read(cx, buf),
+            #[cfg(feature = "async-zstd")]
+            Body::Zstd(r) => Pin::new(r).poll_read(cx, buf),
+            #[cfg(feature = "async-zstd")]
+            Body::ZstdStream(r) => Pin::new(r).poll_read(cx, buf),
+            Body::Opaque(_, e) => Poll::Ready(Err(io::Error::other(e.to_string()))),
+        }
+    }
+
+    /// Recover the input once the body has been read or skipped. Returns the
+    /// input and whether the entry size is known from the local header.
+    async fn into_input(self) -> Result<(Input<R>, bool)> {
+        let mut rest = match self {
+            Body::Stored(r) | Body::Opaque(r, _) => r,
+            Body::Deflate(r) => r.into_inner(),
+            #[cfg(feature = "async-zstd")]
+            Body::Zstd(r) => r.into_inner(),
+            Body::DeflateStream(r) => return Ok((r.into_inner(), false)),
+            #[cfg(feature = "async-zstd")]
+            Body::ZstdStream(r) => return Ok((r.into_inner(), false)),
+        };
+        // Padding after the end of the compressed stream, if any
+        tokio::io::copy(&mut rest, &mut tokio::io::sink()).await?;
+        Ok((rest.into_inner(), true))
+    }
+}
+
+/// Bookkeeping for the entry being read
+struct Current {
+    entry: ZipEntry,
+    data_start: u64,
+    zip64: bool,
+    crc: crc32fast::Hasher,
+    uncompressed: u64,
+    verify: bool,
+}
+
+enum State<R> {
+    Header(Input<R>),
+    Body(Body<R>, Box<Current>),
+    Done,
+    /// A previous operation failed part-way through the input
+    Poisoned,
+}
+
+/// Reads the entries of an archive in order from a forward-only source
+///
+/// See the [module documentation](self) for an example and limitations.
+pub struct ForwardZipReader<R> {
+    state: State<R>,
+}
+
+impl<S, B, E> ForwardZipReader<ByteStreamReader<S, B>>
+where
+    S: Stream<Item = std::result::Result<B, E>>,
+    B: AsRef<[u8]> + Unpin,
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    /// Read an archive from a stream of byte chunks, such as an HTTP body
+    pub fn from_byte_stream(stream: S) -> Self {
+        Self::new(ByteStreamReader::new(stream))
+    }
+}
+
+impl<R: AsyncRead + Unpin> ForwardZipReader<R> {
+    /// Read an archive from the start of `reader`
+    pub fn new(reader: R) -> Self {
+        Self {
+            state: State::Header(Input {
+                inner: BufReader::new(reader),
+                consumed: 0,
+            }),
+        }
+    }
+
+    /// Advance to the next entry, skipping any unread data of the current one
+    ///
+    /// Returns `None` once the central directory (or the end of the input)
+    /// is reached.
+    pub async fn next_entry(&mut self) -> Result<Option<ForwardEntry<'_, R>>> {
+        let mut input = match std::mem::replace(&mut self.state, State::Poisoned) {
+            State::Header(input) => input,
+            State::Body(mut body, mut current) => {
+                if !matches!(body, Body::Opaque(..)) {
+                    drain(&mut body, &mut current).await?;
+                }
+                finish_entry(body, *current).await?
+            }
+            State::Done => {
+                self.state = State::Done;
+                return Ok(None);
+            }
+            State::Poisoned => {
+                return Err(SZipError::InvalidFormat(
+                    "Archive stream is unusable after an earlier error".to_string(),
+                ))
+            }
+        };
+
+        let mut signature = [0u8; 4];
+        let offset = input.consumed;
+        let n = read_up_to(&mut input, &mut signature).await?;
+        let mut signature = u32::from_le_bytes(signature);
+        if n == 4 && signature == DATA_DESCRIPTOR_SIGNATURE && offset == 0 {
+            // Split-archive marker in front of the first header
+            let mut next = [0u8; 4];
+            input.read_exact(&mut next).await?;
+            signature = u32::from_le_bytes(next);
+        }
+        if n < 4 || signature != LOCAL_FILE_HEADER_SIGNATURE {
+            self.state = State::Done;
+            return Ok(None);
+        }
+
+        let (body, current) = read_local_header(input, offset).await?;
+        self.state = State::Body(body, Box::new(current));
+        Ok(Some(ForwardEntry { reader: self }))
+    }
+
+    fn poll_body(&mut self, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        match &mut self.state {
+            State::Body(body, current) => poll_counted(body, current, cx, buf),
+            _ => Poll::Ready(Ok(())),
+        }
+    }
+}
+
+/// An entry of a [`ForwardZipReader`], readable as its decompressed data
+pub struct ForwardEntry<'a, R> {
+    reader: &'a mut ForwardZipReader<R>,
+}
+
+impl<R> ForwardEntry<'_, R> {
+    /// Metadata from the local file header
+    pub fn entry(&self) -> &ZipEntry {
+        match &self.reader.state {
+            State::Body(_, current) => &current.entry,
+            _ => unreachable!("a ForwardEntry exists only while its body is current"),
+        }
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for ForwardEntry<'_, R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        self.reader.poll_body(cx, buf)
+    }
+}
+
+/// Read decompressed data, keeping the CRC-32 and size of the entry
+fn poll_counted<R: AsyncRead + Unpin>(
+    body: &mut Body<R>,
+    current: &mut Current,
+    cx: &mut Context<'_>,
+    buf: &mut ReadBuf<'_>,
+) -> Poll<io::Result<()>> {
+    let before = buf.filled().len();
+    ready!(body.poll_read(cx, buf))?;
+    let data = &buf.filled()[before..];
+    current.crc.update(data);
+    current.uncompressed += data.len() as u64;
+    Poll::Ready(Ok(()))
+}
+
+/// Read and discard the rest of the entry data
+async fn drain<R: AsyncRead + Unpin>(body: &mut Body<R>, current: &mut Current) -> Result<()> {
+    let mut chunk = vec![0u8; 64 * 1024];
+    loop {
+        let mut buf = ReadBuf::new(&mut chunk);
+        poll_fn(|cx| poll_counted(body, current, cx, &mut buf)).await?;
+        if buf.filled().is_empty() {
+            return Ok(());
+        }
+    }
+}
+
+/// Consume the data descriptor, if any, and check the CRC-32 and sizes
+async fn finish_entry<R: AsyncRead + Unpin>(body: Body<R>, current: Current) -> Result<Input<R>> {
+    let (mut input, known_size) = body.into_input().await?;
+    let compressed = input.consumed - current.data_start;
+    let entry = &current.entry;
+    let (crc32, compressed_size, uncompressed_size) = if known_size {
+        (entry.crc32, entry.compressed_size, entry.uncompressed_size)
+    } else {
+        let mut field = [0u8; 4];
+        input.read_exact(&mut field).await?;
+        if u32::from_le_bytes(field) == DATA_DESCRIPTOR_SIGNATURE {
+            input.read_exact(&mut field).await?;
+        }
+        let crc32 = u32::from_le_bytes(field);
+        let zip64 =
+            current.zip64 || compressed > u32::MAX as u64 || current.uncompressed > u32::MAX as u64;
+        let (compressed_size, uncompressed_size) = if zip64 {
+            (input.read_u64_le().await?, input.read_u64_le().await?)
+        } else {
+            (
+                input.read_u32_le().await? as u64,
+                input.read_u32_le().await? as u64,
+            )
+        };
+        (crc32, compressed_size, uncompressed_size)
+    };
+    if !current.verify {
+        return Ok(input);
+    }
+
+    let actual_crc = current.crc.finalize();
+    if compressed != compressed_size || current.uncompressed != uncompressed_size {
+        return Err(SZipError::InvalidFormat(format!(
+            "Entry '{}' size mismatch: expected {} bytes ({} compressed), got {} ({})",
+            entry.name, uncompressed_size, compressed_size, current.uncompressed, compressed
+        )));
+    }
+    if actual_crc != crc32 {
+        return Err(SZipError::InvalidFormat(format!(
+            "Entry '{}' CRC-32 mismatch: expected {:08x}, got {:08x}",
+            entry.name, crc32, actual_crc
+        )));
+    }
+    Ok(input)
+}
+
+/// Parse a local file header (after its signature) and set up the body decoder
+async fn read_local_header<R: AsyncRead + Unpin>(
+    mut input: Input<R>,
+    offset: u64,
+) -> Result<(Body<R>, Current)> {
+    // The signature was already read
+    let mut fixed = [0u8; LOCAL_HEADER_LEN];
+    fixed[..4].copy_from_slice(&LOCAL_FILE_HEADER_SIGNATURE.to_le_bytes());
+    input.read_exact(&mut fixed[4..]).await?;
+    let header = LocalHeader::parse(&fixed)?;
+    let mut variable = vec![0u8; header.variable_len() as usize];
+    input.read_exact(&mut variable).await?;
+
+    let method = header.compression_method;
+    let descriptor = header.flags & 0x08 != 0;
+    let entry = header.into_entry(&variable, offset);
+    let compressed_size = entry.compressed_size;
+
+    let unreadable = if entry.is_encrypted {
+        Some(SZipError::InvalidFormat(format!(
+            "Entry '{}' is encrypted, which forward-only reading does not support",
+            entry.name
+        )))
+    } else {
+        match method {
+            0 | 8 => None,
+            #[cfg(feature = "async-zstd")]
+            93 => None,
+            _ => Some(SZipError::UnsupportedCompression(method)),
+        }
+    };
+    let body = match (descriptor, unreadable) {
+        (true, Some(e)) => return Err(e),
+        (true, None) => match method {
+            8 => Body::DeflateStream(DeflateDecoder::new(input)),
+            #[cfg(feature = "async-zstd")]
+            93 => Body::ZstdStream(zstd_decoder(input)),
+            _ => {
+                return Err(SZipError::InvalidFormat(format!(
+                    "Stored entry '{}' has a data descriptor; its end cannot be found \
+                     without the central directory",
+                    entry.name
+                )))
+            }
+        },
+        (false, Some(e)) => Body::Opaque(input.take(compressed_size), e),
+        (false, None) => {
+            let data = input.take(compressed_size);
+            match method {
+                8 => Body::Deflate(DeflateDecoder::new(data)),
+                #[cfg(feature = "async-zstd")]
+                93 => Body::Zstd(zstd_decoder(data)),
+                _ => Body::Stored(data),
+            }
+        }
+    };
+    let data_start = match &body {
+        Body::Stored(r) | Body::Opaque(r, _) => r.get_ref().consumed,
+        Body::Deflate(r) => r.get_ref().get_ref().consumed,
+        Body::DeflateStream(r) => r.get_ref().consumed,
+        #[cfg(feature = "async-zstd")]
+        Body::Zstd(r) => r.get_ref().get_ref().consumed,
+        #[cfg(feature = "async-zstd")]
+        Body::ZstdStream(r) => r.get_ref().consumed,
+    };
+    let current = Current {
+        verify: !matches!(body, Body::Opaque(..)),
+        zip64: has_zip64_extra(&variable[header.name_len as usize..]),
+        entry,
+        data_start,
+        crc: crc32fast::Hasher::new(),
+        uncompressed: 0,
+    };
+    Ok((body, current))
+}
+
+/// Read into `buf` until it is full or the input ends
+async fn read_up_to<R: AsyncRead + Unpin>(input: &mut R, buf: &mut [u8]) -> Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match input.read(&mut buf[filled..]).await? {
+            0 => break,
+            n => filled += n,
+        }
+    }
+    Ok(filled)
+}
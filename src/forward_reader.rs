@@ -0,0 +1,372 @@
+//! Forward-only reading of archives from a non-seekable source.
+//!
+//! [`StreamingZipForwardReader`] walks the local file headers of an archive
+//! in order, so a ZIP arriving on stdin or through a pipe can be read without
+//! `Seek` and without buffering it. The central directory at the end is
+//! never read. The async counterpart is `forward::ForwardZipReader`.
+//!
+//! ```no_run
+//! use s_zip::StreamingZipForwardReader;
+//! use std::io::Read;
+//!
+//! let mut zip = StreamingZipForwardReader::new(std::io::stdin().lock());
+//! while let Some(mut entry) = zip.next_entry()? {
+//!     let mut data = Vec::new();
+//!     entry.read_to_end(&mut data)?;
+//!     println!("{}: {} bytes", entry.entry().name, data.len());
+//! }
+//! # Ok::<(), s_zip::SZipError>(())
+//! ```
+//!
+//! Limitations of reading without the central directory:
+//!
+//! * Entry metadata comes from the local header. Entries written with a
+//!   data descriptor report a CRC-32 and sizes of 0 until their data has
+//!   been read.
+//! * External attributes are not stored in local headers and read as 0.
+//! * Stored entries with a data descriptor have no detectable end and fail
+//!   with `SZipError::InvalidFormat`; Deflate and Zstandard streams mark
+//!   their own end.
+//! * Encrypted entries and unsupported compression methods can be skipped
+//!   when their size is in the local header, but reading them fails.
+//!
+//! The CRC-32 and size of each entry are checked when the next entry is
+//! requested, after any unread data has been skipped.
+
+use crate::error::{Result, SZipError};
+use crate::format::{ZipEntry, LOCAL_FILE_HEADER_SIGNATURE};
+use crate::sansio::{has_zip64_extra, LocalHeader, LOCAL_HEADER_LEN};
+use flate2::bufread::DeflateDecoder;
+use std::io::{self, BufRead, BufReader, Read, Take};
+
+/// Data descriptor signature (`PK\x07\x08`), also used as a split-archive marker
+pub(crate) const DATA_DESCRIPTOR_SIGNATURE: u32 = 0x0807_4b50;
+
+/// Buffered input that counts the bytes consumed from it
+struct Input<R> {
+    inner: BufReader<R>,
+    consumed: u64,
+}
+
+impl<R: Read> Read for Input<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.consumed += n as u64;
+        Ok(n)
+    }
+}
+
+impl<R: Read> BufRead for Input<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.consumed += amt as u64;
+        self.inner.consume(amt);
+    }
+}
+
+impl<R: Read> Input<R> {
+    fn read_u32_le(&mut self) -> io::Result<u32> {
+        let mut field = [0u8; 4];
+        self.read_exact(&mut field)?;
+        Ok(u32::from_le_bytes(field))
+    }
+
+    fn read_u64_le(&mut self) -> io::Result<u64> {
+        let mut field = [0u8; 8];
+        self.read_exact(&mut field)?;
+        Ok(u64::from_le_bytes(field))
+    }
+}
+
+/// Zstd decoder of one frame, accepting windows up to the format's limit
+#[cfg(feature = "zstd-support")]
+fn zstd_decoder<B: BufRead>(source: B) -> io::Result<zstd::Decoder<'static, B>> {
+    let mut decoder = zstd::Decoder::with_buffer(source)?.single_frame();
+    decoder.window_log_max(crate::writer::ZSTD_WINDOW_LOG_MAX)?;
+    Ok(decoder)
+}
+
+/// Decoder for the data of the current entry
+enum Body<R> {
+    Stored(Take<Input<R>>),
+    Deflate(DeflateDecoder<Take<Input<R>>>),
+    /// Deflate data of unknown size, followed by a data descriptor
+    DeflateStream(DeflateDecoder<Input<R>>),
+    #[cfg(feature = "zstd-support")]
+    Zstd(zstd::Decoder<'static, Take<Input<R>>>),
+    #[cfg(feature = "zstd-support")]
+    ZstdStream(zstd::Decoder<'static, Input<R>>),
+    /// Encrypted or unsupported data of known size, which can only be skipped
+    Opaque(Take<Input<R>>, SZipError),
+}
+
+impl<R: Read> Read for Body<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Body::Stored(r) => r.read(buf),
+            Body::Deflate(r) => r.read(buf),
+            Body::DeflateStream(r) => r.read(buf),
+            #[cfg(feature = "zstd-support")]
+            Body::Zstd(r) => r.read(buf),
+            #[cfg(feature = "zstd-support")]
+            Body::ZstdStream(r) => r.read(buf),
+            Body::Opaque(_, e) => Err(io::Error::other(e.to_string())),
+        }
+    }
+}
+
+impl<R: Read> Body<R> {
+    /// Recover the input once the body has been read or skipped. Returns the
+    /// input and whether the entry size is known from the local header.
+    fn into_input(self) -> Result<(Input<R>, bool)> {
+        let mut rest = match self {
+            Body::Stored(r) | Body::Opaque(r, _) => r,
+            Body::Deflate(r) => r.into_inner(),
+            #[cfg(feature = "zstd-support")]
+            Body::Zstd(r) => r.finish(),
+            Body::DeflateStream(r) => return Ok((r.into_inner(), false)),
+            #[cfg(feature = "zstd-support")]
+            Body::ZstdStream(r) => return Ok((r.finish(), false)),
+        };
+        // Padding after the end of the compressed stream, if any
+        io::copy(&mut rest, &mut io::sink())?;
+        Ok((rest.into_inner(), true))
+    }
+}
+
+/// Bookkeeping for the entry being read
+struct Current {
+    entry: ZipEntry,
+    data_start: u64,
+    zip64: bool,
+    crc: crc32fast::Hasher,
+    uncompressed: u64,
+    verify: bool,
+}
+
+enum State<R> {
+    Header(Input<R>),
+    Body(Body<R>, Box<Current>),
+    Done,
+    /// A previous operation failed part-way through the input
+    Poisoned,
+}
+
+/// Reads the entries of an archive in order from a forward-only source
+///
+/// See the [module documentation](self) for an example and limitations.
+pub struct StreamingZipForwardReader<R> {
+    state: State<R>,
+}
+
+impl<R: Read> StreamingZipForwardReader<R> {
+    /// Read an archive from the start of `reader`
+    pub fn new(reader: R) -> Self {
+        Self {
+            state: State::Header(Input {
+                inner: BufReader::new(reader),
+                consumed: 0,
+            }),
+        }
+    }
+
+    /// Advance to the next entry, skipping any unread data of the current one
+    ///
+    /// Returns `None` once the central directory (or the end of the input)
+    /// is reached.
+    pub fn next_entry(&mut self) -> Result<Option<ForwardZipEntry<'_, R>>> {
+        let mut input = match std::mem::replace(&mut self.state, State::Poisoned) {
+            State::Header(input) => input,
+            State::Body(mut body, mut current) => {
+                if !matches!(body, Body::Opaque(..)) {
+                    io::copy(&mut Counted(&mut body, &mut current), &mut io::sink())?;
+                }
+                finish_entry(body, *current)?
+            }
+            State::Done => {
+                self.state = State::Done;
+                return Ok(None);
+            }
+            State::Poisoned => {
+                return Err(SZipError::InvalidFormat(
+                    "Archive stream is unusable after an earlier error".to_string(),
+                ))
+            }
+        };
+
+        let mut signature = [0u8; 4];
+        let offset = input.consumed;
+        let n = read_up_to(&mut input, &mut signature)?;
+        let mut signature = u32::from_le_bytes(signature);
+        if n == 4 && signature == DATA_DESCRIPTOR_SIGNATURE && offset == 0 {
+            // Split-archive marker in front of the first header
+            signature = input.read_u32_le()?;
+        }
+        if n < 4 || signature != LOCAL_FILE_HEADER_SIGNATURE {
+            self.state = State::Done;
+            return Ok(None);
+        }
+
+        let (body, current) = read_local_header(input, offset)?;
+        self.state = State::Body(body, Box::new(current));
+        Ok(Some(ForwardZipEntry { reader: self }))
+    }
+}
+
+/// An entry of a [`StreamingZipForwardReader`], readable as its decompressed
+/// data
+pub struct ForwardZipEntry<'a, R> {
+    reader: &'a mut StreamingZipForwardReader<R>,
+}
+
+impl<R> ForwardZipEntry<'_, R> {
+    /// Metadata from the local file header
+    pub fn entry(&self) -> &ZipEntry {
+        match &self.reader.state {
+            State::Body(_, current) => &current.entry,
+            _ => unreachable!("a ForwardZipEntry exists only while its body is current"),
+        }
+    }
+}
+
+impl<R: Read> Read for ForwardZipEntry<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match &mut self.reader.state {
+            State::Body(body, current) => Counted(body, current).read(buf),
+            _ => Ok(0),
+        }
+    }
+}
+
+/// Decompressed data of the entry, keeping its CRC-32 and size
+struct Counted<'a, R>(&'a mut Body<R>, &'a mut Current);
+
+impl<R: Read> Read for Counted<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.0.read(buf)?;
+        self.1.crc.update(&buf[..n]);
+        self.1.uncompressed += n as u64;
+        Ok(n)
+    }
+}
+
+/// Consume the data descriptor, if any, and check the CRC-32 and sizes
+fn finish_entry<R: Read>(body: Body<R>, current: Current) -> Result<Input<R>> {
+    let (mut input, known_size) = body.into_input()?;
+    let compressed = input.consumed - current.data_start;
+    let entry = &current.entry;
+    let (crc32, compressed_size, uncompressed_size) = if known_size {
+        (entry.crc32, entry.compressed_size, entry.uncompressed_size)
+    } else {
+        let mut crc32 = input.read_u32_le()?;
+        if crc32 == DATA_DESCRIPTOR_SIGNATURE {
+            crc32 = input.read_u32_le()?;
+        }
+        let zip64 =
+            current.zip64 || compressed > u32::MAX as u64 || current.uncompressed > u32::MAX as u64;
+        let (compressed_size, uncompressed_size) = if zip64 {
+            (input.read_u64_le()?, input.read_u64_le()?)
+        } else {
+            (input.read_u32_le()? as u64, input.read_u32_le()? as u64)
+        };
+        (crc32, compressed_size, uncompressed_size)
+    };
+    if !current.verify {
+        return Ok(input);
+    }
+
+    let actual_crc = current.crc.finalize();
+    if compressed != compressed_size || current.uncompressed != uncompressed_size {
+        return Err(SZipError::InvalidFormat(format!(
+            "Entry '{}' size mismatch: expected {} bytes ({} compressed), got {} ({})",
+            entry.name, uncompressed_size, compressed_size, current.uncompressed, compressed
+        )));
+    }
+    if actual_crc != crc32 {
+        return Err(SZipError::InvalidFormat(format!(
+            "Entry '{}' CRC-32 mismatch: expected {:08x}, got {:08x}",
+            entry.name, crc32, actual_crc
+        )));
+    }
+    Ok(input)
+}
+
+/// Parse a local file header (after its signature) and set up the body decoder
+fn read_local_header<R: Read>(mut input: Input<R>, offset: u64) -> Result<(Body<R>, Current)> {
+    // The signature was already read
+    let mut fixed = [0u8; LOCAL_HEADER_LEN];
+    fixed[..4].copy_from_slice(&LOCAL_FILE_HEADER_SIGNATURE.to_le_bytes());
+    input.read_exact(&mut fixed[4..])?;
+    let header = LocalHeader::parse(&fixed)?;
+    let mut variable = vec![0u8; header.variable_len() as usize];
+    input.read_exact(&mut variable)?;
+
+    let method = header.compression_method;
+    let descriptor = header.flags & 0x08 != 0;
+    let entry = header.into_entry(&variable, offset);
+    let compressed_size = entry.compressed_size;
+    let data_start = input.consumed;
+
+    let unreadable = if entry.is_encrypted {
+        Some(SZipError::InvalidFormat(format!(
+            "Entry '{}' is encrypted, which forward-only reading does not support",
+            entry.name
+        )))
+    } else {
+        match method {
+            0 | 8 => None,
+            #[cfg(feature = "zstd-support")]
+            93 => None,
+            _ => Some(SZipError::UnsupportedCompression(method)),
+        }
+    };
+    let body = match (descriptor, unreadable) {
+        (true, Some(e)) => return Err(e),
+        (true, None) => match method {
+            8 => Body::DeflateStream(DeflateDecoder::new(input)),
+            #[cfg(feature = "zstd-support")]
+            93 => Body::ZstdStream(zstd_decoder(input)?),
+            _ => {
+                return Err(SZipError::InvalidFormat(format!(
+                    "Stored entry '{}' has a data descriptor; its end cannot be found \
+                     without the central directory",
+                    entry.name
+                )))
+            }
+        },
+        (false, Some(e)) => Body::Opaque(input.take(compressed_size), e),
+        (false, None) => {
+            let data = input.take(compressed_size);
+            match method {
+                8 => Body::Deflate(DeflateDecoder::new(data)),
+                #[cfg(feature = "zstd-support")]
+                93 => Body::Zstd(zstd_decoder(data)?),
+                _ => Body::Stored(data),
+            }
+        }
+    };
+    let current = Current {
+        verify: !matches!(body, Body::Opaque(..)),
+        zip64: has_zip64_extra(&variable[header.name_len as usize..]),
+        entry,
+        data_start,
+        crc: crc32fast::Hasher::new(),
+        uncompressed: 0,
+    };
+    Ok((body, current))
+}
+
+/// Read into `buf` until it is full or the input ends
+fn read_up_to<R: Read>(input: &mut R, buf: &mut [u8]) -> Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match input.read(&mut buf[filled..])? {
+            0 => break,
+            n => filled += n,
+        }
+    }
+    Ok(filled)
+}
@@ -0,0 +1,218 @@
+//! Reading archives that are still being written.
+//!
+//! A log that appends one ZIP archive after another, or a writer that
+//! periodically finalizes what it has, leaves a file whose newest end of
+//! central directory record is followed by bytes of an entry in progress.
+//! [`StreamingZipReader::open_growing`] scans backwards past such a trailing
+//! partial entry for the newest valid EOCD record, and
+//! [`StreamingZipReader::refresh`] repeats the scan later to pick up archives
+//! finalized since.
+//!
+//! Archives appended to a log are usually written with offsets relative to
+//! their own start; their entries are rebased onto the file. When the newest
+//! archive starts past the beginning of the file, the archive ending right
+//! before it is read as well, so every entry of the log is listed, oldest
+//! first.
+//!
+//! [`StreamingZipReader::open_growing`]: crate::StreamingZipReader::open_growing
+//! [`StreamingZipReader::refresh`]: crate::StreamingZipReader::refresh
+
+use crate::error::Result;
+use crate::format::{
+    find_zip64_eocd_offset, CentralDirectoryLocation, CENTRAL_DIRECTORY_SIGNATURE,
+    END_OF_CENTRAL_DIRECTORY_SIGNATURE,
+};
+use crate::sansio::{self, ZIP64_EOCD_LEN};
+use std::io::{Read, Seek, SeekFrom};
+
+/// Bytes read per step of the backward scan
+const SCAN_CHUNK: u64 = 64 * 1024;
+/// Size of the ZIP64 end of central directory locator
+const ZIP64_LOCATOR_LEN: u64 = 20;
+/// How far before its end an archive's EOCD record can start
+const EOCD_SEARCH_WINDOW: u64 = 65557;
+
+/// One finalized archive within the file
+#[derive(Debug)]
+pub(crate) struct Segment {
+    /// Central directory location in file offsets
+    pub location: CentralDirectoryLocation,
+    /// File offset of the archive's own offset 0
+    pub base: u64,
+    pub comment: Vec<u8>,
+}
+
+/// Find the newest valid EOCD record that starts in `floor..end`
+///
+/// Signature matches inside entry data are rejected by checking that the
+/// record describes a central directory ending right before it.
+pub(crate) fn find_latest<R: Read + Seek>(
+    file: &mut R,
+    end: u64,
+    floor: u64,
+) -> Result<Option<Segment>> {
+    let signature = END_OF_CENTRAL_DIRECTORY_SIGNATURE.to_le_bytes();
+    let mut chunk_end = end;
+    let mut buf = Vec::new();
+    while chunk_end > floor {
+        let start = chunk_end.saturating_sub(SCAN_CHUNK).max(floor);
+        // Overlap the previous chunk so a signature split between them is seen
+        let read_end = (chunk_end + 3).min(end);
+        file.seek(SeekFrom::Start(start))?;
+        buf.clear();
+        file.by_ref().take(read_end - start).read_to_end(&mut buf)?;
+        for i in (0..buf.len().saturating_sub(3)).rev() {
+            let offset = start + i as u64;
+            if offset < chunk_end && buf[i..i + 4] == signature {
+                if let Some(segment) = segment_at(file, offset, end)? {
+                    return Ok(Some(segment));
+                }
+            }
+        }
+        chunk_end = start;
+    }
+    Ok(None)
+}
+
+/// The archive whose EOCD candidate is at `eocd_offset`, if the candidate is real
+fn segment_at<R: Read + Seek>(file: &mut R, eocd_offset: u64, end: u64) -> Result<Option<Segment>> {
+    let Some(record) = read_at(
+        file,
+        eocd_offset,
+        (end - eocd_offset).min(EOCD_SEARCH_WINDOW),
+    )?
+    else {
+        return Ok(None);
+    };
+    let Ok((found, zip64)) = sansio::parse_eocd(&record, eocd_offset) else {
+        return Ok(None);
+    };
+
+    let (location, cd_end) = if zip64 {
+        // The ZIP64 record and locator sit right before the EOCD record; the
+        // offset in the locator may be relative to the archive, so it is
+        // only used to recognise the locator
+        let Some(record_offset) =
+            eocd_offset.checked_sub(ZIP64_LOCATOR_LEN + ZIP64_EOCD_LEN as u64)
+        else {
+            return Ok(None);
+        };
+        let Some(tail) = read_at(
+            file,
+            record_offset,
+            ZIP64_EOCD_LEN as u64 + ZIP64_LOCATOR_LEN,
+        )?
+        else {
+            return Ok(None);
+        };
+        if find_zip64_eocd_offset(&tail[ZIP64_EOCD_LEN..]).is_none() {
+            return Ok(None);
+        }
+        match sansio::parse_zip64_eocd(&tail) {
+            Ok(location) => (location, record_offset),
+            Err(_) => return Ok(None),
+        }
+    } else {
+        (found.location, eocd_offset)
+    };
+
+    let Some(cd_start) = cd_end.checked_sub(location.size) else {
+        return Ok(None);
+    };
+    let Some(base) = cd_start.checked_sub(location.offset) else {
+        return Ok(None);
+    };
+    if location.count > 0 {
+        match read_at(file, cd_start, 4)? {
+            Some(sig) if sig[..] == CENTRAL_DIRECTORY_SIGNATURE.to_le_bytes() => {}
+            _ => return Ok(None),
+        }
+    } else if location.size > 0 || base > 0 {
+        // An empty archive is only believed at the start of the file; in
+        // entry data a signature followed by zeros would look like one
+        return Ok(None);
+    }
+
+    Ok(Some(Segment {
+        location: CentralDirectoryLocation {
+            offset: cd_start,
+            ..location
+        },
+        base,
+        comment: found.comment,
+    }))
+}
+
+/// The archive that ends exactly at `archive_end`, if there is one
+pub(crate) fn find_ending_at<R: Read + Seek>(
+    file: &mut R,
+    archive_end: u64,
+) -> Result<Option<Segment>> {
+    // Only the last EOCD before the end qualifies; an earlier one belongs to
+    // yet another archive
+    find_latest(
+        file,
+        archive_end,
+        archive_end.saturating_sub(EOCD_SEARCH_WINDOW),
+    )
+}
+
+/// Read `len` bytes at `offset`, or `None` if the file ends first
+fn read_at<R: Read + Seek>(file: &mut R, offset: u64, len: u64) -> Result<Option<Vec<u8>>> {
+    file.seek(SeekFrom::Start(offset))?;
+    let mut buf = Vec::new();
+    file.by_ref().take(len).read_to_end(&mut buf)?;
+    Ok((buf.len() as u64 == len).then_some(buf))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StreamingZipWriter;
+    use std::io::Cursor;
+
+    fn archive(name: &str, data: &[u8]) -> Vec<u8> {
+        let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new())).unwrap();
+        writer.add_entry(name, data).unwrap();
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_finds_eocd_before_partial_entry() {
+        let mut file = archive("a.txt", b"alpha");
+        let archive_len = file.len() as u64;
+        // A partial entry larger than one scan chunk, with a stray signature
+        let mut partial = archive("b.txt", &[7u8; 100])[..40].to_vec();
+        partial.extend(std::iter::repeat_n(0x42u8, 200_000));
+        partial.extend_from_slice(&END_OF_CENTRAL_DIRECTORY_SIGNATURE.to_le_bytes());
+        partial.extend_from_slice(&[0u8; 30]);
+        file.extend(partial);
+
+        let len = file.len() as u64;
+        let segment = find_latest(&mut Cursor::new(&file), len, 0)
+            .unwrap()
+            .unwrap();
+        assert_eq!(segment.base, 0);
+        assert_eq!(segment.location.count, 1);
+        assert!(segment.location.offset + segment.location.size < archive_len);
+
+        // Nothing newer than the archive itself
+        assert!(find_latest(&mut Cursor::new(&file), len, archive_len)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_rebases_appended_archive() {
+        let mut file = archive("a.txt", b"alpha");
+        let first_len = file.len() as u64;
+        file.extend(archive("b.txt", b"beta"));
+
+        let mut source = Cursor::new(&file);
+        let len = file.len() as u64;
+        let newest = find_latest(&mut source, len, 0).unwrap().unwrap();
+        assert_eq!(newest.base, first_len);
+        let previous = find_ending_at(&mut source, newest.base).unwrap().unwrap();
+        assert_eq!(previous.base, 0);
+    }
+}
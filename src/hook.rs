@@ -0,0 +1,181 @@
+//! Observer hooks for the ZIP writers.
+//!
+//! A [`WriterHook`] registered with `add_hook` is called as the archive is
+//! produced: after each local header, for every chunk of entry data, when an
+//! entry is complete and once the central directory is written. They also
+//! receive the names `DuplicateNamePolicy::Warn` reports. Hooks can
+//! checksum content, keep an audit log or mirror entries elsewhere without
+//! touching the writer itself; an error returned from a hook aborts the
+//! write with that error.
+//!
+//! ```
+//! use s_zip::hook::{EntrySummary, WriterHook};
+//! use s_zip::{Result, StreamingZipWriter};
+//! use std::io::Cursor;
+//! use std::sync::{Arc, Mutex};
+//!
+//! struct AuditLog(Arc<Mutex<Vec<String>>>);
+//!
+//! impl WriterHook for AuditLog {
+//!     fn entry_finished(&mut self, entry: &EntrySummary<'_>) -> Result<()> {
+//!         let line = format!("{} ({} bytes)", entry.name, entry.uncompressed_size);
+//!         self.0.lock().unwrap().push(line);
+//!         Ok(())
+//!     }
+//! }
+//!
+//! let log = Arc::new(Mutex::new(Vec::new()));
+//! let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new()))?;
+//! writer.add_hook(AuditLog(log.clone()));
+//! writer.add_entry("hello.txt", b"Hello")?;
+//! writer.finish()?;
+//! assert_eq!(*log.lock().unwrap(), ["hello.txt (5 bytes)"]);
+//! # Ok::<(), s_zip::SZipError>(())
+//! ```
+
+use crate::error::Result;
+use std::time::Duration;
+
+/// Callbacks invoked while a writer produces an archive
+///
+/// Every method has an empty default, so implementations only override the
+/// events they need. Hooks run in registration order.
+pub trait WriterHook: Send {
+    /// The local header of `name` was written at `offset`
+    ///
+    /// `header` holds the bytes as first written; writers that patch sizes
+    /// into the header afterwards (`Compatibility::WindowsExplorer`) report
+    /// the final values through [`entry_finished`](Self::entry_finished).
+    fn header_written(&mut self, name: &str, offset: u64, header: &[u8]) -> Result<()> {
+        let _ = (name, offset, header);
+        Ok(())
+    }
+
+    /// A chunk of uncompressed entry data was accepted by `write_data`
+    ///
+    /// Not called for `write_entries_parallel`, which only handles the
+    /// compressed form of each entry.
+    fn data_written(&mut self, name: &str, data: &[u8]) -> Result<()> {
+        let _ = (name, data);
+        Ok(())
+    }
+
+    /// An entry named like one written before was started, under
+    /// `DuplicateNamePolicy::Warn`
+    fn duplicate_name(&mut self, name: &str) -> Result<()> {
+        let _ = name;
+        Ok(())
+    }
+
+    /// An entry is complete
+    fn entry_finished(&mut self, entry: &EntrySummary<'_>) -> Result<()> {
+        let _ = entry;
+        Ok(())
+    }
+
+    /// The central directory and end record were written
+    fn archive_finished(&mut self, archive: &ArchiveSummary) -> Result<()> {
+        let _ = archive;
+        Ok(())
+    }
+}
+
+/// Metadata of a completed entry, as recorded in the central directory
+#[derive(Debug, Clone, Copy)]
+pub struct EntrySummary<'a> {
+    /// Entry name as stored in the archive
+    pub name: &'a str,
+    /// Offset of the local header
+    pub offset: u64,
+    /// CRC-32 of the uncompressed data
+    pub crc32: u32,
+    /// Stored size, including any encryption overhead
+    pub compressed_size: u64,
+    /// Size of the data passed to the writer
+    pub uncompressed_size: u64,
+    /// ZIP compression method code
+    pub compression_method: u16,
+    /// Wall time from starting the entry until it was complete; for
+    /// `write_entries_parallel`, the time spent compressing it
+    pub elapsed: Duration,
+}
+
+impl EntrySummary<'_> {
+    /// `compressed_size / uncompressed_size`, or `1.0` for an empty entry
+    pub fn compression_ratio(&self) -> f64 {
+        if self.uncompressed_size == 0 {
+            1.0
+        } else {
+            self.compressed_size as f64 / self.uncompressed_size as f64
+        }
+    }
+
+    /// Uncompressed bytes per second over [`elapsed`](Self::elapsed)
+    pub fn throughput(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs == 0.0 {
+            0.0
+        } else {
+            self.uncompressed_size as f64 / secs
+        }
+    }
+}
+
+/// Layout of a finished archive
+#[derive(Debug, Clone, Copy)]
+pub struct ArchiveSummary {
+    /// Number of entries in the central directory
+    pub entry_count: u64,
+    /// Offset of the first central directory record
+    pub central_directory_offset: u64,
+    /// Size of the central directory records
+    pub central_directory_size: u64,
+    /// Total archive size including the end records
+    pub total_size: u64,
+}
+
+/// Hook calling a closure for every finished entry, for `on_entry_stats`
+pub(crate) struct EntryStatsHook<F>(pub(crate) F);
+
+impl<F: FnMut(&EntrySummary<'_>) + Send> WriterHook for EntryStatsHook<F> {
+    fn entry_finished(&mut self, entry: &EntrySummary<'_>) -> Result<()> {
+        (self.0)(entry);
+        Ok(())
+    }
+}
+
+/// Hooks registered on a writer, called in order
+#[derive(Default)]
+pub(crate) struct Hooks(Vec<Box<dyn WriterHook>>);
+
+impl Hooks {
+    pub(crate) fn push(&mut self, hook: Box<dyn WriterHook>) {
+        self.0.push(hook);
+    }
+
+    pub(crate) fn header_written(&mut self, name: &str, offset: u64, header: &[u8]) -> Result<()> {
+        self.0
+            .iter_mut()
+            .try_for_each(|h| h.header_written(name, offset, header))
+    }
+
+    pub(crate) fn data_written(&mut self, name: &str, data: &[u8]) -> Result<()> {
+        self.0
+            .iter_mut()
+            .try_for_each(|h| h.data_written(name, data))
+    }
+
+    pub(crate) fn duplicate_name(&mut self, name: &str) -> Result<()> {
+        self.0.iter_mut().try_for_each(|h| h.duplicate_name(name))
+    }
+
+    pub(crate) fn entry_finished(&mut self, entry: &EntrySummary<'_>) -> Result<()> {
+        self.0.iter_mut().try_for_each(|h| h.entry_finished(entry))
+    }
+
+    pub(crate) fn archive_finished(&mut self, archive: &ArchiveSummary) -> Result<()> {
+        self.0
+            .iter_mut()
+            .try_for_each(|h| h.archive_finished(archive))
+    }
+}
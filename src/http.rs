@@ -0,0 +1,125 @@
+//! Consistency checks for reading archives over HTTP range requests.
+//!
+//! A remote archive is read with many `Range` requests. If the object is
+//! replaced between two of them, later ranges come from the new version and
+//! entries fail to decode far from the cause. [`RangeGuard`] captures the
+//! `ETag` (or, failing a strong one, `Last-Modified`) of the first response
+//! and supplies the `If-Range` value for the following requests. A server
+//! whose object no longer matches answers with the whole object (`200`)
+//! instead of the range (`206`), which [`RangeGuard::check`] turns into an
+//! error converting to `SZipError::SourceChanged`.
+//!
+//! The guard is independent of the HTTP client; wire it into the `AsyncRead`
+//! or `Read` adapter that issues the range requests:
+//!
+//! ```no_run
+//! use s_zip::http::RangeGuard;
+//!
+//! # struct Response { status: u16, etag: Option<String>, last_modified: Option<String>, body: Vec<u8> }
+//! # fn get(url: &str, range: &str, if_range: Option<&str>) -> std::io::Result<Response> { unimplemented!() }
+//! fn read_range(guard: &mut RangeGuard, url: &str, start: u64, end: u64) -> std::io::Result<Vec<u8>> {
+//!     let range = format!("bytes={}-{}", start, end);
+//!     let response = get(url, &range, guard.if_range())?;
+//!     guard.check(response.status)?;
+//!     guard.observe(response.etag.as_deref(), response.last_modified.as_deref());
+//!     Ok(response.body)
+//! }
+//! ```
+
+use crate::error::range_status;
+use std::io;
+
+/// Validator of the object version a series of range requests reads
+#[derive(Debug, Clone, Default)]
+pub struct RangeGuard {
+    validator: Option<String>,
+}
+
+impl RangeGuard {
+    /// Guard that has not seen a response yet
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Guard pinned to a validator captured elsewhere, e.g. from a `HEAD`
+    /// request
+    pub fn with_validator(validator: impl Into<String>) -> Self {
+        Self {
+            validator: Some(validator.into()),
+        }
+    }
+
+    /// Record the `ETag` and `Last-Modified` headers of a response
+    ///
+    /// Only the first response that carries a usable validator counts. Weak
+    /// ETags (`W/"..."`) are not allowed in `If-Range`, so `Last-Modified` is
+    /// used instead.
+    pub fn observe(&mut self, etag: Option<&str>, last_modified: Option<&str>) {
+        if self.validator.is_some() {
+            return;
+        }
+        let strong_etag = etag.filter(|etag| !etag.starts_with("W/"));
+        self.validator = strong_etag.or(last_modified).map(str::to_string);
+    }
+
+    /// Value for the `If-Range` header of the next request, once a validator
+    /// was captured
+    pub fn if_range(&self) -> Option<&str> {
+        self.validator.as_deref()
+    }
+
+    /// Check the status of a range request sent with [`if_range`](Self::if_range)
+    ///
+    /// `206` passes. With a validator, `200` (the whole object instead of the
+    /// range) and `412` mean the object changed; without one, `200` means the
+    /// server ignored the `Range` header. Any other status is an I/O error.
+    pub fn check(&self, status: u16) -> io::Result<()> {
+        match (&self.validator, status) {
+            (_, 206) => Ok(()),
+            (Some(validator), _) => range_status(status, validator),
+            (None, 200) => Err(io::Error::other(
+                "Server ignored the Range header and sent the whole object",
+            )),
+            (None, _) => Err(io::Error::other(format!(
+                "Range request failed with HTTP {}",
+                status
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::SZipError;
+
+    #[test]
+    fn test_prefers_strong_etag() {
+        let mut guard = RangeGuard::new();
+        guard.observe(Some("W/\"weak\""), Some("Wed, 21 Oct 2015 07:28:00 GMT"));
+        assert_eq!(guard.if_range(), Some("Wed, 21 Oct 2015 07:28:00 GMT"));
+
+        let mut guard = RangeGuard::new();
+        guard.observe(Some("\"v1\""), Some("Wed, 21 Oct 2015 07:28:00 GMT"));
+        guard.observe(Some("\"v2\""), None);
+        assert_eq!(guard.if_range(), Some("\"v1\""));
+    }
+
+    #[test]
+    fn test_whole_object_after_first_range() {
+        let mut guard = RangeGuard::new();
+        assert!(guard.check(206).is_ok());
+        // Before a validator is known a 200 is not a version change
+        assert!(!matches!(
+            guard.check(200).map_err(SZipError::from),
+            Err(SZipError::SourceChanged(_))
+        ));
+
+        guard.observe(Some("\"v1\""), None);
+        assert!(guard.check(206).is_ok());
+        assert!(matches!(
+            guard.check(200).map_err(SZipError::from),
+            Err(SZipError::SourceChanged(_))
+        ));
+    }
+}
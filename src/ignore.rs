@@ -0,0 +1,374 @@
+//! Ignore rules for archiving directory trees.
+//!
+//! `StreamingZipWriter::add_dir_recursive` skips paths matched by an
+//! [`IgnoreRules`] set. The defaults cover OS and tool noise (`.DS_Store`,
+//! `Thumbs.db`, VCS directories, editor swap files); further patterns use
+//! `.gitignore` syntax and can be added one by one, loaded from a file, or
+//! picked up from the `.gitignore` files found while walking the tree.
+//! The async writer takes a [`DirOptions`], which adds include patterns,
+//! a depth limit and parallel compression.
+//!
+//! ```
+//! use s_zip::ignore::IgnoreRules;
+//!
+//! let mut rules = IgnoreRules::default();
+//! rules.add("target/").add("*.log").add("!keep.log");
+//! assert!(rules.is_ignored("src/.DS_Store", false));
+//! assert!(rules.is_ignored("target/debug/app", false));
+//! assert!(rules.is_ignored("logs/build.log", false));
+//! assert!(!rules.is_ignored("logs/keep.log", false));
+//! assert!(!rules.is_ignored("src/main.rs", false));
+//! ```
+
+use crate::error::Result;
+use std::path::Path;
+
+/// Patterns used by [`IgnoreRules::default`]
+pub const DEFAULT_PATTERNS: &[&str] = &[
+    // macOS
+    ".DS_Store",
+    "._*",
+    "__MACOSX/",
+    // Windows
+    "Thumbs.db",
+    "ehthumbs.db",
+    "desktop.ini",
+    // Version control
+    ".git/",
+    ".svn/",
+    ".hg/",
+    // Editor swap and backup files
+    "*.swp",
+    "*.swo",
+    "*~",
+    ".#*",
+    "#*#",
+];
+
+#[derive(Debug, Clone)]
+struct Rule {
+    /// Directory the pattern was loaded from, relative to the archived root
+    /// (`""` or ending in `/`)
+    base: String,
+    glob: Vec<char>,
+    negated: bool,
+    dir_only: bool,
+    /// Match the whole path below `base` rather than its last component
+    anchored: bool,
+}
+
+/// A set of `.gitignore`-style patterns
+///
+/// Later patterns take precedence, so a `!pattern` re-includes paths an
+/// earlier one excluded. As with git, nothing inside an ignored directory
+/// can be re-included.
+#[derive(Debug, Clone)]
+pub struct IgnoreRules {
+    rules: Vec<Rule>,
+    read_gitignore: bool,
+}
+
+impl Default for IgnoreRules {
+    /// The [`DEFAULT_PATTERNS`]; `.gitignore` files are not read
+    fn default() -> Self {
+        let mut rules = Self::none();
+        for pattern in DEFAULT_PATTERNS {
+            rules.add(pattern);
+        }
+        rules
+    }
+}
+
+impl IgnoreRules {
+    /// An empty rule set that ignores nothing
+    pub fn none() -> Self {
+        Self {
+            rules: Vec::new(),
+            read_gitignore: false,
+        }
+    }
+
+    /// Add one pattern in `.gitignore` syntax, relative to the archived root
+    pub fn add(&mut self, pattern: &str) -> &mut Self {
+        self.add_line("", pattern);
+        self
+    }
+
+    /// Add every pattern from `.gitignore`-formatted text (comments and
+    /// blank lines are skipped)
+    pub fn add_patterns(&mut self, contents: &str) -> &mut Self {
+        self.add_lines("", contents);
+        self
+    }
+
+    /// Add the patterns from a `.gitignore`-formatted file
+    pub fn add_gitignore_file<P: AsRef<Path>>(&mut self, path: P) -> Result<&mut Self> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(self.add_patterns(&contents))
+    }
+
+    /// Also apply the `.gitignore` file of every directory walked, scoped to
+    /// that directory as git does (default: false)
+    pub fn set_read_gitignore(&mut self, enabled: bool) -> &mut Self {
+        self.read_gitignore = enabled;
+        self
+    }
+
+    /// Whether `path` (relative to the archived root, `/`-separated) is
+    /// ignored, either itself or through one of its parent directories
+    pub fn is_ignored(&self, path: &str, is_dir: bool) -> bool {
+        let path = path.trim_end_matches('/');
+        let mut parents = path.match_indices('/').map(|(i, _)| &path[..i]);
+        parents.any(|dir| self.matches(dir, true)) || self.matches(path, is_dir)
+    }
+
+    /// Whether `.gitignore` files are applied while walking, see
+    /// [`set_read_gitignore`](Self::set_read_gitignore)
+    pub fn reads_gitignore(&self) -> bool {
+        self.read_gitignore
+    }
+
+    /// Whether the last rule matching `path` itself excludes it; parents are
+    /// not checked (the directory walk never enters ignored directories)
+    pub(crate) fn matches(&self, path: &str, is_dir: bool) -> bool {
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| rule_matches(rule, path, is_dir))
+            .is_some_and(|rule| !rule.negated)
+    }
+
+    /// Add `.gitignore` lines found in directory `base` (`""` or ending in `/`)
+    pub(crate) fn add_lines(&mut self, base: &str, contents: &str) {
+        for line in contents.lines() {
+            self.add_line(base, line);
+        }
+    }
+
+    fn add_line(&mut self, base: &str, line: &str) {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return;
+        }
+        let (negated, pattern) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        // `\#` and `\!` escape a leading special character
+        let pattern = pattern.strip_prefix('\\').unwrap_or(pattern);
+        let (dir_only, pattern) = match pattern.strip_suffix('/') {
+            Some(rest) => (true, rest),
+            None => (false, pattern),
+        };
+        let anchored = pattern.contains('/');
+        let pattern = pattern.strip_prefix('/').unwrap_or(pattern);
+        if pattern.is_empty() {
+            return;
+        }
+        self.rules.push(Rule {
+            base: base.to_string(),
+            glob: pattern.chars().collect(),
+            negated,
+            dir_only,
+            anchored,
+        });
+    }
+}
+
+/// What an async directory walk adds, see
+/// `AsyncStreamingZipWriter::add_dir_recursive_with_options`
+///
+/// ```
+/// use s_zip::ignore::DirOptions;
+///
+/// let options = DirOptions::default()
+///     .with_include("*.rs")
+///     .with_include("Cargo.toml")
+///     .with_exclude("target/")
+///     .with_max_depth(3);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DirOptions {
+    /// Paths left out, with their contents. Default: [`IgnoreRules::default`].
+    pub ignore: IgnoreRules,
+    /// Patterns in `.gitignore` syntax; when any are given, only files
+    /// matching one are added and no directory entries are written.
+    /// Default: none, every file is added.
+    pub include: Vec<String>,
+    /// How many directory levels below the root are entered; `Some(0)` adds
+    /// only the files directly inside it. Default: `None`, no limit.
+    pub max_depth: Option<usize>,
+    /// Compress the files with `write_entries_parallel` once the walk is
+    /// done, instead of one by one as they are found. Directory entries then
+    /// come first. Default: `None`.
+    #[cfg(feature = "async")]
+    pub parallel: Option<crate::ParallelConfig>,
+}
+
+impl DirOptions {
+    /// Use `ignore` to leave paths out
+    pub fn with_ignore(mut self, ignore: IgnoreRules) -> Self {
+        self.ignore = ignore;
+        self
+    }
+
+    /// Leave out paths matching `pattern`, in `.gitignore` syntax
+    pub fn with_exclude(mut self, pattern: &str) -> Self {
+        self.ignore.add(pattern);
+        self
+    }
+
+    /// Add only files matching `pattern` (or another include pattern)
+    pub fn with_include(mut self, pattern: impl Into<String>) -> Self {
+        self.include.push(pattern.into());
+        self
+    }
+
+    /// Enter at most `depth` directory levels below the root
+    pub fn with_max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Compress the files in parallel with `config`
+    #[cfg(feature = "async")]
+    pub fn with_parallel(mut self, config: crate::ParallelConfig) -> Self {
+        self.parallel = Some(config);
+        self
+    }
+
+    /// The include patterns as a rule set, or `None` to add every file
+    #[cfg(feature = "async")]
+    pub(crate) fn include_rules(&self) -> Option<IgnoreRules> {
+        if self.include.is_empty() {
+            return None;
+        }
+        let mut rules = IgnoreRules::none();
+        for pattern in &self.include {
+            rules.add(pattern);
+        }
+        Some(rules)
+    }
+}
+
+fn rule_matches(rule: &Rule, path: &str, is_dir: bool) -> bool {
+    if rule.dir_only && !is_dir {
+        return false;
+    }
+    let Some(rel) = path.strip_prefix(rule.base.as_str()) else {
+        return false;
+    };
+    let subject = if rule.anchored {
+        rel
+    } else {
+        rel.rsplit('/').next().unwrap_or(rel)
+    };
+    let text: Vec<char> = subject.chars().collect();
+    glob_match(&rule.glob, &text)
+}
+
+/// Match `text` against a glob: `*` and `?` stay within one path component,
+/// `**` crosses components, `[...]` is a character class
+fn glob_match(pattern: &[char], text: &[char]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some('*') if pattern.get(1) == Some(&'*') => {
+            let rest = &pattern[2..];
+            // "**/" also matches zero directories
+            if let Some(after) = rest.strip_prefix(&['/']) {
+                if glob_match(after, text) {
+                    return true;
+                }
+            }
+            (0..=text.len()).any(|i| glob_match(rest, &text[i..]))
+        }
+        Some('*') => (0..=text.len())
+            .take_while(|&i| i == 0 || text[i - 1] != '/')
+            .any(|i| glob_match(&pattern[1..], &text[i..])),
+        Some('?') => {
+            text.first().is_some_and(|&c| c != '/') && glob_match(&pattern[1..], &text[1..])
+        }
+        Some('[') => match (text.first(), class_match(&pattern[1..], text.first())) {
+            (Some(_), Some((true, len))) => glob_match(&pattern[1 + len..], &text[1..]),
+            (_, Some((false, _))) | (None, Some(_)) => false,
+            // No closing bracket: a literal '['
+            (_, None) => text.first() == Some(&'[') && glob_match(&pattern[1..], &text[1..]),
+        },
+        Some('\\') if pattern.len() > 1 => {
+            text.first() == Some(&pattern[1]) && glob_match(&pattern[2..], &text[1..])
+        }
+        Some(c) => text.first() == Some(c) && glob_match(&pattern[1..], &text[1..]),
+    }
+}
+
+/// Match one character against the class body after `[`, returning whether
+/// it matched and the class length including the closing `]`
+fn class_match(body: &[char], c: Option<&char>) -> Option<(bool, usize)> {
+    let (negated, start) = match body.first() {
+        Some('!') | Some('^') => (true, 1),
+        _ => (false, 0),
+    };
+    // A ']' right after the opening bracket is literal
+    let close = body
+        .iter()
+        .enumerate()
+        .skip(start + 1)
+        .find(|(_, &ch)| ch == ']')
+        .map(|(i, _)| i)?;
+    let set = &body[start..close];
+    let matched = c.is_some_and(|&c| {
+        let mut i = 0;
+        let mut found = false;
+        while i < set.len() {
+            if i + 2 < set.len() && set[i + 1] == '-' {
+                found |= (set[i]..=set[i + 2]).contains(&c);
+                i += 3;
+            } else {
+                found |= set[i] == c;
+                i += 1;
+            }
+        }
+        found
+    });
+    Some((matched != negated && c != Some(&'/'), close + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn glob(pattern: &str, text: &str) -> bool {
+        let p: Vec<char> = pattern.chars().collect();
+        let t: Vec<char> = text.chars().collect();
+        glob_match(&p, &t)
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob("*.swp", ".main.rs.swp"));
+        assert!(!glob("*.swp", "dir/a.swp"));
+        assert!(glob("**/build", "build"));
+        assert!(glob("**/build", "a/b/build"));
+        assert!(glob("docs/**", "docs/a/b.md"));
+        assert!(glob("file?.[ch]", "file1.c"));
+        assert!(!glob("file?.[!ch]", "file1.c"));
+        assert!(glob("log[0-9]", "log7"));
+        assert!(glob("[", "["));
+    }
+
+    #[test]
+    fn test_gitignore_semantics() {
+        let mut rules = IgnoreRules::none();
+        rules.add_patterns("# build output\n/out\nbuild/\n*.tmp\n!important.tmp\n");
+        rules.add_lines("sub/", "local.txt\n");
+
+        assert!(rules.is_ignored("out", true));
+        assert!(!rules.is_ignored("src/out", true));
+        assert!(rules.is_ignored("src/build/x.o", false));
+        assert!(!rules.is_ignored("src/build", false));
+        assert!(rules.is_ignored("a/b.tmp", false));
+        assert!(!rules.is_ignored("a/important.tmp", false));
+        assert!(rules.is_ignored("sub/deeper/local.txt", false));
+        assert!(!rules.is_ignored("local.txt", false));
+    }
+}
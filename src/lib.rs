@@ -70,10 +70,50 @@
 //! # Ok::<(), s_zip::SZipError>(())
 //! ```
 
+mod append;
+mod autostore;
+mod block_deflate;
+pub mod budget;
+pub mod capabilities;
+pub mod compare;
+mod deadline;
+pub mod decoder;
+pub mod dostime;
+pub mod encoding;
 pub mod error;
+pub mod extract;
 pub mod format;
+pub mod forward_reader;
+mod growing;
+pub mod hook;
+pub mod http;
+pub mod ignore;
+#[cfg(feature = "lzma")]
+mod lzma;
+#[cfg(feature = "manifest")]
+pub mod manifest;
+pub mod merge;
+pub mod names;
+pub mod nested;
+#[cfg(any(unix, windows))]
+pub mod pread;
+pub mod quirks;
+pub mod raw;
 pub mod reader;
+pub mod report;
+pub mod retry;
+pub mod rewrite;
+pub mod salvage;
+pub mod sansio;
+pub mod sfx;
+#[cfg(feature = "signing")]
+pub mod signing;
+pub mod split;
+pub mod throttle;
+pub mod verify;
 pub mod writer;
+#[cfg(feature = "zstd-support")]
+pub mod zstd_dict;
 
 #[cfg(feature = "encryption")]
 pub mod encryption;
@@ -93,31 +133,68 @@ macro_rules! trace {
 }
 pub(crate) use trace;
 
+/// Emit a `tracing::warn!` event when the `tracing` feature is enabled.
+/// Expands to nothing when the feature is off, with zero runtime cost.
+#[cfg(feature = "tracing")]
+macro_rules! trace_warn {
+    ($($arg:tt)*) => { ::tracing::warn!($($arg)*) };
+}
+#[cfg(not(feature = "tracing"))]
+macro_rules! trace_warn {
+    ($($arg:tt)*) => {};
+}
+pub(crate) use trace_warn;
+
 #[cfg(feature = "async")]
 pub mod async_writer;
 
 #[cfg(feature = "async")]
 pub mod async_reader;
 
+#[cfg(feature = "async")]
+mod double_buffer;
+
 #[cfg(feature = "async")]
 pub mod parallel;
 
 #[cfg(feature = "async")]
 pub mod seekless;
 
+#[cfg(feature = "async")]
+pub mod blocking;
+
+#[cfg(feature = "async")]
+pub mod forward;
+
 #[cfg(any(feature = "cloud-s3", feature = "cloud-gcs"))]
 pub mod cloud;
 
+pub use capabilities::{capabilities, Capabilities};
+pub use compare::{are_equivalent, EquivalenceOptions};
+pub use dostime::DosDateTime;
 pub use error::{Result, SZipError};
-pub use format::ZipEntry;
-pub use reader::StreamingZipReader;
-pub use writer::{CompressionMethod, StreamingZipWriter};
+pub use extract::{ExtractConfig, ExtractOptions};
+pub use format::{CentralDirectoryLocation, DosAttributes, ExtendedTimestamp, ZipEntry};
+pub use forward_reader::{ForwardZipEntry, StreamingZipForwardReader};
+pub use names::{EntryName, NameTable};
+pub use quirks::QuirksMode;
+pub use reader::{
+    CompactZipReader, GenericZipReader, IndexedZipReader, LazyEntries, ReaderOptions,
+    StreamingZipReader, ZipEntryReader,
+};
+pub use report::FormatReport;
+pub use rewrite::{Rewrite, RewriteReport};
+pub use salvage::{LostEntry, SalvageReport};
+pub use verify::{EntryProblem, EntryReport, VerifyReport};
+#[cfg(feature = "zstd-support")]
+pub use writer::ZstdOptions;
+pub use writer::{Compatibility, CompressionMethod, DuplicateNamePolicy, StreamingZipWriter};
 
 /// Options for a ZIP entry controlling metadata written to the local file header.
 ///
 /// Use with `start_entry_with_options()` on either `StreamingZipWriter` or
-/// `AsyncStreamingZipWriter`. Fields default to "no metadata" (zero timestamp,
-/// no permissions).
+/// `AsyncStreamingZipWriter`. Fields default to the current time and no
+/// permissions; set `mtime` explicitly for reproducible archives.
 ///
 /// # Example
 /// ```no_run
@@ -125,10 +202,9 @@ pub use writer::{CompressionMethod, StreamingZipWriter};
 /// # use std::time::SystemTime;
 /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
 /// let mut writer = StreamingZipWriter::new("output.zip")?;
-/// let opts = EntryOptions {
-///     mtime: Some(SystemTime::now()),
-///     unix_mode: Some(0o644),
-/// };
+/// let opts = EntryOptions::default()
+///     .with_mtime(SystemTime::now())
+///     .with_unix_mode(0o644);
 /// writer.start_entry_with_options("file.txt", opts)?;
 /// writer.write_data(b"Hello")?;
 /// writer.finish()?;
@@ -136,69 +212,84 @@ pub use writer::{CompressionMethod, StreamingZipWriter};
 /// # }
 /// ```
 #[derive(Debug, Clone, Default)]
+#[non_exhaustive]
 pub struct EntryOptions {
-    /// Last-modified time. Written as MS-DOS time/date in the local header.
-    /// If `None`, the timestamp fields are written as zero (no date).
+    /// Last-modified time. Written as MS-DOS time/date in the local header
+    /// and the central directory. If `None`, the time the entry is started is used.
+    ///
+    /// When set, it is also written with second precision in UTC as an
+    /// extended timestamp extra field (ID 0x5455).
     pub mtime: Option<std::time::SystemTime>,
+    /// Last-accessed time, written to the extended timestamp extra field
+    /// (ID 0x5455) in the local header. If `None`, no access time is stored.
+    pub atime: Option<std::time::SystemTime>,
     /// Unix file permission bits (e.g. `0o644`, `0o755`).
-    /// Written as a Unix extra field (ID 0x7875) in the local and central headers.
-    /// If `None`, no Unix extra field is written.
+    /// Written to the upper 16 bits of the external attributes, with the
+    /// regular-file or directory type added when the mode has none, and the
+    /// entry is marked as made on Unix so extractors restore the mode. A Unix
+    /// extra field (ID 0x7875) is written to the local header as well.
+    /// If `None`, no Unix mode is written.
     pub unix_mode: Option<u32>,
+    /// MS-DOS / Windows attributes (read-only, hidden, system, ...).
+    /// Written to the low byte of the external attributes in the central directory.
+    pub dos_attributes: Option<DosAttributes>,
 }
 
 impl EntryOptions {
-    /// Convert `SystemTime` to MS-DOS date/time packed into a `u32`.
-    ///
-    /// MS-DOS time format (16-bit):
-    ///   bits 15-11: hours (0-23)
-    ///   bits 10-5:  minutes (0-59)
-    ///   bits 4-0:   seconds / 2 (0-29)
-    ///
-    /// MS-DOS date format (16-bit):
-    ///   bits 15-9: year - 1980 (0-127 → 1980-2107)
-    ///   bits 8-5:  month (1-12)
-    ///   bits 4-0:  day (1-31)
-    pub(crate) fn msdos_datetime(&self) -> (u16, u16) {
-        use std::time::{Duration, UNIX_EPOCH};
+    /// Set the last-modified time
+    pub fn with_mtime(mut self, mtime: std::time::SystemTime) -> Self {
+        self.mtime = Some(mtime);
+        self
+    }
+
+    /// Set the last-accessed time
+    pub fn with_atime(mut self, atime: std::time::SystemTime) -> Self {
+        self.atime = Some(atime);
+        self
+    }
+
+    /// Set the Unix permission bits
+    pub fn with_unix_mode(mut self, mode: u32) -> Self {
+        self.unix_mode = Some(mode);
+        self
+    }
+
+    /// Set the MS-DOS / Windows attributes
+    pub fn with_dos_attributes(mut self, attributes: DosAttributes) -> Self {
+        self.dos_attributes = Some(attributes);
+        self
+    }
 
-        let Some(mtime) = self.mtime else {
-            return (0, 0);
+    /// Modification time, and Unix mode on Unix, from file system metadata.
+    pub(crate) fn from_metadata(metadata: &std::fs::Metadata) -> Self {
+        #[cfg_attr(not(unix), allow(unused_mut))]
+        let mut options = EntryOptions {
+            mtime: metadata.modified().ok(),
+            ..Default::default()
         };
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            options.unix_mode = Some(metadata.permissions().mode() & 0o7777);
+        }
+        options
+    }
+
+    /// Convert `mtime` to MS-DOS `(time, date)` words, using the current time if unset.
+    pub(crate) fn msdos_datetime(&self) -> (u16, u16) {
+        let dt = self
+            .mtime
+            .map_or_else(DosDateTime::now, DosDateTime::from_system_time);
+        (dt.time, dt.date)
+    }
 
-        // Seconds since Unix epoch; fall back to zero on out-of-range times
-        let secs = mtime
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or(Duration::ZERO)
-            .as_secs();
-
-        // Convert Unix timestamp to calendar (simple Gregorian, no DST)
-        let secs_per_day = 86400u64;
-        let days_since_epoch = secs / secs_per_day;
-        let time_of_day = secs % secs_per_day;
-
-        let hour = (time_of_day / 3600) as u16;
-        let minute = ((time_of_day % 3600) / 60) as u16;
-        let second = (time_of_day % 60) as u16;
-
-        // Days since 1970-01-01 → calendar date (proleptic Gregorian)
-        let z = days_since_epoch as i64 + 719_468;
-        let era = if z >= 0 { z } else { z - 146_096 } / 146_097;
-        let doe = (z - era * 146_097) as u32;
-        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
-        let y = yoe as i64 + era * 400;
-        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
-        let mp = (5 * doy + 2) / 153;
-        let day = doy - (153 * mp + 2) / 5 + 1;
-        let month = if mp < 10 { mp + 3 } else { mp - 9 };
-        let year = if month <= 2 { y + 1 } else { y };
-
-        // Clamp to MS-DOS range (1980-2107)
-        let dos_year = (year.clamp(1980, 2107) - 1980) as u16;
-
-        let dos_time = (hour << 11) | (minute << 5) | (second / 2);
-        let dos_date = (dos_year << 9) | ((month as u16) << 5) | (day as u16);
-
-        (dos_time, dos_date)
+    /// Times for the extended timestamp extra field (ID 0x5455)
+    pub(crate) fn extended_timestamp(&self) -> ExtendedTimestamp {
+        ExtendedTimestamp {
+            modified: self.mtime,
+            accessed: self.atime,
+            created: None,
+        }
     }
 
     /// Build the Unix extra field (ID 0x7875 "Info-ZIP New Unix") carrying uid=0, gid=0.
@@ -227,11 +318,17 @@ impl EntryOptions {
         field
     }
 
-    /// Compute external file attributes from unix_mode for the central directory.
-    /// Returns 0 if no unix_mode is set.
-    #[allow(dead_code)]
-    pub(crate) fn external_attrs(&self) -> u32 {
-        self.unix_mode.map(|m| m << 16).unwrap_or(0)
+    /// Compute external file attributes for the central directory: unix_mode
+    /// (with a file type) in the high 16 bits, DOS attributes in the low byte.
+    pub(crate) fn external_attrs(&self, directory: bool) -> u32 {
+        let with_type = |mode: u32| match mode & 0o170000 {
+            0 if directory => mode | 0o040000,
+            0 => mode | 0o100000,
+            _ => mode,
+        };
+        let unix = self.unix_mode.map(|m| with_type(m) << 16).unwrap_or(0);
+        let dos = self.dos_attributes.map(|a| a.to_bits() as u32).unwrap_or(0);
+        unix | dos
     }
 }
 
@@ -289,10 +386,13 @@ pub use encryption::AesStrength;
 pub use seekless::SeeklessZipWriter;
 
 #[cfg(feature = "async")]
-pub use async_reader::{AsyncStreamingZipReader, GenericAsyncZipReader};
+pub use async_reader::{AsyncStreamingZipReader, AsyncZipEntryReader, GenericAsyncZipReader};
+
+#[cfg(feature = "async")]
+pub use forward::{ByteStreamReader, ForwardEntry, ForwardZipReader};
 
 #[cfg(feature = "async")]
-pub use parallel::{ParallelConfig, ParallelEntry};
+pub use parallel::{ParallelConfig, ParallelEntry, ParallelSource, ReaderFactory};
 
 #[cfg(test)]
 mod tests {
@@ -336,6 +436,12 @@ mod tests {
 
         #[cfg(feature = "zstd-support")]
         assert_eq!(CompressionMethod::Zstd.to_zip_method(), 93);
+        #[cfg(feature = "bzip2")]
+        assert_eq!(CompressionMethod::Bzip2.to_zip_method(), 12);
+        #[cfg(feature = "lzma")]
+        assert_eq!(CompressionMethod::Lzma.to_zip_method(), 14);
+        #[cfg(feature = "lzma")]
+        assert_eq!(CompressionMethod::Xz.to_zip_method(), 95);
     }
 
     #[test]
@@ -0,0 +1,129 @@
+//! LZMA (method 14) and XZ (method 95) entry data.
+//!
+//! An XZ entry is a complete `.xz` stream. An LZMA entry is raw LZMA1 data
+//! behind a small header of its own (APPNOTE 5.8.8): the LZMA SDK version
+//! (two bytes), the length of the properties (two bytes, always 5) and the
+//! properties. General purpose bit 1 says the data ends with an end marker;
+//! without it the decoder stops at the uncompressed size.
+//!
+//! liblzma's raw LZMA1 decoder needs the end marker, so for reading the ZIP
+//! header is turned into a `.lzma` header, which also carries the size.
+
+use crate::error::{Result, SZipError};
+use liblzma::stream::{Check, Filters, LzmaOptions, Stream};
+use std::io::{self, Chain, Cursor, Read};
+
+/// Decoder of entry data read from `R`
+pub(crate) type Decoder<R> = liblzma::read::XzDecoder<Chain<Cursor<Vec<u8>>, R>>;
+
+/// Length of the header in front of LZMA entry data
+pub(crate) const ZIP_HEADER_LEN: usize = 9;
+
+/// General purpose flag of an LZMA entry whose data ends with an end marker
+pub(crate) const FLAG_EOS_MARKER: u16 = 0x0002;
+
+/// LZMA SDK version written to the header (9.20, as 7-Zip does)
+const SDK_VERSION: [u8; 2] = [9, 20];
+
+/// Encoder for `method` (14 or 95) at preset `level`, and the bytes to write
+/// before its output
+pub(crate) fn encoder(method: u16, level: u32) -> io::Result<(Vec<u8>, Stream)> {
+    let level = level.min(9);
+    if method == 95 {
+        return Ok((Vec::new(), Stream::new_easy_encoder(level, Check::Crc64)?));
+    }
+    let mut options = LzmaOptions::new_preset(level)?;
+    // Spelled out so the header can be written before any data
+    let (lc, lp, pb) = (3, 0, 2);
+    let dict_size = preset_dict_size(level);
+    options
+        .literal_context_bits(lc)
+        .literal_position_bits(lp)
+        .position_bits(pb)
+        .dict_size(dict_size);
+    let mut header = Vec::with_capacity(ZIP_HEADER_LEN);
+    header.extend_from_slice(&SDK_VERSION);
+    header.extend_from_slice(&5u16.to_le_bytes());
+    header.push(((pb * 5 + lp) * 9 + lc) as u8);
+    header.extend_from_slice(&dict_size.to_le_bytes());
+    // Raw LZMA1 always ends with an end marker
+    let stream = Stream::new_raw_encoder(Filters::new().lzma1(&options))?;
+    Ok((header, stream))
+}
+
+/// Dictionary size of an xz preset
+fn preset_dict_size(level: u32) -> u32 {
+    const MIB: u32 = 1 << 20;
+    match level {
+        0 => 256 << 10,
+        1 => MIB,
+        2 => 2 * MIB,
+        3 | 4 => 4 * MIB,
+        5 | 6 => 8 * MIB,
+        7 => 16 * MIB,
+        8 => 32 * MIB,
+        _ => 64 * MIB,
+    }
+}
+
+/// `.lzma` header for LZMA entry data starting with `zip_header`
+///
+/// `size` is the uncompressed size, or `None` when the data ends with an
+/// end marker.
+pub(crate) fn alone_header(zip_header: &[u8], size: Option<u64>) -> Result<Vec<u8>> {
+    let props_len = u16::from_le_bytes([zip_header[2], zip_header[3]]);
+    if props_len != 5 {
+        return Err(SZipError::InvalidFormat(format!(
+            "LZMA properties are {} bytes, expected 5",
+            props_len
+        )));
+    }
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&zip_header[4..ZIP_HEADER_LEN]);
+    header.extend_from_slice(&size.unwrap_or(u64::MAX).to_le_bytes());
+    Ok(header)
+}
+
+/// Uncompressed size to put in the `.lzma` header of an entry
+pub(crate) fn known_size(flags: u16, uncompressed_size: u64) -> Option<u64> {
+    (flags & FLAG_EOS_MARKER == 0).then_some(uncompressed_size)
+}
+
+/// Decoder for the data of an LZMA (14) or XZ (95) entry
+pub(crate) fn decoder<R: Read>(
+    method: u16,
+    mut input: R,
+    flags: u16,
+    uncompressed_size: u64,
+) -> Result<Decoder<R>> {
+    if method == 95 {
+        let input = Cursor::new(Vec::new()).chain(input);
+        return Ok(liblzma::read::XzDecoder::new(input));
+    }
+    let mut zip_header = [0u8; ZIP_HEADER_LEN];
+    input.read_exact(&mut zip_header)?;
+    let header = alone_header(&zip_header, known_size(flags, uncompressed_size))?;
+    let stream = Stream::new_lzma_decoder(u64::MAX).map_err(io::Error::from)?;
+    Ok(liblzma::read::XzDecoder::new_stream(
+        Cursor::new(header).chain(input),
+        stream,
+    ))
+}
+
+/// Read the header of LZMA entry data from `input`, returning the `.lzma`
+/// header to decode the rest with
+#[cfg(feature = "async")]
+pub(crate) async fn read_alone_header<R>(
+    input: &mut R,
+    flags: u16,
+    uncompressed_size: u64,
+) -> Result<Vec<u8>>
+where
+    R: tokio::io::AsyncRead + Unpin,
+{
+    use tokio::io::AsyncReadExt;
+
+    let mut zip_header = [0u8; ZIP_HEADER_LEN];
+    input.read_exact(&mut zip_header).await?;
+    alone_header(&zip_header, known_size(flags, uncompressed_size))
+}
@@ -0,0 +1,329 @@
+//! Integrity manifests.
+//!
+//! With `set_manifest(true)`, `StreamingZipWriter` hashes every entry with
+//! SHA-256 as it is written and appends [`MANIFEST_ENTRY`] as the final
+//! entry: one line per entry with its SHA-256, CRC-32, external attributes
+//! (which hold the Unix mode), size and name. `verify_against_manifest()` on
+//! `StreamingZipReader` re-hashes the entries and reports any that were
+//! modified, removed or added since. Unlike
+//! signing (see the `signing` feature) this only detects accidental damage and careless
+//! edits; anyone can rewrite the manifest along with the data.
+//!
+//! ```
+//! use s_zip::{StreamingZipReader, StreamingZipWriter};
+//! use std::io::Cursor;
+//!
+//! let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new()))?;
+//! writer.set_manifest(true);
+//! writer.add_entry("data.csv", b"a,b,c")?;
+//! let zip = writer.finish()?.into_inner();
+//!
+//! let mut reader = StreamingZipReader::from_vec(zip)?;
+//! let report = reader.verify_against_manifest()?;
+//! assert!(report.is_ok());
+//! assert_eq!(report.verified, ["data.csv"]);
+//! # Ok::<(), s_zip::SZipError>(())
+//! ```
+
+use crate::error::{Result, SZipError};
+use crate::hook::{EntrySummary, WriterHook};
+use crate::reader::StreamingZipReader;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::io::{Read, Seek};
+use std::sync::{Arc, Mutex};
+
+/// Name of the manifest entry written by `set_manifest(true)`
+pub const MANIFEST_ENTRY: &str = "META-INF/S-ZIP.MANIFEST";
+
+/// First line of a manifest
+const MANIFEST_MAGIC: &str = "S-ZIP-MANIFEST 1";
+
+/// Digest, size and attributes of one entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestEntry {
+    /// Entry name as stored in the archive
+    pub name: String,
+    /// Uncompressed size
+    pub size: u64,
+    /// CRC-32 of the uncompressed data
+    pub crc32: u32,
+    /// SHA-256 of the uncompressed data
+    pub sha256: [u8; 32],
+    /// External file attributes from the central directory; the Unix mode
+    /// in the high 16 bits for entries made on Unix
+    pub external_attrs: u32,
+}
+
+/// Entry digests in the order the entries were written
+///
+/// The text form starts with the line `S-ZIP-MANIFEST 1`, followed by one
+/// `<sha256 hex> <crc32 hex> <external attributes hex> <size> <name>` line
+/// per entry.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Manifest {
+    /// One record per entry
+    pub entries: Vec<ManifestEntry>,
+}
+
+impl Manifest {
+    /// Parse the text form
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        let malformed = || SZipError::InvalidFormat("Malformed manifest".to_string());
+        let text = std::str::from_utf8(bytes).map_err(|_| malformed())?;
+        let mut lines = text.lines();
+        if lines.next() != Some(MANIFEST_MAGIC) {
+            return Err(malformed());
+        }
+        let entries = lines
+            .map(|line| parse_line(line).ok_or_else(malformed))
+            .collect::<Result<_>>()?;
+        Ok(Self { entries })
+    }
+
+    /// Render the text form
+    ///
+    /// Fails for entry names containing line breaks, which the format cannot
+    /// represent.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut text = format!("{}\n", MANIFEST_MAGIC);
+        for entry in &self.entries {
+            if entry.name.contains(['\n', '\r']) {
+                return Err(SZipError::InvalidFormat(format!(
+                    "Entry name {:?} contains a line break and cannot be listed in a manifest",
+                    entry.name
+                )));
+            }
+            text.push_str(&format!(
+                "{} {:08x} {:08x} {} {}\n",
+                to_hex(&entry.sha256),
+                entry.crc32,
+                entry.external_attrs,
+                entry.size,
+                entry.name
+            ));
+        }
+        Ok(text.into_bytes())
+    }
+}
+
+/// Parse `<sha256 hex> <crc32 hex> <external attributes hex> <size> <name>`
+fn parse_line(line: &str) -> Option<ManifestEntry> {
+    let (digest, rest) = line.split_once(' ')?;
+    let (crc32, rest) = rest.split_once(' ')?;
+    let (external_attrs, rest) = rest.split_once(' ')?;
+    let (size, name) = rest.split_once(' ')?;
+    Some(ManifestEntry {
+        name: name.to_string(),
+        size: size.parse().ok()?,
+        crc32: u32::from_str_radix(crc32, 16).ok()?,
+        sha256: from_hex(digest)?.try_into().ok()?,
+        external_attrs: u32::from_str_radix(external_attrs, 16).ok()?,
+    })
+}
+
+/// Outcome of [`verify_against_manifest`](crate::StreamingZipReader::verify_against_manifest)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ManifestReport {
+    /// Entries whose content matches the manifest
+    pub verified: Vec<String>,
+    /// Entries whose size, CRC-32, SHA-256 or external attributes differ, or
+    /// that could not be read
+    pub modified: Vec<String>,
+    /// Entries listed in the manifest but absent from the archive
+    pub missing: Vec<String>,
+    /// Entries in the archive that the manifest does not list
+    pub unlisted: Vec<String>,
+}
+
+impl ManifestReport {
+    /// `true` if the archive matches the manifest exactly
+    pub fn is_ok(&self) -> bool {
+        self.modified.is_empty() && self.missing.is_empty() && self.unlisted.is_empty()
+    }
+}
+
+/// Check every entry of `reader` except the `reserved` ones against `manifest`
+///
+/// Each reserved name is skipped once; a second entry under the same name is
+/// checked like any other and ends up unlisted.
+pub(crate) fn check_entries<R: Read + Seek>(
+    reader: &mut StreamingZipReader<R>,
+    manifest: &Manifest,
+    reserved: &[&str],
+) -> Result<ManifestReport> {
+    let mut listed: HashMap<&str, &ManifestEntry> = manifest
+        .entries
+        .iter()
+        .map(|e| (e.name.as_str(), e))
+        .collect();
+    let mut reserved = reserved.to_vec();
+    let entries: Vec<_> = reader
+        .entries()
+        .iter()
+        .filter(|e| match reserved.iter().position(|r| *r == e.name) {
+            Some(i) => {
+                reserved.swap_remove(i);
+                false
+            }
+            None => true,
+        })
+        .cloned()
+        .collect();
+
+    let mut report = ManifestReport::default();
+    for entry in entries {
+        let Some(expected) = listed.remove(entry.name.as_str()) else {
+            report.unlisted.push(entry.name);
+            continue;
+        };
+        let matches = entry.crc32 == expected.crc32
+            && entry.uncompressed_size == expected.size
+            && entry.external_attrs == expected.external_attrs
+            && sha256_of(reader, &entry).ok() == Some((expected.size, expected.sha256));
+        if matches {
+            report.verified.push(entry.name);
+        } else {
+            report.modified.push(entry.name);
+        }
+    }
+    report.missing = manifest
+        .entries
+        .iter()
+        .filter(|e| listed.contains_key(e.name.as_str()))
+        .map(|e| e.name.clone())
+        .collect();
+    Ok(report)
+}
+
+/// Size and SHA-256 of the decompressed entry data
+fn sha256_of<R: Read + Seek>(
+    reader: &mut StreamingZipReader<R>,
+    entry: &crate::ZipEntry,
+) -> Result<(u64, [u8; 32])> {
+    let mut hasher = Sha256::new();
+    let size = std::io::copy(
+        &mut reader.read_entry_streaming(entry)?,
+        &mut HashWriter(&mut hasher),
+    )?;
+    Ok((size, hasher.finalize().into()))
+}
+
+/// Adapter feeding `io::copy` output into a hasher
+struct HashWriter<'a>(&'a mut Sha256);
+
+impl std::io::Write for HashWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Digests collected by a [`DigestHook`], shared with the writer
+#[derive(Clone, Default)]
+pub(crate) struct Digests(Arc<Mutex<Vec<ManifestEntry>>>);
+
+impl Digests {
+    /// Hook feeding this collection; register it before the first entry
+    pub(crate) fn hook(&self) -> impl WriterHook {
+        DigestHook {
+            current: None,
+            digests: self.clone(),
+        }
+    }
+
+    /// Manifest of the entries written so far, given their external
+    /// attributes in order
+    pub(crate) fn manifest(&self, external_attrs: &[u32]) -> Result<Manifest> {
+        let mut entries = self.0.lock().unwrap().clone();
+        let entry_count = external_attrs.len();
+        if entries.len() != entry_count {
+            return Err(SZipError::InvalidFormat(format!(
+                "Manifest and signing must be enabled before the first entry \
+                 ({} of {} entries hashed)",
+                entries.len(),
+                entry_count
+            )));
+        }
+        for (entry, &attrs) in entries.iter_mut().zip(external_attrs) {
+            entry.external_attrs = attrs;
+        }
+        Ok(Manifest { entries })
+    }
+}
+
+impl Digests {
+    /// Record an entry that `copy_entry` writes as `name` without passing its
+    /// data through the hooks, hashing it by decompressing it from `reader`
+    pub(crate) fn add_copied<R: Read + Seek>(
+        &self,
+        name: &str,
+        reader: &mut StreamingZipReader<R>,
+        entry: &crate::ZipEntry,
+    ) -> Result<()> {
+        let (size, sha256) = sha256_of(reader, entry)?;
+        self.0.lock().unwrap().push(ManifestEntry {
+            name: name.to_string(),
+            size,
+            crc32: entry.crc32,
+            sha256,
+            external_attrs: entry.external_attrs,
+        });
+        Ok(())
+    }
+}
+
+/// Hook hashing every entry the writer produces
+struct DigestHook {
+    current: Option<Sha256>,
+    digests: Digests,
+}
+
+impl WriterHook for DigestHook {
+    fn header_written(&mut self, _name: &str, _offset: u64, _header: &[u8]) -> Result<()> {
+        self.current = Some(Sha256::new());
+        Ok(())
+    }
+
+    fn data_written(&mut self, _name: &str, data: &[u8]) -> Result<()> {
+        if let Some(hasher) = self.current.as_mut() {
+            hasher.update(data);
+        }
+        Ok(())
+    }
+
+    fn entry_finished(&mut self, entry: &EntrySummary<'_>) -> Result<()> {
+        // An entry started before the hook was registered has no digest;
+        // `Digests::manifest` reports it
+        let Some(hasher) = self.current.take() else {
+            return Ok(());
+        };
+        self.digests.0.lock().unwrap().push(ManifestEntry {
+            name: entry.name.to_string(),
+            size: entry.uncompressed_size,
+            crc32: entry.crc32,
+            sha256: hasher.finalize().into(),
+            // Filled in from the central directory records by `manifest`
+            external_attrs: 0,
+        });
+        Ok(())
+    }
+}
+
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub(crate) fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
@@ -0,0 +1,204 @@
+//! Combining several archives into one.
+//!
+//! Entries are copied as stored, without decompressing them (see
+//! [`StreamingZipWriter::copy_entry`]), so merging the per-shard archives of
+//! a batch job costs one pass over their data. Entries keep the order of
+//! the sources, and within each source their own order.
+//!
+//! An entry name found in more than one place is resolved with a
+//! [`ConflictPolicy`]. Directory entries (names ending in `/`) are expected
+//! to repeat across shards and are written once, whatever the policy.
+//!
+//! ```no_run
+//! use s_zip::merge::{merge, ConflictPolicy};
+//!
+//! let shards = ["out/shard-0.zip", "out/shard-1.zip", "out/shard-2.zip"];
+//! let report = merge(&shards, "out/all.zip", ConflictPolicy::KeepLast)?;
+//! println!("{} entries, {} duplicates dropped", report.copied, report.skipped.len());
+//! # Ok::<(), s_zip::SZipError>(())
+//! ```
+
+use crate::error::{Result, SZipError};
+use crate::format::ZipEntry;
+use crate::reader::StreamingZipReader;
+use crate::writer::StreamingZipWriter;
+use std::collections::HashMap;
+use std::io::{Read, Seek, Write};
+use std::path::Path;
+
+/// What to do with an entry whose name was already seen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConflictPolicy {
+    /// Fail before anything is written (default)
+    #[default]
+    Error,
+    /// Keep the first entry with the name, from the earliest source
+    KeepFirst,
+    /// Keep the last entry with the name, from the latest source
+    KeepLast,
+    /// Write every entry, leaving duplicate names in the archive
+    KeepAll,
+}
+
+/// What a merge did
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MergeReport {
+    /// Entries written to the output
+    pub copied: usize,
+    /// Names of the duplicate entries left out, once per entry dropped
+    pub skipped: Vec<String>,
+}
+
+/// Merge the archives at `sources` into a new archive at `output`
+///
+/// Sources are opened one at a time, so any number of them can be merged.
+pub fn merge<P: AsRef<Path>, Q: AsRef<Path>>(
+    sources: &[P],
+    output: Q,
+    policy: ConflictPolicy,
+) -> Result<MergeReport> {
+    let mut entries = Vec::with_capacity(sources.len());
+    for source in sources {
+        entries.push(StreamingZipReader::open(source)?.entries().to_vec());
+    }
+    let (keep, skipped) = plan(&entries, policy)?;
+
+    let mut writer = StreamingZipWriter::new(output)?;
+    let mut copied = 0;
+    for ((source, entries), keep) in sources.iter().zip(&entries).zip(&keep) {
+        let mut reader = StreamingZipReader::open(source)?;
+        for (entry, _) in entries.iter().zip(keep).filter(|(_, &keep)| keep) {
+            writer.copy_entry(&mut reader, entry)?;
+            copied += 1;
+        }
+    }
+    writer.finish()?;
+    Ok(MergeReport { copied, skipped })
+}
+
+/// Copy the entries of all `sources` to `output`
+///
+/// `output` is left open, so more entries can be added before it is
+/// finished.
+pub fn merge_readers<R: Read + Seek, W: Write + Seek>(
+    sources: &mut [StreamingZipReader<R>],
+    output: &mut StreamingZipWriter<W>,
+    policy: ConflictPolicy,
+) -> Result<MergeReport> {
+    let entries: Vec<Vec<ZipEntry>> = sources.iter().map(|s| s.entries().to_vec()).collect();
+    let (keep, skipped) = plan(&entries, policy)?;
+    let mut copied = 0;
+    for ((reader, entries), keep) in sources.iter_mut().zip(&entries).zip(&keep) {
+        for (entry, _) in entries.iter().zip(keep).filter(|(_, &keep)| keep) {
+            output.copy_entry(reader, entry)?;
+            copied += 1;
+        }
+    }
+    Ok(MergeReport { copied, skipped })
+}
+
+/// Merge the archives at `sources` into a new archive at `output`
+///
+/// Async version of [`merge`].
+#[cfg(feature = "async")]
+pub async fn merge_async<P: AsRef<Path>, Q: AsRef<Path>>(
+    sources: &[P],
+    output: Q,
+    policy: ConflictPolicy,
+) -> Result<MergeReport> {
+    use crate::{AsyncStreamingZipReader, AsyncStreamingZipWriter};
+
+    let mut entries = Vec::with_capacity(sources.len());
+    for source in sources {
+        entries.push(
+            AsyncStreamingZipReader::open(source)
+                .await?
+                .entries()
+                .to_vec(),
+        );
+    }
+    let (keep, skipped) = plan(&entries, policy)?;
+
+    let mut writer = AsyncStreamingZipWriter::new(output).await?;
+    let mut copied = 0;
+    for ((source, entries), keep) in sources.iter().zip(&entries).zip(&keep) {
+        let mut reader = AsyncStreamingZipReader::open(source).await?;
+        for (entry, _) in entries.iter().zip(keep).filter(|(_, &keep)| keep) {
+            writer.copy_entry(&mut reader, entry).await?;
+            copied += 1;
+        }
+    }
+    writer.finish().await?;
+    Ok(MergeReport { copied, skipped })
+}
+
+/// Copy the entries of all `sources` to `output`
+///
+/// Async version of [`merge_readers`].
+#[cfg(feature = "async")]
+pub async fn merge_readers_async<R, W>(
+    sources: &mut [crate::GenericAsyncZipReader<R>],
+    output: &mut crate::AsyncStreamingZipWriter<W>,
+    policy: ConflictPolicy,
+) -> Result<MergeReport>
+where
+    R: tokio::io::AsyncRead + tokio::io::AsyncSeek + Unpin,
+    W: tokio::io::AsyncWrite + Unpin,
+{
+    let entries: Vec<Vec<ZipEntry>> = sources.iter().map(|s| s.entries().to_vec()).collect();
+    let (keep, skipped) = plan(&entries, policy)?;
+    let mut copied = 0;
+    for ((reader, entries), keep) in sources.iter_mut().zip(&entries).zip(&keep) {
+        for (entry, _) in entries.iter().zip(keep).filter(|(_, &keep)| keep) {
+            output.copy_entry(reader, entry).await?;
+            copied += 1;
+        }
+    }
+    Ok(MergeReport { copied, skipped })
+}
+
+/// Decide which entries of each source are written, and name the duplicates
+/// left out
+fn plan(
+    sources: &[Vec<ZipEntry>],
+    policy: ConflictPolicy,
+) -> Result<(Vec<Vec<bool>>, Vec<String>)> {
+    let mut keep: Vec<Vec<bool>> = sources.iter().map(|s| vec![true; s.len()]).collect();
+    let mut skipped = Vec::new();
+    // Source and index of the entry currently kept for each name
+    let mut kept: HashMap<&str, (usize, usize)> = HashMap::new();
+    for (s, entries) in sources.iter().enumerate() {
+        for (i, entry) in entries.iter().enumerate() {
+            let name = entry.name.as_str();
+            let Some(&(first_s, first_i)) = kept.get(name) else {
+                kept.insert(name, (s, i));
+                continue;
+            };
+            if name.ends_with('/') {
+                keep[s][i] = false;
+                continue;
+            }
+            match policy {
+                ConflictPolicy::Error => {
+                    return Err(SZipError::InvalidFormat(format!(
+                        "Entry '{}' of source {} is also in source {}",
+                        name,
+                        s + 1,
+                        first_s + 1
+                    )))
+                }
+                ConflictPolicy::KeepFirst => {
+                    keep[s][i] = false;
+                    skipped.push(name.to_string());
+                }
+                ConflictPolicy::KeepLast => {
+                    keep[first_s][first_i] = false;
+                    kept.insert(name, (s, i));
+                    skipped.push(name.to_string());
+                }
+                ConflictPolicy::KeepAll => {}
+            }
+        }
+    }
+    Ok((keep, skipped))
+}
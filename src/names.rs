@@ -0,0 +1,192 @@
+//! Compact storage for entry names.
+//!
+//! Entry names in large archives repeat long directory prefixes
+//! (`project/src/module/sub/file_0001.rs`), and storing each as its own
+//! `String` costs the full path plus a heap allocation per entry.
+//! [`NameTable`] stores every distinct directory once and only the final
+//! component per entry, all in shared buffers; names are read back as
+//! [`EntryName`] views made of two `&str` parts.
+//!
+//! ```
+//! use s_zip::names::NameTable;
+//!
+//! let mut names = NameTable::new();
+//! names.push("docs/guide/intro.md");
+//! names.push("docs/guide/setup.md");
+//! assert_eq!(names.get(1).unwrap().dir(), "docs/guide/");
+//! assert_eq!(names.get(1).unwrap().file_name(), "setup.md");
+//! assert_eq!(names.position("docs/guide/intro.md"), Some(0));
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+
+/// Entry names stored with shared directory prefixes
+#[derive(Debug, Clone, Default)]
+pub struct NameTable {
+    /// Distinct directory prefixes, each including its trailing `/`
+    dirs: Vec<Box<str>>,
+    dir_ids: HashMap<Box<str>, u32>,
+    /// Per directory: indices of the names in it, in insertion order
+    dir_members: Vec<Vec<u32>>,
+    /// Final components of every name, concatenated
+    files: String,
+    /// Per name: index into `dirs` and end of its component in `files`
+    dir_of: Vec<u32>,
+    file_ends: Vec<usize>,
+}
+
+impl NameTable {
+    /// Create an empty table
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a name, returning its index
+    pub fn push(&mut self, name: &str) -> usize {
+        let (dir, file) = split_name(name);
+        let dir_id = match self.dir_ids.get(dir) {
+            Some(&id) => id,
+            None => {
+                let id = self.dirs.len() as u32;
+                self.dirs.push(dir.into());
+                self.dir_ids.insert(dir.into(), id);
+                self.dir_members.push(Vec::new());
+                id
+            }
+        };
+        self.dir_members[dir_id as usize].push(self.dir_of.len() as u32);
+        self.files.push_str(file);
+        self.dir_of.push(dir_id);
+        self.file_ends.push(self.files.len());
+        self.dir_of.len() - 1
+    }
+
+    /// Number of names
+    pub fn len(&self) -> usize {
+        self.dir_of.len()
+    }
+
+    /// Whether the table is empty
+    pub fn is_empty(&self) -> bool {
+        self.dir_of.is_empty()
+    }
+
+    /// The name at `index`
+    pub fn get(&self, index: usize) -> Option<EntryName<'_>> {
+        let dir = self.dirs[*self.dir_of.get(index)? as usize].as_ref();
+        let start = index.checked_sub(1).map_or(0, |i| self.file_ends[i]);
+        Some(EntryName {
+            dir,
+            file_name: &self.files[start..self.file_ends[index]],
+        })
+    }
+
+    /// Index of the first occurrence of `name`; a linear scan over the
+    /// names in its directory
+    pub fn position(&self, name: &str) -> Option<usize> {
+        let (dir, file) = split_name(name);
+        let dir_id = *self.dir_ids.get(dir)?;
+        self.dir_members[dir_id as usize]
+            .iter()
+            .map(|&i| i as usize)
+            .find(|&i| self.get(i).unwrap().file_name == file)
+    }
+
+    /// Iterate over all names in insertion order
+    pub fn iter(&self) -> impl Iterator<Item = EntryName<'_>> + '_ {
+        (0..self.len()).map(|i| self.get(i).unwrap())
+    }
+}
+
+/// Split a name after its last `/`, ignoring a trailing one (directory entries)
+fn split_name(name: &str) -> (&str, &str) {
+    let trimmed = name.strip_suffix('/').unwrap_or(name);
+    match trimmed.rfind('/') {
+        Some(i) => name.split_at(i + 1),
+        None => ("", name),
+    }
+}
+
+/// A name stored in a [`NameTable`]: its directory prefix and final component
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EntryName<'a> {
+    dir: &'a str,
+    file_name: &'a str,
+}
+
+impl<'a> EntryName<'a> {
+    /// Directory prefix including the trailing `/`, or `""` at the top level
+    pub fn dir(&self) -> &'a str {
+        self.dir
+    }
+
+    /// Final path component (with a trailing `/` for directory entries)
+    pub fn file_name(&self) -> &'a str {
+        self.file_name
+    }
+
+    /// Length of the full name in bytes
+    pub fn len(&self) -> usize {
+        self.dir.len() + self.file_name.len()
+    }
+
+    /// Whether the name is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl fmt::Display for EntryName<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.dir, self.file_name)
+    }
+}
+
+impl PartialEq<str> for EntryName<'_> {
+    fn eq(&self, other: &str) -> bool {
+        other.len() == self.len()
+            && other.starts_with(self.dir)
+            && &other[self.dir.len()..] == self.file_name
+    }
+}
+
+impl PartialEq<&str> for EntryName<'_> {
+    fn eq(&self, other: &&str) -> bool {
+        self == *other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_name_table_round_trip() {
+        let names = [
+            "top.txt",
+            "a/",
+            "a/b/",
+            "a/b/c.txt",
+            "a/b/d.txt",
+            "a/b/c.txt",
+        ];
+        let mut table = NameTable::new();
+        for name in names {
+            table.push(name);
+        }
+
+        let back: Vec<String> = table.iter().map(|n| n.to_string()).collect();
+        assert_eq!(back, names);
+        assert_eq!(table.get(2).unwrap().dir(), "a/");
+        assert_eq!(table.get(2).unwrap().file_name(), "b/");
+        assert!(table.get(3).unwrap() == "a/b/c.txt");
+        assert_eq!(table.position("a/b/c.txt"), Some(3));
+        assert_eq!(table.position("a/b/e.txt"), None);
+        assert_eq!(table.position("a/"), Some(1));
+        assert_eq!(table.position("top.txt"), Some(0));
+        assert_eq!(table.dir_members[2], [3, 4, 5]);
+        // "a/b/" is stored once for three names
+        assert_eq!(table.dirs.len(), 3);
+    }
+}
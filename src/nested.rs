@@ -0,0 +1,164 @@
+//! Archives stored as entries of another archive.
+//!
+//! [`StreamingZipReader::open_nested`](crate::StreamingZipReader::open_nested)
+//! opens an inner ZIP (a `.jar` in a `.zip`, an `.apk` in a release bundle,
+//! ...) as an archive of its own. A stored inner ZIP is read in place through
+//! a window onto the outer archive, without copying it. A compressed or
+//! encrypted one can't be seeked in place, so it is decompressed once into a
+//! temporary file, which is removed when the inner reader is dropped.
+//!
+//! ```no_run
+//! use s_zip::StreamingZipReader;
+//!
+//! let mut outer = StreamingZipReader::open("release.zip")?;
+//! let entry = outer.find_entry("lib/app.jar").unwrap().clone();
+//! let mut jar = outer.open_nested(&entry)?;
+//! let manifest = jar.read_entry_by_name("META-INF/MANIFEST.MF")?;
+//! # Ok::<(), s_zip::SZipError>(())
+//! ```
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Distinguishes the spool files of one process
+static SPOOL_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Source of a nested archive, readable and seekable like a file
+pub struct NestedSource<'a, R> {
+    inner: Inner<'a, R>,
+}
+
+enum Inner<'a, R> {
+    /// Stored bytes of the outer archive
+    Window(Window<'a, R>),
+    /// Decompressed copy in a temporary file
+    Spooled(SpoolFile),
+}
+
+impl<'a, R: Read + Seek> NestedSource<'a, R> {
+    /// Window onto `len` bytes of `source` starting at `start`
+    pub(crate) fn window(source: &'a mut R, start: u64, len: u64) -> Self {
+        NestedSource {
+            inner: Inner::Window(Window {
+                source,
+                start,
+                len,
+                pos: 0,
+                synced: false,
+            }),
+        }
+    }
+
+    /// Copy `data` into a new temporary file
+    pub(crate) fn spool(data: &mut dyn Read) -> io::Result<Self> {
+        let mut spool = SpoolFile::create()?;
+        let file = spool.file.as_mut().expect("spool file is open");
+        io::copy(data, file.get_mut())?;
+        file.get_mut().flush()?;
+        file.seek(SeekFrom::Start(0))?;
+        Ok(NestedSource {
+            inner: Inner::Spooled(spool),
+        })
+    }
+}
+
+impl<R: Read + Seek> Read for NestedSource<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match &mut self.inner {
+            Inner::Window(w) => w.read(buf),
+            Inner::Spooled(s) => s.file.as_mut().expect("spool file is open").read(buf),
+        }
+    }
+}
+
+impl<R: Read + Seek> Seek for NestedSource<'_, R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        match &mut self.inner {
+            Inner::Window(w) => w.seek(pos),
+            Inner::Spooled(s) => s.file.as_mut().expect("spool file is open").seek(pos),
+        }
+    }
+}
+
+/// `len` bytes of a source, starting at `start`
+struct Window<'a, R> {
+    source: &'a mut R,
+    start: u64,
+    len: u64,
+    pos: u64,
+    /// Whether `source` is positioned at `start + pos`
+    synced: bool,
+}
+
+impl<R: Read + Seek> Read for Window<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining = self.len.saturating_sub(self.pos);
+        if remaining == 0 {
+            return Ok(0);
+        }
+        if !self.synced {
+            self.source.seek(SeekFrom::Start(self.start + self.pos))?;
+            self.synced = true;
+        }
+        let max = buf.len().min(remaining.min(usize::MAX as u64) as usize);
+        let n = self.source.read(&mut buf[..max])?;
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+impl<R: Read + Seek> Seek for Window<'_, R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(n) => Some(n),
+            SeekFrom::End(n) => self.len.checked_add_signed(n),
+            SeekFrom::Current(n) => self.pos.checked_add_signed(n),
+        }
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )
+        })?;
+        if target != self.pos {
+            self.pos = target;
+            self.synced = false;
+        }
+        Ok(target)
+    }
+}
+
+/// Temporary file removed on drop
+struct SpoolFile {
+    /// `None` only while dropping, so the file is closed before removal
+    file: Option<BufReader<File>>,
+    path: PathBuf,
+}
+
+impl SpoolFile {
+    fn create() -> io::Result<Self> {
+        let path = std::env::temp_dir().join(format!(
+            "s-zip-nested-{}-{}.zip",
+            std::process::id(),
+            SPOOL_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+        Ok(SpoolFile {
+            file: Some(BufReader::with_capacity(512 * 1024, file)),
+            path,
+        })
+    }
+}
+
+impl Drop for SpoolFile {
+    fn drop(&mut self) {
+        drop(self.file.take());
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
@@ -3,15 +3,23 @@
 //! This module provides parallel compression capabilities with memory constraints.
 //! Uses a bounded semaphore to limit concurrent tasks and prevent memory spikes.
 
+use crate::budget::{MemoryBudget, Reservation};
 use crate::error::{Result, SZipError};
 use crate::writer::CompressionMethod;
+use crate::EntryOptions;
 use async_compression::tokio::bufread::DeflateEncoder;
+#[cfg(feature = "async-zstd")]
+use async_compression::tokio::bufread::ZstdEncoder;
+use bytes::Bytes;
+use futures_util::future::{select, BoxFuture, Either};
+use std::future::Future;
 use std::path::PathBuf;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 use tokio::io::{AsyncBufRead, AsyncRead, AsyncReadExt, ReadBuf};
-use tokio::sync::{mpsc, Semaphore};
+use tokio::sync::{mpsc, watch, Semaphore};
+use tokio::task::JoinSet;
 
 /// An `AsyncRead` + `AsyncBufRead` wrapper that computes CRC32 of bytes passing through it.
 ///
@@ -21,6 +29,7 @@ use tokio::sync::{mpsc, Semaphore};
 struct CrcReader<R> {
     inner: R,
     hasher: crc32fast::Hasher,
+    len: u64,
 }
 
 impl<R> CrcReader<R> {
@@ -28,12 +37,14 @@ impl<R> CrcReader<R> {
         Self {
             inner,
             hasher: crc32fast::Hasher::new(),
+            len: 0,
         }
     }
 
-    /// Consume this wrapper and return the final CRC32 digest.
-    fn finalize(self) -> u32 {
-        self.hasher.finalize()
+    /// Consume this wrapper and return the final CRC32 digest and the
+    /// number of bytes read.
+    fn finalize(self) -> (u32, u64) {
+        (self.hasher.finalize(), self.len)
     }
 }
 
@@ -50,6 +61,7 @@ impl<R: AsyncRead + Unpin> AsyncRead for CrcReader<R> {
             let new_bytes = &buf.filled()[filled_before..];
             if !new_bytes.is_empty() {
                 this.hasher.update(new_bytes);
+                this.len += new_bytes.len() as u64;
             }
         }
         result
@@ -91,10 +103,16 @@ impl<R: AsyncBufRead + Unpin> AsyncBufRead for CrcReader<R> {
         if let Poll::Ready(Ok(buf)) = Pin::new(&mut this.inner).poll_fill_buf(&mut cx) {
             let to_hash = &buf[..amt.min(buf.len())];
             this.hasher.update(to_hash);
+            this.len += to_hash.len() as u64;
         }
         Pin::new(&mut this.inner).consume(amt);
     }
 }
+
+/// Working memory reserved per compression task from a [`MemoryBudget`],
+/// matching [`ParallelConfig::estimated_peak_memory_mb`]
+pub const TASK_MEMORY: usize = 1024 * 1024;
+
 /// Configuration for parallel compression
 #[derive(Debug, Clone)]
 pub struct ParallelConfig {
@@ -102,8 +120,15 @@ pub struct ParallelConfig {
     pub max_concurrent: usize,
     /// Compression level (default: 6)
     pub compression_level: u32,
-    /// Compression method (default: Deflate)
+    /// Compression method: Deflate (default), Stored or, with the
+    /// `async-zstd` feature, Zstd
     pub compression_method: CompressionMethod,
+    /// Budget each task reserves its working memory from before it starts
+    /// (default: none)
+    pub memory_budget: Option<MemoryBudget>,
+    /// Most compressed bytes held for entries waiting for their turn to be
+    /// written (default: 32 MiB)
+    pub max_buffered: usize,
 }
 
 impl Default for ParallelConfig {
@@ -112,6 +137,8 @@ impl Default for ParallelConfig {
             max_concurrent: 4,
             compression_level: 6,
             compression_method: CompressionMethod::Deflate,
+            memory_budget: None,
+            max_buffered: 32 * 1024 * 1024,
         }
     }
 }
@@ -123,6 +150,8 @@ impl ParallelConfig {
             max_concurrent: 2,
             compression_level: 6,
             compression_method: CompressionMethod::Deflate,
+            memory_budget: None,
+            max_buffered: 16 * 1024 * 1024,
         }
     }
 
@@ -137,6 +166,8 @@ impl ParallelConfig {
             max_concurrent: 8,
             compression_level: 6,
             compression_method: CompressionMethod::Deflate,
+            memory_budget: None,
+            max_buffered: 64 * 1024 * 1024,
         }
     }
 
@@ -165,161 +196,481 @@ impl ParallelConfig {
         self
     }
 
+    /// Set compression method: Deflate, Stored or Zstd
+    pub fn with_compression_method(mut self, method: CompressionMethod) -> Self {
+        self.compression_method = method;
+        self
+    }
+
+    /// Hold at most `bytes` of compressed data for entries waiting for
+    /// their turn to be written; their tasks pause once it is used up
+    pub fn with_max_buffered(mut self, bytes: usize) -> Self {
+        self.max_buffered = bytes;
+        self
+    }
+
+    /// Admit a task only once [`TASK_MEMORY`] bytes are available in
+    /// `budget`, so parallel writes across jobs share one cap.
+    ///
+    /// Compressed data held for entries waiting for their turn is bounded
+    /// by [`max_buffered`](Self::max_buffered) instead.
+    pub fn with_memory_budget(mut self, budget: MemoryBudget) -> Self {
+        self.memory_budget = Some(budget);
+        self
+    }
+
     /// Estimate peak memory usage in MB.
     ///
-    /// After the v0.11.3 `CrcReader` streaming pipeline, each concurrent task
-    /// holds in memory:
+    /// Each concurrent task holds in memory:
     ///
     /// - `BufReader` I/O buffer: 64 KB
     /// - `DeflateEncoder` compression window: ~32 KB
-    /// - Compressed output `Vec<u8>`: proportional to compressed size
-    ///
-    /// For highly compressible data the compressed output is negligible.
-    /// For incompressible data (e.g. already-compressed files) the output
-    /// can approach the input file size.
-    ///
-    /// This estimate uses **1 MB per task** as a conservative upper bound for
-    /// typical (moderately compressible) workloads.  Worst-case incompressible
-    /// data will exceed this — plan for `max_concurrent × file_size` in that
-    /// scenario.
+    /// - The compressed chunk being filled: 256 KB
     ///
-    /// Measured: 20 files × 5 MB (compressible), 8 threads → ~5 MB peak RSS.
+    /// which this estimate rounds up to **1 MB per task**. On top of that,
+    /// up to [`max_buffered`](Self::max_buffered) bytes of compressed data
+    /// wait for their turn to be written, whatever the size of the files.
+    /// Data of [`ParallelEntry::from_bytes`] entries is not included.
     pub fn estimated_peak_memory_mb(&self) -> usize {
-        // ~64 KB BufReader + ~32 KB encoder window + compressed output overhead.
-        // 1 MB per task is a reasonable conservative estimate for typical data.
-        self.max_concurrent.max(1)
+        self.max_concurrent.max(1) + self.max_buffered.div_ceil(1024 * 1024)
     }
 }
 
-/// A file entry to be compressed in parallel
+/// Opens the data of a [`ParallelSource::Reader`] entry, once its task
+/// starts
+pub type ReaderFactory = Arc<
+    dyn Fn() -> BoxFuture<'static, std::io::Result<Box<dyn AsyncRead + Send + Unpin>>>
+        + Send
+        + Sync,
+>;
+
+/// Where the data of a [`ParallelEntry`] comes from
+#[derive(Clone)]
+pub enum ParallelSource {
+    /// A file, opened when its task starts
+    Path(PathBuf),
+    /// Data already in memory
+    Bytes(Bytes),
+    /// A reader opened when its task starts, such as a download, so that at
+    /// most `max_concurrent` are open at once
+    Reader(ReaderFactory),
+}
+
+impl std::fmt::Debug for ParallelSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Path(path) => f.debug_tuple("Path").field(path).finish(),
+            Self::Bytes(data) => f.debug_tuple("Bytes").field(&data.len()).finish(),
+            Self::Reader(_) => f.write_str("Reader(..)"),
+        }
+    }
+}
+
+/// An entry to be compressed in parallel
+#[derive(Debug, Clone)]
 pub struct ParallelEntry {
     /// Entry name in ZIP
     pub name: String,
-    /// File path to read from
-    pub path: PathBuf,
+    /// Data of the entry
+    pub source: ParallelSource,
+    /// Modification time and attributes; the current time by default
+    pub options: EntryOptions,
 }
 
 impl ParallelEntry {
-    /// Create a new parallel entry
+    /// Create a new parallel entry from the file at `path`
     pub fn new(name: impl Into<String>, path: impl Into<PathBuf>) -> Self {
         Self {
             name: name.into(),
-            path: path.into(),
+            source: ParallelSource::Path(path.into()),
+            options: EntryOptions::default(),
         }
     }
+
+    /// Create a parallel entry from data in memory: a `Vec<u8>`, `Bytes`,
+    /// `String` or `&'static [u8]`
+    pub fn from_bytes(name: impl Into<String>, data: impl Into<Bytes>) -> Self {
+        Self {
+            name: name.into(),
+            source: ParallelSource::Bytes(data.into()),
+            options: EntryOptions::default(),
+        }
+    }
+
+    /// Create a parallel entry read from whatever `open` resolves to, called
+    /// when the entry's task starts
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use s_zip::ParallelEntry;
+    /// let entry = ParallelEntry::from_reader("report.csv", || async {
+    ///     tokio::fs::File::open("/mnt/share/report.csv").await
+    /// });
+    /// ```
+    pub fn from_reader<F, Fut, R>(name: impl Into<String>, open: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = std::io::Result<R>> + Send + 'static,
+        R: AsyncRead + Send + Unpin + 'static,
+    {
+        let factory: ReaderFactory = Arc::new(move || {
+            let opening = open();
+            Box::pin(async move {
+                let reader: Box<dyn AsyncRead + Send + Unpin> = Box::new(opening.await?);
+                Ok(reader)
+            })
+        });
+        Self {
+            name: name.into(),
+            source: ParallelSource::Reader(factory),
+            options: EntryOptions::default(),
+        }
+    }
+
+    /// Set the entry's modification time and attributes
+    pub fn with_options(mut self, options: EntryOptions) -> Self {
+        self.options = options;
+        self
+    }
 }
 
-/// Result of parallel compression
-pub(crate) struct CompressedEntry {
-    pub name: String,
-    pub data: Vec<u8>,
+/// Compressed bytes a task hands over at a time
+const CHUNK_LEN: usize = 256 * 1024;
+
+/// Sizes and checksum of a compressed entry
+pub(crate) struct EntryTotals {
     pub uncompressed_size: u64,
     pub crc32: u32,
+    /// Time spent reading and compressing the data
+    pub elapsed: std::time::Duration,
+}
+
+/// Compressed data of an entry, as its task hands it to the writer
+pub(crate) enum Chunk {
+    /// The whole entry, compressed before its turn came
+    Complete(Vec<Vec<u8>>, EntryTotals),
+    /// Part of an entry still being compressed; `End` follows the last part
+    Data(Vec<u8>),
+    /// End of an entry sent as `Data`
+    End(EntryTotals),
 }
 
-/// Compress a single file with DEFLATE in a true single-pass stream.
+/// Parallel compression of a batch of entries, handed to the writer in order
 ///
-/// The pipeline is: `File → BufReader → CrcReader → DeflateEncoder → Vec<u8>`.
-/// CRC32 is computed on-the-fly over the raw bytes as they are read by the
-/// encoder, so the full file is **never** buffered in memory.  Peak RAM per
-/// task is bounded by the encoder's internal window (~32 KB) and the 64 KB
-/// `BufReader` buffer — independent of file size.
-async fn compress_file_deflate(path: PathBuf, level: u32) -> Result<(Vec<u8>, u64, u32)> {
-    let file = tokio::fs::File::open(&path).await?;
-    let metadata = file.metadata().await?;
-    let uncompressed_size = metadata.len();
-
-    // File → BufReader (64 KB I/O buffer) → CrcReader (hashes bytes as read)
-    let buf_reader = tokio::io::BufReader::with_capacity(64 * 1024, file);
-    let crc_reader = CrcReader::new(buf_reader);
-
-    // CrcReader → DeflateEncoder: bytes are hashed on-the-fly as the encoder
-    // pulls them; no intermediate Vec<u8> is needed.
-    let mut encoder =
-        DeflateEncoder::with_quality(crc_reader, async_compression::Level::Precise(level as i32));
+/// The entry whose turn it is streams its chunks straight to the writer.
+/// Tasks of later entries hold their chunks until their turn comes, within
+/// [`ParallelConfig::max_buffered`] bytes in all, and wait when that is
+/// used up. The entry whose turn it is never waits for the budget, so the
+/// batch always progresses. Dropping this aborts the tasks.
+pub(crate) struct ParallelCompression {
+    entries: std::vec::IntoIter<(String, mpsc::Receiver<Result<Chunk>>)>,
+    index: usize,
+    turn: watch::Sender<usize>,
+    _tasks: JoinSet<()>,
+}
 
-    let mut compressed = Vec::new();
-    encoder.read_to_end(&mut compressed).await?;
+impl ParallelCompression {
+    /// Next entry in the batch, which it becomes the turn of
+    pub fn next_entry(&mut self) -> Option<(String, CompressingEntry<'_>)> {
+        let (name, chunks) = self.entries.next()?;
+        self.turn.send_replace(self.index);
+        self.index += 1;
+        Some((
+            name,
+            CompressingEntry {
+                chunks,
+                _batch: std::marker::PhantomData,
+            },
+        ))
+    }
+}
 
-    // Unwrap the encoder to recover the CrcReader and obtain the digest.
-    let crc32 = encoder.into_inner().finalize();
+/// Chunks of the entry whose turn it is
+pub(crate) struct CompressingEntry<'a> {
+    chunks: mpsc::Receiver<Result<Chunk>>,
+    _batch: std::marker::PhantomData<&'a mut ParallelCompression>,
+}
 
-    Ok((compressed, uncompressed_size, crc32))
+impl CompressingEntry<'_> {
+    /// Next chunk of the entry
+    pub async fn next(&mut self) -> Result<Chunk> {
+        self.chunks.recv().await.unwrap_or_else(|| {
+            Err(SZipError::InvalidFormat(
+                "Compression task failed".to_string(),
+            ))
+        })
+    }
 }
 
-/// Compress multiple files in parallel with bounded concurrency
-pub(crate) async fn compress_entries_parallel(
-    entries: Vec<ParallelEntry>,
-    config: ParallelConfig,
-) -> Result<Vec<CompressedEntry>> {
-    let semaphore = Arc::new(Semaphore::new(config.max_concurrent));
-    let (tx, mut rx) = mpsc::channel(config.max_concurrent);
-
-    // Spawn compression tasks (bounded by semaphore)
-    let handles: Vec<_> = entries
-        .into_iter()
-        .enumerate()
-        .map(|(index, entry)| {
-            let semaphore = semaphore.clone();
-            let tx = tx.clone();
-            let config = config.clone();
+/// Open the data of `source` for reading
+async fn open_source(source: ParallelSource) -> Result<Box<dyn AsyncBufRead + Send + Unpin>> {
+    Ok(match source {
+        ParallelSource::Path(path) => {
+            let file = tokio::fs::File::open(&path).await?;
+            Box::new(tokio::io::BufReader::with_capacity(64 * 1024, file))
+        }
+        ParallelSource::Bytes(data) => Box::new(std::io::Cursor::new(data)),
+        ParallelSource::Reader(open) => {
+            let reader = open().await?;
+            Box::new(tokio::io::BufReader::with_capacity(64 * 1024, reader))
+        }
+    })
+}
 
-            tokio::task::spawn(async move {
-                // Acquire semaphore permit (blocks if max concurrent reached)
-                let _permit = semaphore
-                    .acquire()
-                    .await
-                    .map_err(|_e| SZipError::InvalidFormat("Semaphore error".to_string()))?;
-
-                // Compress file
-                let (compressed, uncompressed_size, crc32) = match config.compression_method {
-                    CompressionMethod::Deflate => {
-                        compress_file_deflate(entry.path, config.compression_level).await?
-                    }
-                    _ => {
-                        return Err(SZipError::InvalidFormat(
-                            "Only DEFLATE supported in parallel compression".to_string(),
-                        ));
-                    }
-                };
+/// Hands the chunks of one entry over to the writer
+struct ChunkSender {
+    index: usize,
+    chunks: mpsc::Sender<Result<Chunk>>,
+    turn: watch::Receiver<usize>,
+    buffered: MemoryBudget,
+    /// Chunks held until the entry's turn, with the budget they take up
+    held: Vec<(Vec<u8>, Reservation)>,
+    streaming: bool,
+}
 
-                let result = CompressedEntry {
-                    name: entry.name,
-                    data: compressed,
-                    uncompressed_size,
-                    crc32,
-                };
+impl ChunkSender {
+    /// Reserve `len` bytes to hold a chunk with, unless the entry's turn
+    /// comes first
+    async fn reserve(&mut self, len: usize) -> Option<Reservation> {
+        let index = self.index;
+        if *self.turn.borrow() == index {
+            return None;
+        }
+        if self.buffered.limit() == 0 {
+            // The budget would clamp the request to nothing
+            let _ = self.turn.wait_for(|turn| *turn == index).await;
+            return None;
+        }
+        let reserve = std::pin::pin!(self.buffered.reserve(len));
+        let turn = std::pin::pin!(self.turn.wait_for(|turn| *turn == index));
+        match select(reserve, turn).await {
+            Either::Left((reservation, _)) => Some(reservation),
+            Either::Right(_) => None,
+        }
+    }
 
-                // Send result with index to maintain order
-                tx.send((index, result))
-                    .await
-                    .map_err(|_e| SZipError::InvalidFormat("Channel send error".to_string()))?;
+    /// Hand `chunk` over if it is the entry's turn, or else hold it once the
+    /// budget allows, whichever comes first
+    async fn push(&mut self, chunk: Vec<u8>) -> Result<()> {
+        if !self.streaming {
+            if let Some(reservation) = self.reserve(chunk.len()).await {
+                self.held.push((chunk, reservation));
+                return Ok(());
+            }
+            self.streaming = true;
+            for (held, _reservation) in std::mem::take(&mut self.held) {
+                self.send(Chunk::Data(held)).await?;
+            }
+        }
+        self.send(Chunk::Data(chunk)).await
+    }
 
-                // Permit is automatically dropped here
-                Ok::<_, SZipError>(())
-            })
+    /// Hand over `last`, the final chunk, and the end of the entry once its
+    /// turn comes
+    async fn finish(&mut self, last: Vec<u8>, totals: EntryTotals) -> Result<()> {
+        if self.streaming {
+            if !last.is_empty() {
+                self.send(Chunk::Data(last)).await?;
+            }
+            return self.send(Chunk::End(totals)).await;
+        }
+        // Held within the budget too, or entries done early would pile up
+        let reservation = self.reserve(last.len()).await;
+        let index = self.index;
+        // An error only means the batch was dropped, which `send` reports
+        let _ = self.turn.wait_for(|turn| *turn == index).await;
+        let mut chunks: Vec<_> = std::mem::take(&mut self.held)
+            .into_iter()
+            .map(|(chunk, _reservation)| chunk)
+            .collect();
+        chunks.push(last);
+        drop(reservation);
+        self.send(Chunk::Complete(chunks, totals)).await
+    }
+
+    async fn send(&self, chunk: Chunk) -> Result<()> {
+        self.chunks
+            .send(Ok(chunk))
+            .await
+            .map_err(|_e| SZipError::InvalidFormat("Channel send error".to_string()))
+    }
+}
+
+/// Compressed form of a [`CrcReader`] in the configured method
+enum Encoder<R> {
+    Deflate(DeflateEncoder<CrcReader<R>>),
+    #[cfg(feature = "async-zstd")]
+    Zstd(ZstdEncoder<CrcReader<R>>),
+    Stored(CrcReader<R>),
+}
+
+impl<R: AsyncBufRead + Unpin> Encoder<R> {
+    fn new(source: CrcReader<R>, config: &ParallelConfig) -> Result<Self> {
+        check_method(config.compression_method)?;
+        let level = async_compression::Level::Precise(config.compression_level as i32);
+        Ok(match config.compression_method {
+            CompressionMethod::Deflate => {
+                Self::Deflate(DeflateEncoder::with_quality(source, level))
+            }
+            #[cfg(feature = "async-zstd")]
+            CompressionMethod::Zstd => Self::Zstd(ZstdEncoder::with_quality(source, level)),
+            // Stored; the check rejects every other method
+            _ => Self::Stored(source),
         })
-        .collect();
+    }
+
+    /// The source, once everything has been read
+    fn into_inner(self) -> CrcReader<R> {
+        match self {
+            Self::Deflate(encoder) => encoder.into_inner(),
+            #[cfg(feature = "async-zstd")]
+            Self::Zstd(encoder) => encoder.into_inner(),
+            Self::Stored(source) => source,
+        }
+    }
+}
 
-    // Drop sender so receiver knows when all tasks are done
-    drop(tx);
+impl<R: AsyncBufRead + Unpin> AsyncRead for Encoder<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        match self.get_mut() {
+            Self::Deflate(encoder) => Pin::new(encoder).poll_read(cx, buf),
+            #[cfg(feature = "async-zstd")]
+            Self::Zstd(encoder) => Pin::new(encoder).poll_read(cx, buf),
+            Self::Stored(source) => Pin::new(source).poll_read(cx, buf),
+        }
+    }
+}
 
-    // Collect results maintaining original order
-    let mut results = Vec::new();
-    while let Some((index, entry)) = rx.recv().await {
-        results.push((index, entry));
+/// Fail for methods parallel compression doesn't support
+pub(crate) fn check_method(method: CompressionMethod) -> Result<()> {
+    match method {
+        CompressionMethod::Deflate | CompressionMethod::Stored => Ok(()),
+        #[cfg(all(feature = "zstd-support", feature = "async-zstd"))]
+        CompressionMethod::Zstd => Ok(()),
+        #[cfg(all(feature = "zstd-support", not(feature = "async-zstd")))]
+        CompressionMethod::Zstd => Err(SZipError::InvalidFormat(
+            "Zstd compression requires 'async-zstd' feature".to_string(),
+        )),
+        #[allow(unreachable_patterns)]
+        _ => Err(SZipError::InvalidFormat(
+            "Only DEFLATE, Zstd and Stored supported in parallel compression".to_string(),
+        )),
     }
+}
 
-    // Wait for all tasks to complete
-    for handle in handles {
-        handle
-            .await
-            .map_err(|_e| SZipError::InvalidFormat("Task join error".to_string()))??;
+/// Compress an entry in a true single-pass stream.
+///
+/// The pipeline is: `source → CrcReader → encoder → chunks`, where
+/// a file or reader source is read through a 64 KB `BufReader`. CRC32 and
+/// the size are computed on-the-fly over the raw bytes as they are read by
+/// the encoder, and the output is handed over [`CHUNK_LEN`] bytes at a
+/// time, so neither the data nor its compressed form is ever held in full.
+async fn compress_entry(
+    entry: ParallelEntry,
+    config: &ParallelConfig,
+    sender: &mut ChunkSender,
+) -> Result<(Vec<u8>, EntryTotals)> {
+    let started = std::time::Instant::now();
+
+    // source → CrcReader (hashes bytes as read)
+    let crc_reader = CrcReader::new(open_source(entry.source).await?);
+
+    // CrcReader → encoder: bytes are hashed on-the-fly as the encoder
+    // pulls them; no intermediate Vec<u8> is needed.
+    let mut encoder = Encoder::new(crc_reader, config)?;
+
+    // The final chunk, shorter than the others, is left to the caller
+    let last = loop {
+        let mut chunk = Vec::with_capacity(CHUNK_LEN);
+        (&mut encoder)
+            .take(CHUNK_LEN as u64)
+            .read_to_end(&mut chunk)
+            .await?;
+        if chunk.len() < CHUNK_LEN {
+            break chunk;
+        }
+        sender.push(chunk).await?;
+    };
+
+    // Unwrap the encoder to recover the CrcReader and obtain the digest.
+    let (crc32, uncompressed_size) = encoder.into_inner().finalize();
+    let totals = EntryTotals {
+        uncompressed_size,
+        crc32,
+        elapsed: started.elapsed(),
+    };
+    Ok((last, totals))
+}
+
+/// Start compressing multiple entries in parallel with bounded concurrency
+/// and buffering
+pub(crate) fn compress_entries_parallel(
+    entries: Vec<ParallelEntry>,
+    config: ParallelConfig,
+) -> ParallelCompression {
+    let semaphore = Arc::new(Semaphore::new(config.max_concurrent.max(1)));
+    let buffered = MemoryBudget::new(config.max_buffered);
+    let (turn, _) = watch::channel(0);
+
+    let mut receivers = Vec::with_capacity(entries.len());
+    let mut senders = Vec::with_capacity(entries.len());
+    for (index, entry) in entries.into_iter().enumerate() {
+        // One chunk in flight: the entry whose turn it is waits for the writer
+        let (tx, rx) = mpsc::channel(1);
+        receivers.push((entry.name.clone(), rx));
+        senders.push((
+            entry,
+            ChunkSender {
+                index,
+                chunks: tx,
+                turn: turn.subscribe(),
+                buffered: buffered.clone(),
+                held: Vec::new(),
+                streaming: false,
+            },
+        ));
     }
 
-    // Sort by original index and extract entries
-    results.sort_by_key(|(index, _)| *index);
-    Ok(results.into_iter().map(|(_, entry)| entry).collect())
+    // Tasks are admitted in order, so the entry whose turn it is always has
+    // a permit or is done compressing, and never waits for later entries
+    let mut driver = JoinSet::new();
+    driver.spawn(async move {
+        let mut tasks = JoinSet::new();
+        for (entry, mut sender) in senders {
+            let Ok(permit) = semaphore.clone().acquire_owned().await else {
+                return;
+            };
+            let memory = match &config.memory_budget {
+                Some(budget) => Some(budget.reserve(TASK_MEMORY).await),
+                None => None,
+            };
+            let config = config.clone();
+            tasks.spawn(async move {
+                let compressed = compress_entry(entry, &config, &mut sender).await;
+                drop((permit, memory));
+                let sent = match compressed {
+                    Ok((last, totals)) => sender.finish(last, totals).await,
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = sent {
+                    let _ = sender.chunks.send(Err(e)).await;
+                }
+            });
+        }
+        // Aborting the driver drops `tasks`, which aborts them too
+        while tasks.join_next().await.is_some() {}
+    });
+
+    ParallelCompression {
+        entries: receivers.into_iter(),
+        index: 0,
+        turn,
+        _tasks: driver,
+    }
 }
 
 #[cfg(test)]
@@ -346,9 +697,9 @@ mod tests {
     fn test_memory_estimation() {
         let config = ParallelConfig::balanced();
         let estimated = config.estimated_peak_memory_mb();
-        // After v0.11.3 CrcReader pipeline: ~1 MB per task (was 4 MB).
-        // balanced() has max_concurrent=4.
-        assert_eq!(estimated, 4); // 4 concurrent × 1 MB
+        // ~1 MB per task plus the compressed data waiting for its turn.
+        // balanced() has max_concurrent=4 and max_buffered=32 MiB.
+        assert_eq!(estimated, 4 + 32); // 4 concurrent × 1 MB + 32 MB
     }
 
     #[test]
@@ -0,0 +1,115 @@
+//! Positioned-read (`pread`) file backend.
+//!
+//! [`PositionedFile`] reads a local file with `FileExt::read_at` (Unix) or
+//! `FileExt::seek_read` (Windows) instead of moving the OS file cursor. Every
+//! handle keeps its own logical position, and clones share one file
+//! descriptor, so concurrent entry reads on the same archive never contend on a
+//! shared seek cursor.
+//!
+//! ```no_run
+//! use s_zip::StreamingZipReader;
+//!
+//! let reader = StreamingZipReader::open_positioned("archive.zip")?;
+//! let names: Vec<String> = reader.entries().iter().map(|e| e.name.clone()).collect();
+//!
+//! let handles: Vec<_> = names
+//!     .into_iter()
+//!     .map(|name| {
+//!         // Shares the file descriptor and parsed central directory
+//!         let mut reader = reader.clone_handle();
+//!         std::thread::spawn(move || reader.read_entry_by_name(&name))
+//!     })
+//!     .collect();
+//! for handle in handles {
+//!     let data = handle.join().unwrap()?;
+//!     println!("{} bytes", data.len());
+//! }
+//! # Ok::<(), s_zip::SZipError>(())
+//! ```
+
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Read-only file handle with an independent position, backed by positioned reads.
+///
+/// Cloning is cheap: the clone shares the underlying `File` but starts with
+/// its own copy of the current position.
+#[derive(Debug, Clone)]
+pub struct PositionedFile {
+    file: Arc<File>,
+    pos: u64,
+    len: u64,
+}
+
+impl PositionedFile {
+    /// Open a file for positioned reads
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::new(File::open(path)?)
+    }
+
+    /// Wrap an already-open file
+    pub fn new(file: File) -> io::Result<Self> {
+        let len = file.metadata()?.len();
+        Ok(Self {
+            file: Arc::new(file),
+            pos: 0,
+            len,
+        })
+    }
+
+    /// Length of the file when it was opened
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Whether the file was empty when it was opened
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[cfg(unix)]
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+        use std::os::unix::fs::FileExt;
+        self.file.read_at(buf, offset)
+    }
+
+    #[cfg(windows)]
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+        use std::os::windows::fs::FileExt;
+        self.file.seek_read(buf, offset)
+    }
+}
+
+impl Read for PositionedFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.read_at(buf, self.pos)?;
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+impl Seek for PositionedFile {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let new_pos = match pos {
+            SeekFrom::Start(n) => Some(n),
+            SeekFrom::Current(d) => self.pos.checked_add_signed(d),
+            SeekFrom::End(d) => self.len.checked_add_signed(d),
+        };
+        match new_pos {
+            Some(p) => {
+                self.pos = p;
+                Ok(p)
+            }
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )),
+        }
+    }
+
+    fn stream_position(&mut self) -> io::Result<u64> {
+        Ok(self.pos)
+    }
+}
@@ -0,0 +1,156 @@
+//! Compatibility with archives that bend the ZIP format.
+//!
+//! Writers in other ecosystems produce archives that `unzip` and the `zip`
+//! crate accept but a strict reading gets wrong. [`QuirksMode::Lenient`]
+//! handles the known cases, trusting the central directory wherever it and
+//! the local headers disagree:
+//!
+//! - **ZIP64 extra fields with every value** (Java, OpenOffice): the field
+//!   should only hold the values whose 32-bit counterparts are `0xFFFFFFFF`,
+//!   but some writers always store both sizes. Lenient reading takes the
+//!   values from their fixed positions when the field holds more than needed.
+//! - **Zero sizes with a data descriptor** (Java streaming writers): the
+//!   central directory records a compressed size of 0 for data that ends in
+//!   a data descriptor. The CRC-32 and sizes are recovered from the
+//!   descriptor when the archive is opened.
+//! - **Directories without a trailing slash** (Python `zipfile`): empty
+//!   entries marked as directories by their DOS or Unix attributes are
+//!   listed with a `/` appended, so they extract as directories.
+//! - **Local headers disagreeing with the central directory**: the
+//!   encryption flag is taken from the central directory, and
+//!   `verify()` / `quick_check()` no longer report mismatched local fields.
+//!
+//! ```no_run
+//! use s_zip::{QuirksMode, StreamingZipReader};
+//!
+//! let mut reader = StreamingZipReader::open_with_quirks("export.jar", QuirksMode::Lenient)?;
+//! let manifest = reader.read_entry_by_name("META-INF/MANIFEST.MF")?;
+//! # Ok::<(), s_zip::SZipError>(())
+//! ```
+
+use crate::format::{parse_zip64_extra_field, ZipEntry};
+
+/// How strictly archives are parsed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QuirksMode {
+    /// Follow the specification (the default)
+    #[default]
+    Strict,
+    /// Accept the known non-conformances listed in the [module docs](self)
+    Lenient,
+}
+
+impl QuirksMode {
+    /// Whether this is [`QuirksMode::Lenient`]
+    pub fn is_lenient(self) -> bool {
+        self == QuirksMode::Lenient
+    }
+}
+
+/// MS-DOS directory attribute
+const DOS_DIRECTORY: u32 = 0x10;
+/// Unix file type bits and the directory type, in the upper half of the
+/// external attributes
+const UNIX_TYPE_MASK: u32 = 0o170000 << 16;
+const UNIX_DIRECTORY: u32 = 0o040000 << 16;
+
+/// Resolve ZIP64 placeholders, also accepting a ZIP64 extra field that holds
+/// every value in its fixed position
+pub(crate) fn resolve_zip64(
+    extra: &[u8],
+    compressed_32: u64,
+    uncompressed_32: u64,
+    offset_32: u64,
+) -> (u64, u64, u64) {
+    let strict = || parse_zip64_extra_field(extra, compressed_32, uncompressed_32, offset_32);
+    let Some(field) = zip64_field(extra) else {
+        return strict();
+    };
+    let placeholders = [uncompressed_32, compressed_32, offset_32]
+        .iter()
+        .filter(|&&v| v == 0xFFFFFFFF)
+        .count();
+    if field.len() / 8 <= placeholders {
+        return strict();
+    }
+    let value = |i: usize, fallback: u64| match field.get(i * 8..i * 8 + 8) {
+        Some(bytes) if fallback == 0xFFFFFFFF => u64::from_le_bytes(bytes.try_into().unwrap()),
+        _ => fallback,
+    };
+    (
+        value(0, uncompressed_32),
+        value(1, compressed_32),
+        value(2, offset_32),
+    )
+}
+
+/// Data of the ZIP64 extra field (tag `0x0001`)
+fn zip64_field(extra: &[u8]) -> Option<&[u8]> {
+    let mut i = 0;
+    while i + 4 <= extra.len() {
+        let id = u16::from_le_bytes([extra[i], extra[i + 1]]);
+        let len = u16::from_le_bytes([extra[i + 2], extra[i + 3]]) as usize;
+        let data = extra.get(i + 4..i + 4 + len)?;
+        if id == 0x0001 {
+            return Some(data);
+        }
+        i += 4 + len;
+    }
+    None
+}
+
+/// Append the missing `/` to an empty entry whose attributes mark it as a
+/// directory
+pub(crate) fn mark_directory(entry: &mut ZipEntry) {
+    let attrs = entry.external_attrs;
+    let is_dir = attrs & DOS_DIRECTORY != 0 || attrs & UNIX_TYPE_MASK == UNIX_DIRECTORY;
+    if is_dir
+        && entry.uncompressed_size == 0
+        && !entry.name.is_empty()
+        && !entry.name.ends_with('/')
+        && !entry.name.ends_with('\\')
+    {
+        entry.name.push('/');
+    }
+}
+
+/// Whether the central directory left the sizes of this entry to its data
+/// descriptor: compressed data can't be empty, so a zero size is a placeholder
+pub(crate) fn needs_descriptor_sizes(entry: &ZipEntry) -> bool {
+    entry.flags & 0x08 != 0 && entry.compressed_size == 0 && entry.compression_method != 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zip64_extra(values: &[u64]) -> Vec<u8> {
+        let mut extra = vec![0x01, 0x00];
+        extra.extend_from_slice(&((values.len() * 8) as u16).to_le_bytes());
+        for v in values {
+            extra.extend_from_slice(&v.to_le_bytes());
+        }
+        extra
+    }
+
+    #[test]
+    fn test_zip64_field_with_every_value() {
+        // Only the compressed size is a placeholder, but both sizes are stored
+        let extra = zip64_extra(&[5_000_000_000, 4_900_000_000]);
+        assert_eq!(
+            parse_zip64_extra_field(&extra, 0xFFFFFFFF, 100, 0),
+            (100, 5_000_000_000, 0)
+        );
+        assert_eq!(
+            resolve_zip64(&extra, 0xFFFFFFFF, 100, 0),
+            (100, 4_900_000_000, 0)
+        );
+
+        // A field holding just the placeholders reads as before
+        let extra = zip64_extra(&[4_900_000_000]);
+        assert_eq!(
+            resolve_zip64(&extra, 0xFFFFFFFF, 100, 0),
+            (100, 4_900_000_000, 0)
+        );
+    }
+}
@@ -0,0 +1,442 @@
+//! Low-level ZIP record writers.
+//!
+//! These are the building blocks the streaming writers use to serialize local
+//! file headers, data descriptors, central directory records and the end of
+//! central directory. They are exposed for advanced users (custom container
+//! formats, self-extracting archive builders) who need full control over the
+//! archive layout but want s-zip's ZIP64 handling:
+//!
+//! - sizes and offsets are passed as `u64`; when a value does not fit in the
+//!   32-bit field, the `0xFFFFFFFF` marker and a ZIP64 extra field (or ZIP64
+//!   EOCD record and locator) are written automatically.
+//! - every function returns the number of bytes written, so callers can track
+//!   offsets without `Seek`.
+//!
+//! # Example
+//! ```
+//! use s_zip::raw::{self, CentralDirectoryRecord, EndOfCentralDirectory, LocalFileHeader};
+//! use s_zip::StreamingZipReader;
+//!
+//! let data = b"hello raw";
+//! let crc = crc32fast::hash(data);
+//! let mut out = Vec::new();
+//!
+//! let header = LocalFileHeader {
+//!     version_needed: 20,
+//!     crc32: crc,
+//!     compressed_size: data.len() as u64,
+//!     uncompressed_size: data.len() as u64,
+//!     name: b"hello.txt",
+//!     ..Default::default()
+//! };
+//! raw::write_local_header(&mut out, &header)?;
+//! out.extend_from_slice(data);
+//!
+//! let cd_offset = out.len() as u64;
+//! let cd_size = raw::write_central_record(
+//!     &mut out,
+//!     &CentralDirectoryRecord {
+//!         version_made_by: 20,
+//!         version_needed: 20,
+//!         crc32: crc,
+//!         compressed_size: data.len() as u64,
+//!         uncompressed_size: data.len() as u64,
+//!         name: b"hello.txt",
+//!         ..Default::default()
+//!     },
+//! )?;
+//! raw::write_eocd(
+//!     &mut out,
+//!     &EndOfCentralDirectory {
+//!         entry_count: 1,
+//!         cd_size,
+//!         cd_offset,
+//!         ..Default::default()
+//!     },
+//! )?;
+//!
+//! let mut reader = StreamingZipReader::from_vec(out)?;
+//! assert_eq!(reader.read_entry_by_name("hello.txt")?, data);
+//! # Ok::<(), s_zip::SZipError>(())
+//! ```
+
+use crate::error::{Result, SZipError};
+use std::io::Write;
+
+const U32_MAX: u64 = u32::MAX as u64;
+
+/// General purpose flag bit 0: the entry data is encrypted
+pub const FLAG_ENCRYPTED: u16 = 0x0001;
+/// General purpose flag bit 3: CRC-32 and sizes follow the data in a data descriptor
+pub const FLAG_DATA_DESCRIPTOR: u16 = 0x0008;
+/// General purpose flag bit 11: the name and comment are UTF-8
+pub const FLAG_UTF8: u16 = 0x0800;
+
+/// Spec version s-zip writes in the low byte of "version made by" (6.3)
+pub const VERSION_MADE_BY: u16 = 63;
+
+/// "Version needed to extract" for archives using ZIP64 structures (4.5)
+pub const VERSION_ZIP64: u16 = 45;
+
+/// Minimum "version needed to extract" for an entry stored with
+/// `compression_method`, optionally WinZip AES encrypted (APPNOTE 4.4.3.2).
+///
+/// ZIP64 is not accounted for here: the record writers raise the value to
+/// [`VERSION_ZIP64`] themselves whenever they emit ZIP64 fields.
+pub fn version_needed(compression_method: u16, aes_encrypted: bool) -> u16 {
+    let method = match compression_method {
+        0 => 10,            // stored
+        9 => 21,            // deflate64
+        12 => 46,           // bzip2
+        14 | 93 | 95 => 63, // LZMA, zstd, XZ
+        _ => 20,            // deflate and anything else
+    };
+    if aes_encrypted {
+        method.max(51)
+    } else {
+        method
+    }
+}
+
+/// "Version made by" for an entry with `external_attrs`: the Unix host when
+/// the upper half carries a Unix mode, so extractors apply it, otherwise MS-DOS
+pub fn version_made_by(external_attrs: u32) -> u16 {
+    if external_attrs >> 16 != 0 {
+        (crate::format::HOST_UNIX << 8) | VERSION_MADE_BY
+    } else {
+        VERSION_MADE_BY
+    }
+}
+
+/// [`FLAG_UTF8`] when `name` is not plain ASCII, otherwise 0
+///
+/// Without the flag, readers decode non-ASCII names with a legacy code page
+/// (usually CP437) and show them garbled.
+pub fn name_flags(name: &str) -> u16 {
+    if name.is_ascii() {
+        0
+    } else {
+        FLAG_UTF8
+    }
+}
+
+/// Raise a version-needed field to at least 4.5, keeping the high byte
+fn with_zip64(version_needed: u16) -> u16 {
+    (version_needed & 0xFF00) | (version_needed & 0x00FF).max(VERSION_ZIP64)
+}
+
+/// Fields of a local file header (`PK\x03\x04`)
+#[derive(Debug, Clone, Default)]
+pub struct LocalFileHeader<'a> {
+    /// Version needed to extract
+    pub version_needed: u16,
+    /// General purpose bit flag
+    pub flags: u16,
+    /// Compression method (0 = stored, 8 = deflate, 93 = zstd, 99 = AES)
+    pub compression_method: u16,
+    /// MS-DOS last modification time
+    pub last_mod_time: u16,
+    /// MS-DOS last modification date
+    pub last_mod_date: u16,
+    /// CRC-32 of the uncompressed data (0 when deferred to a data descriptor)
+    pub crc32: u32,
+    /// Compressed size (0 when deferred to a data descriptor)
+    pub compressed_size: u64,
+    /// Uncompressed size (0 when deferred to a data descriptor)
+    pub uncompressed_size: u64,
+    /// Entry name bytes
+    pub name: &'a [u8],
+    /// Extra field bytes (a ZIP64 field is prepended automatically when needed)
+    pub extra: &'a [u8],
+}
+
+/// Fields of a central directory file header (`PK\x01\x02`)
+#[derive(Debug, Clone, Default)]
+pub struct CentralDirectoryRecord<'a> {
+    /// Version made by (high byte: host OS, low byte: spec version)
+    pub version_made_by: u16,
+    /// Version needed to extract
+    pub version_needed: u16,
+    /// General purpose bit flag
+    pub flags: u16,
+    /// Compression method
+    pub compression_method: u16,
+    /// MS-DOS last modification time
+    pub last_mod_time: u16,
+    /// MS-DOS last modification date
+    pub last_mod_date: u16,
+    /// CRC-32 of the uncompressed data
+    pub crc32: u32,
+    /// Compressed size
+    pub compressed_size: u64,
+    /// Uncompressed size
+    pub uncompressed_size: u64,
+    /// Internal file attributes
+    pub internal_attrs: u16,
+    /// External file attributes (e.g. Unix mode in the high 16 bits)
+    pub external_attrs: u32,
+    /// Offset of the entry's local file header, from the start of its volume
+    pub local_header_offset: u64,
+    /// Volume holding the local file header (0 unless the archive is split)
+    pub disk_number_start: u32,
+    /// Entry name bytes
+    pub name: &'a [u8],
+    /// Extra field bytes (a ZIP64 field is appended automatically when needed)
+    pub extra: &'a [u8],
+    /// Entry comment bytes
+    pub comment: &'a [u8],
+}
+
+/// Fields of the end of central directory record
+#[derive(Debug, Clone, Default)]
+pub struct EndOfCentralDirectory<'a> {
+    /// Total number of central directory records
+    pub entry_count: u64,
+    /// Size of the central directory in bytes
+    pub cd_size: u64,
+    /// Offset of the start of the central directory, from the start of the
+    /// volume it starts on
+    pub cd_offset: u64,
+    /// Archive comment bytes
+    pub comment: &'a [u8],
+    /// Number of this volume, the last one of a split archive (0 otherwise)
+    pub disk_number: u32,
+    /// Volume the central directory starts on
+    pub cd_disk: u32,
+    /// Central directory records on this volume; `None` for all of them
+    pub entries_on_disk: Option<u64>,
+    /// Offset of these records from the start of this volume; `None` when
+    /// they directly follow the central directory, at `cd_offset + cd_size`
+    pub position: Option<u64>,
+    /// "Version made by" of the ZIP64 record, usually the writer's value
+    /// for the central directory records; its spec version is raised to
+    /// the version needed
+    pub version_made_by: u16,
+    /// "Version needed to extract" of the ZIP64 record: the highest of the
+    /// central directory records, raised to [`VERSION_ZIP64`]
+    pub version_needed: u16,
+}
+
+/// Volume numbers past 65535 would need the ZIP64 extra field, which split
+/// archives never get near
+fn u16_count(value: u32, what: &str) -> Result<u16> {
+    u16::try_from(value)
+        .map_err(|_| SZipError::InvalidFormat(format!("{} too large: {} (max 65535)", what, value)))
+}
+
+fn u16_len(len: usize, what: &str) -> Result<u16> {
+    u16::try_from(len).map_err(|_| {
+        SZipError::InvalidFormat(format!("{} too long: {} bytes (max 65535)", what, len))
+    })
+}
+
+/// Clamp a value to a 32-bit field, returning the `0xFFFFFFFF` ZIP64 marker when it overflows
+fn u32_or_marker(value: u64) -> u32 {
+    if value > U32_MAX {
+        0xFFFF_FFFF
+    } else {
+        value as u32
+    }
+}
+
+/// Write a local file header. Returns the number of bytes written.
+///
+/// If either size exceeds 32 bits, both size fields are set to `0xFFFFFFFF`,
+/// a ZIP64 extra field carrying both sizes is prepended to `extra`, and
+/// `version_needed` is raised to at least [`VERSION_ZIP64`].
+pub fn write_local_header<W: Write>(w: &mut W, header: &LocalFileHeader<'_>) -> Result<u64> {
+    let zip64 = header.compressed_size > U32_MAX || header.uncompressed_size > U32_MAX;
+
+    let mut extra = Vec::with_capacity(header.extra.len() + if zip64 { 20 } else { 0 });
+    if zip64 {
+        extra.extend_from_slice(&0x0001u16.to_le_bytes());
+        extra.extend_from_slice(&16u16.to_le_bytes());
+        extra.extend_from_slice(&header.uncompressed_size.to_le_bytes());
+        extra.extend_from_slice(&header.compressed_size.to_le_bytes());
+    }
+    extra.extend_from_slice(header.extra);
+
+    let name_len = u16_len(header.name.len(), "entry name")?;
+    let extra_len = u16_len(extra.len(), "extra field")?;
+
+    let version_needed = if zip64 {
+        with_zip64(header.version_needed)
+    } else {
+        header.version_needed
+    };
+
+    let mut buf = Vec::with_capacity(30 + header.name.len() + extra.len());
+    buf.extend_from_slice(&[0x50, 0x4b, 0x03, 0x04]); // signature
+    buf.extend_from_slice(&version_needed.to_le_bytes());
+    buf.extend_from_slice(&header.flags.to_le_bytes());
+    buf.extend_from_slice(&header.compression_method.to_le_bytes());
+    buf.extend_from_slice(&header.last_mod_time.to_le_bytes());
+    buf.extend_from_slice(&header.last_mod_date.to_le_bytes());
+    buf.extend_from_slice(&header.crc32.to_le_bytes());
+    if zip64 {
+        buf.extend_from_slice(&0xFFFF_FFFFu32.to_le_bytes());
+        buf.extend_from_slice(&0xFFFF_FFFFu32.to_le_bytes());
+    } else {
+        buf.extend_from_slice(&(header.compressed_size as u32).to_le_bytes());
+        buf.extend_from_slice(&(header.uncompressed_size as u32).to_le_bytes());
+    }
+    buf.extend_from_slice(&name_len.to_le_bytes());
+    buf.extend_from_slice(&extra_len.to_le_bytes());
+    buf.extend_from_slice(header.name);
+    buf.extend_from_slice(&extra);
+
+    w.write_all(&buf)?;
+    Ok(buf.len() as u64)
+}
+
+/// Write a data descriptor (`PK\x07\x08`). Returns the number of bytes written.
+///
+/// Sizes are written as 64-bit values (ZIP64 data descriptor) when either
+/// exceeds 32 bits, otherwise as 32-bit values.
+pub fn write_data_descriptor<W: Write>(
+    w: &mut W,
+    crc32: u32,
+    compressed_size: u64,
+    uncompressed_size: u64,
+) -> Result<u64> {
+    let mut buf = Vec::with_capacity(24);
+    buf.extend_from_slice(&[0x50, 0x4b, 0x07, 0x08]); // signature
+    buf.extend_from_slice(&crc32.to_le_bytes());
+    if compressed_size > U32_MAX || uncompressed_size > U32_MAX {
+        buf.extend_from_slice(&compressed_size.to_le_bytes());
+        buf.extend_from_slice(&uncompressed_size.to_le_bytes());
+    } else {
+        buf.extend_from_slice(&(compressed_size as u32).to_le_bytes());
+        buf.extend_from_slice(&(uncompressed_size as u32).to_le_bytes());
+    }
+
+    w.write_all(&buf)?;
+    Ok(buf.len() as u64)
+}
+
+/// Write a central directory file header. Returns the number of bytes written.
+///
+/// Sizes and the local header offset that exceed 32 bits are replaced with
+/// `0xFFFFFFFF` and carried in a ZIP64 extra field appended after `extra`;
+/// `version_needed` is then raised to at least [`VERSION_ZIP64`].
+pub fn write_central_record<W: Write>(
+    w: &mut W,
+    record: &CentralDirectoryRecord<'_>,
+) -> Result<u64> {
+    let mut extra = Vec::with_capacity(record.extra.len() + 28);
+    extra.extend_from_slice(record.extra);
+
+    let zip64 = record.uncompressed_size > U32_MAX
+        || record.compressed_size > U32_MAX
+        || record.local_header_offset > U32_MAX;
+    if zip64 {
+        // ZIP64 extra field: only the overflowing values, in spec order
+        let mut data: Vec<u8> = Vec::with_capacity(24);
+        if record.uncompressed_size > U32_MAX {
+            data.extend_from_slice(&record.uncompressed_size.to_le_bytes());
+        }
+        if record.compressed_size > U32_MAX {
+            data.extend_from_slice(&record.compressed_size.to_le_bytes());
+        }
+        if record.local_header_offset > U32_MAX {
+            data.extend_from_slice(&record.local_header_offset.to_le_bytes());
+        }
+        extra.extend_from_slice(&0x0001u16.to_le_bytes());
+        extra.extend_from_slice(&(data.len() as u16).to_le_bytes());
+        extra.extend_from_slice(&data);
+    }
+
+    let name_len = u16_len(record.name.len(), "entry name")?;
+    let extra_len = u16_len(extra.len(), "extra field")?;
+    let comment_len = u16_len(record.comment.len(), "entry comment")?;
+    let disk_start = u16_count(record.disk_number_start, "volume number")?;
+
+    let mut buf = Vec::with_capacity(46 + record.name.len() + extra.len() + record.comment.len());
+    buf.extend_from_slice(&[0x50, 0x4b, 0x01, 0x02]); // signature
+    buf.extend_from_slice(&record.version_made_by.to_le_bytes());
+    let version_needed = if zip64 {
+        with_zip64(record.version_needed)
+    } else {
+        record.version_needed
+    };
+    buf.extend_from_slice(&version_needed.to_le_bytes());
+    buf.extend_from_slice(&record.flags.to_le_bytes());
+    buf.extend_from_slice(&record.compression_method.to_le_bytes());
+    buf.extend_from_slice(&record.last_mod_time.to_le_bytes());
+    buf.extend_from_slice(&record.last_mod_date.to_le_bytes());
+    buf.extend_from_slice(&record.crc32.to_le_bytes());
+    buf.extend_from_slice(&u32_or_marker(record.compressed_size).to_le_bytes());
+    buf.extend_from_slice(&u32_or_marker(record.uncompressed_size).to_le_bytes());
+    buf.extend_from_slice(&name_len.to_le_bytes());
+    buf.extend_from_slice(&extra_len.to_le_bytes());
+    buf.extend_from_slice(&comment_len.to_le_bytes());
+    buf.extend_from_slice(&disk_start.to_le_bytes()); // disk number start
+    buf.extend_from_slice(&record.internal_attrs.to_le_bytes());
+    buf.extend_from_slice(&record.external_attrs.to_le_bytes());
+    buf.extend_from_slice(&u32_or_marker(record.local_header_offset).to_le_bytes());
+    buf.extend_from_slice(record.name);
+    buf.extend_from_slice(&extra);
+    buf.extend_from_slice(record.comment);
+
+    w.write_all(&buf)?;
+    Ok(buf.len() as u64)
+}
+
+/// Write the end of central directory. Returns the number of bytes written.
+///
+/// When the entry count, central directory size or offset overflow the
+/// classic fields, a ZIP64 end of central directory record and locator are
+/// written first (directly after the central directory, i.e. at
+/// `cd_offset + cd_size`), and the classic record carries the overflow markers.
+pub fn write_eocd<W: Write>(w: &mut W, eocd: &EndOfCentralDirectory<'_>) -> Result<u64> {
+    let comment_len = u16_len(eocd.comment.len(), "archive comment")?;
+
+    let disk_number = u16_count(eocd.disk_number, "volume number")?;
+    let cd_disk = u16_count(eocd.cd_disk, "volume number")?;
+    let entries_on_disk = eocd.entries_on_disk.unwrap_or(eocd.entry_count);
+    let need_zip64 =
+        eocd.entry_count > u16::MAX as u64 || eocd.cd_size > U32_MAX || eocd.cd_offset > U32_MAX;
+
+    let mut buf = Vec::with_capacity(98 + eocd.comment.len());
+
+    if need_zip64 {
+        // ZIP64 end of central directory record
+        buf.extend_from_slice(&[0x50, 0x4b, 0x06, 0x06]);
+        // size of the remaining record: version made by(2) + version needed(2)
+        // + disk numbers(4+4) + entries on disk(8) + total entries(8) + cd size(8) + cd offset(8)
+        buf.extend_from_slice(&44u64.to_le_bytes());
+        let version_needed = eocd.version_needed.max(VERSION_ZIP64);
+        let version_made_by =
+            eocd.version_made_by & 0xFF00 | (eocd.version_made_by & 0xFF).max(version_needed);
+        buf.extend_from_slice(&version_made_by.to_le_bytes());
+        buf.extend_from_slice(&version_needed.to_le_bytes());
+        buf.extend_from_slice(&eocd.disk_number.to_le_bytes()); // disk number
+        buf.extend_from_slice(&eocd.cd_disk.to_le_bytes()); // disk where central dir starts
+        buf.extend_from_slice(&entries_on_disk.to_le_bytes()); // entries on this disk
+        buf.extend_from_slice(&eocd.entry_count.to_le_bytes()); // total entries
+        buf.extend_from_slice(&eocd.cd_size.to_le_bytes());
+        buf.extend_from_slice(&eocd.cd_offset.to_le_bytes());
+
+        // ZIP64 end of central directory locator
+        buf.extend_from_slice(&[0x50, 0x4b, 0x06, 0x07]);
+        buf.extend_from_slice(&eocd.disk_number.to_le_bytes()); // disk with ZIP64 EOCD
+        let zip64_eocd_pos = eocd.position.unwrap_or(eocd.cd_offset + eocd.cd_size);
+        buf.extend_from_slice(&zip64_eocd_pos.to_le_bytes());
+        buf.extend_from_slice(&(eocd.disk_number + 1).to_le_bytes()); // total number of disks
+    }
+
+    // Classic end of central directory record
+    buf.extend_from_slice(&[0x50, 0x4b, 0x05, 0x06]);
+    buf.extend_from_slice(&disk_number.to_le_bytes()); // disk number
+    buf.extend_from_slice(&cd_disk.to_le_bytes()); // disk with central dir
+    let count16 = |count: u64| u16::try_from(count).unwrap_or(0xFFFF);
+    buf.extend_from_slice(&count16(entries_on_disk).to_le_bytes()); // entries on this disk
+    buf.extend_from_slice(&count16(eocd.entry_count).to_le_bytes()); // total entries
+    buf.extend_from_slice(&u32_or_marker(eocd.cd_size).to_le_bytes());
+    buf.extend_from_slice(&u32_or_marker(eocd.cd_offset).to_le_bytes());
+    buf.extend_from_slice(&comment_len.to_le_bytes());
+    buf.extend_from_slice(eocd.comment);
+
+    w.write_all(&buf)?;
+    Ok(buf.len() as u64)
+}
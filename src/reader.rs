@@ -3,34 +3,123 @@
 //! This is a minimal ZIP reader that can extract specific files from a ZIP archive
 //! without loading the entire central directory into memory.
 
+use crate::capabilities;
+use crate::deadline::DeadlineReader;
+use crate::decoder::{DecoderFn, Decoders};
+use crate::encoding::FilenameEncoding;
 use crate::error::{Result, SZipError};
-use crate::format::{
-    find_eocd_in_buffer, find_zip64_eocd_offset, parse_zip64_extra_field,
-    CENTRAL_DIRECTORY_SIGNATURE, END_OF_CENTRAL_DIRECTORY_SIGNATURE, LOCAL_FILE_HEADER_SIGNATURE,
-    MAX_ENTRY_ALLOC, ZIP64_END_OF_CENTRAL_DIRECTORY_SIGNATURE,
+use crate::extract::{self, ExtractConfig, ExtractOptions};
+use crate::format::{CentralDirectoryLocation, INITIAL_ALLOC_CAP, MAX_ENTRY_ALLOC};
+use crate::growing;
+use crate::names::NameTable;
+use crate::nested::NestedSource;
+use crate::quirks::{self, QuirksMode};
+use crate::report::{self, FormatReport};
+use crate::salvage::{self, SalvageReport};
+use crate::sansio::{
+    self, CentralRecordReader, DirectoryLocator, LocalHeader, RecordRead, LOCAL_HEADER_LEN,
 };
+use crate::split::{self, SplitSource};
+use crate::verify::{self, EntryProblem, EntryReport, VerifyReport};
 
-#[cfg(feature = "encryption")]
-use crate::format::parse_aes_extra_field_buf;
 use flate2::read::DeflateDecoder;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
-use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::io::{BufRead, BufReader, Cursor, Read, Seek, SeekFrom, Take};
 use std::path::Path;
+use std::sync::Arc;
+use std::time::Instant;
+
+#[cfg(any(unix, windows))]
+use crate::pread::PositionedFile;
 
+#[cfg(feature = "encryption")]
+use crate::decrypt_reader::sync::{CtrReader, DecryptingReader};
 #[cfg(feature = "encryption")]
 use crate::encryption::{AesDecryptor, AesStrength};
+#[cfg(feature = "zstd-support")]
+use crate::writer::ZSTD_WINDOW_LOG_MAX;
+#[cfg(feature = "zstd-support")]
+use crate::zstd_dict::{ZstdDictionary, DICTIONARY_ENTRY};
 
 // Re-export ZipEntry so existing `use s_zip::reader::ZipEntry` paths still compile.
 pub use crate::format::ZipEntry;
 
 /// Streaming ZIP archive reader with adaptive buffering
-pub struct StreamingZipReader {
-    file: BufReader<File>,
-    entries: Vec<ZipEntry>,
+///
+/// Generic over the underlying source; defaults to a buffered local file.
+/// [`new`](Self::new) reads from any `Read + Seek` source, such as a
+/// `Cursor<Vec<u8>>`, a memory-mapped region or a block device.
+pub struct StreamingZipReader<R: Read + Seek = BufReader<File>> {
+    file: R,
+    /// Shared with handles made by [`clone_handle`](Self::clone_handle)
+    entries: Arc<Vec<ZipEntry>>,
+    comment: Vec<u8>,
+    /// `None` for salvaged archives, which have no usable central directory
+    cd_location: Option<CentralDirectoryLocation>,
+    decoders: Decoders,
+    #[cfg(feature = "zstd-support")]
+    zstd_dictionary: Option<ZstdDictionary>,
+    /// How the archive was opened, reused when its directory is read again
+    options: ReaderOptions,
+    /// Entry filter the reader was opened with, reapplied on reload
+    filter: Option<NameFilter>,
     #[cfg(feature = "encryption")]
     password: Option<String>,
 }
 
+/// Entry name filter kept by a reader opened with one
+pub(crate) type NameFilter = Arc<dyn Fn(&str) -> bool + Send + Sync>;
+
+/// Sync ZIP reader over any `Read + Seek` source, the counterpart of
+/// `GenericAsyncZipReader`
+pub type GenericZipReader<R> = StreamingZipReader<R>;
+
+/// How [`StreamingZipReader::open_with_options`] parses an archive
+#[derive(Debug, Clone, Default)]
+pub struct ReaderOptions {
+    /// Format non-conformances to accept. Default: [`QuirksMode::Strict`].
+    pub quirks: QuirksMode,
+    /// How names without the UTF-8 flag are decoded. Default:
+    /// [`FilenameEncoding::Auto`].
+    pub filename_encoding: FilenameEncoding,
+    /// Known location of the central directory, e.g. from
+    /// [`StreamingZipReader::cd_location`] of an earlier open. Skips the scan
+    /// for the EOCD record, so the archive comment is empty. Default: `None`.
+    pub cd_location: Option<CentralDirectoryLocation>,
+    /// Fail with `SZipError::DeadlineExceeded` once this passes, while the
+    /// central directory is read (also by `refresh` and `reload`) and while
+    /// entries are read. Default: `None`.
+    pub deadline: Option<Instant>,
+}
+
+impl ReaderOptions {
+    /// Set the accepted non-conformances
+    pub fn with_quirks(mut self, quirks: QuirksMode) -> Self {
+        self.quirks = quirks;
+        self
+    }
+
+    /// Set how names without the UTF-8 flag are decoded
+    pub fn with_filename_encoding(mut self, encoding: FilenameEncoding) -> Self {
+        self.filename_encoding = encoding;
+        self
+    }
+
+    /// Read the central directory at a known location
+    pub fn with_cd_location(mut self, location: CentralDirectoryLocation) -> Self {
+        self.cd_location = Some(location);
+        self
+    }
+
+    /// Give up opening and reading the archive once `deadline` passes
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+}
+
 impl StreamingZipReader {
     /// Open a ZIP file and read its central directory with default buffer size
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
@@ -64,17 +153,503 @@ impl StreamingZipReader {
 
         // Use adaptive buffer size
         let buf_size = buffer_size.unwrap_or(512 * 1024); // Default 512KB
-        let mut file = BufReader::with_capacity(buf_size, file);
+        let file = BufReader::with_capacity(buf_size, file);
 
-        // Find and read central directory
-        let entries = Self::read_central_directory(&mut file)?;
+        Self::from_source(file)
+    }
+
+    /// Open a damaged or truncated ZIP file by scanning its local headers.
+    ///
+    /// Unlike [`open`](Self::open), this does not need the central directory,
+    /// so archives cut off by an interrupted upload still yield every entry
+    /// that is fully present.  The returned reader only lists those entries;
+    /// the [`SalvageReport`] names the entries that were lost or partial.
+    ///
+    /// Salvaged entries carry only what local headers store: external
+    /// attributes are zero and the archive comment is empty.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use s_zip::StreamingZipReader;
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let (mut reader, report) = StreamingZipReader::open_salvage("upload.zip.part")?;
+    /// reader.extract_to_dir("recovered")?;
+    /// if !report.is_complete() {
+    ///     eprintln!("lost entries: {:?}", report.lost);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn open_salvage<P: AsRef<Path>>(path: P) -> Result<(Self, SalvageReport)> {
+        let file = BufReader::with_capacity(512 * 1024, File::open(path)?);
+        Self::salvage_source(file)
+    }
+
+    /// Open a ZIP file that may still be growing, such as an append-only log
+    /// of concatenated archives or an archive a writer finalizes periodically.
+    ///
+    /// Unlike [`open`](Self::open), this tolerates bytes after the newest
+    /// end of central directory record, e.g. an entry still being written,
+    /// and lists the entries of every archive in the log. Call
+    /// [`refresh`](Self::refresh) to pick up archives finalized later.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use s_zip::StreamingZipReader;
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut reader = StreamingZipReader::open_growing("events.zip")?;
+    /// loop {
+    ///     for entry in reader.entries() {
+    ///         println!("{}", entry.name);
+    ///     }
+    ///     std::thread::sleep(std::time::Duration::from_secs(5));
+    ///     reader.refresh()?;
+    /// }
+    /// # }
+    /// ```
+    pub fn open_growing<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let file = BufReader::with_capacity(512 * 1024, File::open(path)?);
+        Self::from_source_growing(file)
+    }
+
+    /// Open a ZIP file accepting the non-conformances that `quirks` allows.
+    ///
+    /// With [`QuirksMode::Lenient`], archives from Java, Python `zipfile` and
+    /// OpenOffice that bend the format open with correct sizes and directory
+    /// entries; see [`crate::quirks`] for the list.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use s_zip::{QuirksMode, StreamingZipReader};
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let reader = StreamingZipReader::open_with_quirks("upload.zip", QuirksMode::Lenient)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn open_with_quirks<P: AsRef<Path>>(path: P, quirks: QuirksMode) -> Result<Self> {
+        Self::open_with_options(path, ReaderOptions::default().with_quirks(quirks))
+    }
+
+    /// Open a ZIP file parsing it as `options` describe: the quirks to
+    /// accept, the encoding of names without the UTF-8 flag and, optionally,
+    /// where the central directory is.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use s_zip::encoding::FilenameEncoding;
+    /// # use s_zip::{ReaderOptions, StreamingZipReader};
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let options = ReaderOptions::default().with_filename_encoding(FilenameEncoding::Cp437);
+    /// let reader = StreamingZipReader::open_with_options("legacy.zip", options)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn open_with_options<P: AsRef<Path>>(path: P, options: ReaderOptions) -> Result<Self> {
+        let file = BufReader::with_capacity(512 * 1024, File::open(path)?);
+        Self::from_source_filtered(file, None, options)
+    }
+
+    /// Open a ZIP file keeping only the entries whose name passes `keep`.
+    ///
+    /// Every central directory record is still parsed, but rejected entries
+    /// are dropped immediately, so memory stays proportional to the subset
+    /// of interest.  `entries()`, `find_entry()` and extraction only see the
+    /// kept entries, also after [`reload`](Self::reload) and
+    /// [`refresh`](Self::refresh).
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use s_zip::StreamingZipReader;
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let reader = StreamingZipReader::open_filtered("huge.zip", |name| name.ends_with(".json"))?;
+    /// println!("{} JSON entries", reader.entries().len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn open_filtered<P: AsRef<Path>>(
+        path: P,
+        keep: impl Fn(&str) -> bool + Send + Sync + 'static,
+    ) -> Result<Self> {
+        let file = BufReader::with_capacity(512 * 1024, File::open(path)?);
+        Self::from_source_filtered(file, Some(Arc::new(keep)), ReaderOptions::default())
+    }
+
+    /// Open a ZIP file keeping only entries whose name starts with one of
+    /// `prefixes`, e.g. `&["metadata/"]`; see [`open_filtered`](Self::open_filtered)
+    pub fn open_with_prefixes<P: AsRef<Path>>(path: P, prefixes: &[&str]) -> Result<Self> {
+        let file = BufReader::with_capacity(512 * 1024, File::open(path)?);
+        Self::from_source_filtered(
+            file,
+            Some(prefix_filter(prefixes)),
+            ReaderOptions::default(),
+        )
+    }
+
+    /// Open a ZIP file whose central directory location is already known,
+    /// e.g. from [`cd_location`](Self::cd_location) of an earlier open.
+    ///
+    /// Skips the backward scan for the EOCD record and reads the central
+    /// directory in a single read.  The EOCD record isn't read, so
+    /// [`comment`](Self::comment) is empty.  Fails with
+    /// `SZipError::InvalidFormat` if the location doesn't match the archive.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use s_zip::StreamingZipReader;
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let location = StreamingZipReader::open("archive.zip")?.cd_location().unwrap();
+    /// // ... later, e.g. after loading `location` from a cache
+    /// let reader = StreamingZipReader::open_with_cd_location(
+    ///     "archive.zip",
+    ///     location.offset,
+    ///     location.size,
+    ///     location.count,
+    /// )?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn open_with_cd_location<P: AsRef<Path>>(
+        path: P,
+        offset: u64,
+        size: u64,
+        count: u64,
+    ) -> Result<Self> {
+        let location = CentralDirectoryLocation {
+            offset,
+            size,
+            count,
+        };
+        Self::open_with_options(path, ReaderOptions::default().with_cd_location(location))
+    }
+
+    /// Open a ZIP file without parsing its central directory upfront.
+    ///
+    /// Only the end of central directory record is read; entries are parsed
+    /// one at a time as the returned [`LazyEntries`] is iterated, so listing
+    /// the first few entries of a huge archive costs neither the full parse
+    /// nor memory for every entry.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use s_zip::StreamingZipReader;
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut entries = StreamingZipReader::open_lazy("huge.zip")?;
+    /// let first: Vec<_> = entries.by_ref().take(10).collect::<Result<_, _>>()?;
+    /// let data = entries.read_entry(&first[0])?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn open_lazy<P: AsRef<Path>>(path: P) -> Result<LazyEntries> {
+        let file = BufReader::with_capacity(512 * 1024, File::open(path)?);
+        LazyEntries::new(file)
+    }
+
+    /// Open a ZIP file keeping only a compact name index in memory.
+    ///
+    /// See [`IndexedZipReader`]: suited to archives with millions of entries,
+    /// where a full `Vec<ZipEntry>` would take hundreds of MB.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use s_zip::StreamingZipReader;
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut reader = StreamingZipReader::open_indexed("catalog.zip")?;
+    /// println!("{} entries", reader.len());
+    /// let data = reader.read_entry_by_name("items/000042.json")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn open_indexed<P: AsRef<Path>>(path: P) -> Result<IndexedZipReader> {
+        let file = BufReader::with_capacity(512 * 1024, File::open(path)?);
+        IndexedZipReader::new(file)
+    }
+
+    /// Open a ZIP file storing entry names in a shared [`NameTable`].
+    ///
+    /// See [`CompactZipReader`]: for deep directory trees, names take a
+    /// fraction of the memory of one `String` per entry.
+    pub fn open_compact<P: AsRef<Path>>(path: P) -> Result<CompactZipReader> {
+        let file = BufReader::with_capacity(512 * 1024, File::open(path)?);
+        CompactZipReader::new(file)
+    }
+}
+
+#[cfg(any(unix, windows))]
+impl StreamingZipReader<BufReader<PositionedFile>> {
+    /// Open a ZIP file using positioned reads (`pread`) instead of a shared seek cursor
+    ///
+    /// Readers opened this way can be duplicated with
+    /// [`clone_handle`](Self::clone_handle) to read entries concurrently from
+    /// several threads without reopening the file.
+    pub fn open_positioned<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let file = PositionedFile::open(path)?;
+        Self::from_source(BufReader::with_capacity(512 * 1024, file))
+    }
+
+    /// Create an independent reader that shares this one's file descriptor
+    /// and parsed central directory.
+    ///
+    /// The new reader has its own position and read buffer, so both can be
+    /// used concurrently (e.g. moved into separate threads).
+    pub fn clone_handle(&self) -> Self {
+        let file = BufReader::with_capacity(self.file.capacity(), self.file.get_ref().clone());
+        self.with_source(file)
+    }
+
+    /// Extract `entries` into `dir`, decompressing and writing up to
+    /// `config.workers` of them at once on as many threads.
+    ///
+    /// See [`StreamingZipReader::extract_entries_parallel`].
+    pub fn extract_entries_parallel<P: AsRef<Path>>(
+        &self,
+        entries: &[ZipEntry],
+        dir: P,
+        config: &ExtractConfig,
+    ) -> Result<()> {
+        self.extract_parallel_with(entries, dir.as_ref(), config, || Ok(self.clone_handle()))
+    }
+}
 
-        Ok(StreamingZipReader {
+#[cfg(any(unix, windows))]
+impl StreamingZipReader {
+    /// Extract `entries` into `dir`, decompressing and writing up to
+    /// `config.workers` of them at once on as many threads.
+    ///
+    /// Each thread reads the archive through its own positioned handle (see
+    /// [`open_positioned`](Self::open_positioned)), so unpacking archives of
+    /// many small files is no longer bound to one core and one outstanding
+    /// read. Paths, filters and metadata follow
+    /// [`extract_to_dir_with_options`](Self::extract_to_dir_with_options)
+    /// with `config.options`; symbolic links are created once every file is
+    /// written. The first failure stops the workers and is returned.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use s_zip::{ExtractConfig, StreamingZipReader};
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let reader = StreamingZipReader::open("node_modules.zip")?;
+    /// reader.extract_entries_parallel(reader.entries(), "out", &ExtractConfig::default())?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn extract_entries_parallel<P: AsRef<Path>>(
+        &self,
+        entries: &[ZipEntry],
+        dir: P,
+        config: &ExtractConfig,
+    ) -> Result<()> {
+        let file = PositionedFile::new(self.file.get_ref().try_clone()?)?;
+        self.extract_parallel_with(entries, dir.as_ref(), config, || {
+            let file = BufReader::with_capacity(self.file.capacity(), file.clone());
+            Ok(self.with_source(file))
+        })
+    }
+}
+
+impl StreamingZipReader<SplitSource<BufReader<File>>> {
+    /// Open a split archive from its last volume (`archive.zip`), finding the
+    /// earlier volumes next to it as `archive.z01`, `archive.z02`, ...
+    ///
+    /// See the [`crate::split`] module.
+    pub fn open_split<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_split(SplitSource::open_last(path)?)
+    }
+
+    /// Open a split archive from its volume files, in order from the first
+    /// to the last
+    pub fn open_split_volumes<P: AsRef<Path>>(paths: &[P]) -> Result<Self> {
+        Self::from_split(SplitSource::open(paths)?)
+    }
+}
+
+impl<R: Read + Seek> StreamingZipReader<SplitSource<R>> {
+    /// Read the central directory of a split archive from its joined volumes
+    ///
+    /// Entry offsets are converted from their volume to the joined stream;
+    /// [`ZipEntry::disk_number`] keeps the volume of each entry.
+    pub fn from_split(file: SplitSource<R>) -> Result<Self> {
+        Self::from_split_with_options(file, ReaderOptions::default())
+    }
+
+    /// Like [`from_split`](Self::from_split), parsing as `options` describe.
+    /// A known [`cd_location`](ReaderOptions::cd_location) is ignored: the
+    /// directory is always located through the last volume.
+    pub fn from_split_with_options(
+        mut file: SplitSource<R>,
+        mut options: ReaderOptions,
+    ) -> Result<Self> {
+        options.cd_location = None;
+        crate::deadline::check(options.deadline)?;
+        let count = file.volume_count();
+        let last = file.volume_start(count as u32 - 1).unwrap_or(0);
+        let locator = DirectoryLocator::for_last_volume(file.seek(SeekFrom::End(0))?, last);
+        let found = split::locate(&mut file, locator)?;
+        if found.disk as usize + 1 != count {
+            return Err(SZipError::InvalidFormat(format!(
+                "Split archive has {} volumes, but its last volume is number {}",
+                count,
+                found.disk as u64 + 1
+            )));
+        }
+        let starts: Vec<u64> = (0..count as u32)
+            .filter_map(|disk| file.volume_start(disk))
+            .collect();
+        let start = |disk: u32| {
+            starts.get(disk as usize).copied().ok_or_else(|| {
+                SZipError::InvalidFormat(format!(
+                    "Split archive refers to volume {} of {}",
+                    disk as u64 + 1,
+                    count
+                ))
+            })
+        };
+        let location = CentralDirectoryLocation {
+            offset: start(found.cd_disk)? + found.location.offset,
+            ..found.location
+        };
+        let mut entries = Self::read_central_directory_at(&mut file, location, &options)?;
+        for entry in &mut entries {
+            entry.offset += start(entry.disk_number)?;
+        }
+        Ok(Self::assemble(
             file,
             entries,
+            found.comment,
+            Some(location),
+            options,
+        ))
+    }
+}
+
+impl<'a> StreamingZipReader<Cursor<&'a [u8]>> {
+    /// Open a ZIP archive held in a borrowed byte slice
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use s_zip::StreamingZipReader;
+    /// # fn example(bytes: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut reader = StreamingZipReader::from_bytes(bytes)?;
+    /// let data = reader.read_entry_by_name("file.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self> {
+        Self::from_source(Cursor::new(bytes))
+    }
+}
+
+impl StreamingZipReader<Cursor<Vec<u8>>> {
+    /// Open a ZIP archive held in an owned byte buffer
+    pub fn from_vec(bytes: Vec<u8>) -> Result<Self> {
+        Self::from_source(Cursor::new(bytes))
+    }
+}
+
+impl<R: Read + Seek> StreamingZipReader<R> {
+    /// Read the central directory of `source` and build a reader over it
+    ///
+    /// `source` is read as given; wrap unbuffered sources in a `BufReader`.
+    ///
+    /// # Example
+    /// ```
+    /// # use s_zip::{GenericZipReader, StreamingZipWriter};
+    /// # use std::io::Cursor;
+    /// # fn example() -> s_zip::Result<()> {
+    /// # let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new()))?;
+    /// # writer.add_entry("file.txt", b"data")?;
+    /// # let bytes = writer.finish()?.into_inner();
+    /// let mut reader = GenericZipReader::new(Cursor::new(bytes))?;
+    /// let data = reader.read_entry_by_name("file.txt")?;
+    /// # assert_eq!(data, b"data");
+    /// # Ok(())
+    /// # }
+    /// # example().unwrap();
+    /// ```
+    pub fn new(source: R) -> Result<Self> {
+        Self::from_source(source)
+    }
+
+    /// Like [`new`](Self::new), parsing as `options` describe
+    pub fn new_with_options(source: R, options: ReaderOptions) -> Result<Self> {
+        Self::from_source_filtered(source, None, options)
+    }
+
+    /// Read the central directory from `file` and build a reader over it
+    fn from_source(file: R) -> Result<Self> {
+        Self::from_source_filtered(file, None, ReaderOptions::default())
+    }
+
+    /// Like `from_source`, keeping only entries whose name passes `filter`
+    /// and parsing as `options` describe
+    fn from_source_filtered(
+        mut file: R,
+        filter: Option<NameFilter>,
+        mut options: ReaderOptions,
+    ) -> Result<Self> {
+        // Find and read central directory
+        let (mut entries, comment, location) = match options.cd_location.take() {
+            Some(location) => {
+                let mut entries = Self::read_central_directory_at(&mut file, location, &options)?;
+                if let Some(keep) = &filter {
+                    entries.retain(|e| keep(&e.name));
+                }
+                (entries, Vec::new(), location)
+            }
+            None => Self::read_central_directory(&mut file, filter.as_ref(), &options)?,
+        };
+        if options.quirks.is_lenient() {
+            Self::recover_descriptor_sizes(&mut file, &mut entries)?;
+        }
+        let mut reader = Self::assemble(file, entries, comment, Some(location), options);
+        reader.filter = filter;
+        Ok(reader)
+    }
+
+    /// Reader over `file` with an already parsed entry list
+    fn assemble(
+        file: R,
+        entries: Vec<ZipEntry>,
+        comment: Vec<u8>,
+        cd_location: Option<CentralDirectoryLocation>,
+        options: ReaderOptions,
+    ) -> Self {
+        StreamingZipReader {
+            file,
+            entries: Arc::new(entries),
+            comment,
+            cd_location,
+            decoders: Decoders::default(),
+            #[cfg(feature = "zstd-support")]
+            zstd_dictionary: None,
+            options,
+            filter: None,
             #[cfg(feature = "encryption")]
             password: None,
-        })
+        }
+    }
+
+    /// Build a reader over the finalized archives in a growing `file`
+    fn from_source_growing(mut file: R) -> Result<Self> {
+        let options = ReaderOptions::default();
+        let (entries, comment, location) = Self::read_growing(&mut file, None, &options)?
+            .map(|(entries, comment, location, _)| (entries, comment, location))
+            .ok_or_else(|| {
+                SZipError::InvalidFormat("End of central directory not found".to_string())
+            })?;
+        Ok(Self::assemble(
+            file,
+            entries,
+            comment,
+            Some(location),
+            options,
+        ))
+    }
+
+    /// Build a reader from the local headers of `file`, ignoring the central directory
+    fn salvage_source(mut file: R) -> Result<(Self, SalvageReport)> {
+        let (entries, report) = salvage::scan_local_entries(&mut file)?;
+        let reader = Self::assemble(file, entries, Vec::new(), None, ReaderOptions::default());
+        Ok((reader, report))
     }
 
     /// Set password for decrypting encrypted entries
@@ -91,48 +666,262 @@ impl StreamingZipReader {
         self
     }
 
+    /// Fail reads with `SZipError::DeadlineExceeded` once `deadline` passes,
+    /// including reads already in progress.
+    ///
+    /// Opening the archive is not covered; to bound the central directory
+    /// scan as well, pass the deadline in
+    /// [`ReaderOptions::deadline`] instead.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use s_zip::StreamingZipReader;
+    /// use std::time::{Duration, Instant};
+    ///
+    /// let mut reader = StreamingZipReader::open("upload.zip")?
+    ///     .with_deadline(Instant::now() + Duration::from_secs(5));
+    /// let data = reader.read_entry_by_name("manifest.json")?;
+    /// # Ok::<(), s_zip::SZipError>(())
+    /// ```
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.options.deadline = Some(deadline);
+        self
+    }
+
+    /// Decompress entries using compression `method` with `decoder`.
+    ///
+    /// The decoder receives the entry's raw (decrypted) data and returns a
+    /// reader over the decompressed bytes; it replaces the built-in decoder
+    /// if there is one. See the [`decoder`](crate::decoder) module.
+    /// Registered decoders are not available through
+    /// [`into_entry_reader`](Self::into_entry_reader), whose stream must be
+    /// `Send`.
+    pub fn register_decoder<F>(&mut self, method: u16, decoder: F) -> &mut Self
+    where
+        F: for<'a> Fn(Box<dyn Read + 'a>) -> std::io::Result<Box<dyn Read + 'a>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.decoders.insert(method, Arc::new(decoder));
+        self
+    }
+
+    /// Decompress Zstd entries with `dictionary`.
+    ///
+    /// Without one, the dictionary embedded in the archive as
+    /// [`DICTIONARY_ENTRY`] is used, if there is one. See
+    /// [`crate::zstd_dict`].
+    #[cfg(feature = "zstd-support")]
+    pub fn set_zstd_dictionary(&mut self, dictionary: ZstdDictionary) -> &mut Self {
+        self.zstd_dictionary = Some(dictionary);
+        self
+    }
+
+    /// The dictionary to decompress `entry` with, loading the embedded one
+    /// the first time a Zstd entry needs it
+    #[cfg(feature = "zstd-support")]
+    fn zstd_dictionary_for(&mut self, entry: &ZipEntry) -> Result<Option<ZstdDictionary>> {
+        if entry.compression_method != 93 || entry.name == DICTIONARY_ENTRY {
+            return Ok(None);
+        }
+        if self.zstd_dictionary.is_none() {
+            if let Some(embedded) = self.find_entry(DICTIONARY_ENTRY).cloned() {
+                self.zstd_dictionary = Some(ZstdDictionary::new(self.read_entry(&embedded)?));
+            }
+        }
+        Ok(self.zstd_dictionary.clone())
+    }
+
+    /// How strictly the archive was parsed; see
+    /// [`open_with_quirks`](StreamingZipReader::open_with_quirks)
+    pub fn quirks_mode(&self) -> QuirksMode {
+        self.options.quirks
+    }
+
     /// Get list of all entries in the ZIP
     pub fn entries(&self) -> &[ZipEntry] {
         &self.entries
     }
 
-    /// Find an entry by name
-    pub fn find_entry(&self, name: &str) -> Option<&ZipEntry> {
-        self.entries.iter().find(|e| e.name == name)
+    /// Number of entries in the ZIP
+    pub fn entry_count(&self) -> usize {
+        self.entries.len()
     }
 
-    /// Read an entry's decompressed data into a vector
-    pub fn read_entry(&mut self, entry: &ZipEntry) -> Result<Vec<u8>> {
-        // Seek to local file header
-        self.file.seek(SeekFrom::Start(entry.offset))?;
+    /// Up to `limit` entries starting at `offset`, in central directory
+    /// order; empty once `offset` is past the last entry
+    pub fn entries_page(&self, offset: usize, limit: usize) -> &[ZipEntry] {
+        let start = offset.min(self.entries.len());
+        let end = start.saturating_add(limit).min(self.entries.len());
+        &self.entries[start..end]
+    }
 
-        // Read and verify local file header
-        let signature = self.read_u32_le()?;
-        if signature != LOCAL_FILE_HEADER_SIGNATURE {
-            return Err(SZipError::InvalidFormat(
-                "Invalid local file header signature".to_string(),
-            ));
+    /// Drop macOS Finder metadata (`.DS_Store`, `__MACOSX/`, `._` AppleDouble
+    /// files) from the entry list, so listings and lookups only see user content.
+    ///
+    /// To skip them while the central directory is parsed instead, open with a
+    /// filter that rejects [`crate::format::is_macos_metadata`].
+    pub fn hide_macos_metadata(&mut self) -> &mut Self {
+        Arc::make_mut(&mut self.entries).retain(|e| !crate::format::is_macos_metadata(&e.name));
+        self
+    }
+
+    /// Pick up archives finalized since the reader was opened or last
+    /// refreshed, returning whether the entry list changed.
+    ///
+    /// Looks for an end of central directory record past the current one,
+    /// tolerating a trailing partial entry as
+    /// [`open_growing`](Self::open_growing) does. An archive appended after
+    /// the current one adds its entries; a rewritten directory covering the
+    /// whole file replaces them. The new entries are parsed with the quirks,
+    /// filename encoding and filter the reader was opened with.
+    pub fn refresh(&mut self) -> Result<bool> {
+        let Some((mut entries, comment, location, extends)) =
+            Self::read_growing(&mut self.file, self.cd_location, &self.options)?
+        else {
+            return Ok(false);
+        };
+        if let Some(keep) = &self.filter {
+            entries.retain(|e| keep(&e.name));
         }
+        if self.options.quirks.is_lenient() {
+            Self::recover_descriptor_sizes(&mut self.file, &mut entries)?;
+        }
+        let current = Arc::make_mut(&mut self.entries);
+        if !extends {
+            current.clear();
+        }
+        current.extend(entries);
+        self.comment = comment;
+        self.cd_location = Some(location);
+        Ok(true)
+    }
 
-        // Skip version
-        self.file.seek(SeekFrom::Current(2))?;
+    /// Re-read the end of central directory record and the central directory
+    /// in place, replacing the entry list and comment.
+    ///
+    /// For an archive that another writer rewrote or appended to (with a new
+    /// central directory at the end) since it was opened. Unlike
+    /// [`refresh`](Self::refresh), the file must end with a complete EOCD
+    /// record. Entries are parsed with the quirks, filename encoding and
+    /// filter the reader was opened with.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use s_zip::StreamingZipReader;
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut reader = StreamingZipReader::open("bundle.zip")?;
+    /// // ... another process appends entries to bundle.zip ...
+    /// reader.reload()?;
+    /// println!("{} entries", reader.entry_count());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn reload(&mut self) -> Result<()> {
+        let (mut entries, comment, location) =
+            Self::read_central_directory(&mut self.file, self.filter.as_ref(), &self.options)?;
+        if self.options.quirks.is_lenient() {
+            Self::recover_descriptor_sizes(&mut self.file, &mut entries)?;
+        }
+        self.entries = Arc::new(entries);
+        self.comment = comment;
+        self.cd_location = Some(location);
+        Ok(())
+    }
 
-        // Read flags to check for encryption
-        let flags = self.read_u16_le()?;
-        let is_encrypted = (flags & 0x01) != 0;
+    /// Where the central directory is, for reopening the archive later with
+    /// [`open_with_cd_location`](StreamingZipReader::open_with_cd_location).
+    ///
+    /// `None` for readers created by `open_salvage`.
+    pub fn cd_location(&self) -> Option<CentralDirectoryLocation> {
+        self.cd_location
+    }
 
-        // Read compression method
-        let _compression_method = self.read_u16_le()?;
+    /// Archive comment stored in the end-of-central-directory record.
+    ///
+    /// Decoded with the reader's
+    /// [`filename_encoding`](crate::ReaderOptions::filename_encoding), like
+    /// entry names. Returns an empty string when the archive has no comment.
+    pub fn comment(&self) -> Cow<'_, str> {
+        self.options.filename_encoding.decode_comment(&self.comment)
+    }
 
-        // Skip modification time and date, CRC-32
-        self.file.seek(SeekFrom::Current(8))?;
+    /// Raw bytes of the archive comment, as stored
+    pub fn archive_comment(&self) -> &[u8] {
+        &self.comment
+    }
 
-        // Read compressed and uncompressed sizes (already known from central directory)
-        self.file.seek(SeekFrom::Current(8))?;
+    /// Whether this build can read `entry`: its compression method is
+    /// enabled and, if encrypted, it uses WinZip AES and a password is set.
+    ///
+    /// Checks the same conditions `read_entry` would fail on, without any I/O.
+    /// See [`capabilities()`](crate::capabilities()) for what is available.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use s_zip::StreamingZipReader;
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut reader = StreamingZipReader::open("archive.zip")?;
+    /// let readable: Vec<_> = reader
+    ///     .entries()
+    ///     .iter()
+    ///     .filter(|e| reader.can_read_entry(e))
+    ///     .cloned()
+    ///     .collect();
+    /// for entry in &readable {
+    ///     let data = reader.read_entry(entry)?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn can_read_entry(&self, entry: &ZipEntry) -> bool {
+        #[cfg(feature = "encryption")]
+        let has_password = self.password.is_some();
+        #[cfg(not(feature = "encryption"))]
+        let has_password = false;
+        let mut methods = capabilities::read_methods();
+        methods.extend(self.decoders.methods());
+        capabilities::can_read(entry, &methods, has_password)
+    }
+
+    /// Describe the ZIP format features this archive uses (ZIP64, data
+    /// descriptors, encryption, compression methods, name encodings, ...).
+    ///
+    /// Useful for explaining why another tool cannot open an archive; the
+    /// report's `Display` output is meant to be pasted into a support ticket.
+    pub fn format_report(&mut self) -> Result<FormatReport> {
+        let file_size = self.file.seek(SeekFrom::End(0))?;
+        self.file
+            .seek(SeekFrom::Start(file_size.saturating_sub(report::TAIL_LEN)))?;
+        let mut tail = Vec::new();
+        self.file.read_to_end(&mut tail)?;
+        Ok(FormatReport::new(&self.entries, &self.comment, &tail))
+    }
+
+    /// Find an entry by name
+    pub fn find_entry(&self, name: &str) -> Option<&ZipEntry> {
+        self.entries.iter().find(|e| e.name == name)
+    }
+
+    /// Read an entry's decompressed data into a vector
+    pub fn read_entry(&mut self, entry: &ZipEntry) -> Result<Vec<u8>> {
+        crate::deadline::check(self.options.deadline)?;
+        #[cfg(feature = "zstd-support")]
+        let zstd_dictionary = self.zstd_dictionary_for(entry)?;
 
-        // Read filename length and extra field length
-        let filename_len = self.read_u16_le()? as i64;
-        let extra_len = self.read_u16_le()? as usize;
+        // Seek to local file header
+        self.file.seek(SeekFrom::Start(entry.offset))?;
+
+        // Read and verify local file header; sizes come from the central directory
+        let header = Self::read_local_header(&mut self.file)?;
+        let is_encrypted = if self.options.quirks.is_lenient() {
+            entry.is_encrypted
+        } else {
+            (header.flags & 0x01) != 0
+        };
+        let filename_len = header.name_len as i64;
+        let extra_len = header.extra_len as usize;
 
         // Skip filename
         self.file.seek(SeekFrom::Current(filename_len))?;
@@ -140,7 +929,7 @@ impl StreamingZipReader {
         // Check for AES encryption in extra field
         #[cfg(feature = "encryption")]
         let encryption_info = if is_encrypted {
-            self.parse_aes_extra_field(extra_len)?
+            Self::parse_aes_extra_field(&mut self.file, extra_len)?
         } else {
             // Skip extra field if not encrypted
             self.file.seek(SeekFrom::Current(extra_len as i64))?;
@@ -172,82 +961,53 @@ impl StreamingZipReader {
         #[cfg(not(feature = "encryption"))]
         let data_size = entry.compressed_size;
 
-        // Guard against OOM from corrupt/malicious compressed_size values.
-        // Entries larger than 2 GiB must use read_entry_streaming() instead.
-        if data_size > MAX_ENTRY_ALLOC {
-            return Err(SZipError::InvalidFormat(format!(
-                "Entry '{}' is too large to read into memory ({} bytes). \
-                 Use read_entry_streaming() for entries larger than 2 GiB.",
-                entry.name, data_size
-            )));
-        }
-
-        // Now read the compressed data
-        let mut compressed_data = vec![0u8; data_size as usize];
-        self.file.read_exact(&mut compressed_data)?;
-
-        // Read auth code if encrypted
         #[cfg(feature = "encryption")]
-        let auth_code = if encryption_info.is_some() {
-            let mut ac = vec![0u8; 10];
-            self.file.read_exact(&mut ac)?;
-            Some(ac)
-        } else {
-            None
-        };
+        let data_start = self.file.stream_position()?;
 
-        // Decrypt if encrypted (Step 1: Decrypt compressed data)
+        // Step 1: set up decryption (password verification happens inside new())
         #[cfg(feature = "encryption")]
-        let decryptor_opt = if let Some((strength, salt, pw_verify)) = encryption_info {
+        let mut decryptor_opt = if let Some((strength, salt, pw_verify)) = encryption_info {
             let password = self.password.as_ref().ok_or_else(|| {
                 SZipError::InvalidFormat("Encrypted entry but no password set".to_string())
             })?;
-
-            // Create decryptor (password verification happens inside new())
-            let mut decryptor = AesDecryptor::new(password, strength, &salt, &pw_verify)?;
-
-            // Decrypt compressed data in-place
-            decryptor.decrypt(&mut compressed_data)?;
-
-            Some(decryptor)
+            Some(AesDecryptor::new(password, strength, &salt, &pw_verify)?)
         } else {
             None
         };
 
-        // Decompress if needed (Step 2: Decompress decrypted data)
-        let data = if entry.compression_method == 8 {
-            // DEFLATE compression
-            let mut decoder = DeflateDecoder::new(&compressed_data[..]);
-            let mut decompressed = Vec::new();
-            decoder.read_to_end(&mut decompressed)?;
-            decompressed
-        } else if entry.compression_method == 0 {
-            // No compression (stored)
-            compressed_data
-        } else if entry.compression_method == 93 {
-            // Zstd compression
-            #[cfg(feature = "zstd-support")]
-            {
-                zstd::decode_all(&compressed_data[..])?
-            }
-            #[cfg(not(feature = "zstd-support"))]
-            {
-                return Err(SZipError::UnsupportedCompression(entry.compression_method));
-            }
-        } else {
-            return Err(SZipError::UnsupportedCompression(entry.compression_method));
-        };
+        // Step 2: stream the payload through decryption and decompression.
+        // Header sizes are untrusted, so only a bounded buffer is reserved upfront.
+        let mut data = Vec::with_capacity(entry.uncompressed_size.min(INITIAL_ALLOC_CAP) as usize);
+        {
+            let limited =
+                DeadlineReader::new((&mut self.file).take(data_size), self.options.deadline);
+            #[cfg(feature = "encryption")]
+            let source = CtrReader {
+                inner: limited,
+                decryptor: decryptor_opt.as_mut(),
+            };
+            #[cfg(not(feature = "encryption"))]
+            let source = limited;
+            let decoder = self.decoders.get(entry.compression_method);
+            Self::decompress_into(
+                entry,
+                source,
+                data_size,
+                &mut data,
+                decoder,
+                #[cfg(feature = "zstd-support")]
+                zstd_dictionary.as_ref(),
+            )?;
+        }
 
-        // Verify HMAC authentication (Step 3: Update HMAC with plaintext and verify)
+        // Step 3: verify the HMAC over the plaintext; the auth code follows the data
         #[cfg(feature = "encryption")]
         if let Some(mut decryptor) = decryptor_opt {
-            // Update HMAC with decompressed plaintext data
+            self.file.seek(SeekFrom::Start(data_start + data_size))?;
+            let mut auth_code = [0u8; 10];
+            self.file.read_exact(&mut auth_code)?;
             decryptor.update_hmac(&data);
-
-            // Verify authentication code
-            if let Some(ac) = auth_code {
-                decryptor.verify_auth_code(&ac)?;
-            }
+            decryptor.verify_auth_code(&auth_code)?;
         }
 
         // Verify CRC-32 integrity — catches bit-rot and truncated downloads.
@@ -289,9 +1049,476 @@ impl StreamingZipReader {
         self.read_entry(&entry)
     }
 
+    /// Read the entry at `index` in [`entries()`](Self::entries)
+    pub fn read_entry_by_index(&mut self, index: usize) -> Result<Vec<u8>> {
+        let entry = self
+            .entries
+            .get(index)
+            .ok_or_else(|| SZipError::EntryNotFound(format!("index {}", index)))?
+            .clone();
+
+        self.read_entry(&entry)
+    }
+
+    /// Read several entries by name in a single forward pass.
+    ///
+    /// The requested entries are sorted by their local header offset before
+    /// reading, so the underlying source is only ever read front-to-back.
+    /// This is much cheaper than N independent `read_entry_by_name` calls
+    /// when the archive lives on a slow or remote source.
+    ///
+    /// Duplicate names, and names whose central records point at the same
+    /// local header, are read once; every requested name is a key of the
+    /// result.  Returns `SZipError::EntryNotFound` for the first name that
+    /// is not present in the archive.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use s_zip::StreamingZipReader;
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut reader = StreamingZipReader::open("archive.zip")?;
+    /// let files = reader.read_entries(&["a.json", "b.json"])?;
+    /// println!("a.json: {} bytes", files["a.json"].len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn read_entries(&mut self, names: &[&str]) -> Result<HashMap<String, Vec<u8>>> {
+        let targets = entries_named(&self.entries, names)?;
+        let mut results: HashMap<String, Vec<u8>> = HashMap::with_capacity(targets.len());
+        let mut previous: Option<&ZipEntry> = None;
+        for entry in &targets {
+            // Names aliasing the data just read share it
+            let data = match previous.filter(|p| p.offset == entry.offset) {
+                Some(alias) => results[&alias.name].clone(),
+                None => self.read_entry(entry)?,
+            };
+            results.insert(entry.name.clone(), data);
+            previous = Some(entry);
+        }
+        Ok(results)
+    }
+
+    /// Extract every entry into `dir`, restoring Unix modes and mtimes.
+    ///
+    /// Shorthand for [`extract_to_dir_with_options`](Self::extract_to_dir_with_options)
+    /// with `ExtractOptions::default()`.
+    pub fn extract_to_dir<P: AsRef<Path>>(&mut self, dir: P) -> Result<()> {
+        self.extract_to_dir_with_options(dir, &ExtractOptions::default())
+    }
+
+    /// Extract every entry into `dir`, restoring the metadata selected in `options`.
+    ///
+    /// Archives with absolute entry names or `..` components are refused
+    /// before anything is written; clear [`ExtractOptions::validate_names`]
+    /// to sanitize such names with [`ZipEntry::safe_path`] instead.
+    /// Symbolic links are created last and only when their target stays
+    /// inside `dir`.  Parent directories are created as
+    /// needed and entry data is streamed, never fully buffered.  Use
+    /// [`ExtractOptions::with_filter`] / [`ExtractOptions::with_rename`] to
+    /// extract a subset or relocate entries.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use s_zip::{ExtractOptions, StreamingZipReader};
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut reader = StreamingZipReader::open("archive.zip")?;
+    /// let options = ExtractOptions {
+    ///     preserve_mtime: false,
+    ///     ..Default::default()
+    /// };
+    /// reader.extract_to_dir_with_options("out", &options)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn extract_to_dir_with_options<P: AsRef<Path>>(
+        &mut self,
+        dir: P,
+        options: &ExtractOptions,
+    ) -> Result<()> {
+        extract::check_names(&self.entries, options)?;
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+
+        let entries = Arc::clone(&self.entries);
+        let mut dirs = Vec::new();
+        let mut links = Vec::new();
+        for entry in entries.iter() {
+            let Some(path) = extract::output_path(dir, entry, options) else {
+                continue;
+            };
+            if extract::is_dir_entry(entry) {
+                std::fs::create_dir_all(&path)?;
+                dirs.push((entry, path));
+                continue;
+            }
+            if extract::is_link_entry(entry) {
+                links.push((entry, path));
+                continue;
+            }
+            self.extract_file(entry, &path, options)?;
+        }
+        // Links last, so no entry is written through one
+        for (entry, path) in links {
+            let target = self.read_symlink_target(entry)?;
+            extract::create_symlink(dir, &path, entry, &target)?;
+        }
+        extract::apply_dir_metadata(dirs, options)?;
+        Ok(())
+    }
+
+    /// Target of a symbolic link entry (see [`ZipEntry::is_symlink`]).
+    ///
+    /// Fails if the entry is not a link, or its target is longer than
+    /// [`MAX_SYMLINK_TARGET`](crate::format::MAX_SYMLINK_TARGET) bytes or not UTF-8.
+    /// `extract_to_dir` recreates links on Unix instead of writing their target as data.
+    pub fn read_symlink_target(&mut self, entry: &ZipEntry) -> Result<String> {
+        extract::check_symlink_entry(entry)?;
+        let data = self.read_entry(entry)?;
+        extract::symlink_target(entry, data)
+    }
+
+    /// Stream a single entry to `path` without loading it into memory.
+    ///
+    /// Data is written to a temporary file next to `path` and renamed into
+    /// place only after the CRC-32 has been verified, so `path` never holds a
+    /// partial or corrupt file.  Parent directories are created as needed and
+    /// the Unix mode and mtime are restored as with `ExtractOptions::default()`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use s_zip::StreamingZipReader;
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut reader = StreamingZipReader::open("archive.zip")?;
+    /// let entry = reader.find_entry("data/large.bin").unwrap().clone();
+    /// reader.extract_entry_to_path(&entry, "large.bin")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn extract_entry_to_path<P: AsRef<Path>>(
+        &mut self,
+        entry: &ZipEntry,
+        path: P,
+    ) -> Result<()> {
+        self.extract_file(entry, path.as_ref(), &ExtractOptions::default())
+    }
+
+    /// Extract `entries` into `dir` on `config.workers` threads, each reading
+    /// through its own reader from `handle`
+    fn extract_parallel_with<S, F>(
+        &self,
+        entries: &[ZipEntry],
+        dir: &Path,
+        config: &ExtractConfig,
+        handle: F,
+    ) -> Result<()>
+    where
+        S: Read + Seek,
+        F: Fn() -> Result<StreamingZipReader<S>> + Sync,
+    {
+        use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+        let options = &config.options;
+        extract::check_names(entries, options)?;
+        std::fs::create_dir_all(dir)?;
+
+        let mut dirs = Vec::new();
+        let mut files = Vec::new();
+        let mut links = Vec::new();
+        for entry in entries {
+            let Some(path) = extract::output_path(dir, entry, options) else {
+                continue;
+            };
+            if extract::is_dir_entry(entry) {
+                std::fs::create_dir_all(&path)?;
+                dirs.push((entry, path));
+            } else if extract::is_link_entry(entry) {
+                links.push((entry, path));
+            } else {
+                files.push((entry, path));
+            }
+        }
+
+        // Workers take the next file until none is left or one fails
+        let next = AtomicUsize::new(0);
+        let failed = AtomicBool::new(false);
+        let work = || -> Result<()> {
+            let mut reader = handle()?;
+            while !failed.load(Ordering::Relaxed) {
+                let Some((entry, path)) = files.get(next.fetch_add(1, Ordering::Relaxed)) else {
+                    break;
+                };
+                reader.extract_file(entry, path, options)?;
+            }
+            Ok(())
+        };
+        std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..config.workers.clamp(1, files.len().max(1)))
+                .map(|_| {
+                    scope.spawn(|| {
+                        let result = work();
+                        if result.is_err() {
+                            failed.store(true, Ordering::Relaxed);
+                        }
+                        result
+                    })
+                })
+                .collect();
+            workers.into_iter().try_for_each(|worker| {
+                worker.join().unwrap_or_else(|_| {
+                    Err(SZipError::Io(std::io::Error::other(
+                        "extraction thread panicked",
+                    )))
+                })
+            })
+        })?;
+
+        // Links last, so no entry is written through one
+        let mut reader = handle()?;
+        for (entry, path) in links {
+            let target = reader.read_symlink_target(entry)?;
+            extract::create_symlink(dir, &path, entry, &target)?;
+        }
+        extract::apply_dir_metadata(dirs, options)?;
+        Ok(())
+    }
+
+    /// Reader of the same archive through `file`, another handle to its source
+    fn with_source<S: Read + Seek>(&self, file: S) -> StreamingZipReader<S> {
+        StreamingZipReader {
+            file,
+            entries: Arc::clone(&self.entries),
+            comment: self.comment.clone(),
+            cd_location: self.cd_location,
+            decoders: self.decoders.clone(),
+            #[cfg(feature = "zstd-support")]
+            zstd_dictionary: self.zstd_dictionary.clone(),
+            options: self.options.clone(),
+            filter: self.filter.clone(),
+            #[cfg(feature = "encryption")]
+            password: self.password.clone(),
+        }
+    }
+
+    /// Extract one file entry via a verified temporary file
+    fn extract_file(
+        &mut self,
+        entry: &ZipEntry,
+        path: &Path,
+        options: &ExtractOptions,
+    ) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let tmp = extract::TempFile::new(path);
+        self.write_entry_file(entry, tmp.path(), options)?;
+        std::fs::rename(tmp.path(), path)?;
+        tmp.keep();
+        Ok(())
+    }
+
+    /// Write and verify an entry's data at `path`, then restore its metadata
+    fn write_entry_file(
+        &mut self,
+        entry: &ZipEntry,
+        path: &Path,
+        options: &ExtractOptions,
+    ) -> Result<()> {
+        let file = extract::open_options(entry).open(path)?;
+        let mut writer = extract::CrcWriter::new(file);
+        std::io::copy(&mut self.read_entry_streaming(entry)?, &mut writer)?;
+        extract::check_crc(entry, writer.crc32())?;
+        extract::apply_metadata(&writer.inner, path, entry, options)?;
+        Ok(())
+    }
+
+    /// Test every entry, like `unzip -t`.
+    ///
+    /// Each local header is compared against its central directory record and
+    /// the entry data is decompressed to a sink to check the CRC-32 and size.
+    /// Problems are collected per entry rather than returned as errors, so one
+    /// corrupt entry does not hide the others.  Encrypted entries need a
+    /// password via `set_password` (requires the `encryption` feature).
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use s_zip::StreamingZipReader;
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut reader = StreamingZipReader::open("archive.zip")?;
+    /// let report = reader.verify()?;
+    /// if !report.is_ok() {
+    ///     for entry in report.failures() {
+    ///         eprintln!("{}: {:?}", entry.name, entry.problems);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn verify(&mut self) -> Result<VerifyReport> {
+        self.check_entries(true)
+    }
+
+    /// Cheap consistency check: compare every local header with the central
+    /// directory without reading any entry data.
+    ///
+    /// Catches truncated archives, mismatched names, methods and flags in a
+    /// few bytes of I/O per entry, so it stays fast on multi-gigabyte files.
+    /// Use [`verify`](Self::verify) to also check CRCs.
+    pub fn quick_check(&mut self) -> Result<VerifyReport> {
+        self.check_entries(false)
+    }
+
+    /// Check every entry against the manifest written by `set_manifest(true)`.
+    ///
+    /// Entries are decompressed and hashed; the report lists those that are
+    /// modified, missing or not in the manifest. Fails with
+    /// `SZipError::EntryNotFound` if the archive has no manifest. See
+    /// [`crate::manifest`].
+    #[cfg(feature = "manifest")]
+    pub fn verify_against_manifest(&mut self) -> Result<crate::manifest::ManifestReport> {
+        use crate::manifest::{Manifest, MANIFEST_ENTRY};
+        let manifest = Manifest::parse(&self.read_entry_by_name(MANIFEST_ENTRY)?)?;
+        let reserved = [
+            MANIFEST_ENTRY,
+            #[cfg(feature = "signing")]
+            crate::signing::SIGNATURE_ENTRY,
+        ];
+        crate::manifest::check_entries(self, &manifest, &reserved)
+    }
+
+    /// Check the archive signature written by `sign_with` and every entry
+    /// against the signed manifest.
+    ///
+    /// Fails with `SZipError::SignatureInvalid` if the archive is unsigned,
+    /// signed with another algorithm or key, or if any entry was added,
+    /// removed or changed after signing. See [`crate::signing`].
+    #[cfg(feature = "signing")]
+    pub fn verify_signature(
+        &mut self,
+        verifier: &dyn crate::signing::SignatureVerifier,
+    ) -> Result<()> {
+        crate::signing::verify_archive(self, verifier)
+    }
+
+    /// Shared body of `verify` / `quick_check`
+    fn check_entries(&mut self, read_data: bool) -> Result<VerifyReport> {
+        let archive_len = self.file.seek(SeekFrom::End(0))?;
+        let entries = Arc::clone(&self.entries);
+        let mut report = VerifyReport::default();
+        for entry in entries.iter() {
+            let mut problems = match self.check_local_header(entry, archive_len) {
+                Ok(problems) => problems,
+                Err(e) => vec![EntryProblem::BadLocalHeader(e.to_string())],
+            };
+            if self.options.quirks.is_lenient() {
+                // The central directory is trusted over the local header
+                problems.retain(|p| !p.is_local_mismatch());
+            }
+            if read_data && !problems.iter().any(EntryProblem::is_fatal) {
+                match self.checksum_entry(entry) {
+                    Ok((crc, size)) => problems.extend(verify::check_data(entry, crc, size)),
+                    Err(e) => problems.push(EntryProblem::ReadError(e.to_string())),
+                }
+            }
+            report.entries.push(EntryReport {
+                name: entry.name.clone(),
+                problems,
+            });
+        }
+        Ok(report)
+    }
+
+    /// Read the local header of `entry` and compare it with the central directory
+    fn check_local_header(
+        &mut self,
+        entry: &ZipEntry,
+        archive_len: u64,
+    ) -> Result<Vec<EntryProblem>> {
+        self.file.seek(SeekFrom::Start(entry.offset))?;
+        let mut fixed = [0u8; LOCAL_HEADER_LEN];
+        self.file.read_exact(&mut fixed)?;
+        let mut variable = vec![0u8; verify::local_variable_len(&fixed)];
+        self.file.read_exact(&mut variable)?;
+        Ok(verify::check_local_header(
+            entry,
+            &fixed,
+            &variable,
+            &self.options.filename_encoding,
+            archive_len,
+        ))
+    }
+
+    /// Where `entry`'s stored data lies in the archive, as
+    /// `(data_offset, data_len)`.
+    ///
+    /// `data_offset` is just past the local header, file name and extra
+    /// field, which only the local header records, so this reads it.  The
+    /// span covers the data exactly as stored: compressed, and for encrypted
+    /// entries including the AES salt, password verifier and auth code.
+    /// External tools can serve the span as an HTTP byte range or hand it
+    /// to an offload engine without parsing local headers themselves.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use s_zip::StreamingZipReader;
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut reader = StreamingZipReader::open("archive.zip")?;
+    /// let entry = reader.find_entry("video.mp4").unwrap().clone();
+    /// let (offset, len) = reader.data_span(&entry)?;
+    /// println!("Range: bytes={}-{}", offset, offset + len - 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn data_span(&mut self, entry: &ZipEntry) -> Result<(u64, u64)> {
+        self.file.seek(SeekFrom::Start(entry.offset))?;
+        let mut fixed = [0u8; LOCAL_HEADER_LEN];
+        self.file.read_exact(&mut fixed)?;
+        Ok((verify::data_offset(entry, &fixed)?, entry.compressed_size))
+    }
+
+    /// Extra field of `entry`'s local header, and its stored data
+    pub(crate) fn raw_entry(&mut self, entry: &ZipEntry) -> Result<(Vec<u8>, Take<&mut R>)> {
+        self.file.seek(SeekFrom::Start(entry.offset))?;
+        let mut fixed = [0u8; LOCAL_HEADER_LEN];
+        self.file.read_exact(&mut fixed)?;
+        let header = LocalHeader::parse(&fixed)?;
+        self.file.seek(SeekFrom::Current(header.name_len as i64))?;
+        let mut extra = vec![0u8; header.extra_len as usize];
+        self.file.read_exact(&mut extra)?;
+        Ok((extra, self.file.by_ref().take(entry.compressed_size)))
+    }
+
+    /// Open `entry`, itself a ZIP archive, as an archive of its own
+    ///
+    /// A stored inner archive is read in place from this one; a compressed
+    /// or encrypted one is first decompressed into a temporary file, removed
+    /// again when the returned reader is dropped. The inner archive is parsed
+    /// with this reader's quirks mode and filename encoding. See the
+    /// [`nested`](crate::nested) module.
+    pub fn open_nested(
+        &mut self,
+        entry: &ZipEntry,
+    ) -> Result<StreamingZipReader<NestedSource<'_, R>>> {
+        let options = self.options.clone();
+        let source = if entry.compression_method == 0 && !entry.is_encrypted {
+            let (offset, len) = self.data_span(entry)?;
+            NestedSource::window(&mut self.file, offset, len)
+        } else {
+            NestedSource::spool(&mut self.read_entry_streaming(entry)?)?
+        };
+        StreamingZipReader::new_with_options(source, options)
+    }
+
+    /// Decompress `entry` to a sink, returning its CRC-32 and size
+    fn checksum_entry(&mut self, entry: &ZipEntry) -> Result<(u32, u64)> {
+        let mut writer = extract::CrcWriter::new(std::io::sink());
+        let size = std::io::copy(&mut self.read_entry_streaming(entry)?, &mut writer)?;
+        Ok((writer.crc32(), size))
+    }
+
     /// Get a streaming reader for an entry by name (for large files)
     /// Returns a reader that decompresses data on-the-fly without loading everything into memory
-    pub fn read_entry_streaming_by_name(&mut self, name: &str) -> Result<Box<dyn Read + '_>> {
+    pub fn read_entry_streaming_by_name(&mut self, name: &str) -> Result<ZipEntryReader<'_, R>> {
         let entry = self
             .find_entry(name)
             .ok_or_else(|| SZipError::EntryNotFound(name.to_string()))?
@@ -302,8 +1529,23 @@ impl StreamingZipReader {
 
     /// Get a streaming reader for an entry (for large files).
     ///
-    /// Returns a `Read` impl that decompresses (and, if encrypted, decrypts)
-    /// data on-the-fly without loading the entire entry into memory.
+    /// Returns a [`ZipEntryReader`] that decompresses (and, if encrypted, decrypts)
+    /// data on-the-fly without loading the entire entry into memory.  It
+    /// implements `BufRead`, so `lines()`, `read_line()` and `split()` work
+    /// directly on the entry.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use s_zip::StreamingZipReader;
+    /// # use std::io::BufRead;
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut reader = StreamingZipReader::open("export.zip")?;
+    /// for line in reader.read_entry_streaming_by_name("events.jsonl")?.lines() {
+    ///     println!("{}", line?);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
     ///
     /// # Encrypted entries
     ///
@@ -320,41 +1562,118 @@ impl StreamingZipReader {
     /// # Errors
     /// Returns `SZipError::EncryptionError` if the entry is encrypted but
     /// `set_password()` was not called or the password is wrong.
-    pub fn read_entry_streaming(&mut self, entry: &ZipEntry) -> Result<Box<dyn Read + '_>> {
-        // Seek to local file header
-        self.file.seek(SeekFrom::Start(entry.offset))?;
+    pub fn read_entry_streaming(&mut self, entry: &ZipEntry) -> Result<ZipEntryReader<'_, R>> {
+        crate::deadline::check(self.options.deadline)?;
+        let deadline = self.options.deadline;
+        #[cfg(feature = "zstd-support")]
+        let zstd_dictionary = self.zstd_dictionary_for(entry)?;
+        let decoder = self.decoders.get(entry.compression_method);
+        let stream = Self::entry_stream(
+            &mut self.file,
+            entry,
+            decoder.is_none(),
+            #[cfg(feature = "encryption")]
+            self.password.as_deref(),
+            #[cfg(feature = "zstd-support")]
+            zstd_dictionary.as_ref(),
+        )?;
+        let stream = match decoder {
+            Some(decode) => EntryReaderStream::Decoded(decode(Box::new(stream))?),
+            None => EntryReaderStream::Builtin(stream),
+        };
+        Ok(ZipEntryReader {
+            entry: entry.clone(),
+            inner: BufReader::new(DeadlineReader::new(stream, deadline)),
+        })
+    }
 
-        // Read and verify local file header
-        let signature = self.read_u32_le()?;
-        if signature != LOCAL_FILE_HEADER_SIGNATURE {
-            return Err(SZipError::InvalidFormat(
-                "Invalid local file header signature".to_string(),
-            ));
+    /// Decompress `data_size` bytes from `source` into `out` for `read_entry`.
+    ///
+    /// Output is capped at `MAX_ENTRY_ALLOC` so a decompression bomb fails
+    /// instead of exhausting memory.
+    fn decompress_into<S: Read>(
+        entry: &ZipEntry,
+        source: S,
+        data_size: u64,
+        out: &mut Vec<u8>,
+        decoder: Option<&DecoderFn>,
+        #[cfg(feature = "zstd-support")] zstd_dictionary: Option<&ZstdDictionary>,
+    ) -> Result<()> {
+        let limit = MAX_ENTRY_ALLOC + 1;
+        if let Some(decode) = decoder {
+            decode(Box::new(source))?.take(limit).read_to_end(out)?;
+        } else {
+            match entry.compression_method {
+                8 => DeflateDecoder::new(source).take(limit).read_to_end(out)?,
+                0 => {
+                    let n = source.take(limit).read_to_end(out)?;
+                    if (n as u64) < data_size {
+                        return Err(SZipError::Io(std::io::ErrorKind::UnexpectedEof.into()));
+                    }
+                    n
+                }
+                #[cfg(feature = "zstd-support")]
+                93 => zstd_decoder(BufReader::new(source), zstd_dictionary)?
+                    .take(limit)
+                    .read_to_end(out)?,
+                #[cfg(feature = "deflate64")]
+                9 => deflate64::Deflate64Decoder::new(source)
+                    .take(limit)
+                    .read_to_end(out)?,
+                #[cfg(feature = "bzip2")]
+                12 => bzip2::read::BzDecoder::new(source)
+                    .take(limit)
+                    .read_to_end(out)?,
+                #[cfg(feature = "lzma")]
+                method @ (14 | 95) => {
+                    crate::lzma::decoder(method, source, entry.flags, entry.uncompressed_size)?
+                        .take(limit)
+                        .read_to_end(out)?
+                }
+                method => return Err(SZipError::UnsupportedCompression(method)),
+            };
         }
+        if out.len() as u64 > MAX_ENTRY_ALLOC {
+            return Err(SZipError::InvalidFormat(format!(
+                "Entry '{}' is too large to read into memory (more than {} bytes). \
+                 Use read_entry_streaming() for entries larger than 2 GiB.",
+                entry.name, MAX_ENTRY_ALLOC
+            )));
+        }
+        Ok(())
+    }
 
-        // Skip version, flags, compression method
-        self.file.seek(SeekFrom::Current(6))?;
-
-        // Skip modification time and date, CRC-32
-        self.file.seek(SeekFrom::Current(8))?;
-
-        // Read compressed and uncompressed sizes (use values from central directory)
-        self.file.seek(SeekFrom::Current(8))?;
+    /// Position `src` at the entry's data and wrap it in the appropriate
+    /// decryption/decompression layers.
+    ///
+    /// Generic over the source so the same logic serves both the borrowing
+    /// (`&mut R`) and owning (`R`) entry readers. With `decompress` false the
+    /// stream yields the raw (decrypted) data for a registered decoder.
+    fn entry_stream<S: Read + Seek>(
+        mut src: S,
+        entry: &ZipEntry,
+        decompress: bool,
+        #[cfg(feature = "encryption")] password: Option<&str>,
+        #[cfg(feature = "zstd-support")] zstd_dictionary: Option<&ZstdDictionary>,
+    ) -> Result<EntryStream<S>> {
+        // Seek to local file header
+        src.seek(SeekFrom::Start(entry.offset))?;
 
-        // Read filename length and extra field length
-        let filename_len = self.read_u16_le()? as i64;
-        let extra_len = self.read_u16_le()? as usize;
+        // Read and verify local file header; sizes come from the central directory
+        let header = Self::read_local_header(&mut src)?;
+        let filename_len = header.name_len as i64;
+        let extra_len = header.extra_len as usize;
 
         // Skip filename
-        self.file.seek(SeekFrom::Current(filename_len))?;
+        src.seek(SeekFrom::Current(filename_len))?;
 
         // For encrypted entries: parse AES extra field and decrypt on-the-fly.
         #[cfg(feature = "encryption")]
         if entry.is_encrypted {
-            let encryption_info = self.parse_aes_extra_field(extra_len)?;
+            let encryption_info = Self::parse_aes_extra_field(&mut src, extra_len)?;
 
             if let Some((strength, salt, pw_verify)) = encryption_info {
-                let password = self.password.as_ref().ok_or_else(|| {
+                let password = password.ok_or_else(|| {
                     SZipError::EncryptionError(
                         "Encrypted entry but no password set. Call set_password() first."
                             .to_string(),
@@ -367,21 +1686,20 @@ impl StreamingZipReader {
 
                 // Read the 10-byte auth code positioned after ciphertext.
                 // We must read it now (by seeking past the ciphertext) then
-                // seek back, because limited_reader borrows self.file mutably.
+                // seek back, because limited_reader takes ownership of src.
                 // Instead we store auth_code and pass it to DecryptingReader.
                 //
                 // Offset of auth_code = current_pos + cipher_size
-                let current_pos = self.file.stream_position()?;
-                self.file.seek(SeekFrom::Start(current_pos + cipher_size))?;
+                let current_pos = src.stream_position()?;
+                src.seek(SeekFrom::Start(current_pos + cipher_size))?;
                 let mut auth_code = vec![0u8; 10];
-                self.file.read_exact(&mut auth_code)?;
+                src.read_exact(&mut auth_code)?;
 
                 // Seek back to start of ciphertext
-                self.file.seek(SeekFrom::Start(current_pos))?;
+                src.seek(SeekFrom::Start(current_pos))?;
 
-                let limited_reader = (&mut self.file).take(cipher_size);
+                let limited_reader = src.take(cipher_size);
 
-                use crate::decrypt_reader::sync::DecryptingReader;
                 let decrypt_reader = DecryptingReader::new(
                     limited_reader,
                     password,
@@ -392,55 +1710,98 @@ impl StreamingZipReader {
                 )?;
 
                 // Wrap with decompressor
-                return if entry.compression_method == 8 {
-                    Ok(Box::new(DeflateDecoder::new(decrypt_reader)))
-                } else if entry.compression_method == 0 {
-                    Ok(Box::new(decrypt_reader))
+                return if !decompress || entry.compression_method == 0 {
+                    Ok(EntryStream::EncryptedStored(decrypt_reader))
+                } else if entry.compression_method == 8 {
+                    Ok(EntryStream::EncryptedDeflate(DeflateDecoder::new(
+                        decrypt_reader,
+                    )))
                 } else {
                     Err(SZipError::UnsupportedCompression(entry.compression_method))
                 };
-            } else {
-                // Extra field didn't contain AES info — skip it
-                self.file.seek(SeekFrom::Current(extra_len as i64))?;
             }
+            // Extra field didn't contain AES info — it has already been
+            // consumed by parse_aes_extra_field, fall through to plain read.
         }
 
         // Non-encrypted path: skip extra field
         #[cfg(feature = "encryption")]
         if !entry.is_encrypted {
-            self.file.seek(SeekFrom::Current(extra_len as i64))?;
+            src.seek(SeekFrom::Current(extra_len as i64))?;
         }
 
         #[cfg(not(feature = "encryption"))]
-        self.file.seek(SeekFrom::Current(extra_len as i64))?;
+        src.seek(SeekFrom::Current(extra_len as i64))?;
 
         // Create a reader limited to compressed data size
-        let limited_reader = (&mut self.file).take(entry.compressed_size);
+        let limited_reader = src.take(entry.compressed_size);
 
         // Wrap with decompressor if needed
-        if entry.compression_method == 8 {
+        if !decompress || entry.compression_method == 0 {
+            // No compression (stored), or left to a registered decoder
+            Ok(EntryStream::Stored(limited_reader))
+        } else if entry.compression_method == 8 {
             // DEFLATE compression
-            Ok(Box::new(DeflateDecoder::new(limited_reader)))
-        } else if entry.compression_method == 0 {
-            // No compression (stored)
-            Ok(Box::new(limited_reader))
+            Ok(EntryStream::Deflate(DeflateDecoder::new(limited_reader)))
         } else if entry.compression_method == 93 {
             // Zstd compression
             #[cfg(feature = "zstd-support")]
             {
-                Ok(Box::new(zstd::Decoder::new(limited_reader)?))
+                Ok(EntryStream::Zstd(zstd_decoder(
+                    BufReader::new(limited_reader),
+                    zstd_dictionary,
+                )?))
             }
             #[cfg(not(feature = "zstd-support"))]
             {
                 Err(SZipError::UnsupportedCompression(entry.compression_method))
             }
+        } else if entry.compression_method == 9 {
+            // Deflate64, as written by Windows for large files
+            #[cfg(feature = "deflate64")]
+            {
+                Ok(EntryStream::Deflate64(deflate64::Deflate64Decoder::new(
+                    limited_reader,
+                )))
+            }
+            #[cfg(not(feature = "deflate64"))]
+            {
+                Err(SZipError::UnsupportedCompression(entry.compression_method))
+            }
+        } else if entry.compression_method == 12 {
+            // BZip2 compression
+            #[cfg(feature = "bzip2")]
+            {
+                Ok(EntryStream::Bzip2(bzip2::read::BzDecoder::new(
+                    limited_reader,
+                )))
+            }
+            #[cfg(not(feature = "bzip2"))]
+            {
+                Err(SZipError::UnsupportedCompression(entry.compression_method))
+            }
+        } else if matches!(entry.compression_method, 14 | 95) {
+            // LZMA or XZ compression
+            #[cfg(feature = "lzma")]
+            {
+                Ok(EntryStream::Lzma(crate::lzma::decoder(
+                    entry.compression_method,
+                    limited_reader,
+                    entry.flags,
+                    entry.uncompressed_size,
+                )?))
+            }
+            #[cfg(not(feature = "lzma"))]
+            {
+                Err(SZipError::UnsupportedCompression(entry.compression_method))
+            }
         } else {
             Err(SZipError::UnsupportedCompression(entry.compression_method))
         }
     }
 
     /// Get a streaming reader for an entry by name
-    pub fn read_entry_by_name_streaming(&mut self, name: &str) -> Result<Box<dyn Read + '_>> {
+    pub fn read_entry_by_name_streaming(&mut self, name: &str) -> Result<ZipEntryReader<'_, R>> {
         let entry = self
             .find_entry(name)
             .ok_or_else(|| SZipError::EntryNotFound(name.to_string()))?
@@ -450,279 +1811,740 @@ impl StreamingZipReader {
     }
 
     /// Read the central directory from the ZIP file
-    fn read_central_directory(file: &mut BufReader<File>) -> Result<Vec<ZipEntry>> {
-        // Find end of central directory record
-        let eocd_offset = Self::find_eocd(file)?;
+    ///
+    /// With `keep`, only entries whose name it accepts are retained.
+    fn read_central_directory(
+        file: &mut R,
+        keep: Option<&NameFilter>,
+        options: &ReaderOptions,
+    ) -> Result<(Vec<ZipEntry>, Vec<u8>, CentralDirectoryLocation)> {
+        crate::deadline::check(options.deadline)?;
+        let (location, comment) = Self::read_eocd(file)?;
+        crate::deadline::check(options.deadline)?;
 
-        // Seek to EOCD
-        file.seek(SeekFrom::Start(eocd_offset))?;
+        // Seek to central directory
+        file.seek(SeekFrom::Start(location.offset))?;
+        let mut file = DeadlineReader::new(file, options.deadline);
 
-        // Read EOCD
-        let signature = Self::read_u32_le_static(file)?;
-        if signature != END_OF_CENTRAL_DIRECTORY_SIGNATURE {
-            return Err(SZipError::InvalidFormat(format!(
-                "Invalid end of central directory signature: 0x{:08x}",
-                signature
-            )));
+        // Read all central directory entries; a filtered list grows as needed
+        let mut entries = match keep {
+            Some(_) => Vec::new(),
+            None => Vec::with_capacity(location.count as usize),
+        };
+        for _ in 0..location.count {
+            match Self::read_cd_record(&mut file, options)? {
+                Some(entry) => {
+                    if keep.is_none_or(|keep| keep(&entry.name)) {
+                        entries.push(entry);
+                    }
+                }
+                None => break,
+            }
         }
 
-        // Skip disk number fields (4 bytes)
-        file.seek(SeekFrom::Current(4))?;
+        Ok((entries, comment, location))
+    }
 
-        // Read number of entries on this disk (2 bytes)
-        let _entries_on_disk = Self::read_u16_le_static(file)?;
+    /// Read the EOCD (and ZIP64 EOCD) record, returning the central directory
+    /// location and the archive comment
+    pub(crate) fn read_eocd(file: &mut R) -> Result<(CentralDirectoryLocation, Vec<u8>)> {
+        let locator = DirectoryLocator::new(file.seek(SeekFrom::End(0))?);
+        let found = split::locate(file, locator)?;
+        Ok((found.location, found.comment))
+    }
 
-        // Read total number of entries (2 bytes)
+    /// Read the archives of a growing file that are newer than the one whose
+    /// directory is at `known`, walking back through concatenated archives.
+    ///
+    /// Returns their entries oldest first, the newest comment and directory
+    /// location, and whether the entries extend those of `known` rather than
+    /// replace them; `None` if there is nothing newer.
+    #[allow(clippy::type_complexity)]
+    fn read_growing(
+        file: &mut R,
+        known: Option<CentralDirectoryLocation>,
+        options: &ReaderOptions,
+    ) -> Result<Option<(Vec<ZipEntry>, Vec<u8>, CentralDirectoryLocation, bool)>> {
+        crate::deadline::check(options.deadline)?;
+        let end = file.seek(SeekFrom::End(0))?;
+        let floor = known.map_or(0, |l| l.offset + l.size);
+        let Some(newest) = growing::find_latest(file, end, floor)? else {
+            return Ok(None);
+        };
+        if Some(newest.location) == known {
+            return Ok(None);
+        }
 
-        // These values may be placeholder 0xFFFF/0xFFFFFFFF when ZIP64 is used
-        let total_entries_16 = Self::read_u16_le_static(file)?;
+        let mut extends = false;
+        let mut segments = vec![newest];
+        while let Some(segment) = segments.last().filter(|s| s.base > 0) {
+            // An appended archive starts where the previous one ends; a
+            // prefix that is not an archive (an SFX stub) ends the walk
+            match growing::find_ending_at(file, segment.base)? {
+                Some(previous) if Some(previous.location) == known => {
+                    extends = true;
+                    break;
+                }
+                Some(previous) => segments.push(previous),
+                None => break,
+            }
+        }
 
-        // Read central directory size (4 bytes)
-        let cd_size_32 = Self::read_u32_le_static(file)?;
+        let mut entries = Vec::new();
+        for segment in segments.iter().rev() {
+            let start = entries.len();
+            entries.extend(Self::read_central_directory_at(
+                file,
+                segment.location,
+                options,
+            )?);
+            for entry in &mut entries[start..] {
+                entry.offset += segment.base;
+            }
+        }
+        let newest = segments.swap_remove(0);
+        Ok(Some((entries, newest.comment, newest.location, extends)))
+    }
 
-        // Read central directory offset (4 bytes)
-        let cd_offset_32 = Self::read_u32_le_static(file)? as u64;
+    /// Read a central directory of known location in one read and parse it
+    fn read_central_directory_at(
+        file: &mut R,
+        location: CentralDirectoryLocation,
+        options: &ReaderOptions,
+    ) -> Result<Vec<ZipEntry>> {
+        crate::deadline::check(options.deadline)?;
+        file.seek(SeekFrom::Start(location.offset))?;
+        // The size is caller-supplied: `take` bounds the read, not the allocation
+        let mut buf = Vec::new();
+        DeadlineReader::new(file.by_ref().take(location.size), options.deadline)
+            .read_to_end(&mut buf)?;
+        if (buf.len() as u64) < location.size {
+            return Err(SZipError::InvalidFormat(
+                "Central directory extends past the end of the archive".to_string(),
+            ));
+        }
+        sansio::parse_central_directory_with_encoding(
+            &buf,
+            location.count,
+            options.quirks,
+            &options.filename_encoding,
+        )
+    }
 
-        // Promote to u64 and handle ZIP64 if markers present
-        let mut total_entries = total_entries_16 as usize;
-        let mut cd_offset = cd_offset_32;
-        let _cd_size = cd_size_32 as u64;
+    /// Read the central directory record at the current position, or `None`
+    /// if there is no record signature there (the end of the directory)
+    fn read_cd_record<S: Read>(file: &mut S, options: &ReaderOptions) -> Result<Option<ZipEntry>> {
+        let mut records = CentralRecordReader::new(options.quirks, &options.filename_encoding);
+        let mut buf = vec![0u8; records.next_len()];
+        loop {
+            file.read_exact(&mut buf)?;
+            match records.feed(&buf)? {
+                RecordRead::More => buf.resize(records.next_len(), 0),
+                RecordRead::Entry(entry) => return Ok(Some(entry)),
+                RecordRead::End => return Ok(None),
+            }
+        }
+    }
 
-        if total_entries_16 == 0xFFFF || cd_size_32 == 0xFFFFFFFF || cd_offset_32 == 0xFFFFFFFF {
-            // Need to find ZIP64 EOCD locator and read ZIP64 EOCD record
-            let (zip64_total_entries, zip64_cd_size, zip64_cd_offset) =
-                Self::read_zip64_eocd(file, eocd_offset)?;
-            total_entries = zip64_total_entries as usize;
-            cd_offset = zip64_cd_offset;
-            // _cd_size can be used if needed (zip64_cd_size)
-            let _ = zip64_cd_size;
+    /// Fill in sizes and CRCs that the central directory left to data
+    /// descriptors (a Java quirk)
+    fn recover_descriptor_sizes(file: &mut R, entries: &mut [ZipEntry]) -> Result<()> {
+        for entry in entries
+            .iter_mut()
+            .filter(|e| quirks::needs_descriptor_sizes(e))
+        {
+            file.seek(SeekFrom::Start(entry.offset))?;
+            let header = Self::read_local_header(file)?;
+            file.seek(SeekFrom::Current(header.variable_len() as i64))?;
+            if let Some(descriptor) = salvage::find_data_descriptor(file)? {
+                entry.crc32 = descriptor.crc32;
+                entry.compressed_size = descriptor.compressed_size;
+                entry.uncompressed_size = descriptor.uncompressed_size;
+            }
         }
+        Ok(())
+    }
 
-        // Seek to central directory
-        file.seek(SeekFrom::Start(cd_offset))?;
+    /// Read and decode the local file header at the current position
+    fn read_local_header<S: Read>(src: &mut S) -> Result<LocalHeader> {
+        let mut fixed = [0u8; LOCAL_HEADER_LEN];
+        src.read_exact(&mut fixed)?;
+        LocalHeader::parse(&fixed)
+    }
 
-        // Read all central directory entries
-        let mut entries = Vec::with_capacity(total_entries);
-        for _ in 0..total_entries {
-            let signature = Self::read_u32_le_static(file)?;
-            if signature != CENTRAL_DIRECTORY_SIGNATURE {
-                break;
-            }
+    /// Parse AES encryption info from extra field
+    #[cfg(feature = "encryption")]
+    #[allow(clippy::type_complexity)]
+    fn parse_aes_extra_field<S: Read>(
+        src: &mut S,
+        extra_len: usize,
+    ) -> Result<Option<(AesStrength, Vec<u8>, [u8; 2])>> {
+        if extra_len == 0 {
+            return Ok(None);
+        }
+
+        let mut extra_buf = vec![0u8; extra_len];
+        src.read_exact(&mut extra_buf)?;
+        let Some(strength) = sansio::parse_aes_extra(&extra_buf)? else {
+            return Ok(None);
+        };
+
+        // Read salt and password verification from actual file data (not extra field)
+        // Salt comes after the extra field, before compressed data
+        let salt_size = strength.salt_size();
+
+        let mut salt = vec![0u8; salt_size];
+        src.read_exact(&mut salt)?;
+
+        let mut pw_verify = [0u8; 2];
+        src.read_exact(&mut pw_verify)?;
+
+        Ok(Some((strength, salt, pw_verify)))
+    }
+}
+
+impl<R: Read + Seek + Send + 'static> StreamingZipReader<R> {
+    /// Consume the reader and return an owned streaming reader for an entry.
+    ///
+    /// Unlike [`read_entry_streaming`](Self::read_entry_streaming), the
+    /// returned reader does not borrow `self`: it takes ownership of the
+    /// underlying source, so it is `Send + 'static` and can be moved
+    /// into a spawned thread or returned from a function.
+    ///
+    /// The same caveats about encrypted entries apply as for
+    /// `read_entry_streaming`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use s_zip::StreamingZipReader;
+    /// # use std::io::Read;
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let reader = StreamingZipReader::open("archive.zip")?;
+    /// let mut stream = reader.into_entry_reader("large.bin")?;
+    ///
+    /// let handle = std::thread::spawn(move || {
+    ///     let mut data = Vec::new();
+    ///     stream.read_to_end(&mut data).map(|_| data.len())
+    /// });
+    /// println!("read {} bytes", handle.join().unwrap()?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(not(feature = "zstd-support"), allow(unused_mut))]
+    pub fn into_entry_reader(mut self, name: &str) -> Result<Box<dyn BufRead + Send + 'static>> {
+        let entry = self
+            .find_entry(name)
+            .ok_or_else(|| SZipError::EntryNotFound(name.to_string()))?
+            .clone();
+
+        #[cfg(feature = "encryption")]
+        let password = self.password.clone();
+        let deadline = self.options.deadline;
+        if self.decoders.get(entry.compression_method).is_some() {
+            return Err(SZipError::UnsupportedCompression(entry.compression_method));
+        }
+        #[cfg(feature = "zstd-support")]
+        let zstd_dictionary = self.zstd_dictionary_for(&entry)?;
 
-            // Skip version made by, version needed
-            file.seek(SeekFrom::Current(4))?;
+        let stream = Self::entry_stream(
+            self.file,
+            &entry,
+            true,
+            #[cfg(feature = "encryption")]
+            password.as_deref(),
+            #[cfg(feature = "zstd-support")]
+            zstd_dictionary.as_ref(),
+        )?;
+        Ok(Box::new(BufReader::new(DeadlineReader::new(
+            stream, deadline,
+        ))))
+    }
+}
 
-            // Read flags (needed for encryption check)
-            #[cfg_attr(not(feature = "encryption"), allow(unused_variables))]
-            let flags = Self::read_u16_le_static(file)?;
+/// Central directory entries parsed on demand, returned by
+/// [`StreamingZipReader::open_lazy`].
+///
+/// Iterating yields one `Result<ZipEntry>` per central directory record and
+/// stops after the first error.  Entries found this way can be read with
+/// [`read_entry`](Self::read_entry) without interrupting the iteration.
+pub struct LazyEntries<R: Read + Seek = BufReader<File>> {
+    /// Reader with an empty entry list, used for its source and entry decoding
+    reader: StreamingZipReader<R>,
+    /// Offset of the next central directory record
+    next_offset: u64,
+    /// Records left according to the EOCD record
+    remaining: usize,
+    /// The source was moved away from `next_offset` by an entry read
+    needs_seek: bool,
+}
 
-            let compression_method = Self::read_u16_le_static(file)?;
+impl<R: Read + Seek> LazyEntries<R> {
+    /// Read the end of central directory record of `source`
+    pub fn new(source: R) -> Result<Self> {
+        Self::new_with_options(source, ReaderOptions::default())
+    }
 
-            // Read modification time, date, and CRC-32
-            file.seek(SeekFrom::Current(4))?; // mod time + date
-            let crc32 = Self::read_u32_le_static(file)?;
+    /// Like [`new`](Self::new), parsing records as `options` describe. A
+    /// known [`cd_location`](ReaderOptions::cd_location) replaces the EOCD
+    /// read, leaving the comment empty.
+    pub fn new_with_options(mut source: R, mut options: ReaderOptions) -> Result<Self> {
+        let (location, comment) = match options.cd_location.take() {
+            Some(location) => (location, Vec::new()),
+            None => StreamingZipReader::read_eocd(&mut source)?,
+        };
+        Ok(LazyEntries {
+            reader: StreamingZipReader::assemble(
+                source,
+                Vec::new(),
+                comment,
+                Some(location),
+                options,
+            ),
+            next_offset: location.offset,
+            remaining: location.count as usize,
+            needs_seek: true,
+        })
+    }
 
-            // Read sizes as 32-bit placeholders (may be 0xFFFFFFFF meaning ZIP64)
-            let compressed_size_32 = Self::read_u32_le_static(file)? as u64;
-            let uncompressed_size_32 = Self::read_u32_le_static(file)? as u64;
-            let filename_len = Self::read_u16_le_static(file)? as usize;
-            let extra_len = Self::read_u16_le_static(file)? as usize;
-            let comment_len = Self::read_u16_le_static(file)? as usize;
+    /// Number of entries the EOCD record declares; not yet validated
+    pub fn declared_len(&self) -> usize {
+        self.remaining
+    }
 
-            // Skip disk number, internal attributes, external attributes
-            file.seek(SeekFrom::Current(8))?;
+    /// Archive comment stored in the end-of-central-directory record
+    pub fn comment(&self) -> Cow<'_, str> {
+        self.reader.comment()
+    }
 
-            let offset_32 = Self::read_u32_le_static(file)? as u64;
+    /// Raw bytes of the archive comment
+    pub fn archive_comment(&self) -> &[u8] {
+        self.reader.archive_comment()
+    }
 
-            // Read filename
-            let mut filename_buf = vec![0u8; filename_len];
-            file.read_exact(&mut filename_buf)?;
-            let name = String::from_utf8_lossy(&filename_buf).to_string();
+    /// Set password for decrypting encrypted entries
+    #[cfg(feature = "encryption")]
+    pub fn set_password(&mut self, password: impl Into<String>) -> &mut Self {
+        self.reader.set_password(password);
+        self
+    }
 
-            // Read extra field so we can parse ZIP64 extra if present
-            let mut extra_buf = vec![0u8; extra_len];
-            if extra_len > 0 {
-                file.read_exact(&mut extra_buf)?;
-            }
+    /// Read an entry's decompressed data, as
+    /// [`StreamingZipReader::read_entry`] does
+    pub fn read_entry(&mut self, entry: &ZipEntry) -> Result<Vec<u8>> {
+        self.needs_seek = true;
+        self.reader.read_entry(entry)
+    }
 
-            // Resolve ZIP64 placeholders using shared pure helper
-            let (uncompressed_size, compressed_size, offset) = if compressed_size_32 == 0xFFFFFFFF
-                || uncompressed_size_32 == 0xFFFFFFFF
-                || offset_32 == 0xFFFFFFFF
-            {
-                parse_zip64_extra_field(
-                    &extra_buf,
-                    compressed_size_32,
-                    uncompressed_size_32,
-                    offset_32,
-                )
-            } else {
-                (uncompressed_size_32, compressed_size_32, offset_32)
-            };
+    /// Stop iterating with `SZipError::DeadlineExceeded` once `deadline`
+    /// passes; also applies to [`read_entry`](Self::read_entry)
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.reader.options.deadline = Some(deadline);
+        self
+    }
+
+    fn next_record(&mut self) -> Result<Option<ZipEntry>> {
+        crate::deadline::check(self.reader.options.deadline)?;
+        let file = &mut self.reader.file;
+        if self.needs_seek {
+            file.seek(SeekFrom::Start(self.next_offset))?;
+            self.needs_seek = false;
+        }
+        let entry = StreamingZipReader::<R>::read_cd_record(file, &self.reader.options)?;
+        self.next_offset = file.stream_position()?;
+        Ok(entry)
+    }
+}
 
-            // Skip comment
-            if comment_len > 0 {
-                file.seek(SeekFrom::Current(comment_len as i64))?;
+impl<R: Read + Seek> Iterator for LazyEntries<R> {
+    type Item = Result<ZipEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        match self.next_record() {
+            Ok(Some(entry)) => Some(Ok(entry)),
+            Ok(None) => {
+                self.remaining = 0;
+                None
             }
+            Err(e) => {
+                self.remaining = 0;
+                Some(Err(e))
+            }
+        }
+    }
 
-            entries.push(ZipEntry {
-                name,
-                compressed_size,
-                uncompressed_size,
-                compression_method,
-                offset,
-                crc32,
-                is_encrypted: (flags & 0x01) != 0,
-            });
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.remaining))
+    }
+}
+
+/// Reader that keeps only name hashes and central directory offsets in memory,
+/// returned by [`StreamingZipReader::open_indexed`].
+///
+/// Each entry costs 16 bytes of index instead of a full [`ZipEntry`]; lookups
+/// re-read and parse the matching central directory record on demand, so
+/// `find_entry` takes `&mut self` and returns an owned entry.
+pub struct IndexedZipReader<R: Read + Seek = BufReader<File>> {
+    /// Reader with an empty entry list, used for its source and entry decoding
+    reader: StreamingZipReader<R>,
+    /// `(name hash, record offset)` pairs sorted by hash, then offset
+    index: Vec<(u64, u64)>,
+}
+
+impl<R: Read + Seek> IndexedZipReader<R> {
+    /// Scan the central directory of `source` and build the name index
+    pub fn new(source: R) -> Result<Self> {
+        Self::new_with_options(source, ReaderOptions::default())
+    }
+
+    /// Like [`new`](Self::new), parsing records as `options` describe; see
+    /// [`LazyEntries::new_with_options`]
+    pub fn new_with_options(source: R, options: ReaderOptions) -> Result<Self> {
+        let mut lazy = LazyEntries::new_with_options(source, options)?;
+        let mut index = Vec::new();
+        loop {
+            let offset = lazy.next_offset;
+            match lazy.next() {
+                Some(entry) => index.push((name_hash(&entry?.name), offset)),
+                None => break,
+            }
         }
+        index.sort_unstable();
+        Ok(IndexedZipReader {
+            reader: lazy.reader,
+            index,
+        })
+    }
 
-        Ok(entries)
+    /// Number of entries in the archive
+    pub fn len(&self) -> usize {
+        self.index.len()
     }
 
-    /// When EOCD indicates ZIP64 usage, find and read ZIP64 EOCD locator and record
-    fn read_zip64_eocd(file: &mut BufReader<File>, eocd_offset: u64) -> Result<(u64, u64, u64)> {
-        // Search backwards from EOCD for ZIP64 EOCD locator signature (50 4b 06 07)
-        let search_start = eocd_offset.saturating_sub(65557);
-        file.seek(SeekFrom::Start(search_start))?;
-        let mut buffer = Vec::new();
-        file.read_to_end(&mut buffer)?;
+    /// Whether the archive has no entries
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
 
-        let zip64_eocd_offset = find_zip64_eocd_offset(&buffer)
-            .ok_or_else(|| SZipError::InvalidFormat("ZIP64 EOCD locator not found".to_string()))?;
+    /// Archive comment stored in the end-of-central-directory record
+    pub fn comment(&self) -> Cow<'_, str> {
+        self.reader.comment()
+    }
 
-        // Seek to ZIP64 EOCD record
-        file.seek(SeekFrom::Start(zip64_eocd_offset))?;
+    /// Raw bytes of the archive comment
+    pub fn archive_comment(&self) -> &[u8] {
+        self.reader.archive_comment()
+    }
 
-        let sig = Self::read_u32_le_static(file)?;
-        if sig != ZIP64_END_OF_CENTRAL_DIRECTORY_SIGNATURE {
-            return Err(SZipError::InvalidFormat(format!(
-                "Invalid ZIP64 EOCD signature: 0x{:08x}",
-                sig
-            )));
+    /// Set password for decrypting encrypted entries
+    #[cfg(feature = "encryption")]
+    pub fn set_password(&mut self, password: impl Into<String>) -> &mut Self {
+        self.reader.set_password(password);
+        self
+    }
+
+    /// Find an entry by name, parsing its central directory record.
+    ///
+    /// With duplicate names, the first entry in directory order is returned,
+    /// as with [`StreamingZipReader::find_entry`].
+    pub fn find_entry(&mut self, name: &str) -> Result<Option<ZipEntry>> {
+        let hash = name_hash(name);
+        let first = self.index.partition_point(|&(h, _)| h < hash);
+        for &(h, offset) in &self.index[first..] {
+            if h != hash {
+                break;
+            }
+            let file = &mut self.reader.file;
+            file.seek(SeekFrom::Start(offset))?;
+            match StreamingZipReader::<R>::read_cd_record(file, &self.reader.options)? {
+                Some(entry) if entry.name == name => return Ok(Some(entry)),
+                Some(_) => {} // hash collision
+                None => {
+                    return Err(SZipError::InvalidFormat(
+                        "Central directory changed while reading".to_string(),
+                    ))
+                }
+            }
         }
+        Ok(None)
+    }
 
-        // size of ZIP64 EOCD record (8 bytes)
-        let _size = {
-            let mut buf = [0u8; 8];
-            file.read_exact(&mut buf)?;
-            u64::from_le_bytes(buf)
-        };
+    /// Read an entry's decompressed data, as
+    /// [`StreamingZipReader::read_entry`] does
+    pub fn read_entry(&mut self, entry: &ZipEntry) -> Result<Vec<u8>> {
+        self.reader.read_entry(entry)
+    }
 
-        // skip version made by (2), version needed (2), disk number (4), disk where central dir starts (4)
-        file.seek(SeekFrom::Current(12))?;
+    /// Read an entry by name
+    pub fn read_entry_by_name(&mut self, name: &str) -> Result<Vec<u8>> {
+        let entry = self
+            .find_entry(name)?
+            .ok_or_else(|| SZipError::EntryNotFound(name.to_string()))?;
+        self.reader.read_entry(&entry)
+    }
+}
 
-        // total number of entries on this disk (8)
-        let total_entries = {
-            let mut buf = [0u8; 8];
-            file.read_exact(&mut buf)?;
-            u64::from_le_bytes(buf)
-        };
+/// Reader that keeps entry names in a shared [`NameTable`], returned by
+/// [`StreamingZipReader::open_compact`].
+///
+/// Each directory prefix is stored once; entries are handed out as owned
+/// [`ZipEntry`] values built on demand.
+///
+/// # Example
+/// ```no_run
+/// # use s_zip::StreamingZipReader;
+/// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut reader = StreamingZipReader::open_compact("tree.zip")?;
+/// for name in reader.names().iter().filter(|n| n.dir() == "assets/icons/") {
+///     println!("{}", name.file_name());
+/// }
+/// let data = reader.read_entry_by_name("assets/icons/home.svg")?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct CompactZipReader<R: Read + Seek = BufReader<File>> {
+    /// Reader with an empty entry list, used for its source and entry decoding
+    reader: StreamingZipReader<R>,
+    names: NameTable,
+    /// Entry metadata with empty names; the name lives in `names`
+    entries: Vec<ZipEntry>,
+}
 
-        // total number of entries (8) - some implementations write both; ignore the second value
-        {
-            let mut buf = [0u8; 8];
-            file.read_exact(&mut buf)?;
+impl<R: Read + Seek> CompactZipReader<R> {
+    /// Read the central directory of `source`
+    pub fn new(source: R) -> Result<Self> {
+        Self::new_with_options(source, ReaderOptions::default())
+    }
+
+    /// Like [`new`](Self::new), parsing records as `options` describe; see
+    /// [`LazyEntries::new_with_options`]
+    pub fn new_with_options(source: R, options: ReaderOptions) -> Result<Self> {
+        let mut lazy = LazyEntries::new_with_options(source, options)?;
+        let mut names = NameTable::new();
+        let mut entries = Vec::new();
+        for entry in lazy.by_ref() {
+            let mut entry = entry?;
+            names.push(&entry.name);
+            entry.name = String::new();
+            entries.push(entry);
         }
+        Ok(CompactZipReader {
+            reader: lazy.reader,
+            names,
+            entries,
+        })
+    }
 
-        // central directory size (8)
-        let cd_size = {
-            let mut buf = [0u8; 8];
-            file.read_exact(&mut buf)?;
-            u64::from_le_bytes(buf)
-        };
+    /// Number of entries in the archive
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
 
-        // central directory offset (8)
-        let cd_offset = {
-            let mut buf = [0u8; 8];
-            file.read_exact(&mut buf)?;
-            u64::from_le_bytes(buf)
-        };
+    /// Whether the archive has no entries
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
 
-        Ok((total_entries, cd_size, cd_offset))
+    /// Entry names, in central directory order
+    pub fn names(&self) -> &NameTable {
+        &self.names
     }
 
-    /// Find the end of central directory record by scanning from the end of the file
-    fn find_eocd(file: &mut BufReader<File>) -> Result<u64> {
-        let file_size = file.seek(SeekFrom::End(0))?;
+    /// Archive comment stored in the end-of-central-directory record
+    pub fn comment(&self) -> Cow<'_, str> {
+        self.reader.comment()
+    }
 
-        // EOCD is at least 22 bytes, search last 65KB (max comment size + EOCD)
-        let search_start = file_size.saturating_sub(65557);
-        file.seek(SeekFrom::Start(search_start))?;
+    /// Raw bytes of the archive comment
+    pub fn archive_comment(&self) -> &[u8] {
+        self.reader.archive_comment()
+    }
 
-        let mut buffer = Vec::new();
-        file.read_to_end(&mut buffer)?;
+    /// Set password for decrypting encrypted entries
+    #[cfg(feature = "encryption")]
+    pub fn set_password(&mut self, password: impl Into<String>) -> &mut Self {
+        self.reader.set_password(password);
+        self
+    }
 
-        find_eocd_in_buffer(&buffer, search_start).ok_or_else(|| {
-            SZipError::InvalidFormat("End of central directory not found".to_string())
+    /// The entry at `index` in central directory order
+    pub fn entry(&self, index: usize) -> Option<ZipEntry> {
+        let name = self.names.get(index)?;
+        Some(ZipEntry {
+            name: name.to_string(),
+            ..self.entries[index].clone()
         })
     }
 
-    fn read_u16_le(&mut self) -> Result<u16> {
-        let mut buf = [0u8; 2];
-        self.file.read_exact(&mut buf)?;
-        Ok(u16::from_le_bytes(buf))
+    /// Find an entry by name (first match in directory order)
+    pub fn find_entry(&self, name: &str) -> Option<ZipEntry> {
+        self.entry(self.names.position(name)?)
     }
 
-    fn read_u32_le(&mut self) -> Result<u32> {
-        let mut buf = [0u8; 4];
-        self.file.read_exact(&mut buf)?;
-        Ok(u32::from_le_bytes(buf))
+    /// Read an entry's decompressed data, as
+    /// [`StreamingZipReader::read_entry`] does
+    pub fn read_entry(&mut self, entry: &ZipEntry) -> Result<Vec<u8>> {
+        self.reader.read_entry(entry)
     }
 
-    fn read_u16_le_static(file: &mut BufReader<File>) -> Result<u16> {
-        let mut buf = [0u8; 2];
-        file.read_exact(&mut buf)?;
-        Ok(u16::from_le_bytes(buf))
+    /// Read an entry by name
+    pub fn read_entry_by_name(&mut self, name: &str) -> Result<Vec<u8>> {
+        let entry = self
+            .find_entry(name)
+            .ok_or_else(|| SZipError::EntryNotFound(name.to_string()))?;
+        self.reader.read_entry(&entry)
     }
 
-    fn read_u32_le_static(file: &mut BufReader<File>) -> Result<u32> {
-        let mut buf = [0u8; 4];
-        file.read_exact(&mut buf)?;
-        Ok(u32::from_le_bytes(buf))
+    /// Read the entry at `index`
+    pub fn read_entry_by_index(&mut self, index: usize) -> Result<Vec<u8>> {
+        let entry = self
+            .entry(index)
+            .ok_or_else(|| SZipError::EntryNotFound(format!("index {}", index)))?;
+        self.reader.read_entry(&entry)
     }
+}
 
-    /// Parse AES encryption info from extra field
-    #[cfg(feature = "encryption")]
-    #[allow(clippy::type_complexity)]
-    fn parse_aes_extra_field(
-        &mut self,
-        extra_len: usize,
-    ) -> Result<Option<(AesStrength, Vec<u8>, [u8; 2])>> {
-        if extra_len == 0 {
-            return Ok(None);
+/// The entries called `names`, sorted by offset with duplicate names removed.
+///
+/// Looks every name up in one pass over `entries`, taking the first match in
+/// directory order as [`StreamingZipReader::find_entry`] does; fails on the
+/// first name that is not present. Central records aliasing one local header
+/// stay separate entries, adjacent to each other.
+pub(crate) fn entries_named(entries: &[ZipEntry], names: &[&str]) -> Result<Vec<ZipEntry>> {
+    let wanted: HashSet<&str> = names.iter().copied().collect();
+    let mut found: HashMap<&str, &ZipEntry> = HashMap::with_capacity(wanted.len());
+    for entry in entries {
+        if let Some(&name) = wanted.get(entry.name.as_str()) {
+            found.entry(name).or_insert(entry);
         }
+    }
+    if let Some(missing) = names.iter().find(|name| !found.contains_key(*name)) {
+        return Err(SZipError::EntryNotFound(missing.to_string()));
+    }
+    let mut targets: Vec<ZipEntry> = found.into_values().cloned().collect();
+    targets.sort_by_key(|e| e.offset);
+    Ok(targets)
+}
 
-        let mut extra_buf = vec![0u8; extra_len];
-        self.file.read_exact(&mut extra_buf)?;
+/// Filter keeping names that start with one of `prefixes`
+pub(crate) fn prefix_filter(prefixes: &[&str]) -> NameFilter {
+    let prefixes: Vec<String> = prefixes.iter().map(|p| p.to_string()).collect();
+    Arc::new(move |name| prefixes.iter().any(|p| name.starts_with(p.as_str())))
+}
 
-        // Use shared pure helper to find the strength code
-        let strength_code = match parse_aes_extra_field_buf(&extra_buf) {
-            Some(code) => code,
-            None => return Ok(None),
-        };
+/// Hash used by [`IndexedZipReader`]; deterministic within a process
+fn name_hash(name: &str) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    name.hash(&mut hasher);
+    hasher.finish()
+}
 
-        let strength = match strength_code {
-            0x01 => AesStrength::Aes128,
-            0x02 => AesStrength::Aes192,
-            0x03 => AesStrength::Aes256,
-            _ => {
-                return Err(SZipError::InvalidFormat(format!(
-                    "Unsupported AES strength: {}",
-                    strength_code
-                )))
-            }
-        };
+/// Streaming reader for one entry, returned by
+/// [`StreamingZipReader::read_entry_streaming`].
+///
+/// Decompresses (and decrypts) on the fly; implements [`Read`] and
+/// [`BufRead`] and keeps the [`ZipEntry`] it reads.
+pub struct ZipEntryReader<'a, R: Read + Seek = BufReader<File>> {
+    entry: ZipEntry,
+    inner: BufReader<DeadlineReader<EntryReaderStream<'a, R>>>,
+}
 
-        // Read salt and password verification from actual file data (not extra field)
-        // Salt comes after the extra field, before compressed data
-        let salt_size = strength.salt_size();
+/// Built-in decoder stack, or the stream of a registered decoder. The
+/// built-in stack stays inline: boxing it is the allocation this type avoids.
+#[allow(clippy::large_enum_variant)]
+enum EntryReaderStream<'a, R: Read> {
+    Builtin(EntryStream<&'a mut R>),
+    Decoded(Box<dyn Read + 'a>),
+}
 
-        let mut salt = vec![0u8; salt_size];
-        self.file.read_exact(&mut salt)?;
+impl<R: Read + Seek> ZipEntryReader<'_, R> {
+    /// The entry being read
+    pub fn entry(&self) -> &ZipEntry {
+        &self.entry
+    }
+}
 
-        let mut pw_verify = [0u8; 2];
-        self.file.read_exact(&mut pw_verify)?;
+impl<R: Read + Seek> Read for ZipEntryReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
 
-        Ok(Some((strength, salt, pw_verify)))
+impl<R: Read + Seek> BufRead for ZipEntryReader<'_, R> {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt)
+    }
+}
+
+impl<R: Read> Read for EntryReaderStream<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            EntryReaderStream::Builtin(r) => r.read(buf),
+            EntryReaderStream::Decoded(r) => r.read(buf),
+        }
+    }
+}
+
+/// Zstd decoder of `source`, accepting windows up to the format's limit
+#[cfg(feature = "zstd-support")]
+fn zstd_decoder<B: BufRead>(
+    source: B,
+    dictionary: Option<&ZstdDictionary>,
+) -> std::io::Result<zstd::Decoder<'static, B>> {
+    let mut decoder = match dictionary {
+        Some(dictionary) => zstd::Decoder::with_dictionary(source, dictionary.as_bytes())?,
+        None => zstd::Decoder::with_buffer(source)?,
+    };
+    decoder.window_log_max(ZSTD_WINDOW_LOG_MAX)?;
+    Ok(decoder)
+}
+
+/// Concrete decoder stack produced for a streamed entry.
+///
+/// An enum rather than a boxed trait object so that it is `Send` exactly when
+/// the underlying source is; callers box it with or without `Send` as needed.
+enum EntryStream<S: Read> {
+    Stored(Take<S>),
+    Deflate(DeflateDecoder<Take<S>>),
+    #[cfg(feature = "zstd-support")]
+    Zstd(zstd::Decoder<'static, BufReader<Take<S>>>),
+    #[cfg(feature = "deflate64")]
+    Deflate64(deflate64::Deflate64Decoder<BufReader<Take<S>>>),
+    #[cfg(feature = "bzip2")]
+    Bzip2(bzip2::read::BzDecoder<Take<S>>),
+    #[cfg(feature = "lzma")]
+    Lzma(crate::lzma::Decoder<Take<S>>),
+    #[cfg(feature = "encryption")]
+    EncryptedStored(DecryptingReader<Take<S>>),
+    #[cfg(feature = "encryption")]
+    EncryptedDeflate(DeflateDecoder<DecryptingReader<Take<S>>>),
+}
+
+impl<S: Read> Read for EntryStream<S> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            EntryStream::Stored(r) => r.read(buf),
+            EntryStream::Deflate(r) => r.read(buf),
+            #[cfg(feature = "zstd-support")]
+            EntryStream::Zstd(r) => r.read(buf),
+            #[cfg(feature = "deflate64")]
+            EntryStream::Deflate64(r) => r.read(buf),
+            #[cfg(feature = "bzip2")]
+            EntryStream::Bzip2(r) => r.read(buf),
+            #[cfg(feature = "lzma")]
+            EntryStream::Lzma(r) => r.read(buf),
+            #[cfg(feature = "encryption")]
+            EntryStream::EncryptedStored(r) => r.read(buf),
+            #[cfg(feature = "encryption")]
+            EntryStream::EncryptedDeflate(r) => r.read(buf),
+        }
     }
 }
@@ -0,0 +1,184 @@
+//! Describing which ZIP format features an archive uses.
+//!
+//! When a third-party tool refuses an archive, the reason is almost always a
+//! format feature it doesn't support: ZIP64 records, data descriptors, AES
+//! encryption, Zstandard compression, or legacy name encodings.
+//! [`FormatReport`] lists them so the cause can be spotted without a hex dump.
+//!
+//! ```no_run
+//! use s_zip::StreamingZipReader;
+//!
+//! let mut reader = StreamingZipReader::open("archive.zip")?;
+//! println!("{}", reader.format_report()?);
+//! # Ok::<(), s_zip::SZipError>(())
+//! ```
+
+use crate::format::{find_eocd_in_buffer, ZipEntry};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+/// How many bytes from the end of the archive are searched for the EOCD
+/// record (maximum comment length + EOCD size)
+pub(crate) const TAIL_LEN: u64 = 65557;
+
+/// ZIP64 end of central directory locator signature (`PK\x06\x07`)
+const ZIP64_LOCATOR_SIGNATURE: [u8; 4] = [0x50, 0x4b, 0x06, 0x07];
+
+/// Format features used by an archive, as returned by `format_report()`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FormatReport {
+    /// Number of central directory entries
+    pub entry_count: usize,
+    /// Entry count per compression method id, as stored in the central directory
+    pub compression_methods: BTreeMap<u16, usize>,
+    /// The archive ends with a ZIP64 end of central directory record
+    pub zip64_end_record: bool,
+    /// Entries whose sizes or offset need ZIP64 extra fields
+    pub zip64_entries: usize,
+    /// Entries written with a trailing data descriptor (flag bit 3)
+    pub data_descriptor_entries: usize,
+    /// Entries encrypted with WinZip AES
+    pub aes_encrypted_entries: usize,
+    /// Entries encrypted with traditional PKWARE (ZipCrypto) encryption
+    pub zipcrypto_encrypted_entries: usize,
+    /// The EOCD records reference more than one disk (split/spanned archive)
+    pub multi_disk: bool,
+    /// Entries with the UTF-8 name flag (bit 11) set
+    pub utf8_flagged_entries: usize,
+    /// Entries with non-ASCII names but no UTF-8 flag, i.e. a legacy code
+    /// page such as CP437 that other tools may decode differently
+    pub legacy_encoded_entries: usize,
+    /// Host systems from "version made by" (0 = MS-DOS, 3 = Unix, 10 = NTFS, ...)
+    pub host_systems: BTreeSet<u8>,
+    /// Highest "version needed to extract" of any entry (e.g. 45 = ZIP64, 63 = Zstd)
+    pub max_version_needed: u16,
+    /// The archive has an EOCD comment
+    pub has_comment: bool,
+}
+
+impl FormatReport {
+    /// Build a report from the parsed entries and the last bytes of the archive
+    /// (at most [`TAIL_LEN`], containing the EOCD record)
+    pub(crate) fn new(entries: &[ZipEntry], comment: &[u8], tail: &[u8]) -> Self {
+        let mut report = FormatReport {
+            entry_count: entries.len(),
+            has_comment: !comment.is_empty(),
+            ..Default::default()
+        };
+
+        for entry in entries {
+            *report
+                .compression_methods
+                .entry(entry.compression_method)
+                .or_default() += 1;
+            if entry.compressed_size >= u32::MAX as u64
+                || entry.uncompressed_size >= u32::MAX as u64
+                || entry.offset >= u32::MAX as u64
+            {
+                report.zip64_entries += 1;
+            }
+            if entry.flags & 0x08 != 0 {
+                report.data_descriptor_entries += 1;
+            }
+            if entry.is_encrypted {
+                if entry.aes_strength.is_some() {
+                    report.aes_encrypted_entries += 1;
+                } else {
+                    report.zipcrypto_encrypted_entries += 1;
+                }
+            }
+            if entry.flags & 0x0800 != 0 {
+                report.utf8_flagged_entries += 1;
+            } else if !entry.name.is_ascii() {
+                report.legacy_encoded_entries += 1;
+            }
+            report
+                .host_systems
+                .insert((entry.version_made_by >> 8) as u8);
+            report.max_version_needed = report.max_version_needed.max(entry.version_needed);
+        }
+
+        if let Some(eocd) = find_eocd_in_buffer(tail, 0).map(|i| i as usize) {
+            let u16_at = |i: usize| u16::from_le_bytes([tail[i], tail[i + 1]]);
+            if eocd + 8 <= tail.len() && (u16_at(eocd + 4) != 0 || u16_at(eocd + 6) != 0) {
+                report.multi_disk = true;
+            }
+            // The ZIP64 locator (20 bytes) sits directly before the EOCD record
+            if let Some(locator) = eocd.checked_sub(20) {
+                if tail[locator..locator + 4] == ZIP64_LOCATOR_SIGNATURE {
+                    report.zip64_end_record = true;
+                    let total_disks =
+                        u32::from_le_bytes(tail[locator + 16..locator + 20].try_into().unwrap());
+                    report.multi_disk |= total_disks > 1;
+                }
+            }
+        }
+        report
+    }
+}
+
+/// Human-readable name of a compression method id
+pub fn method_name(method: u16) -> &'static str {
+    match method {
+        0 => "Stored",
+        8 => "Deflate",
+        9 => "Deflate64",
+        12 => "BZIP2",
+        14 => "LZMA",
+        93 => "Zstandard",
+        95 => "XZ",
+        98 => "PPMd",
+        99 => "AES-encrypted",
+        _ => "unknown",
+    }
+}
+
+impl fmt::Display for FormatReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "entries: {}", self.entry_count)?;
+        let methods: Vec<String> = self
+            .compression_methods
+            .iter()
+            .map(|(id, n)| format!("{} ({}) x{}", method_name(*id), id, n))
+            .collect();
+        writeln!(f, "compression methods: {}", methods.join(", "))?;
+        writeln!(
+            f,
+            "ZIP64: end record {}, {} entries",
+            if self.zip64_end_record {
+                "present"
+            } else {
+                "absent"
+            },
+            self.zip64_entries
+        )?;
+        writeln!(
+            f,
+            "data descriptors: {} entries",
+            self.data_descriptor_entries
+        )?;
+        writeln!(
+            f,
+            "encryption: {} AES, {} ZipCrypto",
+            self.aes_encrypted_entries, self.zipcrypto_encrypted_entries
+        )?;
+        writeln!(
+            f,
+            "names: {} UTF-8 flagged, {} legacy encoded",
+            self.utf8_flagged_entries, self.legacy_encoded_entries
+        )?;
+        let hosts: Vec<String> = self.host_systems.iter().map(u8::to_string).collect();
+        writeln!(f, "host systems: {}", hosts.join(", "))?;
+        writeln!(f, "max version needed: {}", self.max_version_needed)?;
+        writeln!(
+            f,
+            "multi-disk: {}",
+            if self.multi_disk { "yes" } else { "no" }
+        )?;
+        write!(
+            f,
+            "comment: {}",
+            if self.has_comment { "yes" } else { "no" }
+        )
+    }
+}
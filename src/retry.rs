@@ -0,0 +1,305 @@
+//! Retrying reads from flaky sources.
+//!
+//! [`RetryingReader`] wraps a seekable source (`Read + Seek`, or with the
+//! `async` feature `AsyncRead + AsyncSeek` such as `S3ZipReader`) and retries
+//! a failed read after a backoff, seeking back to the offset where it failed
+//! first. A connection reset in the middle of a long entry stream then costs
+//! one extra request instead of the whole extraction.
+//!
+//! Errors that retrying cannot fix are returned at once: a passed deadline,
+//! a remote object that changed (`SZipError::SourceChanged`), and the
+//! `NotFound`, `PermissionDenied`, `InvalidInput`, `InvalidData` and
+//! `Unsupported` error kinds.
+//!
+//! ```no_run
+//! # #[cfg(feature = "cloud-s3")]
+//! # async fn example(client: aws_sdk_s3::Client) -> s_zip::Result<()> {
+//! use s_zip::cloud::S3ZipReader;
+//! use s_zip::retry::RetryingReader;
+//! use s_zip::GenericAsyncZipReader;
+//! use std::time::Duration;
+//!
+//! let source = S3ZipReader::new(client, "my-bucket", "large.zip").await?;
+//! let source = RetryingReader::new(source)
+//!     .with_max_retries(5)
+//!     .with_backoff(Duration::from_millis(200), Duration::from_secs(10));
+//! let zip = GenericAsyncZipReader::new(source).await?;
+//! zip.extract_all("out", 8).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::io::{self, Read, Seek, SeekFrom};
+use std::time::Duration;
+
+/// Reader adapter that retries failed reads with exponential backoff
+///
+/// Offsets are tracked from the inner reader's position when it is wrapped,
+/// which is assumed to be 0; wrap readers before reading from them.
+pub struct RetryingReader<R> {
+    inner: R,
+    pos: u64,
+    max_retries: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    /// Consecutive failures since the last successful read
+    failures: u32,
+    #[cfg(feature = "async")]
+    retry: async_impl::Retry,
+}
+
+impl<R> RetryingReader<R> {
+    /// Retry up to 3 times per read, waiting 100 ms, then 200 ms, 400 ms, ...
+    /// up to 10 s between attempts
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            pos: 0,
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(10),
+            failures: 0,
+            #[cfg(feature = "async")]
+            retry: async_impl::Retry::Idle,
+        }
+    }
+
+    /// Retries after consecutive failures of the same read before giving up
+    pub fn with_max_retries(mut self, retries: u32) -> Self {
+        self.max_retries = retries;
+        self
+    }
+
+    /// Wait `initial` before the first retry, doubling for each further
+    /// attempt up to `max`
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Get a reference to the wrapped reader
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the wrapped reader
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Unwrap, returning the inner reader
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Record a failed read; returns the delay before the next attempt, or
+    /// `None` if the error should be returned
+    fn next_backoff(&mut self, err: &io::Error) -> Option<Duration> {
+        if self.failures >= self.max_retries || !is_retryable(err) {
+            self.failures = 0;
+            return None;
+        }
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(1 << self.failures.min(16))
+            .min(self.max_backoff);
+        self.failures += 1;
+        crate::trace_warn!(
+            error = %err,
+            offset = self.pos,
+            attempt = self.failures,
+            "read failed, retrying"
+        );
+        Some(backoff)
+    }
+}
+
+// A pending retry belongs to the original; clones start idle
+impl<R: Clone> Clone for RetryingReader<R> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            pos: self.pos,
+            max_retries: self.max_retries,
+            initial_backoff: self.initial_backoff,
+            max_backoff: self.max_backoff,
+            failures: 0,
+            #[cfg(feature = "async")]
+            retry: async_impl::Retry::Idle,
+        }
+    }
+}
+
+/// Whether retrying a read that failed with `err` can help
+fn is_retryable(err: &io::Error) -> bool {
+    use io::ErrorKind::*;
+    if crate::deadline::is_expired(err) || crate::error::is_source_changed(err) {
+        return false;
+    }
+    !matches!(
+        err.kind(),
+        NotFound | PermissionDenied | InvalidInput | InvalidData | Unsupported
+    )
+}
+
+impl<R: Read + Seek> Read for RetryingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            match self.inner.read(buf) {
+                Ok(n) => {
+                    self.pos += n as u64;
+                    self.failures = 0;
+                    return Ok(n);
+                }
+                Err(e) => match self.next_backoff(&e) {
+                    Some(backoff) => {
+                        std::thread::sleep(backoff);
+                        self.inner.seek(SeekFrom::Start(self.pos))?;
+                    }
+                    None => return Err(e),
+                },
+            }
+        }
+    }
+}
+
+impl<R: Seek> Seek for RetryingReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.pos = self.inner.seek(pos)?;
+        self.failures = 0;
+        Ok(self.pos)
+    }
+}
+
+#[cfg(feature = "async")]
+mod async_impl {
+    use super::RetryingReader;
+    use std::future::Future;
+    use std::io::{self, SeekFrom};
+    use std::pin::Pin;
+    use std::task::{ready, Context, Poll};
+    use tokio::io::{AsyncRead, AsyncSeek, ReadBuf};
+
+    /// Progress of a retry in `poll_read`
+    pub(super) enum Retry {
+        Idle,
+        Sleeping(Pin<Box<tokio::time::Sleep>>),
+        Seeking,
+    }
+
+    impl<R: AsyncRead + AsyncSeek + Unpin> AsyncRead for RetryingReader<R> {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<io::Result<()>> {
+            let this = &mut *self;
+            loop {
+                match &mut this.retry {
+                    Retry::Idle => {
+                        let before = buf.filled().len();
+                        match ready!(Pin::new(&mut this.inner).poll_read(cx, buf)) {
+                            Ok(()) => {
+                                this.pos += (buf.filled().len() - before) as u64;
+                                this.failures = 0;
+                                return Poll::Ready(Ok(()));
+                            }
+                            Err(e) => match this.next_backoff(&e) {
+                                Some(backoff) => {
+                                    this.retry =
+                                        Retry::Sleeping(Box::pin(tokio::time::sleep(backoff)));
+                                }
+                                None => return Poll::Ready(Err(e)),
+                            },
+                        }
+                    }
+                    Retry::Sleeping(sleep) => {
+                        ready!(sleep.as_mut().poll(cx));
+                        this.retry = Retry::Idle;
+                        Pin::new(&mut this.inner).start_seek(SeekFrom::Start(this.pos))?;
+                        this.retry = Retry::Seeking;
+                    }
+                    Retry::Seeking => {
+                        let result = ready!(Pin::new(&mut this.inner).poll_complete(cx));
+                        this.retry = Retry::Idle;
+                        result?;
+                    }
+                }
+            }
+        }
+    }
+
+    impl<R: AsyncSeek + Unpin> AsyncSeek for RetryingReader<R> {
+        fn start_seek(mut self: Pin<&mut Self>, position: SeekFrom) -> io::Result<()> {
+            self.retry = Retry::Idle;
+            self.failures = 0;
+            Pin::new(&mut self.inner).start_seek(position)
+        }
+
+        fn poll_complete(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<u64>> {
+            let pos = ready!(Pin::new(&mut self.inner).poll_complete(cx))?;
+            self.pos = pos;
+            Poll::Ready(Ok(pos))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// Fails every `every`-th read with a connection reset
+    struct Flaky {
+        inner: Cursor<Vec<u8>>,
+        every: usize,
+        reads: usize,
+    }
+
+    impl Read for Flaky {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.reads += 1;
+            if self.reads.is_multiple_of(self.every) {
+                // Simulate a connection dropped after a partial transfer
+                self.inner.set_position(self.inner.position() + 3);
+                return Err(io::ErrorKind::ConnectionReset.into());
+            }
+            let len = buf.len().min(7);
+            self.inner.read(&mut buf[..len])
+        }
+    }
+
+    impl Seek for Flaky {
+        fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+            self.inner.seek(pos)
+        }
+    }
+
+    fn flaky(every: usize) -> Flaky {
+        Flaky {
+            inner: Cursor::new((0..=255).collect()),
+            every,
+            reads: 0,
+        }
+    }
+
+    #[test]
+    fn test_resumes_at_failed_offset() {
+        let mut reader = RetryingReader::new(flaky(3)).with_backoff(Duration::ZERO, Duration::ZERO);
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data).unwrap();
+        assert_eq!(data, (0..=255).collect::<Vec<u8>>());
+    }
+
+    #[test]
+    fn test_gives_up_after_max_retries() {
+        // Every read fails
+        let mut reader = RetryingReader::new(flaky(1))
+            .with_max_retries(2)
+            .with_backoff(Duration::ZERO, Duration::ZERO);
+        let err = reader.read(&mut [0u8; 8]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::ConnectionReset);
+        assert_eq!(reader.get_ref().reads, 3);
+    }
+}
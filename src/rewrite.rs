@@ -0,0 +1,218 @@
+//! Removing or replacing entries of an existing archive.
+//!
+//! A ZIP archive can't be edited in place, but it doesn't need to be
+//! extracted and recreated either. A [`Rewrite`] copies an archive to a new
+//! output, streaming the stored data of kept entries through without
+//! decompressing it (see
+//! [`StreamingZipWriter::copy_entry`](crate::StreamingZipWriter::copy_entry));
+//! only replacement data is compressed.
+//!
+//! ```no_run
+//! use s_zip::Rewrite;
+//!
+//! let mut rewrite = Rewrite::new();
+//! rewrite
+//!     .remove("secrets.env")
+//!     .replace("config.json", br#"{"debug": false}"#.to_vec());
+//! // Writes a temporary file next to the target, then renames it
+//! let report = rewrite.to_file("app.zip", "app.zip")?;
+//! println!("{} entries copied", report.copied);
+//! # Ok::<(), s_zip::SZipError>(())
+//! ```
+
+use crate::error::Result;
+use crate::extract::EntryFilter;
+use crate::format::ZipEntry;
+use crate::reader::StreamingZipReader;
+use crate::writer::StreamingZipWriter;
+use crate::EntryOptions;
+use std::collections::HashSet;
+use std::io::{Read, Seek, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Entries to remove or replace while copying an archive
+#[derive(Default)]
+pub struct Rewrite {
+    removed: HashSet<String>,
+    remove_if: Vec<EntryFilter>,
+    /// Replacements in the order given, taken as they are written
+    replacements: Vec<(String, Option<Replacement>)>,
+}
+
+enum Replacement {
+    Bytes(Vec<u8>),
+    Reader(Box<dyn Read + Send>),
+}
+
+/// What a [`Rewrite`] did
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RewriteReport {
+    /// Entries copied unchanged
+    pub copied: usize,
+    /// Names of the entries left out
+    pub removed: Vec<String>,
+    /// Names of the entries whose data was replaced
+    pub replaced: Vec<String>,
+    /// Replacements without a matching entry, added at the end
+    pub added: Vec<String>,
+}
+
+impl Rewrite {
+    /// A rewrite that copies every entry unchanged
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Leave out the entries named `name`
+    pub fn remove(&mut self, name: &str) -> &mut Self {
+        self.removed.insert(name.to_string());
+        self
+    }
+
+    /// Leave out the entries matching `filter`
+    ///
+    /// Filters add up: an entry is left out if any of them matches.
+    pub fn remove_if<F>(&mut self, filter: F) -> &mut Self
+    where
+        F: Fn(&ZipEntry) -> bool + Send + Sync + 'static,
+    {
+        self.remove_if.push(Arc::new(filter));
+        self
+    }
+
+    /// Write `data` in place of the entry named `name`, or add it at the end
+    /// if there is no such entry
+    ///
+    /// The replacement keeps the entry's position, Unix mode and DOS
+    /// attributes, and is stamped with the current time.
+    pub fn replace(&mut self, name: &str, data: impl Into<Vec<u8>>) -> &mut Self {
+        self.replacements
+            .push((name.to_string(), Some(Replacement::Bytes(data.into()))));
+        self
+    }
+
+    /// Like [`replace`](Self::replace), streaming the new data from `source`
+    pub fn replace_with(&mut self, name: &str, source: impl Read + Send + 'static) -> &mut Self {
+        self.replacements.push((
+            name.to_string(),
+            Some(Replacement::Reader(Box::new(source))),
+        ));
+        self
+    }
+
+    /// Copy the entries of `reader` to `writer`, applying the changes
+    ///
+    /// `writer` is left open, so more entries can be added before it is
+    /// finished. The archive comment isn't copied.
+    pub fn apply<R: Read + Seek, W: Write + Seek>(
+        mut self,
+        reader: &mut StreamingZipReader<R>,
+        writer: &mut StreamingZipWriter<W>,
+    ) -> Result<RewriteReport> {
+        let mut report = RewriteReport::default();
+        for entry in reader.entries().to_vec() {
+            if let Some(replacement) = self.take_replacement(&entry.name) {
+                let options = EntryOptions {
+                    unix_mode: entry.unix_mode(),
+                    dos_attributes: entry.dos_attributes(),
+                    ..Default::default()
+                };
+                writer.start_entry_with_options(&entry.name, options)?;
+                write_replacement(writer, replacement)?;
+                report.replaced.push(entry.name);
+            } else if self.removed.contains(&entry.name)
+                || self.remove_if.iter().any(|remove| remove(&entry))
+            {
+                report.removed.push(entry.name);
+            } else {
+                writer.copy_entry(reader, &entry)?;
+                report.copied += 1;
+            }
+        }
+        for (name, replacement) in self.replacements {
+            if let Some(replacement) = replacement {
+                writer.start_entry(&name)?;
+                write_replacement(writer, replacement)?;
+                report.added.push(name);
+            }
+        }
+        Ok(report)
+    }
+
+    /// Rewrite the archive at `source` into `dest`, keeping its comment
+    ///
+    /// The new archive is written to a temporary file next to `dest` and
+    /// renamed over it once finished, so `dest` may be `source` itself and
+    /// is never left half-written. Kept entries are copied as stored,
+    /// replacements are compressed with DEFLATE.
+    pub fn to_file<P: AsRef<Path>, Q: AsRef<Path>>(
+        self,
+        source: P,
+        dest: Q,
+    ) -> Result<RewriteReport> {
+        let dest = dest.as_ref();
+        let mut name = dest.file_name().unwrap_or_default().to_os_string();
+        name.push(".rewrite");
+        let temp = dest.with_file_name(name);
+
+        let result = (|| {
+            let mut reader = StreamingZipReader::open(source)?;
+            let mut writer = StreamingZipWriter::new(&temp)?;
+            writer.set_archive_comment(&reader.comment());
+            let report = self.apply(&mut reader, &mut writer)?;
+            writer.finish()?.sync_all()?;
+            Ok(report)
+        })();
+        match result {
+            Ok(report) => {
+                std::fs::rename(&temp, dest)?;
+                Ok(report)
+            }
+            Err(e) => {
+                let _ = std::fs::remove_file(&temp);
+                Err(e)
+            }
+        }
+    }
+
+    /// Take the first unused replacement for `name`
+    fn take_replacement(&mut self, name: &str) -> Option<Replacement> {
+        self.replacements
+            .iter_mut()
+            .find(|(n, r)| n == name && r.is_some())
+            .and_then(|(_, r)| r.take())
+    }
+}
+
+fn write_replacement<W: Write + Seek>(
+    writer: &mut StreamingZipWriter<W>,
+    replacement: Replacement,
+) -> Result<()> {
+    match replacement {
+        Replacement::Bytes(data) => writer.write_data(&data),
+        Replacement::Reader(mut source) => {
+            let mut buf = vec![0u8; 64 * 1024];
+            loop {
+                let n = source.read(&mut buf)?;
+                if n == 0 {
+                    return Ok(());
+                }
+                writer.write_data(&buf[..n])?;
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for Rewrite {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Rewrite")
+            .field("removed", &self.removed)
+            .field("remove_if", &self.remove_if.len())
+            .field(
+                "replaced",
+                &self.replacements.iter().map(|(n, _)| n).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
@@ -0,0 +1,292 @@
+//! Recovering entries from truncated archives.
+//!
+//! An interrupted upload or download usually loses the end of the file, and
+//! with it the central directory that [`StreamingZipReader::open`] relies on.
+//! The entries before the cut are still intact, so salvaging walks the local
+//! file headers from the start of the file instead and keeps every entry whose
+//! data is completely present.
+//!
+//! ```no_run
+//! use s_zip::StreamingZipReader;
+//!
+//! let (mut reader, report) = StreamingZipReader::open_salvage("partial.zip")?;
+//! reader.extract_to_dir("recovered")?;
+//! for lost in &report.lost {
+//!     eprintln!("lost {} at offset {}: {}", lost.name, lost.offset, lost.reason);
+//! }
+//! # Ok::<(), s_zip::SZipError>(())
+//! ```
+//!
+//! [`StreamingZipReader::open`]: crate::StreamingZipReader::open
+
+use crate::error::Result;
+use crate::format::{ZipEntry, LOCAL_FILE_HEADER_SIGNATURE};
+use crate::sansio::{LocalHeader, LOCAL_HEADER_LEN};
+use std::io::{Read, Seek, SeekFrom};
+
+/// Data descriptor signature (`PK\x07\x08`)
+const DATA_DESCRIPTOR_SIGNATURE: [u8; 4] = [0x50, 0x4b, 0x07, 0x08];
+
+/// Outcome of salvaging an archive
+#[derive(Debug, Clone, Default)]
+pub struct SalvageReport {
+    /// Names of the entries that were fully present, in archive order
+    pub recovered: Vec<String>,
+    /// Entries that were cut off or could not be parsed
+    pub lost: Vec<LostEntry>,
+}
+
+impl SalvageReport {
+    /// Whether every entry found in the archive was recovered
+    pub fn is_complete(&self) -> bool {
+        self.lost.is_empty()
+    }
+}
+
+/// An entry that could not be recovered
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LostEntry {
+    /// Entry name, or empty if the header was cut off before the name
+    pub name: String,
+    /// Offset of the entry's local file header
+    pub offset: u64,
+    /// Why the entry could not be recovered
+    pub reason: String,
+}
+
+/// Walk local file headers from the start of `file`, returning every entry
+/// whose data is complete.
+///
+/// Scanning stops at the first record that is not a local file header
+/// (normally the central directory) or at the first entry that is cut off,
+/// since nothing after it can be located.
+pub(crate) fn scan_local_entries<R: Read + Seek>(
+    file: &mut R,
+) -> Result<(Vec<ZipEntry>, SalvageReport)> {
+    let archive_len = file.seek(SeekFrom::End(0))?;
+    let mut entries = Vec::new();
+    let mut report = SalvageReport::default();
+    let mut offset = 0u64;
+
+    while offset < archive_len {
+        file.seek(SeekFrom::Start(offset))?;
+        match read_local_entry(file, offset, archive_len)? {
+            Scanned::Entry(entry, next) => {
+                report.recovered.push(entry.name.clone());
+                entries.push(entry);
+                offset = next;
+            }
+            Scanned::Lost(lost) => {
+                report.lost.push(lost);
+                break;
+            }
+            Scanned::End => break,
+        }
+    }
+    Ok((entries, report))
+}
+
+enum Scanned {
+    /// A complete entry and the offset of the record after it
+    Entry(ZipEntry, u64),
+    Lost(LostEntry),
+    /// Not a local file header: the end of the entry records
+    End,
+}
+
+fn read_local_entry<R: Read + Seek>(
+    file: &mut R,
+    offset: u64,
+    archive_len: u64,
+) -> Result<Scanned> {
+    let lost = |name: &str, reason: &str| {
+        Ok(Scanned::Lost(LostEntry {
+            name: name.to_string(),
+            offset,
+            reason: reason.to_string(),
+        }))
+    };
+
+    let mut fixed = Vec::with_capacity(LOCAL_HEADER_LEN);
+    file.by_ref()
+        .take(LOCAL_HEADER_LEN as u64)
+        .read_to_end(&mut fixed)?;
+    if fixed.len() < 4 || fixed[..4] != LOCAL_FILE_HEADER_SIGNATURE.to_le_bytes() {
+        return Ok(Scanned::End);
+    }
+    let Ok(fixed) = <&[u8; LOCAL_HEADER_LEN]>::try_from(fixed.as_slice()) else {
+        return lost("", "local file header is truncated");
+    };
+    let header = LocalHeader::parse(fixed)?;
+
+    let mut variable = vec![0u8; header.variable_len() as usize];
+    let (name_buf, extra) = variable.split_at_mut(header.name_len as usize);
+    if file.read_exact(name_buf).is_err() {
+        return lost("", "file name is truncated");
+    }
+    if file.read_exact(extra).is_err() {
+        return lost(
+            &String::from_utf8_lossy(name_buf),
+            "extra field is truncated",
+        );
+    }
+    let data_start = offset + LOCAL_HEADER_LEN as u64 + header.variable_len();
+
+    let mut entry = header.into_entry(&variable, offset);
+    let next = if header.flags & 0x08 == 0 {
+        let data_end = data_start + entry.compressed_size;
+        if data_end > archive_len {
+            return lost(&entry.name, "entry data is truncated");
+        }
+        data_end
+    } else {
+        // Sizes follow the data; find the descriptor whose size field matches
+        match find_data_descriptor(file)? {
+            Some(descriptor) => {
+                entry.crc32 = descriptor.crc32;
+                entry.compressed_size = descriptor.compressed_size;
+                entry.uncompressed_size = descriptor.uncompressed_size;
+                data_start + descriptor.compressed_size + descriptor.len
+            }
+            None => return lost(&entry.name, "entry data or data descriptor is truncated"),
+        }
+    };
+
+    Ok(Scanned::Entry(entry, next))
+}
+
+pub(crate) struct DataDescriptor {
+    pub(crate) crc32: u32,
+    pub(crate) compressed_size: u64,
+    pub(crate) uncompressed_size: u64,
+    /// Length of the descriptor record itself
+    pub(crate) len: u64,
+}
+
+/// Scan forward from the start of entry data for a data descriptor whose
+/// compressed size equals its distance from the start.
+///
+/// Accepts 32-bit and ZIP64 (64-bit) descriptors; descriptors written
+/// without the optional signature cannot be located this way.
+pub(crate) fn find_data_descriptor<R: Read>(file: &mut R) -> Result<Option<DataDescriptor>> {
+    let mut scan = DescriptorScan::default();
+    let mut chunk = vec![0u8; DescriptorScan::CHUNK];
+    loop {
+        let n = file.read(&mut chunk)?;
+        if let Some(found) = scan.push(&chunk[..n]) {
+            return Ok(found);
+        }
+    }
+}
+
+/// I/O-free state of [`find_data_descriptor`], fed the entry data in chunks
+/// so that sync and async readers share the search
+#[derive(Default)]
+pub(crate) struct DescriptorScan {
+    window: Vec<u8>,
+    /// Data offset of `window[0]`
+    base: u64,
+}
+
+impl DescriptorScan {
+    /// Read size the drivers use
+    pub(crate) const CHUNK: usize = 64 * 1024;
+
+    /// Feed the next chunk of entry data, empty at end of input. Returns
+    /// `Some` once the search is over: the descriptor, or `None` if the
+    /// input ended without one.
+    pub(crate) fn push(&mut self, chunk: &[u8]) -> Option<Option<DataDescriptor>> {
+        let eof = chunk.is_empty();
+        self.window.extend_from_slice(chunk);
+
+        let mut i = 0;
+        while i + 16 <= self.window.len() {
+            // A 64-bit descriptor needs 24 bytes; wait for more data unless at EOF
+            if !eof && i + 24 > self.window.len() {
+                break;
+            }
+            if self.window[i..i + 4] == DATA_DESCRIPTOR_SIGNATURE {
+                if let Some(d) = parse_descriptor(&self.window[i..], self.base + i as u64) {
+                    return Some(Some(d));
+                }
+            }
+            i += 1;
+        }
+        self.window.drain(..i);
+        self.base += i as u64;
+        eof.then_some(None)
+    }
+}
+
+/// Interpret `buf` (starting at a descriptor signature found `distance` bytes
+/// into the entry data) as a descriptor, the way `raw::write_data_descriptor`
+/// lays it out
+fn parse_descriptor(buf: &[u8], distance: u64) -> Option<DataDescriptor> {
+    let crc32 = u32::from_le_bytes(buf[4..8].try_into().unwrap());
+    if buf.len() >= 24 {
+        let compressed = u64::from_le_bytes(buf[8..16].try_into().unwrap());
+        let uncompressed = u64::from_le_bytes(buf[16..24].try_into().unwrap());
+        // 64-bit sizes are only written when one of them needs them
+        if compressed == distance
+            && (compressed > u32::MAX as u64 || uncompressed > u32::MAX as u64)
+        {
+            return Some(DataDescriptor {
+                crc32,
+                compressed_size: compressed,
+                uncompressed_size: uncompressed,
+                len: 24,
+            });
+        }
+    }
+    let compressed = u32::from_le_bytes(buf[8..12].try_into().unwrap()) as u64;
+    if compressed == distance {
+        return Some(DataDescriptor {
+            crc32,
+            compressed_size: compressed,
+            uncompressed_size: u32::from_le_bytes(buf[12..16].try_into().unwrap()) as u64,
+            len: 16,
+        });
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_find_data_descriptor_across_chunks() {
+        // Descriptor straddles the 64 KiB read boundary
+        let distance = 64 * 1024 - 6;
+        let mut data = vec![0u8; distance];
+        crate::raw::write_data_descriptor(&mut data, 0xdead_beef, distance as u64, 12345).unwrap();
+        data.extend_from_slice(b"PK\x01\x02 trailing");
+
+        let found = find_data_descriptor(&mut Cursor::new(data))
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.crc32, 0xdead_beef);
+        assert_eq!(found.compressed_size, distance as u64);
+        assert_eq!(found.uncompressed_size, 12345);
+        assert_eq!(found.len, 16);
+    }
+
+    #[test]
+    fn test_find_data_descriptor_ignores_false_signature() {
+        // A signature inside the data whose size field doesn't match is skipped
+        let mut data = b"PK\x07\x08garbage-bytes!!".to_vec();
+        let distance = data.len() as u64;
+        crate::raw::write_data_descriptor(&mut data, 1, distance, 2).unwrap();
+
+        let found = find_data_descriptor(&mut Cursor::new(data))
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.compressed_size, distance);
+        assert!(
+            find_data_descriptor(&mut Cursor::new(b"PK\x07\x08".to_vec()))
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,785 @@
+//! Sans-I/O parsing of ZIP records.
+//!
+//! Everything here works on byte slices and never performs I/O, so the same
+//! logic serves the blocking reader, the tokio reader and any other frontend
+//! (futures-io, WASM, io_uring): a frontend only moves bytes between its
+//! source and these parsers. [`crate::raw`] is the writing counterpart.
+//!
+//! - [`LocalHeader`] and [`CentralHeader`] decode the fixed part of a local
+//!   file header and a central directory record; the frontend then reads the
+//!   variable part they announce. [`CentralRecordReader`] does the same for
+//!   a stream of central directory records, one read at a time.
+//! - `parse_aes_extra` decodes the WinZip AES extra field of an encrypted
+//!   entry (with the `encryption` feature); [`has_zip64_extra`] looks for a
+//!   ZIP64 field and [`CentralHeader::zip64_values`] resolves the values it
+//!   holds.
+//! - [`DirectoryLocator`] is a small state machine that finds the central
+//!   directory: it asks for byte ranges ([`ReadRequest`]) until it has seen
+//!   the end of central directory record and, for ZIP64 archives, the ZIP64
+//!   end records.
+//!
+//! A blocking driver for the locator:
+//!
+//! ```
+//! use s_zip::sansio::DirectoryLocator;
+//! use s_zip::StreamingZipWriter;
+//! use std::io::{Cursor, Read, Seek, SeekFrom};
+//!
+//! let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new()))?;
+//! writer.add_entry("a.txt", b"alpha")?;
+//! let mut file = writer.finish()?;
+//!
+//! let mut locator = DirectoryLocator::new(file.seek(SeekFrom::End(0))?);
+//! while let Some(request) = locator.next_read() {
+//!     file.seek(SeekFrom::Start(request.offset))?;
+//!     let mut buf = Vec::new();
+//!     file.by_ref().take(request.len).read_to_end(&mut buf)?;
+//!     locator.feed(&buf)?;
+//! }
+//! let directory = locator.finish()?;
+//! assert_eq!(directory.location.count, 1);
+//! # Ok::<(), s_zip::SZipError>(())
+//! ```
+
+use crate::dostime::DosDateTime;
+use crate::encoding::{self, FilenameEncoding};
+use crate::error::{Result, SZipError};
+use crate::format::{
+    extra_fields, find_eocd_in_buffer, find_zip64_eocd_offset, parse_aes_extra_field_buf,
+    parse_extended_timestamp_extra_field, parse_unix_owner_extra_field, parse_zip64_extra_field,
+    CentralDirectoryLocation, ZipEntry, CENTRAL_DIRECTORY_SIGNATURE,
+    END_OF_CENTRAL_DIRECTORY_SIGNATURE, LOCAL_FILE_HEADER_SIGNATURE,
+    ZIP64_END_OF_CENTRAL_DIRECTORY_SIGNATURE,
+};
+use crate::quirks::{self, QuirksMode};
+
+#[cfg(feature = "encryption")]
+use crate::encryption::AesStrength;
+
+/// Size of the fixed part of a local file header
+pub const LOCAL_HEADER_LEN: usize = 30;
+/// Size of the fixed part of a central directory record
+pub const CENTRAL_HEADER_LEN: usize = 46;
+/// Size of the end of central directory record without its comment
+pub const EOCD_LEN: usize = 22;
+/// Size of the ZIP64 end of central directory record without extensions
+pub const ZIP64_EOCD_LEN: usize = 56;
+
+/// ID of the ZIP64 extended information extra field
+pub const ZIP64_EXTRA_ID: u16 = 0x0001;
+
+/// How far before the end of the file the EOCD record can start: its own
+/// size plus the longest possible comment
+const EOCD_SEARCH_WINDOW: u64 = 65557;
+
+fn u16_at(buf: &[u8], i: usize) -> u16 {
+    u16::from_le_bytes([buf[i], buf[i + 1]])
+}
+
+fn u32_at(buf: &[u8], i: usize) -> u32 {
+    u32::from_le_bytes([buf[i], buf[i + 1], buf[i + 2], buf[i + 3]])
+}
+
+fn u64_at(buf: &[u8], i: usize) -> u64 {
+    u64::from_le_bytes(buf[i..i + 8].try_into().unwrap())
+}
+
+/// Fixed fields of a local file header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LocalHeader {
+    pub version_needed: u16,
+    pub flags: u16,
+    pub compression_method: u16,
+    pub last_modified: DosDateTime,
+    pub crc32: u32,
+    /// 32-bit size field; `0xFFFFFFFF` when the ZIP64 extra field holds it
+    pub compressed_size: u32,
+    /// 32-bit size field; `0xFFFFFFFF` when the ZIP64 extra field holds it
+    pub uncompressed_size: u32,
+    pub name_len: u16,
+    pub extra_len: u16,
+}
+
+impl LocalHeader {
+    /// Decode the fixed fields, checking the signature
+    pub fn parse(fixed: &[u8; LOCAL_HEADER_LEN]) -> Result<Self> {
+        if u32_at(fixed, 0) != LOCAL_FILE_HEADER_SIGNATURE {
+            return Err(SZipError::InvalidFormat(
+                "Invalid local file header signature".to_string(),
+            ));
+        }
+        Ok(Self {
+            version_needed: u16_at(fixed, 4),
+            flags: u16_at(fixed, 6),
+            compression_method: u16_at(fixed, 8),
+            last_modified: DosDateTime::new(u16_at(fixed, 10), u16_at(fixed, 12)),
+            crc32: u32_at(fixed, 14),
+            compressed_size: u32_at(fixed, 18),
+            uncompressed_size: u32_at(fixed, 22),
+            name_len: u16_at(fixed, 26),
+            extra_len: u16_at(fixed, 28),
+        })
+    }
+
+    /// Length of the name and extra field that follow the fixed part
+    pub fn variable_len(&self) -> u64 {
+        self.name_len as u64 + self.extra_len as u64
+    }
+
+    /// Entry described by this header alone, for readers without a central
+    /// directory; `variable` holds the name and extra field
+    ///
+    /// Sizes are zero for entries with a data descriptor. Fields that only
+    /// the central directory records (external attributes, host) are zero.
+    pub fn into_entry(self, variable: &[u8], offset: u64) -> ZipEntry {
+        let (name, extra) = variable.split_at((self.name_len as usize).min(variable.len()));
+        let (uncompressed_size, compressed_size, _) = parse_zip64_extra_field(
+            extra,
+            self.compressed_size as u64,
+            self.uncompressed_size as u64,
+            0,
+        );
+        ZipEntry {
+            name: encoding::decode_entry_name(name, self.flags, extra),
+            compressed_size,
+            uncompressed_size,
+            compression_method: self.compression_method,
+            offset,
+            disk_number: 0,
+            crc32: self.crc32,
+            is_encrypted: self.flags & 0x01 != 0,
+            flags: self.flags,
+            // Only the central directory records the host
+            version_made_by: 0,
+            version_needed: self.version_needed,
+            last_modified: self.last_modified,
+            internal_attrs: 0,
+            external_attrs: 0,
+            unix_owner: parse_unix_owner_extra_field(extra),
+            extended_timestamp: parse_extended_timestamp_extra_field(extra),
+            aes_strength: parse_aes_extra_field_buf(extra),
+            extra: extra.to_vec(),
+        }
+    }
+}
+
+/// Fixed fields of a central directory record
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CentralHeader {
+    pub version_made_by: u16,
+    pub version_needed: u16,
+    pub flags: u16,
+    pub compression_method: u16,
+    pub last_modified: DosDateTime,
+    pub crc32: u32,
+    /// 32-bit size field; `0xFFFFFFFF` when the ZIP64 extra field holds it
+    pub compressed_size: u32,
+    /// 32-bit size field; `0xFFFFFFFF` when the ZIP64 extra field holds it
+    pub uncompressed_size: u32,
+    pub name_len: u16,
+    pub extra_len: u16,
+    pub comment_len: u16,
+    /// Volume holding the local header, for split archives
+    pub disk_start: u16,
+    pub internal_attrs: u16,
+    pub external_attrs: u32,
+    /// 32-bit offset field; `0xFFFFFFFF` when the ZIP64 extra field holds it
+    pub offset: u32,
+}
+
+impl CentralHeader {
+    /// Decode the fixed fields, or `None` if there is no record signature
+    /// (the end of the directory)
+    pub fn parse(fixed: &[u8; CENTRAL_HEADER_LEN]) -> Option<Self> {
+        if u32_at(fixed, 0) != CENTRAL_DIRECTORY_SIGNATURE {
+            return None;
+        }
+        Some(Self {
+            version_made_by: u16_at(fixed, 4),
+            version_needed: u16_at(fixed, 6),
+            flags: u16_at(fixed, 8),
+            compression_method: u16_at(fixed, 10),
+            last_modified: DosDateTime::new(u16_at(fixed, 12), u16_at(fixed, 14)),
+            crc32: u32_at(fixed, 16),
+            compressed_size: u32_at(fixed, 20),
+            uncompressed_size: u32_at(fixed, 24),
+            name_len: u16_at(fixed, 28),
+            extra_len: u16_at(fixed, 30),
+            comment_len: u16_at(fixed, 32),
+            disk_start: u16_at(fixed, 34),
+            internal_attrs: u16_at(fixed, 36),
+            external_attrs: u32_at(fixed, 38),
+            offset: u32_at(fixed, 42),
+        })
+    }
+
+    /// Length of the name, extra field and comment that follow the fixed part
+    pub fn variable_len(&self) -> usize {
+        self.name_len as usize + self.extra_len as usize + self.comment_len as usize
+    }
+
+    /// Uncompressed size, compressed size and local header offset, taking
+    /// the fields that hold `0xFFFFFFFF` from the ZIP64 field of `extra`
+    pub fn zip64_values(&self, extra: &[u8]) -> (u64, u64, u64) {
+        let compressed_32 = self.compressed_size as u64;
+        let uncompressed_32 = self.uncompressed_size as u64;
+        let offset_32 = self.offset as u64;
+        if compressed_32 != 0xFFFFFFFF && uncompressed_32 != 0xFFFFFFFF && offset_32 != 0xFFFFFFFF {
+            (uncompressed_32, compressed_32, offset_32)
+        } else {
+            parse_zip64_extra_field(extra, compressed_32, uncompressed_32, offset_32)
+        }
+    }
+
+    /// Entry described by this record; `variable` holds the name, extra
+    /// field and comment
+    pub fn into_entry(self, variable: &[u8]) -> ZipEntry {
+        self.into_entry_with(variable, QuirksMode::Strict)
+    }
+
+    /// Like [`into_entry`](Self::into_entry), applying the fixes of `quirks`
+    /// that need only the record itself
+    pub fn into_entry_with(self, variable: &[u8], quirks: QuirksMode) -> ZipEntry {
+        self.into_entry_with_encoding(variable, quirks, &FilenameEncoding::Auto)
+    }
+
+    /// Like [`into_entry_with`](Self::into_entry_with), decoding the name
+    /// with `encoding`
+    pub fn into_entry_with_encoding(
+        self,
+        variable: &[u8],
+        quirks: QuirksMode,
+        encoding: &FilenameEncoding,
+    ) -> ZipEntry {
+        let name_end = (self.name_len as usize).min(variable.len());
+        let extra_end = (name_end + self.extra_len as usize).min(variable.len());
+        let name = &variable[..name_end];
+        let extra = &variable[name_end..extra_end];
+        let (uncompressed_size, compressed_size, offset) = if quirks.is_lenient() {
+            quirks::resolve_zip64(
+                extra,
+                self.compressed_size as u64,
+                self.uncompressed_size as u64,
+                self.offset as u64,
+            )
+        } else {
+            self.zip64_values(extra)
+        };
+        let mut entry = ZipEntry {
+            name: encoding.decode(name, self.flags, extra),
+            compressed_size,
+            uncompressed_size,
+            compression_method: self.compression_method,
+            offset,
+            disk_number: self.disk_start as u32,
+            crc32: self.crc32,
+            is_encrypted: self.flags & 0x01 != 0,
+            flags: self.flags,
+            version_made_by: self.version_made_by,
+            version_needed: self.version_needed,
+            last_modified: self.last_modified,
+            internal_attrs: self.internal_attrs,
+            external_attrs: self.external_attrs,
+            unix_owner: parse_unix_owner_extra_field(extra),
+            extended_timestamp: parse_extended_timestamp_extra_field(extra),
+            aes_strength: parse_aes_extra_field_buf(extra),
+            extra: extra.to_vec(),
+        };
+        if quirks.is_lenient() {
+            quirks::mark_directory(&mut entry);
+        }
+        entry
+    }
+}
+
+/// Parse `count` central directory records from `buf`.
+///
+/// Used when the central directory location comes from the caller, so a
+/// missing or truncated record is an error rather than the end of the
+/// directory.
+pub fn parse_central_directory(buf: &[u8], count: u64) -> Result<Vec<ZipEntry>> {
+    parse_central_directory_with_encoding(buf, count, QuirksMode::Strict, &FilenameEncoding::Auto)
+}
+
+/// Like [`parse_central_directory`], applying the record fixes of `quirks`
+/// and decoding names with `encoding` (see
+/// [`CentralHeader::into_entry_with_encoding`])
+pub fn parse_central_directory_with_encoding(
+    buf: &[u8],
+    count: u64,
+    quirks: QuirksMode,
+    encoding: &FilenameEncoding,
+) -> Result<Vec<ZipEntry>> {
+    // Each record is at least 46 bytes, which bounds a bogus count
+    let mut entries =
+        Vec::with_capacity(count.min((buf.len() / CENTRAL_HEADER_LEN) as u64) as usize);
+    let mut position = 0usize;
+    for _ in 0..count {
+        let missing = || {
+            SZipError::InvalidFormat(format!(
+                "No central directory record at offset {} of the given location",
+                position
+            ))
+        };
+        let fixed = buf
+            .get(position..position + CENTRAL_HEADER_LEN)
+            .ok_or_else(missing)?;
+        let header = CentralHeader::parse(fixed.try_into().unwrap()).ok_or_else(missing)?;
+        let start = position + CENTRAL_HEADER_LEN;
+        let variable = buf
+            .get(start..start + header.variable_len())
+            .ok_or_else(missing)?;
+        entries.push(header.into_entry_with_encoding(variable, quirks, encoding));
+        position = start + header.variable_len();
+    }
+    Ok(entries)
+}
+
+/// State machine reading consecutive central directory records
+///
+/// For frontends that read the directory record by record instead of in one
+/// buffer: read [`next_len`](Self::next_len) bytes and pass them to
+/// [`feed`](Self::feed) until it returns an entry, then go on with the next
+/// record. The signature is requested on its own, since the EOCD record
+/// after the last record is shorter than a record; anything but a record
+/// signature there ends the directory.
+pub struct CentralRecordReader<'a> {
+    quirks: QuirksMode,
+    encoding: &'a FilenameEncoding,
+    fixed: [u8; CENTRAL_HEADER_LEN],
+    state: RecordState,
+}
+
+enum RecordState {
+    /// Needs the signature of the next record
+    Signature,
+    /// Needs the rest of the fixed part
+    Fixed,
+    /// Needs the name, extra field and comment of the record
+    Variable(CentralHeader),
+    /// Past the last record
+    End,
+}
+
+/// What a [`CentralRecordReader`] made of the bytes fed to it
+#[derive(Debug)]
+pub enum RecordRead {
+    /// The record is incomplete; read [`next_len`](CentralRecordReader::next_len) more bytes
+    More,
+    /// A complete record
+    Entry(ZipEntry),
+    /// No record signature: the directory ended
+    End,
+}
+
+impl<'a> CentralRecordReader<'a> {
+    /// Reader applying the record fixes of `quirks` and decoding names with
+    /// `encoding` (see [`CentralHeader::into_entry_with_encoding`])
+    pub fn new(quirks: QuirksMode, encoding: &'a FilenameEncoding) -> Self {
+        Self {
+            quirks,
+            encoding,
+            fixed: [0; CENTRAL_HEADER_LEN],
+            state: RecordState::Signature,
+        }
+    }
+
+    /// Number of bytes to read next; 0 once the directory ended
+    pub fn next_len(&self) -> usize {
+        match &self.state {
+            RecordState::Signature => 4,
+            RecordState::Fixed => CENTRAL_HEADER_LEN - 4,
+            RecordState::Variable(header) => header.variable_len(),
+            RecordState::End => 0,
+        }
+    }
+
+    /// Hand over the [`next_len`](Self::next_len) bytes read
+    pub fn feed(&mut self, data: &[u8]) -> Result<RecordRead> {
+        if data.len() != self.next_len() {
+            return Err(SZipError::InvalidFormat(format!(
+                "Expected {} bytes of a central directory record, got {}",
+                self.next_len(),
+                data.len()
+            )));
+        }
+        let state = std::mem::replace(&mut self.state, RecordState::End);
+        match state {
+            RecordState::Signature => {
+                if data != CENTRAL_DIRECTORY_SIGNATURE.to_le_bytes() {
+                    return Ok(RecordRead::End);
+                }
+                self.fixed[..4].copy_from_slice(data);
+                self.state = RecordState::Fixed;
+                Ok(RecordRead::More)
+            }
+            RecordState::Fixed => {
+                self.fixed[4..].copy_from_slice(data);
+                let Some(header) = CentralHeader::parse(&self.fixed) else {
+                    return Ok(RecordRead::End);
+                };
+                self.state = RecordState::Variable(header);
+                Ok(RecordRead::More)
+            }
+            RecordState::Variable(header) => {
+                self.state = RecordState::Signature;
+                Ok(RecordRead::Entry(header.into_entry_with_encoding(
+                    data,
+                    self.quirks,
+                    self.encoding,
+                )))
+            }
+            RecordState::End => Ok(RecordRead::End),
+        }
+    }
+}
+
+/// Whether `extra` holds a ZIP64 extended information field (ID `0x0001`)
+pub fn has_zip64_extra(extra: &[u8]) -> bool {
+    extra_fields(extra).any(|(id, _)| id == ZIP64_EXTRA_ID)
+}
+
+/// Decode the WinZip AES extra field (ID `0x9901`) of a local header
+///
+/// Returns the key strength, or `None` if the extra field has no AES
+/// record. The salt and password-verification bytes that precede the
+/// encrypted data are the frontend's to read.
+#[cfg(feature = "encryption")]
+pub fn parse_aes_extra(extra: &[u8]) -> Result<Option<AesStrength>> {
+    let Some(strength_code) = parse_aes_extra_field_buf(extra) else {
+        return Ok(None);
+    };
+    match strength_code {
+        0x01 => Ok(Some(AesStrength::Aes128)),
+        0x02 => Ok(Some(AesStrength::Aes192)),
+        0x03 => Ok(Some(AesStrength::Aes256)),
+        _ => Err(SZipError::InvalidFormat(format!(
+            "Unsupported AES strength: {}",
+            strength_code
+        ))),
+    }
+}
+
+/// A byte range a frontend must read for a [`DirectoryLocator`]
+///
+/// `len` may reach past the end of the source; hand over what was read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReadRequest {
+    pub offset: u64,
+    pub len: u64,
+}
+
+/// Where the central directory is, as found by a [`DirectoryLocator`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocatedDirectory {
+    /// Location of the central directory records
+    pub location: CentralDirectoryLocation,
+    /// Archive comment from the EOCD record
+    pub comment: Vec<u8>,
+    /// Offset of the EOCD record
+    pub eocd_offset: u64,
+    /// Number of the volume holding the EOCD record, the last one of a split
+    /// archive; 0 for single-file archives
+    pub disk: u32,
+    /// Number of the volume where the central directory starts
+    pub cd_disk: u32,
+}
+
+/// State machine locating the central directory of an archive of known size
+///
+/// Call [`next_read`](Self::next_read), read the requested range and pass
+/// the bytes to [`feed`](Self::feed) until no more reads are requested, then
+/// take the result with [`finish`](Self::finish).
+#[derive(Debug)]
+pub struct DirectoryLocator {
+    file_size: u64,
+    /// Start of the volume whose offsets the ZIP64 EOCD locator records
+    record_base: u64,
+    state: LocatorState,
+}
+
+#[derive(Debug)]
+enum LocatorState {
+    /// Needs the end of the file, which holds the EOCD record
+    Tail,
+    /// Needs the wider window searched for the ZIP64 EOCD locator
+    Zip64Locator(LocatedDirectory),
+    /// Needs the ZIP64 EOCD record at the given offset
+    Zip64Record(LocatedDirectory, u64),
+    Done(LocatedDirectory),
+}
+
+impl DirectoryLocator {
+    /// Locator for a source of `file_size` bytes
+    pub fn new(file_size: u64) -> Self {
+        Self::for_last_volume(file_size, 0)
+    }
+
+    /// Locator for the joined volumes of a split archive, the last of which
+    /// starts at `volume_start`
+    ///
+    /// The ZIP64 EOCD record is expected on the last volume, next to its
+    /// locator. The central directory offset in the result is still relative
+    /// to the volume [`cd_disk`](LocatedDirectory::cd_disk).
+    pub fn for_last_volume(file_size: u64, volume_start: u64) -> Self {
+        Self {
+            file_size,
+            record_base: volume_start,
+            state: LocatorState::Tail,
+        }
+    }
+
+    /// Next range to read, or `None` once the directory is located
+    pub fn next_read(&self) -> Option<ReadRequest> {
+        let to_end = |offset: u64| ReadRequest {
+            offset,
+            len: self.file_size - offset,
+        };
+        match &self.state {
+            LocatorState::Tail => Some(to_end(self.tail_start())),
+            LocatorState::Zip64Locator(found) => {
+                Some(to_end(found.eocd_offset.saturating_sub(EOCD_SEARCH_WINDOW)))
+            }
+            LocatorState::Zip64Record(_, offset) => Some(ReadRequest {
+                offset: *offset,
+                len: ZIP64_EOCD_LEN as u64,
+            }),
+            LocatorState::Done(_) => None,
+        }
+    }
+
+    /// Hand over the bytes read for the last [`next_read`](Self::next_read)
+    pub fn feed(&mut self, data: &[u8]) -> Result<()> {
+        let state = std::mem::replace(&mut self.state, LocatorState::Tail);
+        self.state = match state {
+            LocatorState::Tail => {
+                let start = self.tail_start();
+                let eocd_offset = find_eocd_in_buffer(data, start).ok_or_else(|| {
+                    SZipError::InvalidFormat("End of central directory not found".to_string())
+                })?;
+                let (found, zip64) =
+                    parse_eocd(&data[(eocd_offset - start) as usize..], eocd_offset)?;
+                if !zip64 {
+                    LocatorState::Done(found)
+                } else if let Some(record) = find_zip64_eocd_offset(data) {
+                    // The tail is a suffix of the locator search window, and
+                    // the search runs backwards from the end
+                    LocatorState::Zip64Record(found, self.record_base + record)
+                } else if found.eocd_offset.saturating_sub(EOCD_SEARCH_WINDOW) < start {
+                    LocatorState::Zip64Locator(found)
+                } else {
+                    return Err(zip64_locator_missing());
+                }
+            }
+            LocatorState::Zip64Locator(found) => {
+                let record = find_zip64_eocd_offset(data).ok_or_else(zip64_locator_missing)?;
+                LocatorState::Zip64Record(found, self.record_base + record)
+            }
+            LocatorState::Zip64Record(mut found, _) => {
+                found.location = parse_zip64_eocd(data)?;
+                found.disk = u32_at(data, 16);
+                found.cd_disk = u32_at(data, 20);
+                LocatorState::Done(found)
+            }
+            LocatorState::Done(found) => LocatorState::Done(found),
+        };
+        Ok(())
+    }
+
+    /// The located directory; fails if reads are still outstanding
+    pub fn finish(self) -> Result<LocatedDirectory> {
+        match self.state {
+            LocatorState::Done(found) => Ok(found),
+            _ => Err(SZipError::InvalidFormat(
+                "Central directory location is incomplete".to_string(),
+            )),
+        }
+    }
+
+    fn tail_start(&self) -> u64 {
+        self.file_size.saturating_sub(EOCD_SEARCH_WINDOW)
+    }
+}
+
+fn zip64_locator_missing() -> SZipError {
+    SZipError::InvalidFormat("ZIP64 EOCD locator not found".to_string())
+}
+
+/// Decode the EOCD record at the start of `buf`; the flag reports ZIP64
+/// placeholders that the ZIP64 EOCD record must resolve
+pub(crate) fn parse_eocd(buf: &[u8], eocd_offset: u64) -> Result<(LocatedDirectory, bool)> {
+    if buf.len() < EOCD_LEN {
+        return Err(SZipError::InvalidFormat(
+            "Truncated end of central directory record".to_string(),
+        ));
+    }
+    let signature = u32_at(buf, 0);
+    if signature != END_OF_CENTRAL_DIRECTORY_SIGNATURE {
+        return Err(SZipError::InvalidFormat(format!(
+            "Invalid end of central directory signature: 0x{:08x}",
+            signature
+        )));
+    }
+    // These values may be placeholder 0xFFFF/0xFFFFFFFF when ZIP64 is used
+    let total_entries_16 = u16_at(buf, 10);
+    let cd_size_32 = u32_at(buf, 12);
+    let cd_offset_32 = u32_at(buf, 16);
+    // The comment may be truncated; keep what is there
+    let comment_len = u16_at(buf, 20) as usize;
+    let comment = buf[EOCD_LEN..]
+        .get(..comment_len)
+        .unwrap_or(&buf[EOCD_LEN..]);
+
+    let zip64 =
+        total_entries_16 == 0xFFFF || cd_size_32 == 0xFFFFFFFF || cd_offset_32 == 0xFFFFFFFF;
+    let found = LocatedDirectory {
+        location: CentralDirectoryLocation {
+            offset: cd_offset_32 as u64,
+            size: cd_size_32 as u64,
+            count: total_entries_16 as u64,
+        },
+        comment: comment.to_vec(),
+        eocd_offset,
+        disk: u16_at(buf, 4) as u32,
+        cd_disk: u16_at(buf, 6) as u32,
+    };
+    Ok((found, zip64))
+}
+
+/// Decode a ZIP64 EOCD record
+pub(crate) fn parse_zip64_eocd(buf: &[u8]) -> Result<CentralDirectoryLocation> {
+    if buf.len() < ZIP64_EOCD_LEN {
+        return Err(SZipError::InvalidFormat(
+            "Truncated ZIP64 end of central directory record".to_string(),
+        ));
+    }
+    let signature = u32_at(buf, 0);
+    if signature != ZIP64_END_OF_CENTRAL_DIRECTORY_SIGNATURE {
+        return Err(SZipError::InvalidFormat(format!(
+            "Invalid ZIP64 EOCD signature: 0x{:08x}",
+            signature
+        )));
+    }
+    // Record size (8), versions (4), disk numbers (8), then entries on this
+    // disk, total entries, directory size and offset
+    Ok(CentralDirectoryLocation {
+        count: u64_at(buf, 24),
+        size: u64_at(buf, 40),
+        offset: u64_at(buf, 48),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Drive a locator over an in-memory archive, counting the reads
+    fn locate(archive: &[u8]) -> Result<(LocatedDirectory, usize)> {
+        let mut locator = DirectoryLocator::new(archive.len() as u64);
+        let mut reads = 0;
+        while let Some(request) = locator.next_read() {
+            let start = (request.offset as usize).min(archive.len());
+            let end = (start + request.len as usize).min(archive.len());
+            locator.feed(&archive[start..end])?;
+            reads += 1;
+        }
+        Ok((locator.finish()?, reads))
+    }
+
+    fn eocd(count: u16, size: u32, offset: u32, comment: &[u8]) -> Vec<u8> {
+        let mut record = Vec::new();
+        record.extend_from_slice(&END_OF_CENTRAL_DIRECTORY_SIGNATURE.to_le_bytes());
+        record.extend_from_slice(&[0; 4]);
+        record.extend_from_slice(&count.to_le_bytes());
+        record.extend_from_slice(&count.to_le_bytes());
+        record.extend_from_slice(&size.to_le_bytes());
+        record.extend_from_slice(&offset.to_le_bytes());
+        record.extend_from_slice(&(comment.len() as u16).to_le_bytes());
+        record.extend_from_slice(comment);
+        record
+    }
+
+    #[test]
+    fn test_locates_plain_eocd_in_one_read() {
+        let mut archive = vec![0u8; 100];
+        archive.extend(eocd(3, 60, 40, b"hello"));
+        let (found, reads) = locate(&archive).unwrap();
+        assert_eq!(reads, 1);
+        assert_eq!(found.eocd_offset, 100);
+        assert_eq!(found.comment, b"hello");
+        assert_eq!(
+            found.location,
+            CentralDirectoryLocation {
+                offset: 40,
+                size: 60,
+                count: 3
+            }
+        );
+    }
+
+    #[test]
+    fn test_locates_zip64_records() {
+        let mut archive = vec![0u8; 64];
+        let record_offset = archive.len() as u64;
+        archive.extend_from_slice(&ZIP64_END_OF_CENTRAL_DIRECTORY_SIGNATURE.to_le_bytes());
+        archive.extend_from_slice(&44u64.to_le_bytes());
+        archive.extend_from_slice(&[0; 12]);
+        archive.extend_from_slice(&70_000u64.to_le_bytes());
+        archive.extend_from_slice(&70_000u64.to_le_bytes());
+        archive.extend_from_slice(&5_000_000u64.to_le_bytes());
+        archive.extend_from_slice(&6_000_000_000u64.to_le_bytes());
+        // ZIP64 EOCD locator
+        archive.extend_from_slice(&0x07064b50u32.to_le_bytes());
+        archive.extend_from_slice(&0u32.to_le_bytes());
+        archive.extend_from_slice(&record_offset.to_le_bytes());
+        archive.extend_from_slice(&1u32.to_le_bytes());
+        archive.extend(eocd(0xFFFF, 0xFFFFFFFF, 0xFFFFFFFF, b""));
+
+        let (found, reads) = locate(&archive).unwrap();
+        assert_eq!(reads, 2);
+        assert_eq!(
+            found.location,
+            CentralDirectoryLocation {
+                offset: 6_000_000_000,
+                size: 5_000_000,
+                count: 70_000
+            }
+        );
+    }
+
+    #[test]
+    fn test_missing_eocd_is_an_error() {
+        assert!(matches!(
+            locate(&[0u8; 50]),
+            Err(SZipError::InvalidFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_reads_central_records_until_eocd() {
+        let mut writer =
+            crate::StreamingZipWriter::from_writer(std::io::Cursor::new(Vec::new())).unwrap();
+        writer.add_entry("a.txt", b"alpha").unwrap();
+        writer.add_entry("dir/b.txt", b"beta").unwrap();
+        let archive = writer.finish().unwrap().into_inner();
+        let (found, _) = locate(&archive).unwrap();
+
+        let encoding = FilenameEncoding::Auto;
+        let mut records = CentralRecordReader::new(QuirksMode::Strict, &encoding);
+        let mut position = found.location.offset as usize;
+        let mut names = Vec::new();
+        loop {
+            let len = records.next_len();
+            match records.feed(&archive[position..position + len]).unwrap() {
+                RecordRead::More => {}
+                RecordRead::Entry(entry) => names.push(entry.name),
+                RecordRead::End => break,
+            }
+            position += len;
+        }
+        assert_eq!(names, ["a.txt", "dir/b.txt"]);
+        // Stopped at the EOCD signature
+        assert_eq!(position as u64, found.eocd_offset);
+        assert_eq!(records.next_len(), 0);
+    }
+}
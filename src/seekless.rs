@@ -37,6 +37,7 @@
 //! ```
 
 use crate::error::{Result, SZipError};
+use crate::raw;
 use crate::writer::CompressionMethod;
 use crate::EntryOptions;
 use flate2::write::DeflateEncoder;
@@ -51,11 +52,14 @@ struct PreparedEntry {
     compressed_size: u64,
     crc32: u32,
     compression_method: u16,
+    /// General purpose bit flag
+    flags: u16,
     local_header_offset: u64,
     /// MS-DOS mod time / date (0 if not set)
     dos_time: u16,
     dos_date: u16,
-    unix_extra: Vec<u8>,
+    /// Extra fields for the central directory, besides ZIP64
+    central_extra: Vec<u8>,
     external_attrs: u32,
 }
 
@@ -131,25 +135,32 @@ impl<W: AsyncWrite + Unpin> SeeklessZipWriter<W> {
         let compressed_size = compressed.len() as u64;
         let (dos_time, dos_date) = options.msdos_datetime();
         let unix_extra = options.unix_extra_field();
-        let external_attrs = options.external_attrs();
+        let external_attrs = options.external_attrs(name.ends_with('/'));
+        let flags = raw::name_flags(name);
 
         // Record the local-header offset *before* writing
         let local_header_offset = self.bytes_out;
 
         // Build and write local file header + compressed data
         let use_zip64 = uncompressed_size > u32::MAX as u64 || compressed_size > u32::MAX as u64;
-        let extra_field_local = if use_zip64 {
+        let mut extra_field_local = if use_zip64 {
             build_zip64_extra(uncompressed_size, compressed_size)
         } else {
             unix_extra.clone()
         };
+        let timestamp = options.extended_timestamp();
+        extra_field_local.extend_from_slice(&timestamp.to_extra_field(false));
 
-        let version_needed: u16 = if use_zip64 { 45 } else { 20 };
+        let version_needed = if use_zip64 {
+            raw::version_needed(method_u16, false).max(raw::VERSION_ZIP64)
+        } else {
+            raw::version_needed(method_u16, false)
+        };
 
         let mut header = Vec::with_capacity(30 + name_bytes.len() + extra_field_local.len());
         header.extend_from_slice(&0x04034b50u32.to_le_bytes()); // local sig
         header.extend_from_slice(&version_needed.to_le_bytes());
-        header.extend_from_slice(&0u16.to_le_bytes()); // flags
+        header.extend_from_slice(&flags.to_le_bytes()); // flags
         header.extend_from_slice(&method_u16.to_le_bytes());
         header.extend_from_slice(&dos_time.to_le_bytes());
         header.extend_from_slice(&dos_date.to_le_bytes());
@@ -171,11 +182,12 @@ impl<W: AsyncWrite + Unpin> SeeklessZipWriter<W> {
         self.bytes_out += header.len() as u64 + compressed_size;
 
         // Store metadata for central directory
-        let entry_unix_extra = if use_zip64 {
+        let mut central_extra = if use_zip64 {
             unix_extra // put unix extra only in central dir for zip64 entries
         } else {
             Vec::new() // already included in local header
         };
+        central_extra.extend_from_slice(&timestamp.to_extra_field(true));
 
         self.entries.push(PreparedEntry {
             name: name_bytes,
@@ -183,10 +195,11 @@ impl<W: AsyncWrite + Unpin> SeeklessZipWriter<W> {
             compressed_size,
             crc32,
             compression_method: method_u16,
+            flags,
             local_header_offset,
             dos_time,
             dos_date,
-            unix_extra: entry_unix_extra,
+            central_extra,
             external_attrs,
         });
 
@@ -219,13 +232,17 @@ impl<W: AsyncWrite + Unpin> SeeklessZipWriter<W> {
                     );
                 }
                 let mut combined = v;
-                combined.extend_from_slice(&entry.unix_extra);
+                combined.extend_from_slice(&entry.central_extra);
                 combined
             } else {
-                entry.unix_extra.clone()
+                entry.central_extra.clone()
             };
 
-            let version_needed: u16 = if use_zip64 { 45 } else { 20 };
+            let version_needed = if use_zip64 {
+                raw::version_needed(entry.compression_method, false).max(raw::VERSION_ZIP64)
+            } else {
+                raw::version_needed(entry.compression_method, false)
+            };
             let local_offset: u32 = if entry.local_header_offset > u32::MAX as u64 {
                 0xFFFFFFFF
             } else {
@@ -234,9 +251,9 @@ impl<W: AsyncWrite + Unpin> SeeklessZipWriter<W> {
 
             let mut cd = Vec::new();
             cd.extend_from_slice(&0x02014b50u32.to_le_bytes()); // central dir sig
-            cd.extend_from_slice(&version_needed.to_le_bytes()); // version made by
+            cd.extend_from_slice(&raw::version_made_by(entry.external_attrs).to_le_bytes());
             cd.extend_from_slice(&version_needed.to_le_bytes()); // version needed
-            cd.extend_from_slice(&0u16.to_le_bytes()); // flags
+            cd.extend_from_slice(&entry.flags.to_le_bytes()); // flags
             cd.extend_from_slice(&entry.compression_method.to_le_bytes());
             cd.extend_from_slice(&entry.dos_time.to_le_bytes());
             cd.extend_from_slice(&entry.dos_date.to_le_bytes());
@@ -333,6 +350,23 @@ impl<W: AsyncWrite + Unpin> SeeklessZipWriter<W> {
                     zstd::encode_all(data, self.compression_level as i32).map_err(SZipError::Io)?;
                 Ok((compressed, crc32, 93))
             }
+            #[cfg(feature = "bzip2")]
+            CompressionMethod::Bzip2 => {
+                let level = bzip2::Compression::new(self.compression_level.clamp(1, 9));
+                let mut encoder = bzip2::write::BzEncoder::new(Vec::new(), level);
+                encoder.write_all(data).map_err(SZipError::Io)?;
+                let compressed = encoder.finish().map_err(SZipError::Io)?;
+                Ok((compressed, crc32, 12))
+            }
+            #[cfg(feature = "lzma")]
+            method @ (CompressionMethod::Lzma | CompressionMethod::Xz) => {
+                let (header, stream) =
+                    crate::lzma::encoder(method.to_zip_method(), self.compression_level)?;
+                let mut encoder = liblzma::write::XzEncoder::new_stream(header, stream);
+                encoder.write_all(data).map_err(SZipError::Io)?;
+                let compressed = encoder.finish().map_err(SZipError::Io)?;
+                Ok((compressed, crc32, method.to_zip_method()))
+            }
         }
     }
 }
@@ -0,0 +1,193 @@
+//! Self-extracting archives.
+//!
+//! A self-extractor is an ordinary ZIP archive preceded by an executable
+//! stub. [`SfxBuilder`] writes the stub and hands back a streaming writer
+//! whose offsets already account for it, or prepends a stub to an existing
+//! archive and rewrites its central directory. Readers locate the archive
+//! from its end, so the result still opens with `StreamingZipReader`,
+//! `unzip` and Windows Explorer.
+//!
+//! A shell stub for Unix-like systems is bundled ([`SfxBuilder::unix_shell`]);
+//! Windows `.exe` self-extractors need a PE stub such as Info-ZIP's
+//! `unzipsfx.exe`, passed to [`SfxBuilder::new`] or
+//! [`SfxBuilder::from_stub_file`].
+//!
+//! ```no_run
+//! use s_zip::sfx::SfxBuilder;
+//!
+//! let mut writer = SfxBuilder::unix_shell().create("installer.run")?;
+//! writer.add_entry("install.sh", b"#!/bin/sh\necho installed\n")?;
+//! writer.finish()?;
+//! // ./installer.run /opt/app
+//! # Ok::<(), s_zip::SZipError>(())
+//! ```
+
+use crate::error::{Result, SZipError};
+use crate::format::extra_fields;
+use crate::raw::{self, CentralDirectoryRecord, EndOfCentralDirectory};
+use crate::reader::StreamingZipReader;
+use crate::sansio::{CentralHeader, CENTRAL_HEADER_LEN, ZIP64_EXTRA_ID};
+use crate::writer::StreamingZipWriter;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+/// Stub for Unix-like systems: extracts next to the working directory, or
+/// into the directory given as the first argument, using `unzip`
+const UNIX_SHELL_STUB: &[u8] = b"#!/bin/sh\n\
+# Self-extracting ZIP archive. Usage: $0 [destination]\n\
+exec unzip -o \"$0\" -d \"${1:-.}\"\n";
+
+/// Builds self-extracting archives from an executable stub
+#[derive(Debug, Clone)]
+pub struct SfxBuilder {
+    stub: Vec<u8>,
+}
+
+impl SfxBuilder {
+    /// Use `stub` as the executable prefix
+    pub fn new(stub: impl Into<Vec<u8>>) -> Self {
+        Self { stub: stub.into() }
+    }
+
+    /// Load the executable prefix from a file
+    pub fn from_stub_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(Self::new(std::fs::read(path)?))
+    }
+
+    /// The bundled `/bin/sh` stub, which extracts with `unzip`
+    pub fn unix_shell() -> Self {
+        Self::new(UNIX_SHELL_STUB)
+    }
+
+    /// The stub bytes
+    pub fn stub(&self) -> &[u8] {
+        &self.stub
+    }
+
+    /// Create a self-extractor at `path` and return a writer for its entries
+    ///
+    /// On Unix the file is made executable.
+    pub fn create<P: AsRef<Path>>(&self, path: P) -> Result<StreamingZipWriter<File>> {
+        let file = File::create(path)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            file.set_permissions(std::fs::Permissions::from_mode(0o755))?;
+        }
+        self.writer(file)
+    }
+
+    /// Write the stub to `output` and return a writer for the archive after it
+    ///
+    /// The writer records absolute positions, so no offsets need fixing up.
+    pub fn writer<W: Write + Seek>(&self, mut output: W) -> Result<StreamingZipWriter<W>> {
+        output.write_all(&self.stub)?;
+        StreamingZipWriter::from_writer(output)
+    }
+
+    /// Write the stub to `output` and return an async writer for the archive
+    /// after it, with offsets starting past the stub
+    #[cfg(feature = "async")]
+    pub async fn async_writer<W>(&self, mut output: W) -> Result<crate::AsyncStreamingZipWriter<W>>
+    where
+        W: tokio::io::AsyncWrite + Unpin,
+    {
+        use tokio::io::AsyncWriteExt;
+        output.write_all(&self.stub).await?;
+        Ok(crate::AsyncStreamingZipWriter::from_writer(output)
+            .with_start_offset(self.stub.len() as u64))
+    }
+
+    /// Copy an existing archive to `output` behind the stub, shifting every
+    /// local header offset in the central directory and the end records by
+    /// the stub length. Returns the number of bytes written.
+    pub fn wrap_archive<R: Read + Seek, W: Write>(
+        &self,
+        mut archive: R,
+        mut output: W,
+    ) -> Result<u64> {
+        let (location, comment) = StreamingZipReader::read_eocd(&mut archive)?;
+        let shift = self.stub.len() as u64;
+
+        output.write_all(&self.stub)?;
+        archive.seek(SeekFrom::Start(0))?;
+        io::copy(&mut (&mut archive).take(location.offset), &mut output)?;
+
+        let mut central = vec![0u8; location.size as usize];
+        archive.seek(SeekFrom::Start(location.offset))?;
+        archive.read_exact(&mut central)?;
+        let mut cd_size = 0;
+        let mut pos = 0;
+        for _ in 0..location.count {
+            let (len, record, extra) = parse_central_record(&central[pos..])?;
+            cd_size += raw::write_central_record(
+                &mut output,
+                &CentralDirectoryRecord {
+                    local_header_offset: record.local_header_offset + shift,
+                    extra: &extra,
+                    ..record
+                },
+            )?;
+            pos += len;
+        }
+        let eocd_len = raw::write_eocd(
+            &mut output,
+            &EndOfCentralDirectory {
+                entry_count: location.count,
+                cd_size,
+                cd_offset: shift + location.offset,
+                comment: &comment,
+                ..Default::default()
+            },
+        )?;
+        output.flush()?;
+        Ok(shift + location.offset + cd_size + eocd_len)
+    }
+}
+
+/// Parse one central directory record, resolving ZIP64 values. The ZIP64
+/// extra field is left out of the returned extra data, since the record
+/// writer adds its own when needed.
+fn parse_central_record(buf: &[u8]) -> Result<(usize, CentralDirectoryRecord<'_>, Vec<u8>)> {
+    let header = buf
+        .get(..CENTRAL_HEADER_LEN)
+        .and_then(|fixed| CentralHeader::parse(fixed.try_into().unwrap()))
+        .ok_or_else(|| {
+            SZipError::InvalidFormat("Invalid central directory record signature".to_string())
+        })?;
+    let len = CENTRAL_HEADER_LEN + header.variable_len();
+    let variable = buf
+        .get(CENTRAL_HEADER_LEN..len)
+        .ok_or_else(|| SZipError::InvalidFormat("Truncated central directory".to_string()))?;
+    let (name, rest) = variable.split_at(header.name_len as usize);
+    let (extra, comment) = rest.split_at(header.extra_len as usize);
+
+    let (uncompressed_size, compressed_size, local_header_offset) = header.zip64_values(extra);
+    let mut kept_extra = Vec::with_capacity(extra.len());
+    for (id, data) in extra_fields(extra).filter(|&(id, _)| id != ZIP64_EXTRA_ID) {
+        kept_extra.extend_from_slice(&id.to_le_bytes());
+        kept_extra.extend_from_slice(&(data.len() as u16).to_le_bytes());
+        kept_extra.extend_from_slice(data);
+    }
+
+    let record = CentralDirectoryRecord {
+        version_made_by: header.version_made_by,
+        version_needed: header.version_needed,
+        flags: header.flags,
+        compression_method: header.compression_method,
+        last_mod_time: header.last_modified.time,
+        last_mod_date: header.last_modified.date,
+        crc32: header.crc32,
+        compressed_size,
+        uncompressed_size,
+        internal_attrs: header.internal_attrs,
+        external_attrs: header.external_attrs,
+        local_header_offset,
+        disk_number_start: header.disk_start as u32,
+        name,
+        extra: &[],
+        comment,
+    };
+    Ok((len, record, kept_extra))
+}
@@ -0,0 +1,181 @@
+//! Signed archives for software distribution.
+//!
+//! With a [`Signer`] registered through `sign_with`, `StreamingZipWriter`
+//! hashes every entry with SHA-256 as it is written and, on `finish()`,
+//! stores a signed [`Manifest`] of the digests either as the reserved entry
+//! [`SIGNATURE_ENTRY`] or as the archive comment. `verify_signature()` on
+//! `StreamingZipReader` checks the signature with a [`SignatureVerifier`]
+//! and then re-hashes every entry against the manifest, so added, removed,
+//! renamed or modified entries are all caught, as are changed external
+//! attributes such as the Unix mode. Other metadata (timestamps, extra
+//! fields, comments) is not covered by the signature.
+//!
+//! The signature algorithm is pluggable: implement the two traits over
+//! ed25519, RSA or an HSM/KMS client. The manifest and signature are plain
+//! text, so they can also be inspected with `unzip -p` or `unzip -z`.
+//!
+//! ```
+//! use ed25519_dalek::{Signature, SignatureError, Signer as _, SigningKey};
+//! use ed25519_dalek::{Verifier as _, VerifyingKey};
+//! use s_zip::signing::{SignaturePlacement, SignatureVerifier, Signer};
+//! use s_zip::{Result, SZipError, StreamingZipReader, StreamingZipWriter};
+//! use std::io::Cursor;
+//!
+//! struct Ed25519Signer(SigningKey);
+//!
+//! impl Signer for Ed25519Signer {
+//!     fn algorithm(&self) -> &str {
+//!         "ed25519"
+//!     }
+//!
+//!     fn sign(&self, manifest: &[u8]) -> Result<Vec<u8>> {
+//!         Ok(self.0.sign(manifest).to_vec())
+//!     }
+//! }
+//!
+//! struct Ed25519Verifier(VerifyingKey);
+//!
+//! impl SignatureVerifier for Ed25519Verifier {
+//!     fn algorithm(&self) -> &str {
+//!         "ed25519"
+//!     }
+//!
+//!     fn verify(&self, manifest: &[u8], signature: &[u8]) -> Result<()> {
+//!         let invalid = |e: SignatureError| SZipError::SignatureInvalid(e.to_string());
+//!         let signature = Signature::from_slice(signature).map_err(invalid)?;
+//!         self.0.verify(manifest, &signature).map_err(invalid)
+//!     }
+//! }
+//!
+//! let key = SigningKey::from_bytes(&[7; 32]);
+//! let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new()))?;
+//! writer.sign_with(Ed25519Signer(key.clone()), SignaturePlacement::Entry);
+//! writer.add_entry("bin/tool", b"\x7fELF...")?;
+//! let zip = writer.finish()?.into_inner();
+//!
+//! let mut reader = StreamingZipReader::from_vec(zip)?;
+//! reader.verify_signature(&Ed25519Verifier(key.verifying_key()))?;
+//! # Ok::<(), SZipError>(())
+//! ```
+
+use crate::error::{Result, SZipError};
+use crate::manifest::{self, from_hex, to_hex, Manifest};
+use crate::reader::StreamingZipReader;
+use std::io::{Read, Seek};
+
+/// Name of the entry holding the signed manifest with [`SignaturePlacement::Entry`]
+pub const SIGNATURE_ENTRY: &str = "META-INF/S-ZIP.SIG";
+
+/// First line of a signature block
+const SIGNATURE_MAGIC: &str = "S-ZIP-SIGNATURE 1";
+
+/// Produces signatures over the manifest of a signed archive
+pub trait Signer: Send {
+    /// Algorithm name recorded next to the signature, e.g. `ed25519`
+    fn algorithm(&self) -> &str;
+
+    /// Sign the manifest bytes
+    fn sign(&self, manifest: &[u8]) -> Result<Vec<u8>>;
+}
+
+/// Checks signatures produced by a [`Signer`]
+pub trait SignatureVerifier {
+    /// Algorithm name this verifier accepts
+    fn algorithm(&self) -> &str;
+
+    /// Check `signature` over the manifest bytes, returning
+    /// `SZipError::SignatureInvalid` if it does not match
+    fn verify(&self, manifest: &[u8], signature: &[u8]) -> Result<()>;
+}
+
+/// Where the writer stores the signed manifest
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SignaturePlacement {
+    /// As the last entry, named [`SIGNATURE_ENTRY`]
+    #[default]
+    Entry,
+    /// As the archive comment, which leaves the entry list untouched but is
+    /// limited to 64 KiB, roughly 600 entries
+    Comment,
+}
+
+/// Sign `manifest` (in text form) and build the signature block
+pub(crate) fn seal(signer: &dyn Signer, manifest: &[u8]) -> Result<Vec<u8>> {
+    let signature = signer.sign(manifest)?;
+    let mut block = format!(
+        "{}\nalgorithm: {}\nsignature: {}\n\n",
+        SIGNATURE_MAGIC,
+        signer.algorithm(),
+        to_hex(&signature)
+    )
+    .into_bytes();
+    block.extend_from_slice(manifest);
+    Ok(block)
+}
+
+/// Check the signature block of `reader` and every entry against its manifest
+pub(crate) fn verify_archive<R: Read + Seek>(
+    reader: &mut StreamingZipReader<R>,
+    verifier: &dyn SignatureVerifier,
+) -> Result<()> {
+    let invalid = |msg: &str| SZipError::SignatureInvalid(msg.to_string());
+
+    let sealed = reader
+        .entries()
+        .iter()
+        .filter(|e| e.name == SIGNATURE_ENTRY)
+        .count();
+    if sealed > 1 {
+        return Err(SZipError::SignatureInvalid(format!(
+            "Archive has {} '{}' entries",
+            sealed, SIGNATURE_ENTRY
+        )));
+    }
+    let block = if sealed == 1 {
+        reader.read_entry_by_name(SIGNATURE_ENTRY)?
+    } else if reader
+        .archive_comment()
+        .starts_with(SIGNATURE_MAGIC.as_bytes())
+    {
+        reader.archive_comment().to_vec()
+    } else {
+        return Err(invalid("Archive is not signed"));
+    };
+    let block = std::str::from_utf8(&block).map_err(|_| invalid("Malformed signature block"))?;
+
+    let (header, text) = block
+        .split_once("\n\n")
+        .ok_or_else(|| invalid("Malformed signature block"))?;
+    let mut lines = header.lines();
+    let algorithm = match (lines.next(), lines.next()) {
+        (Some(SIGNATURE_MAGIC), Some(line)) => line.strip_prefix("algorithm: "),
+        _ => None,
+    };
+    let signature = lines.next().and_then(|l| l.strip_prefix("signature: "));
+    let (Some(algorithm), Some(signature)) = (algorithm, signature.and_then(from_hex)) else {
+        return Err(invalid("Malformed signature block"));
+    };
+    if algorithm != verifier.algorithm() {
+        return Err(SZipError::SignatureInvalid(format!(
+            "Archive is signed with {}, verifier expects {}",
+            algorithm,
+            verifier.algorithm()
+        )));
+    }
+    verifier.verify(text.as_bytes(), &signature)?;
+
+    // The signature is good; now hold the entries to the manifest
+    let manifest =
+        Manifest::parse(text.as_bytes()).map_err(|_| invalid("Malformed signed manifest"))?;
+    let report = manifest::check_entries(reader, &manifest, &[SIGNATURE_ENTRY])?;
+    let problem = if let Some(name) = report.modified.first() {
+        format!("Entry '{}' was modified after signing", name)
+    } else if let Some(name) = report.missing.first() {
+        format!("Signed entry '{}' is missing", name)
+    } else if let Some(name) = report.unlisted.first() {
+        format!("Entry '{}' is not signed", name)
+    } else {
+        return Ok(());
+    };
+    Err(SZipError::SignatureInvalid(problem))
+}
@@ -0,0 +1,377 @@
+//! Archives split across several volumes (`.z01`, `.z02`, ..., `.zip`).
+//!
+//! Split archives are written in volumes of a maximum size, for media or
+//! upload targets that limit the file size. Offsets in the central directory
+//! count from the start of the volume named by the entry's disk number, and
+//! the central directory itself may start on an earlier volume than the
+//! last one, which holds the end of central directory record.
+//!
+//! [`SplitSource`] joins the volumes into one seekable stream, and
+//! [`StreamingZipReader::from_split`](crate::StreamingZipReader::from_split)
+//! reads the archive from it, turning per-volume offsets into offsets in the
+//! joined stream.
+//!
+//! [`StreamingZipWriter::new_split`](crate::StreamingZipWriter::new_split)
+//! writes such an archive through a [`SplitOutput`], starting a new volume
+//! whenever the current one reaches the maximum size. Headers and central
+//! directory records are kept within one volume, and the end of central
+//! directory record is always on the last volume. The first volume starts
+//! with the spanning signature (APPNOTE 8.5.3), or with the `PK00` marker
+//! if the archive fit in a single volume after all (8.5.4).
+//!
+//! ```no_run
+//! use s_zip::StreamingZipWriter;
+//!
+//! // backup.z01, backup.z02, ... of at most 100 MB each, then backup.zip
+//! let mut writer = StreamingZipWriter::new_split("backup.zip", 100_000_000)?;
+//! writer.add_entry("data.bin", &vec![0u8; 1 << 20])?;
+//! writer.finish()?;
+//! # Ok::<(), s_zip::SZipError>(())
+//! ```
+//!
+//! ```no_run
+//! use s_zip::StreamingZipReader;
+//!
+//! // Finds backup.z01, backup.z02, ... next to the last volume
+//! let mut reader = StreamingZipReader::open_split("backup.zip")?;
+//! for entry in reader.entries() {
+//!     println!("{} (volume {})", entry.name, entry.disk_number + 1);
+//! }
+//! # Ok::<(), s_zip::SZipError>(())
+//! ```
+
+use crate::error::{Result, SZipError};
+use crate::sansio::{DirectoryLocator, LocatedDirectory};
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// Volumes of a split archive, read as one stream
+pub struct SplitSource<R> {
+    volumes: Vec<R>,
+    /// Offset of each volume in the joined stream, then the total length
+    starts: Vec<u64>,
+    pos: u64,
+    /// Volume positioned at `pos`, if any
+    current: Option<usize>,
+}
+
+impl<R: Read + Seek> SplitSource<R> {
+    /// Join `volumes`, given in order from the first to the last
+    pub fn from_volumes(mut volumes: Vec<R>) -> Result<Self> {
+        if volumes.is_empty() {
+            return Err(SZipError::InvalidFormat(
+                "A split archive needs at least one volume".to_string(),
+            ));
+        }
+        let mut starts = Vec::with_capacity(volumes.len() + 1);
+        let mut total = 0u64;
+        for volume in &mut volumes {
+            starts.push(total);
+            total += volume.seek(SeekFrom::End(0))?;
+        }
+        starts.push(total);
+        Ok(SplitSource {
+            volumes,
+            starts,
+            pos: 0,
+            current: None,
+        })
+    }
+
+    /// Join the volumes of the archive whose last volume is `last`, asking
+    /// `resolve` for each earlier volume by number (0 for the first)
+    ///
+    /// The number of volumes comes from the end of central directory record
+    /// in `last`.
+    pub fn resolve<F>(mut last: R, mut resolve: F) -> Result<Self>
+    where
+        F: FnMut(u32) -> io::Result<R>,
+    {
+        let len = last.seek(SeekFrom::End(0))?;
+        let found = locate(&mut last, DirectoryLocator::new(len))?;
+        let mut volumes = (0..found.disk)
+            .map(&mut resolve)
+            .collect::<io::Result<Vec<_>>>()?;
+        volumes.push(last);
+        Self::from_volumes(volumes)
+    }
+
+    /// Number of volumes
+    pub fn volume_count(&self) -> usize {
+        self.volumes.len()
+    }
+
+    /// Offset in the joined stream where volume `disk` starts
+    pub fn volume_start(&self, disk: u32) -> Option<u64> {
+        self.starts[..self.volumes.len()]
+            .get(disk as usize)
+            .copied()
+    }
+
+    fn len(&self) -> u64 {
+        self.starts[self.volumes.len()]
+    }
+}
+
+impl SplitSource<BufReader<File>> {
+    /// Open the volume files at `paths`, in order from the first to the last
+    pub fn open<P: AsRef<Path>>(paths: &[P]) -> Result<Self> {
+        let volumes = paths
+            .iter()
+            .map(|path| Ok(BufReader::with_capacity(512 * 1024, File::open(path)?)))
+            .collect::<io::Result<Vec<_>>>()?;
+        Self::from_volumes(volumes)
+    }
+
+    /// Open the archive whose last volume is `path`, with the earlier volumes
+    /// named after it: `archive.z01`, `archive.z02`, ... for `archive.zip`
+    pub fn open_last<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let last = BufReader::with_capacity(512 * 1024, File::open(path)?);
+        Self::resolve(last, |disk| {
+            let volume = volume_path(path, disk);
+            Ok(BufReader::with_capacity(512 * 1024, File::open(volume)?))
+        })
+    }
+}
+
+/// Path of volume `disk` of the split archive whose last volume is `last`
+pub fn volume_path(last: &Path, disk: u32) -> PathBuf {
+    last.with_extension(format!("z{:02}", disk + 1))
+}
+
+impl<R: Read + Seek> Read for SplitSource<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pos >= self.len() || buf.is_empty() {
+            return Ok(0);
+        }
+        // Last volume starting at or before `pos`; empty volumes are skipped
+        let index = self.starts[..self.volumes.len()].partition_point(|&s| s <= self.pos) - 1;
+        let start = self.starts[index];
+        if self.current != Some(index) {
+            self.volumes[index].seek(SeekFrom::Start(self.pos - start))?;
+            self.current = Some(index);
+        }
+        let in_volume = self.starts[index + 1] - self.pos;
+        let max = buf.len().min(in_volume.min(usize::MAX as u64) as usize);
+        let n = self.volumes[index].read(&mut buf[..max])?;
+        if n == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                format!("Volume {} ended early", index + 1),
+            ));
+        }
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+impl<R: Read + Seek> Seek for SplitSource<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(n) => Some(n),
+            SeekFrom::End(n) => self.len().checked_add_signed(n),
+            SeekFrom::Current(n) => self.pos.checked_add_signed(n),
+        }
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )
+        })?;
+        if target != self.pos {
+            self.pos = target;
+            self.current = None;
+        }
+        Ok(target)
+    }
+}
+
+/// Smallest volume size accepted by [`SplitOutput`], as in Info-ZIP `zip -s`
+pub const MIN_VOLUME_SIZE: u64 = 64 * 1024;
+
+/// Spanning signature at the start of the first volume
+pub const SPLIT_SIGNATURE: u32 = 0x0807_4b50;
+
+/// Marker replacing [`SPLIT_SIGNATURE`] when the archive needed one volume
+pub const SINGLE_VOLUME_MARKER: u32 = 0x3030_4b50;
+
+/// Volume files of a split archive being written, as one stream
+///
+/// Volumes are written as `archive.z01`, `archive.z02`, ... and the last one
+/// is renamed to the archive path once the archive is finished. Use it
+/// through [`StreamingZipWriter::new_split`](crate::StreamingZipWriter::new_split),
+/// which also writes the volume numbers into the archive records.
+pub struct SplitOutput {
+    path: PathBuf,
+    max_volume_size: u64,
+    volumes: Vec<File>,
+    /// Offset of each volume in the joined stream
+    starts: Vec<u64>,
+    pos: u64,
+    len: u64,
+    /// Volume positioned at `pos`, if any
+    current: Option<usize>,
+}
+
+impl SplitOutput {
+    /// Start the first volume of the archive at `path`
+    pub fn create<P: AsRef<Path>>(path: P, max_volume_size: u64) -> Result<Self> {
+        if max_volume_size < MIN_VOLUME_SIZE {
+            return Err(SZipError::InvalidFormat(format!(
+                "Volume size {} is below the minimum of {} bytes",
+                max_volume_size, MIN_VOLUME_SIZE
+            )));
+        }
+        let path = path.as_ref().to_path_buf();
+        let mut first = File::create(volume_path(&path, 0))?;
+        // Offsets within the first volume count from before the signature
+        first.write_all(&SPLIT_SIGNATURE.to_le_bytes())?;
+        Ok(SplitOutput {
+            path,
+            max_volume_size,
+            volumes: vec![first],
+            starts: vec![0],
+            pos: 4,
+            len: 4,
+            current: Some(0),
+        })
+    }
+
+    /// Number of volumes written so far
+    pub fn volume_count(&self) -> usize {
+        self.volumes.len()
+    }
+
+    /// Paths of the volumes, in order; the last is the archive path once
+    /// the archive is finished
+    pub fn volume_paths(&self) -> Vec<PathBuf> {
+        let last = self.volumes.len() - 1;
+        (0..last as u32)
+            .map(|disk| volume_path(&self.path, disk))
+            .chain(std::iter::once(self.path.clone()))
+            .collect()
+    }
+
+    fn start_volume(&mut self) -> io::Result<()> {
+        let file = File::create(volume_path(&self.path, self.volumes.len() as u32))?;
+        self.volumes.push(file);
+        self.starts.push(self.len);
+        self.current = Some(self.volumes.len() - 1);
+        Ok(())
+    }
+}
+
+impl Write for SplitOutput {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        if self.pos > self.len {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "cannot write past the end of a split archive",
+            ));
+        }
+        let mut index = self.starts.partition_point(|&s| s <= self.pos) - 1;
+        let volume_end = |index: usize| self.starts[index] + self.max_volume_size;
+        if self.pos == volume_end(index) {
+            // Only the last volume can be full with more to write
+            self.start_volume()?;
+            index += 1;
+        }
+        let start = self.starts[index];
+        if self.current != Some(index) {
+            self.volumes[index].seek(SeekFrom::Start(self.pos - start))?;
+            self.current = Some(index);
+        }
+        let limit = match self.starts.get(index + 1) {
+            Some(&next) => next,
+            None => start + self.max_volume_size,
+        };
+        let max = buf
+            .len()
+            .min((limit - self.pos).min(usize::MAX as u64) as usize);
+        let n = self.volumes[index].write(&buf[..max])?;
+        self.pos += n as u64;
+        self.len = self.len.max(self.pos);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.volumes
+            .iter_mut()
+            .try_for_each(|volume| volume.flush())
+    }
+}
+
+impl Seek for SplitOutput {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(n) => Some(n),
+            SeekFrom::End(n) => self.len.checked_add_signed(n),
+            SeekFrom::Current(n) => self.pos.checked_add_signed(n),
+        }
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )
+        })?;
+        if target != self.pos {
+            self.pos = target;
+            self.current = None;
+        }
+        Ok(target)
+    }
+}
+
+/// Volume bookkeeping the writer needs from a split output
+pub(crate) trait Volumes {
+    /// Start a new volume unless `len` more bytes fit in the current one
+    fn reserve(&mut self, len: u64) -> io::Result<()>;
+    /// Volume holding `offset`, and the offset within that volume
+    fn locate(&self, offset: u64) -> (u32, u64);
+    /// Give the last volume the archive path
+    fn finish(&mut self) -> io::Result<()>;
+}
+
+impl Volumes for SplitOutput {
+    fn reserve(&mut self, len: u64) -> io::Result<()> {
+        let used = self.len - self.starts[self.volumes.len() - 1];
+        if self.pos == self.len && used > 0 && used + len > self.max_volume_size {
+            self.start_volume()?;
+        }
+        Ok(())
+    }
+
+    fn locate(&self, offset: u64) -> (u32, u64) {
+        let index = self.starts.partition_point(|&s| s <= offset) - 1;
+        (index as u32, offset - self.starts[index])
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        if self.volumes.len() == 1 {
+            self.seek(SeekFrom::Start(0))?;
+            self.write_all(&SINGLE_VOLUME_MARKER.to_le_bytes())?;
+        }
+        self.flush()?;
+        let last = volume_path(&self.path, self.volumes.len() as u32 - 1);
+        std::fs::rename(last, &self.path)
+    }
+}
+
+/// Drive `locator` over `file`
+pub(crate) fn locate<S: Read + Seek>(
+    file: &mut S,
+    mut locator: DirectoryLocator,
+) -> Result<LocatedDirectory> {
+    while let Some(request) = locator.next_read() {
+        file.seek(SeekFrom::Start(request.offset))?;
+        let mut buf = Vec::new();
+        file.by_ref().take(request.len).read_to_end(&mut buf)?;
+        locator.feed(&buf)?;
+    }
+    locator.finish()
+}
@@ -0,0 +1,245 @@
+//! Bandwidth limiting for archive output.
+//!
+//! [`ThrottledWriter`] wraps any `Write` (or, with the `async` feature,
+//! `AsyncWrite`) target and caps the bytes written per second with a token
+//! bucket, so a large export to local disk, a socket or a cloud writer does
+//! not starve other users of shared infrastructure. Seeking is passed
+//! through untouched, so the wrapper works with both the seekable and the
+//! streaming writers.
+//!
+//! ```no_run
+//! use s_zip::throttle::ThrottledWriter;
+//! use s_zip::StreamingZipWriter;
+//! use std::fs::File;
+//!
+//! // At most 10 MB/s to disk
+//! let file = ThrottledWriter::new(File::create("export.zip")?, 10 * 1024 * 1024);
+//! let mut writer = StreamingZipWriter::from_writer(file)?;
+//! writer.add_entry("data.csv", b"a,b,c")?;
+//! writer.finish()?;
+//! # Ok::<(), s_zip::SZipError>(())
+//! ```
+
+use std::io::{self, Seek, SeekFrom, Write};
+use std::time::{Duration, Instant};
+
+/// Token bucket refilled at `rate` bytes per second, holding at most `burst`
+#[derive(Debug, Clone)]
+struct TokenBucket {
+    rate: u64,
+    burst: u64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: u64, burst: u64) -> Self {
+        assert!(rate > 0, "rate must be at least 1 byte per second");
+        let burst = burst.max(1);
+        Self {
+            rate,
+            burst,
+            tokens: burst as f64,
+            last_refill: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate as f64).min(self.burst as f64);
+        self.last_refill = now;
+    }
+
+    /// Bytes of a `len`-byte write that may go out now, or how long to wait
+    /// before at least one write of `min(len, burst)` bytes fits
+    fn take(&mut self, len: usize) -> std::result::Result<usize, Duration> {
+        self.refill();
+        let want = (len as u64).min(self.burst).max(1);
+        if self.tokens >= want as f64 {
+            self.tokens -= want as f64;
+            Ok(want as usize)
+        } else {
+            let missing = want as f64 - self.tokens;
+            Err(Duration::from_secs_f64(missing / self.rate as f64))
+        }
+    }
+
+    /// Return tokens for bytes that were taken but not written
+    fn refund(&mut self, unused: usize) {
+        self.tokens = (self.tokens + unused as f64).min(self.burst as f64);
+    }
+}
+
+/// Writer adapter limiting throughput to a fixed number of bytes per second
+///
+/// Writes larger than the burst size are split, so the rate holds even for
+/// callers that hand over whole entries at once.
+pub struct ThrottledWriter<W> {
+    inner: W,
+    bucket: TokenBucket,
+    #[cfg(feature = "async")]
+    sleep: Option<std::pin::Pin<Box<tokio::time::Sleep>>>,
+}
+
+impl<W> ThrottledWriter<W> {
+    /// Limit `inner` to `bytes_per_sec`, allowing bursts of up to one
+    /// second's worth of data
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes_per_sec` is 0.
+    pub fn new(inner: W, bytes_per_sec: u64) -> Self {
+        Self::with_burst(inner, bytes_per_sec, bytes_per_sec)
+    }
+
+    /// Limit `inner` to `bytes_per_sec` with an explicit bucket size
+    ///
+    /// A smaller `burst` smooths the output at the cost of more, smaller
+    /// writes to `inner`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes_per_sec` is 0.
+    pub fn with_burst(inner: W, bytes_per_sec: u64, burst: u64) -> Self {
+        Self {
+            inner,
+            bucket: TokenBucket::new(bytes_per_sec, burst),
+            #[cfg(feature = "async")]
+            sleep: None,
+        }
+    }
+
+    /// Configured rate in bytes per second
+    pub fn rate(&self) -> u64 {
+        self.bucket.rate
+    }
+
+    /// Get a reference to the wrapped writer
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the wrapped writer
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Unwrap, returning the inner writer
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for ThrottledWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let allowed = loop {
+            match self.bucket.take(buf.len()) {
+                Ok(allowed) => break allowed,
+                Err(wait) => std::thread::sleep(wait),
+            }
+        };
+        let written = self.inner.write(&buf[..allowed])?;
+        self.bucket.refund(allowed - written);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<W: Seek> Seek for ThrottledWriter<W> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+#[cfg(feature = "async")]
+mod async_impl {
+    use super::ThrottledWriter;
+    use std::future::Future;
+    use std::io::{self, SeekFrom};
+    use std::pin::Pin;
+    use std::task::{ready, Context, Poll};
+    use tokio::io::{AsyncSeek, AsyncWrite};
+
+    impl<W: AsyncWrite + Unpin> AsyncWrite for ThrottledWriter<W> {
+        fn poll_write(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            if buf.is_empty() {
+                return Poll::Ready(Ok(0));
+            }
+            let this = &mut *self;
+            let allowed = loop {
+                if let Some(sleep) = this.sleep.as_mut() {
+                    ready!(sleep.as_mut().poll(cx));
+                    this.sleep = None;
+                }
+                match this.bucket.take(buf.len()) {
+                    Ok(allowed) => break allowed,
+                    Err(wait) => this.sleep = Some(Box::pin(tokio::time::sleep(wait))),
+                }
+            };
+            match Pin::new(&mut this.inner).poll_write(cx, &buf[..allowed]) {
+                Poll::Ready(Ok(written)) => {
+                    this.bucket.refund(allowed - written);
+                    Poll::Ready(Ok(written))
+                }
+                other => {
+                    // Nothing was written; the caller will retry
+                    this.bucket.refund(allowed);
+                    other
+                }
+            }
+        }
+
+        fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Pin::new(&mut self.inner).poll_flush(cx)
+        }
+
+        fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Pin::new(&mut self.inner).poll_shutdown(cx)
+        }
+    }
+
+    impl<W: AsyncSeek + Unpin> AsyncSeek for ThrottledWriter<W> {
+        fn start_seek(mut self: Pin<&mut Self>, position: SeekFrom) -> io::Result<()> {
+            Pin::new(&mut self.inner).start_seek(position)
+        }
+
+        fn poll_complete(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<u64>> {
+            Pin::new(&mut self.inner).poll_complete(cx)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_writes_are_split_at_burst_size() {
+        let mut writer = ThrottledWriter::with_burst(Vec::new(), 1 << 30, 4);
+        assert_eq!(writer.write(b"abcdefgh").unwrap(), 4);
+        writer.write_all(b"ijkl").unwrap();
+        assert_eq!(writer.into_inner(), b"abcdijkl");
+    }
+
+    #[test]
+    fn test_rate_is_enforced() {
+        // 1000 bytes at 4000 B/s with a 100-byte bucket: ~225 ms after the
+        // initial burst
+        let start = Instant::now();
+        let mut writer = ThrottledWriter::with_burst(Vec::new(), 4000, 100);
+        writer.write_all(&[0u8; 1000]).unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(200));
+        assert_eq!(writer.get_ref().len(), 1000);
+    }
+}
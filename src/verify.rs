@@ -0,0 +1,257 @@
+//! Archive verification reports.
+//!
+//! `verify()` on `StreamingZipReader` and `GenericAsyncZipReader` is the
+//! library equivalent of `unzip -t`: every entry's local header is compared
+//! against the central directory and its data is decompressed to a sink so
+//! the CRC-32 and size can be checked.  `quick_check()` does only the header
+//! comparison, reading a few bytes per entry, which is enough to catch
+//! truncation and most corruption on very large archives.  Problems are
+//! collected per entry instead of aborting on the first one.
+//!
+//! ```no_run
+//! use s_zip::StreamingZipReader;
+//!
+//! let mut reader = StreamingZipReader::open("archive.zip")?;
+//! let report = reader.verify()?;
+//! for entry in report.failures() {
+//!     for problem in &entry.problems {
+//!         eprintln!("{}: {}", entry.name, problem);
+//!     }
+//! }
+//! # Ok::<(), s_zip::SZipError>(())
+//! ```
+
+use crate::encoding::FilenameEncoding;
+use crate::error::Result;
+use crate::format::ZipEntry;
+use crate::sansio::{LocalHeader, LOCAL_HEADER_LEN};
+use std::fmt;
+
+/// A single problem found while verifying an entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EntryProblem {
+    /// The local file header could not be read or has a bad signature
+    BadLocalHeader(String),
+    /// A local header field disagrees with the central directory
+    HeaderMismatch {
+        field: &'static str,
+        central: u64,
+        local: u64,
+    },
+    /// The local header stores a different file name
+    NameMismatch { local: String },
+    /// The entry data extends past the end of the archive
+    Truncated { data_end: u64, archive_len: u64 },
+    /// Decompressed data does not match the stored CRC-32
+    CrcMismatch { expected: u32, actual: u32 },
+    /// Decompressed data does not match the stored uncompressed size
+    SizeMismatch { expected: u64, actual: u64 },
+    /// The entry data could not be read or decompressed
+    ReadError(String),
+}
+
+impl fmt::Display for EntryProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EntryProblem::BadLocalHeader(msg) => write!(f, "bad local header: {}", msg),
+            EntryProblem::HeaderMismatch {
+                field,
+                central,
+                local,
+            } => write!(
+                f,
+                "local header {} {:#x} differs from central directory {:#x}",
+                field, local, central
+            ),
+            EntryProblem::NameMismatch { local } => {
+                write!(
+                    f,
+                    "local header name '{}' differs from central directory",
+                    local
+                )
+            }
+            EntryProblem::Truncated {
+                data_end,
+                archive_len,
+            } => write!(
+                f,
+                "entry data ends at byte {} but the archive is only {} bytes",
+                data_end, archive_len
+            ),
+            EntryProblem::CrcMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "CRC-32 mismatch: expected {:#010x}, got {:#010x}",
+                    expected, actual
+                )
+            }
+            EntryProblem::SizeMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "size mismatch: expected {} bytes, got {}",
+                    expected, actual
+                )
+            }
+            EntryProblem::ReadError(msg) => write!(f, "read error: {}", msg),
+        }
+    }
+}
+
+impl EntryProblem {
+    /// Whether the entry data cannot be located, so decompressing it is pointless
+    pub(crate) fn is_fatal(&self) -> bool {
+        matches!(
+            self,
+            EntryProblem::BadLocalHeader(_) | EntryProblem::Truncated { .. }
+        )
+    }
+
+    /// Whether the local header merely disagrees with the central directory
+    pub(crate) fn is_local_mismatch(&self) -> bool {
+        matches!(
+            self,
+            EntryProblem::HeaderMismatch { .. } | EntryProblem::NameMismatch { .. }
+        )
+    }
+}
+
+/// Verification result for one entry
+#[derive(Debug, Clone)]
+pub struct EntryReport {
+    /// Entry name from the central directory
+    pub name: String,
+    /// Problems found; empty when the entry is intact
+    pub problems: Vec<EntryProblem>,
+}
+
+impl EntryReport {
+    /// Whether no problems were found
+    pub fn is_ok(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// Verification result for a whole archive, one report per central-directory entry
+#[derive(Debug, Clone, Default)]
+pub struct VerifyReport {
+    pub entries: Vec<EntryReport>,
+}
+
+impl VerifyReport {
+    /// Whether every entry verified cleanly
+    pub fn is_ok(&self) -> bool {
+        self.entries.iter().all(EntryReport::is_ok)
+    }
+
+    /// Entries with at least one problem
+    pub fn failures(&self) -> impl Iterator<Item = &EntryReport> {
+        self.entries.iter().filter(|e| !e.is_ok())
+    }
+}
+
+/// Length of the name and extra field that follow the fixed local header
+/// fields, read without checking the signature
+pub(crate) fn local_variable_len(fixed: &[u8; LOCAL_HEADER_LEN]) -> usize {
+    u16::from_le_bytes([fixed[26], fixed[27]]) as usize
+        + u16::from_le_bytes([fixed[28], fixed[29]]) as usize
+}
+
+/// Offset where an entry's data begins, from its fixed local header fields
+pub(crate) fn data_offset(entry: &ZipEntry, fixed: &[u8; LOCAL_HEADER_LEN]) -> Result<u64> {
+    let header = LocalHeader::parse(fixed)?;
+    Ok(entry.offset + LOCAL_HEADER_LEN as u64 + header.variable_len())
+}
+
+/// Compare a local file header (fixed fields, name and extra field) against
+/// its central directory entry, and check that the entry data fits in
+/// `archive_len` bytes.
+pub(crate) fn check_local_header(
+    entry: &ZipEntry,
+    fixed: &[u8; LOCAL_HEADER_LEN],
+    variable: &[u8],
+    encoding: &FilenameEncoding,
+    archive_len: u64,
+) -> Vec<EntryProblem> {
+    let Ok(header) = LocalHeader::parse(fixed) else {
+        return vec![EntryProblem::BadLocalHeader(format!(
+            "invalid signature {:#010x}",
+            u32::from_le_bytes([fixed[0], fixed[1], fixed[2], fixed[3]])
+        ))];
+    };
+
+    let mut problems = Vec::new();
+    let mut compare = |field: &'static str, central: u64, local: u64| {
+        if central != local {
+            problems.push(EntryProblem::HeaderMismatch {
+                field,
+                central,
+                local,
+            });
+        }
+    };
+
+    compare(
+        "compression method",
+        entry.compression_method as u64,
+        header.compression_method as u64,
+    );
+    compare(
+        "encryption flag",
+        (entry.flags & 0x01) as u64,
+        (header.flags & 0x01) as u64,
+    );
+
+    // Without a data descriptor (bit 3) the local header carries the real CRC
+    // and sizes; ZIP64 placeholders are resolved from the extra field instead.
+    if header.flags & 0x08 == 0 {
+        compare("CRC-32", entry.crc32 as u64, header.crc32 as u64);
+        let compressed = header.compressed_size;
+        let uncompressed = header.uncompressed_size;
+        if compressed != u32::MAX && uncompressed != u32::MAX {
+            compare("compressed size", entry.compressed_size, compressed as u64);
+            compare(
+                "uncompressed size",
+                entry.uncompressed_size,
+                uncompressed as u64,
+            );
+        }
+    }
+
+    let (name, extra) = variable.split_at((header.name_len as usize).min(variable.len()));
+    let local_name = encoding.decode(name, header.flags, extra);
+    if local_name != entry.name {
+        problems.push(EntryProblem::NameMismatch { local: local_name });
+    }
+
+    let data_end = entry.offset
+        + LOCAL_HEADER_LEN as u64
+        + name.len() as u64
+        + header.extra_len as u64
+        + entry.compressed_size;
+    if data_end > archive_len {
+        problems.push(EntryProblem::Truncated {
+            data_end,
+            archive_len,
+        });
+    }
+    problems
+}
+
+/// Compare the checksum and size of decompressed data against the central
+/// directory.  The CRC is not checked for encrypted entries (AE-2 stores none).
+pub(crate) fn check_data(entry: &ZipEntry, crc32: u32, size: u64) -> Vec<EntryProblem> {
+    let mut problems = Vec::new();
+    if size != entry.uncompressed_size {
+        problems.push(EntryProblem::SizeMismatch {
+            expected: entry.uncompressed_size,
+            actual: size,
+        });
+    }
+    if !entry.is_encrypted && crc32 != entry.crc32 {
+        problems.push(EntryProblem::CrcMismatch {
+            expected: entry.crc32,
+            actual: crc32,
+        });
+    }
+    problems
+}
@@ -9,13 +9,32 @@
 //!
 //! Now supports arbitrary writers (File, `Vec<u8>`, network streams, etc.)
 
+use crate::append::ExistingArchive;
+use crate::block_deflate;
+use crate::budget::{MemoryBudget, Reservation};
+use crate::dostime::DosDateTime;
 use crate::error::{Result, SZipError};
+use crate::format::ExtendedTimestamp;
+use crate::hook::{ArchiveSummary, EntryStatsHook, EntrySummary, Hooks, WriterHook};
+use crate::ignore::IgnoreRules;
+use crate::raw::{self, CentralDirectoryRecord, EndOfCentralDirectory, LocalFileHeader};
+use crate::reader::StreamingZipReader;
+use crate::split::{SplitOutput, Volumes};
 use crc32fast::Hasher as Crc32;
 use flate2::write::DeflateEncoder;
 use flate2::Compression;
-use std::fs::File;
-use std::io::{Seek, Write};
+use std::collections::HashSet;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
 use std::path::Path;
+use std::time::Instant;
+
+#[cfg(feature = "manifest")]
+use crate::manifest::{Digests, MANIFEST_ENTRY};
+#[cfg(feature = "signing")]
+use crate::signing::{SignaturePlacement, Signer};
+#[cfg(feature = "zstd-support")]
+use crate::zstd_dict::{ZstdDictionary, DICTIONARY_ENTRY};
 
 #[cfg(feature = "encryption")]
 use crate::encryption::{AesEncryptor, AesStrength};
@@ -30,6 +49,15 @@ pub enum CompressionMethod {
     /// Zstd compression (requires zstd-support feature)
     #[cfg(feature = "zstd-support")]
     Zstd,
+    /// BZip2 compression (requires bzip2 feature)
+    #[cfg(feature = "bzip2")]
+    Bzip2,
+    /// LZMA compression (requires lzma feature)
+    #[cfg(feature = "lzma")]
+    Lzma,
+    /// XZ compression (requires lzma feature)
+    #[cfg(feature = "lzma")]
+    Xz,
 }
 
 impl CompressionMethod {
@@ -39,7 +67,215 @@ impl CompressionMethod {
             CompressionMethod::Deflate => 8,
             #[cfg(feature = "zstd-support")]
             CompressionMethod::Zstd => 93,
+            #[cfg(feature = "bzip2")]
+            CompressionMethod::Bzip2 => 12,
+            #[cfg(feature = "lzma")]
+            CompressionMethod::Lzma => 14,
+            #[cfg(feature = "lzma")]
+            CompressionMethod::Xz => 95,
+        }
+    }
+
+    /// General purpose flags describing the method's data
+    pub(crate) fn flags(self) -> u16 {
+        match self {
+            // Raw LZMA1 data ends with an end marker
+            #[cfg(feature = "lzma")]
+            CompressionMethod::Lzma => crate::lzma::FLAG_EOS_MARKER,
+            _ => 0,
+        }
+    }
+}
+
+/// What the writers do when an entry name is written more than once
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DuplicateNamePolicy {
+    /// Write duplicates without checking (default, no name tracking)
+    #[default]
+    Allow,
+    /// Write the duplicate and report it to the writer's hooks
+    /// ([`WriterHook::duplicate_name`](crate::hook::WriterHook::duplicate_name)),
+    /// and as a warning through `tracing` with that feature
+    Warn,
+    /// Fail `start_entry` with `SZipError::InvalidFormat`
+    Error,
+}
+
+/// Name policy shared by the sync and async writers: normalization,
+/// validation and duplicate detection
+#[derive(Default)]
+pub(crate) struct NamePolicy {
+    pub(crate) normalize: bool,
+    pub(crate) validate: bool,
+    pub(crate) duplicates: DuplicateNamePolicy,
+    /// Names written so far, tracked unless duplicates are allowed
+    pub(crate) seen: HashSet<String>,
+}
+
+impl NamePolicy {
+    /// Apply the policy to a caller-supplied entry name, reporting warnings
+    /// to `hooks`
+    pub(crate) fn entry_name(&mut self, name: &str, hooks: &mut Hooks) -> Result<String> {
+        let name = if self.normalize {
+            let normalized = crate::format::normalize_entry_name(name);
+            if normalized.is_empty() {
+                return Err(SZipError::InvalidFormat(format!(
+                    "Entry name '{}' is empty after normalization",
+                    name
+                )));
+            }
+            normalized
+        } else {
+            name.to_string()
+        };
+        if self.validate {
+            if let Err(reason) = crate::format::check_entry_name(&name) {
+                return Err(SZipError::InvalidFormat(format!(
+                    "Unsafe entry name '{}': {}",
+                    name, reason
+                )));
+            }
+        }
+        if self.duplicates != DuplicateNamePolicy::Allow && !self.seen.insert(name.clone()) {
+            if self.duplicates == DuplicateNamePolicy::Error {
+                return Err(SZipError::InvalidFormat(format!(
+                    "Duplicate entry name '{}'",
+                    name
+                )));
+            }
+            crate::trace_warn!(entry = name.as_str(), "duplicate entry name");
+            hooks.duplicate_name(&name)?;
+        }
+        Ok(name)
+    }
+}
+
+/// Name policy setters of [`StreamingZipWriter`] and `AsyncStreamingZipWriter`,
+/// which keep the policy in a `names: NamePolicy` field
+macro_rules! name_policy_setters {
+    () => {
+        /// Normalize caller-supplied entry names before they are written.
+        ///
+        /// When enabled, backslashes become `/`, leading slashes and drive letters
+        /// are stripped, and `.`/`..` segments are collapsed (see
+        /// [`crate::format::normalize_entry_name`]). Disabled by default.
+        pub fn set_normalize_names(&mut self, enabled: bool) -> &mut Self {
+            self.names.normalize = enabled;
+            self
+        }
+
+        /// Reject entry names that are unsafe to extract.
+        ///
+        /// When enabled, `start_entry` fails on absolute paths, `..` components,
+        /// NUL bytes and Windows-reserved device names (see
+        /// [`crate::format::check_entry_name`]). Validation runs after
+        /// normalization. Disabled by default.
+        pub fn set_validate_names(&mut self, enabled: bool) -> &mut Self {
+            self.names.validate = enabled;
+            self
+        }
+
+        /// Choose how repeated entry names are handled (default: `Allow`).
+        ///
+        /// Duplicates usually indicate a bug and many extractors silently keep
+        /// only one of them. Names are compared after normalization.
+        pub fn set_duplicate_policy(
+            &mut self,
+            policy: crate::writer::DuplicateNamePolicy,
+        ) -> &mut Self {
+            self.names.duplicates = policy;
+            self
+        }
+    };
+}
+pub(crate) use name_policy_setters;
+
+/// Interoperability profile for [`StreamingZipWriter`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Compatibility {
+    /// Use the configured method and stream sizes in data descriptors (default)
+    #[default]
+    Standard,
+    /// Archives that open by double-click in Windows Explorer's built-in ZIP
+    /// support:
+    ///
+    /// - zstd, bzip2, LZMA and XZ are replaced by DEFLATE (the level is
+    ///   capped at 9)
+    /// - no data descriptors: CRC-32 and sizes are patched into each local
+    ///   header once the entry is finished, which needs `Seek`. Entries over
+    ///   4 GiB need a size hint above 4 GiB so ZIP64 room is reserved.
+    /// - every entry gets a timestamp (now, unless set) and DOS attributes
+    ///   (archive, or directory for names ending in `/`)
+    /// - names are normalized and validated; ASCII names are written as-is
+    ///   (identical in CP437) and others as UTF-8 with the language encoding flag
+    WindowsExplorer,
+    /// Archives that Archive Utility and `ditto` extract with permissions:
+    ///
+    /// - "version made by" names Unix as the host
+    /// - entries without a `unix_mode` get `0o644` (`0o755` for names ending
+    ///   in `/`), and the file type bits are added to modes that lack them
+    MacOs,
+}
+
+/// Largest Zstd window the readers accept, as a base-2 logarithm: the
+/// format's limit, beyond zstd's default of 2^27
+#[cfg(feature = "zstd-support")]
+pub(crate) const ZSTD_WINDOW_LOG_MAX: u32 = if cfg!(target_pointer_width = "64") {
+    31
+} else {
+    30
+};
+
+/// Zstd encoder settings that trade memory for throughput on large
+/// entries; see [`StreamingZipWriter::set_zstd_options`]
+#[cfg(feature = "zstd-support")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ZstdOptions {
+    /// Worker threads compressing each entry while the caller writes more
+    /// (default: 0, compress on the calling thread). Requires the `zstdmt`
+    /// feature.
+    pub workers: u32,
+    /// Base-2 logarithm of the match window, 10 to 31 (default: `None`, up
+    /// to 8 MiB as the level decides). Readers need memory for the whole
+    /// window; windows over 2^27 (128 MiB) also need `--long` or a raised
+    /// limit in other tools. s-zip's readers accept up to 2^31, except the
+    /// async reader for entries compressed with a dictionary.
+    pub window_log: Option<u32>,
+    /// Find matches far back in a large window, for data repeating at long
+    /// distances such as backups and VM images (default: off). The window
+    /// grows to 128 MiB unless `window_log` is set.
+    pub long_distance_matching: bool,
+}
+
+#[cfg(feature = "zstd-support")]
+impl ZstdOptions {
+    /// Encoder of one entry writing to `out`
+    pub(crate) fn encoder<W: Write>(
+        &self,
+        out: W,
+        level: i32,
+        dictionary: Option<&ZstdDictionary>,
+    ) -> Result<zstd::Encoder<'static, W>> {
+        let mut encoder = match dictionary {
+            Some(dictionary) => zstd::Encoder::with_dictionary(out, level, dictionary.as_bytes())?,
+            None => zstd::Encoder::new(out, level)?,
+        };
+        encoder.include_checksum(false)?; // ZIP uses CRC32, not zstd checksum
+        if self.workers > 0 {
+            #[cfg(feature = "zstdmt")]
+            encoder.multithread(self.workers)?;
+            #[cfg(not(feature = "zstdmt"))]
+            return Err(SZipError::InvalidFormat(
+                "Zstd worker threads require the 'zstdmt' feature".to_string(),
+            ));
         }
+        if let Some(window_log) = self.window_log {
+            encoder.window_log(window_log)?;
+        }
+        if self.long_distance_matching {
+            encoder.long_distance_matching(true)?;
+        }
+        Ok(encoder)
     }
 }
 
@@ -51,9 +287,88 @@ struct ZipEntry {
     compressed_size: u64,
     uncompressed_size: u64,
     compression_method: u16,
+    /// General purpose bit flag, as written to the local header
+    flags: u16,
+    last_modified: DosDateTime,
+    external_attrs: u32,
+    extended_timestamp: ExtendedTimestamp,
     #[cfg(feature = "encryption")]
     #[allow(dead_code)] // Will be used for central directory in future versions
     encryption_strength: Option<u16>,
+    /// Set for entries copied from another archive
+    copied: Option<CopiedRecord>,
+}
+
+/// Central directory fields kept from the archive an entry was copied from
+pub(crate) struct CopiedRecord {
+    pub version_made_by: u16,
+    pub version_needed: u16,
+    pub internal_attrs: u16,
+    /// Extra field without the ZIP64 field, which is rebuilt for the new offset
+    pub extra: Vec<u8>,
+}
+
+impl CopiedRecord {
+    pub(crate) fn new(entry: &crate::ZipEntry) -> Self {
+        CopiedRecord {
+            version_made_by: entry.version_made_by,
+            version_needed: entry.version_needed,
+            internal_attrs: entry.internal_attrs,
+            extra: without_zip64(&entry.extra),
+        }
+    }
+}
+
+/// Local header for a copy of `entry` named `name`, and its flags
+///
+/// The header states the sizes up front. Only entries with traditional
+/// encryption keep the data descriptor flag, as their password check byte
+/// depends on it; their data must be followed by a data descriptor.
+pub(crate) fn copied_header(
+    entry: &crate::ZipEntry,
+    name: &str,
+    local_extra: &[u8],
+) -> Result<(Vec<u8>, u16)> {
+    let descriptor = entry.flags & raw::FLAG_DATA_DESCRIPTOR != 0
+        && entry.is_encrypted
+        && entry.aes_strength.is_none();
+    let mut flags = (entry.flags & !raw::FLAG_UTF8) | raw::name_flags(name);
+    if !descriptor {
+        flags &= !raw::FLAG_DATA_DESCRIPTOR;
+    }
+    let (crc32, compressed_size, uncompressed_size) = if descriptor {
+        (0, 0, 0)
+    } else {
+        (entry.crc32, entry.compressed_size, entry.uncompressed_size)
+    };
+    let mut header = Vec::with_capacity(30 + name.len() + local_extra.len());
+    raw::write_local_header(
+        &mut header,
+        &LocalFileHeader {
+            version_needed: entry.version_needed,
+            flags,
+            compression_method: entry.compression_method,
+            last_mod_time: entry.last_modified.time,
+            last_mod_date: entry.last_modified.date,
+            crc32,
+            compressed_size,
+            uncompressed_size,
+            name: name.as_bytes(),
+            extra: &without_zip64(local_extra),
+        },
+    )?;
+    Ok((header, flags))
+}
+
+/// `extra` without its ZIP64 field (`0x0001`)
+fn without_zip64(extra: &[u8]) -> Vec<u8> {
+    let mut kept = Vec::with_capacity(extra.len());
+    for (id, data) in crate::format::extra_fields(extra).filter(|&(id, _)| id != 0x0001) {
+        kept.extend_from_slice(&id.to_le_bytes());
+        kept.extend_from_slice(&(data.len() as u16).to_le_bytes());
+        kept.extend_from_slice(data);
+    }
+    kept
 }
 
 /// Streaming ZIP writer that compresses data on-the-fly
@@ -63,6 +378,41 @@ pub struct StreamingZipWriter<W: Write + Seek> {
     current_entry: Option<CurrentEntry>,
     compression_level: u32,
     compression_method: CompressionMethod,
+    version_made_by: Option<u16>,
+    /// Archive comment written to the EOCD record
+    comment: Vec<u8>,
+    version_needed: Option<u16>,
+    names: NamePolicy,
+    deadline: Option<Instant>,
+    hooks: Hooks,
+    /// Share of a `MemoryBudget` covering the compressed-data buffer
+    memory: Option<Reservation>,
+    #[cfg(feature = "manifest")]
+    digests: Option<Digests>,
+    #[cfg(feature = "manifest")]
+    write_manifest: bool,
+    #[cfg(feature = "signing")]
+    signature: Option<(Box<dyn Signer>, SignaturePlacement)>,
+    /// Dictionary for Zstd entries, and whether to embed it
+    #[cfg(feature = "zstd-support")]
+    zstd_dictionary: Option<(ZstdDictionary, bool)>,
+    #[cfg(feature = "zstd-support")]
+    zstd_options: ZstdOptions,
+    compatibility: Compatibility,
+    omit_macos_metadata: bool,
+    auto_store: bool,
+    /// Threads each DEFLATE entry is compressed on
+    deflate_threads: usize,
+    /// Set while an omitted entry is "open", so its data is discarded
+    skipping_entry: bool,
+    /// Volume bookkeeping of a split archive's output; see
+    /// [`new_split`](StreamingZipWriter::new_split)
+    volumes: Option<fn(&mut W) -> &mut dyn Volumes>,
+    /// Central directory of the archive being appended to; see
+    /// [`append`](StreamingZipWriter::append)
+    existing: Option<ExistingArchive>,
+    /// Cuts the output to its final length once finished
+    truncate: Option<fn(&mut W, u64) -> std::io::Result<()>>,
     #[cfg(feature = "encryption")]
     password: Option<String>,
     #[cfg(feature = "encryption")]
@@ -75,10 +425,29 @@ struct CurrentEntry {
     encoder: Box<dyn CompressorWrite>,
     counter: CrcCounter,
     compression_method: u16,
+    flags: u16,
+    /// Whether the local header carries a ZIP64 extra field to patch
+    zip64_header: bool,
+    /// Header and first data of an entry whose method auto-store hasn't
+    /// chosen yet; nothing of the entry is written until it has
+    undecided: Option<Undecided>,
+    last_modified: DosDateTime,
+    external_attrs: u32,
+    extended_timestamp: ExtendedTimestamp,
+    /// When `start_entry` was called
+    started: Instant,
     #[cfg(feature = "encryption")]
     encryptor: Option<AesEncryptor>,
 }
 
+/// An entry held back while auto-store samples its data
+struct Undecided {
+    header: Vec<u8>,
+    /// AES salt and password verifier, written after the header
+    preamble: Vec<u8>,
+    sample: Vec<u8>,
+}
+
 trait CompressorWrite: Write {
     fn finish_compression(self: Box<Self>) -> Result<CompressedBuffer>;
     fn get_buffer_mut(&mut self) -> &mut CompressedBuffer;
@@ -108,6 +477,71 @@ impl CompressorWrite for DeflateCompressor {
     }
 }
 
+/// DEFLATE of independent blocks on several threads; see
+/// [`set_deflate_threads`](StreamingZipWriter::set_deflate_threads)
+struct ParallelDeflateCompressor {
+    level: Compression,
+    threads: usize,
+    /// Full blocks waiting to be compressed as one batch
+    blocks: Vec<Vec<u8>>,
+    /// Block being filled
+    block: Vec<u8>,
+    buffer: CompressedBuffer,
+}
+
+impl ParallelDeflateCompressor {
+    fn new(level: Compression, threads: usize, buffer: CompressedBuffer) -> Self {
+        Self {
+            level,
+            threads,
+            blocks: Vec::with_capacity(threads),
+            block: Vec::new(),
+            buffer,
+        }
+    }
+
+    /// Compress the waiting blocks into the buffer
+    fn compress(&mut self, last: bool) -> std::io::Result<()> {
+        let blocks = std::mem::take(&mut self.blocks);
+        for compressed in block_deflate::compress_blocks(&blocks, self.level, last)? {
+            self.buffer.write_all(&compressed)?;
+        }
+        Ok(())
+    }
+}
+
+impl Write for ParallelDeflateCompressor {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let n = buf.len().min(block_deflate::BLOCK_LEN - self.block.len());
+        self.block.extend_from_slice(&buf[..n]);
+        if self.block.len() == block_deflate::BLOCK_LEN {
+            self.blocks.push(std::mem::take(&mut self.block));
+            if self.blocks.len() == self.threads {
+                self.compress(false)?;
+            }
+        }
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        // Blocks are compressed as a batch fills; output appears then
+        Ok(())
+    }
+}
+
+impl CompressorWrite for ParallelDeflateCompressor {
+    fn finish_compression(mut self: Box<Self>) -> Result<CompressedBuffer> {
+        let block = std::mem::take(&mut self.block);
+        self.blocks.push(block);
+        self.compress(true)?;
+        Ok(self.buffer)
+    }
+
+    fn get_buffer_mut(&mut self) -> &mut CompressedBuffer {
+        &mut self.buffer
+    }
+}
+
 /// Stored (no compression) pass-through compressor
 struct StoredCompressor {
     buffer: CompressedBuffer,
@@ -136,6 +570,8 @@ impl CompressorWrite for StoredCompressor {
 #[cfg(feature = "zstd-support")]
 struct ZstdCompressor {
     encoder: zstd::Encoder<'static, CompressedBuffer>,
+    /// A flush would wait for every worker thread
+    multithreaded: bool,
 }
 
 #[cfg(feature = "zstd-support")]
@@ -145,6 +581,10 @@ impl Write for ZstdCompressor {
     }
 
     fn flush(&mut self) -> std::io::Result<()> {
+        if self.multithreaded {
+            // Output appears as the workers finish their jobs
+            return Ok(());
+        }
         self.encoder.flush()
     }
 }
@@ -160,6 +600,65 @@ impl CompressorWrite for ZstdCompressor {
     }
 }
 
+#[cfg(feature = "bzip2")]
+struct Bzip2Compressor {
+    encoder: bzip2::write::BzEncoder<CompressedBuffer>,
+}
+
+#[cfg(feature = "bzip2")]
+impl Write for Bzip2Compressor {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.encoder.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        // A bzip2 flush ends the current block, which would cut every
+        // `write_data` chunk into its own block; output appears as blocks fill
+        Ok(())
+    }
+}
+
+#[cfg(feature = "bzip2")]
+impl CompressorWrite for Bzip2Compressor {
+    fn finish_compression(self: Box<Self>) -> Result<CompressedBuffer> {
+        Ok(self.encoder.finish()?)
+    }
+
+    fn get_buffer_mut(&mut self) -> &mut CompressedBuffer {
+        self.encoder.get_mut()
+    }
+}
+
+/// LZMA or XZ, depending on the stream the encoder was made with
+#[cfg(feature = "lzma")]
+struct LzmaCompressor {
+    encoder: liblzma::write::XzEncoder<CompressedBuffer>,
+}
+
+#[cfg(feature = "lzma")]
+impl Write for LzmaCompressor {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.encoder.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        // Raw LZMA1 can't be flushed mid-stream; output appears as the
+        // encoder's buffers fill
+        Ok(())
+    }
+}
+
+#[cfg(feature = "lzma")]
+impl CompressorWrite for LzmaCompressor {
+    fn finish_compression(self: Box<Self>) -> Result<CompressedBuffer> {
+        Ok(self.encoder.finish()?)
+    }
+
+    fn get_buffer_mut(&mut self) -> &mut CompressedBuffer {
+        self.encoder.get_mut()
+    }
+}
+
 /// Metadata tracker for CRC and byte counts
 struct CrcCounter {
     crc: Crc32,
@@ -277,6 +776,31 @@ impl StreamingZipWriter<File> {
             current_entry: None,
             compression_level,
             compression_method: method,
+            version_made_by: None,
+            comment: Vec::new(),
+            version_needed: None,
+            names: NamePolicy::default(),
+            deadline: None,
+            hooks: Hooks::default(),
+            memory: None,
+            #[cfg(feature = "manifest")]
+            digests: None,
+            #[cfg(feature = "manifest")]
+            write_manifest: false,
+            #[cfg(feature = "signing")]
+            signature: None,
+            #[cfg(feature = "zstd-support")]
+            zstd_dictionary: None,
+            #[cfg(feature = "zstd-support")]
+            zstd_options: ZstdOptions::default(),
+            compatibility: Compatibility::Standard,
+            omit_macos_metadata: false,
+            auto_store: false,
+            deflate_threads: 1,
+            skipping_entry: false,
+            volumes: None,
+            existing: None,
+            truncate: None,
             #[cfg(feature = "encryption")]
             password: None,
             #[cfg(feature = "encryption")]
@@ -294,6 +818,31 @@ impl StreamingZipWriter<File> {
             current_entry: None,
             compression_level: compression_level as u32,
             compression_method: CompressionMethod::Zstd,
+            version_made_by: None,
+            comment: Vec::new(),
+            version_needed: None,
+            names: NamePolicy::default(),
+            deadline: None,
+            hooks: Hooks::default(),
+            memory: None,
+            #[cfg(feature = "manifest")]
+            digests: None,
+            #[cfg(feature = "manifest")]
+            write_manifest: false,
+            #[cfg(feature = "signing")]
+            signature: None,
+            #[cfg(feature = "zstd-support")]
+            zstd_dictionary: None,
+            #[cfg(feature = "zstd-support")]
+            zstd_options: ZstdOptions::default(),
+            compatibility: Compatibility::Standard,
+            omit_macos_metadata: false,
+            auto_store: false,
+            deflate_threads: 1,
+            skipping_entry: false,
+            volumes: None,
+            existing: None,
+            truncate: None,
             #[cfg(feature = "encryption")]
             password: None,
             #[cfg(feature = "encryption")]
@@ -302,6 +851,82 @@ impl StreamingZipWriter<File> {
     }
 }
 
+impl StreamingZipWriter<File> {
+    /// Open an existing archive to add entries to it (DEFLATE, level 6)
+    ///
+    /// New entries are written where the central directory was, and
+    /// [`finish`](Self::finish) writes a central directory listing the
+    /// existing entries followed by the new ones. Existing entry data isn't
+    /// read or rewritten, and the archive comment is kept unless replaced
+    /// with [`set_archive_comment`](Self::set_archive_comment).
+    ///
+    /// Until `finish` returns the file has no valid central directory, so an
+    /// interrupted append leaves an archive that needs
+    /// [`salvage`](crate::salvage) to read. Existing names count for the
+    /// [duplicate policy](Self::set_duplicate_policy); a manifest or
+    /// signature covers only the new entries.
+    ///
+    /// ```no_run
+    /// use s_zip::StreamingZipWriter;
+    ///
+    /// let mut writer = StreamingZipWriter::append("logs.zip")?;
+    /// writer.add_entry("2024-06-02.log", b"...")?;
+    /// writer.finish()?;
+    /// # Ok::<(), s_zip::SZipError>(())
+    /// ```
+    pub fn append<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::append_with_method(path, CompressionMethod::Deflate, 6)
+    }
+
+    /// Like [`append`](Self::append), compressing new entries with the
+    /// given method and level
+    pub fn append_with_method<P: AsRef<Path>>(
+        path: P,
+        method: CompressionMethod,
+        compression_level: u32,
+    ) -> Result<Self> {
+        let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+        let mut existing = crate::append::read(&mut file)?;
+        file.seek(SeekFrom::Start(existing.offset))?;
+        let mut writer = Self::from_writer_with_method(file, method, compression_level)?;
+        writer.comment = std::mem::take(&mut existing.comment);
+        writer.names.seen.extend(existing.names.iter().cloned());
+        writer.existing = Some(existing);
+        writer.truncate = Some(|file, len| file.set_len(len));
+        Ok(writer)
+    }
+}
+
+impl StreamingZipWriter<SplitOutput> {
+    /// Create a writer for an archive split into volumes of at most
+    /// `max_volume_size` bytes (DEFLATE, level 6)
+    ///
+    /// Volumes are written as `name.z01`, `name.z02`, ... next to `path`, and
+    /// the last one becomes `path` when the archive is finished. See the
+    /// [`split`](crate::split) module.
+    pub fn new_split<P: AsRef<Path>>(path: P, max_volume_size: u64) -> Result<Self> {
+        Self::new_split_with_method(path, max_volume_size, CompressionMethod::Deflate, 6)
+    }
+
+    /// Like [`new_split`](Self::new_split), with the given compression
+    /// method and level
+    pub fn new_split_with_method<P: AsRef<Path>>(
+        path: P,
+        max_volume_size: u64,
+        method: CompressionMethod,
+        compression_level: u32,
+    ) -> Result<Self> {
+        let output = SplitOutput::create(path, max_volume_size)?;
+        let mut writer = Self::from_writer_with_method(output, method, compression_level)?;
+        writer.volumes = Some(split_volumes);
+        Ok(writer)
+    }
+}
+
+fn split_volumes(output: &mut SplitOutput) -> &mut dyn Volumes {
+    output
+}
+
 impl<W: Write + Seek> StreamingZipWriter<W> {
     /// Create a new ZIP writer from an arbitrary writer with default compression level (6) using DEFLATE
     pub fn from_writer(writer: W) -> Result<Self> {
@@ -330,6 +955,31 @@ impl<W: Write + Seek> StreamingZipWriter<W> {
             current_entry: None,
             compression_level,
             compression_method: method,
+            version_made_by: None,
+            comment: Vec::new(),
+            version_needed: None,
+            names: NamePolicy::default(),
+            deadline: None,
+            hooks: Hooks::default(),
+            memory: None,
+            #[cfg(feature = "manifest")]
+            digests: None,
+            #[cfg(feature = "manifest")]
+            write_manifest: false,
+            #[cfg(feature = "signing")]
+            signature: None,
+            #[cfg(feature = "zstd-support")]
+            zstd_dictionary: None,
+            #[cfg(feature = "zstd-support")]
+            zstd_options: ZstdOptions::default(),
+            compatibility: Compatibility::Standard,
+            omit_macos_metadata: false,
+            auto_store: false,
+            deflate_threads: 1,
+            skipping_entry: false,
+            volumes: None,
+            existing: None,
+            truncate: None,
             #[cfg(feature = "encryption")]
             password: None,
             #[cfg(feature = "encryption")]
@@ -380,6 +1030,248 @@ impl<W: Write + Seek> StreamingZipWriter<W> {
         self
     }
 
+    /// Set the archive comment written to the end of central directory record
+    /// on `finish()`, e.g. to stamp build metadata into the archive.
+    ///
+    /// The comment is limited to 65,535 bytes; `finish()` fails if it is longer.
+    pub fn set_archive_comment(&mut self, comment: &str) -> &mut Self {
+        self.comment = comment.as_bytes().to_vec();
+        self
+    }
+
+    /// Override the "version made by" field written to the central directory.
+    ///
+    /// Some ecosystems (Java, Android) inspect this value, e.g. the high byte
+    /// must be 3 (Unix) for permission bits to be honoured. Defaults to 63
+    /// (MS-DOS host, spec 6.3), with the Unix host for entries that have a
+    /// `unix_mode`; the override applies to every entry.
+    pub fn set_version_made_by(&mut self, version: u16) -> &mut Self {
+        self.version_made_by = Some(version);
+        self
+    }
+
+    /// Override the "version needed to extract" field written to local headers
+    /// and the central directory.
+    ///
+    /// The override is raised per entry to the minimum its compression method,
+    /// encryption and ZIP64 use require (e.g. 5.1 for AES).
+    pub fn set_version_needed(&mut self, version: u16) -> &mut Self {
+        self.version_needed = Some(version);
+        self
+    }
+
+    /// The version needed for an entry requiring at least `minimum`,
+    /// honouring the override
+    fn version_needed_for(&self, minimum: u16) -> u16 {
+        self.version_needed
+            .map_or(minimum, |version| version.max(minimum))
+    }
+
+    crate::writer::name_policy_setters!();
+
+    /// Fail with `SZipError::DeadlineExceeded` once `deadline` passes.
+    ///
+    /// Checked when entries start, on every `write_data` call and before the
+    /// central directory is written, so work stops at the next chunk boundary.
+    /// The partial archive is not valid and should be discarded.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use s_zip::StreamingZipWriter;
+    /// use std::time::{Duration, Instant};
+    ///
+    /// let mut writer = StreamingZipWriter::new("export.zip")?
+    ///     .with_deadline(Instant::now() + Duration::from_secs(30));
+    /// writer.add_entry("data.csv", b"a,b,c")?;
+    /// writer.finish()?;
+    /// # Ok::<(), s_zip::SZipError>(())
+    /// ```
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Draw compressed-data buffers from `budget`, shared with other jobs.
+    ///
+    /// When the budget runs low the buffer is written out early instead of
+    /// growing. See [`crate::budget`].
+    pub fn with_memory_budget(mut self, budget: &MemoryBudget) -> Self {
+        self.memory = Some(budget.empty());
+        self
+    }
+
+    /// Register a [`WriterHook`] called as headers, data, entries and the
+    /// central directory are written
+    pub fn add_hook(&mut self, hook: impl WriterHook + 'static) -> &mut Self {
+        self.hooks.push(Box::new(hook));
+        self
+    }
+
+    /// Call `callback` after each entry with its sizes, compression method
+    /// and wall time, e.g. to log compression ratios and throughput
+    pub fn on_entry_stats(
+        &mut self,
+        callback: impl FnMut(&EntrySummary<'_>) + Send + 'static,
+    ) -> &mut Self {
+        self.hooks.push(Box::new(EntryStatsHook(callback)));
+        self
+    }
+
+    /// Append an integrity manifest listing the SHA-256, CRC-32, size and
+    /// external attributes of every entry as the final entry (default: off).
+    ///
+    /// Must be enabled before the first entry. See [`crate::manifest`].
+    #[cfg(feature = "manifest")]
+    pub fn set_manifest(&mut self, enabled: bool) -> &mut Self {
+        self.write_manifest = enabled;
+        if enabled {
+            self.collect_digests();
+        }
+        self
+    }
+
+    /// Sign the archive: every entry is hashed with SHA-256 as it is written
+    /// and `finish()` stores the manifest signed by `signer` at `placement`.
+    ///
+    /// Must be called before the first entry. See [`crate::signing`].
+    #[cfg(feature = "signing")]
+    pub fn sign_with(
+        &mut self,
+        signer: impl Signer + 'static,
+        placement: SignaturePlacement,
+    ) -> &mut Self {
+        self.signature = Some((Box::new(signer), placement));
+        self.collect_digests();
+        self
+    }
+
+    /// External attributes of the entries written so far, for the manifest
+    #[cfg(feature = "manifest")]
+    fn external_attrs(&self) -> Vec<u32> {
+        self.entries.iter().map(|e| e.external_attrs).collect()
+    }
+
+    /// Start hashing entries for the manifest, once
+    #[cfg(feature = "manifest")]
+    fn collect_digests(&mut self) {
+        if self.digests.is_none() {
+            let digests = Digests::default();
+            self.hooks.push(Box::new(digests.hook()));
+            self.digests = Some(digests);
+        }
+    }
+
+    /// Select an interoperability profile (default: `Standard`).
+    ///
+    /// [`Compatibility::WindowsExplorer`] also enables name normalization and
+    /// validation; either can be switched off again afterwards.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use s_zip::{Compatibility, StreamingZipWriter};
+    ///
+    /// let mut writer = StreamingZipWriter::new("for-users.zip")?;
+    /// writer.set_compatibility(Compatibility::WindowsExplorer);
+    /// writer.add_entry("report.txt", b"opens by double-click")?;
+    /// writer.finish()?;
+    /// # Ok::<(), s_zip::SZipError>(())
+    /// ```
+    pub fn set_compatibility(&mut self, compatibility: Compatibility) -> &mut Self {
+        self.compatibility = compatibility;
+        if compatibility == Compatibility::WindowsExplorer {
+            self.names.normalize = true;
+            self.names.validate = true;
+        }
+        self
+    }
+
+    /// Silently drop macOS Finder metadata entries (`.DS_Store`, `__MACOSX/`,
+    /// `._` AppleDouble files) instead of writing them (default: false).
+    ///
+    /// Names are matched with [`crate::format::is_macos_metadata`] after
+    /// normalization (whether or not names are normalized when written); data
+    /// written to a dropped entry is discarded.
+    pub fn set_omit_macos_metadata(&mut self, omit: bool) -> &mut Self {
+        self.omit_macos_metadata = omit;
+        self
+    }
+
+    /// Store entries that wouldn't compress instead of compressing them
+    /// (default: false).
+    ///
+    /// Entries named like already-compressed files (`.jpg`, `.mp4`,
+    /// `.parquet`, `.zip`, ...) are stored without trying. Other entries
+    /// are stored if compressing their first 64 KiB saves less than 5%,
+    /// which spares the CPU time of deflating media and other packed data.
+    /// Until that much data is written (or the entry ends), the entry is
+    /// held in memory. Has no effect when the method is Stored already.
+    pub fn set_auto_store(&mut self, enabled: bool) -> &mut Self {
+        self.auto_store = enabled;
+        self
+    }
+
+    /// Compress each DEFLATE entry on `threads` threads (default: 1).
+    ///
+    /// Entry data is cut into 1 MiB blocks that are compressed
+    /// independently, `threads` at a time, and joined into one DEFLATE
+    /// stream that any unzip tool reads. This costs a fraction of a percent
+    /// of compression and up to two blocks per thread of memory; entries
+    /// smaller than a block are compressed on the calling thread as usual.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use s_zip::StreamingZipWriter;
+    ///
+    /// let mut writer = StreamingZipWriter::new("backup.zip")?;
+    /// writer.set_deflate_threads(8);
+    /// writer.start_entry("disk.img")?;
+    /// // ... write_data() 10 GB ...
+    /// writer.finish()?;
+    /// # Ok::<(), s_zip::SZipError>(())
+    /// ```
+    pub fn set_deflate_threads(&mut self, threads: usize) -> &mut Self {
+        self.deflate_threads = threads.max(1);
+        self
+    }
+
+    /// Configure the Zstd encoder: worker threads, window size and
+    /// long-distance matching (default: [`ZstdOptions::default`]).
+    ///
+    /// Takes effect from the next entry.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use s_zip::{StreamingZipWriter, ZstdOptions};
+    ///
+    /// let mut writer = StreamingZipWriter::with_zstd("images.zip", 9)?;
+    /// writer.set_zstd_options(ZstdOptions {
+    ///     window_log: Some(27),
+    ///     long_distance_matching: true,
+    ///     ..Default::default()
+    /// });
+    /// writer.start_entry("vm.qcow2")?;
+    /// // ... write_data() ...
+    /// writer.finish()?;
+    /// # Ok::<(), s_zip::SZipError>(())
+    /// ```
+    #[cfg(feature = "zstd-support")]
+    pub fn set_zstd_options(&mut self, options: ZstdOptions) -> &mut Self {
+        self.zstd_options = options;
+        self
+    }
+
+    /// Compress Zstd entries with `dictionary`.
+    ///
+    /// With `embed`, `finish()` stores the dictionary as
+    /// [`DICTIONARY_ENTRY`], where readers find it by themselves; otherwise
+    /// it must be supplied to readers separately. Must be set before the
+    /// first entry. See [`crate::zstd_dict`].
+    #[cfg(feature = "zstd-support")]
+    pub fn set_zstd_dictionary(&mut self, dictionary: ZstdDictionary, embed: bool) -> &mut Self {
+        self.zstd_dictionary = Some((dictionary, embed));
+        self
+    }
+
     /// Start a new entry (file) in the ZIP
     pub fn start_entry(&mut self, name: &str) -> Result<()> {
         crate::trace!(entry = name, "start_entry");
@@ -403,6 +1295,258 @@ impl<W: Write + Seek> StreamingZipWriter<W> {
         self.write_data(data)
     }
 
+    /// Add an empty directory entry, so extractors create the folder even
+    /// when no file is archived inside it.
+    ///
+    /// A `/` is appended to `name` if missing. The entry is stored, has no
+    /// data and carries the directory attribute; it is never encrypted.
+    ///
+    /// ```no_run
+    /// # use s_zip::StreamingZipWriter;
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut writer = StreamingZipWriter::new("out.zip")?;
+    /// writer.add_directory("logs")?;
+    /// writer.finish()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn add_directory(&mut self, name: &str) -> Result<()> {
+        let options = crate::EntryOptions {
+            dos_attributes: Some(crate::DosAttributes {
+                directory: true,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        self.start_entry_with_options(&crate::format::directory_entry_name(name), options)?;
+        self.finish_current_entry()
+    }
+
+    /// Add a symbolic link `name` pointing at `target`.
+    ///
+    /// The target is stored as the entry's data and the Unix mode marks the
+    /// entry as a link (`0o120777`), which `unzip` and
+    /// [`extract_to_dir`](crate::StreamingZipReader::extract_to_dir) recreate
+    /// as a link on Unix.
+    ///
+    /// ```no_run
+    /// # use s_zip::StreamingZipWriter;
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut writer = StreamingZipWriter::new("out.zip")?;
+    /// writer.add_entry("lib/libfoo.so.1", b"...")?;
+    /// writer.add_symlink("lib/libfoo.so", "libfoo.so.1")?;
+    /// writer.finish()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn add_symlink(&mut self, name: &str, target: &str) -> Result<()> {
+        let options =
+            crate::EntryOptions::default().with_unix_mode(crate::format::UNIX_SYMLINK | 0o777);
+        self.start_entry_with_options(name, options)?;
+        self.write_data(target.as_bytes())?;
+        self.finish_current_entry()
+    }
+
+    /// Write the `__MACOSX/…/._name` AppleDouble companion for `name`,
+    /// carrying Finder info and a resource fork, as macOS's own archiver does.
+    ///
+    /// Not affected by [`set_omit_macos_metadata`](Self::set_omit_macos_metadata).
+    pub fn add_apple_double(
+        &mut self,
+        name: &str,
+        finder_info: &[u8; 32],
+        resource_fork: &[u8],
+    ) -> Result<()> {
+        let omit = std::mem::replace(&mut self.omit_macos_metadata, false);
+        let result = self.add_entry(
+            &crate::format::apple_double_name(name),
+            &crate::format::apple_double(finder_info, resource_fork),
+        );
+        self.omit_macos_metadata = omit;
+        result
+    }
+
+    /// Add every file and directory under `dir`, skipping the
+    /// [`DEFAULT_PATTERNS`](crate::ignore::DEFAULT_PATTERNS) junk files.
+    ///
+    /// See [`add_dir_recursive_with_ignore`](Self::add_dir_recursive_with_ignore).
+    pub fn add_dir_recursive<P: AsRef<Path>>(&mut self, dir: P, prefix: &str) -> Result<usize> {
+        self.add_dir_recursive_with_ignore(dir, prefix, &IgnoreRules::default())
+    }
+
+    /// Add every file and directory under `dir` that `ignore` does not
+    /// exclude, returning the number of files added.
+    ///
+    /// Entries are named `prefix` followed by the `/`-separated path relative
+    /// to `dir`, in sorted order; directories get their own `name/` entries so
+    /// empty ones survive. Modification times (and Unix modes on Unix) come
+    /// from the file system. Symbolic links are skipped.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use s_zip::ignore::IgnoreRules;
+    /// use s_zip::StreamingZipWriter;
+    ///
+    /// let mut rules = IgnoreRules::default();
+    /// rules.add("target/").set_read_gitignore(true);
+    ///
+    /// let mut writer = StreamingZipWriter::new("project.zip")?;
+    /// writer.add_dir_recursive_with_ignore("my-project", "my-project/", &rules)?;
+    /// writer.finish()?;
+    /// # Ok::<(), s_zip::SZipError>(())
+    /// ```
+    pub fn add_dir_recursive_with_ignore<P: AsRef<Path>>(
+        &mut self,
+        dir: P,
+        prefix: &str,
+        ignore: &IgnoreRules,
+    ) -> Result<usize> {
+        let prefix = match prefix {
+            "" => String::new(),
+            p if p.ends_with('/') => p.to_string(),
+            p => format!("{}/", p),
+        };
+        self.add_dir_entries(dir.as_ref(), "", &prefix, ignore)
+    }
+
+    /// Add the children of `root/rel` (`rel` is `""` or ends in `/`)
+    fn add_dir_entries(
+        &mut self,
+        root: &Path,
+        rel: &str,
+        prefix: &str,
+        ignore: &IgnoreRules,
+    ) -> Result<usize> {
+        let dir = root.join(rel);
+
+        let scoped;
+        let mut ignore = ignore;
+        let gitignore = dir.join(".gitignore");
+        if ignore.reads_gitignore() && gitignore.is_file() {
+            let mut rules = ignore.clone();
+            rules.add_lines(rel, &std::fs::read_to_string(&gitignore)?);
+            scoped = rules;
+            ignore = &scoped;
+        }
+
+        let mut children = std::fs::read_dir(&dir)?.collect::<std::io::Result<Vec<_>>>()?;
+        children.sort_by_key(|child| child.file_name());
+
+        let mut added = 0;
+        for child in children {
+            let file_name = child.file_name();
+            let file_name = file_name.to_str().ok_or_else(|| {
+                SZipError::InvalidFormat(format!("File name {:?} is not valid UTF-8", child.path()))
+            })?;
+            let path = format!("{}{}", rel, file_name);
+            let file_type = child.file_type()?;
+            if !(file_type.is_dir() || file_type.is_file())
+                || ignore.matches(&path, file_type.is_dir())
+            {
+                continue;
+            }
+
+            if file_type.is_dir() {
+                let options = crate::EntryOptions::from_metadata(&child.metadata()?);
+                self.start_entry_with_options(&format!("{}{}/", prefix, path), options)?;
+                added += self.add_dir_entries(root, &format!("{}/", path), prefix, ignore)?;
+            } else {
+                self.write_file(&format!("{}{}", prefix, path), child.path())?;
+                added += 1;
+            }
+        }
+        Ok(added)
+    }
+
+    /// Copy `entry` from `reader` without decompressing it
+    ///
+    /// The stored data, compressed and possibly encrypted, is streamed
+    /// through unchanged under a new local header. The entry keeps its
+    /// timestamp, attributes and extra fields; entry comments aren't kept.
+    /// The name policy applies as for new entries, but compression and
+    /// encryption settings don't. With a manifest or signature enabled, the
+    /// entry is also decompressed from `reader` once to hash it, which needs
+    /// the reader's password for encrypted entries.
+    ///
+    /// ```no_run
+    /// use s_zip::{StreamingZipReader, StreamingZipWriter};
+    ///
+    /// let mut reader = StreamingZipReader::open("photos.zip")?;
+    /// let mut writer = StreamingZipWriter::new("jpegs.zip")?;
+    /// for entry in reader.entries().to_vec() {
+    ///     if entry.name.ends_with(".jpg") {
+    ///         writer.copy_entry(&mut reader, &entry)?;
+    ///     }
+    /// }
+    /// writer.finish()?;
+    /// # Ok::<(), s_zip::SZipError>(())
+    /// ```
+    pub fn copy_entry<R: Read + Seek>(
+        &mut self,
+        reader: &mut StreamingZipReader<R>,
+        entry: &crate::ZipEntry,
+    ) -> Result<()> {
+        crate::trace!(entry = entry.name.as_str(), "copy_entry");
+        crate::deadline::check(self.deadline)?;
+        let name = self.names.entry_name(&entry.name, &mut self.hooks)?;
+        self.finish_current_entry()?;
+
+        let started = Instant::now();
+        #[cfg(feature = "manifest")]
+        if let Some(digests) = &self.digests {
+            digests.add_copied(&name, reader, entry)?;
+        }
+        let (local_extra, mut data) = reader.raw_entry(entry)?;
+        let (header, flags) = copied_header(entry, &name, &local_extra)?;
+        let descriptor = flags & raw::FLAG_DATA_DESCRIPTOR != 0;
+        if let Some(volumes) = self.volumes {
+            volumes(&mut self.output).reserve(header.len() as u64)?;
+        }
+        let local_header_offset = self.output.stream_position()?;
+        self.output.write_all(&header)?;
+        let copied = std::io::copy(&mut data, &mut self.output)?;
+        if copied != entry.compressed_size {
+            return Err(SZipError::InvalidFormat(format!(
+                "Entry '{}' ends after {} of {} bytes",
+                entry.name, copied, entry.compressed_size
+            )));
+        }
+        if descriptor {
+            raw::write_data_descriptor(
+                &mut self.output,
+                entry.crc32,
+                entry.compressed_size,
+                entry.uncompressed_size,
+            )?;
+        }
+
+        self.hooks.entry_finished(&EntrySummary {
+            name: &name,
+            offset: local_header_offset,
+            crc32: entry.crc32,
+            compressed_size: entry.compressed_size,
+            uncompressed_size: entry.uncompressed_size,
+            compression_method: entry.compression_method,
+            elapsed: started.elapsed(),
+        })?;
+        self.entries.push(ZipEntry {
+            name,
+            local_header_offset,
+            crc32: entry.crc32,
+            compressed_size: entry.compressed_size,
+            uncompressed_size: entry.uncompressed_size,
+            compression_method: entry.compression_method,
+            flags,
+            last_modified: entry.last_modified,
+            external_attrs: entry.external_attrs,
+            extended_timestamp: ExtendedTimestamp::default(),
+            #[cfg(feature = "encryption")]
+            encryption_strength: None,
+            copied: Some(CopiedRecord::new(entry)),
+        });
+        Ok(())
+    }
+
     /// Number of entries fully written so far.
     ///
     /// Includes any in-progress entry (started but not yet followed by
@@ -421,18 +1565,17 @@ impl<W: Write + Seek> StreamingZipWriter<W> {
     /// Start a new entry with file metadata (modification time and Unix permissions).
     ///
     /// This is the recommended method when writing files that should preserve their
-    /// original timestamps and permissions. Entries created with `start_entry()` have
-    /// zero timestamps and no permission bits.
+    /// original timestamps and permissions. Entries created with `start_entry()` are
+    /// stamped with the current time and carry no permission bits.
     ///
     /// # Example
     /// ```no_run
     /// # use s_zip::{StreamingZipWriter, EntryOptions};
     /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
     /// let mut writer = StreamingZipWriter::new("output.zip")?;
-    /// let opts = EntryOptions {
-    ///     mtime: Some(std::time::SystemTime::now()),
-    ///     unix_mode: Some(0o644),
-    /// };
+    /// let opts = EntryOptions::default()
+    ///     .with_mtime(std::time::SystemTime::now())
+    ///     .with_unix_mode(0o644);
     /// writer.start_entry_with_options("readme.txt", opts)?;
     /// writer.write_data(b"Hello")?;
     /// writer.finish()?;
@@ -471,92 +1614,240 @@ impl<W: Write + Seek> StreamingZipWriter<W> {
         self.start_entry_with_options_and_hint(name, crate::EntryOptions::default(), size_hint)
     }
 
+    /// Add the file at `path` as entry `name`, streaming it in chunks.
+    ///
+    /// The modification time (and Unix mode on Unix) come from the file
+    /// system, and the file size is used as the size hint.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use s_zip::StreamingZipWriter;
+    /// # use std::path::Path;
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut writer = StreamingZipWriter::new("output.zip")?;
+    /// writer.write_file("bin/run.sh", Path::new("scripts/run.sh"))?;
+    /// writer.finish()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn write_file<P: AsRef<Path>>(&mut self, name: &str, path: P) -> Result<()> {
+        let mut file = File::open(path)?;
+        let metadata = file.metadata()?;
+        self.start_entry_with_options_and_hint(
+            name,
+            crate::EntryOptions::from_metadata(&metadata),
+            Some(metadata.len()),
+        )?;
+        let mut buf = vec![0u8; 64 * 1024];
+        loop {
+            let n = file.read(&mut buf)?;
+            if n == 0 {
+                break;
+            }
+            self.write_data(&buf[..n])?;
+        }
+        Ok(())
+    }
+
     fn start_entry_with_options_and_hint(
         &mut self,
         name: &str,
-        options: crate::EntryOptions,
+        mut options: crate::EntryOptions,
         size_hint: Option<u64>,
     ) -> Result<()> {
+        crate::deadline::check(self.deadline)?;
+        // Matched on the normalized name, so `./__MACOSX/x` and `dir\._x` are caught too
+        self.skipping_entry = self.omit_macos_metadata
+            && crate::format::is_macos_metadata(&crate::format::normalize_entry_name(name));
+        if self.skipping_entry {
+            return self.finish_current_entry();
+        }
+        let name = self.names.entry_name(name, &mut self.hooks)?;
+
         // Finish previous entry if any
         self.finish_current_entry()?;
 
-        let local_header_offset = self.output.stream_position()?;
-        let compression_method = self.compression_method.to_zip_method();
+        let explorer = self.compatibility == Compatibility::WindowsExplorer;
+        // Directories have no data to compress or encrypt
+        let directory = name.ends_with('/');
+        let (method, level) = match self.compression_method {
+            _ if directory => (CompressionMethod::Stored, 0),
+            _ if self.auto_store && crate::autostore::stored_by_name(&name) => {
+                (CompressionMethod::Stored, 0)
+            }
+            #[cfg(feature = "zstd-support")]
+            CompressionMethod::Zstd if explorer => {
+                (CompressionMethod::Deflate, self.compression_level.min(9))
+            }
+            #[cfg(feature = "bzip2")]
+            CompressionMethod::Bzip2 if explorer => {
+                (CompressionMethod::Deflate, self.compression_level.min(9))
+            }
+            #[cfg(feature = "lzma")]
+            CompressionMethod::Lzma | CompressionMethod::Xz if explorer => {
+                (CompressionMethod::Deflate, self.compression_level.min(9))
+            }
+            method => (method, self.compression_level),
+        };
+        if explorer {
+            options.dos_attributes.get_or_insert(crate::DosAttributes {
+                directory,
+                archive: !directory,
+                ..Default::default()
+            });
+        }
+        if self.compatibility == Compatibility::MacOs {
+            // The file type is added along with the external attributes
+            options
+                .unix_mode
+                .get_or_insert(if directory { 0o755 } else { 0o644 });
+            if directory {
+                options.dos_attributes.get_or_insert(crate::DosAttributes {
+                    directory,
+                    ..Default::default()
+                });
+            }
+        }
+
+        let started = Instant::now();
+        let compression_method = method.to_zip_method();
 
         // Check if encryption is enabled
         #[cfg(feature = "encryption")]
-        let (encryptor, encryption_flag) = if let Some(ref password) = self.password {
-            let enc = AesEncryptor::new(password, self.encryption_strength)?;
-            (Some(enc), 0x01) // bit 0 set for encryption
-        } else {
-            (None, 0x00)
-        };
+        let (encryptor, encryption_flag) =
+            if let Some(password) = self.password.as_ref().filter(|_| !directory) {
+                let enc = AesEncryptor::new(password, self.encryption_strength)?;
+                (Some(enc), 0x01) // bit 0 set for encryption
+            } else {
+                (None, 0x00)
+            };
 
         #[cfg(not(feature = "encryption"))]
         let encryption_flag = 0x00;
 
-        // Write local file header with data descriptor flag (bit 3) + encryption flag (bit 0)
-        self.output.write_all(&[0x50, 0x4b, 0x03, 0x04])?; // signature
-        self.output.write_all(&[51, 0])?; // version needed (5.1 for AES)
-        self.output.write_all(&[8 | encryption_flag, 0])?; // general purpose bit flag
-        self.output.write_all(&compression_method.to_le_bytes())?; // compression method
-
-        // MS-DOS timestamp (time, date)
-        let (dos_time, dos_date) = options.msdos_datetime();
-        self.output.write_all(&dos_time.to_le_bytes())?;
-        self.output.write_all(&dos_date.to_le_bytes())?;
-
-        self.output.write_all(&0u32.to_le_bytes())?; // crc32 placeholder
-        self.output.write_all(&0u32.to_le_bytes())?; // compressed size placeholder
-        self.output.write_all(&0u32.to_le_bytes())?; // uncompressed size placeholder
-        self.output.write_all(&(name.len() as u16).to_le_bytes())?;
-
         // Extra field: AES (11 bytes) + Unix permissions (15 bytes) if set
-        let unix_extra = options.unix_extra_field();
-        #[cfg(feature = "encryption")]
-        let extra_len = if encryptor.is_some() { 11 } else { 0 } + unix_extra.len();
-        #[cfg(not(feature = "encryption"))]
-        let extra_len = unix_extra.len();
-
-        self.output.write_all(&(extra_len as u16).to_le_bytes())?; // extra len
-        self.output.write_all(name.as_bytes())?;
-
-        // Write AES extra field if encryption is enabled
+        #[cfg_attr(not(feature = "encryption"), allow(unused_mut))]
+        let mut extra: Vec<u8> = Vec::new();
         #[cfg(feature = "encryption")]
         if let Some(ref enc) = encryptor {
             // AES extra field header (0x9901)
             // Format per WinZip AE-2 spec:
             //   ID(2) + Length(2) + Version(2) + Vendor(2) + Strength(1) + ActualCompression(2) = 7 bytes data
-            self.output.write_all(&[0x01, 0x99])?; // WinZip AES encryption marker
-            self.output.write_all(&[7, 0])?; // data size (7 bytes)
-            self.output.write_all(&[2, 0])?; // AE-2 format version
-            self.output.write_all(&[0x41, 0x45])?; // vendor ID "AE"
-            self.output
-                .write_all(&[enc.strength().to_winzip_code() as u8])?; // strength (1 byte!)
-            self.output.write_all(&compression_method.to_le_bytes())?; // actual compression (2 bytes)
+            extra.extend_from_slice(&[0x01, 0x99]); // WinZip AES encryption marker
+            extra.extend_from_slice(&[7, 0]); // data size (7 bytes)
+            extra.extend_from_slice(&[2, 0]); // AE-2 format version
+            extra.extend_from_slice(&[0x41, 0x45]); // vendor ID "AE"
+            extra.push(enc.strength().to_winzip_code() as u8); // strength (1 byte!)
+            extra.extend_from_slice(&compression_method.to_le_bytes()); // actual compression (2 bytes)
+        }
+        extra.extend_from_slice(&options.unix_extra_field());
+        extra.extend_from_slice(&options.extended_timestamp().to_extra_field(false));
+
+        // Write local file header with data descriptor flag (bit 3) + encryption flag (bit 0).
+        // CRC-32 and sizes are zero here and follow in the data descriptor, or are
+        // patched into the header when data descriptors are avoided.
+        let mut flags = encryption_flag | raw::name_flags(&name) | method.flags();
+        if !explorer {
+            flags |= raw::FLAG_DATA_DESCRIPTOR;
+        }
+
+        // Sizes follow the data; a hint past 4 GiB announces ZIP64 up front
+        let large = size_hint.is_some_and(|n| n > u32::MAX as u64);
+        let mut version_needed = raw::version_needed(compression_method, encryption_flag != 0);
+        if large {
+            version_needed = version_needed.max(raw::VERSION_ZIP64);
+        }
+        // Oversized placeholders make the record writer reserve a ZIP64 extra field
+        let zip64_header = explorer && large;
+        let placeholder = if zip64_header { u64::MAX } else { 0 };
+        let (dos_time, dos_date) = options.msdos_datetime();
+        let mut header = Vec::with_capacity(30 + name.len() + extra.len());
+        raw::write_local_header(
+            &mut header,
+            &LocalFileHeader {
+                version_needed: self.version_needed_for(version_needed),
+                flags,
+                compression_method,
+                last_mod_time: dos_time,
+                last_mod_date: dos_date,
+                compressed_size: placeholder,
+                uncompressed_size: placeholder,
+                name: name.as_bytes(),
+                extra: &extra,
+                ..Default::default()
+            },
+        )?;
 
-            // Write salt and password verification
-            self.output.write_all(enc.salt())?;
-            self.output.write_all(enc.password_verify())?;
+        // Salt and password verification follow the header
+        #[cfg_attr(not(feature = "encryption"), allow(unused_mut))]
+        let mut preamble = Vec::new();
+        #[cfg(feature = "encryption")]
+        if let Some(ref enc) = encryptor {
+            preamble.extend_from_slice(enc.salt());
+            preamble.extend_from_slice(enc.password_verify());
         }
+        // With auto-store the offset is known once the header is written
+        let (local_header_offset, undecided) =
+            if self.auto_store && method != CompressionMethod::Stored {
+                let sample = Vec::new();
+                (
+                    0,
+                    Some(Undecided {
+                        header,
+                        preamble,
+                        sample,
+                    }),
+                )
+            } else {
+                (self.write_entry_header(&name, &header, &preamble)?, None)
+            };
 
         // Create encoder for this entry based on compression method
         // Use adaptive buffer if size hint is provided
-        let encoder: Box<dyn CompressorWrite> = match self.compression_method {
+        let encoder: Box<dyn CompressorWrite> = match method {
+            CompressionMethod::Deflate if self.deflate_threads > 1 => {
+                Box::new(ParallelDeflateCompressor::new(
+                    Compression::new(level),
+                    self.deflate_threads,
+                    CompressedBuffer::with_size_hint(size_hint),
+                ))
+            }
             CompressionMethod::Deflate => Box::new(DeflateCompressor {
                 encoder: DeflateEncoder::new(
                     CompressedBuffer::with_size_hint(size_hint),
-                    Compression::new(self.compression_level),
+                    Compression::new(level),
                 ),
             }),
             #[cfg(feature = "zstd-support")]
             CompressionMethod::Zstd => {
-                let mut encoder = zstd::Encoder::new(
+                let dictionary = self
+                    .zstd_dictionary
+                    .as_ref()
+                    .map(|(dictionary, _)| dictionary);
+                Box::new(ZstdCompressor {
+                    encoder: self.zstd_options.encoder(
+                        CompressedBuffer::with_size_hint(size_hint),
+                        level as i32,
+                        dictionary,
+                    )?,
+                    multithreaded: self.zstd_options.workers > 0,
+                })
+            }
+            #[cfg(feature = "bzip2")]
+            CompressionMethod::Bzip2 => Box::new(Bzip2Compressor {
+                encoder: bzip2::write::BzEncoder::new(
                     CompressedBuffer::with_size_hint(size_hint),
-                    self.compression_level as i32,
-                )?;
-                encoder.include_checksum(false)?; // ZIP uses CRC32, not zstd checksum
-                Box::new(ZstdCompressor { encoder })
+                    bzip2::Compression::new(level.clamp(1, 9)),
+                ),
+            }),
+            #[cfg(feature = "lzma")]
+            CompressionMethod::Lzma | CompressionMethod::Xz => {
+                let (header, stream) = crate::lzma::encoder(compression_method, level)?;
+                let mut buffer = CompressedBuffer::with_size_hint(size_hint);
+                buffer.write_all(&header)?;
+                Box::new(LzmaCompressor {
+                    encoder: liblzma::write::XzEncoder::new_stream(buffer, stream),
+                })
             }
             CompressionMethod::Stored => {
                 // Stored method: no compression, pass through data
@@ -582,6 +1873,13 @@ impl<W: Write + Seek> StreamingZipWriter<W> {
             encoder,
             counter,
             compression_method,
+            flags,
+            zip64_header,
+            undecided,
+            last_modified: DosDateTime::new(dos_time, dos_date),
+            external_attrs: options.external_attrs(name.ends_with('/')),
+            extended_timestamp: options.extended_timestamp(),
+            started,
             #[cfg(feature = "encryption")]
             encryptor,
         });
@@ -592,10 +1890,71 @@ impl<W: Write + Seek> StreamingZipWriter<W> {
     /// Write uncompressed data to current entry (will be compressed and/or encrypted on-the-fly)
     pub fn write_data(&mut self, data: &[u8]) -> Result<()> {
         crate::trace!(bytes = data.len(), "write_data");
+        crate::deadline::check(self.deadline)?;
+        if self.skipping_entry {
+            return Ok(());
+        }
         let entry = self
             .current_entry
             .as_mut()
             .ok_or_else(|| SZipError::InvalidFormat("No entry started".to_string()))?;
+        if let Some(undecided) = entry.undecided.as_mut() {
+            undecided.sample.extend_from_slice(data);
+            if undecided.sample.len() >= crate::autostore::SAMPLE_LEN {
+                self.choose_method()?;
+            }
+            return Ok(());
+        }
+        self.compress_data(data)
+    }
+
+    /// Write an entry's local header and the preamble of encrypted data,
+    /// returning the header's offset
+    fn write_entry_header(&mut self, name: &str, header: &[u8], preamble: &[u8]) -> Result<u64> {
+        if let Some(volumes) = self.volumes {
+            // Keep the header within one volume
+            volumes(&mut self.output).reserve(header.len() as u64)?;
+        }
+        let offset = self.output.stream_position()?;
+        self.output.write_all(header)?;
+        self.hooks.header_written(name, offset, header)?;
+        self.output.write_all(preamble)?;
+        Ok(offset)
+    }
+
+    /// Settle the method of an entry held back by auto-store, then write its
+    /// header and the data sampled so far
+    fn choose_method(&mut self) -> Result<()> {
+        let Some(entry) = self.current_entry.as_mut() else {
+            return Ok(());
+        };
+        let Some(mut undecided) = entry.undecided.take() else {
+            return Ok(());
+        };
+        if crate::autostore::incompressible(&undecided.sample) {
+            crate::autostore::patch_header(&mut undecided.header, self.version_needed.is_some());
+            entry.compression_method = CompressionMethod::Stored.to_zip_method();
+            entry.flags &= !crate::autostore::METHOD_FLAGS;
+            entry.encoder = Box::new(StoredCompressor {
+                buffer: CompressedBuffer::new(),
+            });
+        }
+        let name = entry.name.clone();
+        let offset = self.write_entry_header(&name, &undecided.header, &undecided.preamble)?;
+        if let Some(entry) = self.current_entry.as_mut() {
+            entry.local_header_offset = offset;
+        }
+        self.compress_data(&undecided.sample)
+    }
+
+    /// Compress (and encrypt) data of the current entry, whose header is
+    /// written
+    fn compress_data(&mut self, data: &[u8]) -> Result<()> {
+        let Some(entry) = self.current_entry.as_mut() else {
+            return Err(SZipError::InvalidFormat("No entry started".to_string()));
+        };
+
+        self.hooks.data_written(&entry.name, data)?;
 
         // Update CRC and size with uncompressed data
         entry.counter.update_uncompressed(data);
@@ -614,7 +1973,11 @@ impl<W: Write + Seek> StreamingZipWriter<W> {
 
         // Check if buffer should be flushed to output
         let buffer = entry.encoder.get_buffer_mut();
-        if buffer.should_flush() {
+        let over_budget = self
+            .memory
+            .as_mut()
+            .is_some_and(|memory| !memory.try_resize(buffer.buffer.len()));
+        if buffer.should_flush() || over_budget {
             // Flush buffer to output to keep memory usage low
             let compressed_data = buffer.take();
 
@@ -633,6 +1996,9 @@ impl<W: Write + Seek> StreamingZipWriter<W> {
 
             self.output.write_all(&data_to_write)?;
             entry.counter.add_compressed(data_to_write.len() as u64);
+            if let Some(memory) = self.memory.as_mut() {
+                memory.try_resize(0);
+            }
         }
 
         Ok(())
@@ -640,6 +2006,7 @@ impl<W: Write + Seek> StreamingZipWriter<W> {
 
     /// Finish current entry and write data descriptor
     fn finish_current_entry(&mut self) -> Result<()> {
+        self.choose_method()?;
         if let Some(mut entry) = self.current_entry.take() {
             // Finish compression and get remaining buffered data
             let mut buffer = entry.encoder.finish_compression()?;
@@ -664,6 +2031,10 @@ impl<W: Write + Seek> StreamingZipWriter<W> {
                 entry.counter.add_compressed(data_to_write.len() as u64);
             }
 
+            if let Some(memory) = self.memory.as_mut() {
+                memory.try_resize(0);
+            }
+
             // Write authentication code for AES encryption
             #[cfg(feature = "encryption")]
             let (encryption_strength_code, auth_code_size) =
@@ -683,21 +2054,34 @@ impl<W: Write + Seek> StreamingZipWriter<W> {
             let compressed_size = entry.counter.compressed_count + auth_code_size;
             let uncompressed_size = entry.counter.uncompressed_count;
 
-            // Write data descriptor
-            // signature
-            self.output.write_all(&[0x50, 0x4b, 0x07, 0x08])?;
-            self.output.write_all(&crc.to_le_bytes())?;
-            // If sizes exceed 32-bit, write 64-bit sizes (ZIP64 data descriptor)
-            if compressed_size > u32::MAX as u64 || uncompressed_size > u32::MAX as u64 {
-                self.output.write_all(&compressed_size.to_le_bytes())?;
-                self.output.write_all(&uncompressed_size.to_le_bytes())?;
+            if entry.flags & raw::FLAG_DATA_DESCRIPTOR != 0 {
+                raw::write_data_descriptor(
+                    &mut self.output,
+                    crc,
+                    compressed_size,
+                    uncompressed_size,
+                )?;
             } else {
-                self.output
-                    .write_all(&(compressed_size as u32).to_le_bytes())?;
-                self.output
-                    .write_all(&(uncompressed_size as u32).to_le_bytes())?;
+                self.patch_local_header(
+                    &entry.name,
+                    entry.local_header_offset,
+                    entry.zip64_header,
+                    crc,
+                    compressed_size,
+                    uncompressed_size,
+                )?;
             }
 
+            self.hooks.entry_finished(&EntrySummary {
+                name: &entry.name,
+                offset: entry.local_header_offset,
+                crc32: crc,
+                compressed_size,
+                uncompressed_size,
+                compression_method: entry.compression_method,
+                elapsed: entry.started.elapsed(),
+            })?;
+
             // Save entry info for central directory
             self.entries.push(ZipEntry {
                 name: entry.name,
@@ -706,63 +2090,121 @@ impl<W: Write + Seek> StreamingZipWriter<W> {
                 compressed_size,
                 uncompressed_size,
                 compression_method: entry.compression_method,
+                flags: entry.flags,
+                last_modified: entry.last_modified,
+                external_attrs: entry.external_attrs,
+                extended_timestamp: entry.extended_timestamp,
                 #[cfg(feature = "encryption")]
                 encryption_strength: encryption_strength_code,
+                copied: None,
             });
         }
         Ok(())
     }
 
+    /// Write CRC-32 and sizes into the local header at `header_offset`, then return to the end
+    fn patch_local_header(
+        &mut self,
+        name: &str,
+        header_offset: u64,
+        zip64_header: bool,
+        crc: u32,
+        compressed_size: u64,
+        uncompressed_size: u64,
+    ) -> Result<()> {
+        let overflow = compressed_size > u32::MAX as u64 || uncompressed_size > u32::MAX as u64;
+        if overflow && !zip64_header {
+            return Err(SZipError::InvalidFormat(format!(
+                "Entry '{}' exceeds 4 GiB; pass a size hint above 4 GiB to reserve ZIP64 space",
+                name
+            )));
+        }
+
+        let end = self.output.stream_position()?;
+        self.output.seek(SeekFrom::Start(header_offset + 14))?;
+        self.output.write_all(&crc.to_le_bytes())?;
+        if zip64_header {
+            // Sizes stay 0xFFFFFFFF; the ZIP64 extra field leads the extra data
+            let extra_start = header_offset + 30 + name.len() as u64;
+            self.output.seek(SeekFrom::Start(extra_start + 4))?;
+            self.output.write_all(&uncompressed_size.to_le_bytes())?;
+            self.output.write_all(&compressed_size.to_le_bytes())?;
+        } else {
+            self.output
+                .write_all(&(compressed_size as u32).to_le_bytes())?;
+            self.output
+                .write_all(&(uncompressed_size as u32).to_le_bytes())?;
+        }
+        self.output.seek(SeekFrom::Start(end))?;
+        Ok(())
+    }
+
     /// Finish ZIP file (write central directory and return the writer)
     pub fn finish(mut self) -> Result<W> {
         crate::trace!(entries = self.entries.len(), "finish");
+        crate::deadline::check(self.deadline)?;
         self.finish_current_entry()?;
 
-        let central_dir_offset = self.output.stream_position()?;
-
-        // Write central directory
-        for entry in &self.entries {
-            self.output.write_all(&[0x50, 0x4b, 0x01, 0x02])?; // central dir sig
-            self.output.write_all(&[20, 0])?; // version made by
-            self.output.write_all(&[20, 0])?; // version needed
-
-            // Set encryption flag (bit 0) if entry was encrypted
-            #[cfg(feature = "encryption")]
-            let flags = if entry.encryption_strength.is_some() {
-                0x08 | 0x01 // bit 3 (data descriptor) + bit 0 (encryption)
-            } else {
-                0x08 // bit 3 only (data descriptor)
-            };
-            #[cfg(not(feature = "encryption"))]
-            let flags = 0x08;
-
-            self.output.write_all(&[flags, 0])?; // general purpose bit flag
-            self.output
-                .write_all(&entry.compression_method.to_le_bytes())?; // compression method
-            self.output.write_all(&[0, 0, 0, 0])?; // mod time/date
-            self.output.write_all(&entry.crc32.to_le_bytes())?;
+        // Stored, as it is read before anything can be decompressed
+        #[cfg(feature = "zstd-support")]
+        if let Some((dictionary, true)) = self.zstd_dictionary.clone() {
+            let method = std::mem::replace(&mut self.compression_method, CompressionMethod::Stored);
+            self.add_entry(DICTIONARY_ENTRY, dictionary.as_bytes())?;
+            self.finish_current_entry()?;
+            self.compression_method = method;
+        }
 
-            // Write sizes (32-bit placeholders or actual values)
-            if entry.compressed_size > u32::MAX as u64 {
-                self.output.write_all(&0xFFFFFFFFu32.to_le_bytes())?;
-            } else {
-                self.output
-                    .write_all(&(entry.compressed_size as u32).to_le_bytes())?;
-            }
+        #[cfg(feature = "manifest")]
+        if self.write_manifest {
+            let digests = self.digests.clone().unwrap_or_default();
+            let manifest = digests.manifest(&self.external_attrs())?;
+            self.add_entry(MANIFEST_ENTRY, &manifest.to_bytes()?)?;
+            self.finish_current_entry()?;
+        }
 
-            if entry.uncompressed_size > u32::MAX as u64 {
-                self.output.write_all(&0xFFFFFFFFu32.to_le_bytes())?;
-            } else {
-                self.output
-                    .write_all(&(entry.uncompressed_size as u32).to_le_bytes())?;
+        #[cfg_attr(not(feature = "signing"), allow(unused_mut))]
+        let mut comment = std::mem::take(&mut self.comment);
+        #[cfg(feature = "signing")]
+        if let Some((signer, placement)) = self.signature.take() {
+            let digests = self.digests.clone().unwrap_or_default();
+            let manifest = digests.manifest(&self.external_attrs())?;
+            let block = crate::signing::seal(signer.as_ref(), &manifest.to_bytes()?)?;
+            match placement {
+                SignaturePlacement::Entry => {
+                    self.add_entry(crate::signing::SIGNATURE_ENTRY, &block)?;
+                    self.finish_current_entry()?;
+                }
+                SignaturePlacement::Comment if !comment.is_empty() => {
+                    return Err(SZipError::InvalidFormat(
+                        "An archive comment can't be set when the signature is stored in the comment"
+                            .to_string(),
+                    ))
+                }
+                SignaturePlacement::Comment => comment = block,
             }
+        }
 
-            self.output
-                .write_all(&(entry.name.len() as u16).to_le_bytes())?;
+        let mut central_dir_offset = self.output.stream_position()?;
 
-            // Prepare extra fields
+        // Write central directory
+        let mut central_dir_size = 0u64;
+        let mut entry_count = self.entries.len() as u64;
+        // Highest version needed over all records, for the ZIP64 EOCD record
+        let mut max_version_needed = 0;
+        if let Some(existing) = &self.existing {
+            // Existing entries haven't moved, so their records are unchanged
+            self.output.write_all(&existing.records)?;
+            central_dir_size += existing.records.len() as u64;
+            entry_count += existing.count();
+            max_version_needed = existing.version_needed;
+        }
+        // Records on the volume being written, for the EOCD record
+        let mut records_on_disk = (0u32, 0u64);
+        for (i, entry) in self.entries.iter().enumerate() {
             let mut extra_field: Vec<u8> = Vec::new();
-
+            if let Some(copied) = &entry.copied {
+                extra_field.extend_from_slice(&copied.extra);
+            }
             // Add AES extra field if entry was encrypted
             #[cfg(feature = "encryption")]
             if let Some(strength_code) = entry.encryption_strength {
@@ -775,128 +2217,113 @@ impl<W: Write + Seek> StreamingZipWriter<W> {
                 extra_field.extend_from_slice(&entry.compression_method.to_le_bytes());
                 // actual compression
             }
-
-            // Add ZIP64 extra field if needed
-            if entry.uncompressed_size > u32::MAX as u64
-                || entry.compressed_size > u32::MAX as u64
-                || entry.local_header_offset > u32::MAX as u64
-            {
-                // ZIP64 extra header ID 0x0001
-                extra_field.extend_from_slice(&0x0001u16.to_le_bytes());
-                // data size: we'll include uncompressed (8) if needed, compressed (8) if needed, and offset (8) if needed
-                let mut data: Vec<u8> = Vec::new();
-                if entry.uncompressed_size > u32::MAX as u64 {
-                    data.extend_from_slice(&entry.uncompressed_size.to_le_bytes());
-                }
-                if entry.compressed_size > u32::MAX as u64 {
-                    data.extend_from_slice(&entry.compressed_size.to_le_bytes());
-                }
-                if entry.local_header_offset > u32::MAX as u64 {
-                    data.extend_from_slice(&entry.local_header_offset.to_le_bytes());
-                }
-                extra_field.extend_from_slice(&(data.len() as u16).to_le_bytes());
-                extra_field.extend_from_slice(&data);
+            if entry.copied.is_none() {
+                extra_field.extend_from_slice(&entry.extended_timestamp.to_extra_field(true));
             }
-
-            self.output
-                .write_all(&(extra_field.len() as u16).to_le_bytes())?; // extra len
-            self.output.write_all(&0u16.to_le_bytes())?; // file comment len
-            self.output.write_all(&0u16.to_le_bytes())?; // disk number start
-            self.output.write_all(&0u16.to_le_bytes())?; // internal attrs
-            self.output.write_all(&0u32.to_le_bytes())?; // external attrs
-
-            // local header offset (32-bit or 0xFFFFFFFF)
-            if entry.local_header_offset > u32::MAX as u64 {
-                self.output.write_all(&0xFFFFFFFFu32.to_le_bytes())?;
-            } else {
-                self.output
-                    .write_all(&(entry.local_header_offset as u32).to_le_bytes())?;
+            let (version_made_by, version_needed, internal_attrs) = match &entry.copied {
+                Some(copied) => (
+                    copied.version_made_by,
+                    copied.version_needed,
+                    copied.internal_attrs,
+                ),
+                None => (
+                    self.version_made_by
+                        .unwrap_or(raw::version_made_by(entry.external_attrs)),
+                    self.version_needed_for(raw::version_needed(
+                        entry.compression_method,
+                        entry.flags & raw::FLAG_ENCRYPTED != 0,
+                    )),
+                    0,
+                ),
+            };
+            max_version_needed = max_version_needed.max(version_needed);
+
+            let mut disk_number_start = 0;
+            let mut local_header_offset = entry.local_header_offset;
+            if let Some(volumes) = self.volumes {
+                // Room for the record with a ZIP64 extra field
+                let len = 46 + entry.name.len() + extra_field.len() + 28;
+                volumes(&mut self.output).reserve(len as u64)?;
+                let position = self.output.stream_position()?;
+                if i == 0 {
+                    central_dir_offset = position;
+                }
+                let output = volumes(&mut self.output);
+                (disk_number_start, local_header_offset) = output.locate(local_header_offset);
+                let (disk, _) = output.locate(position);
+                records_on_disk = match records_on_disk {
+                    (d, n) if d == disk => (d, n + 1),
+                    _ => (disk, 1),
+                };
             }
 
-            self.output.write_all(entry.name.as_bytes())?;
-            if !extra_field.is_empty() {
-                self.output.write_all(&extra_field)?;
-            }
+            // ZIP64 extra field is added by the record writer when needed
+            central_dir_size += raw::write_central_record(
+                &mut self.output,
+                &CentralDirectoryRecord {
+                    version_made_by,
+                    version_needed,
+                    flags: entry.flags,
+                    compression_method: entry.compression_method,
+                    crc32: entry.crc32,
+                    compressed_size: entry.compressed_size,
+                    uncompressed_size: entry.uncompressed_size,
+                    last_mod_time: entry.last_modified.time,
+                    last_mod_date: entry.last_modified.date,
+                    local_header_offset,
+                    disk_number_start,
+                    internal_attrs,
+                    external_attrs: entry.external_attrs,
+                    name: entry.name.as_bytes(),
+                    extra: &extra_field,
+                    ..Default::default()
+                },
+            )?;
         }
 
-        let central_dir_size = self.output.stream_position()? - central_dir_offset;
-
-        // Determine if we need ZIP64 EOCD
-        let need_zip64 = self.entries.len() > u16::MAX as usize
-            || central_dir_size > u32::MAX as u64
-            || central_dir_offset > u32::MAX as u64;
-
-        if need_zip64 {
-            // Write ZIP64 End of Central Directory Record
-            // signature
-            self.output.write_all(&[0x50, 0x4b, 0x06, 0x06])?; // 0x06064b50
-                                                               // size of zip64 eocd record (size of remaining fields)
-                                                               // We'll write fixed-size fields: version made by(2)+version needed(2)+disk numbers(4+4)+entries on disk(8)+total entries(8)+cd size(8)+cd offset(8)
-            let zip64_eocd_size: u64 = 44;
-            self.output.write_all(&zip64_eocd_size.to_le_bytes())?;
-            // version made by, version needed
-            self.output.write_all(&[20, 0])?;
-            self.output.write_all(&[20, 0])?;
-            // disk number, disk where central dir starts
-            self.output.write_all(&0u32.to_le_bytes())?;
-            self.output.write_all(&0u32.to_le_bytes())?;
-            // entries on this disk (8)
-            self.output
-                .write_all(&(self.entries.len() as u64).to_le_bytes())?;
-            // total entries (8)
-            self.output
-                .write_all(&(self.entries.len() as u64).to_le_bytes())?;
-            // central directory size (8)
-            self.output.write_all(&central_dir_size.to_le_bytes())?;
-            // central directory offset (8)
-            self.output.write_all(&central_dir_offset.to_le_bytes())?;
-
-            // Write ZIP64 EOCD locator
-            // signature
-            self.output.write_all(&[0x50, 0x4b, 0x06, 0x07])?; // 0x07064b50
-                                                               // disk with ZIP64 EOCD (4)
-            self.output.write_all(&0u32.to_le_bytes())?;
-            // relative offset of ZIP64 EOCD (8)
-            let zip64_eocd_pos = central_dir_offset + central_dir_size; // directly after central dir
-            self.output.write_all(&zip64_eocd_pos.to_le_bytes())?;
-            // total number of disks
-            self.output.write_all(&0u32.to_le_bytes())?;
-        }
-
-        // Write end of central directory (classic)
-        self.output.write_all(&[0x50, 0x4b, 0x05, 0x06])?;
-        self.output.write_all(&0u16.to_le_bytes())?; // disk number
-        self.output.write_all(&0u16.to_le_bytes())?; // disk with central dir
-
-        // number of entries (16-bit or 0xFFFF if ZIP64 used)
-        if self.entries.len() > u16::MAX as usize {
-            self.output.write_all(&0xFFFFu16.to_le_bytes())?;
-            self.output.write_all(&0xFFFFu16.to_le_bytes())?;
-        } else {
-            self.output
-                .write_all(&(self.entries.len() as u16).to_le_bytes())?;
-            self.output
-                .write_all(&(self.entries.len() as u16).to_le_bytes())?;
+        // Write end of central directory (with ZIP64 record + locator if needed)
+        let mut eocd = EndOfCentralDirectory {
+            entry_count,
+            cd_size: central_dir_size,
+            cd_offset: central_dir_offset,
+            comment: &comment,
+            version_made_by: self.version_made_by.unwrap_or(raw::VERSION_MADE_BY),
+            version_needed: max_version_needed,
+            ..Default::default()
+        };
+        if let Some(volumes) = self.volumes {
+            // ZIP64 record and locator, then the EOCD record, on the last volume
+            volumes(&mut self.output).reserve(56 + 20 + 22 + comment.len() as u64)?;
+            let position = self.output.stream_position()?;
+            if self.entries.is_empty() {
+                central_dir_offset = position;
+            }
+            let output = volumes(&mut self.output);
+            let (disk, position) = output.locate(position);
+            (eocd.cd_disk, eocd.cd_offset) = output.locate(central_dir_offset);
+            eocd.disk_number = disk;
+            eocd.position = Some(position);
+            eocd.entries_on_disk = Some(match records_on_disk {
+                (d, n) if d == disk => n,
+                _ => 0,
+            });
         }
+        raw::write_eocd(&mut self.output, &eocd)?;
 
-        // central dir size and offset (32-bit or 0xFFFFFFFF)
-        if central_dir_size > u32::MAX as u64 {
-            self.output.write_all(&0xFFFFFFFFu32.to_le_bytes())?;
-        } else {
-            self.output
-                .write_all(&(central_dir_size as u32).to_le_bytes())?;
+        self.output.flush()?;
+        if let Some(volumes) = self.volumes {
+            volumes(&mut self.output).finish()?;
         }
-
-        if central_dir_offset > u32::MAX as u64 {
-            self.output.write_all(&0xFFFFFFFFu32.to_le_bytes())?;
-        } else {
-            self.output
-                .write_all(&(central_dir_offset as u32).to_le_bytes())?;
+        if let Some(truncate) = self.truncate {
+            let len = self.output.stream_position()?;
+            truncate(&mut self.output, len)?;
         }
-
-        self.output.write_all(&0u16.to_le_bytes())?; // comment len
-
-        self.output.flush()?;
+        self.hooks.archive_finished(&ArchiveSummary {
+            entry_count,
+            central_directory_offset: central_dir_offset,
+            central_directory_size: central_dir_size,
+            total_size: self.output.stream_position()?,
+        })?;
         Ok(self.output)
     }
 
@@ -0,0 +1,92 @@
+//! Zstandard dictionaries.
+//!
+//! Small entries compress poorly on their own: each one starts with an
+//! empty window. A dictionary trained on typical entries (say, a few
+//! hundred JSON log records) gives every entry that shared context up
+//! front, which often shrinks small, similar files several times over.
+//!
+//! `set_zstd_dictionary()` on a writer compresses every Zstd entry with the
+//! dictionary. With `embed` set, the dictionary is also stored in the
+//! archive as [`DICTIONARY_ENTRY`], and readers load it from there on their
+//! own; otherwise it has to be handed to the reader with its own
+//! `set_zstd_dictionary()`. Other tools can only read the entries if they
+//! are given the dictionary too. Forward-only readers don't support
+//! dictionaries.
+//!
+//! ```
+//! use s_zip::zstd_dict::ZstdDictionary;
+//! use s_zip::{CompressionMethod, StreamingZipReader, StreamingZipWriter};
+//! use std::io::Cursor;
+//!
+//! let records: Vec<String> = (0..1000)
+//!     .map(|i| format!(r#"{{"level":"info","service":"api","request":{i},"status":200}}"#))
+//!     .collect();
+//! let dictionary = ZstdDictionary::train(&records, 4096)?;
+//!
+//! let mut writer = StreamingZipWriter::from_writer_with_method(
+//!     Cursor::new(Vec::new()),
+//!     CompressionMethod::Zstd,
+//!     3,
+//! )?;
+//! writer.set_zstd_dictionary(dictionary, true);
+//! for (i, record) in records.iter().enumerate() {
+//!     writer.add_entry(&format!("logs/{i}.json"), record.as_bytes())?;
+//! }
+//! let zip = writer.finish()?.into_inner();
+//!
+//! let mut reader = StreamingZipReader::from_vec(zip)?;
+//! assert_eq!(reader.read_entry_by_name("logs/7.json")?, records[7].as_bytes());
+//! # Ok::<(), s_zip::SZipError>(())
+//! ```
+
+use crate::error::Result;
+use std::fmt;
+use std::sync::Arc;
+
+/// Name of the entry holding the dictionary when it is embedded
+pub const DICTIONARY_ENTRY: &str = "META-INF/S-ZIP.ZSTD-DICT";
+
+/// A Zstandard dictionary, cheap to clone
+#[derive(Clone, PartialEq, Eq)]
+pub struct ZstdDictionary {
+    bytes: Arc<[u8]>,
+}
+
+impl ZstdDictionary {
+    /// Use `bytes` as the dictionary: one trained by `zstd --train` or
+    /// [`train`](Self::train), or any raw content to use as a prefix
+    pub fn new(bytes: impl Into<Vec<u8>>) -> Self {
+        Self {
+            bytes: bytes.into().into(),
+        }
+    }
+
+    /// Train a dictionary of at most `max_size` bytes on `samples`, which
+    /// should look like the entries it will compress
+    ///
+    /// Training needs a fair number of samples, typically hundreds; with
+    /// too few it fails.
+    pub fn train<S: AsRef<[u8]>>(samples: &[S], max_size: usize) -> Result<Self> {
+        Ok(Self::new(zstd::dict::from_samples(samples, max_size)?))
+    }
+
+    /// The dictionary bytes
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// The ID a trained dictionary records in the frames it compresses, or
+    /// `None` for a raw content dictionary
+    pub fn id(&self) -> Option<u32> {
+        zstd::zstd_safe::get_dict_id_from_dict(&self.bytes).map(|id| id.get())
+    }
+}
+
+impl fmt::Debug for ZstdDictionary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ZstdDictionary")
+            .field("id", &self.id())
+            .field("len", &self.bytes.len())
+            .finish()
+    }
+}
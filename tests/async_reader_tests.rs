@@ -1,7 +1,37 @@
 //! Tests for async streaming ZIP reader
 
-use s_zip::{AsyncStreamingZipReader, AsyncStreamingZipWriter, Result};
-use tokio::io::AsyncReadExt;
+use s_zip::{AsyncStreamingZipReader, AsyncStreamingZipWriter, GenericAsyncZipReader, Result};
+use std::io::Cursor;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, ReadBuf};
+
+/// In-memory source that is deliberately `!Send` (holds an `Rc`).
+struct RcSource {
+    inner: Cursor<Vec<u8>>,
+    _not_send: Rc<()>,
+}
+
+impl AsyncRead for RcSource {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_read(cx, buf)
+    }
+}
+
+impl AsyncSeek for RcSource {
+    fn start_seek(mut self: Pin<&mut Self>, position: std::io::SeekFrom) -> std::io::Result<()> {
+        Pin::new(&mut self.inner).start_seek(position)
+    }
+
+    fn poll_complete(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<u64>> {
+        Pin::new(&mut self.inner).poll_complete(cx)
+    }
+}
 
 #[tokio::test]
 async fn test_async_reader_basic() -> Result<()> {
@@ -52,6 +82,7 @@ async fn test_async_reader_streaming() -> Result<()> {
     // Read using streaming
     let mut reader = AsyncStreamingZipReader::open("test_async_streaming.zip").await?;
     let mut stream = reader.read_entry_streaming_by_name("large.txt").await?;
+    assert_eq!(stream.entry().uncompressed_size, content.len() as u64);
 
     let mut buffer = Vec::new();
     stream.read_to_end(&mut buffer).await?;
@@ -87,6 +118,64 @@ async fn test_async_reader_find_entry() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_async_open_with_passed_deadline() -> Result<()> {
+    use s_zip::{ReaderOptions, SZipError};
+    use std::time::{Duration, Instant};
+
+    let mut writer = AsyncStreamingZipWriter::from_writer(Cursor::new(Vec::new()));
+    writer.add_entry("a.txt", b"a").await?;
+    let bytes = writer.finish().await?.into_inner();
+
+    let options = ReaderOptions::default().with_deadline(Instant::now() - Duration::from_secs(1));
+    let opened = GenericAsyncZipReader::new_with_options(Cursor::new(bytes), options).await;
+    assert!(matches!(opened, Err(SZipError::DeadlineExceeded)));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_async_reader_reload() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("growing.zip");
+    let mut writer = AsyncStreamingZipWriter::new(&path).await?;
+    writer.add_entry("a.txt", b"alpha").await?;
+    writer.finish().await?;
+    let mut reader = AsyncStreamingZipReader::open(&path).await?;
+    assert_eq!(reader.entry_count(), 1);
+
+    let mut writer = AsyncStreamingZipWriter::new(&path).await?;
+    writer.add_entry("a.txt", b"alpha").await?;
+    writer.add_entry("b.txt", b"beta").await?;
+    writer.finish().await?;
+
+    reader.reload().await?;
+    assert_eq!(reader.entry_count(), 2);
+    assert_eq!(reader.read_entry_by_name("b.txt").await?, b"beta");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_async_reader_header_fields() -> Result<()> {
+    let mut writer = s_zip::StreamingZipWriter::from_writer(Cursor::new(Vec::new()))?;
+    writer.set_version_made_by(0x0314).set_version_needed(20);
+    writer.add_entry("résumé.txt", b"header fields")?;
+    let bytes = writer.finish()?.into_inner();
+
+    // Both readers report the same central directory fields
+    let sync = s_zip::StreamingZipReader::from_bytes(&bytes)?;
+    let reader = s_zip::GenericAsyncZipReader::from_bytes(bytes.clone()).await?;
+    let (expected, entry) = (&sync.entries()[0], &reader.entries()[0]);
+    assert_eq!(entry.crc32, crc32fast::hash(b"header fields"));
+    assert_eq!(entry.crc32, expected.crc32);
+    assert_eq!(entry.flags, 0x0808);
+    assert_eq!(entry.flags, expected.flags);
+    assert_eq!(entry.version_made_by, 0x0314);
+    assert_eq!(entry.version_needed, 20);
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_async_reader_multiple_entries() -> Result<()> {
     // Create a ZIP with multiple entries
@@ -105,6 +194,14 @@ async fn test_async_reader_multiple_entries() -> Result<()> {
     let mut reader = AsyncStreamingZipReader::open("test_async_multiple.zip").await?;
 
     assert_eq!(reader.entries().len(), 10);
+    assert_eq!(reader.entry_count(), 10);
+    let page: Vec<&str> = reader
+        .entries_page(8, 4)
+        .iter()
+        .map(|e| e.name.as_str())
+        .collect();
+    assert_eq!(page, ["file8.txt", "file9.txt"]);
+    assert_eq!(reader.read_entry_by_index(9).await?, b"Content of file 9");
 
     // Read each entry and verify
     for i in 0..10 {
@@ -210,3 +307,833 @@ async fn test_async_reader_binary_data() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_async_reader_read_entries_batch() -> Result<()> {
+    let mut writer = AsyncStreamingZipWriter::new("test_async_batch.zip").await?;
+    for i in 0..5 {
+        writer
+            .add_entry(&format!("file{}.txt", i), format!("data {}", i).as_bytes())
+            .await?;
+    }
+    writer.finish().await?;
+
+    let mut reader = AsyncStreamingZipReader::open("test_async_batch.zip").await?;
+    let files = reader
+        .read_entries(&["file4.txt", "file1.txt", "file2.txt"])
+        .await?;
+
+    assert_eq!(files.len(), 3);
+    assert_eq!(files["file1.txt"], b"data 1");
+    assert_eq!(files["file2.txt"], b"data 2");
+    assert_eq!(files["file4.txt"], b"data 4");
+
+    // Cleanup
+    std::fs::remove_file("test_async_batch.zip").ok();
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_async_reader_into_entry_reader_spawn() -> Result<()> {
+    let mut writer = AsyncStreamingZipWriter::new("test_async_owned.zip").await?;
+    writer.add_entry("first.txt", b"x").await?;
+    let content = "owned stream\n".repeat(500);
+    writer.add_entry("big.txt", content.as_bytes()).await?;
+    writer.finish().await?;
+
+    let reader = AsyncStreamingZipReader::open("test_async_owned.zip").await?;
+    let mut stream = reader.into_entry_reader("big.txt").await?;
+
+    let data = tokio::spawn(async move {
+        let mut data = Vec::new();
+        stream.read_to_end(&mut data).await.map(|_| data)
+    })
+    .await
+    .unwrap()?;
+
+    assert_eq!(data, content.as_bytes());
+
+    // Cleanup
+    std::fs::remove_file("test_async_owned.zip").ok();
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_local_reader_non_send_source() -> Result<()> {
+    let mut writer = AsyncStreamingZipWriter::new("test_async_local.zip").await?;
+    writer.add_entry("a.txt", b"local data").await?;
+    let content = "streamed locally\n".repeat(200);
+    writer.add_entry("b.txt", content.as_bytes()).await?;
+    writer.finish().await?;
+
+    let bytes = std::fs::read("test_async_local.zip")?;
+    std::fs::remove_file("test_async_local.zip").ok();
+
+    let source = RcSource {
+        inner: Cursor::new(bytes),
+        _not_send: Rc::new(()),
+    };
+    let mut reader = GenericAsyncZipReader::new(source).await?;
+
+    assert_eq!(reader.read_entry_by_name("a.txt").await?, b"local data");
+
+    let mut stream = reader.read_entry_streaming_by_name("b.txt").await?;
+    let mut buffer = Vec::new();
+    stream.read_to_end(&mut buffer).await?;
+    assert_eq!(buffer, content.as_bytes());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_async_reader_from_bytes() -> Result<()> {
+    let mut writer = AsyncStreamingZipWriter::from_writer(Cursor::new(Vec::new()));
+    writer.add_entry("a.txt", b"in memory").await?;
+    let bytes = writer.finish().await?.into_inner();
+
+    let mut reader = s_zip::GenericAsyncZipReader::from_bytes(bytes).await?;
+    assert_eq!(reader.read_entry_by_name("a.txt").await?, b"in memory");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_async_extract_to_dir() -> Result<()> {
+    let mut writer = AsyncStreamingZipWriter::from_writer(Cursor::new(Vec::new()));
+    writer.add_entry("docs/readme.txt", b"hello").await?;
+    writer
+        .add_entry("docs/nested/data.bin", &[7u8; 4096])
+        .await?;
+    let bytes = writer.finish().await?.into_inner();
+
+    let out = tempfile::tempdir()?;
+    let mut reader = s_zip::GenericAsyncZipReader::from_bytes(bytes).await?;
+    reader.extract_to_dir(out.path()).await?;
+
+    assert_eq!(std::fs::read(out.path().join("docs/readme.txt"))?, b"hello");
+    assert_eq!(
+        std::fs::read(out.path().join("docs/nested/data.bin"))?,
+        vec![7u8; 4096]
+    );
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_async_extract_restores_directory_metadata() -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    let mut writer = AsyncStreamingZipWriter::from_writer(Cursor::new(Vec::new()));
+    let private = s_zip::EntryOptions::default().with_unix_mode(0o700);
+    writer.start_entry_with_options("private/", private).await?;
+    writer.add_entry("private/id", b"secret").await?;
+    let bytes = writer.finish().await?.into_inner();
+
+    let source = Cursor::new(std::sync::Arc::<[u8]>::from(bytes));
+    let mut reader = s_zip::GenericAsyncZipReader::new(source).await?;
+    let out = tempfile::tempdir()?;
+    reader.extract_to_dir(out.path()).await?;
+    let parallel = tempfile::tempdir()?;
+    let config = s_zip::ExtractConfig::default().with_workers(2);
+    reader
+        .extract_entries_parallel(reader.entries(), parallel.path(), &config)
+        .await?;
+
+    for root in [out.path(), parallel.path()] {
+        assert_eq!(std::fs::read(root.join("private/id"))?, b"secret");
+        let mode = std::fs::metadata(root.join("private"))?
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o7777, 0o700);
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_async_extract_entries_parallel() -> Result<()> {
+    let mut writer = AsyncStreamingZipWriter::from_writer(Cursor::new(Vec::new()));
+    for i in 0..16u8 {
+        let data = vec![i; 20_000 + i as usize * 100];
+        writer.add_entry(&format!("part/{i:02}.bin"), &data).await?;
+    }
+    writer.add_symlink("latest.bin", "part/15.bin").await?;
+    let bytes = writer.finish().await?.into_inner();
+
+    // Cursor<Arc<[u8]>> clones share the buffer but keep their own position
+    let source = Cursor::new(std::sync::Arc::<[u8]>::from(bytes));
+    let reader = s_zip::GenericAsyncZipReader::new(source).await?;
+    let out = tempfile::tempdir()?;
+    let config = s_zip::ExtractConfig::default().with_workers(4);
+    reader
+        .extract_entries_parallel(reader.entries(), out.path(), &config)
+        .await?;
+
+    for i in 0..16u8 {
+        let data = std::fs::read(out.path().join(format!("part/{i:02}.bin")))?;
+        assert_eq!(data, vec![i; 20_000 + i as usize * 100]);
+    }
+    #[cfg(unix)]
+    assert_eq!(
+        std::fs::read_link(out.path().join("latest.bin"))?,
+        std::path::Path::new("part/15.bin")
+    );
+
+    // A subset, with a single worker
+    let subset: Vec<_> = reader
+        .entries()
+        .iter()
+        .filter(|e| e.name.starts_with("part/0"))
+        .cloned()
+        .collect();
+    let some = tempfile::tempdir()?;
+    let config = s_zip::ExtractConfig::default().with_workers(1);
+    reader
+        .extract_entries_parallel(&subset, some.path(), &config)
+        .await?;
+    assert_eq!(std::fs::read_dir(some.path().join("part"))?.count(), 10);
+    assert!(!some.path().join("latest.bin").exists());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_async_extract_all() -> Result<()> {
+    let data = |i: usize| vec![i as u8; 30_000 + i * 257];
+    let mut writer = AsyncStreamingZipWriter::from_writer(Cursor::new(Vec::new()));
+    writer.add_directory("empty/").await?;
+    for i in 0..20 {
+        writer
+            .add_entry(&format!("dir{}/{i:02}.bin", i % 3), &data(i))
+            .await?;
+    }
+    let bytes = writer.finish().await?.into_inner();
+
+    let source = Cursor::new(std::sync::Arc::<[u8]>::from(bytes));
+    let reader = s_zip::GenericAsyncZipReader::new(source).await?;
+    for concurrency in [1, 3, 8] {
+        let out = tempfile::tempdir()?;
+        reader.extract_all(out.path(), concurrency).await?;
+        for i in 0..20 {
+            let path = out.path().join(format!("dir{}/{i:02}.bin", i % 3));
+            assert_eq!(std::fs::read(path)?, data(i), "concurrency {concurrency}");
+        }
+        assert!(out.path().join("empty").is_dir());
+        // Nothing but the extracted tree is left behind
+        assert_eq!(std::fs::read_dir(out.path())?.count(), 4);
+    }
+    Ok(())
+}
+
+/// Cloneable in-memory source whose reads never complete once `stalled` is set
+#[derive(Clone)]
+struct StallingSource {
+    inner: Cursor<std::sync::Arc<[u8]>>,
+    stalled: std::sync::Arc<std::sync::atomic::AtomicBool>,
+}
+
+impl AsyncRead for StallingSource {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        if self.stalled.load(std::sync::atomic::Ordering::Relaxed) {
+            return Poll::Pending;
+        }
+        Pin::new(&mut self.inner).poll_read(cx, buf)
+    }
+}
+
+impl AsyncSeek for StallingSource {
+    fn start_seek(mut self: Pin<&mut Self>, position: std::io::SeekFrom) -> std::io::Result<()> {
+        Pin::new(&mut self.inner).start_seek(position)
+    }
+
+    fn poll_complete(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<u64>> {
+        Pin::new(&mut self.inner).poll_complete(cx)
+    }
+}
+
+#[tokio::test]
+async fn test_async_extract_cancelled_leaves_no_temp_files() -> Result<()> {
+    let mut writer = AsyncStreamingZipWriter::from_writer(Cursor::new(Vec::new()));
+    for i in 0..4u8 {
+        writer.add_entry(&format!("{i}.bin"), &[i; 10_000]).await?;
+    }
+    let bytes = writer.finish().await?.into_inner();
+
+    // The central directory reads fine, entry data never arrives
+    let stalled = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let reader = s_zip::GenericAsyncZipReader::new(StallingSource {
+        inner: Cursor::new(std::sync::Arc::<[u8]>::from(bytes)),
+        stalled: stalled.clone(),
+    })
+    .await?;
+    stalled.store(true, std::sync::atomic::Ordering::Relaxed);
+    let out = tempfile::tempdir()?;
+    let extract = reader.extract_all(out.path(), 2);
+    let timed_out = tokio::time::timeout(std::time::Duration::from_millis(50), extract).await;
+    assert!(timed_out.is_err());
+
+    // Let the aborted tasks be dropped
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    assert_eq!(std::fs::read_dir(out.path())?.count(), 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_async_shared_entry_readers_interleave() -> Result<()> {
+    use tokio::io::AsyncReadExt;
+
+    let mut writer = AsyncStreamingZipWriter::from_writer(Cursor::new(Vec::new()));
+    writer.add_entry("a.bin", &vec![b'a'; 300_000]).await?;
+    writer.add_entry("b.bin", &vec![b'b'; 300_000]).await?;
+    let bytes = writer.finish().await?.into_inner();
+
+    let source = Cursor::new(std::sync::Arc::<[u8]>::from(bytes));
+    let reader = s_zip::GenericAsyncZipReader::new(source).await?;
+    let mut a = reader.shared_entry_reader(&reader.entries()[0]).await?;
+    let mut b = reader.shared_entry_reader(&reader.entries()[1]).await?;
+
+    // Alternate small reads between the two streams
+    let (mut data_a, mut data_b) = (Vec::new(), Vec::new());
+    let mut chunk = [0u8; 4096];
+    loop {
+        let n = a.read(&mut chunk).await?;
+        data_a.extend_from_slice(&chunk[..n]);
+        let m = b.read(&mut chunk).await?;
+        data_b.extend_from_slice(&chunk[..m]);
+        if n == 0 && m == 0 {
+            break;
+        }
+    }
+    assert_eq!(data_a, vec![b'a'; 300_000]);
+    assert_eq!(data_b, vec![b'b'; 300_000]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_async_prefetch_serves_read_entry() -> Result<()> {
+    let mut writer = AsyncStreamingZipWriter::from_writer(Cursor::new(Vec::new()));
+    writer.add_entry("manifest.txt", b"a.bin\nb.bin\n").await?;
+    writer.add_entry("a.bin", &vec![1u8; 50_000]).await?;
+    writer.add_entry("b.bin", &vec![2u8; 50_000]).await?;
+    let bytes = writer.finish().await?.into_inner();
+
+    let source = Cursor::new(std::sync::Arc::<[u8]>::from(bytes));
+    let mut reader = s_zip::GenericAsyncZipReader::new(source).await?;
+    reader.prefetch(&["a.bin", "b.bin", "missing.bin"]);
+
+    assert_eq!(reader.read_entry_by_name("b.bin").await?, vec![2u8; 50_000]);
+    assert_eq!(reader.read_entry_by_name("a.bin").await?, vec![1u8; 50_000]);
+    // Entries are re-read from the source once their prefetched copy is used
+    assert_eq!(reader.read_entry_by_name("a.bin").await?, vec![1u8; 50_000]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_async_prefetch_cancelled_with_reader() -> Result<()> {
+    let mut writer = AsyncStreamingZipWriter::from_writer(Cursor::new(Vec::new()));
+    writer.add_entry("a.bin", &vec![1u8; 50_000]).await?;
+    let bytes = writer.finish().await?.into_inner();
+
+    let stalled = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let mut reader = s_zip::GenericAsyncZipReader::new(StallingSource {
+        inner: Cursor::new(std::sync::Arc::<[u8]>::from(bytes)),
+        stalled: stalled.clone(),
+    })
+    .await?;
+    stalled.store(true, std::sync::atomic::Ordering::Relaxed);
+    reader.prefetch(&["a.bin"]);
+    tokio::task::yield_now().await;
+    assert!(std::sync::Arc::strong_count(&stalled) > 2);
+
+    // The stuck fetch and its clone of the source go away with the reader
+    drop(reader);
+    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    assert_eq!(std::sync::Arc::strong_count(&stalled), 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_async_open_with_cd_location() -> Result<()> {
+    let mut writer = AsyncStreamingZipWriter::from_writer(Cursor::new(Vec::new()));
+    writer.add_entry("a.txt", b"alpha").await?;
+    writer.add_entry("b.txt", b"beta").await?;
+    let bytes = writer.finish().await?.into_inner();
+
+    let location = s_zip::GenericAsyncZipReader::from_bytes(bytes.clone())
+        .await?
+        .cd_location();
+    let mut reader = s_zip::GenericAsyncZipReader::open_with_cd_location(
+        Cursor::new(bytes),
+        location.offset,
+        location.size,
+        location.count,
+    )
+    .await?;
+    assert_eq!(reader.entries().len(), 2);
+    assert_eq!(reader.read_entry_by_name("a.txt").await?, b"alpha");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_async_new_filtered() -> Result<()> {
+    let mut writer = AsyncStreamingZipWriter::from_writer(Cursor::new(Vec::new()));
+    writer.add_entry("metadata/a.json", b"{}").await?;
+    writer.add_entry("data/blob.bin", b"blob").await?;
+    let bytes = writer.finish().await?.into_inner();
+
+    let mut reader =
+        s_zip::GenericAsyncZipReader::new_with_prefixes(Cursor::new(bytes), &["metadata/"]).await?;
+    assert_eq!(reader.entries().len(), 1);
+    assert_eq!(reader.read_entry_by_name("metadata/a.json").await?, b"{}");
+
+    // Reloading keeps the filter
+    reader.reload().await?;
+    assert_eq!(reader.entries().len(), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_async_verify() -> Result<()> {
+    let mut writer = AsyncStreamingZipWriter::from_writer(Cursor::new(Vec::new()));
+    writer
+        .add_entry("a.txt", &b"verify me ".repeat(100))
+        .await?;
+    let mut bytes = writer.finish().await?.into_inner();
+
+    let mut reader = s_zip::GenericAsyncZipReader::from_bytes(bytes.clone()).await?;
+    assert!(reader.verify().await?.is_ok());
+
+    // Local header claims "stored" while the central directory says deflate
+    bytes[8..10].copy_from_slice(&0u16.to_le_bytes());
+    let mut reader = s_zip::GenericAsyncZipReader::from_bytes(bytes).await?;
+    let report = reader.verify().await?;
+    assert!(matches!(
+        report.entries[0].problems.first(),
+        Some(s_zip::EntryProblem::HeaderMismatch {
+            field: "compression method",
+            central: 8,
+            local: 0,
+        })
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_async_streaming_entry_lines() -> Result<()> {
+    use tokio::io::AsyncBufReadExt;
+
+    let mut writer = AsyncStreamingZipWriter::from_writer(Cursor::new(Vec::new()));
+    writer.add_entry("rows.csv", b"a,b\n1,2\n3,4\n").await?;
+    let bytes = writer.finish().await?.into_inner();
+
+    let mut reader = s_zip::GenericAsyncZipReader::from_bytes(bytes).await?;
+    let mut lines = reader
+        .read_entry_streaming_by_name("rows.csv")
+        .await?
+        .lines();
+    let mut rows = Vec::new();
+    while let Some(line) = lines.next_line().await? {
+        rows.push(line);
+    }
+    assert_eq!(rows, ["a,b", "1,2", "3,4"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_forward_reader_over_byte_stream() -> Result<()> {
+    use s_zip::forward::ForwardZipReader;
+    use s_zip::{Compatibility, CompressionMethod, StreamingZipWriter};
+
+    let big: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+
+    // Async writer: Deflate with data descriptors
+    let mut writer = AsyncStreamingZipWriter::from_writer(Cursor::new(Vec::new()));
+    writer.start_entry("first.txt").await?;
+    writer.write_data(b"hello").await?;
+    writer.start_entry("skipped.bin").await?;
+    writer.write_data(&big).await?;
+    writer.start_entry("big.bin").await?;
+    writer.write_data(&big).await?;
+    let streamed = writer.finish().await?.into_inner();
+
+    // Sync writer: Stored with sizes patched into the local headers
+    let mut writer = StreamingZipWriter::from_writer_with_method(
+        Cursor::new(Vec::new()),
+        CompressionMethod::Stored,
+        0,
+    )?;
+    writer.set_compatibility(Compatibility::WindowsExplorer);
+    writer.add_entry("first.txt", b"hello")?;
+    writer.add_entry("skipped.bin", &big)?;
+    writer.add_entry("big.bin", &big)?;
+    let stored = writer.finish()?.into_inner();
+
+    for zip in [streamed, stored] {
+        // Delivered in small chunks, as an HTTP body would be
+        let chunks: Vec<std::io::Result<Vec<u8>>> =
+            zip.chunks(1000).map(|c| Ok(c.to_vec())).collect();
+        let mut reader = ForwardZipReader::from_byte_stream(futures_util::stream::iter(chunks));
+
+        let mut entry = reader.next_entry().await?.unwrap();
+        assert_eq!(entry.entry().name, "first.txt");
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data).await?;
+        assert_eq!(data, b"hello");
+
+        // Partially read, then skipped
+        let mut entry = reader.next_entry().await?.unwrap();
+        assert_eq!(entry.entry().name, "skipped.bin");
+        let mut head = [0u8; 10];
+        entry.read_exact(&mut head).await?;
+
+        let mut entry = reader.next_entry().await?.unwrap();
+        assert_eq!(entry.entry().name, "big.bin");
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data).await?;
+        assert_eq!(data, big);
+
+        assert!(reader.next_entry().await?.is_none());
+        assert!(reader.next_entry().await?.is_none());
+    }
+
+    // A corrupted byte is caught by the CRC check once the entry is done
+    let mut writer = StreamingZipWriter::from_writer_with_method(
+        Cursor::new(Vec::new()),
+        CompressionMethod::Stored,
+        0,
+    )?;
+    writer.set_compatibility(Compatibility::WindowsExplorer);
+    writer.add_entry("a.txt", b"alpha")?;
+    let mut zip = writer.finish()?.into_inner();
+    let at = zip.windows(5).position(|w| w == b"alpha").unwrap();
+    zip[at] = b'A';
+    let mut reader = ForwardZipReader::new(Cursor::new(zip));
+    reader.next_entry().await?.unwrap();
+    assert!(reader.next_entry().await.is_err());
+
+    // Stream errors surface as I/O errors
+    let chunks: Vec<std::io::Result<Vec<u8>>> = vec![
+        Ok(b"PK".to_vec()),
+        Err(std::io::ErrorKind::ConnectionReset.into()),
+    ];
+    let mut reader = ForwardZipReader::from_byte_stream(futures_util::stream::iter(chunks));
+    assert!(reader.next_entry().await.is_err());
+    Ok(())
+}
+
+/// In-memory source whose every second read drops the connection after
+/// advancing the position, like a reset mid-transfer
+struct FlakySource {
+    inner: Cursor<Vec<u8>>,
+    reads: usize,
+}
+
+impl AsyncRead for FlakySource {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        self.reads += 1;
+        if self.reads.is_multiple_of(2) {
+            let pos = self.inner.position();
+            self.inner.set_position(pos + 5);
+            return Poll::Ready(Err(std::io::ErrorKind::ConnectionReset.into()));
+        }
+        Pin::new(&mut self.inner).poll_read(cx, buf)
+    }
+}
+
+impl AsyncSeek for FlakySource {
+    fn start_seek(mut self: Pin<&mut Self>, position: std::io::SeekFrom) -> std::io::Result<()> {
+        Pin::new(&mut self.inner).start_seek(position)
+    }
+
+    fn poll_complete(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<u64>> {
+        Pin::new(&mut self.inner).poll_complete(cx)
+    }
+}
+
+#[tokio::test]
+async fn test_retrying_reader_recovers_from_resets() -> Result<()> {
+    use s_zip::retry::RetryingReader;
+    use s_zip::GenericAsyncZipReader;
+    use std::time::Duration;
+
+    let mut writer = s_zip::StreamingZipWriter::from_writer(Cursor::new(Vec::new()))?;
+    let data: Vec<u8> = (0..200_000u32).map(|i| (i * 7 % 251) as u8).collect();
+    writer.add_entry("data.bin", &data)?;
+    writer.add_entry("small.txt", b"hello")?;
+    let zip = writer.finish()?.into_inner();
+
+    let source = || FlakySource {
+        inner: Cursor::new(zip.clone()),
+        reads: 0,
+    };
+
+    let retrying = RetryingReader::new(source()).with_backoff(Duration::ZERO, Duration::ZERO);
+    let mut reader = GenericAsyncZipReader::new(retrying).await?;
+    assert_eq!(reader.read_entry_by_name("data.bin").await?, data);
+    assert_eq!(reader.read_entry_by_name("small.txt").await?, b"hello");
+
+    // Without retries the first reset is fatal
+    let no_retries = RetryingReader::new(source()).with_max_retries(0);
+    assert!(GenericAsyncZipReader::new(no_retries).await.is_err());
+    Ok(())
+}
+
+#[tokio::test]
+#[cfg(feature = "deflate64")]
+async fn test_async_read_deflate64_entry() -> Result<()> {
+    use s_zip::raw::{self, CentralDirectoryRecord, EndOfCentralDirectory, LocalFileHeader};
+    use s_zip::GenericAsyncZipReader;
+
+    // "ab" repeated 501 times; length code 285 carries 16 extra bits in Deflate64
+    let stream = [0x4b, 0x4c, 0x1a, 0x2d, 0x1f, 0x80, 0x00];
+    let data = b"ab".repeat(501);
+    let crc32 = crc32fast::hash(&data);
+    let mut zip = Vec::new();
+    raw::write_local_header(
+        &mut zip,
+        &LocalFileHeader {
+            compression_method: 9,
+            crc32,
+            compressed_size: stream.len() as u64,
+            uncompressed_size: data.len() as u64,
+            name: b"large.log",
+            ..Default::default()
+        },
+    )?;
+    zip.extend_from_slice(&stream);
+    let cd_offset = zip.len() as u64;
+    let cd_size = raw::write_central_record(
+        &mut zip,
+        &CentralDirectoryRecord {
+            compression_method: 9,
+            crc32,
+            compressed_size: stream.len() as u64,
+            uncompressed_size: data.len() as u64,
+            name: b"large.log",
+            ..Default::default()
+        },
+    )?;
+    raw::write_eocd(
+        &mut zip,
+        &EndOfCentralDirectory {
+            entry_count: 1,
+            cd_size,
+            cd_offset,
+            ..Default::default()
+        },
+    )?;
+
+    let mut reader = GenericAsyncZipReader::new(Cursor::new(zip)).await?;
+    assert_eq!(reader.read_entry_by_name("large.log").await?, data);
+    let mut streamed = Vec::new();
+    reader
+        .read_entry_streaming_by_name("large.log")
+        .await?
+        .read_to_end(&mut streamed)
+        .await?;
+    assert_eq!(streamed, data);
+    Ok(())
+}
+
+#[tokio::test]
+#[cfg(feature = "encryption")]
+async fn test_async_non_aes_encrypted_entry_offset_matches_sync() -> Result<()> {
+    use s_zip::raw::{self, CentralDirectoryRecord, EndOfCentralDirectory, LocalFileHeader};
+    use s_zip::{GenericAsyncZipReader, StreamingZipReader};
+    use std::io::Read;
+
+    // A ZipCrypto-flagged stored entry whose local header carries an unknown
+    // extra field: both readers must start the data right after it
+    let data = b"\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0a\x0b\x0cciphertext".to_vec();
+    let crc32 = crc32fast::hash(&data);
+    let extra = [0xfe, 0xca, 4, 0, 1, 2, 3, 4];
+    let mut zip = Vec::new();
+    raw::write_local_header(
+        &mut zip,
+        &LocalFileHeader {
+            flags: 0x01,
+            crc32,
+            compressed_size: data.len() as u64,
+            uncompressed_size: data.len() as u64,
+            name: b"secret.bin",
+            extra: &extra,
+            ..Default::default()
+        },
+    )?;
+    zip.extend_from_slice(&data);
+    let cd_offset = zip.len() as u64;
+    let cd_size = raw::write_central_record(
+        &mut zip,
+        &CentralDirectoryRecord {
+            flags: 0x01,
+            crc32,
+            compressed_size: data.len() as u64,
+            uncompressed_size: data.len() as u64,
+            name: b"secret.bin",
+            ..Default::default()
+        },
+    )?;
+    raw::write_eocd(
+        &mut zip,
+        &EndOfCentralDirectory {
+            entry_count: 1,
+            cd_size,
+            cd_offset,
+            ..Default::default()
+        },
+    )?;
+
+    let mut sync = StreamingZipReader::from_vec(zip.clone())?;
+    let mut sync_data = Vec::new();
+    sync.read_entry_streaming_by_name("secret.bin")?
+        .read_to_end(&mut sync_data)?;
+    let mut reader = GenericAsyncZipReader::new(Cursor::new(zip)).await?;
+    let mut async_data = Vec::new();
+    reader
+        .read_entry_streaming_by_name("secret.bin")
+        .await?
+        .read_to_end(&mut async_data)
+        .await?;
+    assert_eq!(sync_data, data);
+    assert_eq!(async_data, data);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_async_reader_options_filename_encoding() -> Result<()> {
+    use s_zip::encoding::FilenameEncoding;
+    use s_zip::raw::{self, CentralDirectoryRecord, EndOfCentralDirectory, LocalFileHeader};
+    use s_zip::{GenericAsyncZipReader, ReaderOptions};
+    use std::sync::Arc;
+
+    // "日本.txt" in Shift-JIS, without the UTF-8 flag
+    let name = b"\x93\xfa\x96\x7b.txt";
+    let mut zip = Vec::new();
+    raw::write_local_header(
+        &mut zip,
+        &LocalFileHeader {
+            name,
+            ..Default::default()
+        },
+    )?;
+    let cd_offset = zip.len() as u64;
+    let cd_size = raw::write_central_record(
+        &mut zip,
+        &CentralDirectoryRecord {
+            name,
+            ..Default::default()
+        },
+    )?;
+    raw::write_eocd(
+        &mut zip,
+        &EndOfCentralDirectory {
+            entry_count: 1,
+            cd_size,
+            cd_offset,
+            ..Default::default()
+        },
+    )?;
+    let options = || {
+        ReaderOptions::default().with_filename_encoding(FilenameEncoding::Custom(Arc::new(
+            |name: &[u8]| {
+                let name = name.strip_prefix(b"\x93\xfa\x96\x7b").unwrap();
+                format!("日本{}", std::str::from_utf8(name).unwrap())
+            },
+        )))
+    };
+
+    let mut reader =
+        GenericAsyncZipReader::new_with_options(Cursor::new(zip.clone()), options()).await?;
+    assert_eq!(reader.entries()[0].name, "日本.txt");
+    // The local header name is decoded the same way
+    assert!(reader.verify().await?.is_ok());
+    reader.reload().await?;
+    assert_eq!(reader.entries()[0].name, "日本.txt");
+
+    let location = reader.cd_location();
+    let reader = GenericAsyncZipReader::new_with_options(
+        Cursor::new(zip),
+        options().with_cd_location(location),
+    )
+    .await?;
+    assert_eq!(reader.entries()[0].name, "日本.txt");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_async_lenient_quirks_mode() -> Result<()> {
+    use s_zip::raw::{self, CentralDirectoryRecord, EndOfCentralDirectory, LocalFileHeader};
+    use s_zip::{QuirksMode, ReaderOptions};
+    use std::io::Write;
+
+    let data = b"class file bytes ".repeat(50);
+    let mut encoder = flate2::write::DeflateEncoder::new(Vec::new(), Default::default());
+    encoder.write_all(&data)?;
+    let packed = encoder.finish()?;
+    let crc = crc32fast::hash(&data);
+
+    // Java streaming writer: sizes and CRC only in the data descriptor, and
+    // a local header that disagrees with the central directory on the method
+    let mut zip = Vec::new();
+    raw::write_local_header(
+        &mut zip,
+        &LocalFileHeader {
+            flags: 0x08,
+            name: b"App.class",
+            ..Default::default()
+        },
+    )?;
+    zip.extend_from_slice(&packed);
+    raw::write_data_descriptor(&mut zip, crc, packed.len() as u64, data.len() as u64)?;
+    let cd_offset = zip.len() as u64;
+    let cd_size = raw::write_central_record(
+        &mut zip,
+        &CentralDirectoryRecord {
+            flags: 0x08,
+            compression_method: 8,
+            name: b"App.class",
+            ..Default::default()
+        },
+    )?;
+    raw::write_eocd(
+        &mut zip,
+        &EndOfCentralDirectory {
+            entry_count: 1,
+            cd_size,
+            cd_offset,
+            ..Default::default()
+        },
+    )?;
+
+    let mut strict = GenericAsyncZipReader::new(Cursor::new(zip.clone())).await?;
+    assert_eq!(strict.entries()[0].compressed_size, 0);
+    assert!(!strict.quick_check().await?.is_ok());
+
+    let options = ReaderOptions::default().with_quirks(QuirksMode::Lenient);
+    let mut lenient = GenericAsyncZipReader::new_with_options(Cursor::new(zip), options).await?;
+    let class = &lenient.entries()[0];
+    assert_eq!(class.compressed_size, packed.len() as u64);
+    assert_eq!(class.uncompressed_size, data.len() as u64);
+    assert_eq!(class.crc32, crc);
+    assert_eq!(lenient.read_entry_by_name("App.class").await?, data);
+    assert!(lenient.verify().await?.is_ok());
+    lenient.reload().await?;
+    assert_eq!(lenient.entries()[0].crc32, crc);
+    Ok(())
+}
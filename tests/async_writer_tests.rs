@@ -4,7 +4,10 @@
 
 #[cfg(feature = "async")]
 mod async_tests {
-    use s_zip::{AsyncStreamingZipWriter, Result, StreamingZipReader};
+    use s_zip::{
+        AsyncStreamingZipWriter, CompressionMethod, DosDateTime, EntryOptions, ParallelConfig,
+        ParallelEntry, Result, StreamingZipReader,
+    };
     use std::io::Cursor;
     use tempfile::NamedTempFile;
     use tokio::io::AsyncReadExt;
@@ -224,4 +227,714 @@ mod async_tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_async_writer_non_seekable_output() -> Result<()> {
+        // DuplexStream implements AsyncWrite but not AsyncSeek
+        let (client, mut server) = tokio::io::duplex(64 * 1024);
+
+        let producer = tokio::spawn(async move {
+            let mut writer = AsyncStreamingZipWriter::from_writer(client);
+            writer.add_entry("a.txt", b"over a pipe").await?;
+            writer
+                .add_entry("b.txt", "repeated ".repeat(1000).as_bytes())
+                .await?;
+            writer.finish().await?;
+            Ok::<_, s_zip::SZipError>(())
+        });
+
+        let mut bytes = Vec::new();
+        server.read_to_end(&mut bytes).await?;
+        producer.await.unwrap()?;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        std::fs::write(temp_file.path(), &bytes)?;
+
+        let mut reader = StreamingZipReader::open(temp_file.path())?;
+        assert_eq!(reader.read_entry_by_name("a.txt")?, b"over a pipe");
+        assert_eq!(
+            reader.read_entry_by_name("b.txt")?,
+            "repeated ".repeat(1000).as_bytes()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_async_parallel_entries_have_no_descriptor_flag() -> Result<()> {
+        let source = NamedTempFile::new().unwrap();
+        std::fs::write(source.path(), "parallel ".repeat(100))?;
+
+        let mut writer = AsyncStreamingZipWriter::from_writer(Vec::new());
+        writer
+            .write_entries_parallel(
+                vec![ParallelEntry::new("données.txt", source.path())],
+                ParallelConfig::conservative(),
+            )
+            .await?;
+        let bytes = writer.finish().await?;
+
+        // Sizes are in the local header, so bit 3 must be clear; bit 11 marks the UTF-8 name
+        assert_eq!(&bytes[6..8], &0x0800u16.to_le_bytes());
+        let mut reader = StreamingZipReader::from_vec(bytes)?;
+        assert_eq!(reader.entries()[0].flags, 0x0800);
+        assert_eq!(
+            reader.read_entry_by_name("données.txt")?,
+            "parallel ".repeat(100).as_bytes()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_async_entry_stats_callback() -> Result<()> {
+        use std::sync::{Arc, Mutex};
+
+        let source = NamedTempFile::new().unwrap();
+        std::fs::write(source.path(), "parallel ".repeat(100))?;
+
+        let stats = Arc::new(Mutex::new(Vec::new()));
+        let sink = stats.clone();
+        let mut writer = AsyncStreamingZipWriter::from_writer(Vec::new());
+        writer.on_entry_stats(move |entry| {
+            assert!(entry.throughput() >= 0.0);
+            sink.lock()
+                .unwrap()
+                .push((entry.name.to_string(), entry.uncompressed_size))
+        });
+        writer.start_entry("streamed.txt").await?;
+        writer.write_data(b"streamed").await?;
+        writer
+            .write_entries_parallel(
+                vec![ParallelEntry::new("parallel.txt", source.path())],
+                ParallelConfig::conservative(),
+            )
+            .await?;
+        writer.finish().await?;
+
+        assert_eq!(
+            *stats.lock().unwrap(),
+            [
+                ("streamed.txt".to_string(), 8),
+                ("parallel.txt".to_string(), 900)
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_async_memory_budget() -> Result<()> {
+        use s_zip::budget::MemoryBudget;
+        use s_zip::GenericAsyncZipReader;
+
+        let dir = tempfile::tempdir().unwrap();
+        let entries: Vec<_> = (0..6)
+            .map(|i| {
+                let path = dir.path().join(format!("{i}.txt"));
+                std::fs::write(&path, format!("file {i} ").repeat(1000)).unwrap();
+                ParallelEntry::new(format!("{i}.txt"), path)
+            })
+            .collect();
+
+        // Smaller than one task's share: tasks run one at a time
+        let budget = MemoryBudget::new(64 * 1024);
+        let config = ParallelConfig::aggressive().with_memory_budget(budget.clone());
+        let mut writer =
+            AsyncStreamingZipWriter::from_writer(Vec::new()).with_memory_budget(&budget);
+        writer.write_entries_parallel(entries, config).await?;
+        writer.start_entry("streamed.txt").await?;
+        writer.write_data(b"streamed").await?;
+        let bytes = writer.finish().await?;
+        assert_eq!(budget.used(), 0);
+
+        // Entry streams still work with the budget exhausted
+        let held = budget.try_reserve(budget.limit()).unwrap();
+        let mut reader = GenericAsyncZipReader::new(Cursor::new(bytes))
+            .await?
+            .with_memory_budget(&budget);
+        for i in 0..6 {
+            let data = reader.read_entry_by_name(&format!("{i}.txt")).await?;
+            assert_eq!(data, format!("file {i} ").repeat(1000).as_bytes());
+        }
+        let mut stream = reader.read_entry_streaming_by_name("streamed.txt").await?;
+        let mut data = Vec::new();
+        stream.read_to_end(&mut data).await?;
+        assert_eq!(data, b"streamed");
+        drop(held);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_async_parallel_sources() -> Result<()> {
+        use s_zip::GenericAsyncZipReader;
+
+        let source = NamedTempFile::new().unwrap();
+        std::fs::write(source.path(), "from a file ".repeat(500))?;
+        let entries = vec![
+            ParallelEntry::new("file.txt", source.path()),
+            ParallelEntry::from_bytes("vec.txt", "from a vec ".repeat(500).into_bytes()),
+            ParallelEntry::from_bytes("static.txt", &b"static"[..]),
+            ParallelEntry::from_reader("reader.txt", || async {
+                Ok(Cursor::new("from a reader ".repeat(500)))
+            }),
+        ];
+        let mut writer = AsyncStreamingZipWriter::from_writer(Vec::new());
+        writer
+            .write_entries_parallel(entries, ParallelConfig::balanced())
+            .await?;
+        let zip = writer.finish().await?;
+
+        let mut reader = GenericAsyncZipReader::new(Cursor::new(zip)).await?;
+        for (name, text) in [
+            ("file.txt", "from a file ".repeat(500)),
+            ("vec.txt", "from a vec ".repeat(500)),
+            ("static.txt", "static".to_string()),
+            ("reader.txt", "from a reader ".repeat(500)),
+        ] {
+            assert_eq!(reader.read_entry_by_name(name).await?, text.as_bytes());
+        }
+
+        // A reader that fails to open fails the batch
+        let failing = ParallelEntry::from_reader("missing.txt", || async {
+            tokio::fs::File::open("/nonexistent/s-zip/missing.txt").await
+        });
+        let mut writer = AsyncStreamingZipWriter::from_writer(Vec::new());
+        assert!(writer
+            .write_entries_parallel(vec![failing], ParallelConfig::default())
+            .await
+            .is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_async_parallel_streams_large_entries() -> Result<()> {
+        use s_zip::GenericAsyncZipReader;
+
+        // Incompressible, so every entry spans several chunks
+        let mut state = 0x2545_f491u32;
+        let files: Vec<Vec<u8>> = (0..5)
+            .map(|_| {
+                (0..1_500_000)
+                    .map(|_| {
+                        state ^= state << 13;
+                        state ^= state >> 17;
+                        state ^= state << 5;
+                        state as u8
+                    })
+                    .collect()
+            })
+            .collect();
+        let entries = files
+            .iter()
+            .enumerate()
+            .map(|(i, data)| ParallelEntry::from_bytes(format!("{i}.bin"), data.clone()))
+            .chain([ParallelEntry::from_bytes("small.txt", "small")])
+            .collect();
+
+        // Far less than the batch: later entries wait for earlier ones
+        let config = ParallelConfig::aggressive().with_max_buffered(512 * 1024);
+        let mut writer = AsyncStreamingZipWriter::from_writer(Vec::new());
+        writer.write_entries_parallel(entries, config).await?;
+        let zip = writer.finish().await?;
+
+        let mut reader = GenericAsyncZipReader::new(Cursor::new(zip)).await?;
+        // The first entry streams out while compressed, so a descriptor
+        // follows it; the small one is complete before its turn
+        assert_ne!(reader.entries()[0].flags & 0x0008, 0);
+        assert_eq!(reader.entries()[5].flags & 0x0008, 0);
+        for (i, data) in files.iter().enumerate() {
+            assert_eq!(reader.entries()[i].uncompressed_size, data.len() as u64);
+            assert_eq!(&reader.read_entry_by_name(&format!("{i}.bin")).await?, data);
+        }
+        assert_eq!(reader.read_entry_by_name("small.txt").await?, b"small");
+        assert!(reader.verify().await?.is_ok());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_async_parallel_methods() -> Result<()> {
+        use s_zip::{CompressionMethod, GenericAsyncZipReader};
+
+        let text = "parallel methods ".repeat(40_000);
+        #[cfg_attr(not(feature = "async-zstd"), allow(unused_mut))]
+        let mut methods = vec![
+            (CompressionMethod::Stored, 0),
+            (CompressionMethod::Deflate, 8),
+        ];
+        #[cfg(feature = "async-zstd")]
+        methods.push((CompressionMethod::Zstd, 93));
+        for (method, id) in methods {
+            let entries = vec![
+                ParallelEntry::from_bytes("big.txt", text.clone()),
+                ParallelEntry::from_bytes("small.txt", "small"),
+                ParallelEntry::from_bytes("empty.txt", ""),
+            ];
+            let config = ParallelConfig::default()
+                .with_compression_method(method)
+                .with_max_buffered(0);
+            let mut writer = AsyncStreamingZipWriter::from_writer(Vec::new());
+            writer.write_entries_parallel(entries, config).await?;
+            let zip = writer.finish().await?;
+
+            let mut reader = GenericAsyncZipReader::new(Cursor::new(zip)).await?;
+            for (entry, data) in reader.entries().iter().zip([&text[..], "small", ""]) {
+                assert_eq!(entry.compression_method, id);
+                assert_eq!(entry.uncompressed_size, data.len() as u64);
+                assert_eq!(entry.crc32, crc32fast::hash(data.as_bytes()));
+                if id == 0 {
+                    assert_eq!(entry.compressed_size, data.len() as u64);
+                }
+            }
+            assert_eq!(reader.read_entry_by_name("big.txt").await?, text.as_bytes());
+            assert_eq!(reader.read_entry_by_name("small.txt").await?, b"small");
+            assert_eq!(reader.read_entry_by_name("empty.txt").await?, b"");
+            assert!(reader.verify().await?.is_ok());
+        }
+
+        // Other methods fail before anything is written
+        #[cfg(feature = "bzip2")]
+        {
+            let config =
+                ParallelConfig::default().with_compression_method(CompressionMethod::Bzip2);
+            let mut writer = AsyncStreamingZipWriter::from_writer(Vec::new());
+            let entries = vec![ParallelEntry::from_bytes("a.txt", "a")];
+            assert!(writer
+                .write_entries_parallel(entries, config)
+                .await
+                .is_err());
+            assert_eq!(writer.bytes_written(), 0);
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_async_compression_offload() -> Result<()> {
+        let text: Vec<u8> = (0..300_000u32)
+            .flat_map(|i| format!("line {} ", i % 977).into_bytes())
+            .collect();
+        // A fixed timestamp keeps the two archives comparable
+        let options = || {
+            EntryOptions::default()
+                .with_mtime(std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000))
+        };
+
+        let mut writer = AsyncStreamingZipWriter::from_writer(Vec::new());
+        writer.set_compression_offload(Some(64 * 1024));
+        writer
+            .start_entry_with_options("mixed.txt", options())
+            .await?;
+        // Large chunks go to the blocking pool, small ones stay inline
+        for chunk in text.chunks(100_000) {
+            writer.write_data(&chunk[..10]).await?;
+            writer.write_data(&chunk[10..]).await?;
+        }
+        writer
+            .start_entry_with_options("small.txt", options())
+            .await?;
+        writer.write_data(b"small").await?;
+        let offloaded = writer.finish().await?;
+
+        let mut writer = AsyncStreamingZipWriter::from_writer(Vec::new());
+        writer
+            .start_entry_with_options("mixed.txt", options())
+            .await?;
+        for chunk in text.chunks(100_000) {
+            writer.write_data(&chunk[..10]).await?;
+            writer.write_data(&chunk[10..]).await?;
+        }
+        writer
+            .start_entry_with_options("small.txt", options())
+            .await?;
+        writer.write_data(b"small").await?;
+        let inline = writer.finish().await?;
+
+        // Same encoder calls in the same order, so the output is identical
+        assert_eq!(offloaded, inline);
+        let mut reader = StreamingZipReader::from_vec(offloaded)?;
+        assert_eq!(reader.read_entry_by_name("mixed.txt")?, text);
+        assert_eq!(reader.read_entry_by_name("small.txt")?, b"small");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_async_writer_through_throttle() -> Result<()> {
+        use s_zip::throttle::ThrottledWriter;
+
+        let start = std::time::Instant::now();
+        let target = ThrottledWriter::with_burst(Vec::new(), 20_000, 1000);
+        let mut writer = AsyncStreamingZipWriter::from_writer(target);
+        writer.start_entry("noise.bin").await?;
+        let mut state = 0x2545_f491u32;
+        let noise: Vec<u8> = (0..5000)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as u8
+            })
+            .collect();
+        writer.write_data(&noise).await?;
+        let target = writer.finish().await?;
+
+        // At least 4 KB beyond the initial burst at 20 KB/s
+        assert!(start.elapsed() >= std::time::Duration::from_millis(150));
+        let mut reader = StreamingZipReader::from_vec(target.into_inner())?;
+        assert_eq!(reader.read_entry_by_name("noise.bin")?, noise);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "encryption")]
+    #[tokio::test]
+    async fn test_async_writer_encrypted_entry() -> Result<()> {
+        let mut writer = AsyncStreamingZipWriter::from_writer(Vec::new());
+        writer.add_entry("public.txt", b"open").await?;
+        writer
+            .start_entry_encrypted("secret.txt", "hunter2")
+            .await?;
+        writer.write_data(&b"classified ".repeat(1000)).await?;
+        writer.add_entry("after.txt", b"open again").await?;
+        let bytes = writer.finish().await?;
+
+        let mut reader = StreamingZipReader::from_vec(bytes)?;
+        let flags: Vec<bool> = reader.entries().iter().map(|e| e.is_encrypted).collect();
+        assert_eq!(flags, [false, true, false]);
+        assert!(reader.read_entry_by_name("secret.txt").is_err());
+
+        reader.set_password("hunter2");
+        assert_eq!(
+            reader.read_entry_by_name("secret.txt")?,
+            b"classified ".repeat(1000)
+        );
+        assert_eq!(reader.read_entry_by_name("after.txt")?, b"open again");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_async_self_extracting_writer() -> Result<()> {
+        let sfx = s_zip::sfx::SfxBuilder::new(b"#!/bin/sh\nexit 0\n".to_vec());
+        let mut writer = sfx.async_writer(Vec::new()).await?;
+        writer.add_entry("a.txt", b"stubbed").await?;
+        let bytes = writer.finish().await?;
+
+        assert!(bytes.starts_with(sfx.stub()));
+        let mut reader = StreamingZipReader::from_vec(bytes)?;
+        assert_eq!(reader.entries()[0].offset, sfx.stub().len() as u64);
+        assert_eq!(reader.read_entry_by_name("a.txt")?, b"stubbed");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_async_archive_comment() -> Result<()> {
+        let mut writer = AsyncStreamingZipWriter::from_writer(Vec::new());
+        writer.set_archive_comment("nightly");
+        writer.add_entry("a.txt", b"a").await?;
+        let bytes = writer.finish().await?;
+        let reader = s_zip::GenericAsyncZipReader::from_bytes(bytes).await?;
+        assert_eq!(reader.comment(), "nightly");
+        assert_eq!(reader.archive_comment(), b"nightly");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_async_add_directory() -> Result<()> {
+        let mut writer = AsyncStreamingZipWriter::from_writer(Vec::new());
+        writer.add_directory("assets").await?;
+        writer.add_entry("assets/logo.txt", b"logo").await?;
+        let bytes = writer.finish().await?;
+
+        let mut reader = StreamingZipReader::from_vec(bytes)?;
+        let dir = &reader.entries()[0];
+        assert_eq!(dir.name, "assets/");
+        assert_eq!((dir.compression_method, dir.compressed_size), (0, 0));
+        assert!(dir.dos_attributes().unwrap().directory);
+        assert_eq!(reader.read_entry_by_name("assets/logo.txt")?, b"logo");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_async_entry_timestamps() -> Result<()> {
+        let mtime = DosDateTime::from_parts(2021, 3, 14, 15, 9, 26).unwrap();
+        let mut writer = AsyncStreamingZipWriter::from_writer(Vec::new());
+        let opts = EntryOptions::default()
+            .with_mtime(mtime.to_system_time().unwrap())
+            .with_unix_mode(0o100755);
+        writer.start_entry_with_options("run.sh", opts).await?;
+        writer.write_data(b"#!/bin/sh\n").await?;
+        writer.add_entry("now.txt", b"stamped").await?;
+        let bytes = writer.finish().await?;
+
+        let mut reader = StreamingZipReader::from_vec(bytes)?;
+        let entry = &reader.entries()[0];
+        assert_eq!(entry.last_modified, mtime);
+        assert_eq!(entry.unix_mode(), Some(0o100755));
+        // Without options the entry carries the current time, not 1980-01-01
+        assert!(reader.entries()[1].last_modified.year() >= 2024);
+        // The local header agrees with the central directory
+        assert!(reader.verify()?.is_ok());
+        assert_eq!(reader.read_entry_by_name("run.sh")?, b"#!/bin/sh\n");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_async_write_file_keeps_metadata() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("run.sh");
+        let content = "echo hello\n".repeat(10_000);
+        std::fs::write(&path, &content)?;
+        let mtime = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_600_000_000);
+        std::fs::File::options()
+            .write(true)
+            .open(&path)?
+            .set_modified(mtime)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o750))?;
+        }
+
+        let mut writer = AsyncStreamingZipWriter::from_writer(Vec::new());
+        writer.write_file("bin/run.sh", &path).await?;
+        let bytes = writer.finish().await?;
+
+        let mut reader = StreamingZipReader::from_vec(bytes)?;
+        let entry = &reader.entries()[0];
+        assert_eq!(entry.modified(), Some(mtime));
+        #[cfg(unix)]
+        assert_eq!(entry.unix_mode(), Some(0o100750));
+        assert_eq!(reader.read_entry_by_name("bin/run.sh")?, content.as_bytes());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_async_add_dir_recursive() -> Result<()> {
+        use s_zip::ignore::{DirOptions, IgnoreRules};
+
+        let dir = tempfile::tempdir()?;
+        let root = dir.path();
+        for (path, data) in [
+            ("Cargo.toml", "[package]"),
+            (".DS_Store", "junk"),
+            ("src/lib.rs", "pub mod a;"),
+            ("src/a/mod.rs", "pub fn a() {}"),
+            ("src/a/deep/x.rs", "// deep"),
+            ("src/notes.md", "notes"),
+            ("target/debug/app.rs", "built"),
+            ("docs/.gitignore", "*.tmp\n"),
+            ("docs/guide.tmp", "scratch"),
+        ] {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap())?;
+            std::fs::write(path, data)?;
+        }
+        std::fs::create_dir(root.join("empty"))?;
+        let names = |bytes: Vec<u8>| -> Result<Vec<String>> {
+            let reader = StreamingZipReader::from_vec(bytes)?;
+            Ok(reader.entries().iter().map(|e| e.name.clone()).collect())
+        };
+
+        // Defaults: junk skipped, directories kept, same order as the sync walk
+        let mut writer = AsyncStreamingZipWriter::from_writer(Vec::new());
+        assert_eq!(writer.add_dir_recursive(root, "proj").await?, 8);
+        let mut sync = s_zip::StreamingZipWriter::from_writer(Cursor::new(Vec::new()))?;
+        sync.add_dir_recursive(root, "proj")?;
+        assert_eq!(
+            names(writer.finish().await?)?,
+            names(sync.finish()?.into_inner())?
+        );
+
+        // Include and exclude patterns, a depth limit and .gitignore files
+        let mut rules = IgnoreRules::default();
+        rules.set_read_gitignore(true);
+        let options = DirOptions::default()
+            .with_ignore(rules)
+            .with_exclude("target/")
+            .with_include("*.rs")
+            .with_include("*.toml")
+            .with_include("docs/*")
+            .with_max_depth(2);
+        let mut writer = AsyncStreamingZipWriter::from_writer(Vec::new());
+        assert_eq!(
+            writer
+                .add_dir_recursive_with_options(root, "", &options)
+                .await?,
+            4
+        );
+        assert_eq!(
+            names(writer.finish().await?)?,
+            [
+                "Cargo.toml",
+                "docs/.gitignore",
+                "src/a/mod.rs",
+                "src/lib.rs"
+            ]
+        );
+
+        // The parallel path keeps file metadata
+        let mtime = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_600_000_000);
+        std::fs::File::options()
+            .write(true)
+            .open(root.join("src/lib.rs"))?
+            .set_modified(mtime)?;
+        let options = DirOptions::default()
+            .with_include("src/**")
+            .with_parallel(ParallelConfig::default());
+        let mut writer = AsyncStreamingZipWriter::from_writer(Vec::new());
+        assert_eq!(
+            writer
+                .add_dir_recursive_with_options(root, "", &options)
+                .await?,
+            4
+        );
+        let mut reader = StreamingZipReader::from_vec(writer.finish().await?)?;
+        let entry = reader.find_entry("src/lib.rs").unwrap();
+        assert_eq!(entry.modified(), Some(mtime));
+        #[cfg(unix)]
+        assert_eq!(
+            entry.unix_mode().map(|mode| mode & 0o170000),
+            Some(0o100000)
+        );
+        assert_eq!(reader.read_entry_by_name("src/a/deep/x.rs")?, b"// deep");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_async_stored_entries_pass_data_through() -> Result<()> {
+        let media: Vec<u8> = (0..300_000u32)
+            .map(|i| (i.wrapping_mul(2_654_435_761) >> 24) as u8)
+            .collect();
+        let mut writer = AsyncStreamingZipWriter::from_writer_with_method(
+            Vec::new(),
+            CompressionMethod::Stored,
+            0,
+        );
+        writer.add_entry("clip.mp4", &media).await?;
+        let zip = writer.finish().await?;
+
+        let mut reader = StreamingZipReader::from_vec(zip.clone())?;
+        let entry = reader.find_entry("clip.mp4").unwrap().clone();
+        assert_eq!(entry.compression_method, 0);
+        assert_eq!(entry.compressed_size, media.len() as u64);
+        assert_eq!(entry.uncompressed_size, media.len() as u64);
+        assert_eq!(entry.crc32, crc32fast::hash(&media));
+        let (offset, len) = reader.data_span(&entry)?;
+        assert_eq!(&zip[offset as usize..(offset + len) as usize], &media[..]);
+        assert!(reader.verify()?.is_ok());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_async_auto_store() -> Result<()> {
+        let mut state = 0x9e37_79b9_7f4a_7c15u64;
+        let noise: Vec<u8> = (0..100_000)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state as u8
+            })
+            .collect();
+        let text = b"the quick brown fox jumps over the lazy dog\n".repeat(2_000);
+
+        let mut writer = AsyncStreamingZipWriter::from_writer(Vec::new());
+        writer.set_auto_store(true);
+        writer.add_entry("noise.bin", &noise).await?;
+        writer.add_entry("notes.txt", &text).await?;
+        writer.add_entry("clip.mp4", &text).await?;
+        let zip = writer.finish().await?;
+
+        let mut reader = StreamingZipReader::from_vec(zip)?;
+        assert_eq!(
+            reader.find_entry("noise.bin").unwrap().compression_method,
+            0
+        );
+        assert_eq!(
+            reader.find_entry("notes.txt").unwrap().compression_method,
+            8
+        );
+        assert_eq!(reader.find_entry("clip.mp4").unwrap().compression_method, 0);
+        assert!(reader.verify()?.is_ok());
+        assert_eq!(reader.read_entry_by_name("noise.bin")?, noise);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_async_deflate_threads() -> Result<()> {
+        let text: Vec<u8> = (0..120_000u32)
+            .flat_map(|i| format!("row {} of the export, checksum {}\n", i, i % 97).into_bytes())
+            .collect();
+
+        let mut writer = AsyncStreamingZipWriter::from_writer(Vec::new());
+        writer.set_deflate_threads(4);
+        writer.set_compression_offload(Some(1));
+        writer.start_entry("export.txt").await?;
+        for chunk in text.chunks(100_000) {
+            writer.write_data(chunk).await?;
+        }
+        writer.add_entry("small.txt", b"below one block").await?;
+        let zip = writer.finish().await?;
+
+        let mut reader = StreamingZipReader::from_vec(zip)?;
+        assert!(reader.verify()?.is_ok());
+        assert!(reader.find_entry("export.txt").unwrap().compressed_size < text.len() as u64 / 4);
+        assert_eq!(reader.read_entry_by_name("export.txt")?, text);
+        assert_eq!(reader.read_entry_by_name("small.txt")?, b"below one block");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_async_append() -> Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("logs.zip");
+        let mut writer = AsyncStreamingZipWriter::new(&path).await?;
+        writer.add_entry("monday.log", b"first day").await?;
+        writer.finish().await?;
+
+        let mut writer = AsyncStreamingZipWriter::append(&path).await?;
+        writer.add_entry("tuesday.log", b"second day").await?;
+        writer.finish().await?;
+
+        let mut reader = StreamingZipReader::open(&path)?;
+        let names: Vec<_> = reader.entries().iter().map(|e| e.name.clone()).collect();
+        assert_eq!(names, ["monday.log", "tuesday.log"]);
+        assert!(reader.verify()?.is_ok());
+        assert_eq!(reader.read_entry_by_name("monday.log")?, b"first day");
+        assert_eq!(reader.read_entry_by_name("tuesday.log")?, b"second day");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_async_merge() -> Result<()> {
+        use s_zip::merge::{merge_async, ConflictPolicy};
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut shards = Vec::new();
+        for i in 0..3 {
+            let path = dir.path().join(format!("shard-{}.zip", i));
+            let mut writer = AsyncStreamingZipWriter::new(&path).await?;
+            writer
+                .add_entry(&format!("part-{}.txt", i), format!("part {}", i).as_bytes())
+                .await?;
+            writer.add_entry("latest.txt", &[b'0' + i]).await?;
+            writer.finish().await?;
+            shards.push(path);
+        }
+        let output = dir.path().join("all.zip");
+        let report = merge_async(&shards, &output, ConflictPolicy::KeepLast).await?;
+        assert_eq!(report.copied, 4);
+        assert_eq!(report.skipped, ["latest.txt", "latest.txt"]);
+
+        let mut reader = StreamingZipReader::open(&output)?;
+        let names: Vec<_> = reader.entries().iter().map(|e| e.name.clone()).collect();
+        assert_eq!(
+            names,
+            ["part-0.txt", "part-1.txt", "part-2.txt", "latest.txt"]
+        );
+        assert!(reader.verify()?.is_ok());
+        assert_eq!(reader.read_entry_by_name("part-1.txt")?, b"part 1");
+        assert_eq!(reader.read_entry_by_name("latest.txt")?, b"2");
+        Ok(())
+    }
 }
@@ -0,0 +1,76 @@
+//! Tests for the blocking wrappers around the async writers
+//!
+//! Run with: cargo test --features async
+
+#![cfg(feature = "async")]
+
+use s_zip::blocking::BlockingZipWriter;
+use s_zip::StreamingZipReader;
+use std::io::Write;
+use tempfile::NamedTempFile;
+
+#[test]
+fn test_blocking_writer_file() {
+    let tmp = NamedTempFile::new().unwrap();
+
+    let mut writer = BlockingZipWriter::new(tmp.path()).unwrap();
+    writer.add_entry("a.txt", b"from sync code").unwrap();
+    writer.start_entry("b.txt").unwrap();
+    write!(writer, "{}", "line\n".repeat(100)).unwrap();
+    assert_eq!(writer.entry_count(), 2);
+    writer.finish().unwrap();
+
+    let mut reader = StreamingZipReader::open(tmp.path()).unwrap();
+    assert_eq!(
+        reader.read_entry_by_name("a.txt").unwrap(),
+        b"from sync code"
+    );
+    assert_eq!(
+        reader.read_entry_by_name("b.txt").unwrap(),
+        "line\n".repeat(100).as_bytes()
+    );
+}
+
+#[test]
+fn test_blocking_writer_from_async() {
+    let mut writer =
+        BlockingZipWriter::from_async(|| async { Ok(std::io::Cursor::new(Vec::new())) }).unwrap();
+    writer.add_entry("data.bin", &[7u8; 4096]).unwrap();
+    let (cursor, stats) = writer.finish_with_stats().unwrap();
+    assert_eq!(stats.entry_count, 1);
+
+    let tmp = NamedTempFile::new().unwrap();
+    std::fs::write(tmp.path(), cursor.into_inner()).unwrap();
+    let mut reader = StreamingZipReader::open(tmp.path()).unwrap();
+    assert_eq!(
+        reader.read_entry_by_name("data.bin").unwrap(),
+        vec![7u8; 4096]
+    );
+}
+
+#[test]
+fn test_blocking_writer_runs_background_tasks_between_calls() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    // Like an upload task spawned by a cloud writer
+    let uploaded = Arc::new(AtomicBool::new(false));
+    let flag = Arc::clone(&uploaded);
+    let mut writer = BlockingZipWriter::from_async(|| async move {
+        tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            flag.store(true, Ordering::SeqCst);
+        });
+        Ok(std::io::Cursor::new(Vec::new()))
+    })
+    .unwrap();
+
+    // No call is blocking on the runtime while the caller sleeps
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+    while !uploaded.load(Ordering::SeqCst) && std::time::Instant::now() < deadline {
+        std::thread::sleep(std::time::Duration::from_millis(5));
+    }
+    assert!(uploaded.load(Ordering::SeqCst));
+    writer.add_entry("a.txt", b"a").unwrap();
+    writer.finish().unwrap();
+}
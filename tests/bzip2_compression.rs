@@ -0,0 +1,69 @@
+#[cfg(feature = "bzip2")]
+#[test]
+fn test_bzip2_roundtrip() {
+    use s_zip::{CompressionMethod, StreamingZipReader, StreamingZipWriter};
+    use std::io::{Cursor, Read};
+
+    let text = b"legacy vendor export, one row per line\n".repeat(5_000);
+    let mut writer = StreamingZipWriter::from_writer_with_method(
+        Cursor::new(Vec::new()),
+        CompressionMethod::Bzip2,
+        9,
+    )
+    .unwrap();
+    writer.start_entry("export.csv").unwrap();
+    // Many small writes still share bzip2 blocks
+    for chunk in text.chunks(1_000) {
+        writer.write_data(chunk).unwrap();
+    }
+    writer.add_entry("empty.txt", b"").unwrap();
+    writer.add_entry("data/", b"").unwrap();
+    let zip = writer.finish().unwrap().into_inner();
+
+    let mut reader = StreamingZipReader::from_vec(zip).unwrap();
+    let entry = reader.find_entry("export.csv").unwrap().clone();
+    assert_eq!(entry.compression_method, 12);
+    assert!(entry.compressed_size < 1_000);
+    assert_eq!(reader.find_entry("data/").unwrap().compression_method, 0);
+    assert_eq!(reader.read_entry_by_name("export.csv").unwrap(), text);
+    assert_eq!(reader.read_entry_by_name("empty.txt").unwrap(), b"");
+
+    let mut streamed = Vec::new();
+    reader
+        .read_entry_streaming(&entry)
+        .unwrap()
+        .read_to_end(&mut streamed)
+        .unwrap();
+    assert_eq!(streamed, text);
+    assert!(reader.verify().unwrap().is_ok());
+}
+
+#[cfg(all(feature = "bzip2", feature = "async"))]
+#[tokio::test]
+async fn test_async_bzip2_roundtrip() -> s_zip::Result<()> {
+    use s_zip::{AsyncStreamingZipWriter, CompressionMethod, GenericAsyncZipReader};
+    use std::io::Cursor;
+    use tokio::io::AsyncReadExt;
+
+    let text = b"legacy vendor export, one row per line\n".repeat(5_000);
+    let mut writer =
+        AsyncStreamingZipWriter::from_writer_with_method(Vec::new(), CompressionMethod::Bzip2, 6);
+    writer.start_entry("export.csv").await?;
+    for chunk in text.chunks(1_000) {
+        writer.write_data(chunk).await?;
+    }
+    let zip = writer.finish().await?;
+
+    let mut reader = GenericAsyncZipReader::new(Cursor::new(zip)).await?;
+    assert_eq!(reader.entries()[0].compression_method, 12);
+    assert!(reader.entries()[0].compressed_size < 1_000);
+    assert_eq!(reader.read_entry_by_name("export.csv").await?, text);
+    let mut streamed = Vec::new();
+    reader
+        .read_entry_streaming_by_name("export.csv")
+        .await?
+        .read_to_end(&mut streamed)
+        .await?;
+    assert_eq!(streamed, text);
+    Ok(())
+}
@@ -0,0 +1,67 @@
+#[cfg(feature = "lzma")]
+#[test]
+fn test_lzma_and_xz_roundtrip() {
+    use s_zip::{CompressionMethod, StreamingZipReader, StreamingZipWriter};
+    use std::io::{Cursor, Read};
+
+    let text = b"7-Zip users send LZMA archives\n".repeat(5_000);
+    for (method, id) in [(CompressionMethod::Lzma, 14), (CompressionMethod::Xz, 95)] {
+        let mut writer =
+            StreamingZipWriter::from_writer_with_method(Cursor::new(Vec::new()), method, 6)
+                .unwrap();
+        writer.start_entry("notes.txt").unwrap();
+        for chunk in text.chunks(1_000) {
+            writer.write_data(chunk).unwrap();
+        }
+        writer.add_entry("empty.txt", b"").unwrap();
+        let zip = writer.finish().unwrap().into_inner();
+
+        let mut reader = StreamingZipReader::from_vec(zip).unwrap();
+        let entry = reader.find_entry("notes.txt").unwrap().clone();
+        assert_eq!(entry.compression_method, id);
+        // LZMA data ends with an end marker, and says so
+        assert_eq!(entry.flags & 0x0002 != 0, id == 14);
+        assert!(entry.compressed_size < 1_000);
+        assert_eq!(reader.read_entry_by_name("notes.txt").unwrap(), text);
+        assert_eq!(reader.read_entry_by_name("empty.txt").unwrap(), b"");
+
+        let mut streamed = Vec::new();
+        reader
+            .read_entry_streaming(&entry)
+            .unwrap()
+            .read_to_end(&mut streamed)
+            .unwrap();
+        assert_eq!(streamed, text);
+        assert!(reader.verify().unwrap().is_ok());
+    }
+}
+
+#[cfg(all(feature = "lzma", feature = "async"))]
+#[tokio::test]
+async fn test_async_lzma_and_xz_roundtrip() -> s_zip::Result<()> {
+    use s_zip::{AsyncStreamingZipWriter, CompressionMethod, GenericAsyncZipReader};
+    use std::io::Cursor;
+    use tokio::io::AsyncReadExt;
+
+    let text = b"7-Zip users send LZMA archives\n".repeat(5_000);
+    for (method, id) in [(CompressionMethod::Lzma, 14), (CompressionMethod::Xz, 95)] {
+        let mut writer = AsyncStreamingZipWriter::from_writer_with_method(Vec::new(), method, 6);
+        writer.start_entry("notes.txt").await?;
+        for chunk in text.chunks(1_000) {
+            writer.write_data(chunk).await?;
+        }
+        let zip = writer.finish().await?;
+
+        let mut reader = GenericAsyncZipReader::new(Cursor::new(zip)).await?;
+        assert_eq!(reader.entries()[0].compression_method, id);
+        assert_eq!(reader.read_entry_by_name("notes.txt").await?, text);
+        let mut streamed = Vec::new();
+        reader
+            .read_entry_streaming_by_name("notes.txt")
+            .await?
+            .read_to_end(&mut streamed)
+            .await?;
+        assert_eq!(streamed, text);
+    }
+    Ok(())
+}
@@ -83,6 +83,26 @@ async fn test_seekless_entry_count_bytes_written() {
     writer.finish().await.unwrap();
 }
 
+#[tokio::test]
+async fn test_seekless_extended_timestamp() {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    let mtime = UNIX_EPOCH + Duration::from_secs(1_700_000_001);
+    let mut writer = SeeklessZipWriter::new(Vec::new());
+    let options = s_zip::EntryOptions::default()
+        .with_mtime(mtime)
+        .with_unix_mode(0o644);
+    writer
+        .add_entry_with_options("a.txt", b"abc", options)
+        .await
+        .unwrap();
+    let bytes = writer.finish().await.unwrap();
+
+    let mut reader = s_zip::StreamingZipReader::from_vec(bytes).unwrap();
+    assert_eq!(reader.entries()[0].modified(), Some(mtime));
+    assert!(reader.verify().unwrap().is_ok());
+}
+
 // ── add_entry / entry_count / bytes_written (sync) ──────────────────────────
 
 #[test]
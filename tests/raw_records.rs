@@ -0,0 +1,151 @@
+//! Tests for the low-level record writers in `s_zip::raw`
+
+use s_zip::format::parse_zip64_extra_field;
+use s_zip::raw::{self, CentralDirectoryRecord, EndOfCentralDirectory};
+use s_zip::{CompressionMethod, EntryOptions, StreamingZipReader, StreamingZipWriter};
+use std::io::Cursor;
+
+#[test]
+fn test_central_record_zip64_offset() {
+    let mut out = Vec::new();
+    let offset = 5 * 1024 * 1024 * 1024u64;
+    let written = raw::write_central_record(
+        &mut out,
+        &CentralDirectoryRecord {
+            compressed_size: 10,
+            uncompressed_size: 20,
+            local_header_offset: offset,
+            name: b"far.bin",
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(written as usize, out.len());
+
+    // Offset field carries the ZIP64 marker, sizes are stored inline
+    assert_eq!(&out[20..24], &10u32.to_le_bytes());
+    assert_eq!(&out[24..28], &20u32.to_le_bytes());
+    assert_eq!(&out[42..46], &0xFFFF_FFFFu32.to_le_bytes());
+    // Version needed raised to 4.5 for the ZIP64 extra field
+    assert_eq!(&out[6..8], &45u16.to_le_bytes());
+
+    let extra_len = u16::from_le_bytes([out[30], out[31]]) as usize;
+    assert_eq!(extra_len, 12);
+    let extra = &out[46 + 7..46 + 7 + extra_len];
+    assert_eq!(
+        parse_zip64_extra_field(extra, 10, 20, 0xFFFF_FFFF),
+        (20, 10, offset)
+    );
+}
+
+#[test]
+fn test_eocd_zip64_when_many_entries() {
+    let mut out = Vec::new();
+    raw::write_eocd(
+        &mut out,
+        &EndOfCentralDirectory {
+            entry_count: 70_000,
+            cd_size: 100,
+            cd_offset: 200,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    // ZIP64 record (56) + locator (20) + classic EOCD (22)
+    assert_eq!(out.len(), 56 + 20 + 22);
+    assert_eq!(&out[0..4], b"PK\x06\x06");
+    assert_eq!(&out[12..14], &45u16.to_le_bytes()); // version made by
+    assert_eq!(&out[14..16], &45u16.to_le_bytes()); // version needed
+    assert_eq!(&out[56..60], b"PK\x06\x07");
+    assert_eq!(&out[76..80], b"PK\x05\x06");
+    assert_eq!(&out[84..86], &0xFFFFu16.to_le_bytes());
+
+    // The entries' versions carry over; made-by keeps its host
+    let mut out = Vec::new();
+    raw::write_eocd(
+        &mut out,
+        &EndOfCentralDirectory {
+            entry_count: 70_000,
+            version_made_by: 0x0314,
+            version_needed: 63,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(&out[12..14], &0x033Fu16.to_le_bytes());
+    assert_eq!(&out[14..16], &63u16.to_le_bytes());
+
+    // Written by the streaming writer, from its made-by and the highest
+    // version any entry needs
+    let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new())).unwrap();
+    writer.set_version_made_by(0x0340);
+    for i in 0..=u16::MAX as u32 {
+        writer.add_entry(&format!("{i}"), b"").unwrap();
+    }
+    writer.set_version_needed(51);
+    writer.add_entry("last", b"").unwrap();
+    let bytes = writer.finish().unwrap().into_inner();
+    let zip64 = bytes.len() - (56 + 20 + 22);
+    assert_eq!(&bytes[zip64..zip64 + 4], b"PK\x06\x06");
+    assert_eq!(&bytes[zip64 + 12..zip64 + 14], &0x0340u16.to_le_bytes());
+    assert_eq!(&bytes[zip64 + 14..zip64 + 16], &51u16.to_le_bytes());
+}
+
+#[test]
+fn test_entry_options_unix_mode_roundtrip() {
+    let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new())).unwrap();
+    let opts = EntryOptions::default().with_unix_mode(0o755);
+    writer.start_entry_with_options("run.sh", opts).unwrap();
+    writer.write_data(b"#!/bin/sh\necho hi\n").unwrap();
+    writer.add_entry("after.txt", b"next").unwrap();
+    let bytes = writer.finish().unwrap().into_inner();
+
+    let mut reader = StreamingZipReader::from_vec(bytes).unwrap();
+    assert_eq!(
+        reader.read_entry_by_name("run.sh").unwrap(),
+        b"#!/bin/sh\necho hi\n"
+    );
+    assert_eq!(reader.read_entry_by_name("after.txt").unwrap(), b"next");
+}
+
+#[test]
+fn test_writer_version_needed_per_method() {
+    assert_eq!(raw::version_needed(0, false), 10);
+    assert_eq!(raw::version_needed(8, false), 20);
+    assert_eq!(raw::version_needed(93, false), 63);
+    assert_eq!(raw::version_needed(95, false), 63);
+    assert_eq!(raw::version_needed(0, true), 51);
+
+    let mut writer = StreamingZipWriter::from_writer_with_method(
+        Cursor::new(Vec::new()),
+        CompressionMethod::Stored,
+        0,
+    )
+    .unwrap();
+    writer.add_entry("plain.txt", b"stored").unwrap();
+    let bytes = writer.finish().unwrap().into_inner();
+
+    // Local header and central record both announce 1.0 for a stored entry
+    assert_eq!(&bytes[4..6], &10u16.to_le_bytes());
+    let reader = StreamingZipReader::from_vec(bytes).unwrap();
+    assert_eq!(reader.entries()[0].version_needed, 10);
+}
+
+#[cfg(feature = "lzma")]
+#[test]
+fn test_writer_version_needed_xz() {
+    let mut writer = StreamingZipWriter::from_writer_with_method(
+        Cursor::new(Vec::new()),
+        CompressionMethod::Xz,
+        6,
+    )
+    .unwrap();
+    writer.add_entry("packed.txt", b"xz xz xz").unwrap();
+    let bytes = writer.finish().unwrap().into_inner();
+
+    // APPNOTE 4.4.3: XZ needs 6.3
+    assert_eq!(&bytes[4..6], &63u16.to_le_bytes());
+    let reader = StreamingZipReader::from_vec(bytes).unwrap();
+    assert_eq!(reader.entries()[0].version_needed, 63);
+}
@@ -0,0 +1,2001 @@
+//! Tests for the sync streaming ZIP reader
+
+use s_zip::encoding::FilenameEncoding;
+use s_zip::raw::{self, CentralDirectoryRecord, EndOfCentralDirectory, LocalFileHeader};
+use s_zip::{
+    CompactZipReader, CompressionMethod, DosDateTime, EntryOptions, EntryProblem, ExtractConfig,
+    ExtractOptions, IndexedZipReader, LazyEntries, ReaderOptions, SZipError, StreamingZipReader,
+    StreamingZipWriter,
+};
+use std::io::{Cursor, Read, Write};
+use std::sync::Arc;
+use tempfile::NamedTempFile;
+
+fn write_sample_zip(entries: &[(&str, &[u8])]) -> NamedTempFile {
+    let tmp = NamedTempFile::new().unwrap();
+    let mut writer = StreamingZipWriter::new(tmp.path()).unwrap();
+    for (name, data) in entries {
+        writer.add_entry(name, data).unwrap();
+    }
+    writer.finish().unwrap();
+    tmp
+}
+
+#[test]
+fn test_read_entries_batch() {
+    let tmp = write_sample_zip(&[
+        ("a.json", b"{\"a\":1}"),
+        ("b.json", b"{\"b\":2}"),
+        ("c.json", b"{\"c\":3}"),
+    ]);
+
+    let mut reader = StreamingZipReader::open(tmp.path()).unwrap();
+    // Request out of archive order, with a duplicate
+    let files = reader
+        .read_entries(&["c.json", "a.json", "c.json"])
+        .unwrap();
+
+    assert_eq!(files.len(), 2);
+    assert_eq!(files["a.json"], b"{\"a\":1}");
+    assert_eq!(files["c.json"], b"{\"c\":3}");
+}
+
+#[test]
+fn test_read_entries_missing_name() {
+    let tmp = write_sample_zip(&[("a.json", b"{}")]);
+
+    let mut reader = StreamingZipReader::open(tmp.path()).unwrap();
+    let err = reader.read_entries(&["a.json", "nope.json"]).unwrap_err();
+    assert!(matches!(err, SZipError::EntryNotFound(ref n) if n == "nope.json"));
+}
+
+#[test]
+fn test_read_entries_aliased_records() {
+    // Two central records pointing at the same stored local header
+    let data = b"shared";
+    let crc32 = crc32fast::hash(data);
+    let mut out = Vec::new();
+    raw::write_local_header(
+        &mut out,
+        &LocalFileHeader {
+            crc32,
+            compressed_size: data.len() as u64,
+            uncompressed_size: data.len() as u64,
+            name: b"a.txt",
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    out.extend_from_slice(data);
+    let cd_offset = out.len() as u64;
+    let mut cd_size = 0;
+    for name in [b"a.txt", b"b.txt"] {
+        cd_size += raw::write_central_record(
+            &mut out,
+            &CentralDirectoryRecord {
+                crc32,
+                compressed_size: data.len() as u64,
+                uncompressed_size: data.len() as u64,
+                name,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    }
+    raw::write_eocd(
+        &mut out,
+        &EndOfCentralDirectory {
+            entry_count: 2,
+            cd_size,
+            cd_offset,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let mut reader = StreamingZipReader::from_vec(out).unwrap();
+    let files = reader.read_entries(&["b.txt", "a.txt"]).unwrap();
+    assert_eq!(files.len(), 2);
+    assert_eq!(files["a.txt"], data);
+    assert_eq!(files["b.txt"], data);
+}
+
+#[test]
+fn test_into_entry_reader_moves_to_thread() {
+    let content = "owned stream\n".repeat(500);
+    let tmp = write_sample_zip(&[("first.txt", b"x"), ("big.txt", content.as_bytes())]);
+
+    let reader = StreamingZipReader::open(tmp.path()).unwrap();
+    let mut stream = reader.into_entry_reader("big.txt").unwrap();
+
+    let data = std::thread::spawn(move || {
+        let mut data = Vec::new();
+        stream.read_to_end(&mut data).unwrap();
+        data
+    })
+    .join()
+    .unwrap();
+
+    assert_eq!(data, content.as_bytes());
+}
+
+#[test]
+fn test_from_bytes_and_from_vec() {
+    let tmp = write_sample_zip(&[("a.txt", b"in memory"), ("b.txt", b"also")]);
+    let bytes = std::fs::read(tmp.path()).unwrap();
+
+    let mut reader = StreamingZipReader::from_bytes(&bytes).unwrap();
+    assert_eq!(reader.entries().len(), 2);
+    assert_eq!(reader.read_entry_by_name("a.txt").unwrap(), b"in memory");
+
+    let mut reader = StreamingZipReader::from_vec(bytes).unwrap();
+    let mut stream = reader.read_entry_streaming_by_name("b.txt").unwrap();
+    let mut data = Vec::new();
+    stream.read_to_end(&mut data).unwrap();
+    assert_eq!(data, b"also");
+}
+
+#[test]
+fn test_entries_page() {
+    let names: Vec<String> = (0..25).map(|i| format!("f{i:02}.txt")).collect();
+    let entries: Vec<(&str, &[u8])> = names.iter().map(|n| (n.as_str(), &b"x"[..])).collect();
+    let tmp = write_sample_zip(&entries);
+    let reader = StreamingZipReader::open(tmp.path()).unwrap();
+
+    assert_eq!(reader.entry_count(), 25);
+    let pages: Vec<usize> = (0..3)
+        .map(|page| reader.entries_page(page * 10, 10).len())
+        .collect();
+    assert_eq!(pages, [10, 10, 5]);
+    assert_eq!(reader.entries_page(10, 10)[0].name, "f10.txt");
+    assert!(reader.entries_page(30, 10).is_empty());
+    assert_eq!(reader.entries_page(20, usize::MAX).len(), 5);
+}
+
+#[test]
+fn test_reload_rewritten_archive() {
+    let tmp = write_sample_zip(&[("a.txt", b"alpha")]);
+    let mut reader = StreamingZipReader::open(tmp.path()).unwrap();
+    assert_eq!(reader.entry_count(), 1);
+
+    // Another writer rewrites the file in place with more entries
+    let bigger = write_sample_zip(&[("a.txt", b"alpha"), ("b.txt", b"beta")]);
+    std::fs::write(tmp.path(), std::fs::read(bigger.path()).unwrap()).unwrap();
+
+    reader.reload().unwrap();
+    assert_eq!(reader.entry_count(), 2);
+    assert_eq!(reader.read_entry_by_name("b.txt").unwrap(), b"beta");
+}
+
+#[test]
+fn test_read_entry_by_index() {
+    let tmp = write_sample_zip(&[("a.txt", b"alpha"), ("b.txt", b"beta")]);
+    let mut reader = StreamingZipReader::open(tmp.path()).unwrap();
+    for i in 0..reader.entry_count() {
+        let data = reader.read_entry_by_index(i).unwrap();
+        assert_eq!(data.len() as u64, reader.entries()[i].uncompressed_size);
+    }
+    assert_eq!(reader.read_entry_by_index(1).unwrap(), b"beta");
+    assert!(matches!(
+        reader.read_entry_by_index(2),
+        Err(SZipError::EntryNotFound(_))
+    ));
+
+    let mut compact = StreamingZipReader::open_compact(tmp.path()).unwrap();
+    assert_eq!(compact.read_entry_by_index(0).unwrap(), b"alpha");
+}
+
+#[test]
+fn test_open_positioned_concurrent_reads() {
+    let contents: Vec<(String, Vec<u8>)> = (0..8)
+        .map(|i| (format!("file{}.bin", i), vec![i as u8; 10_000 + i * 1000]))
+        .collect();
+    let entries: Vec<(&str, &[u8])> = contents
+        .iter()
+        .map(|(n, d)| (n.as_str(), d.as_slice()))
+        .collect();
+    let tmp = write_sample_zip(&entries);
+
+    let reader = StreamingZipReader::open_positioned(tmp.path()).unwrap();
+    let handles: Vec<_> = contents
+        .iter()
+        .cloned()
+        .map(|(name, expected)| {
+            let mut reader = reader.clone_handle();
+            std::thread::spawn(move || {
+                assert_eq!(reader.read_entry_by_name(&name).unwrap(), expected);
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+}
+
+#[test]
+fn test_entry_exposes_flags_and_versions() {
+    let mut writer = StreamingZipWriter::from_writer(std::io::Cursor::new(Vec::new())).unwrap();
+    writer.set_version_made_by(0x0314).set_version_needed(20);
+    writer.add_entry("custom.txt", b"b").unwrap();
+    let bytes = writer.finish().unwrap().into_inner();
+
+    let reader = StreamingZipReader::from_vec(bytes).unwrap();
+    let custom = reader.find_entry("custom.txt").unwrap();
+    assert_eq!(custom.version_made_by, 0x0314);
+    assert_eq!(custom.version_needed, 20);
+    // Data descriptor flag (bit 3) is always set by the streaming writer
+    assert_eq!(custom.flags & 0x08, 0x08);
+    assert!(!custom.is_encrypted);
+}
+
+#[test]
+fn test_archive_comment() {
+    let tmp = write_sample_zip(&[("a.txt", b"a")]);
+    let reader = StreamingZipReader::open(tmp.path()).unwrap();
+    assert_eq!(reader.comment(), "");
+
+    // Patch the EOCD comment length (last two bytes) and append a comment
+    let mut bytes = std::fs::read(tmp.path()).unwrap();
+    let comment = b"signed-by: release-bot";
+    let len = bytes.len();
+    bytes[len - 2..].copy_from_slice(&(comment.len() as u16).to_le_bytes());
+    bytes.extend_from_slice(comment);
+
+    let mut reader = StreamingZipReader::from_vec(bytes.clone()).unwrap();
+    assert_eq!(reader.comment(), "signed-by: release-bot");
+    assert_eq!(reader.archive_comment(), comment);
+    assert_eq!(reader.read_entry_by_name("a.txt").unwrap(), b"a");
+
+    // A CP437 comment is kept as written and decoded like entry names
+    let len = bytes.len();
+    bytes[len - 1] = 0x81;
+    let reader = StreamingZipReader::from_vec(bytes.clone()).unwrap();
+    assert_eq!(reader.archive_comment(), b"signed-by: release-bo\x81");
+    assert_eq!(reader.comment(), "signed-by: release-bo\u{FC}");
+
+    let utf8 = ReaderOptions::default().with_filename_encoding(FilenameEncoding::Utf8);
+    let reader = StreamingZipReader::new_with_options(Cursor::new(bytes), utf8).unwrap();
+    assert_eq!(reader.comment(), "signed-by: release-bo\u{FFFD}");
+}
+
+#[test]
+fn test_open_with_passed_deadline() {
+    use std::time::{Duration, Instant};
+
+    let tmp = write_sample_zip(&[("a.txt", b"a"), ("b.txt", b"b")]);
+    let bytes = std::fs::read(tmp.path()).unwrap();
+    let past = Instant::now() - Duration::from_secs(1);
+
+    let options = ReaderOptions::default().with_deadline(past);
+    let opened = StreamingZipReader::open_with_options(tmp.path(), options.clone());
+    assert!(matches!(opened, Err(SZipError::DeadlineExceeded)));
+    let opened = StreamingZipReader::new_with_options(Cursor::new(bytes.clone()), options);
+    assert!(matches!(opened, Err(SZipError::DeadlineExceeded)));
+
+    // A deadline set after opening bounds reloading the directory
+    let mut reader = StreamingZipReader::from_vec(bytes)
+        .unwrap()
+        .with_deadline(past);
+    assert!(matches!(reader.reload(), Err(SZipError::DeadlineExceeded)));
+}
+
+#[test]
+fn test_archive_comment_cp437() {
+    let tmp = write_sample_zip(&[("a.txt", b"a")]);
+    let mut bytes = std::fs::read(tmp.path()).unwrap();
+    // "Größe" in CP437
+    let comment = b"Gr\x94\xe1e";
+    let len = bytes.len();
+    bytes[len - 2..].copy_from_slice(&(comment.len() as u16).to_le_bytes());
+    bytes.extend_from_slice(comment);
+    std::fs::write(tmp.path(), &bytes).unwrap();
+
+    let options = ReaderOptions::default().with_filename_encoding(FilenameEncoding::Cp437);
+    let reader = StreamingZipReader::open_with_options(tmp.path(), options).unwrap();
+    assert_eq!(reader.comment(), "Größe");
+    assert_eq!(reader.archive_comment(), comment);
+}
+
+#[test]
+fn test_entry_last_modified() {
+    let stamp = DosDateTime::from_parts(2021, 6, 15, 8, 30, 10).unwrap();
+    let mut out = Vec::new();
+    raw::write_local_header(
+        &mut out,
+        &LocalFileHeader {
+            last_mod_time: stamp.time,
+            last_mod_date: stamp.date,
+            name: b"dated.txt",
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let cd_offset = out.len() as u64;
+    let cd_size = raw::write_central_record(
+        &mut out,
+        &CentralDirectoryRecord {
+            last_mod_time: stamp.time,
+            last_mod_date: stamp.date,
+            name: b"dated.txt",
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    raw::write_eocd(
+        &mut out,
+        &EndOfCentralDirectory {
+            entry_count: 1,
+            cd_size,
+            cd_offset,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let reader = StreamingZipReader::from_vec(out).unwrap();
+    let modified = reader.entries()[0].last_modified;
+    assert_eq!(modified, stamp);
+    assert_eq!(modified.to_unix_timestamp(), Some(1_623_745_810));
+}
+
+/// Deflate64 stream of "ab" repeated 501 times: a fixed-Huffman block with two
+/// literals and one match whose length code (285) takes 16 extra bits, which
+/// plain DEFLATE would read as a 258-byte match
+#[cfg(feature = "deflate64")]
+const DEFLATE64_ABAB: [u8; 7] = [0x4b, 0x4c, 0x1a, 0x2d, 0x1f, 0x80, 0x00];
+
+#[test]
+#[cfg(feature = "deflate64")]
+fn test_read_deflate64_entry() {
+    let data = b"ab".repeat(501);
+    let crc32 = crc32fast::hash(&data);
+    let mut out = Vec::new();
+    raw::write_local_header(
+        &mut out,
+        &LocalFileHeader {
+            version_needed: 21,
+            compression_method: 9,
+            crc32,
+            compressed_size: DEFLATE64_ABAB.len() as u64,
+            uncompressed_size: data.len() as u64,
+            name: b"large.log",
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    out.extend_from_slice(&DEFLATE64_ABAB);
+    let cd_offset = out.len() as u64;
+    let cd_size = raw::write_central_record(
+        &mut out,
+        &CentralDirectoryRecord {
+            version_needed: 21,
+            compression_method: 9,
+            crc32,
+            compressed_size: DEFLATE64_ABAB.len() as u64,
+            uncompressed_size: data.len() as u64,
+            name: b"large.log",
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    raw::write_eocd(
+        &mut out,
+        &EndOfCentralDirectory {
+            entry_count: 1,
+            cd_size,
+            cd_offset,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let mut reader = StreamingZipReader::from_vec(out).unwrap();
+    assert!(s_zip::capabilities().read_methods.contains(&9));
+    assert_eq!(reader.read_entry_by_name("large.log").unwrap(), data);
+    let mut streamed = Vec::new();
+    reader
+        .read_entry_by_name_streaming("large.log")
+        .unwrap()
+        .read_to_end(&mut streamed)
+        .unwrap();
+    assert_eq!(streamed, data);
+    assert!(reader.verify().unwrap().is_ok());
+}
+
+/// Archive of empty entries with the given raw names and general purpose flags
+fn raw_names_zip(names: &[(&[u8], u16)]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut offsets = Vec::new();
+    for &(name, flags) in names {
+        offsets.push(out.len() as u64);
+        raw::write_local_header(
+            &mut out,
+            &LocalFileHeader {
+                flags,
+                name,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    }
+    let cd_offset = out.len() as u64;
+    let mut cd_size = 0;
+    for (&(name, flags), local_header_offset) in names.iter().zip(offsets) {
+        cd_size += raw::write_central_record(
+            &mut out,
+            &CentralDirectoryRecord {
+                flags,
+                local_header_offset,
+                name,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    }
+    raw::write_eocd(
+        &mut out,
+        &EndOfCentralDirectory {
+            entry_count: names.len() as u64,
+            cd_size,
+            cd_offset,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    out
+}
+
+#[test]
+fn test_entry_name_encoding() {
+    // Windows tools write CP437 names without the UTF-8 flag (bit 11)
+    let out = raw_names_zip(&[(b"M\x81nchen.txt", 0), ("Zürich.txt".as_bytes(), 0x0800)]);
+    let mut reader = StreamingZipReader::from_vec(out).unwrap();
+    let names: Vec<&str> = reader.entries().iter().map(|e| e.name.as_str()).collect();
+    assert_eq!(names, ["München.txt", "Zürich.txt"]);
+    assert!(reader.verify().unwrap().is_ok());
+
+    // Non-ASCII names are written with the flag set
+    let tmp = write_sample_zip(&[("Zürich.txt", b"z")]);
+    let reader = StreamingZipReader::open(tmp.path()).unwrap();
+    assert_eq!(reader.entries()[0].flags & 0x0800, 0x0800);
+}
+
+#[test]
+fn test_custom_filename_encoding() {
+    // "日本.txt" in Shift-JIS, as written by Japanese Windows
+    let out = raw_names_zip(&[
+        (b"\x93\xfa\x96\x7b.txt", 0),
+        ("Zürich.txt".as_bytes(), 0x0800),
+    ]);
+    let mut tmp = NamedTempFile::new().unwrap();
+    tmp.write_all(&out).unwrap();
+
+    let shift_jis = |name: &[u8]| {
+        let name = name.strip_prefix(b"\x93\xfa\x96\x7b").unwrap();
+        format!("日本{}", std::str::from_utf8(name).unwrap())
+    };
+    let options = ReaderOptions::default()
+        .with_filename_encoding(FilenameEncoding::Custom(Arc::new(shift_jis)));
+    let mut reader = StreamingZipReader::open_with_options(tmp.path(), options).unwrap();
+    let names: Vec<&str> = reader.entries().iter().map(|e| e.name.as_str()).collect();
+    // Flagged names stay UTF-8
+    assert_eq!(names, ["日本.txt", "Zürich.txt"]);
+    assert!(reader.verify().unwrap().is_ok());
+
+    let reader = StreamingZipReader::open(tmp.path()).unwrap();
+    assert_eq!(reader.entries()[0].name, "ô·û{.txt");
+}
+
+#[test]
+fn test_filename_encoding_kept_for_every_directory_read() {
+    let shift_jis = || {
+        FilenameEncoding::Custom(Arc::new(|name: &[u8]| {
+            let name = name.strip_prefix(b"\x93\xfa\x96\x7b").unwrap_or(name);
+            format!("日本{}", std::str::from_utf8(name).unwrap())
+        }))
+    };
+    let first = raw_names_zip(&[(b"\x93\xfa\x96\x7b1.txt", 0)]);
+    let names = |reader: &StreamingZipReader<_>| -> Vec<String> {
+        reader.entries().iter().map(|e| e.name.clone()).collect()
+    };
+
+    // A known directory location
+    let mut tmp = NamedTempFile::new().unwrap();
+    tmp.write_all(&first).unwrap();
+    let location = StreamingZipReader::open(tmp.path())
+        .unwrap()
+        .cd_location()
+        .unwrap();
+    let options = ReaderOptions::default()
+        .with_filename_encoding(shift_jis())
+        .with_cd_location(location);
+    let reader = StreamingZipReader::open_with_options(tmp.path(), options).unwrap();
+    assert_eq!(names(&reader), ["日本1.txt"]);
+
+    // An archive appended after opening
+    let options = ReaderOptions::default().with_filename_encoding(shift_jis());
+    let mut reader = StreamingZipReader::open_with_options(tmp.path(), options).unwrap();
+    tmp.write_all(&raw_names_zip(&[(b"\x93\xfa\x96\x7b2.txt", 0)]))
+        .unwrap();
+    assert!(reader.refresh().unwrap());
+    assert_eq!(names(&reader), ["日本1.txt", "日本2.txt"]);
+
+    // A split archive
+    let source = s_zip::split::SplitSource::from_volumes(vec![Cursor::new(first)]).unwrap();
+    let options = ReaderOptions::default().with_filename_encoding(shift_jis());
+    let reader = StreamingZipReader::from_split_with_options(source, options).unwrap();
+    assert_eq!(reader.entries()[0].name, "日本1.txt");
+}
+
+#[test]
+fn test_filename_encoding_for_lazy_indexed_and_compact_readers() {
+    let options = || {
+        ReaderOptions::default().with_filename_encoding(FilenameEncoding::Custom(Arc::new(
+            |name: &[u8]| {
+                let name = name.strip_prefix(b"\x93\xfa\x96\x7b").unwrap_or(name);
+                format!("日本{}", std::str::from_utf8(name).unwrap())
+            },
+        )))
+    };
+    let zip = raw_names_zip(&[(b"\x93\xfa\x96\x7b1.txt", 0), (b"\x93\xfa\x96\x7b2.txt", 0)]);
+
+    let names: Vec<String> = LazyEntries::new_with_options(Cursor::new(&zip), options())
+        .unwrap()
+        .map(|entry| entry.unwrap().name)
+        .collect();
+    assert_eq!(names, ["日本1.txt", "日本2.txt"]);
+
+    let mut indexed = IndexedZipReader::new_with_options(Cursor::new(&zip), options()).unwrap();
+    assert_eq!(
+        indexed.find_entry("日本2.txt").unwrap().unwrap().name,
+        "日本2.txt"
+    );
+
+    let compact = CompactZipReader::new_with_options(Cursor::new(&zip), options()).unwrap();
+    assert_eq!(compact.entry(0).unwrap().name, "日本1.txt");
+    assert!(compact.find_entry("日本2.txt").is_some());
+}
+
+#[test]
+fn test_entry_attributes() {
+    let mut out = Vec::new();
+    raw::write_local_header(
+        &mut out,
+        &LocalFileHeader {
+            name: b"notes.txt",
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let cd_offset = out.len() as u64;
+    let cd_size = raw::write_central_record(
+        &mut out,
+        &CentralDirectoryRecord {
+            version_made_by: (3 << 8) | 30,
+            internal_attrs: 1,
+            external_attrs: 0o100640 << 16,
+            name: b"notes.txt",
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    raw::write_eocd(
+        &mut out,
+        &EndOfCentralDirectory {
+            entry_count: 1,
+            cd_size,
+            cd_offset,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let reader = StreamingZipReader::from_vec(out.clone()).unwrap();
+    let entry = &reader.entries()[0];
+    assert!(entry.is_text());
+    assert_eq!(entry.unix_mode(), Some(0o100640));
+
+    // The same mode under an MS-DOS host isn't a Unix mode
+    out[cd_offset as usize + 5] = 0;
+    let reader = StreamingZipReader::from_vec(out).unwrap();
+    assert_eq!(reader.entries()[0].external_attrs >> 16, 0o100640);
+    assert_eq!(reader.entries()[0].unix_mode(), None);
+}
+
+#[test]
+fn test_extract_to_dir_restores_metadata() {
+    let mtime = DosDateTime::from_parts(2020, 5, 17, 12, 0, 0)
+        .unwrap()
+        .to_system_time()
+        .unwrap();
+    let mut writer = StreamingZipWriter::from_writer(std::io::Cursor::new(Vec::new())).unwrap();
+    let opts = EntryOptions::default()
+        .with_mtime(mtime)
+        .with_unix_mode(0o750);
+    writer.start_entry_with_options("bin/run.sh", opts).unwrap();
+    writer.write_data(b"#!/bin/sh\n").unwrap();
+    writer.add_entry("empty/", b"").unwrap();
+    let bytes = writer.finish().unwrap().into_inner();
+
+    let out = tempfile::tempdir().unwrap();
+    let mut reader = StreamingZipReader::from_vec(bytes).unwrap();
+    reader.extract_to_dir(out.path()).unwrap();
+
+    let script = out.path().join("bin/run.sh");
+    assert_eq!(std::fs::read(&script).unwrap(), b"#!/bin/sh\n");
+    assert_eq!(
+        std::fs::metadata(&script).unwrap().modified().unwrap(),
+        mtime
+    );
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&script).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o750);
+    }
+    assert!(out.path().join("empty").is_dir());
+    // Metadata restoration can be switched off
+    let plain = tempfile::tempdir().unwrap();
+    let options = ExtractOptions {
+        preserve_mtime: false,
+        ..Default::default()
+    };
+    reader
+        .extract_to_dir_with_options(plain.path(), &options)
+        .unwrap();
+    let modified = std::fs::metadata(plain.path().join("bin/run.sh"))
+        .unwrap()
+        .modified()
+        .unwrap();
+    assert_ne!(modified, mtime);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_extract_restores_directory_metadata() {
+    use std::os::unix::fs::PermissionsExt;
+    let mtime = DosDateTime::from_parts(2019, 3, 2, 8, 30, 0)
+        .unwrap()
+        .to_system_time()
+        .unwrap();
+    let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new())).unwrap();
+    let dir_opts = EntryOptions::default()
+        .with_mtime(mtime)
+        .with_unix_mode(0o700);
+    writer
+        .start_entry_with_options("private/", dir_opts.clone())
+        .unwrap();
+    writer
+        .start_entry_with_options("private/keys/", dir_opts)
+        .unwrap();
+    writer.add_entry("private/keys/id", b"secret").unwrap();
+    let bytes = writer.finish().unwrap().into_inner();
+    let mut tmp = NamedTempFile::new().unwrap();
+    tmp.write_all(&bytes).unwrap();
+
+    let mut reader = StreamingZipReader::open(tmp.path()).unwrap();
+    let out = tempfile::tempdir().unwrap();
+    reader.extract_to_dir(out.path()).unwrap();
+    let parallel = tempfile::tempdir().unwrap();
+    let config = ExtractConfig::default().with_workers(2);
+    reader
+        .extract_entries_parallel(reader.entries(), parallel.path(), &config)
+        .unwrap();
+
+    for root in [out.path(), parallel.path()] {
+        assert_eq!(
+            std::fs::read(root.join("private/keys/id")).unwrap(),
+            b"secret"
+        );
+        // Restored after their contents were written
+        for dir in ["private", "private/keys"] {
+            let meta = std::fs::metadata(root.join(dir)).unwrap();
+            assert_eq!(meta.permissions().mode() & 0o7777, 0o700, "{dir}");
+            assert_eq!(meta.modified().unwrap(), mtime, "{dir}");
+        }
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_extract_preserve_owner_without_privileges() {
+    use std::os::unix::fs::MetadataExt;
+    // Entries with a Unix mode are recorded as owned by uid/gid 0
+    let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new())).unwrap();
+    writer
+        .start_entry_with_options("owned.txt", EntryOptions::default().with_unix_mode(0o644))
+        .unwrap();
+    writer.write_data(b"data").unwrap();
+    let bytes = writer.finish().unwrap().into_inner();
+
+    let out = tempfile::tempdir().unwrap();
+    let uid = std::fs::metadata(out.path()).unwrap().uid();
+    let options = ExtractOptions {
+        preserve_owner: true,
+        ..Default::default()
+    };
+    let mut reader = StreamingZipReader::from_vec(bytes).unwrap();
+    // Like `unzip -X`, a refused chown leaves the file with the extracting user
+    reader
+        .extract_to_dir_with_options(out.path(), &options)
+        .unwrap();
+    assert_eq!(
+        std::fs::read(out.path().join("owned.txt")).unwrap(),
+        b"data"
+    );
+    // Root gets the archived owner, which is root as well
+    let meta = std::fs::metadata(out.path().join("owned.txt")).unwrap();
+    assert_eq!(meta.uid(), uid);
+}
+
+#[test]
+fn test_extract_validate_names() {
+    let out = raw_names_zip(&[(b"ok.txt", 0), (b"../../etc/cron.d/job", 0)]);
+    let mut reader = StreamingZipReader::from_vec(out).unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let err = reader.extract_to_dir(dir.path().join("out")).unwrap_err();
+    assert!(err.to_string().contains("'..'"), "{err}");
+    // Refused before anything is written
+    assert!(!dir.path().join("out").exists());
+
+    // Filtered-out entries aren't checked
+    let options = ExtractOptions::default().with_filter(|entry| entry.name == "ok.txt");
+    reader
+        .extract_to_dir_with_options(dir.path().join("out"), &options)
+        .unwrap();
+    assert!(dir.path().join("out/ok.txt").is_file());
+
+    // Without validation the name is sanitized instead
+    let options = ExtractOptions {
+        validate_names: false,
+        ..Default::default()
+    };
+    reader
+        .extract_to_dir_with_options(dir.path().join("sanitized"), &options)
+        .unwrap();
+    assert!(dir.path().join("sanitized/etc/cron.d/job").is_file());
+}
+
+#[test]
+fn test_extended_timestamp_roundtrip() {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    // An odd second, which the DOS timestamp can't hold
+    let mtime = UNIX_EPOCH + Duration::from_secs(1_700_000_001);
+    let atime = UNIX_EPOCH + Duration::from_secs(1_700_000_999);
+    let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new())).unwrap();
+    let opts = EntryOptions::default().with_mtime(mtime).with_atime(atime);
+    writer
+        .start_entry_with_options("stamped.txt", opts)
+        .unwrap();
+    writer.write_data(b"precise").unwrap();
+    writer.add_entry("plain.txt", b"dos only").unwrap();
+    let bytes = writer.finish().unwrap().into_inner();
+
+    // The local header carries both times
+    let header = s_zip::sansio::LocalHeader::parse(bytes[..30].try_into().unwrap()).unwrap();
+    let variable = &bytes[30..30 + header.variable_len() as usize];
+    let local = header.into_entry(variable, 0).extended_timestamp.unwrap();
+    assert_eq!(local.modified, Some(mtime));
+    assert_eq!(local.accessed, Some(atime));
+
+    // The central directory only the modification time
+    let mut reader = StreamingZipReader::from_vec(bytes).unwrap();
+    let entry = &reader.entries()[0];
+    let central = entry.extended_timestamp.unwrap();
+    assert_eq!(central.modified, Some(mtime));
+    assert_eq!(central.accessed, None);
+    // The raw field is kept: flags and the modification time
+    let (_, raw) = entry.extra_fields().find(|&(id, _)| id == 0x5455).unwrap();
+    assert_eq!(raw[1..], 1_700_000_001u32.to_le_bytes());
+    assert_eq!(entry.modified(), Some(mtime));
+    assert_eq!(entry.last_modified.second(), 20);
+    assert!(reader.entries()[1].extended_timestamp.is_none());
+
+    let out = tempfile::tempdir().unwrap();
+    reader.extract_to_dir(out.path()).unwrap();
+    let extracted = std::fs::metadata(out.path().join("stamped.txt")).unwrap();
+    assert_eq!(extracted.modified().unwrap(), mtime);
+}
+
+#[test]
+fn test_symlink_entries() {
+    let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new())).unwrap();
+    writer.add_entry("lib/libfoo.so.1", b"library").unwrap();
+    writer.add_symlink("lib/libfoo.so", "libfoo.so.1").unwrap();
+    writer.add_symlink("bin/lib", "../lib").unwrap();
+    let bytes = writer.finish().unwrap().into_inner();
+
+    let mut reader = StreamingZipReader::from_vec(bytes).unwrap();
+    let link = reader.find_entry("lib/libfoo.so").unwrap().clone();
+    assert!(link.is_symlink());
+    assert_eq!(link.unix_mode(), Some(0o120777));
+    assert!(!reader.entries()[0].is_symlink());
+    assert_eq!(reader.read_symlink_target(&link).unwrap(), "libfoo.so.1");
+    let file = reader.entries()[0].clone();
+    assert!(reader.read_symlink_target(&file).is_err());
+
+    let out = tempfile::tempdir().unwrap();
+    reader.extract_to_dir(out.path()).unwrap();
+    #[cfg(unix)]
+    {
+        let link = out.path().join("lib/libfoo.so");
+        assert_eq!(
+            std::fs::read_link(&link).unwrap(),
+            std::path::Path::new("libfoo.so.1")
+        );
+        assert_eq!(std::fs::read(&link).unwrap(), b"library");
+        assert_eq!(
+            std::fs::read(out.path().join("bin/lib/libfoo.so.1")).unwrap(),
+            b"library"
+        );
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_symlinks_outside_target_are_refused() {
+    for target in ["../../outside", "/etc", "sub/../../outside", ""] {
+        let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new())).unwrap();
+        writer.add_symlink("dir/link", target).unwrap();
+        let bytes = writer.finish().unwrap().into_inner();
+
+        let out = tempfile::tempdir().unwrap();
+        let mut reader = StreamingZipReader::from_vec(bytes).unwrap();
+        let err = reader.extract_to_dir(out.path().join("x")).unwrap_err();
+        assert!(err.to_string().contains("Refusing"), "{target}: {err}");
+        assert!(!out.path().join("x/dir/link").exists());
+    }
+
+    // A link can't replace a directory that entries were extracted into
+    let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new())).unwrap();
+    writer.add_entry("dir/file.txt", b"data").unwrap();
+    writer.add_symlink("dir", "elsewhere").unwrap();
+    let bytes = writer.finish().unwrap().into_inner();
+    let out = tempfile::tempdir().unwrap();
+    let mut reader = StreamingZipReader::from_vec(bytes).unwrap();
+    assert!(reader.extract_to_dir(out.path()).is_err());
+    assert!(out.path().join("dir/file.txt").is_file());
+}
+
+#[test]
+fn test_extract_to_dir_filter_and_rename() {
+    let tmp = write_sample_zip(&[
+        ("export/a.csv", b"a"),
+        ("export/b.json", b"b"),
+        ("export/sub/c.csv", b"c"),
+        ("other/d.csv", b"d"),
+    ]);
+
+    let options = ExtractOptions::default()
+        .with_filter(|entry| entry.name.ends_with(".csv"))
+        .with_rename(|name| name.strip_prefix("export/").map(Into::into));
+    let out = tempfile::tempdir().unwrap();
+    let mut reader = StreamingZipReader::open(tmp.path()).unwrap();
+    reader
+        .extract_to_dir_with_options(out.path(), &options)
+        .unwrap();
+
+    assert_eq!(std::fs::read(out.path().join("a.csv")).unwrap(), b"a");
+    assert_eq!(std::fs::read(out.path().join("sub/c.csv")).unwrap(), b"c");
+    assert!(!out.path().join("b.json").exists());
+    assert!(!out.path().join("other").exists());
+}
+
+#[test]
+fn test_extract_entries_parallel() {
+    let tmp = NamedTempFile::new().unwrap();
+    let mut writer = StreamingZipWriter::new(tmp.path()).unwrap();
+    for i in 0..64u32 {
+        let data = format!("file {i}\n").repeat(200 + i as usize);
+        writer
+            .add_entry(&format!("pkg/{i:02}/index.js"), data.as_bytes())
+            .unwrap();
+        writer
+            .add_entry(&format!("pkg/{i:02}.txt"), data.as_bytes())
+            .unwrap();
+    }
+    writer.add_symlink("latest.txt", "pkg/63.txt").unwrap();
+    writer.finish().unwrap();
+
+    // Same tree as the sequential extraction, from both handle kinds
+    let expected = tempfile::tempdir().unwrap();
+    StreamingZipReader::open(tmp.path())
+        .unwrap()
+        .extract_to_dir(expected.path())
+        .unwrap();
+    let reader = StreamingZipReader::open(tmp.path()).unwrap();
+    let out = tempfile::tempdir().unwrap();
+    let config = ExtractConfig::default().with_workers(4);
+    reader
+        .extract_entries_parallel(reader.entries(), out.path(), &config)
+        .unwrap();
+    let positioned = StreamingZipReader::open_positioned(tmp.path()).unwrap();
+    let out_positioned = tempfile::tempdir().unwrap();
+    positioned
+        .extract_entries_parallel(positioned.entries(), out_positioned.path(), &config)
+        .unwrap();
+    for i in 0..64u32 {
+        let name = format!("pkg/{i:02}.txt");
+        let want = std::fs::read(expected.path().join(&name)).unwrap();
+        assert_eq!(std::fs::read(out.path().join(&name)).unwrap(), want);
+        assert_eq!(
+            std::fs::read(out_positioned.path().join(&name)).unwrap(),
+            want
+        );
+    }
+    #[cfg(unix)]
+    assert_eq!(
+        std::fs::read_link(out.path().join("latest.txt")).unwrap(),
+        std::path::Path::new("pkg/63.txt")
+    );
+
+    // A subset, with options applied
+    let subset: Vec<_> = reader
+        .entries()
+        .iter()
+        .filter(|e| e.name.ends_with("7/index.js"))
+        .cloned()
+        .collect();
+    let options =
+        ExtractOptions::default().with_rename(|name| name.strip_prefix("pkg/").map(Into::into));
+    let only = tempfile::tempdir().unwrap();
+    reader
+        .extract_entries_parallel(&subset, only.path(), &config.with_options(options))
+        .unwrap();
+    assert_eq!(std::fs::read_dir(only.path()).unwrap().count(), 6);
+    assert_eq!(
+        std::fs::read(only.path().join("07/index.js")).unwrap(),
+        std::fs::read(expected.path().join("pkg/07/index.js")).unwrap()
+    );
+}
+
+#[test]
+fn test_extract_entry_to_path() {
+    let content = "one file out\n".repeat(1000);
+    let mut writer = StreamingZipWriter::from_writer_with_method(
+        std::io::Cursor::new(Vec::new()),
+        CompressionMethod::Stored,
+        0,
+    )
+    .unwrap();
+    writer
+        .add_entry("data/log.txt", content.as_bytes())
+        .unwrap();
+    let mut bytes = writer.finish().unwrap().into_inner();
+
+    let out = tempfile::tempdir().unwrap();
+    let target = out.path().join("nested/log.txt");
+    let mut reader = StreamingZipReader::from_bytes(&bytes).unwrap();
+    let entry = reader.find_entry("data/log.txt").unwrap().clone();
+    reader.extract_entry_to_path(&entry, &target).unwrap();
+    assert_eq!(std::fs::read(&target).unwrap(), content.as_bytes());
+
+    // Corrupt one stored data byte: the CRC check fails and nothing is left behind
+    let pos = bytes
+        .windows(12)
+        .position(|w| w == b"one file out")
+        .unwrap();
+    bytes[pos] = b'O';
+    let bad_target = out.path().join("bad.txt");
+    let mut reader = StreamingZipReader::from_vec(bytes).unwrap();
+    let err = reader
+        .extract_entry_to_path(&entry, &bad_target)
+        .unwrap_err();
+    assert!(matches!(err, SZipError::InvalidFormat(ref m) if m.contains("CRC-32")));
+    assert_eq!(std::fs::read_dir(out.path()).unwrap().count(), 1); // only "nested"
+}
+
+#[cfg(feature = "encryption")]
+#[test]
+fn test_extract_entry_to_path_checks_ae1_crc() {
+    let mut writer = StreamingZipWriter::from_writer(std::io::Cursor::new(Vec::new())).unwrap();
+    writer.set_password("secret");
+    writer.add_entry("secret.txt", b"hidden").unwrap();
+    let mut bytes = writer.finish().unwrap().into_inner();
+
+    // Give the central directory record a CRC that does not match the data
+    let cd = bytes.windows(4).rposition(|w| w == b"PK\x01\x02").unwrap();
+    bytes[cd + 16..cd + 20].copy_from_slice(&0xdead_beef_u32.to_le_bytes());
+    let out = tempfile::tempdir().unwrap();
+
+    // AE-2 stores no CRC, so the bogus value is ignored
+    let mut reader = StreamingZipReader::from_bytes(&bytes).unwrap();
+    reader.set_password("secret");
+    let entry = reader.find_entry("secret.txt").unwrap().clone();
+    reader
+        .extract_entry_to_path(&entry, out.path().join("ae2.txt"))
+        .unwrap();
+
+    // Relabelled as AE-1, which keeps the CRC, the mismatch is caught
+    let aes_extra = [0x01, 0x99, 7, 0, 2, 0];
+    while let Some(pos) = bytes.windows(6).position(|w| w == aes_extra) {
+        bytes[pos + 4] = 1;
+    }
+    let mut reader = StreamingZipReader::from_vec(bytes).unwrap();
+    reader.set_password("secret");
+    let entry = reader.find_entry("secret.txt").unwrap().clone();
+    let err = reader
+        .extract_entry_to_path(&entry, out.path().join("ae1.txt"))
+        .unwrap_err();
+    assert!(matches!(err, SZipError::InvalidFormat(ref m) if m.contains("CRC-32")));
+}
+
+#[test]
+fn test_verify_reports_per_entry_problems() {
+    let mut writer = StreamingZipWriter::from_writer_with_method(
+        std::io::Cursor::new(Vec::new()),
+        CompressionMethod::Stored,
+        0,
+    )
+    .unwrap();
+    writer.add_entry("good.txt", b"intact data").unwrap();
+    writer.add_entry("bad.txt", b"corrupted data").unwrap();
+    writer.add_entry("renamed.txt", b"x").unwrap();
+    let mut bytes = writer.finish().unwrap().into_inner();
+
+    let mut reader = StreamingZipReader::from_bytes(&bytes).unwrap();
+    let report = reader.verify().unwrap();
+    assert!(report.is_ok());
+    assert_eq!(report.entries.len(), 3);
+
+    // Flip a data byte in one entry and a local-header name byte in another
+    let pos = bytes.windows(9).position(|w| w == b"corrupted").unwrap();
+    bytes[pos] = b'C';
+    let pos = bytes.windows(11).position(|w| w == b"renamed.txt").unwrap();
+    bytes[pos] = b'R';
+
+    let mut reader = StreamingZipReader::from_vec(bytes).unwrap();
+    let report = reader.verify().unwrap();
+    assert!(!report.is_ok());
+    let failures: Vec<_> = report.failures().collect();
+    assert_eq!(failures.len(), 2);
+    assert_eq!(failures[0].name, "bad.txt");
+    assert!(matches!(
+        failures[0].problems[..],
+        [EntryProblem::CrcMismatch { .. }]
+    ));
+    assert_eq!(failures[1].name, "renamed.txt");
+    assert_eq!(
+        failures[1].problems,
+        vec![EntryProblem::NameMismatch {
+            local: "Renamed.txt".to_string()
+        }]
+    );
+}
+
+#[test]
+fn test_quick_check_detects_truncation() {
+    let tmp = write_sample_zip(&[("a.txt", b"first"), ("b.txt", b"second")]);
+    let mut bytes = std::fs::read(tmp.path()).unwrap();
+
+    let mut reader = StreamingZipReader::from_bytes(&bytes).unwrap();
+    assert!(reader.quick_check().unwrap().is_ok());
+
+    // Claim a compressed size for b.txt that runs past the end of the file
+    let cd = bytes.windows(4).rposition(|w| w == b"PK\x01\x02").unwrap();
+    bytes[cd + 20..cd + 24].copy_from_slice(&1_000_000u32.to_le_bytes());
+
+    let mut reader = StreamingZipReader::from_vec(bytes).unwrap();
+    let report = reader.quick_check().unwrap();
+    assert!(report.entries[0].is_ok());
+    assert!(matches!(
+        report.entries[1].problems[..],
+        [EntryProblem::Truncated { .. }]
+    ));
+}
+
+#[test]
+fn test_lenient_quirks_mode() {
+    use s_zip::QuirksMode;
+    use std::io::Write;
+
+    let data = b"class file bytes ".repeat(50);
+    let mut encoder = flate2::write::DeflateEncoder::new(Vec::new(), Default::default());
+    encoder.write_all(&data).unwrap();
+    let packed = encoder.finish().unwrap();
+    let crc = crc32fast::hash(&data);
+
+    // Java streaming writer: sizes and CRC only in the data descriptor,
+    // zeros in both headers
+    let mut out = Vec::new();
+    raw::write_local_header(
+        &mut out,
+        &LocalFileHeader {
+            flags: 0x08,
+            compression_method: 8,
+            name: b"App.class",
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    out.extend_from_slice(&packed);
+    raw::write_data_descriptor(&mut out, crc, packed.len() as u64, data.len() as u64).unwrap();
+    // Python zipfile: a directory marked only by its DOS attribute
+    let dir_offset = out.len() as u64;
+    raw::write_local_header(
+        &mut out,
+        &LocalFileHeader {
+            name: b"folder",
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let cd_offset = out.len() as u64;
+    let mut cd_size = raw::write_central_record(
+        &mut out,
+        &CentralDirectoryRecord {
+            flags: 0x08,
+            compression_method: 8,
+            name: b"App.class",
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    cd_size += raw::write_central_record(
+        &mut out,
+        &CentralDirectoryRecord {
+            external_attrs: 0x10,
+            local_header_offset: dir_offset,
+            name: b"folder",
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    raw::write_eocd(
+        &mut out,
+        &EndOfCentralDirectory {
+            entry_count: 2,
+            cd_size,
+            cd_offset,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let tmp = NamedTempFile::new().unwrap();
+    std::fs::write(tmp.path(), &out).unwrap();
+
+    let mut strict = StreamingZipReader::open(tmp.path()).unwrap();
+    assert_eq!(strict.quirks_mode(), QuirksMode::Strict);
+    assert_eq!(strict.entries()[0].compressed_size, 0);
+    assert_ne!(
+        strict.read_entry_by_name("App.class").ok(),
+        Some(data.clone())
+    );
+    assert_eq!(strict.entries()[1].name, "folder");
+
+    let mut lenient =
+        StreamingZipReader::open_with_quirks(tmp.path(), QuirksMode::Lenient).unwrap();
+    let class = &lenient.entries()[0];
+    assert_eq!(class.compressed_size, packed.len() as u64);
+    assert_eq!(class.uncompressed_size, data.len() as u64);
+    assert_eq!(class.crc32, crc);
+    assert_eq!(lenient.entries()[1].name, "folder/");
+    assert_eq!(lenient.read_entry_by_name("App.class").unwrap(), data);
+    assert!(lenient.verify().unwrap().is_ok());
+
+    // The same archive appended to a growing file
+    let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new())).unwrap();
+    writer.add_entry("first.txt", b"first").unwrap();
+    let mut tmp = NamedTempFile::new().unwrap();
+    tmp.write_all(&writer.finish().unwrap().into_inner())
+        .unwrap();
+    let options = ReaderOptions::default().with_quirks(QuirksMode::Lenient);
+    let mut growing = StreamingZipReader::open_with_options(tmp.path(), options).unwrap();
+    tmp.write_all(&out).unwrap();
+    assert!(growing.refresh().unwrap());
+    let class = growing.find_entry("App.class").unwrap();
+    assert_eq!(class.compressed_size, packed.len() as u64);
+    assert_eq!(class.crc32, crc);
+    assert_eq!(growing.read_entry_by_name("App.class").unwrap(), data);
+}
+
+#[test]
+fn test_open_growing_log_and_refresh() {
+    let archive = |name: &str, data: &[u8]| {
+        let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new())).unwrap();
+        writer.add_entry(name, data).unwrap();
+        writer.finish().unwrap().into_inner()
+    };
+    let first = archive("first.txt", b"first batch");
+    let second = archive("second.txt", &b"second batch ".repeat(100));
+
+    // The second archive is being appended and is only partly written
+    let log = NamedTempFile::new().unwrap();
+    let mut bytes = first.clone();
+    bytes.extend_from_slice(&second[..second.len() / 2]);
+    std::fs::write(log.path(), &bytes).unwrap();
+
+    let mut reader = StreamingZipReader::open_growing(log.path()).unwrap();
+    assert_eq!(reader.entries().len(), 1);
+    assert!(!reader.refresh().unwrap());
+
+    bytes.truncate(first.len());
+    bytes.extend_from_slice(&second);
+    std::fs::write(log.path(), &bytes).unwrap();
+    assert!(reader.refresh().unwrap());
+    let names: Vec<_> = reader.entries().iter().map(|e| e.name.as_str()).collect();
+    assert_eq!(names, ["first.txt", "second.txt"]);
+    assert_eq!(
+        reader.read_entry_by_name("second.txt").unwrap(),
+        b"second batch ".repeat(100)
+    );
+    assert!(reader.verify().unwrap().is_ok());
+    assert!(!reader.refresh().unwrap());
+
+    // Opening the complete log lists both archives, oldest first
+    let mut reader = StreamingZipReader::open_growing(log.path()).unwrap();
+    assert_eq!(reader.entries().len(), 2);
+    assert_eq!(
+        reader.read_entry_by_name("first.txt").unwrap(),
+        b"first batch"
+    );
+}
+
+#[test]
+fn test_open_salvage_truncated_archive() {
+    let tmp = write_sample_zip(&[
+        ("one.txt", b"first entry"),
+        ("two.txt", &b"second entry ".repeat(200)),
+        ("three.txt", &b"cut off here ".repeat(200)),
+    ]);
+    let bytes = std::fs::read(tmp.path()).unwrap();
+
+    // Cut the file in the middle of the third entry's data
+    let third = bytes.windows(9).position(|w| w == b"three.txt").unwrap();
+    let partial = NamedTempFile::new().unwrap();
+    std::fs::write(partial.path(), &bytes[..third + 40]).unwrap();
+    assert!(StreamingZipReader::open(partial.path()).is_err());
+
+    let (mut reader, report) = StreamingZipReader::open_salvage(partial.path()).unwrap();
+    assert_eq!(report.recovered, ["one.txt", "two.txt"]);
+    assert_eq!(report.lost.len(), 1);
+    assert_eq!(report.lost[0].name, "three.txt");
+    // The host is unknown without a central directory record
+    assert_eq!(reader.entries()[0].version_made_by, 0);
+    assert_eq!(
+        reader.read_entry_by_name("one.txt").unwrap(),
+        b"first entry"
+    );
+    assert_eq!(
+        reader.read_entry_by_name("two.txt").unwrap(),
+        b"second entry ".repeat(200)
+    );
+    assert!(reader.verify().unwrap().is_ok());
+
+    // An intact archive salvages completely
+    let (_, report) = StreamingZipReader::open_salvage(tmp.path()).unwrap();
+    assert!(report.is_complete());
+    assert_eq!(report.recovered.len(), 3);
+}
+
+#[test]
+fn test_format_report() {
+    let tmp = write_sample_zip(&[("a.txt", b"alpha"), ("b.txt", b"beta")]);
+    let mut reader = StreamingZipReader::open(tmp.path()).unwrap();
+    let report = reader.format_report().unwrap();
+
+    assert_eq!(report.entry_count, 2);
+    assert_eq!(report.compression_methods.get(&8), Some(&2));
+    assert_eq!(report.data_descriptor_entries, 2);
+    assert!(!report.zip64_end_record);
+    assert_eq!(report.zip64_entries, 0);
+    assert_eq!(report.aes_encrypted_entries, 0);
+    assert!(!report.multi_disk);
+    assert!(!report.has_comment);
+
+    let text = report.to_string();
+    assert!(text.contains("Deflate (8) x2"), "{}", text);
+    assert!(text.contains("data descriptors: 2 entries"), "{}", text);
+}
+
+#[test]
+fn test_capabilities_and_can_read_entry() {
+    let caps = s_zip::capabilities();
+    assert!(caps.read_methods.contains(&0) && caps.read_methods.contains(&8));
+    assert!(!caps.zipcrypto);
+    assert_eq!(caps.aes_encryption, cfg!(feature = "encryption"));
+
+    // One Deflate entry and one PPMd (method 98) entry, which s-zip can't decode
+    let mut out = Vec::new();
+    let mut records = Vec::new();
+    for (name, method) in [(&b"plain.txt"[..], 0u16), (&b"packed.ppmd"[..], 98)] {
+        let offset = out.len() as u64;
+        raw::write_local_header(
+            &mut out,
+            &LocalFileHeader {
+                compression_method: method,
+                name,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        records.push((name, method, offset));
+    }
+    let cd_offset = out.len() as u64;
+    let mut cd_size = 0;
+    for (name, method, offset) in &records {
+        cd_size += raw::write_central_record(
+            &mut out,
+            &CentralDirectoryRecord {
+                compression_method: *method,
+                local_header_offset: *offset,
+                name,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    }
+    raw::write_eocd(
+        &mut out,
+        &EndOfCentralDirectory {
+            entry_count: 2,
+            cd_size,
+            cd_offset,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let reader = StreamingZipReader::from_vec(out).unwrap();
+    assert!(reader.can_read_entry(&reader.entries()[0]));
+    assert!(!reader.can_read_entry(&reader.entries()[1]));
+}
+
+#[cfg(feature = "encryption")]
+#[test]
+fn test_can_read_encrypted_entry_needs_password() {
+    let mut writer = StreamingZipWriter::from_writer(std::io::Cursor::new(Vec::new())).unwrap();
+    writer.set_password("secret");
+    writer.add_entry("secret.txt", b"hidden").unwrap();
+    let bytes = writer.finish().unwrap().into_inner();
+
+    let mut reader = StreamingZipReader::from_vec(bytes).unwrap();
+    let entry = reader.entries()[0].clone();
+    assert!(entry.aes_strength.is_some());
+    assert!(!reader.can_read_entry(&entry));
+    reader.set_password("secret");
+    assert!(reader.can_read_entry(&entry));
+    assert_eq!(reader.format_report().unwrap().aes_encrypted_entries, 1);
+}
+
+#[cfg(feature = "encryption")]
+#[test]
+fn test_version_needed_override_keeps_entry_minimum() {
+    let mut writer = StreamingZipWriter::from_writer(std::io::Cursor::new(Vec::new())).unwrap();
+    writer.set_version_needed(10);
+    writer.add_entry("plain.txt", b"plain").unwrap();
+    writer.set_password("secret");
+    writer.add_entry("secret.txt", b"hidden").unwrap();
+    let bytes = writer.finish().unwrap().into_inner();
+
+    let reader = StreamingZipReader::from_vec(bytes).unwrap();
+    // Deflate needs 2.0 and AES 5.1, whatever the override says
+    assert_eq!(reader.find_entry("plain.txt").unwrap().version_needed, 20);
+    assert_eq!(reader.find_entry("secret.txt").unwrap().version_needed, 51);
+}
+
+#[test]
+fn test_streaming_entry_is_bufread() {
+    use std::io::BufRead;
+
+    let jsonl = "{\"id\":1}\n{\"id\":2}\n{\"id\":3}\n";
+    let tmp = write_sample_zip(&[("events.jsonl", jsonl.as_bytes())]);
+    let mut reader = StreamingZipReader::open(tmp.path()).unwrap();
+
+    let lines: Vec<String> = reader
+        .read_entry_streaming_by_name("events.jsonl")
+        .unwrap()
+        .lines()
+        .collect::<std::io::Result<_>>()
+        .unwrap();
+    assert_eq!(lines, ["{\"id\":1}", "{\"id\":2}", "{\"id\":3}"]);
+}
+
+#[test]
+fn test_streaming_entry_reader_keeps_entry() {
+    fn read_all<R: Read>(mut stream: R) -> Vec<u8> {
+        let mut data = Vec::new();
+        stream.read_to_end(&mut data).unwrap();
+        data
+    }
+
+    let tmp = write_sample_zip(&[("a.txt", b"alpha"), ("b.txt", b"beta")]);
+    let mut reader = StreamingZipReader::open(tmp.path()).unwrap();
+    let stream: s_zip::ZipEntryReader<'_> = reader.read_entry_streaming_by_name("b.txt").unwrap();
+    assert_eq!(stream.entry().name, "b.txt");
+    assert_eq!(stream.entry().uncompressed_size, 4);
+    assert_eq!(read_all(stream), b"beta");
+}
+
+#[test]
+fn test_read_entry_untrusted_sizes() {
+    let content = b"small payload ".repeat(50);
+    let mut writer = StreamingZipWriter::from_writer_with_method(
+        std::io::Cursor::new(Vec::new()),
+        CompressionMethod::Stored,
+        0,
+    )
+    .unwrap();
+    writer.add_entry("a.bin", &content).unwrap();
+    let bytes = writer.finish().unwrap().into_inner();
+    let cd = bytes.windows(4).rposition(|w| w == b"PK\x01\x02").unwrap();
+
+    // A huge declared uncompressed size no longer drives the allocation
+    let mut inflated = bytes.clone();
+    inflated[cd + 24..cd + 28].copy_from_slice(&0xFFFF_FFF0u32.to_le_bytes());
+    let mut reader = StreamingZipReader::from_vec(inflated).unwrap();
+    assert_eq!(reader.read_entry_by_name("a.bin").unwrap(), content);
+
+    // A compressed size running past the data fails cleanly instead of allocating
+    let mut oversized = bytes;
+    oversized[cd + 20..cd + 24].copy_from_slice(&0x7FFF_FFF0u32.to_le_bytes());
+    let mut reader = StreamingZipReader::from_vec(oversized).unwrap();
+    assert!(reader.read_entry_by_name("a.bin").is_err());
+}
+
+#[test]
+fn test_open_lazy_iterates_and_reads() {
+    let tmp = write_sample_zip(&[("a.txt", b"alpha"), ("b.txt", b"beta"), ("c.txt", b"gamma")]);
+    let mut entries = StreamingZipReader::open_lazy(tmp.path()).unwrap();
+    assert_eq!(entries.declared_len(), 3);
+
+    // Reading an entry mid-iteration doesn't disturb the next record
+    let first = entries.next().unwrap().unwrap();
+    assert_eq!(first.name, "a.txt");
+    assert_eq!(entries.read_entry(&first).unwrap(), b"alpha");
+    let rest: Vec<String> = entries.map(|e| e.unwrap().name).collect();
+    assert_eq!(rest, ["b.txt", "c.txt"]);
+
+    // Records cut short by a bad signature end the iteration early
+    let mut bytes = std::fs::read(tmp.path()).unwrap();
+    let last_cd = bytes.windows(4).rposition(|w| w == b"PK\x01\x02").unwrap();
+    bytes[last_cd] = b'X';
+    let names: Vec<String> = s_zip::LazyEntries::new(std::io::Cursor::new(bytes))
+        .unwrap()
+        .map(|e| e.unwrap().name)
+        .collect();
+    assert_eq!(names, ["a.txt", "b.txt"]);
+}
+
+#[test]
+fn test_open_indexed_lookups() {
+    let names: Vec<String> = (0..200).map(|i| format!("items/{i:04}.json")).collect();
+    let contents: Vec<Vec<u8>> = (0..200)
+        .map(|i| format!("{{\"id\":{i}}}").into_bytes())
+        .collect();
+    let entries: Vec<(&str, &[u8])> = names
+        .iter()
+        .zip(&contents)
+        .map(|(n, c)| (n.as_str(), c.as_slice()))
+        .collect();
+    let tmp = write_sample_zip(&entries);
+
+    let mut reader = StreamingZipReader::open_indexed(tmp.path()).unwrap();
+    assert_eq!(reader.len(), 200);
+    assert_eq!(
+        reader.read_entry_by_name("items/0137.json").unwrap(),
+        b"{\"id\":137}"
+    );
+    let entry = reader.find_entry("items/0000.json").unwrap().unwrap();
+    assert_eq!(entry.uncompressed_size, 8);
+    assert!(reader.find_entry("items/9999.json").unwrap().is_none());
+    assert!(matches!(
+        reader.read_entry_by_name("missing"),
+        Err(SZipError::EntryNotFound(_))
+    ));
+}
+
+#[test]
+fn test_open_compact_shares_directory_prefixes() {
+    let tmp = write_sample_zip(&[
+        ("assets/icons/home.svg", b"<svg/>"),
+        ("assets/icons/user.svg", b"<svg></svg>"),
+        ("README", b"read me"),
+    ]);
+    let mut reader = StreamingZipReader::open_compact(tmp.path()).unwrap();
+    assert_eq!(reader.len(), 3);
+
+    let icons: Vec<&str> = reader
+        .names()
+        .iter()
+        .filter(|n| n.dir() == "assets/icons/")
+        .map(|n| n.file_name())
+        .collect();
+    assert_eq!(icons, ["home.svg", "user.svg"]);
+
+    let entry = reader.entry(1).unwrap();
+    assert_eq!(entry.name, "assets/icons/user.svg");
+    assert_eq!(entry.uncompressed_size, 11);
+    assert_eq!(reader.read_entry_by_name("README").unwrap(), b"read me");
+    assert!(reader.find_entry("assets/icons/none.svg").is_none());
+}
+
+#[test]
+fn test_open_with_cd_location() {
+    let tmp = write_sample_zip(&[("a.txt", b"alpha"), ("b.txt", b"beta")]);
+    let location = StreamingZipReader::open(tmp.path())
+        .unwrap()
+        .cd_location()
+        .unwrap();
+    assert_eq!(location.count, 2);
+
+    let mut reader = StreamingZipReader::open_with_cd_location(
+        tmp.path(),
+        location.offset,
+        location.size,
+        location.count,
+    )
+    .unwrap();
+    assert_eq!(reader.entries().len(), 2);
+    assert_eq!(reader.read_entry_by_name("b.txt").unwrap(), b"beta");
+
+    // A stale location is rejected rather than yielding garbage entries
+    assert!(matches!(
+        StreamingZipReader::open_with_cd_location(
+            tmp.path(),
+            location.offset + 1,
+            location.size,
+            2
+        ),
+        Err(SZipError::InvalidFormat(_))
+    ));
+    assert!(StreamingZipReader::open_with_cd_location(
+        tmp.path(),
+        location.offset,
+        location.size + 100,
+        2
+    )
+    .is_err());
+}
+
+#[test]
+fn test_data_span_points_at_stored_bytes() {
+    let mut writer = StreamingZipWriter::from_writer_with_method(
+        std::io::Cursor::new(Vec::new()),
+        CompressionMethod::Stored,
+        0,
+    )
+    .unwrap();
+    writer.add_entry("first.txt", b"first").unwrap();
+    writer
+        .add_entry("dir/second.bin", b"second payload")
+        .unwrap();
+    let bytes = writer.finish().unwrap().into_inner();
+
+    let mut reader = StreamingZipReader::from_bytes(&bytes).unwrap();
+    let entry = reader.find_entry("dir/second.bin").unwrap().clone();
+    let (offset, len) = reader.data_span(&entry).unwrap();
+    assert_eq!(
+        &bytes[offset as usize..(offset + len) as usize],
+        b"second payload"
+    );
+}
+
+#[test]
+fn test_open_filtered_keeps_matching_entries() {
+    let tmp = write_sample_zip(&[
+        ("metadata/a.json", b"{}"),
+        ("data/blob.bin", b"blob"),
+        ("metadata/b.json", b"[]"),
+        ("notes.txt", b"notes"),
+    ]);
+
+    let mut reader = StreamingZipReader::open_with_prefixes(tmp.path(), &["metadata/"]).unwrap();
+    let names: Vec<&str> = reader.entries().iter().map(|e| e.name.as_str()).collect();
+    assert_eq!(names, ["metadata/a.json", "metadata/b.json"]);
+    assert_eq!(reader.read_entry_by_name("metadata/b.json").unwrap(), b"[]");
+    assert!(reader.find_entry("data/blob.bin").is_none());
+
+    let mut reader =
+        StreamingZipReader::open_filtered(tmp.path(), |name| name.ends_with(".txt")).unwrap();
+    assert_eq!(reader.entries().len(), 1);
+
+    // The filter survives a rewrite of the archive
+    let rewritten = write_sample_zip(&[
+        ("notes.txt", b"notes"),
+        ("more.txt", b"more"),
+        ("c.bin", b"c"),
+    ]);
+    std::fs::write(tmp.path(), std::fs::read(rewritten.path()).unwrap()).unwrap();
+    reader.reload().unwrap();
+    let names: Vec<&str> = reader.entries().iter().map(|e| e.name.as_str()).collect();
+    assert_eq!(names, ["notes.txt", "more.txt"]);
+
+    // ... and an archive appended after it
+    let mut bytes = std::fs::read(tmp.path()).unwrap();
+    let appended = write_sample_zip(&[("d.bin", b"d"), ("later.txt", b"later")]);
+    bytes.extend_from_slice(&std::fs::read(appended.path()).unwrap());
+    std::fs::write(tmp.path(), &bytes).unwrap();
+    assert!(reader.refresh().unwrap());
+    let names: Vec<&str> = reader.entries().iter().map(|e| e.name.as_str()).collect();
+    assert_eq!(names, ["notes.txt", "more.txt", "later.txt"]);
+    assert_eq!(reader.read_entry_by_name("later.txt").unwrap(), b"later");
+}
+
+#[test]
+fn test_register_decoder() {
+    // A private method 0x8001: run-length pairs of (count, byte)
+    let plain = b"aaaabbbc";
+    let packed = [4, b'a', 3, b'b', 1, b'c'];
+    let crc32 = crc32fast::hash(plain);
+    let mut out = Vec::new();
+    raw::write_local_header(
+        &mut out,
+        &LocalFileHeader {
+            compression_method: 0x8001,
+            crc32,
+            compressed_size: packed.len() as u64,
+            uncompressed_size: plain.len() as u64,
+            name: b"rle.bin",
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    out.extend_from_slice(&packed);
+    let cd_offset = out.len() as u64;
+    let cd_size = raw::write_central_record(
+        &mut out,
+        &CentralDirectoryRecord {
+            compression_method: 0x8001,
+            crc32,
+            compressed_size: packed.len() as u64,
+            uncompressed_size: plain.len() as u64,
+            name: b"rle.bin",
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    raw::write_eocd(
+        &mut out,
+        &EndOfCentralDirectory {
+            entry_count: 1,
+            cd_size,
+            cd_offset,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let mut reader = StreamingZipReader::from_vec(out).unwrap();
+    let entry = reader.entries()[0].clone();
+    assert!(!reader.can_read_entry(&entry));
+    assert!(matches!(
+        reader.read_entry(&entry),
+        Err(SZipError::UnsupportedCompression(0x8001))
+    ));
+
+    reader.register_decoder(0x8001, |mut input| {
+        let mut packed = Vec::new();
+        input.read_to_end(&mut packed)?;
+        let plain: Vec<u8> = packed
+            .chunks(2)
+            .flat_map(|pair| std::iter::repeat_n(pair[1], pair[0] as usize))
+            .collect();
+        Ok(Box::new(Cursor::new(plain)))
+    });
+    assert!(reader.can_read_entry(&entry));
+    assert_eq!(reader.read_entry(&entry).unwrap(), plain);
+    let mut streamed = Vec::new();
+    reader
+        .read_entry_streaming(&entry)
+        .unwrap()
+        .read_to_end(&mut streamed)
+        .unwrap();
+    assert_eq!(streamed, plain);
+    assert!(reader.verify().unwrap().is_ok());
+}
+
+#[test]
+fn test_are_equivalent() {
+    use s_zip::compare::{differences, Difference};
+    use s_zip::{are_equivalent, EquivalenceOptions};
+    use std::time::{Duration, SystemTime};
+
+    let build = |method, order: &[&str], mtime: u64, mode: u32| {
+        let mut writer =
+            StreamingZipWriter::from_writer_with_method(Cursor::new(Vec::new()), method, 6)
+                .unwrap();
+        for name in order {
+            let options = EntryOptions::default()
+                .with_mtime(SystemTime::UNIX_EPOCH + Duration::from_secs(mtime))
+                .with_unix_mode(mode);
+            writer.start_entry_with_options(name, options).unwrap();
+            writer.write_data(name.repeat(100).as_bytes()).unwrap();
+        }
+        StreamingZipReader::from_vec(writer.finish().unwrap().into_inner()).unwrap()
+    };
+    let (deflate, stored) = (CompressionMethod::Deflate, CompressionMethod::Stored);
+    let mut base = build(deflate, &["a", "b"], 1_700_000_000, 0o644);
+    let mut same = build(deflate, &["a", "b"], 1_700_000_000, 0o644);
+    assert!(are_equivalent(&mut base, &mut same, EquivalenceOptions::default()).unwrap());
+
+    let mut other = build(stored, &["b", "a"], 1_600_000_000, 0o755);
+    let diffs = differences(&mut base, &mut other, EquivalenceOptions::default()).unwrap();
+    assert_eq!(diffs.len(), 7, "{:?}", diffs);
+    assert!(diffs.contains(&Difference::Order));
+    assert!(diffs
+        .iter()
+        .all(|d| !matches!(d, Difference::Content(_) | Difference::OnlyInFirst(_))));
+    let lenient = EquivalenceOptions {
+        ignore_timestamps: true,
+        ignore_order: true,
+        ignore_compression: true,
+        ignore_attributes: true,
+    };
+    assert!(are_equivalent(&mut base, &mut other, lenient).unwrap());
+
+    // Same names and sizes, different bytes
+    let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new())).unwrap();
+    writer.add_entry("a", &b"A".repeat(100)).unwrap();
+    writer.add_entry("c", b"").unwrap();
+    let mut changed = StreamingZipReader::from_vec(writer.finish().unwrap().into_inner()).unwrap();
+    assert_eq!(
+        differences(&mut base, &mut changed, lenient).unwrap(),
+        [
+            Difference::Content("a".to_string()),
+            Difference::OnlyInFirst("b".to_string()),
+            Difference::OnlyInSecond("c".to_string()),
+        ]
+    );
+}
+
+/// Source with `Read` only, like stdin or a pipe
+struct PipeReader(Cursor<Vec<u8>>);
+
+impl Read for PipeReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        // Short reads, as a pipe delivers them
+        let n = buf.len().min(1000);
+        self.0.read(&mut buf[..n])
+    }
+}
+
+#[test]
+fn test_forward_reader_over_pipe() {
+    use s_zip::{Compatibility, StreamingZipForwardReader};
+
+    let big: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+    let methods = [
+        CompressionMethod::Deflate,
+        CompressionMethod::Stored,
+        #[cfg(feature = "zstd-support")]
+        CompressionMethod::Zstd,
+    ];
+
+    for method in methods {
+        let mut writer =
+            StreamingZipWriter::from_writer_with_method(Cursor::new(Vec::new()), method, 6)
+                .unwrap();
+        if method == CompressionMethod::Stored {
+            writer.set_compatibility(Compatibility::WindowsExplorer);
+        }
+        writer.add_entry("first.txt", b"hello").unwrap();
+        writer.add_entry("skipped.bin", &big).unwrap();
+        writer.add_entry("big.bin", &big).unwrap();
+        let zip = writer.finish().unwrap().into_inner();
+
+        let mut reader = StreamingZipForwardReader::new(PipeReader(Cursor::new(zip)));
+        let mut entry = reader.next_entry().unwrap().unwrap();
+        assert_eq!(entry.entry().name, "first.txt");
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data).unwrap();
+        assert_eq!(data, b"hello");
+
+        // Partially read, then skipped
+        let mut entry = reader.next_entry().unwrap().unwrap();
+        assert_eq!(entry.entry().name, "skipped.bin");
+        let mut head = [0u8; 10];
+        entry.read_exact(&mut head).unwrap();
+
+        let mut entry = reader.next_entry().unwrap().unwrap();
+        assert_eq!(entry.entry().name, "big.bin");
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data).unwrap();
+        assert_eq!(data, big, "{:?}", method);
+
+        assert!(reader.next_entry().unwrap().is_none());
+        assert!(reader.next_entry().unwrap().is_none());
+    }
+
+    // A corrupted byte is caught by the CRC check once the entry is done
+    let mut writer = StreamingZipWriter::from_writer_with_method(
+        Cursor::new(Vec::new()),
+        CompressionMethod::Stored,
+        0,
+    )
+    .unwrap();
+    writer.set_compatibility(Compatibility::WindowsExplorer);
+    writer.add_entry("a.txt", b"alpha").unwrap();
+    let mut zip = writer.finish().unwrap().into_inner();
+    let at = zip.windows(5).position(|w| w == b"alpha").unwrap();
+    zip[at] = b'A';
+    let mut reader = StreamingZipForwardReader::new(PipeReader(Cursor::new(zip)));
+    reader.next_entry().unwrap().unwrap();
+    assert!(reader.next_entry().is_err());
+    assert!(reader.next_entry().is_err());
+}
+
+/// Fixed-size block device: reads return at most one 512-byte block
+struct BlockDevice(Cursor<Vec<u8>>);
+
+impl Read for BlockDevice {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let in_block = 512 - (self.0.position() % 512) as usize;
+        let n = buf.len().min(in_block);
+        self.0.read(&mut buf[..n])
+    }
+}
+
+impl std::io::Seek for BlockDevice {
+    fn seek(&mut self, pos: std::io::SeekFrom) -> std::io::Result<u64> {
+        self.0.seek(pos)
+    }
+}
+
+#[test]
+fn test_generic_reader_over_read_seek() {
+    use s_zip::{GenericZipReader, QuirksMode};
+
+    let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new())).unwrap();
+    writer.add_entry("a.txt", b"alpha").unwrap();
+    writer.add_entry("b.bin", &[7u8; 5000]).unwrap();
+    let zip = writer.finish().unwrap().into_inner();
+
+    let mut reader = GenericZipReader::new(Cursor::new(zip.clone())).unwrap();
+    assert_eq!(reader.entry_count(), 2);
+    assert_eq!(reader.read_entry_by_name("a.txt").unwrap(), b"alpha");
+
+    let options = ReaderOptions::default().with_quirks(QuirksMode::Lenient);
+    let mut reader =
+        StreamingZipReader::new_with_options(BlockDevice(Cursor::new(zip)), options).unwrap();
+    assert_eq!(reader.read_entry_by_name("b.bin").unwrap(), [7u8; 5000]);
+    let mut data = Vec::new();
+    reader
+        .read_entry_streaming_by_name("a.txt")
+        .unwrap()
+        .read_to_end(&mut data)
+        .unwrap();
+    assert_eq!(data, b"alpha");
+
+    assert!(GenericZipReader::new(Cursor::new(b"not a zip".to_vec())).is_err());
+}
+
+#[test]
+fn test_open_nested() {
+    let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new())).unwrap();
+    writer
+        .add_entry("META-INF/MANIFEST.MF", b"Manifest-Version: 1.0\n")
+        .unwrap();
+    writer.add_entry("app/Main.class", &[0xCA; 3000]).unwrap();
+    let jar = writer.finish().unwrap().into_inner();
+
+    for method in [CompressionMethod::Stored, CompressionMethod::Deflate] {
+        let mut writer =
+            StreamingZipWriter::from_writer_with_method(Cursor::new(Vec::new()), method, 6)
+                .unwrap();
+        writer.add_entry("readme.txt", b"outer").unwrap();
+        writer.add_entry("lib/app.jar", &jar).unwrap();
+        let zip = writer.finish().unwrap().into_inner();
+
+        let mut outer = StreamingZipReader::from_vec(zip).unwrap();
+        let entry = outer.find_entry("lib/app.jar").unwrap().clone();
+        let mut inner = outer.open_nested(&entry).unwrap();
+        let names: Vec<_> = inner.entries().iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["META-INF/MANIFEST.MF", "app/Main.class"]);
+        assert_eq!(
+            inner.read_entry_by_name("app/Main.class").unwrap(),
+            [0xCA; 3000]
+        );
+        assert_eq!(
+            inner.read_entry_by_name("META-INF/MANIFEST.MF").unwrap(),
+            b"Manifest-Version: 1.0\n"
+        );
+        drop(inner);
+
+        // The outer reader is usable again once the inner one is dropped
+        assert_eq!(outer.read_entry_by_name("readme.txt").unwrap(), b"outer");
+
+        // An entry that isn't a ZIP fails to open
+        let entry = outer.find_entry("readme.txt").unwrap().clone();
+        assert!(outer.open_nested(&entry).is_err());
+    }
+}
+
+/// Split a single-volume archive without ZIP64 records or a comment into
+/// volumes of `volume_size` bytes, rewriting its offsets per volume
+fn split_zip(mut zip: Vec<u8>, volume_size: usize) -> Vec<Vec<u8>> {
+    let eocd = zip.len() - 22;
+    let u16_at = |b: &[u8], i: usize| u16::from_le_bytes([b[i], b[i + 1]]) as usize;
+    let u32_at = |b: &[u8], i: usize| u32::from_le_bytes(b[i..i + 4].try_into().unwrap()) as usize;
+    let locate = |offset: usize| ((offset / volume_size) as u16, (offset % volume_size) as u32);
+
+    let count = u16_at(&zip, eocd + 10);
+    let cd_offset = u32_at(&zip, eocd + 16);
+    let mut record = cd_offset;
+    for _ in 0..count {
+        let (disk, offset) = locate(u32_at(&zip, record + 42));
+        zip[record + 34..record + 36].copy_from_slice(&disk.to_le_bytes());
+        zip[record + 42..record + 46].copy_from_slice(&offset.to_le_bytes());
+        record +=
+            46 + u16_at(&zip, record + 28) + u16_at(&zip, record + 30) + u16_at(&zip, record + 32);
+    }
+
+    let mut volumes: Vec<Vec<u8>> = zip[..eocd]
+        .chunks(volume_size)
+        .map(<[u8]>::to_vec)
+        .collect();
+    if eocd.is_multiple_of(volume_size) {
+        volumes.push(Vec::new());
+    }
+    let (cd_disk, cd_offset) = locate(cd_offset);
+    let mut end = zip[eocd..].to_vec();
+    end[4..6].copy_from_slice(&((volumes.len() - 1) as u16).to_le_bytes());
+    end[6..8].copy_from_slice(&cd_disk.to_le_bytes());
+    end[16..20].copy_from_slice(&cd_offset.to_le_bytes());
+    volumes.last_mut().unwrap().extend_from_slice(&end);
+    volumes
+}
+
+#[test]
+fn test_open_split_archive() {
+    use s_zip::split::{volume_path, SplitSource};
+
+    let data = |seed: u32| -> Vec<u8> { (0..2500u32).map(|i| (i * seed % 251) as u8).collect() };
+    let mut writer = StreamingZipWriter::from_writer_with_method(
+        Cursor::new(Vec::new()),
+        CompressionMethod::Stored,
+        0,
+    )
+    .unwrap();
+    for (name, seed) in [("a.bin", 3), ("b.bin", 5), ("c.bin", 7)] {
+        writer.add_entry(name, &data(seed)).unwrap();
+    }
+    let volumes = split_zip(writer.finish().unwrap().into_inner(), 1000);
+    assert!(volumes.len() > 7);
+
+    let dir = tempfile::tempdir().unwrap();
+    let last = dir.path().join("archive.zip");
+    for (disk, volume) in volumes.iter().enumerate() {
+        let path = if disk + 1 == volumes.len() {
+            last.clone()
+        } else {
+            volume_path(&last, disk as u32)
+        };
+        std::fs::write(path, volume).unwrap();
+    }
+    assert!(dir.path().join("archive.z01").exists());
+
+    let mut reader = StreamingZipReader::open_split(&last).unwrap();
+    let disks: Vec<u32> = reader.entries().iter().map(|e| e.disk_number).collect();
+    assert_eq!(disks, [0, 2, 5]);
+    for (name, seed) in [("a.bin", 3), ("b.bin", 5), ("c.bin", 7)] {
+        assert_eq!(
+            reader.read_entry_by_name(name).unwrap(),
+            data(seed),
+            "{name}"
+        );
+    }
+    let mut streamed = Vec::new();
+    reader
+        .read_entry_streaming_by_name("b.bin")
+        .unwrap()
+        .read_to_end(&mut streamed)
+        .unwrap();
+    assert_eq!(streamed, data(5));
+
+    // Volumes in memory, joined explicitly
+    let cursors = volumes.iter().cloned().map(Cursor::new).collect();
+    let source = SplitSource::from_volumes(cursors).unwrap();
+    let mut reader = StreamingZipReader::from_split(source).unwrap();
+    assert_eq!(reader.read_entry_by_name("c.bin").unwrap(), data(7));
+
+    // Missing volumes are reported
+    let only_last =
+        SplitSource::from_volumes(vec![Cursor::new(volumes.last().unwrap().clone())]).unwrap();
+    assert!(StreamingZipReader::from_split(only_last).is_err());
+    std::fs::remove_file(dir.path().join("archive.z02")).unwrap();
+    assert!(StreamingZipReader::open_split(&last).is_err());
+}
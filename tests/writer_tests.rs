@@ -0,0 +1,1198 @@
+//! Tests for the sync streaming ZIP writer
+
+use s_zip::hook::{ArchiveSummary, EntrySummary, WriterHook};
+use s_zip::ignore::IgnoreRules;
+use s_zip::{
+    Compatibility, DosAttributes, DuplicateNamePolicy, EntryOptions, SZipError, StreamingZipReader,
+    StreamingZipWriter,
+};
+use std::io::Cursor;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+fn entry_names(bytes: Vec<u8>) -> Vec<String> {
+    let reader = StreamingZipReader::from_vec(bytes).unwrap();
+    reader.entries().iter().map(|e| e.name.clone()).collect()
+}
+
+#[test]
+fn test_normalize_names() {
+    let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new())).unwrap();
+    writer.set_normalize_names(true);
+    writer
+        .add_entry("C:\\exports\\.\\report.csv", b"a")
+        .unwrap();
+    writer.add_entry("/var/../tmp//x.txt", b"b").unwrap();
+    writer.add_entry("logs\\", b"").unwrap();
+    let err = writer.add_entry("./..", b"c").unwrap_err();
+    assert!(matches!(err, SZipError::InvalidFormat(_)));
+    let bytes = writer.finish().unwrap().into_inner();
+
+    assert_eq!(
+        entry_names(bytes),
+        ["exports/report.csv", "tmp/x.txt", "logs/"]
+    );
+}
+
+#[test]
+fn test_names_unchanged_by_default() {
+    let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new())).unwrap();
+    writer.add_entry("dir\\file.txt", b"a").unwrap();
+    let bytes = writer.finish().unwrap().into_inner();
+
+    assert_eq!(entry_names(bytes), ["dir\\file.txt"]);
+}
+
+#[test]
+fn test_validate_names_rejects_unsafe() {
+    let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new())).unwrap();
+    writer.set_validate_names(true);
+    for name in [
+        "/etc/passwd",
+        "a/../../b",
+        "nul\0byte",
+        "docs/aux.txt",
+        "D:\\x",
+    ] {
+        let err = writer.add_entry(name, b"x").unwrap_err();
+        assert!(matches!(err, SZipError::InvalidFormat(_)), "{name}");
+    }
+    writer.add_entry("docs/readme.txt", b"ok").unwrap();
+
+    // Normalization runs first, so cleaned-up names pass validation
+    writer.set_normalize_names(true);
+    writer.add_entry("/srv/../data.bin", b"ok").unwrap();
+    let bytes = writer.finish().unwrap().into_inner();
+
+    assert_eq!(entry_names(bytes), ["docs/readme.txt", "data.bin"]);
+}
+
+#[test]
+fn test_duplicate_policy() {
+    struct Duplicates(Arc<Mutex<Vec<String>>>);
+
+    impl WriterHook for Duplicates {
+        fn duplicate_name(&mut self, name: &str) -> s_zip::Result<()> {
+            self.0.lock().unwrap().push(name.to_string());
+            Ok(())
+        }
+    }
+
+    let warned = Arc::new(Mutex::new(Vec::new()));
+    let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new())).unwrap();
+    writer.add_hook(Duplicates(warned.clone()));
+    writer.set_duplicate_policy(DuplicateNamePolicy::Error);
+    writer.add_entry("a.txt", b"1").unwrap();
+    let err = writer.add_entry("a.txt", b"2").unwrap_err();
+    assert!(matches!(err, SZipError::InvalidFormat(_)));
+    assert!(warned.lock().unwrap().is_empty());
+
+    // Warn still writes the duplicate, and reports it to the hooks
+    writer.set_duplicate_policy(DuplicateNamePolicy::Warn);
+    writer.add_entry("a.txt", b"3").unwrap();
+    writer.add_entry("b.txt", b"4").unwrap();
+    let bytes = writer.finish().unwrap().into_inner();
+
+    assert_eq!(entry_names(bytes), ["a.txt", "a.txt", "b.txt"]);
+    assert_eq!(*warned.lock().unwrap(), ["a.txt"]);
+}
+
+#[test]
+fn test_dos_attributes_roundtrip() {
+    let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new())).unwrap();
+    writer.set_version_made_by(0x0A14); // NTFS host, spec 2.0
+    let attrs = DosAttributes {
+        read_only: true,
+        hidden: true,
+        ..Default::default()
+    };
+    let opts = EntryOptions::default().with_dos_attributes(attrs);
+    writer
+        .start_entry_with_options("desktop.ini", opts)
+        .unwrap();
+    writer.write_data(b"[.ShellClassInfo]").unwrap();
+    writer.add_entry("plain.txt", b"x").unwrap();
+    let bytes = writer.finish().unwrap().into_inner();
+
+    let reader = StreamingZipReader::from_vec(bytes).unwrap();
+    let entry = reader.find_entry("desktop.ini").unwrap();
+    assert_eq!(entry.external_attrs, 0x03);
+    assert_eq!(entry.dos_attributes(), Some(attrs));
+    assert_eq!(
+        reader.find_entry("plain.txt").unwrap().dos_attributes(),
+        Some(DosAttributes::default())
+    );
+}
+
+#[test]
+fn test_flags_and_version_made_by() {
+    let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new())).unwrap();
+    writer.add_entry("plain.txt", b"ascii").unwrap();
+    writer.add_entry("résumé.txt", b"utf-8").unwrap();
+    let bytes = writer.finish().unwrap().into_inner();
+
+    let mut reader = StreamingZipReader::from_vec(bytes).unwrap();
+    let plain = reader.find_entry("plain.txt").unwrap();
+    assert_eq!(plain.flags, 0x0008);
+    assert_eq!(plain.version_made_by & 0xFF, 63);
+    assert_eq!(reader.find_entry("résumé.txt").unwrap().flags, 0x0808);
+    assert_eq!(reader.read_entry_by_name("résumé.txt").unwrap(), b"utf-8");
+}
+
+#[test]
+fn test_windows_explorer_profile() {
+    let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new())).unwrap();
+    writer.set_compatibility(Compatibility::WindowsExplorer);
+    writer
+        .add_entry("docs\\readme.txt", b"hello explorer")
+        .unwrap();
+    writer.add_entry("docs/", b"").unwrap();
+    let bytes = writer.finish().unwrap().into_inner();
+
+    // No data descriptor: CRC-32 and sizes are patched into the local header
+    assert_eq!(&bytes[6..8], &0u16.to_le_bytes());
+    assert_eq!(
+        &bytes[14..18],
+        &crc32fast::hash(b"hello explorer").to_le_bytes()
+    );
+    assert_eq!(&bytes[22..26], &14u32.to_le_bytes());
+
+    let mut reader = StreamingZipReader::from_vec(bytes).unwrap();
+    let file = reader.find_entry("docs/readme.txt").unwrap();
+    assert_eq!(file.flags, 0);
+    assert_eq!(file.compression_method, 8);
+    assert_ne!(file.last_modified.date, 0);
+    assert!(file.dos_attributes().unwrap().archive);
+    assert!(
+        reader
+            .find_entry("docs/")
+            .unwrap()
+            .dos_attributes()
+            .unwrap()
+            .directory
+    );
+    assert_eq!(
+        reader.read_entry_by_name("docs/readme.txt").unwrap(),
+        b"hello explorer"
+    );
+}
+
+#[test]
+fn test_windows_explorer_reserves_zip64_for_large_hint() {
+    let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new())).unwrap();
+    writer.set_compatibility(Compatibility::WindowsExplorer);
+    writer
+        .start_entry_with_hint("big.bin", Some(5 * 1024 * 1024 * 1024))
+        .unwrap();
+    writer.write_data(b"actually small").unwrap();
+    let bytes = writer.finish().unwrap().into_inner();
+
+    // Size fields hold the ZIP64 marker; the extra field carries the real sizes
+    assert_eq!(&bytes[18..26], &[0xFF; 8]);
+    let extra = 30 + "big.bin".len();
+    assert_eq!(&bytes[extra..extra + 2], &1u16.to_le_bytes());
+    assert_eq!(&bytes[extra + 4..extra + 12], &14u64.to_le_bytes());
+
+    let mut reader = StreamingZipReader::from_vec(bytes).unwrap();
+    assert_eq!(
+        reader.read_entry_by_name("big.bin").unwrap(),
+        b"actually small"
+    );
+}
+
+#[test]
+fn test_macos_profile_and_metadata() {
+    let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new())).unwrap();
+    writer
+        .set_compatibility(Compatibility::MacOs)
+        .set_omit_macos_metadata(true);
+    writer.add_entry("app/", b"").unwrap();
+    writer.add_entry("app/.DS_Store", b"finder junk").unwrap();
+    writer
+        .add_entry("./__MACOSX/app/._x", b"finder junk")
+        .unwrap();
+    writer
+        .add_entry("app\\._notes.txt", b"finder junk")
+        .unwrap();
+    writer.add_entry("app/notes.txt", b"keep me").unwrap();
+    let script = EntryOptions::default().with_unix_mode(0o755);
+    writer
+        .start_entry_with_options("app/run.sh", script)
+        .unwrap();
+    writer.write_data(b"#!/bin/sh\n").unwrap();
+    writer
+        .add_apple_double("app/notes.txt", &[0u8; 32], b"")
+        .unwrap();
+    let bytes = writer.finish().unwrap().into_inner();
+
+    let mut reader = StreamingZipReader::from_vec(bytes).unwrap();
+    let names: Vec<&str> = reader.entries().iter().map(|e| e.name.as_str()).collect();
+    assert_eq!(
+        names,
+        [
+            "app/",
+            "app/notes.txt",
+            "app/run.sh",
+            "__MACOSX/app/._notes.txt"
+        ]
+    );
+    let notes = reader.find_entry("app/notes.txt").unwrap();
+    assert_eq!(notes.version_made_by >> 8, 3);
+    assert_eq!(notes.external_attrs >> 16, 0o100644);
+    assert_eq!(
+        reader.find_entry("app/run.sh").unwrap().external_attrs >> 16,
+        0o100755
+    );
+    assert_eq!(
+        reader.find_entry("app/").unwrap().external_attrs >> 16,
+        0o040755
+    );
+
+    let double = reader
+        .read_entry_by_name("__MACOSX/app/._notes.txt")
+        .unwrap();
+    assert_eq!(&double[..4], &[0x00, 0x05, 0x16, 0x07]);
+
+    reader.hide_macos_metadata();
+    assert_eq!(reader.entries().len(), 3);
+}
+
+#[test]
+fn test_archive_comment() {
+    let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new())).unwrap();
+    writer.set_archive_comment("build 1234 (commit abcdef)");
+    writer.add_entry("a.txt", b"a").unwrap();
+    let bytes = writer.finish().unwrap().into_inner();
+    let reader = StreamingZipReader::from_vec(bytes).unwrap();
+    assert_eq!(reader.comment(), "build 1234 (commit abcdef)");
+
+    let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new())).unwrap();
+    writer.set_archive_comment(&"x".repeat(70_000));
+    assert!(writer.finish().is_err());
+}
+
+#[test]
+fn test_add_directory() {
+    let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new())).unwrap();
+    writer.add_directory("empty").unwrap();
+    writer.add_directory("logs/").unwrap();
+    #[cfg(feature = "encryption")]
+    writer.set_password("secret");
+    writer.add_directory("private").unwrap();
+    // Nothing is open after a directory
+    assert!(writer.write_data(b"stray").is_err());
+    let bytes = writer.finish().unwrap().into_inner();
+
+    let mut reader = StreamingZipReader::from_vec(bytes).unwrap();
+    let names: Vec<&str> = reader.entries().iter().map(|e| e.name.as_str()).collect();
+    assert_eq!(names, ["empty/", "logs/", "private/"]);
+    for entry in reader.entries() {
+        assert_eq!(entry.compression_method, 0);
+        assert_eq!(entry.compressed_size, 0);
+        assert!(!entry.is_encrypted);
+        assert!(entry.dos_attributes().unwrap().directory);
+    }
+
+    let out = tempfile::tempdir().unwrap();
+    reader.extract_to_dir(out.path()).unwrap();
+    assert!(out.path().join("empty").is_dir());
+    assert!(out.path().join("private").is_dir());
+}
+
+#[test]
+fn test_unix_mode_marks_unix_host() {
+    let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new())).unwrap();
+    let script = EntryOptions::default().with_unix_mode(0o755);
+    writer.start_entry_with_options("run.sh", script).unwrap();
+    writer.write_data(b"#!/bin/sh\necho hi\n").unwrap();
+    let dir = EntryOptions::default().with_unix_mode(0o700);
+    writer.start_entry_with_options("private/", dir).unwrap();
+    writer.add_entry("plain.txt", b"no mode").unwrap();
+    let bytes = writer.finish().unwrap().into_inner();
+
+    let mut reader = StreamingZipReader::from_vec(bytes).unwrap();
+    let run = reader.find_entry("run.sh").unwrap();
+    assert_eq!(run.version_made_by >> 8, 3);
+    assert_eq!(run.unix_mode(), Some(0o100755));
+    let private = reader.find_entry("private/").unwrap();
+    assert_eq!(private.unix_mode(), Some(0o040700));
+    // Entries without a mode keep the MS-DOS host
+    let plain = reader.find_entry("plain.txt").unwrap();
+    assert_eq!(plain.version_made_by >> 8, 0);
+    assert_eq!(plain.unix_mode(), None);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let out = tempfile::tempdir().unwrap();
+        reader.extract_to_dir(out.path()).unwrap();
+        let mode = std::fs::metadata(out.path().join("run.sh"))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o755);
+    }
+}
+
+#[test]
+fn test_write_file_keeps_metadata() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("run.sh");
+    let content = "echo hello\n".repeat(10_000);
+    std::fs::write(&path, &content).unwrap();
+    let mtime = std::time::UNIX_EPOCH + Duration::from_secs(1_600_000_000);
+    std::fs::File::options()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(mtime)
+        .unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o750)).unwrap();
+    }
+
+    let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new())).unwrap();
+    writer.write_file("bin/run.sh", &path).unwrap();
+    let bytes = writer.finish().unwrap().into_inner();
+
+    let mut reader = StreamingZipReader::from_vec(bytes).unwrap();
+    let entry = &reader.entries()[0];
+    assert_eq!(entry.modified(), Some(mtime));
+    #[cfg(unix)]
+    assert_eq!(entry.unix_mode(), Some(0o100750));
+    assert_eq!(
+        reader.read_entry_by_name("bin/run.sh").unwrap(),
+        content.as_bytes()
+    );
+
+    // A missing file fails before any entry is started
+    let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new())).unwrap();
+    let err = writer.write_file("gone.txt", dir.path().join("gone.txt"));
+    assert!(matches!(err, Err(SZipError::Io(_))));
+    assert!(entry_names(writer.finish().unwrap().into_inner()).is_empty());
+}
+
+#[test]
+fn test_add_dir_recursive_skips_junk() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    for (path, data) in [
+        ("a.txt", "a"),
+        (".DS_Store", "junk"),
+        ("notes.txt.swp", "swap"),
+        (".git/HEAD", "ref"),
+        ("sub/.gitignore", "*.log\n"),
+        ("sub/x.log", "log"),
+        ("sub/y.txt", "y"),
+        ("x.log", "top-level log"),
+    ] {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, data).unwrap();
+    }
+    std::fs::create_dir(root.join("empty")).unwrap();
+
+    let mut rules = IgnoreRules::default();
+    rules.set_read_gitignore(true);
+    let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new())).unwrap();
+    let added = writer
+        .add_dir_recursive_with_ignore(root, "proj", &rules)
+        .unwrap();
+    let bytes = writer.finish().unwrap().into_inner();
+
+    assert_eq!(added, 4);
+    assert_eq!(
+        entry_names(bytes),
+        [
+            "proj/a.txt",
+            "proj/empty/",
+            "proj/sub/",
+            "proj/sub/.gitignore",
+            "proj/sub/y.txt",
+            "proj/x.log",
+        ]
+    );
+}
+
+#[test]
+fn test_deadline_exceeded() {
+    let past = Instant::now() - Duration::from_secs(1);
+    let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new()))
+        .unwrap()
+        .with_deadline(past);
+    let err = writer.add_entry("a.txt", b"a").unwrap_err();
+    assert!(matches!(err, SZipError::DeadlineExceeded));
+
+    let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new()))
+        .unwrap()
+        .with_deadline(Instant::now() + Duration::from_secs(600));
+    writer.add_entry("a.txt", b"a").unwrap();
+    let bytes = writer.finish().unwrap().into_inner();
+    assert_eq!(entry_names(bytes.clone()), ["a.txt"]);
+
+    let mut reader = StreamingZipReader::from_vec(bytes)
+        .unwrap()
+        .with_deadline(past);
+    let err = reader.read_entry_by_name("a.txt").unwrap_err();
+    assert!(matches!(err, SZipError::DeadlineExceeded));
+}
+
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<Vec<String>>>);
+
+impl WriterHook for Recorder {
+    fn header_written(&mut self, name: &str, offset: u64, header: &[u8]) -> s_zip::Result<()> {
+        let event = format!("header {name} @{offset} {}", header.len());
+        self.0.lock().unwrap().push(event);
+        Ok(())
+    }
+
+    fn data_written(&mut self, name: &str, data: &[u8]) -> s_zip::Result<()> {
+        if data == b"forbidden" {
+            return Err(SZipError::InvalidFormat("rejected by hook".into()));
+        }
+        let event = format!("data {name} {}", data.len());
+        self.0.lock().unwrap().push(event);
+        Ok(())
+    }
+
+    fn entry_finished(&mut self, entry: &EntrySummary<'_>) -> s_zip::Result<()> {
+        let event = format!("entry {} {:08x}", entry.name, entry.crc32);
+        self.0.lock().unwrap().push(event);
+        Ok(())
+    }
+
+    fn archive_finished(&mut self, archive: &ArchiveSummary) -> s_zip::Result<()> {
+        let event = format!("archive {} {}", archive.entry_count, archive.total_size);
+        self.0.lock().unwrap().push(event);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_writer_hooks() {
+    let recorder = Recorder::default();
+    let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new())).unwrap();
+    writer.add_hook(recorder.clone());
+    writer.start_entry("a.txt").unwrap();
+    writer.write_data(b"hello ").unwrap();
+    writer.write_data(b"world").unwrap();
+    writer.add_entry("b.txt", b"").unwrap();
+    let bytes = writer.finish().unwrap().into_inner();
+
+    let crc = crc32fast::hash(b"hello world");
+    // Second header follows the first entry's data and 16-byte data descriptor
+    let b_offset = 30
+        + 5
+        + {
+            let reader = StreamingZipReader::from_vec(bytes.clone()).unwrap();
+            reader.entries()[0].compressed_size
+        }
+        + 16;
+    assert_eq!(
+        *recorder.0.lock().unwrap(),
+        [
+            "header a.txt @0 35".to_string(),
+            "data a.txt 6".to_string(),
+            "data a.txt 5".to_string(),
+            format!("entry a.txt {crc:08x}"),
+            format!("header b.txt @{b_offset} 35"),
+            "data b.txt 0".to_string(),
+            "entry b.txt 00000000".to_string(),
+            format!("archive 2 {}", bytes.len()),
+        ]
+    );
+
+    let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new())).unwrap();
+    writer.add_hook(Recorder::default());
+    writer.start_entry("c.txt").unwrap();
+    let err = writer.write_data(b"forbidden").unwrap_err();
+    assert!(matches!(err, SZipError::InvalidFormat(_)));
+}
+
+#[test]
+fn test_entry_stats_callback() {
+    let stats = Arc::new(Mutex::new(Vec::new()));
+    let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new())).unwrap();
+    let sink = stats.clone();
+    writer.on_entry_stats(move |entry| {
+        sink.lock().unwrap().push((
+            entry.name.to_string(),
+            entry.uncompressed_size,
+            entry.compressed_size,
+            entry.compression_method,
+            entry.compression_ratio(),
+        ))
+    });
+    writer.add_entry("zeros.bin", &[0u8; 10_000]).unwrap();
+    writer.add_entry("empty.txt", b"").unwrap();
+    let bytes = writer.finish().unwrap().into_inner();
+
+    let reader = StreamingZipReader::from_vec(bytes).unwrap();
+    let stats = stats.lock().unwrap();
+    assert_eq!(stats.len(), 2);
+    let (name, uncompressed, compressed, method, ratio) = &stats[0];
+    assert_eq!(name, "zeros.bin");
+    assert_eq!(*uncompressed, 10_000);
+    assert_eq!(*compressed, reader.entries()[0].compressed_size);
+    assert_eq!(*method, 8);
+    assert!(*ratio < 0.1);
+    assert_eq!(stats[1].4, 1.0);
+}
+
+#[test]
+fn test_memory_budget_caps_buffering() {
+    use s_zip::budget::MemoryBudget;
+
+    let budget = MemoryBudget::new(16 * 1024);
+    let mut writer = StreamingZipWriter::from_writer_with_method(
+        Cursor::new(Vec::new()),
+        s_zip::CompressionMethod::Stored,
+        0,
+    )
+    .unwrap()
+    .with_memory_budget(&budget);
+    let mut state = 0x2545_f491u32;
+    let noise: Vec<u8> = (0..200_000)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state as u8
+        })
+        .collect();
+    writer.start_entry("noise.bin").unwrap();
+    for chunk in noise.chunks(4096) {
+        writer.write_data(chunk).unwrap();
+        assert!(budget.used() <= budget.limit());
+    }
+    // Another job holding the whole budget forces every chunk straight out
+    let held = budget.try_reserve(budget.available()).unwrap();
+    writer.add_entry("more.bin", &noise[..50_000]).unwrap();
+    drop(held);
+    let bytes = writer.finish().unwrap().into_inner();
+    assert_eq!(budget.used(), 0);
+
+    let mut reader = StreamingZipReader::from_vec(bytes).unwrap();
+    assert_eq!(reader.read_entry_by_name("noise.bin").unwrap(), noise);
+    assert_eq!(
+        reader.read_entry_by_name("more.bin").unwrap(),
+        &noise[..50_000]
+    );
+}
+
+#[test]
+fn test_self_extracting_archives() {
+    use s_zip::sfx::SfxBuilder;
+
+    let sfx = SfxBuilder::unix_shell();
+    let mut writer = sfx.writer(Cursor::new(Vec::new())).unwrap();
+    writer.add_entry("hello.txt", b"hello").unwrap();
+    let built = writer.finish().unwrap().into_inner();
+    assert!(built.starts_with(sfx.stub()));
+
+    let mut plain = StreamingZipWriter::from_writer(Cursor::new(Vec::new())).unwrap();
+    plain.add_entry("hello.txt", b"hello").unwrap();
+    let plain = plain.finish().unwrap().into_inner();
+    let mut wrapped = Vec::new();
+    let len = sfx.wrap_archive(Cursor::new(plain), &mut wrapped).unwrap();
+    assert_eq!(len, wrapped.len() as u64);
+    // Both paths give the same bytes: stub, then the archive with shifted offsets
+    assert_eq!(wrapped, built);
+
+    let mut reader = StreamingZipReader::from_vec(wrapped).unwrap();
+    assert_eq!(reader.entries()[0].offset, sfx.stub().len() as u64);
+    assert_eq!(reader.read_entry_by_name("hello.txt").unwrap(), b"hello");
+    assert!(reader.verify().unwrap().is_ok());
+
+    // Run the stub itself when `unzip` is available
+    #[cfg(unix)]
+    if std::process::Command::new("unzip")
+        .arg("-v")
+        .output()
+        .is_ok()
+    {
+        let dir = tempfile::tempdir().unwrap();
+        let exe = dir.path().join("hello.run");
+        std::fs::write(&exe, &built).unwrap();
+        let status = std::process::Command::new("sh")
+            .arg(&exe)
+            .arg(dir.path().join("out"))
+            .output()
+            .unwrap();
+        assert!(status.status.success(), "{:?}", status);
+        let extracted = std::fs::read(dir.path().join("out/hello.txt")).unwrap();
+        assert_eq!(extracted, b"hello");
+    }
+}
+
+#[cfg(feature = "signing")]
+#[test]
+fn test_signed_archives() {
+    use ed25519_dalek::{Signature, Signer as _, SigningKey, Verifier as _, VerifyingKey};
+    use s_zip::signing::{SignaturePlacement, SignatureVerifier, Signer, SIGNATURE_ENTRY};
+    use s_zip::CompressionMethod;
+
+    struct Ed25519Signer(SigningKey);
+    impl Signer for Ed25519Signer {
+        fn algorithm(&self) -> &str {
+            "ed25519"
+        }
+        fn sign(&self, manifest: &[u8]) -> s_zip::Result<Vec<u8>> {
+            Ok(self.0.sign(manifest).to_vec())
+        }
+    }
+
+    struct Ed25519Verifier(VerifyingKey);
+    impl SignatureVerifier for Ed25519Verifier {
+        fn algorithm(&self) -> &str {
+            "ed25519"
+        }
+        fn verify(&self, manifest: &[u8], signature: &[u8]) -> s_zip::Result<()> {
+            let invalid =
+                |e: ed25519_dalek::SignatureError| SZipError::SignatureInvalid(e.to_string());
+            let signature = Signature::from_slice(signature).map_err(invalid)?;
+            self.0.verify(manifest, &signature).map_err(invalid)
+        }
+    }
+
+    let key = SigningKey::from_bytes(&[42; 32]);
+    let verifier = Ed25519Verifier(key.verifying_key());
+    let build = |placement| {
+        let mut writer = StreamingZipWriter::from_writer_with_method(
+            Cursor::new(Vec::new()),
+            CompressionMethod::Stored,
+            0,
+        )
+        .unwrap();
+        writer.sign_with(Ed25519Signer(key.clone()), placement);
+        writer.add_entry("VERSION", b"release 1.0.0").unwrap();
+        writer.start_entry("bin/tool").unwrap();
+        writer.write_data(b"\x7fELF").unwrap();
+        writer.write_data(&[0u8; 1000]).unwrap();
+        writer.add_entry("empty/", b"").unwrap();
+        writer.finish().unwrap().into_inner()
+    };
+
+    let signed = build(SignaturePlacement::Entry);
+    let mut reader = StreamingZipReader::from_vec(signed.clone()).unwrap();
+    assert_eq!(reader.entries().last().unwrap().name, SIGNATURE_ENTRY);
+    reader.verify_signature(&verifier).unwrap();
+
+    let in_comment = build(SignaturePlacement::Comment);
+    let mut reader = StreamingZipReader::from_vec(in_comment).unwrap();
+    assert_eq!(reader.entries().len(), 3);
+    assert!(reader
+        .comment()
+        .starts_with("S-ZIP-SIGNATURE 1\nalgorithm: ed25519\n"));
+    reader.verify_signature(&verifier).unwrap();
+
+    // Another key
+    let other = Ed25519Verifier(SigningKey::from_bytes(&[1; 32]).verifying_key());
+    let mut reader = StreamingZipReader::from_vec(signed.clone()).unwrap();
+    assert!(matches!(
+        reader.verify_signature(&other),
+        Err(SZipError::SignatureInvalid(_))
+    ));
+
+    // Entry data changed after signing
+    let at = signed.windows(5).position(|w| w == b"1.0.0").unwrap();
+    let mut tampered = signed.clone();
+    tampered[at..at + 5].copy_from_slice(b"6.6.6");
+    let mut reader = StreamingZipReader::from_vec(tampered).unwrap();
+    match reader.verify_signature(&verifier) {
+        Err(SZipError::SignatureInvalid(msg)) => assert!(msg.contains("VERSION"), "{}", msg),
+        other => panic!("expected a signature error, got {:?}", other),
+    }
+
+    // Mode changed after signing: set the setuid bit of bin/tool
+    let record = (0..signed.len() - 54)
+        .find(|&i| {
+            signed[i..].starts_with(b"PK\x01\x02") && signed[i + 46..].starts_with(b"bin/tool")
+        })
+        .unwrap();
+    let mut tampered = signed.clone();
+    tampered[record + 41] |= 0x08;
+    let mut reader = StreamingZipReader::from_vec(tampered).unwrap();
+    match reader.verify_signature(&verifier) {
+        Err(SZipError::SignatureInvalid(msg)) => assert!(msg.contains("bin/tool"), "{}", msg),
+        other => panic!("expected a signature error, got {:?}", other),
+    }
+
+    // A second signature entry appended after signing
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("signed.zip");
+    std::fs::write(&path, &signed).unwrap();
+    let mut writer = StreamingZipWriter::append(&path).unwrap();
+    writer.add_entry(SIGNATURE_ENTRY, b"anything").unwrap();
+    writer.finish().unwrap();
+    let mut reader = StreamingZipReader::open(&path).unwrap();
+    assert!(matches!(
+        reader.verify_signature(&verifier),
+        Err(SZipError::SignatureInvalid(_))
+    ));
+
+    // Unsigned archive
+    let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new())).unwrap();
+    writer.add_entry("VERSION", b"release 1.0.0").unwrap();
+    let unsigned = writer.finish().unwrap().into_inner();
+    let mut reader = StreamingZipReader::from_vec(unsigned).unwrap();
+    assert!(matches!(
+        reader.verify_signature(&verifier),
+        Err(SZipError::SignatureInvalid(_))
+    ));
+
+    // Entries added before sign_with cannot be covered
+    let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new())).unwrap();
+    writer.add_entry("early.txt", b"x").unwrap();
+    writer.sign_with(Ed25519Signer(key.clone()), SignaturePlacement::Entry);
+    assert!(matches!(writer.finish(), Err(SZipError::InvalidFormat(_))));
+}
+
+#[cfg(feature = "manifest")]
+#[test]
+fn test_manifest_entry() {
+    use s_zip::manifest::{Manifest, MANIFEST_ENTRY};
+    use s_zip::CompressionMethod;
+
+    let mut writer = StreamingZipWriter::from_writer_with_method(
+        Cursor::new(Vec::new()),
+        CompressionMethod::Stored,
+        0,
+    )
+    .unwrap();
+    writer.set_manifest(true);
+    writer.add_entry("a.txt", b"alpha").unwrap();
+    writer.start_entry("b.txt").unwrap();
+    writer.write_data(b"be").unwrap();
+    writer.write_data(b"ta").unwrap();
+    let zip = writer.finish().unwrap().into_inner();
+
+    let mut reader = StreamingZipReader::from_vec(zip.clone()).unwrap();
+    assert_eq!(reader.entries().last().unwrap().name, MANIFEST_ENTRY);
+    let manifest = Manifest::parse(&reader.read_entry_by_name(MANIFEST_ENTRY).unwrap()).unwrap();
+    let names: Vec<_> = manifest.entries.iter().map(|e| e.name.as_str()).collect();
+    assert_eq!(names, ["a.txt", "b.txt"]);
+    assert_eq!(manifest.entries[1].size, 4);
+    assert_eq!(manifest.entries[1].crc32, reader.entries()[1].crc32);
+    assert_eq!(
+        Manifest::parse(&manifest.to_bytes().unwrap()).unwrap(),
+        manifest
+    );
+
+    let report = reader.verify_against_manifest().unwrap();
+    assert!(report.is_ok(), "{:?}", report);
+    assert_eq!(report.verified, ["a.txt", "b.txt"]);
+
+    // Same length, different content: the CRC and SHA-256 no longer match
+    let at = zip.windows(5).position(|w| w == b"alpha").unwrap();
+    let mut tampered = zip;
+    tampered[at..at + 5].copy_from_slice(b"omega");
+    let mut reader = StreamingZipReader::from_vec(tampered).unwrap();
+    let report = reader.verify_against_manifest().unwrap();
+    assert!(!report.is_ok());
+    assert_eq!(report.modified, ["a.txt"]);
+    assert_eq!(report.verified, ["b.txt"]);
+
+    let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new())).unwrap();
+    writer.add_entry("a.txt", b"alpha").unwrap();
+    let plain = writer.finish().unwrap().into_inner();
+    let mut reader = StreamingZipReader::from_vec(plain).unwrap();
+    assert!(matches!(
+        reader.verify_against_manifest(),
+        Err(SZipError::EntryNotFound(_))
+    ));
+
+    // Copied entries are hashed too
+    let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new())).unwrap();
+    writer.set_manifest(true);
+    let entry = reader.entries()[0].clone();
+    writer.copy_entry(&mut reader, &entry).unwrap();
+    writer.add_entry("b.txt", b"beta").unwrap();
+    let zip = writer.finish().unwrap().into_inner();
+    let report = StreamingZipReader::from_vec(zip)
+        .unwrap()
+        .verify_against_manifest()
+        .unwrap();
+    assert_eq!(report.verified, ["a.txt", "b.txt"]);
+}
+
+#[test]
+fn test_new_split_round_trip() {
+    use s_zip::split::{volume_path, MIN_VOLUME_SIZE};
+    use s_zip::CompressionMethod;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("backup.zip");
+    assert!(StreamingZipWriter::new_split(&path, MIN_VOLUME_SIZE - 1).is_err());
+
+    let data = |seed: u32| -> Vec<u8> {
+        (0..50_000u32)
+            .map(|i| (i.wrapping_mul(seed) % 251) as u8)
+            .collect()
+    };
+    let mut writer = StreamingZipWriter::new_split_with_method(
+        &path,
+        MIN_VOLUME_SIZE,
+        CompressionMethod::Stored,
+        0,
+    )
+    .unwrap();
+    for seed in 1..=5 {
+        writer
+            .add_entry(&format!("part{}.bin", seed), &data(seed))
+            .unwrap();
+    }
+    let output = writer.finish().unwrap();
+    let volumes = output.volume_count();
+    assert!(volumes >= 4, "{} volumes", volumes);
+    assert!(path.exists());
+    for disk in 0..volumes as u32 - 1 {
+        let len = std::fs::metadata(volume_path(&path, disk)).unwrap().len();
+        assert!(len <= MIN_VOLUME_SIZE);
+    }
+    // Spanning signature (APPNOTE 8.5.3) ahead of the first local header
+    let first = std::fs::read(volume_path(&path, 0)).unwrap();
+    assert_eq!(&first[..8], b"PK\x07\x08PK\x03\x04");
+
+    let mut reader = StreamingZipReader::open_split(&path).unwrap();
+    assert!(reader.entries().iter().any(|e| e.disk_number > 0));
+    for seed in 1..=5 {
+        let name = format!("part{}.bin", seed);
+        assert_eq!(reader.read_entry_by_name(&name).unwrap(), data(seed));
+    }
+
+    // An archive that fits one volume gets the single-segment marker
+    let single = dir.path().join("small.zip");
+    let mut writer = StreamingZipWriter::new_split(&single, MIN_VOLUME_SIZE).unwrap();
+    writer.add_entry("small.txt", b"fits").unwrap();
+    assert_eq!(writer.finish().unwrap().volume_count(), 1);
+    let bytes = std::fs::read(&single).unwrap();
+    assert_eq!(&bytes[..8], b"PK00PK\x03\x04");
+    let mut reader = StreamingZipReader::open(&single).unwrap();
+    assert_eq!(reader.read_entry_by_name("small.txt").unwrap(), b"fits");
+}
+
+#[test]
+fn test_append_to_existing_archive() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("logs.zip");
+    let mut writer = StreamingZipWriter::new(&path).unwrap();
+    writer.set_archive_comment("daily logs");
+    writer.add_entry("monday.log", b"first day").unwrap();
+    writer.add_entry("tuesday.log", b"second day").unwrap();
+    writer.finish().unwrap();
+    let before = std::fs::read(&path).unwrap();
+    let data_end = StreamingZipReader::open(&path)
+        .unwrap()
+        .cd_location()
+        .unwrap()
+        .offset as usize;
+
+    let mut writer = StreamingZipWriter::append(&path).unwrap();
+    writer.set_duplicate_policy(DuplicateNamePolicy::Error);
+    assert!(writer.add_entry("monday.log", b"again").is_err());
+    writer.add_entry("wednesday.log", b"third day").unwrap();
+    writer.finish().unwrap();
+
+    // Existing entry data is left untouched
+    let after = std::fs::read(&path).unwrap();
+    assert_eq!(after[..data_end], before[..data_end]);
+
+    let mut writer = StreamingZipWriter::append(&path).unwrap();
+    writer.add_entry("thursday.log", b"fourth day").unwrap();
+    writer.finish().unwrap();
+
+    let mut reader = StreamingZipReader::open(&path).unwrap();
+    assert_eq!(reader.comment(), "daily logs");
+    let names: Vec<_> = reader.entries().iter().map(|e| e.name.clone()).collect();
+    assert_eq!(
+        names,
+        ["monday.log", "tuesday.log", "wednesday.log", "thursday.log"]
+    );
+    assert!(reader.verify().unwrap().is_ok());
+    assert_eq!(
+        reader.read_entry_by_name("monday.log").unwrap(),
+        b"first day"
+    );
+    assert_eq!(
+        reader.read_entry_by_name("thursday.log").unwrap(),
+        b"fourth day"
+    );
+
+    // Appending without new entries leaves an equivalent archive
+    StreamingZipWriter::append(&path).unwrap().finish().unwrap();
+    assert_eq!(StreamingZipReader::open(&path).unwrap().entries().len(), 4);
+
+    std::fs::write(&path, b"not a zip").unwrap();
+    assert!(StreamingZipWriter::append(&path).is_err());
+}
+
+#[test]
+fn test_rewrite_removes_and_replaces_entries() {
+    use s_zip::Rewrite;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("app.zip");
+    let mut writer = StreamingZipWriter::new(&path).unwrap();
+    writer.set_archive_comment("release 1");
+    let options = EntryOptions::default().with_unix_mode(0o755);
+    writer.start_entry_with_options("bin/run", options).unwrap();
+    writer.write_data(b"#!/bin/sh\nexec app\n").unwrap();
+    writer
+        .add_entry("config.json", b"{\"debug\": true}")
+        .unwrap();
+    writer.add_entry("secrets.env", b"TOKEN=hunter2").unwrap();
+    writer.add_entry("logs/old.log", b"stale").unwrap();
+    writer
+        .add_entry("data.txt", &b"payload ".repeat(1000))
+        .unwrap();
+    writer.finish().unwrap();
+
+    let mut original = StreamingZipReader::open(&path).unwrap();
+    let entry = original.find_entry("data.txt").unwrap().clone();
+    let (offset, len) = original.data_span(&entry).unwrap();
+    let stored = std::fs::read(&path).unwrap()[offset as usize..(offset + len) as usize].to_vec();
+    drop(original);
+
+    let mut rewrite = Rewrite::new();
+    rewrite
+        .remove("secrets.env")
+        .remove_if(|entry| entry.name.starts_with("logs/"))
+        .replace("config.json", b"{\"debug\": false}".to_vec())
+        .replace_with("NOTES.txt", Cursor::new(b"rewritten".to_vec()));
+    let report = rewrite.to_file(&path, &path).unwrap();
+    assert_eq!(report.copied, 2);
+    assert_eq!(report.removed, ["secrets.env", "logs/old.log"]);
+    assert_eq!(report.replaced, ["config.json"]);
+    assert_eq!(report.added, ["NOTES.txt"]);
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+    let mut reader = StreamingZipReader::open(&path).unwrap();
+    assert_eq!(reader.comment(), "release 1");
+    let names: Vec<_> = reader.entries().iter().map(|e| e.name.clone()).collect();
+    assert_eq!(names, ["bin/run", "config.json", "data.txt", "NOTES.txt"]);
+    assert!(reader.verify().unwrap().is_ok());
+    assert_eq!(reader.entries()[0].unix_mode(), Some(0o100755));
+    assert_eq!(
+        reader.read_entry_by_name("config.json").unwrap(),
+        b"{\"debug\": false}"
+    );
+    assert_eq!(
+        reader.read_entry_by_name("NOTES.txt").unwrap(),
+        b"rewritten"
+    );
+
+    // Kept entries are copied as stored, not recompressed
+    let entry = reader.find_entry("data.txt").unwrap().clone();
+    let (offset, len) = reader.data_span(&entry).unwrap();
+    let copied = std::fs::read(&path).unwrap()[offset as usize..(offset + len) as usize].to_vec();
+    assert_eq!(copied, stored);
+    assert_eq!(
+        reader.read_entry_by_name("data.txt").unwrap(),
+        b"payload ".repeat(1000)
+    );
+}
+
+#[test]
+fn test_rewrite_remove_if_filters_add_up() {
+    use s_zip::Rewrite;
+
+    let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new())).unwrap();
+    for name in ["logs/a.log", "tmp/b.tmp", "keep.txt"] {
+        writer.add_entry(name, b"x").unwrap();
+    }
+    let mut reader = StreamingZipReader::from_vec(writer.finish().unwrap().into_inner()).unwrap();
+
+    let mut rewrite = Rewrite::new();
+    rewrite
+        .remove_if(|entry| entry.name.starts_with("logs/"))
+        .remove_if(|entry| entry.name.ends_with(".tmp"));
+    let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new())).unwrap();
+    let report = rewrite.apply(&mut reader, &mut writer).unwrap();
+    assert_eq!(report.removed, ["logs/a.log", "tmp/b.tmp"]);
+    assert_eq!(
+        entry_names(writer.finish().unwrap().into_inner()),
+        ["keep.txt"]
+    );
+}
+
+#[test]
+fn test_merge_shards() {
+    use s_zip::merge::{merge, ConflictPolicy};
+
+    let dir = tempfile::tempdir().unwrap();
+    let shard = |i: usize, entries: &[(&str, &str)]| {
+        let path = dir.path().join(format!("shard-{}.zip", i));
+        let mut writer = StreamingZipWriter::new(&path).unwrap();
+        writer.add_directory("out/").unwrap();
+        for (name, data) in entries {
+            writer.add_entry(name, data.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap();
+        path
+    };
+    let shards = [
+        shard(0, &[("out/a.txt", "a0"), ("out/common.txt", "from 0")]),
+        shard(1, &[("out/b.txt", "b1")]),
+        shard(2, &[("out/common.txt", "from 2"), ("out/c.txt", "c2")]),
+    ];
+    let output = dir.path().join("all.zip");
+
+    assert!(merge(&shards, &output, ConflictPolicy::Error).is_err());
+
+    let report = merge(&shards, &output, ConflictPolicy::KeepFirst).unwrap();
+    assert_eq!(report.copied, 5);
+    assert_eq!(report.skipped, ["out/common.txt"]);
+    let mut reader = StreamingZipReader::open(&output).unwrap();
+    let names: Vec<_> = reader.entries().iter().map(|e| e.name.clone()).collect();
+    assert_eq!(
+        names,
+        [
+            "out/",
+            "out/a.txt",
+            "out/common.txt",
+            "out/b.txt",
+            "out/c.txt"
+        ]
+    );
+    assert!(reader.verify().unwrap().is_ok());
+    assert_eq!(
+        reader.read_entry_by_name("out/common.txt").unwrap(),
+        b"from 0"
+    );
+
+    merge(&shards, &output, ConflictPolicy::KeepLast).unwrap();
+    let mut reader = StreamingZipReader::open(&output).unwrap();
+    let names: Vec<_> = reader.entries().iter().map(|e| e.name.clone()).collect();
+    assert_eq!(
+        names,
+        [
+            "out/",
+            "out/a.txt",
+            "out/b.txt",
+            "out/common.txt",
+            "out/c.txt"
+        ]
+    );
+    assert_eq!(
+        reader.read_entry_by_name("out/common.txt").unwrap(),
+        b"from 2"
+    );
+
+    let report = merge(&shards, &output, ConflictPolicy::KeepAll).unwrap();
+    assert_eq!(report.copied, 6);
+    assert!(report.skipped.is_empty());
+}
+
+#[test]
+fn test_stored_entries_pass_data_through() {
+    use s_zip::CompressionMethod;
+
+    // Already-compressed media: any byte pattern, stored verbatim
+    let media: Vec<u8> = (0..300_000u32)
+        .map(|i| (i.wrapping_mul(2_654_435_761) >> 24) as u8)
+        .collect();
+    let mut writer = StreamingZipWriter::from_writer_with_method(
+        Cursor::new(Vec::new()),
+        CompressionMethod::Stored,
+        0,
+    )
+    .unwrap();
+    writer.add_entry("clip.mp4", &media).unwrap();
+    writer.add_entry("empty.bin", b"").unwrap();
+    let zip = writer.finish().unwrap().into_inner();
+
+    let mut reader = StreamingZipReader::from_vec(zip.clone()).unwrap();
+    let entry = reader.find_entry("clip.mp4").unwrap().clone();
+    assert_eq!(entry.compression_method, 0);
+    assert_eq!(entry.compressed_size, media.len() as u64);
+    assert_eq!(entry.uncompressed_size, media.len() as u64);
+    assert_eq!(entry.crc32, crc32fast::hash(&media));
+    let (offset, len) = reader.data_span(&entry).unwrap();
+    assert_eq!(&zip[offset as usize..(offset + len) as usize], &media[..]);
+    assert!(reader.verify().unwrap().is_ok());
+    assert_eq!(reader.read_entry_by_name("empty.bin").unwrap(), b"");
+}
+
+#[test]
+fn test_auto_store_skips_incompressible_entries() {
+    // xorshift noise doesn't compress; its first 64 KiB decide the method
+    let mut state = 0x9e37_79b9_7f4a_7c15u64;
+    let noise: Vec<u8> = (0..200_000)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as u8
+        })
+        .collect();
+    let text = b"the quick brown fox jumps over the lazy dog\n".repeat(2_000);
+
+    let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new())).unwrap();
+    writer.set_auto_store(true);
+    writer.start_entry("noise.bin").unwrap();
+    for chunk in noise.chunks(10_000) {
+        writer.write_data(chunk).unwrap();
+    }
+    writer
+        .add_entry("small-noise.bin", &noise[..1_000])
+        .unwrap();
+    writer.add_entry("notes.txt", &text).unwrap();
+    writer.add_entry("photos/IMG_0001.JPG", &text).unwrap();
+    writer.add_entry("empty.txt", b"").unwrap();
+    let zip = writer.finish().unwrap().into_inner();
+
+    let mut reader = StreamingZipReader::from_vec(zip).unwrap();
+    let method =
+        |reader: &StreamingZipReader<_>, name| reader.find_entry(name).unwrap().compression_method;
+    assert_eq!(method(&reader, "noise.bin"), 0);
+    assert_eq!(method(&reader, "small-noise.bin"), 0);
+    assert_eq!(method(&reader, "notes.txt"), 8);
+    assert_eq!(method(&reader, "photos/IMG_0001.JPG"), 0);
+    assert!(reader.verify().unwrap().is_ok());
+    assert_eq!(reader.read_entry_by_name("noise.bin").unwrap(), noise);
+    assert_eq!(reader.read_entry_by_name("notes.txt").unwrap(), text);
+    assert_eq!(reader.read_entry_by_name("empty.txt").unwrap(), b"");
+}
+
+#[test]
+fn test_deflate_threads() {
+    // Several 1 MiB blocks plus a partial one
+    let text: Vec<u8> = (0..120_000u32)
+        .flat_map(|i| format!("row {} of the export, checksum {}\n", i, i % 97).into_bytes())
+        .collect();
+    assert!(text.len() > 4 * 1024 * 1024);
+
+    let write = |threads| {
+        let mut writer = StreamingZipWriter::from_writer(Cursor::new(Vec::new())).unwrap();
+        writer.set_deflate_threads(threads);
+        writer.start_entry("export.txt").unwrap();
+        for chunk in text.chunks(100_000) {
+            writer.write_data(chunk).unwrap();
+        }
+        writer.add_entry("small.txt", b"below one block").unwrap();
+        // Exactly one block: the stream ends in a block of its own
+        writer.add_entry("block.bin", &text[..1024 * 1024]).unwrap();
+        writer.finish().unwrap().into_inner()
+    };
+    let single = write(1);
+    let parallel = write(3);
+
+    let mut reader = StreamingZipReader::from_vec(parallel).unwrap();
+    assert!(reader.verify().unwrap().is_ok());
+    assert_eq!(reader.read_entry_by_name("export.txt").unwrap(), text);
+    assert_eq!(
+        reader.read_entry_by_name("small.txt").unwrap(),
+        b"below one block"
+    );
+    assert_eq!(
+        reader.read_entry_by_name("block.bin").unwrap(),
+        &text[..1024 * 1024]
+    );
+    let size = |zip: &StreamingZipReader<_>| zip.find_entry("export.txt").unwrap().compressed_size;
+    let single = StreamingZipReader::from_vec(single).unwrap();
+    assert!(size(&reader) < size(&single) * 101 / 100);
+}
@@ -116,3 +116,268 @@ fn test_zstd_streaming_read() {
         assert!(buffer.iter().all(|&b| b == 0x55));
     }
 }
+
+#[cfg(feature = "zstd-support")]
+#[test]
+fn test_zstd_dictionary() {
+    use s_zip::zstd_dict::{ZstdDictionary, DICTIONARY_ENTRY};
+    use s_zip::{CompressionMethod, StreamingZipReader, StreamingZipWriter};
+    use std::io::{Cursor, Read};
+
+    let records: Vec<String> = (0..500)
+        .map(|i| {
+            format!(
+                r#"{{"ts":"2024-05-{:02}T10:00:{:02}Z","level":"info","service":"checkout","user":{},"msg":"payment accepted"}}"#,
+                i % 28 + 1,
+                i % 60,
+                i * 7919 % 10_000
+            )
+        })
+        .collect();
+    let dictionary = ZstdDictionary::train(&records, 4096).unwrap();
+    assert!(dictionary.id().is_some());
+
+    let write = |dictionary: Option<(ZstdDictionary, bool)>| {
+        let mut writer = StreamingZipWriter::from_writer_with_method(
+            Cursor::new(Vec::new()),
+            CompressionMethod::Zstd,
+            3,
+        )
+        .unwrap();
+        if let Some((dictionary, embed)) = dictionary {
+            writer.set_zstd_dictionary(dictionary, embed);
+        }
+        for (i, record) in records.iter().enumerate() {
+            writer
+                .add_entry(&format!("logs/{}.json", i), record.as_bytes())
+                .unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    };
+    let compressed = |zip: &[u8]| -> u64 {
+        let reader = StreamingZipReader::from_bytes(zip).unwrap();
+        reader
+            .entries()
+            .iter()
+            .filter(|e| e.name.starts_with("logs/"))
+            .map(|e| e.compressed_size)
+            .sum()
+    };
+
+    let plain = write(None);
+    let embedded = write(Some((dictionary.clone(), true)));
+    assert!(compressed(&embedded) * 2 < compressed(&plain));
+
+    // The embedded dictionary is found without being told
+    let mut reader = StreamingZipReader::from_vec(embedded).unwrap();
+    let dict_entry = reader.find_entry(DICTIONARY_ENTRY).unwrap();
+    assert_eq!(dict_entry.compression_method, 0);
+    assert_eq!(
+        reader.read_entry_by_name("logs/42.json").unwrap(),
+        records[42].as_bytes()
+    );
+    let mut streamed = Vec::new();
+    reader
+        .read_entry_streaming_by_name("logs/7.json")
+        .unwrap()
+        .read_to_end(&mut streamed)
+        .unwrap();
+    assert_eq!(streamed, records[7].as_bytes());
+    assert!(reader.verify().unwrap().is_ok());
+
+    // Kept out of the archive, it has to be supplied
+    let external = write(Some((dictionary.clone(), false)));
+    let mut reader = StreamingZipReader::from_vec(external.clone()).unwrap();
+    assert!(reader.find_entry(DICTIONARY_ENTRY).is_none());
+    assert!(reader.read_entry_by_name("logs/0.json").is_err());
+    reader.set_zstd_dictionary(dictionary.clone());
+    assert_eq!(
+        reader.read_entry_by_name("logs/0.json").unwrap(),
+        records[0].as_bytes()
+    );
+
+    let mut reader = StreamingZipReader::from_vec(external).unwrap();
+    reader.set_zstd_dictionary(dictionary);
+    let mut owned = Vec::new();
+    reader
+        .into_entry_reader("logs/499.json")
+        .unwrap()
+        .read_to_end(&mut owned)
+        .unwrap();
+    assert_eq!(owned, records[499].as_bytes());
+}
+
+#[cfg(feature = "async-zstd")]
+#[tokio::test]
+async fn test_async_zstd_dictionary() -> s_zip::Result<()> {
+    use s_zip::zstd_dict::ZstdDictionary;
+    use s_zip::{AsyncStreamingZipWriter, CompressionMethod, GenericAsyncZipReader};
+    use std::io::Cursor;
+    use tokio::io::AsyncReadExt;
+
+    let records: Vec<String> = (0..500)
+        .map(|i| {
+            format!(
+                r#"{{"event":"page_view","path":"/docs/{}","ms":{}}}"#,
+                i % 40,
+                i * 37 % 900
+            )
+        })
+        .collect();
+    let dictionary = ZstdDictionary::train(&records, 2048)?;
+
+    let mut writer =
+        AsyncStreamingZipWriter::from_writer_with_method(Vec::new(), CompressionMethod::Zstd, 3);
+    writer.set_zstd_dictionary(dictionary, true);
+    for (i, record) in records.iter().enumerate() {
+        writer
+            .add_entry(&format!("events/{}.json", i), record.as_bytes())
+            .await?;
+    }
+    let zip = writer.finish().await?;
+
+    let mut reader = GenericAsyncZipReader::new(Cursor::new(zip)).await?;
+    // Shared readers load the dictionary through their own handle
+    let mut shared = Vec::new();
+    let entry = reader.find_entry("events/3.json").unwrap().clone();
+    reader
+        .shared_entry_reader(&entry)
+        .await?
+        .read_to_end(&mut shared)
+        .await?;
+    assert_eq!(shared, records[3].as_bytes());
+
+    assert_eq!(
+        reader.read_entry_by_name("events/9.json").await?,
+        records[9].as_bytes()
+    );
+    let mut streamed = Vec::new();
+    reader
+        .read_entry_streaming_by_name("events/10.json")
+        .await?
+        .read_to_end(&mut streamed)
+        .await?;
+    assert_eq!(streamed, records[10].as_bytes());
+
+    let dir = tempfile::tempdir()?;
+    reader.extract_all(dir.path(), 4).await?;
+    assert_eq!(
+        std::fs::read(dir.path().join("events/499.json"))?,
+        records[499].as_bytes()
+    );
+    Ok(())
+}
+
+#[cfg(feature = "zstd-support")]
+#[test]
+fn test_zstd_options() {
+    use s_zip::{
+        CompressionMethod, StreamingZipForwardReader, StreamingZipReader, StreamingZipWriter,
+        ZstdOptions,
+    };
+    use std::io::{Cursor, Read};
+
+    // Repeats far apart, for long-distance matching to find
+    let block: Vec<u8> = (0..300_000u64).map(|i| (i * 7919 % 251) as u8).collect();
+    let mut data = block.clone();
+    data.extend((0..300_000u64).map(|i| (i * 104_729 % 241) as u8));
+    data.extend_from_slice(&block);
+
+    let mut writer = StreamingZipWriter::from_writer_with_method(
+        Cursor::new(Vec::new()),
+        CompressionMethod::Zstd,
+        3,
+    )
+    .unwrap();
+    // A 256 MiB window is over zstd's default decoding limit
+    writer.set_zstd_options(ZstdOptions {
+        window_log: Some(28),
+        long_distance_matching: true,
+        ..Default::default()
+    });
+    writer.start_entry("image.bin").unwrap();
+    for chunk in data.chunks(64 * 1024) {
+        writer.write_data(chunk).unwrap();
+    }
+    let zip = writer.finish().unwrap().into_inner();
+
+    let mut reader = StreamingZipReader::from_vec(zip.clone()).unwrap();
+    assert_eq!(reader.read_entry_by_name("image.bin").unwrap(), data);
+    let mut streamed = Vec::new();
+    reader
+        .read_entry_streaming_by_name("image.bin")
+        .unwrap()
+        .read_to_end(&mut streamed)
+        .unwrap();
+    assert_eq!(streamed, data);
+
+    let mut forward = StreamingZipForwardReader::new(Cursor::new(zip));
+    let mut entry = forward.next_entry().unwrap().unwrap();
+    let mut streamed = Vec::new();
+    entry.read_to_end(&mut streamed).unwrap();
+    assert_eq!(streamed, data);
+
+    // Worker threads need zstd built with multithreading
+    let mut writer = StreamingZipWriter::from_writer_with_method(
+        Cursor::new(Vec::new()),
+        CompressionMethod::Zstd,
+        3,
+    )
+    .unwrap();
+    writer.set_zstd_options(ZstdOptions {
+        workers: 2,
+        ..Default::default()
+    });
+    let written = writer.add_entry("image.bin", &data);
+    if cfg!(feature = "zstdmt") {
+        written.unwrap();
+        let zip = writer.finish().unwrap().into_inner();
+        let mut reader = StreamingZipReader::from_vec(zip).unwrap();
+        assert_eq!(reader.read_entry_by_name("image.bin").unwrap(), data);
+    } else {
+        assert!(written.is_err());
+    }
+}
+
+#[cfg(feature = "async-zstd")]
+#[tokio::test]
+async fn test_async_zstd_options() -> s_zip::Result<()> {
+    use s_zip::{
+        AsyncStreamingZipWriter, CompressionMethod, ForwardZipReader, GenericAsyncZipReader,
+        ZstdOptions,
+    };
+    use std::io::Cursor;
+    use tokio::io::AsyncReadExt;
+
+    let data: Vec<u8> = (0..500_000u64).map(|i| (i * 7919 % 251) as u8).collect();
+    let mut writer =
+        AsyncStreamingZipWriter::from_writer_with_method(Vec::new(), CompressionMethod::Zstd, 3);
+    writer.set_zstd_options(ZstdOptions {
+        workers: if cfg!(feature = "zstdmt") { 2 } else { 0 },
+        window_log: Some(28),
+        long_distance_matching: true,
+    });
+    writer.set_compression_offload(Some(1));
+    writer.start_entry("image.bin").await?;
+    for chunk in data.chunks(64 * 1024) {
+        writer.write_data(chunk).await?;
+    }
+    let zip = writer.finish().await?;
+
+    let mut reader = GenericAsyncZipReader::new(Cursor::new(zip.clone())).await?;
+    assert_eq!(reader.read_entry_by_name("image.bin").await?, data);
+    let mut streamed = Vec::new();
+    reader
+        .read_entry_streaming_by_name("image.bin")
+        .await?
+        .read_to_end(&mut streamed)
+        .await?;
+    assert_eq!(streamed, data);
+
+    let mut forward = ForwardZipReader::new(Cursor::new(zip));
+    let mut entry = forward.next_entry().await?.unwrap();
+    let mut streamed = Vec::new();
+    entry.read_to_end(&mut streamed).await?;
+    assert_eq!(streamed, data);
+    Ok(())
+}